@@ -0,0 +1,190 @@
+//! Changes made from the app this session
+//!
+//! Reconciles, suspends, resumes, ref overrides and rollbacks are logged with
+//! their time and outcome, so "what I did" can be read back with `L` and
+//! written to a file for an incident channel. The log is kept in memory only.
+
+use std::{fmt, path::PathBuf};
+
+use chrono::{DateTime, Utc};
+use color_eyre::Result;
+
+use crate::kubernetes::resources::FluxResource;
+
+use super::buffer::BoundedBuffer;
+
+/// How a logged change ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// Requested; the result wasn't waited for (yet)
+    Started,
+    Succeeded,
+    Failed(String),
+}
+
+impl Outcome {
+    /// Outcome of a finished request
+    pub fn of<E: fmt::Display>(result: &Result<(), E>) -> Self {
+        match result {
+            Ok(()) => Outcome::Succeeded,
+            Err(e) => Outcome::Failed(e.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Started => write!(f, "started"),
+            Outcome::Succeeded => write!(f, "ok"),
+            // Only the first line, so every entry stays on one line
+            Outcome::Failed(e) => write!(f, "failed: {}", e.lines().next().unwrap_or("")),
+        }
+    }
+}
+
+/// A change made to a resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub at: DateTime<Utc>,
+
+    /// What was done, e.g. "Reconcile with source"
+    pub action: String,
+
+    /// e.g. "Kustomization flux-system/apps"
+    pub resource: String,
+    pub cluster: String,
+    pub outcome: Outcome,
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}  {} {} ({}): {}",
+            self.at.format("%Y-%m-%d %H:%M:%S UTC"),
+            self.action,
+            self.resource,
+            self.cluster,
+            self.outcome
+        )
+    }
+}
+
+/// Session log of changes, oldest first
+#[derive(Debug, Clone)]
+pub struct ActionLog {
+    entries: BoundedBuffer<Entry>,
+}
+
+impl ActionLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: BoundedBuffer::new(capacity),
+        }
+    }
+
+    /// Log a change to a resource
+    pub fn record(
+        &mut self,
+        at: DateTime<Utc>,
+        action: impl Into<String>,
+        resource: &dyn FluxResource,
+        outcome: Outcome,
+    ) {
+        self.entries.push(Entry {
+            at,
+            action: action.into(),
+            resource: format!(
+                "{} {}/{}",
+                resource.kind(),
+                resource.namespace(),
+                resource.name()
+            ),
+            cluster: resource.cluster().to_string(),
+            outcome,
+        });
+    }
+
+    /// Set the outcome of the latest change, once a started request finished
+    pub fn finish_latest(&mut self, outcome: Outcome) {
+        if let Some(entry) = self
+            .entries
+            .latest_mut()
+            .filter(|entry| entry.outcome == Outcome::Started)
+        {
+            entry.outcome = outcome;
+        }
+    }
+
+    /// Logged changes, oldest first
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &Entry> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// The log as plain text, one change per line
+    pub fn to_text(&self) -> String {
+        self.entries().map(|entry| format!("{entry}\n")).collect()
+    }
+
+    /// Write the log to a new file in the system temp directory
+    pub fn write(&self) -> Result<PathBuf> {
+        let file_name = format!(
+            "flux-tui-actions-{}.txt",
+            Utc::now().format("%Y%m%d-%H%M%S%.3f")
+        );
+        let path = std::env::temp_dir().join(file_name);
+        std::fs::write(&path, self.to_text())?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::Kustomization;
+
+    fn apps() -> Kustomization {
+        Kustomization {
+            name: "apps".to_string(),
+            namespace: "flux-system".to_string(),
+            cluster: "prod".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_record_and_text() {
+        let at = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut log = ActionLog::new(10);
+        log.record(at, "Suspend", &apps(), Outcome::Succeeded);
+        log.record(
+            at,
+            "Resume",
+            &apps(),
+            Outcome::of(&Err::<(), _>("forbidden\nmore detail")),
+        );
+        assert_eq!(
+            log.to_text(),
+            "2023-11-14 22:13:20 UTC  Suspend Kustomization flux-system/apps (prod): ok\n\
+             2023-11-14 22:13:20 UTC  Resume Kustomization flux-system/apps (prod): failed: forbidden\n"
+        );
+    }
+
+    #[test]
+    fn test_finish_latest() {
+        let mut log = ActionLog::new(10);
+        log.record(Utc::now(), "Reconcile", &apps(), Outcome::Started);
+        log.finish_latest(Outcome::Succeeded);
+        assert_eq!(log.entries().last().unwrap().outcome, Outcome::Succeeded);
+
+        // A finished entry keeps its outcome
+        log.finish_latest(Outcome::Failed("late".to_string()));
+        assert_eq!(log.entries().last().unwrap().outcome, Outcome::Succeeded);
+        assert_eq!(log.len(), 1);
+    }
+}
@@ -2,6 +2,8 @@
 //!
 //! These actions represent all possible state transitions in the application.
 
+use super::state::Tab;
+
 /// Actions that can be performed in the application
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Action {
@@ -14,6 +16,12 @@ pub enum Action {
     /// Navigate to the previous tab
     PreviousTab,
 
+    /// Switch to a clicked tab
+    ClickTab(Tab),
+
+    /// Select a clicked row of the current table
+    ClickRow(usize),
+
     /// Move selection up in the current list
     Up,
 
@@ -23,9 +31,24 @@ pub enum Action {
     /// Move to the top of the list
     Top,
 
-    /// Move to the bottom of the list
+    /// Move to the bottom of the list, or to the row given by a count prefix
     Bottom,
 
+    /// Move a page of rows down in the current list
+    PageDown,
+
+    /// Move a page of rows up in the current list
+    PageUp,
+
+    /// Move half a page of rows down in the current list
+    HalfPageDown,
+
+    /// Move half a page of rows up in the current list
+    HalfPageUp,
+
+    /// Add a digit to the count repeating the next movement (e.g. `5j`)
+    CountDigit(u8),
+
     /// Select/Enter - view details or confirm action
     Select,
 
@@ -35,12 +58,205 @@ pub enum Action {
     /// Reconcile the selected resource with its source
     ReconcileWithSource,
 
+    /// Force an upgrade of the selected HelmRelease, even when its release
+    /// failed or is locked
+    ForceReconcile,
+
+    /// Resume the selected resource if it is suspended, then reconcile it
+    ResumeAndReconcile,
+
     /// Open namespace filter popup
     FilterNamespace,
 
     /// Set namespace filter (None = all namespaces)
     SetNamespace(Option<String>),
 
+    /// Type a character into the namespace popup's search
+    NamespaceInput(char),
+
+    /// Delete the last character of the namespace popup's search
+    NamespaceBackspace,
+
+    /// Move the selection up in the namespace popup
+    PopupUp,
+
+    /// Move the selection down in the namespace popup
+    PopupDown,
+
+    /// Open kube context switcher popup
+    SwitchContext,
+
+    /// Highlight an item in the context or cluster popup
+    HighlightItem(usize),
+
+    /// Reconnect using the given kube context
+    SetContext(String),
+
+    /// Open cluster filter popup (multi-cluster mode)
+    FilterCluster,
+
+    /// Set cluster filter (None = all clusters)
+    SetClusterFilter(Option<String>),
+
+    /// Cycle the sort column of the current tab
+    CycleSort,
+
+    /// Reverse the sort order of the current tab
+    ReverseSort,
+
+    /// Cycle the status filter of the current tab (all, failed, stalled, not ready,
+    /// suspended)
+    CycleStatusFilter,
+
+    /// Group Kustomizations and HelmReleases under their sources, or stop
+    ToggleGrouping,
+
+    /// Collapse or expand the source group of the selected row
+    ToggleGroup,
+
+    /// Start typing a fuzzy filter for the current tab
+    StartSearch,
+
+    /// Append a character to the fuzzy filter
+    SearchInput(char),
+
+    /// Remove the last character of the fuzzy filter
+    SearchBackspace,
+
+    /// Stop typing and keep the fuzzy filter applied
+    ConfirmSearch,
+
+    /// Clear the fuzzy filter
+    ClearSearch,
+
+    /// Show consumers of the selected source (GitRepository, HelmRepository, HelmChart)
+    FilterBySource,
+
+    /// Stop showing only consumers of a source
+    ClearSourceFilter,
+
+    /// Show the full YAML manifest of the selected resource
+    ShowYaml,
+
+    /// Show Kubernetes Events about the selected resource
+    ShowEvents,
+
+    /// Show the complete status message of the selected resource
+    ShowMessage,
+
+    /// Show the Kustomization dependency tree
+    ShowDependencies,
+
+    /// Trace the selected resource back to its source
+    Trace,
+
+    /// Select the resource the selected one is built from on its tab: a
+    /// HelmRelease's HelmChart, a Kustomization's or HelmChart's source
+    Jump,
+
+    /// Browse the manifests at the selected Kustomization's path in its
+    /// source artifact
+    BrowsePath,
+
+    /// Show the highlighted file of the path browser
+    OpenFile,
+
+    /// Go back from a file to the path browser's file list
+    CloseFile,
+
+    /// Show (or refresh) the Flux controllers' metrics
+    ShowControllers,
+
+    /// List the Helm release revisions of the selected HelmRelease
+    ShowHelmHistory,
+
+    /// Ask to roll back to the highlighted Helm release revision
+    Rollback,
+
+    /// Show the keybinding help
+    ShowHelp,
+
+    /// Show the local usage statistics
+    ShowUsage,
+
+    /// Show the changes made from the app this session
+    ShowActionLog,
+
+    /// Write the action log to a file
+    WriteActionLog,
+
+    /// Check the Flux installation and show the results
+    ShowPreflight,
+
+    /// Show the Flux distribution and controller versions
+    ShowFluxVersion,
+
+    /// Expand or collapse labels and annotations in the details popup
+    ToggleMetadata,
+
+    /// Show or hide the recent activity sidebar
+    ToggleActivity,
+
+    /// Show or hide the details pane beside the table
+    ToggleDetailsPane,
+
+    /// Edit the local note on the selected resource
+    EditNote,
+
+    /// Type a character into the note being edited
+    NoteInput(char),
+
+    /// Delete the last character of the note being edited
+    NoteBackspace,
+
+    /// Save the note being edited (an empty note is removed)
+    SaveNote,
+
+    /// Open the command palette
+    OpenCommand,
+
+    /// Type a character into the command palette
+    CommandInput(char),
+
+    /// Delete the last character of the command palette
+    CommandBackspace,
+
+    /// Run the command typed into the palette
+    RunCommand,
+
+    /// Search resource names across all tabs
+    StartGlobalSearch,
+
+    /// Type a character into the search across tabs
+    GlobalSearchInput(char),
+
+    /// Delete the last character of the search across tabs
+    GlobalSearchBackspace,
+
+    /// Go to the tab and row of the highlighted search match
+    GoToMatch,
+
+    /// Show the next step of the onboarding tour, closing it after the last
+    TourNext,
+
+    /// Show the previous step of the onboarding tour
+    TourBack,
+
+    /// Close the onboarding tour for good
+    EndTour,
+
+    /// Complete the word being typed, or cycle to the next candidate
+    Complete,
+
+    /// Cycle to the previous completion candidate
+    CompleteBack,
+
+    /// Acknowledge the selected failing resource (or lift its acknowledgement)
+    Acknowledge,
+
+    /// Scroll the open popup by a number of lines (negative = up)
+    Scroll(i32),
+
     /// Close any open popup
     ClosePopup,
 
@@ -50,14 +266,50 @@ pub enum Action {
     /// Toggle suspend on selected resource
     ToggleSuspend,
 
+    /// Suspend the source shown in the confirmation popup, or every resource
+    /// in the bulk suspend preview
+    ConfirmSuspend,
+
+    /// Write a diagnostic report bundle for bug reports
+    WriteDiagnostics,
+
+    /// Save the loaded resources to a snapshot file
+    ExportSnapshot,
+
     /// No operation (used for unhandled keys)
     Noop,
 }
 
+impl Action {
+    /// Whether the action changes resources in the cluster
+    pub fn is_mutating(&self) -> bool {
+        matches!(
+            self,
+            Action::Reconcile
+                | Action::ReconcileWithSource
+                | Action::ForceReconcile
+                | Action::ResumeAndReconcile
+                | Action::ToggleSuspend
+                | Action::ConfirmSuspend
+                | Action::Rollback
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_mutating() {
+        assert!(Action::Reconcile.is_mutating());
+        assert!(Action::ConfirmSuspend.is_mutating());
+        assert!(Action::ForceReconcile.is_mutating());
+        assert!(Action::ResumeAndReconcile.is_mutating());
+        assert!(!Action::ShowHelmHistory.is_mutating());
+        assert!(!Action::Select.is_mutating());
+    }
+
     #[test]
     fn test_action_debug() {
         assert_eq!(format!("{:?}", Action::Quit), "Quit");
@@ -67,6 +319,9 @@ mod tests {
         assert_eq!(format!("{:?}", Action::Down), "Down");
         assert_eq!(format!("{:?}", Action::Top), "Top");
         assert_eq!(format!("{:?}", Action::Bottom), "Bottom");
+        assert_eq!(format!("{:?}", Action::PageDown), "PageDown");
+        assert_eq!(format!("{:?}", Action::HalfPageUp), "HalfPageUp");
+        assert_eq!(format!("{:?}", Action::CountDigit(5)), "CountDigit(5)");
         assert_eq!(format!("{:?}", Action::Select), "Select");
         assert_eq!(format!("{:?}", Action::Reconcile), "Reconcile");
         assert_eq!(
@@ -74,9 +329,53 @@ mod tests {
             "ReconcileWithSource"
         );
         assert_eq!(format!("{:?}", Action::FilterNamespace), "FilterNamespace");
+        assert_eq!(format!("{:?}", Action::SwitchContext), "SwitchContext");
+        assert_eq!(format!("{:?}", Action::FilterCluster), "FilterCluster");
+        assert_eq!(format!("{:?}", Action::CycleSort), "CycleSort");
+        assert_eq!(format!("{:?}", Action::ReverseSort), "ReverseSort");
+        assert_eq!(
+            format!("{:?}", Action::CycleStatusFilter),
+            "CycleStatusFilter"
+        );
+        assert_eq!(format!("{:?}", Action::ToggleGrouping), "ToggleGrouping");
+        assert_eq!(format!("{:?}", Action::ToggleGroup), "ToggleGroup");
+        assert_eq!(format!("{:?}", Action::StartSearch), "StartSearch");
+        assert_eq!(
+            format!("{:?}", Action::StartGlobalSearch),
+            "StartGlobalSearch"
+        );
+        assert_eq!(format!("{:?}", Action::GoToMatch), "GoToMatch");
+        assert_eq!(format!("{:?}", Action::TourNext), "TourNext");
+        assert_eq!(format!("{:?}", Action::ClearSearch), "ClearSearch");
+        assert_eq!(format!("{:?}", Action::FilterBySource), "FilterBySource");
+        assert_eq!(
+            format!("{:?}", Action::ClearSourceFilter),
+            "ClearSourceFilter"
+        );
+        assert_eq!(format!("{:?}", Action::ShowYaml), "ShowYaml");
+        assert_eq!(format!("{:?}", Action::ShowEvents), "ShowEvents");
+        assert_eq!(
+            format!("{:?}", Action::ShowDependencies),
+            "ShowDependencies"
+        );
+        assert_eq!(format!("{:?}", Action::Trace), "Trace");
+        assert_eq!(format!("{:?}", Action::ShowHelp), "ShowHelp");
+        assert_eq!(
+            format!("{:?}", Action::ClickTab(Tab::HelmCharts)),
+            "ClickTab(HelmCharts)"
+        );
+        assert_eq!(format!("{:?}", Action::ClickRow(3)), "ClickRow(3)");
+        assert_eq!(format!("{:?}", Action::Acknowledge), "Acknowledge");
+        assert_eq!(format!("{:?}", Action::NoteInput('x')), "NoteInput('x')");
         assert_eq!(format!("{:?}", Action::ClosePopup), "ClosePopup");
         assert_eq!(format!("{:?}", Action::Refresh), "Refresh");
         assert_eq!(format!("{:?}", Action::ToggleSuspend), "ToggleSuspend");
+        assert_eq!(format!("{:?}", Action::ConfirmSuspend), "ConfirmSuspend");
+        assert_eq!(
+            format!("{:?}", Action::WriteDiagnostics),
+            "WriteDiagnostics"
+        );
+        assert_eq!(format!("{:?}", Action::ExportSnapshot), "ExportSnapshot");
         assert_eq!(format!("{:?}", Action::Noop), "Noop");
     }
 
@@ -0,0 +1,120 @@
+//! Recent activity: the resources whose status changed last, across all kinds
+//!
+//! Transitions come from the controllers' condition timestamps, so the feed
+//! is right from the first refresh and doesn't depend on how long flux-tui
+//! has been running.
+
+use chrono::{DateTime, Utc};
+
+use crate::kubernetes::resources::FluxResource;
+
+/// Number of resources listed in the activity sidebar
+pub const RECENT: usize = 10;
+
+/// A resource and when its status last changed
+#[derive(Debug, Clone, Copy)]
+pub struct Transition<'a> {
+    pub resource: &'a dyn FluxResource,
+    pub at: DateTime<Utc>,
+}
+
+/// When a resource's status last changed: the Ready condition's transition,
+/// else the latest transition of any condition
+pub fn last_transition(resource: &dyn FluxResource) -> Option<DateTime<Utc>> {
+    let conditions = &resource.reconcile_info().conditions;
+    conditions
+        .iter()
+        .find(|c| c.condition_type == "Ready")
+        .and_then(|c| c.last_transition)
+        .or_else(|| conditions.iter().filter_map(|c| c.last_transition).max())
+}
+
+/// The `limit` most recently transitioned resources, newest first
+pub fn recent<'a>(resources: &[&'a dyn FluxResource], limit: usize) -> Vec<Transition<'a>> {
+    let mut transitions: Vec<Transition> = resources
+        .iter()
+        .filter_map(|resource| {
+            Some(Transition {
+                resource: *resource,
+                at: last_transition(*resource)?,
+            })
+        })
+        .collect();
+    transitions.sort_by(|a, b| {
+        b.at.cmp(&a.at)
+            .then_with(|| a.resource.name().cmp(b.resource.name()))
+    });
+    transitions.truncate(limit);
+    transitions
+}
+
+/// kubectl short name of a Flux kind (e.g. "ks", "hr")
+pub fn short_kind(kind: &str) -> &str {
+    match kind {
+        "Kustomization" => "ks",
+        "HelmRelease" => "hr",
+        "HelmChart" => "hc",
+        "GitRepository" => "gitrepo",
+        "HelmRepository" => "helmrepo",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    use crate::kubernetes::resources::{
+        Condition, HelmRelease, Kustomization, ReconcileInfo, ResourceStatus,
+    };
+
+    fn info(transitions: &[(&str, u32)]) -> ReconcileInfo {
+        ReconcileInfo {
+            conditions: transitions
+                .iter()
+                .map(|(condition_type, minute)| Condition {
+                    condition_type: condition_type.to_string(),
+                    last_transition: Some(
+                        Utc.with_ymd_and_hms(2026, 3, 4, 12, *minute, 0).unwrap(),
+                    ),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_recent_newest_first_across_kinds() {
+        let apps = Kustomization {
+            name: "apps".to_string(),
+            reconcile: info(&[("Ready", 5)]),
+            ..Default::default()
+        };
+        let infra = Kustomization {
+            name: "infra".to_string(),
+            reconcile: info(&[("Reconciling", 30), ("Ready", 1)]),
+            ..Default::default()
+        };
+        let podinfo = HelmRelease {
+            name: "podinfo".to_string(),
+            status: ResourceStatus::Failed,
+            reconcile: info(&[("Released", 20)]),
+            ..Default::default()
+        };
+        let pending = Kustomization {
+            name: "pending".to_string(),
+            ..Default::default()
+        };
+        let resources: Vec<&dyn FluxResource> = vec![&apps, &infra, &podinfo, &pending];
+
+        let names: Vec<&str> = recent(&resources, RECENT)
+            .iter()
+            .map(|t| t.resource.name())
+            .collect();
+        assert_eq!(names, vec!["podinfo", "apps", "infra"]);
+        assert_eq!(recent(&resources, 1)[0].resource.kind(), "HelmRelease");
+        assert_eq!(short_kind("HelmRelease"), "hr");
+    }
+}
@@ -0,0 +1,108 @@
+//! Fixed-capacity buffers for session data
+//!
+//! A long-lived session keeps collecting errors, events and history entries.
+//! These buffers evict the oldest entry once full so memory stays bounded.
+
+use std::collections::VecDeque;
+
+/// A FIFO buffer that drops its oldest entries when full
+#[derive(Debug, Clone)]
+pub struct BoundedBuffer<T> {
+    /// Buffered entries, oldest first
+    items: VecDeque<T>,
+
+    /// Maximum number of entries kept
+    capacity: usize,
+}
+
+impl<T> BoundedBuffer<T> {
+    /// Create an empty buffer holding at most `capacity` entries
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            items: VecDeque::with_capacity(capacity.min(64)),
+            capacity,
+        }
+    }
+
+    /// Append an entry, evicting the oldest one if the buffer is full
+    pub fn push(&mut self, item: T) {
+        if self.capacity == 0 {
+            return;
+        }
+        while self.items.len() >= self.capacity {
+            self.items.pop_front();
+        }
+        self.items.push_back(item);
+    }
+
+    /// Number of buffered entries
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Check if the buffer is empty
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Iterate over entries, oldest first
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.items.iter()
+    }
+
+    /// Get the most recently pushed entry
+    #[cfg(test)]
+    pub fn latest(&self) -> Option<&T> {
+        self.items.back()
+    }
+
+    /// Get the most recently pushed entry, to update it
+    pub fn latest_mut(&mut self) -> Option<&mut T> {
+        self.items.back_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_within_capacity() {
+        let mut buffer = BoundedBuffer::new(3);
+        buffer.push(1);
+        buffer.push(2);
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_push_evicts_oldest() {
+        let mut buffer = BoundedBuffer::new(2);
+        buffer.push(1);
+        buffer.push(2);
+        buffer.push(3);
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(buffer.latest(), Some(&3));
+    }
+
+    #[test]
+    fn test_zero_capacity_keeps_nothing() {
+        let mut buffer = BoundedBuffer::new(0);
+        buffer.push("dropped");
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.latest(), None);
+    }
+
+    #[test]
+    fn test_iter_rev_newest_first() {
+        let mut buffer = BoundedBuffer::new(5);
+        for i in 0..4 {
+            buffer.push(i);
+        }
+        assert_eq!(
+            buffer.iter().rev().copied().collect::<Vec<_>>(),
+            vec![3, 2, 1, 0]
+        );
+    }
+}
@@ -0,0 +1,218 @@
+//! Message bus between the UI loop and background work
+//!
+//! `App::update` never waits on a cluster. Work that does (refreshes,
+//! fetching a manifest, suspending, switching context) runs as a task on the
+//! bus and reports back with a [`StateUpdate`], which the UI loop applies
+//! between frames. Drawing reads the app between updates, so it always sees
+//! a consistent state while input keeps being handled.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+
+use crate::kubernetes::{
+    api::FluxApi, flux_version::FluxVersion, portforward::PortForward, resources::FluxResource,
+};
+
+use super::{
+    action_log::Outcome,
+    config::Config,
+    state::{Fetched, FetchedNamespace, Popup, SourceFilter},
+};
+
+/// The primary client and the clients of the other contexts
+pub type Clients = (Arc<dyn FluxApi>, Vec<Arc<dyn FluxApi>>);
+
+/// Result of background work, applied by [`App::apply`](super::state::App::apply)
+pub enum StateUpdate {
+    /// A refresh finished
+    Fetched(Fetched),
+
+    /// One allow-listed namespace of a running refresh arrived
+    FetchedNamespace(Box<FetchedNamespace>),
+
+    /// Data for a popup arrived, with port-forwards to keep open while it is
+    /// shown
+    Popup {
+        popup: Popup,
+        forwards: Vec<(String, PortForward)>,
+    },
+
+    /// A request failed; the message is recorded and shown
+    Failed(String),
+
+    /// Changes to resources finished
+    Changed {
+        /// Action log entries: what was done, to which resource, and how it went
+        logged: Vec<(String, Box<dyn FluxResource>, Outcome)>,
+
+        /// Shown once done, if anything
+        popup: Option<Popup>,
+    },
+
+    /// A client for another context was built
+    Connected {
+        context: String,
+        config: Box<Config>,
+        client: Arc<dyn FluxApi>,
+    },
+
+    /// Consumers affected by Git changes were determined (`:changes`)
+    Changes(SourceFilter),
+
+    /// The clients were rebuilt from the kubeconfig, or couldn't be
+    Reloaded(Result<Clients, String>),
+
+    /// A task panicked before reporting; which one isn't known
+    Panicked(String),
+
+    /// The Flux version of the first cluster was read, to show in the
+    /// version popup too when `show` is set
+    FluxVersion { version: FluxVersion, show: bool },
+
+    /// Nothing to apply, e.g. startup checks that all passed
+    Idle,
+}
+
+impl StateUpdate {
+    /// Show a popup that holds no port-forwards
+    pub fn popup(popup: Popup) -> Self {
+        StateUpdate::Popup {
+            popup,
+            forwards: Vec::new(),
+        }
+    }
+}
+
+/// An update on the channel, and whether its task is done with it
+struct Message {
+    update: StateUpdate,
+    done: bool,
+}
+
+/// Reports updates of a running task ahead of its result
+#[derive(Clone)]
+pub struct Progress(mpsc::UnboundedSender<Message>);
+
+impl Progress {
+    pub fn send(&self, update: StateUpdate) {
+        // The receiver only goes away when the app quits
+        let _ = self.0.send(Message {
+            update,
+            done: false,
+        });
+    }
+}
+
+/// Channel that background tasks report their [`StateUpdate`] on
+pub struct Bus {
+    tx: mpsc::UnboundedSender<Message>,
+    rx: mpsc::UnboundedReceiver<Message>,
+
+    /// Tasks that have not reported yet
+    in_flight: usize,
+}
+
+impl Default for Bus {
+    fn default() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self {
+            tx,
+            rx,
+            in_flight: 0,
+        }
+    }
+}
+
+impl Bus {
+    /// Run a task in the background; its result arrives as an update
+    ///
+    /// A task that panics still reports, so it isn't counted as running
+    /// forever.
+    pub fn spawn(&mut self, task: impl Future<Output = StateUpdate> + Send + 'static) {
+        self.in_flight += 1;
+        let tx = self.tx.clone();
+        let task = tokio::spawn(task);
+        tokio::spawn(async move {
+            let update = task
+                .await
+                .unwrap_or_else(|e| StateUpdate::Panicked(format!("Background task failed: {e}")));
+            // The receiver only goes away when the app quits
+            let _ = tx.send(Message { update, done: true });
+        });
+    }
+
+    /// A sender for updates a task reports before its result
+    pub fn progress(&self) -> Progress {
+        Progress(self.tx.clone())
+    }
+
+    /// An update that already arrived, without waiting
+    pub fn try_recv(&mut self) -> Option<StateUpdate> {
+        let message = self.rx.try_recv().ok()?;
+        Some(self.received(message))
+    }
+
+    /// Wait for the next update, None when no task is running
+    #[cfg(test)]
+    pub async fn recv(&mut self) -> Option<StateUpdate> {
+        if self.in_flight == 0 {
+            return None;
+        }
+        let message = self.rx.recv().await?;
+        Some(self.received(message))
+    }
+
+    fn received(&mut self, message: Message) -> StateUpdate {
+        if message.done {
+            self.in_flight = self.in_flight.saturating_sub(1);
+        }
+        message.update
+    }
+
+    /// Whether any task is still running
+    pub fn is_busy(&self) -> bool {
+        self.in_flight > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_spawn_and_recv() {
+        let mut bus = Bus::default();
+        assert!(!bus.is_busy());
+        assert!(bus.recv().await.is_none());
+
+        bus.spawn(async { StateUpdate::Failed("boom".to_string()) });
+        assert!(bus.is_busy());
+        assert!(matches!(bus.recv().await, Some(StateUpdate::Failed(m)) if m == "boom"));
+        assert!(!bus.is_busy());
+        assert!(bus.try_recv().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_progress_keeps_the_task_running() {
+        let mut bus = Bus::default();
+        let progress = bus.progress();
+        bus.spawn(async move {
+            progress.send(StateUpdate::Idle);
+            StateUpdate::Failed("done".to_string())
+        });
+        assert!(matches!(bus.recv().await, Some(StateUpdate::Idle)));
+        assert!(bus.is_busy());
+        assert!(matches!(bus.recv().await, Some(StateUpdate::Failed(_))));
+        assert!(!bus.is_busy());
+    }
+
+    #[tokio::test]
+    async fn test_panicking_task_still_reports() {
+        let mut bus = Bus::default();
+        bus.spawn(async { panic!("boom") });
+        assert!(matches!(bus.recv().await, Some(StateUpdate::Panicked(m)) if m.contains("boom")));
+        assert!(!bus.is_busy());
+    }
+}
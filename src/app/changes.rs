@@ -0,0 +1,304 @@
+//! What a Git change affects (`:changes`)
+//!
+//! The Git provider's compare API lists the files changed between two
+//! revisions of a GitRepository; a Kustomization is affected when a changed
+//! file is under its `spec.path` (a HelmRelease when one is under its chart
+//! path). Files pulled in from elsewhere, e.g. a kustomize base outside the
+//! path, aren't followed.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use color_eyre::{eyre::eyre, Result};
+use serde::Deserialize;
+
+use crate::kubernetes::resources::{FluxResource, ResourceKey};
+
+use super::config::{GitProvider, ProviderKind};
+
+/// Give up on a slow provider rather than leave the user waiting
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Files changed in a GitRepository between two revisions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeSet {
+    pub from: String,
+    pub to: String,
+
+    /// Changed paths relative to the repository root; renamed files are
+    /// listed under both names
+    pub paths: Vec<String>,
+}
+
+impl ChangeSet {
+    /// Whether a changed file is under a source path (the root covers all)
+    pub fn affects(&self, source_path: &str) -> bool {
+        let dir = normalize(source_path);
+        dir.is_empty()
+            || self.paths.iter().any(|path| {
+                path.strip_prefix(dir)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+    }
+}
+
+/// A path as written in a spec ("./apps/prod/") relative to the root
+fn normalize(path: &str) -> &str {
+    let path = path.trim_start_matches("./").trim_matches('/');
+    if path == "." {
+        ""
+    } else {
+        path
+    }
+}
+
+/// A repository on a Git provider's API
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Remote {
+    pub provider: GitProvider,
+
+    /// Repository path on the host, e.g. "org/fleet"
+    pub path: String,
+}
+
+impl Remote {
+    /// The remote of a GitRepository URL, if its host is a known provider
+    ///
+    /// HTTPS, `ssh://` and scp-like (`git@host:org/repo`) URLs are understood.
+    pub fn of_url(url: &str, providers: &[GitProvider]) -> Result<Self> {
+        let (host, path) = split_url(url).ok_or_else(|| eyre!("Unsupported Git URL {url}"))?;
+        let provider = GitProvider::for_host(providers, &host).ok_or_else(|| {
+            eyre!("No Git provider configured for {host} (see git_providers in the config file)")
+        })?;
+        Ok(Self { provider, path })
+    }
+
+    /// Paths changed between two revisions (commit SHAs, tags or branches)
+    pub async fn changed_paths(&self, from: &str, to: &str) -> Result<Vec<String>> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("flux-tui/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        let api = self.provider.api_url();
+        let token = self.provider.token();
+        let body = match self.provider.kind {
+            ProviderKind::GitHub => {
+                let mut request = client
+                    .get(format!("{api}/repos/{}/compare/{from}...{to}", self.path))
+                    .header("Accept", "application/vnd.github+json");
+                if let Some(token) = token {
+                    request = request.bearer_auth(token);
+                }
+                request.send().await?.error_for_status()?.text().await?
+            }
+            ProviderKind::GitLab => {
+                let mut request = client
+                    .get(format!(
+                        "{api}/projects/{}/repository/compare",
+                        self.path.replace('/', "%2F")
+                    ))
+                    .query(&[("from", from), ("to", to)]);
+                if let Some(token) = token {
+                    request = request.header("PRIVATE-TOKEN", token);
+                }
+                request.send().await?.error_for_status()?.text().await?
+            }
+        };
+        parse_compare(self.provider.kind, &body)
+    }
+}
+
+/// Host and repository path of a Git URL, without a `.git` suffix
+fn split_url(url: &str) -> Option<(String, String)> {
+    let rest = match url.split_once("://") {
+        Some((_, rest)) => rest.to_string(),
+        // scp-like: git@github.com:org/repo.git
+        None => url.replacen(':', "/", 1),
+    };
+    let (authority, path) = rest.split_once('/')?;
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?;
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+    (!host.is_empty() && !path.is_empty()).then(|| (host.to_string(), path.to_string()))
+}
+
+#[derive(Deserialize)]
+struct GitHubCompare {
+    #[serde(default)]
+    files: Vec<GitHubFile>,
+}
+
+#[derive(Deserialize)]
+struct GitHubFile {
+    filename: String,
+    previous_filename: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GitLabCompare {
+    #[serde(default)]
+    diffs: Vec<GitLabDiff>,
+}
+
+#[derive(Deserialize)]
+struct GitLabDiff {
+    old_path: String,
+    new_path: String,
+}
+
+/// Changed paths from a compare API response
+fn parse_compare(kind: ProviderKind, body: &str) -> Result<Vec<String>> {
+    let mut paths: Vec<String> = match kind {
+        ProviderKind::GitHub => serde_json::from_str::<GitHubCompare>(body)?
+            .files
+            .into_iter()
+            .flat_map(|file| std::iter::once(file.filename).chain(file.previous_filename))
+            .collect(),
+        ProviderKind::GitLab => serde_json::from_str::<GitLabCompare>(body)?
+            .diffs
+            .into_iter()
+            .flat_map(|diff| [diff.old_path, diff.new_path])
+            .collect(),
+    };
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+/// Commit of a displayed revision ("main@1a2b3c4" → "1a2b3c4")
+pub fn commit(revision: &str) -> &str {
+    let commit = revision.rsplit('@').next().unwrap_or(revision);
+    commit.rsplit(':').next().unwrap_or(commit)
+}
+
+/// Revisions of the GitRepositories seen this session, to compare the
+/// current one against the one before
+#[derive(Debug, Default)]
+pub struct RevisionHistory {
+    current: HashMap<ResourceKey, String>,
+    previous: HashMap<ResourceKey, String>,
+}
+
+impl RevisionHistory {
+    /// Record the revisions of a refresh
+    pub fn record<'a>(&mut self, resources: impl IntoIterator<Item = &'a dyn FluxResource>) {
+        for resource in resources {
+            let Some(revision) = resource.revision() else {
+                continue;
+            };
+            let key = ResourceKey::of(resource);
+            match self.current.insert(key.clone(), revision.to_string()) {
+                Some(old) if old != revision => {
+                    self.previous.insert(key, old);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// The revision a resource had before its current one
+    pub fn previous(&self, resource: &dyn FluxResource) -> Option<&str> {
+        self.previous
+            .get(&ResourceKey::of(resource))
+            .map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::GitRepository;
+
+    fn changes(paths: &[&str]) -> ChangeSet {
+        ChangeSet {
+            from: "1a2b3c4".to_string(),
+            to: "5d6e7f8".to_string(),
+            paths: paths.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_affects() {
+        let changes = changes(&["apps/prod/podinfo.yaml", "infrastructure-old/x.yaml"]);
+        assert!(changes.affects("./apps/prod"));
+        assert!(changes.affects("apps/"));
+        assert!(changes.affects("./apps/prod/podinfo.yaml"));
+        assert!(changes.affects("./"));
+        assert!(changes.affects(""));
+        assert!(!changes.affects("./apps/staging"));
+        assert!(!changes.affects("./infrastructure"));
+    }
+
+    #[test]
+    fn test_split_url() {
+        for url in [
+            "https://github.com/org/fleet",
+            "https://github.com/org/fleet.git",
+            "ssh://git@github.com/org/fleet.git",
+            "ssh://git@github.com:22/org/fleet",
+            "git@github.com:org/fleet.git",
+        ] {
+            assert_eq!(
+                split_url(url),
+                Some(("github.com".to_string(), "org/fleet".to_string())),
+                "{url}"
+            );
+        }
+        assert_eq!(
+            split_url("https://gitlab.com/group/sub/fleet.git"),
+            Some(("gitlab.com".to_string(), "group/sub/fleet".to_string()))
+        );
+        assert_eq!(split_url("unknown"), None);
+    }
+
+    #[test]
+    fn test_remote_needs_a_provider() {
+        assert!(Remote::of_url("https://github.com/org/fleet", &[]).is_ok());
+        let error = Remote::of_url("https://git.example.com/org/fleet", &[]).unwrap_err();
+        assert!(error.to_string().contains("git.example.com"));
+    }
+
+    #[test]
+    fn test_parse_compare() {
+        let github = r#"{"files": [
+            {"filename": "apps/prod/podinfo.yaml", "status": "modified"},
+            {"filename": "apps/new.yaml", "previous_filename": "apps/old.yaml", "status": "renamed"}
+        ]}"#;
+        assert_eq!(
+            parse_compare(ProviderKind::GitHub, github).unwrap(),
+            vec!["apps/new.yaml", "apps/old.yaml", "apps/prod/podinfo.yaml"]
+        );
+
+        let gitlab = r#"{"diffs": [
+            {"old_path": "infra/a.yaml", "new_path": "infra/a.yaml"},
+            {"old_path": "infra/b.yaml", "new_path": "infra/c.yaml"}
+        ]}"#;
+        assert_eq!(
+            parse_compare(ProviderKind::GitLab, gitlab).unwrap(),
+            vec!["infra/a.yaml", "infra/b.yaml", "infra/c.yaml"]
+        );
+    }
+
+    #[test]
+    fn test_commit() {
+        assert_eq!(commit("main@1a2b3c4"), "1a2b3c4");
+        assert_eq!(commit("main@sha1:1a2b3c4"), "1a2b3c4");
+        assert_eq!(commit("1a2b3c4"), "1a2b3c4");
+    }
+
+    #[test]
+    fn test_revision_history() {
+        let at = |revision: &str| GitRepository {
+            name: "fleet".to_string(),
+            revision: Some(revision.to_string()),
+            ..Default::default()
+        };
+        let mut history = RevisionHistory::default();
+        history.record([&at("main@1a2b3c4") as &dyn FluxResource]);
+        assert_eq!(history.previous(&at("main@1a2b3c4")), None);
+
+        history.record([&at("main@5d6e7f8") as &dyn FluxResource]);
+        history.record([&at("main@5d6e7f8") as &dyn FluxResource]);
+        assert_eq!(history.previous(&at("main@5d6e7f8")), Some("main@1a2b3c4"));
+    }
+}
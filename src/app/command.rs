@@ -0,0 +1,265 @@
+//! Command palette (`:`)
+//!
+//! Operations that act on many resources at once, or that need an argument,
+//! are typed as commands rather than bound to keys, e.g.
+//! `suspend -l team=payments` or `ref feature/x`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::kubernetes::resources::GitRef;
+
+/// A parsed command palette command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Suspend every Kustomization and HelmRelease matching a label selector
+    Suspend { selector: LabelSelector },
+
+    /// Point the selected GitRepository at another ref, diverging from Git
+    SetRef { reference: GitRef },
+
+    /// Restore the selected GitRepository's original ref
+    RevertRef,
+
+    /// Show the consumers of the selected GitRepository affected by the
+    /// changes between two revisions (None = the previous revision seen, and
+    /// the current one)
+    Changes {
+        from: Option<String>,
+        to: Option<String>,
+    },
+}
+
+impl Command {
+    /// The word the command is typed with
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Suspend { .. } => "suspend",
+            Command::SetRef { .. } | Command::RevertRef => "ref",
+            Command::Changes { .. } => "changes",
+        }
+    }
+
+    /// Whether running the command changes the cluster
+    pub fn is_mutating(&self) -> bool {
+        !matches!(self, Command::Changes { .. })
+    }
+
+    /// Parse a command line, returning a usage message on error
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut words = input.split_whitespace();
+        match words.next() {
+            Some("suspend") => {
+                let selector = match (words.next(), words.next(), words.next()) {
+                    (Some("-l" | "--selector"), Some(selector), None) => selector,
+                    _ => return Err(SUSPEND_USAGE.to_string()),
+                };
+                Ok(Command::Suspend {
+                    selector: LabelSelector::parse(selector)?,
+                })
+            }
+            Some("ref") => match (words.next(), words.next()) {
+                (Some("revert"), None) => Ok(Command::RevertRef),
+                (Some(reference), None) => GitRef::parse(reference)
+                    .map(|reference| Command::SetRef { reference })
+                    .ok_or_else(|| format!("Usage: {REF_USAGE}")),
+                _ => Err(format!("Usage: {REF_USAGE}")),
+            },
+            Some("changes") => match (words.next(), words.next(), words.next()) {
+                (from, to, None) => Ok(Command::Changes {
+                    from: from.map(String::from),
+                    to: to.map(String::from),
+                }),
+                _ => Err(format!("Usage: {CHANGES_USAGE}")),
+            },
+            Some(other) => Err(format!(
+                "Unknown command \"{other}\". {SUSPEND_USAGE}, {REF_USAGE}, or {CHANGES_USAGE}"
+            )),
+            None => Err(format!("{SUSPEND_USAGE}, {REF_USAGE}, or {CHANGES_USAGE}")),
+        }
+    }
+}
+
+const SUSPEND_USAGE: &str = "Usage: suspend -l key=value[,key2!=value2,key3,!key4]";
+
+const REF_USAGE: &str = "ref [branch=|tag=|semver=|commit=]<ref> | ref revert";
+
+const CHANGES_USAGE: &str = "changes [<from revision> [<to revision>]]";
+
+/// One requirement of a label selector
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Requirement {
+    Equals(String, String),
+    NotEquals(String, String),
+    Exists(String),
+    NotExists(String),
+}
+
+/// Equality-based Kubernetes label selector (`key=value,key!=value,key,!key`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelSelector {
+    requirements: Vec<Requirement>,
+}
+
+impl LabelSelector {
+    /// Parse a comma-separated selector
+    pub fn parse(selector: &str) -> Result<Self, String> {
+        let requirements = selector
+            .split(',')
+            .map(|part| {
+                let requirement = if let Some((key, value)) = part.split_once("!=") {
+                    Requirement::NotEquals(key.to_string(), value.to_string())
+                } else if let Some((key, value)) =
+                    part.split_once("==").or_else(|| part.split_once('='))
+                {
+                    Requirement::Equals(key.to_string(), value.to_string())
+                } else if let Some(key) = part.strip_prefix('!') {
+                    Requirement::NotExists(key.to_string())
+                } else {
+                    Requirement::Exists(part.to_string())
+                };
+                match &requirement {
+                    Requirement::Equals(key, _)
+                    | Requirement::NotEquals(key, _)
+                    | Requirement::Exists(key)
+                    | Requirement::NotExists(key)
+                        if key.is_empty() =>
+                    {
+                        Err(format!("Invalid label selector \"{selector}\""))
+                    }
+                    _ => Ok(requirement),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { requirements })
+    }
+
+    /// Whether a resource's labels satisfy every requirement
+    pub fn matches(&self, labels: &BTreeMap<String, String>) -> bool {
+        self.requirements
+            .iter()
+            .all(|requirement| match requirement {
+                Requirement::Equals(key, value) => labels.get(key) == Some(value),
+                Requirement::NotEquals(key, value) => labels.get(key) != Some(value),
+                Requirement::Exists(key) => labels.contains_key(key),
+                Requirement::NotExists(key) => !labels.contains_key(key),
+            })
+    }
+}
+
+impl fmt::Display for LabelSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self
+            .requirements
+            .iter()
+            .map(|requirement| match requirement {
+                Requirement::Equals(key, value) => format!("{key}={value}"),
+                Requirement::NotEquals(key, value) => format!("{key}!={value}"),
+                Requirement::Exists(key) => key.clone(),
+                Requirement::NotExists(key) => format!("!{key}"),
+            })
+            .collect();
+        write!(f, "{}", parts.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_suspend() {
+        let Ok(Command::Suspend { selector }) = Command::parse("suspend -l team=payments") else {
+            panic!("Expected suspend");
+        };
+        assert_eq!(selector.to_string(), "team=payments");
+        let Ok(Command::Suspend { selector }) =
+            Command::parse("  suspend --selector team==payments,!canary ")
+        else {
+            panic!("Expected suspend");
+        };
+        assert_eq!(selector.to_string(), "team=payments,!canary");
+
+        assert!(Command::parse("suspend").is_err());
+        assert!(Command::parse("suspend -l").is_err());
+        assert!(Command::parse("suspend -l a=b extra").is_err());
+        assert!(Command::parse("suspend -l =b").is_err());
+        assert!(Command::parse("delete -l a=b")
+            .unwrap_err()
+            .starts_with("Unknown command \"delete\""));
+    }
+
+    #[test]
+    fn test_parse_ref() {
+        assert_eq!(
+            Command::parse("ref feature/x"),
+            Ok(Command::SetRef {
+                reference: GitRef::Branch("feature/x".to_string())
+            })
+        );
+        assert_eq!(
+            Command::parse("ref tag=v1.2.0"),
+            Ok(Command::SetRef {
+                reference: GitRef::Tag("v1.2.0".to_string())
+            })
+        );
+        assert_eq!(Command::parse("ref revert"), Ok(Command::RevertRef));
+        assert!(Command::parse("ref").is_err());
+        assert!(Command::parse("ref a b").is_err());
+        assert!(Command::parse("ref sha=abc").is_err());
+    }
+
+    #[test]
+    fn test_parse_changes() {
+        assert_eq!(
+            Command::parse("changes"),
+            Ok(Command::Changes {
+                from: None,
+                to: None
+            })
+        );
+        assert_eq!(
+            Command::parse("changes 1a2b3c4 v1.2.0"),
+            Ok(Command::Changes {
+                from: Some("1a2b3c4".to_string()),
+                to: Some("v1.2.0".to_string())
+            })
+        );
+        assert!(Command::parse("changes a b c").is_err());
+    }
+
+    #[test]
+    fn test_is_mutating() {
+        assert!(Command::parse("suspend -l team=payments")
+            .unwrap()
+            .is_mutating());
+        assert!(Command::RevertRef.is_mutating());
+        assert!(!Command::parse("changes").unwrap().is_mutating());
+    }
+
+    #[test]
+    fn test_selector_matches() {
+        let payments = labels(&[("team", "payments"), ("tier", "backend")]);
+        let search = labels(&[("team", "search")]);
+
+        let selector = LabelSelector::parse("team=payments").unwrap();
+        assert!(selector.matches(&payments));
+        assert!(!selector.matches(&search));
+
+        let selector = LabelSelector::parse("team!=payments,!tier").unwrap();
+        assert!(!selector.matches(&payments));
+        assert!(selector.matches(&search));
+        assert!(selector.matches(&labels(&[])));
+
+        let selector = LabelSelector::parse("tier").unwrap();
+        assert!(selector.matches(&payments));
+        assert!(!selector.matches(&search));
+    }
+}
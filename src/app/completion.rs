@@ -0,0 +1,187 @@
+//! Tab completion in text inputs
+//!
+//! The word before the cursor is completed from data already loaded:
+//! namespaces and resource names in the fuzzy filter and notes, command
+//! names, flags, label keys and label values in the command palette.
+//! Pressing Tab again cycles through the other candidates.
+
+use std::collections::BTreeMap;
+
+use crate::kubernetes::resources::FluxResource;
+
+/// Characters that end a word; selectors are completed key by key
+const SEPARATORS: [char; 4] = [' ', ',', '=', '!'];
+
+/// Command palette commands
+const COMMANDS: [&str; 3] = ["changes", "ref", "suspend"];
+
+/// An ongoing completion, cycled through with Tab
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    /// Text before the word being completed
+    head: String,
+
+    /// Candidates for the word, sorted
+    candidates: Vec<String>,
+
+    /// Candidate currently shown (None until the first Tab)
+    index: Option<usize>,
+}
+
+impl Completion {
+    /// Complete `word` from `pool`, None when nothing starts with it
+    pub fn new(head: &str, word: &str, pool: impl IntoIterator<Item = String>) -> Option<Self> {
+        let mut candidates: Vec<String> = pool
+            .into_iter()
+            .filter(|candidate| candidate.starts_with(word) && candidate != word)
+            .collect();
+        candidates.sort();
+        candidates.dedup();
+        if candidates.is_empty() {
+            return None;
+        }
+        Some(Self {
+            head: head.to_string(),
+            candidates,
+            index: None,
+        })
+    }
+
+    /// Move to the next (or previous) candidate and return the completed text
+    pub fn cycle(&mut self, reverse: bool) -> String {
+        let len = self.candidates.len();
+        let index = match (self.index, reverse) {
+            (None, false) => 0,
+            (None, true) => len - 1,
+            (Some(i), false) => (i + 1) % len,
+            (Some(i), true) => (i + len - 1) % len,
+        };
+        self.index = Some(index);
+        format!("{}{}", self.head, self.candidates[index])
+    }
+}
+
+/// Split text into the part before the word being completed and that word
+pub fn split_word(text: &str) -> (&str, &str) {
+    let start = text.rfind(SEPARATORS).map(|i| i + 1).unwrap_or_default();
+    text.split_at(start)
+}
+
+/// Candidates for the command palette, depending on what was typed before
+///
+/// Commands come first, then the selector flag, then label keys, and label
+/// values after `key=` or `key!=`. `ref` only completes to `revert`, and
+/// `changes` takes revisions, which aren't completed.
+pub fn command_candidates(head: &str, labels: &[&BTreeMap<String, String>]) -> Vec<String> {
+    if head.trim().is_empty() {
+        return COMMANDS.iter().map(|c| c.to_string()).collect();
+    }
+    match head.split_whitespace().next() {
+        Some("ref") => return vec!["revert".to_string()],
+        Some("changes") => return Vec::new(),
+        _ => {}
+    }
+    if let Some(before) = head.strip_suffix('=') {
+        let before = before.trim_end_matches(['=', '!']);
+        let (_, key) = split_word(before);
+        return labels
+            .iter()
+            .filter_map(|labels| labels.get(key).cloned())
+            .collect();
+    }
+    let in_selector = head
+        .split_whitespace()
+        .any(|word| word == "-l" || word == "--selector");
+    if in_selector {
+        labels
+            .iter()
+            .flat_map(|labels| labels.keys().cloned())
+            .collect()
+    } else {
+        vec!["-l".to_string()]
+    }
+}
+
+/// Resource names and namespaces, for the fuzzy filter and notes
+pub fn name_candidates(resources: &[&dyn FluxResource], namespaces: &[String]) -> Vec<String> {
+    resources
+        .iter()
+        .flat_map(|r| [r.name().to_string(), r.namespace().to_string()])
+        .chain(namespaces.iter().cloned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::Kustomization;
+
+    fn labels(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_split_word() {
+        assert_eq!(split_word("podinfo"), ("", "podinfo"));
+        assert_eq!(split_word("suspend -l te"), ("suspend -l ", "te"));
+        assert_eq!(
+            split_word("suspend -l team=pay"),
+            ("suspend -l team=", "pay")
+        );
+        assert_eq!(split_word("suspend -l a=b,"), ("suspend -l a=b,", ""));
+    }
+
+    #[test]
+    fn test_cycle() {
+        let pool = ["apps", "flux-system", "apps-staging", "infra"].map(String::from);
+        let mut completion = Completion::new("", "ap", pool).unwrap();
+        assert_eq!(completion.cycle(false), "apps");
+        assert_eq!(completion.cycle(false), "apps-staging");
+        assert_eq!(completion.cycle(false), "apps");
+        assert_eq!(completion.cycle(true), "apps-staging");
+
+        assert!(Completion::new("", "zz", ["apps".to_string()]).is_none());
+        assert!(Completion::new("", "apps", ["apps".to_string()]).is_none());
+    }
+
+    #[test]
+    fn test_command_candidates() {
+        let payments = labels(&[("team", "payments"), ("tier", "backend")]);
+        let search = labels(&[("team", "search")]);
+        let all = [&payments, &search];
+
+        assert_eq!(
+            command_candidates("", &all),
+            vec!["changes", "ref", "suspend"]
+        );
+        assert!(command_candidates("changes ", &all).is_empty());
+        assert_eq!(command_candidates("ref ", &all), vec!["revert"]);
+        assert_eq!(command_candidates("suspend ", &all), vec!["-l"]);
+        let mut keys = command_candidates("suspend -l ", &all);
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys, vec!["team", "tier"]);
+        assert_eq!(
+            command_candidates("suspend -l tier=backend,team=", &all),
+            vec!["payments", "search"]
+        );
+        assert_eq!(
+            command_candidates("suspend -l team!=", &all),
+            vec!["payments", "search"]
+        );
+    }
+
+    #[test]
+    fn test_name_candidates() {
+        let apps = Kustomization {
+            name: "apps".to_string(),
+            namespace: "flux-system".to_string(),
+            ..Default::default()
+        };
+        let pool = name_candidates(&[&apps], &["default".to_string()]);
+        assert_eq!(pool, vec!["apps", "flux-system", "default"]);
+    }
+}
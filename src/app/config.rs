@@ -1,9 +1,19 @@
 //! Application configuration
+//!
+//! Settings are read from `<config dir>/flux-tui/config.toml` when present
+//! (e.g. `~/.config/flux-tui/config.toml` on Linux). CLI arguments take
+//! precedence over values from the file.
 
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
+
+use color_eyre::{eyre::eyre, Result};
+use serde::Deserialize;
+
+use crate::{kubernetes::reconcile::Backend, ui::theme::parse_color};
 
 /// Application configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 #[allow(dead_code)]
 pub struct Config {
     /// Path to kubeconfig file (None = use default)
@@ -15,8 +25,75 @@ pub struct Config {
     /// Default namespace filter (None = all namespaces)
     pub namespace: Option<String>,
 
+    /// Contexts to aggregate in multi-cluster mode (empty = single cluster)
+    pub contexts: Vec<String>,
+
+    /// Namespaces to fetch one by one (empty = all namespaces in one request)
+    pub namespaces: Vec<String>,
+
+    /// Maximum concurrent namespace fetches when `namespaces` is set
+    pub fetch_concurrency: usize,
+
+    /// How long acknowledging a failing resource snoozes it, in minutes
+    pub snooze_minutes: u64,
+
+    /// Resources starting to reconcile in one refresh that count as a
+    /// reconcile storm (0 = off)
+    pub storm_threshold: usize,
+
+    /// Locale for timestamps and counts, e.g. "de-DE" (None = system locale)
+    pub locale: Option<String>,
+
+    /// Color theme: "dark", "light" or the path of a theme file (None = dark)
+    pub theme: Option<String>,
+
+    /// Draw without colors (also set by `NO_COLOR`)
+    pub no_color: bool,
+
+    /// Draw in this many rows at the bottom of the terminal instead of the
+    /// alternate screen, keeping the scrollback above (None = full screen)
+    pub inline_height: Option<u16>,
+
+    /// Read resources from a running `flux-tui daemon` instead of the cluster
+    pub attach: bool,
+
+    /// File the daemon keeps a JSON status summary in, for desktop bars
+    pub tray_file: Option<PathBuf>,
+
     /// Auto-refresh interval in seconds
     pub refresh_interval: u64,
+
+    /// How reconcile and suspend requests are made: the flux CLI or the
+    /// Kubernetes API
+    pub reconcile_backend: Backend,
+
+    /// Check GitHub for a newer flux-tui release on startup (opt-in)
+    pub update_check: bool,
+
+    /// Refuse reconciles, suspends and other changes to the cluster
+    pub read_only: bool,
+
+    /// Count used actions and session lengths in a local file (never sent)
+    pub usage_stats: bool,
+
+    /// Size limits for session buffers
+    pub buffers: BufferLimits,
+
+    /// Resource table columns to hide, by title (e.g. "AGE")
+    pub hidden_columns: Vec<String>,
+
+    /// Named profiles, selected with `--profile`
+    pub profiles: BTreeMap<String, Profile>,
+
+    /// Banners marking environments by context name; the first match wins
+    pub environments: Vec<Environment>,
+
+    /// Self-hosted Git servers to ask for changed paths (`:changes`);
+    /// github.com and gitlab.com need no entry
+    pub git_providers: Vec<GitProvider>,
+
+    /// How a resource going from Ready to Failed is brought to attention
+    pub notifications: Notifications,
 }
 
 impl Default for Config {
@@ -25,8 +102,299 @@ impl Default for Config {
             kubeconfig: None,
             context: None,
             namespace: None,
+            contexts: Vec::new(),
+            namespaces: Vec::new(),
+            fetch_concurrency: 4,
+            snooze_minutes: 240,
+            storm_threshold: 10,
+            locale: None,
+            theme: None,
+            no_color: false,
+            inline_height: None,
+            attach: false,
+            tray_file: None,
             refresh_interval: 5,
+            reconcile_backend: Backend::Cli,
+            update_check: false,
+            read_only: false,
+            usage_stats: true,
+            buffers: BufferLimits::default(),
+            hidden_columns: Vec::new(),
+            profiles: BTreeMap::new(),
+            environments: Vec::new(),
+            git_providers: Vec::new(),
+            notifications: Notifications::default(),
+        }
+    }
+}
+
+/// Banner shown at the top while connected to a matching context
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Environment {
+    /// Context name pattern, where `*` matches any characters (e.g. "prod-*")
+    pub pattern: String,
+
+    /// Banner text, e.g. "PRODUCTION"
+    pub label: String,
+
+    /// Banner background: a color name, "#rrggbb" or a 256-color index
+    #[serde(default = "default_banner_color")]
+    pub color: String,
+}
+
+fn default_banner_color() -> String {
+    "red".to_string()
+}
+
+impl Environment {
+    /// Whether a context name matches the pattern
+    pub fn matches(&self, context: &str) -> bool {
+        let mut parts = self.pattern.split('*');
+        let Some(mut rest) = context.strip_prefix(parts.next().unwrap_or_default()) else {
+            return false;
+        };
+        let parts: Vec<&str> = parts.collect();
+        // Without a `*`, the whole name must match
+        let Some((last, middle)) = parts.split_last() else {
+            return rest.is_empty();
+        };
+        for part in middle {
+            match rest.find(part) {
+                Some(i) => rest = &rest[i + part.len()..],
+                None => return false,
+            }
+        }
+        rest.ends_with(last)
+    }
+}
+
+/// Settings bundled under a name, e.g. one profile per cluster
+///
+/// Fields set in the profile replace the top-level ones when it is selected.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub context: Option<String>,
+    pub contexts: Option<Vec<String>>,
+    pub namespace: Option<String>,
+    pub namespaces: Option<Vec<String>>,
+    pub theme: Option<String>,
+    pub hidden_columns: Option<Vec<String>>,
+}
+
+/// Alerts when a refresh finds a resource that was Ready has failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Notifications {
+    /// Ring the terminal bell
+    pub bell: bool,
+
+    /// Send a desktop notification (notify-send on Linux, osascript on macOS)
+    pub desktop: bool,
+
+    /// Flash the header for a few seconds
+    pub flash: bool,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self {
+            bell: false,
+            desktop: false,
+            flash: true,
+        }
+    }
+}
+
+/// API of a Git server hosting GitRepository URLs
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct GitProvider {
+    /// Host name in the repository URLs, e.g. "git.example.com"
+    pub host: String,
+
+    /// API flavor: "github" or "gitlab"
+    pub kind: ProviderKind,
+
+    /// API base URL (None = the host's default for its kind)
+    pub api_url: Option<String>,
+
+    /// Environment variable holding an access token (None = GITHUB_TOKEN or
+    /// GITLAB_TOKEN)
+    pub token_env: Option<String>,
+}
+
+/// API flavor of a Git server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    GitHub,
+    GitLab,
+}
+
+impl GitProvider {
+    /// Provider for a host: the configured one, or github.com or gitlab.com
+    pub fn for_host(configured: &[GitProvider], host: &str) -> Option<GitProvider> {
+        if let Some(provider) = configured.iter().find(|p| p.host == host) {
+            return Some(provider.clone());
+        }
+        let kind = match host {
+            "github.com" => ProviderKind::GitHub,
+            "gitlab.com" => ProviderKind::GitLab,
+            _ => return None,
+        };
+        Some(GitProvider {
+            host: host.to_string(),
+            kind,
+            api_url: None,
+            token_env: None,
+        })
+    }
+
+    /// API base URL, without a trailing slash
+    pub fn api_url(&self) -> String {
+        match (&self.api_url, self.kind) {
+            (Some(url), _) => url.trim_end_matches('/').to_string(),
+            (None, ProviderKind::GitHub) if self.host == "github.com" => {
+                "https://api.github.com".to_string()
+            }
+            // GitHub Enterprise Server
+            (None, ProviderKind::GitHub) => format!("https://{}/api/v3", self.host),
+            (None, ProviderKind::GitLab) => format!("https://{}/api/v4", self.host),
+        }
+    }
+
+    /// Access token from the environment, if set
+    pub fn token(&self) -> Option<String> {
+        let var = self.token_env.as_deref().unwrap_or(match self.kind {
+            ProviderKind::GitHub => "GITHUB_TOKEN",
+            ProviderKind::GitLab => "GITLAB_TOKEN",
+        });
+        std::env::var(var).ok().filter(|token| !token.is_empty())
+    }
+}
+
+/// Maximum number of entries kept by each session buffer
+///
+/// Oldest entries are evicted once a buffer is full, so a long-running
+/// session doesn't grow without bound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct BufferLimits {
+    /// Recent error messages
+    pub errors: usize,
+
+    /// Kubernetes events per popup
+    pub events: usize,
+
+    /// Refreshes kept in each resource's status timeline
+    pub history: usize,
+
+    /// Recent Kubernetes API requests (for diagnostics)
+    pub api_calls: usize,
+
+    /// Changes made from the app, shown in the action log
+    pub actions: usize,
+}
+
+impl Default for BufferLimits {
+    fn default() -> Self {
+        Self {
+            errors: 50,
+            events: 200,
+            history: 500,
+            api_calls: 100,
+            actions: 500,
+        }
+    }
+}
+
+impl Config {
+    /// Default location of the config file
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("flux-tui").join("config.toml"))
+    }
+
+    /// Load the config file, falling back to defaults if it doesn't exist
+    ///
+    /// A non-empty `NO_COLOR` turns colors off whatever the file says.
+    pub fn load() -> Result<Self> {
+        let mut config = match Self::path() {
+            Some(path) if path.exists() => {
+                let contents = std::fs::read_to_string(&path)?;
+                Self::from_toml(&contents)
+                    .map_err(|e| eyre!("Invalid config file {}: {}", path.display(), e))?
+            }
+            _ => Self::default(),
+        };
+        if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            config.no_color = true;
+        }
+        Ok(config)
+    }
+
+    /// Replace settings with those of a named profile
+    ///
+    /// A profile naming a single context leaves multi-cluster mode, like
+    /// `--context` does.
+    pub fn apply_profile(&mut self, name: &str) -> Result<()> {
+        let Some(profile) = self.profiles.get(name).cloned() else {
+            let known: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            return Err(if known.is_empty() {
+                eyre!("Unknown profile {name}: the config file defines no profiles")
+            } else {
+                eyre!("Unknown profile {name} (defined: {})", known.join(", "))
+            });
+        };
+        if profile.context.is_some() {
+            self.context = profile.context;
+            self.contexts.clear();
         }
+        if let Some(contexts) = profile.contexts {
+            self.contexts = contexts;
+        }
+        if profile.namespace.is_some() {
+            self.namespace = profile.namespace;
+        }
+        if let Some(namespaces) = profile.namespaces {
+            self.namespaces = namespaces;
+        }
+        if profile.theme.is_some() {
+            self.theme = profile.theme;
+        }
+        if let Some(hidden_columns) = profile.hidden_columns {
+            self.hidden_columns = hidden_columns;
+        }
+        Ok(())
+    }
+
+    /// Environment of the first banner matching any of the contexts
+    pub fn environment<'a>(
+        &self,
+        contexts: impl IntoIterator<Item = &'a str>,
+    ) -> Option<&Environment> {
+        let contexts: Vec<&str> = contexts.into_iter().collect();
+        self.environments
+            .iter()
+            .find(|env| contexts.iter().any(|context| env.matches(context)))
+    }
+
+    /// Parse a config from TOML
+    pub fn from_toml(contents: &str) -> Result<Self> {
+        let mut config: Self = toml::from_str(contents)?;
+        config.kubeconfig = config.kubeconfig.map(expand_home);
+        config.tray_file = config.tray_file.map(expand_home);
+        for env in &config.environments {
+            parse_color(&env.color)?;
+        }
+        Ok(config)
+    }
+}
+
+/// Expand a leading `~` to the home directory (`%USERPROFILE%` on Windows)
+fn expand_home(path: PathBuf) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path,
     }
 }
 
@@ -34,13 +402,38 @@ impl Default for Config {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_kubeconfig_home_is_expanded() {
+        let config = Config::from_toml("kubeconfig = \"~/.kube/prod\"").unwrap();
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(config.kubeconfig, Some(home.join(".kube").join("prod")));
+        }
+
+        let config = Config::from_toml("kubeconfig = \"/etc/kubeconfig\"").unwrap();
+        assert_eq!(config.kubeconfig, Some(PathBuf::from("/etc/kubeconfig")));
+
+        let config = Config::from_toml("tray_file = \"~/tray.json\"").unwrap();
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(config.tray_file, Some(home.join("tray.json")));
+        }
+    }
+
     #[test]
     fn test_config_default() {
         let config = Config::default();
         assert!(config.kubeconfig.is_none());
         assert!(config.context.is_none());
         assert!(config.namespace.is_none());
+        assert!(config.contexts.is_empty());
+        assert!(config.namespaces.is_empty());
+        assert_eq!(config.fetch_concurrency, 4);
+        assert_eq!(config.snooze_minutes, 240);
+        assert_eq!(config.storm_threshold, 10);
+        assert!(!config.attach);
+        assert_eq!(config.locale, None);
         assert_eq!(config.refresh_interval, 5);
+        assert!(!config.update_check);
+        assert_eq!(config.buffers, BufferLimits::default());
     }
 
     #[test]
@@ -57,6 +450,7 @@ mod tests {
             context: Some("my-context".to_string()),
             namespace: Some("default".to_string()),
             refresh_interval: 10,
+            ..Default::default()
         };
         let cloned = config.clone();
         assert_eq!(config.kubeconfig, cloned.kubeconfig);
@@ -64,4 +458,172 @@ mod tests {
         assert_eq!(config.namespace, cloned.namespace);
         assert_eq!(config.refresh_interval, cloned.refresh_interval);
     }
+
+    #[test]
+    fn test_config_from_toml_empty() {
+        let config = Config::from_toml("").unwrap();
+        assert_eq!(config.refresh_interval, 5);
+        assert_eq!(config.buffers, BufferLimits::default());
+    }
+
+    #[test]
+    fn test_config_from_toml_partial() {
+        let config = Config::from_toml(
+            r#"
+            context = "prod"
+            contexts = ["prod-eu", "prod-us"]
+            namespaces = ["apps", "infra"]
+            fetch_concurrency = 8
+            refresh_interval = 30
+            reconcile_backend = "native"
+            update_check = true
+
+            [buffers]
+            events = 50
+
+            [notifications]
+            bell = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.context.as_deref(), Some("prod"));
+        assert_eq!(config.contexts, vec!["prod-eu", "prod-us"]);
+        assert_eq!(config.namespaces, vec!["apps", "infra"]);
+        assert_eq!(config.fetch_concurrency, 8);
+        assert_eq!(config.refresh_interval, 30);
+        assert_eq!(config.reconcile_backend, Backend::Native);
+        assert!(config.update_check);
+        assert_eq!(config.buffers.events, 50);
+        assert_eq!(config.buffers.errors, BufferLimits::default().errors);
+        assert!(config.notifications.bell);
+        assert!(!config.notifications.desktop);
+        assert!(config.notifications.flash);
+    }
+
+    #[test]
+    fn test_apply_profile() {
+        let mut config = Config::from_toml(
+            r#"
+            contexts = ["prod-eu", "prod-us"]
+            namespace = "apps"
+            hidden_columns = ["AGE"]
+
+            [profiles.prod]
+            context = "prod-eu"
+            namespaces = ["payments"]
+            theme = "light"
+            hidden_columns = []
+
+            [profiles.dev]
+            namespace = "dev"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.profiles.len(), 2);
+
+        let mut dev = config.clone();
+        dev.apply_profile("dev").unwrap();
+        assert_eq!(dev.namespace.as_deref(), Some("dev"));
+        assert_eq!(dev.contexts, vec!["prod-eu", "prod-us"]);
+        assert_eq!(dev.hidden_columns, vec!["AGE"]);
+
+        config.apply_profile("prod").unwrap();
+        assert_eq!(config.context.as_deref(), Some("prod-eu"));
+        assert!(config.contexts.is_empty());
+        assert_eq!(config.namespace.as_deref(), Some("apps"));
+        assert_eq!(config.namespaces, vec!["payments"]);
+        assert_eq!(config.theme.as_deref(), Some("light"));
+        assert!(config.hidden_columns.is_empty());
+
+        let err = config.apply_profile("staging").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown profile staging (defined: dev, prod)"
+        );
+    }
+
+    #[test]
+    fn test_environment() {
+        let config = Config::from_toml(
+            r#"
+            [[environments]]
+            pattern = "*prod*"
+            label = "PRODUCTION"
+
+            [[environments]]
+            pattern = "staging-*-eu"
+            label = "STAGING"
+            color = "yellow"
+            "#,
+        )
+        .unwrap();
+        let label = |contexts: &[&str]| {
+            config
+                .environment(contexts.iter().copied())
+                .map(|env| env.label.as_str())
+        };
+        assert_eq!(label(&["gke_prod-eu"]), Some("PRODUCTION"));
+        assert_eq!(label(&["staging-1-eu"]), Some("STAGING"));
+        assert_eq!(label(&["staging-1-eu", "prod"]), Some("PRODUCTION"));
+        assert_eq!(label(&["staging-1-us", "kind-dev"]), None);
+        assert_eq!(config.environments[0].color, "red");
+
+        let env = |pattern: &str| Environment {
+            pattern: pattern.to_string(),
+            label: String::new(),
+            color: String::new(),
+        };
+        assert!(env("prod").matches("prod"));
+        assert!(!env("prod").matches("prod-eu"));
+        assert!(env("a*b*b").matches("abb"));
+        assert!(!env("a*b*b").matches("ab"));
+
+        assert!(Config::from_toml(
+            "[[environments]]\npattern = \"prod\"\nlabel = \"P\"\ncolor = \"reddish\""
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_git_providers() {
+        let config = Config::from_toml(
+            r#"
+            [[git_providers]]
+            host = "git.example.com"
+            kind = "gitlab"
+
+            [[git_providers]]
+            host = "ghe.example.com"
+            kind = "github"
+            api_url = "https://ghe.example.com/api/v3/"
+            token_env = "GHE_TOKEN"
+            "#,
+        )
+        .unwrap();
+        let api_url = |host| {
+            GitProvider::for_host(&config.git_providers, host).map(|provider| provider.api_url())
+        };
+        assert_eq!(
+            api_url("git.example.com").as_deref(),
+            Some("https://git.example.com/api/v4")
+        );
+        assert_eq!(
+            api_url("ghe.example.com").as_deref(),
+            Some("https://ghe.example.com/api/v3")
+        );
+        assert_eq!(
+            api_url("github.com").as_deref(),
+            Some("https://api.github.com")
+        );
+        assert_eq!(
+            api_url("gitlab.com").as_deref(),
+            Some("https://gitlab.com/api/v4")
+        );
+        assert_eq!(api_url("bitbucket.org"), None);
+    }
+
+    #[test]
+    fn test_config_from_toml_invalid() {
+        assert!(Config::from_toml("refresh_interval = \"soon\"").is_err());
+    }
 }
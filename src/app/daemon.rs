@@ -0,0 +1,258 @@
+//! Background daemon keeping resources and session history across TUI runs
+//!
+//! `flux-tui daemon` refreshes like the TUI does and serves its latest state
+//! on a local socket. `flux-tui --attach` reads from that socket instead of
+//! listing resources itself, so failure streaks and status timelines survive
+//! closing the terminal. Reconcile, suspend and the other actions still talk
+//! to the cluster directly.
+//!
+//! The protocol is a single JSON document per connection: the daemon writes
+//! its state and closes the socket.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::kubernetes::resources::{
+    GitRepository, HelmChart, HelmRelease, HelmRepository, Kustomization, ResourceKey,
+    ResourceStatus,
+};
+
+use super::{state::App, storm::ReconcileStorm};
+
+/// Everything an attached TUI takes over from the daemon
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DaemonState {
+    pub kustomizations: Vec<Kustomization>,
+    pub helm_releases: Vec<HelmRelease>,
+    pub helm_charts: Vec<HelmChart>,
+    pub git_repositories: Vec<GitRepository>,
+    pub helm_repositories: Vec<HelmRepository>,
+    pub namespaces: Vec<String>,
+
+    /// Failure streak per resource
+    pub streaks: Vec<(ResourceKey, u32)>,
+
+    /// Status timeline per resource, oldest first
+    pub timelines: Vec<(ResourceKey, Vec<ResourceStatus>)>,
+
+    /// Ongoing reconcile storm
+    pub storm: Option<ReconcileStorm>,
+
+    /// Error of the daemon's last refresh
+    pub last_error: Option<String>,
+
+    /// When the daemon last refreshed
+    pub refreshed_at: DateTime<Utc>,
+}
+
+impl DaemonState {
+    /// Capture the state of the daemon's app
+    pub fn of(app: &App) -> Self {
+        Self {
+            kustomizations: app.kustomizations.clone(),
+            helm_releases: app.helm_releases.clone(),
+            helm_charts: app.helm_charts.clone(),
+            git_repositories: app.git_repositories.clone(),
+            helm_repositories: app.helm_repositories.clone(),
+            namespaces: app.namespaces.clone(),
+            streaks: app.failure_streaks.entries(),
+            timelines: app.timelines.entries(),
+            storm: app.storm.clone(),
+            last_error: app.last_error.clone(),
+            refreshed_at: Utc::now(),
+        }
+    }
+}
+
+/// Default location of the daemon socket
+pub fn socket_path() -> Option<PathBuf> {
+    dirs::runtime_dir()
+        .or_else(dirs::state_dir)
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join("flux-tui").join("daemon.sock"))
+}
+
+#[cfg(unix)]
+pub use unix::{fetch, run};
+
+#[cfg(not(unix))]
+pub use unsupported::{fetch, run};
+
+#[cfg(unix)]
+mod unix {
+    use std::time::Duration;
+
+    use color_eyre::eyre::{eyre, WrapErr};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{UnixListener, UnixStream},
+        sync::watch,
+    };
+
+    use super::*;
+    use crate::app::{config::Config, export, ticker::Summary, tray};
+
+    /// Run the daemon until interrupted, refreshing every `refresh_interval`
+    pub async fn run(config: &Config) -> Result<()> {
+        let path = socket_path().ok_or_else(|| eyre!("No directory for the daemon socket"))?;
+        let mut app = App::new(config).await?;
+        let listener = bind(&path).await?;
+        tracing::info!("flux-tui daemon listening on {}", path.display());
+
+        let (state, updates) = watch::channel(serde_json::to_string(&DaemonState::of(&app))?);
+        tokio::spawn(serve(listener, updates));
+        let mut tray_file = config.tray_file.as_deref().map(TrayFile::new);
+        if let Some(tray_file) = &mut tray_file {
+            tray_file.update(&app);
+        }
+
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(config.refresh_interval.max(1)));
+        interval.tick().await;
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    app.refresh_data().await?;
+                    if let Some(error) = &app.last_error {
+                        tracing::warn!("{error}");
+                    }
+                    state.send_replace(serde_json::to_string(&DaemonState::of(&app))?);
+                    if let Some(tray_file) = &mut tray_file {
+                        tray_file.update(&app);
+                    }
+                }
+                _ = tokio::signal::ctrl_c() => break,
+            }
+        }
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    /// The tray file, rewritten only when its contents change
+    struct TrayFile<'a> {
+        path: &'a Path,
+        written: Option<String>,
+    }
+
+    impl<'a> TrayFile<'a> {
+        fn new(path: &'a Path) -> Self {
+            Self {
+                path,
+                written: None,
+            }
+        }
+
+        /// Write the summary of the app, if it changed
+        ///
+        /// Failures are logged; the socket keeps being served.
+        fn update(&mut self, app: &App) {
+            let result = tray::render(&Summary::of(app)).and_then(|contents| {
+                if self.written.as_ref() != Some(&contents) {
+                    export::write(&contents, Some(self.path))?;
+                    self.written = Some(contents);
+                }
+                Ok(())
+            });
+            if let Err(e) = result {
+                tracing::warn!("Failed to write {}: {e}", self.path.display());
+            }
+        }
+    }
+
+    /// Bind the socket, replacing a stale one left by a daemon that died
+    async fn bind(path: &Path) -> Result<UnixListener> {
+        if path.exists() {
+            if UnixStream::connect(path).await.is_ok() {
+                return Err(eyre!(
+                    "A flux-tui daemon is already listening on {}",
+                    path.display()
+                ));
+            }
+            std::fs::remove_file(path)?;
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        UnixListener::bind(path).wrap_err_with(|| format!("Failed to bind {}", path.display()))
+    }
+
+    /// Answer every connection with the latest state
+    async fn serve(listener: UnixListener, state: watch::Receiver<String>) {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let json = state.borrow().clone();
+            tokio::spawn(async move {
+                if let Err(e) = stream.write_all(json.as_bytes()).await {
+                    tracing::warn!("Failed to send state to an attached TUI: {e}");
+                }
+            });
+        }
+    }
+
+    /// Read the daemon's current state
+    pub async fn fetch(path: &Path) -> Result<DaemonState> {
+        let mut stream = UnixStream::connect(path)
+            .await
+            .wrap_err_with(|| format!("No flux-tui daemon listening on {}", path.display()))?;
+        let mut json = String::new();
+        stream.read_to_string(&mut json).await?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
+
+#[cfg(not(unix))]
+mod unsupported {
+    use color_eyre::eyre::eyre;
+
+    use super::*;
+    use crate::app::config::Config;
+
+    /// The daemon needs Unix domain sockets
+    pub async fn run(_config: &Config) -> Result<()> {
+        Err(eyre!("The flux-tui daemon is only supported on Unix"))
+    }
+
+    /// The daemon needs Unix domain sockets
+    pub async fn fetch(_path: &Path) -> Result<DaemonState> {
+        Err(eyre!("The flux-tui daemon is only supported on Unix"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::FluxResource;
+
+    #[test]
+    fn test_state_round_trips_through_json() {
+        let kustomization = Kustomization {
+            name: "apps".to_string(),
+            namespace: "flux-system".to_string(),
+            status: ResourceStatus::Failed,
+            ..Default::default()
+        };
+        let key = ResourceKey::of(&kustomization as &dyn FluxResource);
+        let state = DaemonState {
+            kustomizations: vec![kustomization],
+            streaks: vec![(key.clone(), 3)],
+            timelines: vec![(key, vec![ResourceStatus::Ready, ResourceStatus::Failed])],
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&state).unwrap();
+        let parsed: DaemonState = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.kustomizations[0].name, "apps");
+        assert_eq!(parsed.streaks, state.streaks);
+        assert_eq!(parsed.timelines, state.timelines);
+    }
+
+    #[test]
+    fn test_socket_path_is_under_flux_tui() {
+        if let Some(path) = socket_path() {
+            assert!(path.ends_with("flux-tui/daemon.sock"));
+        }
+    }
+}
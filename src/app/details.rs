@@ -0,0 +1,374 @@
+//! Contents of the resource details popup
+//!
+//! The text is built here rather than in the UI so the popup's scroll offset
+//! can be clamped to its length when handling actions.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Local, Utc};
+
+use crate::kubernetes::resources::{format_age, format_duration, Condition, FluxResource};
+
+use super::{locale::Locale, state_file::StateFile, streaks};
+
+/// Full details text for a resource
+///
+/// Labels and annotations are summarized in one line unless `metadata` is set.
+pub fn text(
+    resource: &dyn FluxResource,
+    streak: u32,
+    state: &StateFile,
+    locale: &Locale,
+    metadata: bool,
+    now: DateTime<Utc>,
+) -> String {
+    rows(resource, streak, state, locale, metadata, now)
+        .iter()
+        .map(|(label, value)| detail_line(label, value))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Number of lines in the details text (before wrapping)
+pub fn line_count(
+    resource: &dyn FluxResource,
+    streak: u32,
+    state: &StateFile,
+    locale: &Locale,
+    metadata: bool,
+) -> usize {
+    text(resource, streak, state, locale, metadata, Utc::now())
+        .lines()
+        .count()
+}
+
+/// Labelled values shown in the popup, in display order
+fn rows(
+    resource: &dyn FluxResource,
+    streak: u32,
+    state: &StateFile,
+    locale: &Locale,
+    metadata: bool,
+    now: DateTime<Utc>,
+) -> Vec<(&'static str, String)> {
+    let yes_no = |value: bool| if value { "Yes" } else { "No" }.to_string();
+    let or_dash = |value: Option<&str>| value.unwrap_or("-").to_string();
+    let info = resource.reconcile_info();
+
+    let mut rows = vec![
+        ("Name", resource.name().to_string()),
+        ("Namespace", resource.namespace().to_string()),
+        ("Ready", yes_no(resource.is_ready())),
+        ("Status", resource.status_message().to_string()),
+        ("Revision", or_dash(resource.revision())),
+        ("Suspended", yes_no(resource.is_suspended())),
+        (
+            "Age",
+            resource
+                .created_at()
+                .map(|created| {
+                    format!(
+                        "{} (created {})",
+                        format_age(created, now),
+                        locale.datetime(&created.with_timezone(&Local))
+                    )
+                })
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+    ];
+
+    if let Some(generation) = resource.generation() {
+        let observed = match resource.observed_generation() {
+            Some(observed) if resource.has_unobserved_generation() => {
+                format!(" (controller has only observed {observed})")
+            }
+            _ => String::new(),
+        };
+        rows.push(("Generation", format!("{generation}{observed}")));
+    }
+
+    if let Some(note) = state.note(resource) {
+        rows.push((
+            "Note",
+            format!("{} ({} ago)", note.text, format_age(note.updated_at, now)),
+        ));
+    }
+
+    if let Some(until) = state.acknowledged_until(resource, now) {
+        rows.push((
+            "Acknowledged",
+            format!(
+                "for {} more (until {})",
+                format_duration(until - now),
+                locale.datetime(&until.with_timezone(&Local))
+            ),
+        ));
+    }
+
+    if streak > 0 {
+        rows.push(("Failing", streaks::describe(streak)));
+    }
+
+    rows.push(("Interval", or_dash(info.interval.as_deref())));
+    rows.push(("Timeout", or_dash(info.timeout.as_deref())));
+    // The reconcile.fluxcd.io/requestedAt value, an RFC 3339 timestamp when set by the flux CLI
+    let requested =
+        info.last_handled_reconcile_at.as_deref().map(
+            |requested| match DateTime::parse_from_rfc3339(requested) {
+                Ok(at) => locale.datetime(&at.with_timezone(&Local)),
+                Err(_) => requested.to_string(),
+            },
+        );
+    rows.push(("Requested", or_dash(requested.as_deref())));
+
+    rows.extend(resource.details());
+
+    if !info.conditions.is_empty() {
+        let conditions = info
+            .conditions
+            .iter()
+            .map(|c| condition_text(c, now))
+            .collect::<Vec<_>>()
+            .join("\n");
+        rows.push(("Conditions", conditions));
+    }
+
+    let (labels, annotations) = (resource.labels(), resource.annotations());
+    if metadata {
+        rows.push(("Labels", key_values(labels)));
+        rows.push(("Annotations", key_values(annotations)));
+    } else {
+        rows.push((
+            "Metadata",
+            format!(
+                "{} labels, {} annotations (m to expand)",
+                labels.len(),
+                annotations.len()
+            ),
+        ));
+    }
+
+    rows
+}
+
+/// Labels or annotations as "key=value" lines
+fn key_values(map: &BTreeMap<String, String>) -> String {
+    if map.is_empty() {
+        return "-".to_string();
+    }
+    map.iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Format a condition as a heading (e.g. "Ready=False BuildFailed, 3m ago") and its message
+fn condition_text(condition: &Condition, now: DateTime<Utc>) -> String {
+    let mut heading = format!("{}={}", condition.condition_type, condition.status);
+    let context: Vec<String> = condition
+        .reason
+        .clone()
+        .into_iter()
+        .chain(
+            condition
+                .last_transition
+                .map(|t| format!("{} ago", format_age(t, now))),
+        )
+        .collect();
+    if !context.is_empty() {
+        heading.push_str(&format!(" ({})", context.join(", ")));
+    }
+
+    condition
+        .message
+        .lines()
+        .fold(heading, |text, line| format!("{text}\n  {line}"))
+}
+
+/// Format a labelled details line, indenting continuation lines under the value
+fn detail_line(label: &str, value: &str) -> String {
+    let label = format!("{label}:");
+    let indent = " ".repeat(label.len().max(10) + 1);
+    let value = value
+        .lines()
+        .collect::<Vec<_>>()
+        .join(&format!("\n{indent}"));
+    format!("{label:<10} {value}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::{Kustomization, ReconcileInfo, ResourceStatus};
+
+    fn kustomization() -> Kustomization {
+        Kustomization {
+            name: "apps".to_string(),
+            namespace: "flux-system".to_string(),
+            status: ResourceStatus::Failed,
+            status_message: "kustomize build failed:\nmissing resource".to_string(),
+            reconcile: ReconcileInfo {
+                interval: Some("10m".to_string()),
+                conditions: vec![Condition {
+                    condition_type: "Ready".to_string(),
+                    status: "False".to_string(),
+                    reason: Some("BuildFailed".to_string()),
+                    message: "kustomize build failed:\nmissing resource".to_string(),
+                    last_transition: None,
+                }],
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_detail_line_multiline() {
+        assert_eq!(detail_line("URL", "https://x"), "URL:       https://x");
+        assert_eq!(detail_line("Include", "a\nb"), "Include:   a\n           b");
+    }
+
+    #[test]
+    fn test_text_includes_full_status_and_conditions() {
+        let text = text(
+            &kustomization(),
+            0,
+            &StateFile::default(),
+            &Locale::ISO,
+            false,
+            Utc::now(),
+        );
+        assert!(text.contains("Status:    kustomize build failed:\n           missing resource"));
+        assert!(text.contains("Interval:  10m"));
+        assert!(text.contains("Timeout:   -"));
+        assert!(text.contains(
+            "Conditions: Ready=False (BuildFailed)\n              kustomize build failed:\n              missing resource"
+        ));
+        assert!(!text.contains("Failing:"));
+        assert!(!text.contains("Note:"));
+    }
+
+    #[test]
+    fn test_text_includes_streak() {
+        let text = text(
+            &kustomization(),
+            4,
+            &StateFile::default(),
+            &Locale::ISO,
+            false,
+            Utc::now(),
+        );
+        assert!(text.contains("Failing:   4 refreshes (persistent)"));
+    }
+
+    #[test]
+    fn test_text_includes_note() {
+        let now = Utc::now();
+        let mut state = StateFile::default();
+        state.set_note(
+            &kustomization(),
+            "known issue",
+            now - chrono::Duration::hours(2),
+        );
+        let text = text(&kustomization(), 0, &state, &Locale::ISO, false, now);
+        assert!(text.contains("Note:      known issue (2h ago)"));
+    }
+
+    #[test]
+    fn test_text_includes_acknowledgement() {
+        let now = Utc::now();
+        let mut state = StateFile::default();
+        state.toggle_ack(&kustomization(), now + chrono::Duration::hours(3), now);
+        let text = text(&kustomization(), 0, &state, &Locale::ISO, false, now);
+        assert!(text.contains("Acknowledged: for 3h more"));
+    }
+
+    #[test]
+    fn test_text_formats_requested_at_with_locale() {
+        let mut resource = kustomization();
+        resource.reconcile.last_handled_reconcile_at = Some("2026-03-04T14:05:00Z".to_string());
+        let expected = DateTime::parse_from_rfc3339("2026-03-04T14:05:00Z")
+            .unwrap()
+            .with_timezone(&Local)
+            .format("%d.%m.%Y %H:%M")
+            .to_string();
+        let german = Locale::from_tag("de-DE").unwrap();
+        let details = text(
+            &resource,
+            0,
+            &StateFile::default(),
+            &german,
+            false,
+            Utc::now(),
+        );
+        assert!(details.contains(&format!("Requested: {expected}")));
+
+        resource.reconcile.last_handled_reconcile_at = Some("manual".to_string());
+        let details = text(
+            &resource,
+            0,
+            &StateFile::default(),
+            &german,
+            false,
+            Utc::now(),
+        );
+        assert!(details.contains("Requested: manual"));
+    }
+
+    #[test]
+    fn test_text_metadata_section() {
+        let mut resource = kustomization();
+        resource.labels = [("team".to_string(), "payments".to_string())].into();
+        resource.annotations = [
+            ("a".to_string(), "1".to_string()),
+            ("b".to_string(), "2".to_string()),
+        ]
+        .into();
+        let state = StateFile::default();
+
+        let collapsed = text(&resource, 0, &state, &Locale::ISO, false, Utc::now());
+        assert!(collapsed.contains("Metadata:  1 labels, 2 annotations (m to expand)"));
+        assert!(!collapsed.contains("team=payments"));
+
+        let expanded = text(&resource, 0, &state, &Locale::ISO, true, Utc::now());
+        assert!(expanded.contains("Labels:    team=payments"));
+        assert!(expanded.contains("Annotations: a=1\n             b=2"));
+    }
+
+    #[test]
+    fn test_condition_text_with_transition() {
+        let now = Utc::now();
+        let condition = Condition {
+            condition_type: "Ready".to_string(),
+            status: "True".to_string(),
+            last_transition: Some(now - chrono::Duration::minutes(3)),
+            ..Default::default()
+        };
+        assert_eq!(condition_text(&condition, now), "Ready=True (3m ago)");
+    }
+
+    #[test]
+    fn test_line_count() {
+        let count = line_count(
+            &kustomization(),
+            0,
+            &StateFile::default(),
+            &Locale::ISO,
+            false,
+        );
+        assert_eq!(
+            count,
+            text(
+                &kustomization(),
+                0,
+                &StateFile::default(),
+                &Locale::ISO,
+                false,
+                Utc::now()
+            )
+            .lines()
+            .count()
+        );
+        assert!(count > 10);
+    }
+}
@@ -0,0 +1,202 @@
+//! Diagnostic report bundles for bug reports
+//!
+//! A report holds version info, a summary of the app state, recent errors and
+//! recent API requests. It never includes kubeconfig contents, credentials or
+//! resource specs, so it can be attached to an issue as-is.
+
+use std::{fmt::Write, path::PathBuf, sync::Mutex};
+
+use chrono::Utc;
+use color_eyre::Result;
+
+use crate::kubernetes::resources::ResourceStatus;
+
+use super::state::{App, Tab};
+
+/// Last known state summary, used when writing a report from the panic hook
+static SNAPSHOT: Mutex<Option<String>> = Mutex::new(None);
+
+/// Build a full diagnostic report for the running app
+pub fn report(app: &App) -> String {
+    format!("{}\n{}", header(), summary(app))
+}
+
+/// Build a report for a panic, using the last remembered state summary
+pub fn panic_report(panic: &str) -> String {
+    let snapshot = SNAPSHOT
+        .lock()
+        .ok()
+        .and_then(|s| s.clone())
+        .unwrap_or_else(|| "(no state captured yet)\n".to_string());
+
+    format!("{}\n== Panic ==\n{}\n\n{}", header(), panic, snapshot)
+}
+
+/// Remember a summary of the current state in case the app panics later
+pub fn remember(app: &App) {
+    if let Ok(mut snapshot) = SNAPSHOT.lock() {
+        *snapshot = Some(summary(app));
+    }
+}
+
+/// Write a report to a new file in the system temp directory
+pub fn write_report(contents: &str) -> Result<PathBuf> {
+    let file_name = format!(
+        "flux-tui-report-{}.txt",
+        Utc::now().format("%Y%m%d-%H%M%S%.3f")
+    );
+    let path = std::env::temp_dir().join(file_name);
+    std::fs::write(&path, contents)?;
+    Ok(path)
+}
+
+/// Version and platform information
+fn header() -> String {
+    format!(
+        "flux-tui diagnostic report\n\
+         version:   {}\n\
+         platform:  {} ({})\n\
+         generated: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        Utc::now().to_rfc3339(),
+    )
+}
+
+/// Summary of the app state, recent errors and recent API requests
+fn summary(app: &App) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "== State ==");
+    let _ = writeln!(out, "cluster:   {}", app.cluster_name);
+    let _ = writeln!(
+        out,
+        "namespace: {}",
+        app.namespace_filter.as_deref().unwrap_or("all")
+    );
+    let _ = writeln!(out, "tab:       {}", app.tab.name());
+    let _ = writeln!(out, "loading:   {}", app.loading);
+    let resources = app.all_resources();
+    for tab in Tab::all() {
+        let statuses = resources
+            .iter()
+            .filter(|r| r.kind() == tab.kind())
+            .map(|r| r.status());
+        let label = format!("{}:", tab.name().to_lowercase());
+        let _ = writeln!(out, "{label:<17} {}", status_counts(statuses));
+    }
+
+    let _ = writeln!(out, "\n== Recent errors ==");
+    if app.recent_errors.is_empty() {
+        let _ = writeln!(out, "(none)");
+    }
+    for (at, message) in app.recent_errors.iter() {
+        let _ = writeln!(out, "{} {}", at.to_rfc3339(), message);
+    }
+
+    let _ = writeln!(out, "\n== Recent API requests ==");
+    if let Ok(log) = app.api_log.lock() {
+        if log.is_empty() {
+            let _ = writeln!(out, "(none)");
+        }
+        for call in log.iter() {
+            let _ = writeln!(
+                out,
+                "{} {} ({} ms){}",
+                call.at.to_rfc3339(),
+                call.request,
+                call.duration.as_millis(),
+                call.error
+                    .as_ref()
+                    .map(|e| format!(" error: {e}"))
+                    .unwrap_or_default()
+            );
+        }
+    }
+
+    out
+}
+
+/// Count resources by status, e.g. "3 total (2 Ready, 1 Failed)"
+fn status_counts<'a>(statuses: impl Iterator<Item = &'a ResourceStatus>) -> String {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    let mut total = 0;
+
+    for status in statuses {
+        total += 1;
+        let name = format!("{status:?}");
+        match counts.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((name, 1)),
+        }
+    }
+
+    if counts.is_empty() {
+        return "0 total".to_string();
+    }
+
+    let parts: Vec<String> = counts
+        .iter()
+        .map(|(name, count)| format!("{count} {name}"))
+        .collect();
+    format!("{total} total ({})", parts.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::state::Tab;
+    use crate::kubernetes::resources::Kustomization;
+
+    #[test]
+    fn test_status_counts() {
+        let statuses = [
+            ResourceStatus::Ready,
+            ResourceStatus::Failed,
+            ResourceStatus::Ready,
+        ];
+        assert_eq!(
+            status_counts(statuses.iter()),
+            "3 total (2 Ready, 1 Failed)"
+        );
+        assert_eq!(status_counts([].iter()), "0 total");
+    }
+
+    #[test]
+    fn test_report_contains_state_and_errors() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![Kustomization {
+                name: "apps".to_string(),
+                status: ResourceStatus::Failed,
+                ..Default::default()
+            }],
+            vec![],
+            vec![],
+        );
+        app.recent_errors
+            .push((Utc::now(), "Failed to fetch resources".to_string()));
+
+        let report = report(&app);
+        assert!(report.contains(env!("CARGO_PKG_VERSION")));
+        assert!(report.contains("cluster:   test-cluster"));
+        assert!(report.contains("kustomizations:   1 total (1 Failed)"));
+        assert!(report.contains("helmrepositories: 0 total"));
+        assert!(report.contains("Failed to fetch resources"));
+    }
+
+    #[test]
+    fn test_panic_report_includes_message() {
+        let report = panic_report("index out of bounds");
+        assert!(report.contains("== Panic =="));
+        assert!(report.contains("index out of bounds"));
+    }
+
+    #[test]
+    fn test_write_report() {
+        let path = write_report("contents").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "contents");
+        std::fs::remove_file(path).unwrap();
+    }
+}
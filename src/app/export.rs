@@ -0,0 +1,271 @@
+//! Headless export of resource status (`flux-tui export`)
+//!
+//! Lists resources once, without starting the TUI, and writes them in a
+//! machine-readable format to stdout or a file.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+
+use chrono::Utc;
+use color_eyre::Result;
+
+use crate::kubernetes::resources::{FluxResource, ResourceStatus};
+
+use super::{
+    snapshot::Snapshot,
+    state::{App, Tab},
+};
+
+/// Export format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Status counts in Prometheus exposition format (node_exporter textfile collector)
+    Prometheus,
+
+    /// JUnit XML report with one test case per resource, failing unless Ready
+    Junit,
+
+    /// Every loaded resource as JSON, to open later with `--snapshot`
+    Snapshot,
+}
+
+/// Every status, in the order series are written
+const STATUSES: [ResourceStatus; 8] = [
+    ResourceStatus::Ready,
+    ResourceStatus::Failed,
+    ResourceStatus::Reconciling,
+    ResourceStatus::Stalled,
+    ResourceStatus::Suspended,
+    ResourceStatus::SuspendedFailed,
+    ResourceStatus::Pending,
+    ResourceStatus::Unknown,
+];
+
+/// Render the app's resources in an export format
+pub fn render(app: &App, format: Format) -> Result<String> {
+    match format {
+        Format::Prometheus => Ok(prometheus(app)),
+        Format::Junit => Ok(junit(app)),
+        Format::Snapshot => Snapshot::of(app, Utc::now()).to_json(),
+    }
+}
+
+/// Write an export to a file, or to stdout when no file is given
+///
+/// Files are replaced atomically, so a collector never reads a partial file.
+pub fn write(contents: &str, file: Option<&Path>) -> Result<()> {
+    match file {
+        Some(path) => {
+            let tmp = path.with_extension("tmp");
+            std::fs::write(&tmp, contents)?;
+            std::fs::rename(&tmp, path)?;
+        }
+        None => std::io::stdout().write_all(contents.as_bytes())?,
+    }
+    Ok(())
+}
+
+/// Resource counts per cluster, kind and status, including zero counts so
+/// series don't disappear when the last failure is fixed
+fn prometheus(app: &App) -> String {
+    let mut counts: BTreeMap<(String, &str), [usize; STATUSES.len()]> = BTreeMap::new();
+    let clusters = if app.is_multi_cluster() {
+        app.cluster_labels()
+    } else {
+        vec![app.cluster_name.clone()]
+    };
+    for cluster in clusters {
+        for tab in Tab::all() {
+            counts.insert((cluster.clone(), tab.kind()), Default::default());
+        }
+    }
+    for resource in app.all_resources() {
+        let cluster = cluster_label(app, resource);
+        let index = STATUSES
+            .iter()
+            .position(|s| s == resource.status())
+            .unwrap_or(STATUSES.len() - 1);
+        counts.entry((cluster, resource.kind())).or_default()[index] += 1;
+    }
+
+    let mut out = String::from(
+        "# HELP flux_tui_resources Flux resources by cluster, kind and status\n\
+         # TYPE flux_tui_resources gauge\n",
+    );
+    for ((cluster, kind), by_status) in counts {
+        for (status, count) in STATUSES.iter().zip(by_status) {
+            out.push_str(&format!(
+                "flux_tui_resources{{cluster=\"{}\",kind=\"{}\",status=\"{}\"}} {}\n",
+                escape(&cluster),
+                kind,
+                status_label(status),
+                count
+            ));
+        }
+    }
+    out
+}
+
+/// One test suite per kind, one test case per resource
+///
+/// Resources fail unless Ready; suspended resources are skipped.
+fn junit(app: &App) -> String {
+    let resources = app.all_resources();
+    let mut out =
+        String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"flux\">\n");
+    for tab in Tab::all() {
+        let cases: Vec<&dyn FluxResource> = resources
+            .iter()
+            .copied()
+            .filter(|r| r.kind() == tab.kind())
+            .collect();
+        let failures = cases
+            .iter()
+            .filter(|r| !r.is_ready() && !r.is_suspended())
+            .count();
+        let skipped = cases.iter().filter(|r| r.is_suspended()).count();
+        out.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\">\n",
+            tab.kind(),
+            cases.len(),
+            failures,
+            skipped
+        ));
+        for resource in cases {
+            out.push_str(&format!(
+                "    <testcase classname=\"{}.{}\" name=\"{}/{}\"",
+                xml_escape(&cluster_label(app, resource)),
+                resource.kind(),
+                xml_escape(resource.namespace()),
+                xml_escape(resource.name())
+            ));
+            if resource.is_suspended() {
+                out.push_str(">\n      <skipped message=\"suspended\"/>\n    </testcase>\n");
+            } else if !resource.is_ready() {
+                out.push_str(&format!(
+                    ">\n      <failure message=\"{}\">{}</failure>\n    </testcase>\n",
+                    xml_escape(&format!("{:?}", resource.status())),
+                    xml_escape(resource.status_message())
+                ));
+            } else {
+                out.push_str("/>\n");
+            }
+        }
+        out.push_str("  </testsuite>\n");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+/// Cluster a resource was listed from; single-cluster resources carry none
+fn cluster_label(app: &App, resource: &dyn FluxResource) -> String {
+    match resource.cluster() {
+        "" => app.cluster_name.clone(),
+        cluster => cluster.to_string(),
+    }
+}
+
+/// Lowercase status label value
+fn status_label(status: &ResourceStatus) -> &'static str {
+    match status {
+        ResourceStatus::Ready => "ready",
+        ResourceStatus::Failed => "failed",
+        ResourceStatus::Reconciling => "reconciling",
+        ResourceStatus::Stalled => "stalled",
+        ResourceStatus::Suspended => "suspended",
+        ResourceStatus::SuspendedFailed => "suspended_failed",
+        ResourceStatus::Pending => "pending",
+        ResourceStatus::Unknown => "unknown",
+    }
+}
+
+/// Escape a Prometheus label value
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Escape text and attribute values for XML
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::Kustomization;
+
+    #[test]
+    fn test_prometheus_counts_with_zeroes() {
+        let failed = Kustomization {
+            name: "apps".to_string(),
+            status: ResourceStatus::Failed,
+            ..Default::default()
+        };
+        let ready = Kustomization {
+            name: "infra".to_string(),
+            status: ResourceStatus::Ready,
+            ..Default::default()
+        };
+        let mut app = App::for_testing(Tab::Kustomizations, vec![failed, ready], vec![], vec![]);
+        app.cluster_name = "prod".to_string();
+
+        let out = render(&app, Format::Prometheus).unwrap();
+        assert!(out.starts_with("# HELP flux_tui_resources"));
+        assert!(out.contains(
+            "flux_tui_resources{cluster=\"prod\",kind=\"Kustomization\",status=\"failed\"} 1\n"
+        ));
+        assert!(out.contains(
+            "flux_tui_resources{cluster=\"prod\",kind=\"Kustomization\",status=\"ready\"} 1\n"
+        ));
+        assert!(out.contains(
+            "flux_tui_resources{cluster=\"prod\",kind=\"HelmRelease\",status=\"failed\"} 0\n"
+        ));
+        assert_eq!(out.lines().count(), 2 + 5 * STATUSES.len());
+    }
+
+    #[test]
+    fn test_junit_case_per_resource() {
+        let failed = Kustomization {
+            name: "apps".to_string(),
+            namespace: "flux-system".to_string(),
+            status: ResourceStatus::Failed,
+            status_message: "kustomize build failed: <missing>".to_string(),
+            ..Default::default()
+        };
+        let suspended = Kustomization {
+            name: "infra".to_string(),
+            namespace: "flux-system".to_string(),
+            status: ResourceStatus::Suspended,
+            suspended: true,
+            ..Default::default()
+        };
+        let mut app =
+            App::for_testing(Tab::Kustomizations, vec![failed, suspended], vec![], vec![]);
+        app.cluster_name = "prod".to_string();
+
+        let out = render(&app, Format::Junit).unwrap();
+        assert!(out.contains(
+            "<testsuite name=\"Kustomization\" tests=\"2\" failures=\"1\" skipped=\"1\">"
+        ));
+        assert!(out.contains(
+            "<testcase classname=\"prod.Kustomization\" name=\"flux-system/apps\">\n      <failure message=\"Failed\">kustomize build failed: &lt;missing&gt;</failure>"
+        ));
+        assert!(out.contains("<skipped message=\"suspended\"/>"));
+        assert!(out
+            .contains("<testsuite name=\"HelmRelease\" tests=\"0\" failures=\"0\" skipped=\"0\">"));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape(r#"a"b\c"#), r#"a\"b\\c"#);
+        assert_eq!(xml_escape("a<b & \"c\""), "a&lt;b &amp; &quot;c&quot;");
+    }
+}
@@ -0,0 +1,84 @@
+//! Search across all kinds (`Ctrl+F`)
+//!
+//! For when the name of a resource is known but not which controller owns it:
+//! the names of every loaded kind are matched at once, and picking a match
+//! goes to its tab and row.
+
+use std::cmp::Reverse;
+
+use crate::kubernetes::resources::FluxResource;
+
+use super::search::FuzzyFilter;
+
+/// Resources whose name matches the query, best match first
+///
+/// Of equally good matches the shortest name comes first, so an exact match
+/// leads its longer namesakes; ties keep the tab order. An empty query lists
+/// everything.
+pub fn matches<'a>(resources: Vec<&'a dyn FluxResource>, query: &str) -> Vec<&'a dyn FluxResource> {
+    let filter = FuzzyFilter::new(query);
+    let mut scored: Vec<(i64, &dyn FluxResource)> = resources
+        .into_iter()
+        .filter_map(|resource| Some((filter.score(resource.name())?, resource)))
+        .collect();
+    scored.sort_by_key(|(score, resource)| (Reverse(*score), resource.name().len()));
+    scored.into_iter().map(|(_, resource)| resource).collect()
+}
+
+/// Entry of a match, e.g. "HelmRelease apps/podinfo"
+pub fn label(resource: &dyn FluxResource, show_cluster: bool) -> String {
+    let label = format!(
+        "{} {}/{}",
+        resource.kind(),
+        resource.namespace(),
+        resource.name()
+    );
+    if show_cluster {
+        format!("{}: {label}", resource.cluster())
+    } else {
+        label
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::{GitRepository, HelmRelease, Kustomization};
+
+    #[test]
+    fn test_matches() {
+        let kustomization = Kustomization {
+            name: "podinfo-config".to_string(),
+            namespace: "flux-system".to_string(),
+            ..Default::default()
+        };
+        let release = HelmRelease {
+            name: "podinfo".to_string(),
+            namespace: "apps".to_string(),
+            ..Default::default()
+        };
+        let repository = GitRepository {
+            name: "fleet".to_string(),
+            namespace: "flux-system".to_string(),
+            ..Default::default()
+        };
+        let resources: Vec<&dyn FluxResource> = vec![&kustomization, &release, &repository];
+
+        let labels = |query| {
+            matches(resources.clone(), query)
+                .into_iter()
+                .map(|r| label(r, false))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            labels("podinfo"),
+            vec![
+                "HelmRelease apps/podinfo",
+                "Kustomization flux-system/podinfo-config"
+            ]
+        );
+        assert_eq!(labels("flt"), vec!["GitRepository flux-system/fleet"]);
+        assert_eq!(labels("").len(), 3);
+        assert!(labels("cert-manager").is_empty());
+    }
+}
@@ -0,0 +1,252 @@
+//! One-shot listing of resources (`flux-tui get`)
+//!
+//! Lists a single kind with the same client and resource parsers as the TUI,
+//! prints it once and exits, so scripts and CI can use the same binary.
+
+use chrono::{DateTime, Utc};
+use color_eyre::Result;
+use serde::Serialize;
+
+use crate::kubernetes::{
+    client::KubeClient,
+    resources::{
+        format_age, FluxResource, GitRepository, HelmChart, HelmRelease, HelmRepository,
+        Kustomization,
+    },
+    yaml,
+};
+
+use super::config::Config;
+use super::state::Tab;
+
+/// Resource kind to list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Kind {
+    #[value(name = "kustomizations", alias = "kustomization", alias = "ks")]
+    Kustomizations,
+
+    #[value(name = "helmreleases", alias = "helmrelease", alias = "hr")]
+    HelmReleases,
+
+    #[value(name = "helmcharts", alias = "helmchart", alias = "hc")]
+    HelmCharts,
+
+    #[value(name = "gitrepositories", alias = "gitrepository", alias = "gitrepo")]
+    GitRepositories,
+
+    #[value(
+        name = "helmrepositories",
+        alias = "helmrepository",
+        alias = "helmrepo"
+    )]
+    HelmRepositories,
+}
+
+impl Kind {
+    /// Tab listing this kind
+    pub fn tab(self) -> Tab {
+        match self {
+            Kind::Kustomizations => Tab::Kustomizations,
+            Kind::HelmReleases => Tab::HelmReleases,
+            Kind::HelmCharts => Tab::HelmCharts,
+            Kind::GitRepositories => Tab::GitRepositories,
+            Kind::HelmRepositories => Tab::HelmRepositories,
+        }
+    }
+}
+
+/// Output format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Output {
+    /// Aligned columns, like kubectl
+    Table,
+
+    /// Parsed resources as a JSON array
+    Json,
+
+    /// Parsed resources as a YAML sequence
+    Yaml,
+}
+
+/// List resources of a kind and render them
+///
+/// Lists the configured namespace, or all namespaces when none is set.
+pub async fn run(config: &Config, kind: Kind, output: Output) -> Result<String> {
+    let client = KubeClient::new(config).await?;
+    let namespace = config.namespace.as_deref();
+    let now = Utc::now();
+    match kind {
+        Kind::Kustomizations => render(client.list::<Kustomization>(namespace).await?, output, now),
+        Kind::HelmReleases => render(client.list::<HelmRelease>(namespace).await?, output, now),
+        Kind::HelmCharts => render(client.list::<HelmChart>(namespace).await?, output, now),
+        Kind::GitRepositories => {
+            render(client.list::<GitRepository>(namespace).await?, output, now)
+        }
+        Kind::HelmRepositories => {
+            render(client.list::<HelmRepository>(namespace).await?, output, now)
+        }
+    }
+}
+
+/// Render resources sorted by namespace and name
+pub fn render<T: FluxResource + Serialize>(
+    mut items: Vec<T>,
+    output: Output,
+    now: DateTime<Utc>,
+) -> Result<String> {
+    items.sort_by(|a, b| (a.namespace(), a.name()).cmp(&(b.namespace(), b.name())));
+    Ok(match output {
+        Output::Table => table(&items, now),
+        Output::Json => serde_json::to_string_pretty(&items)? + "\n",
+        Output::Yaml => yaml::to_string(&items)?,
+    })
+}
+
+/// Columns padded to their widest cell; the message is left unpadded
+fn table<T: FluxResource>(items: &[T], now: DateTime<Utc>) -> String {
+    let mut rows =
+        vec![["NAMESPACE", "NAME", "STATUS", "SUSPENDED", "AGE", "MESSAGE"].map(String::from)];
+    rows.extend(items.iter().map(|item| {
+        [
+            item.namespace().to_string(),
+            item.name().to_string(),
+            format!("{:?}", item.status()),
+            item.is_suspended().to_string(),
+            item.created_at()
+                .map(|created| format_age(created, now))
+                .unwrap_or_else(|| "-".to_string()),
+            item.status_message()
+                .lines()
+                .next()
+                .unwrap_or("")
+                .to_string(),
+        ]
+    }));
+
+    let mut widths = [0; 5];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let mut out = String::new();
+    for row in rows {
+        let mut line = String::new();
+        for (cell, width) in row.iter().zip(widths) {
+            line.push_str(&format!("{cell:<width$}   "));
+        }
+        line.push_str(&row[5]);
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::{Condition, Kustomization, ReconcileInfo, ResourceStatus};
+
+    fn kustomizations() -> Vec<Kustomization> {
+        vec![
+            Kustomization {
+                name: "podinfo".to_string(),
+                namespace: "apps".to_string(),
+                status: ResourceStatus::Failed,
+                status_message: "kustomize build failed\nat line 3".to_string(),
+                created_at: DateTime::from_timestamp(1_700_000_000, 0),
+                ..Default::default()
+            },
+            Kustomization {
+                name: "flux-system".to_string(),
+                namespace: "flux-system".to_string(),
+                status: ResourceStatus::Ready,
+                suspended: true,
+                ..Default::default()
+            },
+            Kustomization {
+                name: "infra".to_string(),
+                namespace: "apps".to_string(),
+                status: ResourceStatus::Ready,
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_render_table() {
+        let now = DateTime::from_timestamp(1_700_000_000 + 3 * 3600, 0).unwrap();
+        let out = render(kustomizations(), Output::Table, now).unwrap();
+        assert_eq!(
+            out,
+            "NAMESPACE     NAME          STATUS   SUSPENDED   AGE   MESSAGE\n\
+             apps          infra         Ready    false       -\n\
+             apps          podinfo       Failed   false       3h    kustomize build failed\n\
+             flux-system   flux-system   Ready    true        -\n"
+        );
+    }
+
+    #[test]
+    fn test_render_json_and_yaml() {
+        let now = Utc::now();
+        let json = render(kustomizations(), Output::Json, now).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[0]["name"], "infra");
+        assert_eq!(parsed[1]["status"], "Failed");
+
+        let podinfo = Kustomization {
+            depends_on: vec!["infra".to_string()],
+            reconcile: ReconcileInfo {
+                interval: Some("10m".to_string()),
+                conditions: vec![Condition {
+                    condition_type: "Ready".to_string(),
+                    status: "False".to_string(),
+                    reason: Some("BuildFailed".to_string()),
+                    message: "kustomize build failed\nat line 3".to_string(),
+                    last_transition: None,
+                }],
+                ..Default::default()
+            },
+            ..kustomizations().remove(0)
+        };
+        let yaml = render(vec![podinfo], Output::Yaml, now).unwrap();
+        assert_eq!(
+            yaml,
+            "\
+- annotations: {}
+  cluster: \"\"
+  created_at: \"2023-11-14T22:13:20Z\"
+  depends_on:
+  - infra
+  generation: null
+  health_checks: []
+  labels: {}
+  name: podinfo
+  namespace: apps
+  observed_generation: null
+  path: \"\"
+  reconcile:
+    conditions:
+    - condition_type: Ready
+      last_transition: null
+      message: |-
+        kustomize build failed
+        at line 3
+      reason: BuildFailed
+      status: \"False\"
+    interval: 10m
+    last_handled_reconcile_at: null
+    timeout: null
+  revision: null
+  source: null
+  source_ref: \"\"
+  status: Failed
+  status_message: |-
+    kustomize build failed
+    at line 3
+  suspended: false
+  wait: false
+"
+        );
+    }
+}
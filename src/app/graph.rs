@@ -0,0 +1,260 @@
+//! Kustomization `dependsOn` graph, flattened into an indented tree
+
+use std::collections::{HashMap, HashSet};
+
+use crate::kubernetes::resources::{FluxResource, Kustomization, ResourceStatus};
+
+/// One line of the dependency tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphNode {
+    /// Indentation level (0 = no dependencies in the cluster)
+    pub depth: usize,
+
+    /// Cluster the Kustomization was listed from
+    pub cluster: String,
+
+    /// Kustomization as "namespace/name"
+    pub name: String,
+
+    /// Current status
+    pub status: ResourceStatus,
+
+    /// Dependencies that are not Ready (or don't exist), as "namespace/name"
+    pub waiting_on: Vec<String>,
+
+    /// Already shown earlier in the tree; its dependents are not repeated
+    pub repeated: bool,
+}
+
+/// Build the tree of Kustomizations, dependents nested under what they depend on
+///
+/// A Kustomization with several dependencies appears under each of them.
+/// Cycles are broken by showing every Kustomization's dependents only once.
+pub fn dependency_tree(kustomizations: &[&Kustomization]) -> Vec<GraphNode> {
+    let by_key: HashMap<(&str, String), &Kustomization> = kustomizations
+        .iter()
+        .map(|k| ((k.cluster(), qualified_name(k)), *k))
+        .collect();
+
+    let mut dependents: HashMap<(&str, String), Vec<&Kustomization>> = HashMap::new();
+    let mut roots = Vec::new();
+    for k in kustomizations {
+        let known: Vec<&String> = k
+            .depends_on
+            .iter()
+            .filter(|dep| by_key.contains_key(&(k.cluster(), (*dep).clone())))
+            .collect();
+        if known.is_empty() {
+            roots.push(*k);
+        }
+        for dep in known {
+            dependents
+                .entry((k.cluster(), dep.clone()))
+                .or_default()
+                .push(*k);
+        }
+    }
+    for list in dependents.values_mut() {
+        list.sort_by_key(|k| (k.cluster(), qualified_name(k)));
+    }
+    roots.sort_by_key(|k| (k.cluster(), qualified_name(k)));
+
+    let mut tree = TreeBuilder {
+        by_key: &by_key,
+        dependents: &dependents,
+        expanded: HashSet::new(),
+        nodes: Vec::new(),
+    };
+    for root in roots {
+        tree.visit(root, 0);
+    }
+
+    // Kustomizations that only depend on each other in a cycle have no root
+    let mut rest: Vec<&Kustomization> = kustomizations
+        .iter()
+        .copied()
+        .filter(|k| !tree.expanded.contains(&(k.cluster(), qualified_name(k))))
+        .collect();
+    rest.sort_by_key(|k| (k.cluster(), qualified_name(k)));
+    for k in rest {
+        if !tree.expanded.contains(&(k.cluster(), qualified_name(k))) {
+            tree.visit(k, 0);
+        }
+    }
+
+    tree.nodes
+}
+
+/// Depth-first walk state for [`dependency_tree`]
+struct TreeBuilder<'a, 'k> {
+    by_key: &'a HashMap<(&'k str, String), &'k Kustomization>,
+    dependents: &'a HashMap<(&'k str, String), Vec<&'k Kustomization>>,
+    expanded: HashSet<(&'k str, String)>,
+    nodes: Vec<GraphNode>,
+}
+
+impl<'k> TreeBuilder<'_, 'k> {
+    fn visit(&mut self, k: &'k Kustomization, depth: usize) {
+        let key = (k.cluster(), qualified_name(k));
+        let repeated = !self.expanded.insert(key.clone());
+
+        let waiting_on = k
+            .depends_on
+            .iter()
+            .filter(|dep| {
+                self.by_key
+                    .get(&(k.cluster(), (*dep).clone()))
+                    .is_none_or(|d| d.status != ResourceStatus::Ready)
+            })
+            .cloned()
+            .collect();
+
+        self.nodes.push(GraphNode {
+            depth,
+            cluster: k.cluster().to_string(),
+            name: key.1.clone(),
+            status: k.status.clone(),
+            waiting_on,
+            repeated,
+        });
+
+        if repeated {
+            return;
+        }
+        if let Some(children) = self.dependents.get(&key) {
+            for child in children.clone() {
+                self.visit(child, depth + 1);
+            }
+        }
+    }
+}
+
+/// "namespace/name", as used in `dependsOn`
+fn qualified_name(k: &Kustomization) -> String {
+    format!("{}/{}", k.namespace, k.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kustomization(name: &str, status: ResourceStatus, depends_on: &[&str]) -> Kustomization {
+        Kustomization {
+            name: name.to_string(),
+            namespace: "flux-system".to_string(),
+            status,
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn summary(nodes: &[GraphNode]) -> Vec<(usize, &str, bool)> {
+        nodes
+            .iter()
+            .map(|n| (n.depth, n.name.as_str(), n.repeated))
+            .collect()
+    }
+
+    #[test]
+    fn test_dependents_nested_under_dependencies() {
+        let items = [
+            kustomization("apps", ResourceStatus::Failed, &["flux-system/infra"]),
+            kustomization("infra", ResourceStatus::Ready, &[]),
+            kustomization("monitoring", ResourceStatus::Ready, &[]),
+        ];
+        let refs: Vec<&Kustomization> = items.iter().collect();
+        let nodes = dependency_tree(&refs);
+
+        assert_eq!(
+            summary(&nodes),
+            [
+                (0, "flux-system/infra", false),
+                (1, "flux-system/apps", false),
+                (0, "flux-system/monitoring", false),
+            ]
+        );
+        assert!(nodes[1].waiting_on.is_empty());
+    }
+
+    #[test]
+    fn test_waiting_on_unready_and_missing_dependencies() {
+        let items = [
+            kustomization("infra", ResourceStatus::Failed, &[]),
+            kustomization(
+                "apps",
+                ResourceStatus::Reconciling,
+                &["flux-system/infra", "flux-system/missing"],
+            ),
+        ];
+        let refs: Vec<&Kustomization> = items.iter().collect();
+        let nodes = dependency_tree(&refs);
+
+        assert_eq!(nodes[1].name, "flux-system/apps");
+        assert_eq!(
+            nodes[1].waiting_on,
+            ["flux-system/infra", "flux-system/missing"]
+        );
+    }
+
+    #[test]
+    fn test_shared_dependent_expanded_once() {
+        let items = [
+            kustomization("a", ResourceStatus::Ready, &[]),
+            kustomization("b", ResourceStatus::Ready, &[]),
+            kustomization(
+                "c",
+                ResourceStatus::Ready,
+                &["flux-system/a", "flux-system/b"],
+            ),
+            kustomization("d", ResourceStatus::Ready, &["flux-system/c"]),
+        ];
+        let refs: Vec<&Kustomization> = items.iter().collect();
+
+        assert_eq!(
+            summary(&dependency_tree(&refs)),
+            [
+                (0, "flux-system/a", false),
+                (1, "flux-system/c", false),
+                (2, "flux-system/d", false),
+                (0, "flux-system/b", false),
+                (1, "flux-system/c", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cycle_is_shown_without_recursing_forever() {
+        let items = [
+            kustomization("a", ResourceStatus::Ready, &["flux-system/b"]),
+            kustomization("b", ResourceStatus::Ready, &["flux-system/a"]),
+        ];
+        let refs: Vec<&Kustomization> = items.iter().collect();
+
+        assert_eq!(
+            summary(&dependency_tree(&refs)),
+            [
+                (0, "flux-system/a", false),
+                (1, "flux-system/b", false),
+                (2, "flux-system/a", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clusters_kept_apart() {
+        let eu = Kustomization {
+            cluster: "prod-eu".to_string(),
+            ..kustomization("infra", ResourceStatus::Ready, &[])
+        };
+        let us = Kustomization {
+            cluster: "prod-us".to_string(),
+            ..kustomization("apps", ResourceStatus::Ready, &["flux-system/infra"])
+        };
+        let refs = [&eu, &us];
+        let nodes = dependency_tree(&refs);
+
+        assert_eq!(nodes.len(), 2);
+        assert!(nodes.iter().all(|n| n.depth == 0));
+        assert_eq!(nodes[1].waiting_on, ["flux-system/infra"]);
+    }
+}
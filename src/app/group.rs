@@ -0,0 +1,192 @@
+//! Grouping of the consumer tabs under their sources (`Z`)
+//!
+//! With one Git repository and dozens of paths, a flat Kustomization list is
+//! hard to scan. Grouped, each source gets a header row with its consumer and
+//! failure counts, and can be collapsed to that single row.
+
+use std::collections::{BTreeMap, HashSet};
+
+use crate::kubernetes::resources::FluxResource;
+
+use super::status_filter::StatusFilter;
+
+/// Header of the resources consuming one source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    /// Identifies the group when collapsing it
+    pub key: String,
+
+    /// The source, e.g. "GitRepository flux-system/fleet"
+    pub label: String,
+
+    pub resources: usize,
+    pub failing: usize,
+    pub collapsed: bool,
+}
+
+impl Group {
+    /// Header text, e.g. "▾ GitRepository flux-system/fleet · 12 resources, 2 failing"
+    pub fn title(&self) -> String {
+        let fold = if self.collapsed { "▸" } else { "▾" };
+        let resources = match self.resources {
+            1 => "1 resource".to_string(),
+            n => format!("{n} resources"),
+        };
+        match self.failing {
+            0 => format!("{fold} {} · {resources}", self.label),
+            n => format!("{fold} {} · {resources}, {n} failing", self.label),
+        }
+    }
+}
+
+/// A row of a resource table
+#[derive(Debug)]
+pub enum TableRow<'a, T: ?Sized> {
+    Group(Group),
+    Resource(&'a T),
+}
+
+impl<'a, T: ?Sized> TableRow<'a, T> {
+    /// The resource of a resource row
+    pub fn resource(&self) -> Option<&'a T> {
+        match self {
+            TableRow::Group(_) => None,
+            TableRow::Resource(resource) => Some(resource),
+        }
+    }
+}
+
+/// Key of the group a resource is listed under
+pub fn key(resource: &dyn FluxResource) -> String {
+    match resource.source() {
+        Some(source) => format!(
+            "{}/{}/{}/{}",
+            resource.cluster(),
+            source.kind,
+            source.namespace,
+            source.name
+        ),
+        None => format!("{}/", resource.cluster()),
+    }
+}
+
+/// Header label of the group a resource is listed under
+fn label(resource: &dyn FluxResource, show_cluster: bool) -> String {
+    let source = match resource.source() {
+        Some(source) => format!("{} {}/{}", source.kind, source.namespace, source.name),
+        None => "No source".to_string(),
+    };
+    if show_cluster {
+        format!("{}: {source}", resource.cluster())
+    } else {
+        source
+    }
+}
+
+/// Rows of resources under a header per source, ordered by source
+///
+/// Resources keep their order within a group; those of collapsed groups are
+/// left out.
+pub fn rows<'a, T: FluxResource>(
+    items: Vec<&'a T>,
+    collapsed: &HashSet<String>,
+    show_cluster: bool,
+) -> Vec<TableRow<'a, T>> {
+    let mut groups: BTreeMap<String, Vec<&T>> = BTreeMap::new();
+    for item in items {
+        groups.entry(key(item)).or_default().push(item);
+    }
+
+    let mut rows = Vec::new();
+    for (key, members) in groups {
+        let collapsed = collapsed.contains(&key);
+        rows.push(TableRow::Group(Group {
+            label: label(members[0], show_cluster),
+            resources: members.len(),
+            failing: members
+                .iter()
+                .filter(|m| StatusFilter::Failed.matches(**m))
+                .count(),
+            collapsed,
+            key,
+        }));
+        if !collapsed {
+            rows.extend(members.into_iter().map(TableRow::Resource));
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::{Kustomization, ResourceStatus, SourceRef};
+
+    fn kustomization(
+        name: &str,
+        repository: Option<&str>,
+        status: ResourceStatus,
+    ) -> Kustomization {
+        Kustomization {
+            name: name.to_string(),
+            namespace: "flux-system".to_string(),
+            status,
+            source: repository.map(|name| SourceRef {
+                kind: "GitRepository".to_string(),
+                name: name.to_string(),
+                namespace: "flux-system".to_string(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn titles(rows: &[TableRow<Kustomization>]) -> Vec<String> {
+        rows.iter()
+            .map(|row| match row {
+                TableRow::Group(group) => group.title(),
+                TableRow::Resource(k) => k.name.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rows() {
+        let items = [
+            kustomization("apps", Some("fleet"), ResourceStatus::Ready),
+            kustomization("adhoc", None, ResourceStatus::Ready),
+            kustomization("infra", Some("fleet"), ResourceStatus::Failed),
+            kustomization("addons", Some("addons"), ResourceStatus::Ready),
+        ];
+        let rows = rows(items.iter().collect(), &HashSet::new(), false);
+        assert_eq!(
+            titles(&rows),
+            vec![
+                "▾ No source · 1 resource",
+                "adhoc",
+                "▾ GitRepository flux-system/addons · 1 resource",
+                "addons",
+                "▾ GitRepository flux-system/fleet · 2 resources, 1 failing",
+                "apps",
+                "infra",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rows_collapsed() {
+        let items = [
+            kustomization("apps", Some("fleet"), ResourceStatus::Ready),
+            kustomization("addons", Some("addons"), ResourceStatus::Ready),
+        ];
+        let collapsed = HashSet::from([key(&items[0])]);
+        let rows = rows(items.iter().collect(), &collapsed, false);
+        assert_eq!(
+            titles(&rows),
+            vec![
+                "▾ GitRepository flux-system/addons · 1 resource",
+                "addons",
+                "▸ GitRepository flux-system/fleet · 1 resource",
+            ]
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! Reverse references from sources to the resources consuming them
+
+use std::collections::HashMap;
+
+use crate::kubernetes::resources::{FluxResource, SourceRef};
+
+/// Kustomizations and HelmReleases indexed by the source they consume
+#[derive(Debug, Default)]
+pub struct ConsumerIndex {
+    consumers: HashMap<(String, SourceRef), Vec<String>>,
+}
+
+impl ConsumerIndex {
+    /// Index resources by their source, keyed per cluster
+    pub fn build<'a>(resources: impl IntoIterator<Item = &'a dyn FluxResource>) -> Self {
+        let mut consumers: HashMap<(String, SourceRef), Vec<String>> = HashMap::new();
+        for resource in resources {
+            if let Some(source) = resource.source() {
+                consumers
+                    .entry((resource.cluster().to_string(), source.clone()))
+                    .or_default()
+                    .push(format!(
+                        "{} {}/{}",
+                        resource.kind(),
+                        resource.namespace(),
+                        resource.name()
+                    ));
+            }
+        }
+        for labels in consumers.values_mut() {
+            labels.sort();
+        }
+        Self { consumers }
+    }
+
+    /// Resources consuming a source, as "Kind namespace/name" labels
+    pub fn consumers_of(&self, source: &dyn FluxResource) -> &[String] {
+        let key = (
+            source.cluster().to_string(),
+            SourceRef {
+                kind: source.kind().to_string(),
+                name: source.name().to_string(),
+                namespace: source.namespace().to_string(),
+            },
+        );
+        self.consumers.get(&key).map(Vec::as_slice).unwrap_or(&[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::{GitRepository, HelmRelease, Kustomization};
+
+    fn git_source(name: &str) -> SourceRef {
+        SourceRef {
+            kind: "GitRepository".to_string(),
+            name: name.to_string(),
+            namespace: "flux-system".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_consumers_of() {
+        let kustomizations = [
+            Kustomization {
+                name: "infra".to_string(),
+                namespace: "flux-system".to_string(),
+                source: Some(git_source("fleet")),
+                ..Default::default()
+            },
+            Kustomization {
+                name: "other".to_string(),
+                namespace: "flux-system".to_string(),
+                source: Some(git_source("other")),
+                ..Default::default()
+            },
+        ];
+        let helm_releases = [HelmRelease {
+            name: "podinfo".to_string(),
+            namespace: "apps".to_string(),
+            source: Some(git_source("fleet")),
+            ..Default::default()
+        }];
+        let index = ConsumerIndex::build(
+            kustomizations
+                .iter()
+                .map(|k| k as &dyn FluxResource)
+                .chain(helm_releases.iter().map(|h| h as &dyn FluxResource)),
+        );
+
+        let fleet = GitRepository {
+            name: "fleet".to_string(),
+            namespace: "flux-system".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            index.consumers_of(&fleet),
+            [
+                "HelmRelease apps/podinfo",
+                "Kustomization flux-system/infra"
+            ]
+        );
+
+        let unused = GitRepository {
+            name: "unused".to_string(),
+            ..fleet.clone()
+        };
+        assert!(index.consumers_of(&unused).is_empty());
+
+        let other_cluster = GitRepository {
+            cluster: "prod-us".to_string(),
+            ..fleet
+        };
+        assert!(index.consumers_of(&other_cluster).is_empty());
+    }
+}
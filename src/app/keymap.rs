@@ -0,0 +1,677 @@
+//! Keybinding registry
+//!
+//! Every normal-mode key is declared once here with the action it triggers,
+//! its description and, for the most used ones, a status bar hint.
+//! `event/handler.rs` dispatches through it and the help popup and status
+//! bar render it, so the three can't drift apart.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::{actions::Action, state::Tab};
+
+/// Group a keybinding is listed under
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Navigation,
+    Views,
+    Actions,
+    Filters,
+    General,
+}
+
+impl Category {
+    /// All categories, in display order
+    pub const ALL: [Category; 5] = [
+        Category::Navigation,
+        Category::Views,
+        Category::Actions,
+        Category::Filters,
+        Category::General,
+    ];
+
+    /// Heading shown in the help popup
+    pub fn title(&self) -> &'static str {
+        match self {
+            Category::Navigation => "Navigation",
+            Category::Views => "Views",
+            Category::Actions => "Actions",
+            Category::Filters => "Filters and sorting",
+            Category::General => "General",
+        }
+    }
+}
+
+/// A key, with the modifiers it needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    /// Whether a key press triggers this key
+    ///
+    /// Only Ctrl has to match, and Alt when the key needs it; Shift comes
+    /// with uppercase letters and other modifiers are ignored.
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        let control = KeyModifiers::CONTROL;
+        let alt = KeyModifiers::ALT;
+        self.code == event.code
+            && event.modifiers.contains(control) == self.modifiers.contains(control)
+            && (!self.modifiers.contains(alt) || event.modifiers.contains(alt))
+    }
+
+    /// Key as shown to the user, e.g. "↑", "F5", "^c" or "M-1"
+    pub fn label(&self) -> String {
+        let name = match self.code {
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::Home => "Home".to_string(),
+            KeyCode::End => "End".to_string(),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDn".to_string(),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::BackTab => "S-Tab".to_string(),
+            KeyCode::F(n) => format!("F{n}"),
+            KeyCode::Char(c) => c.to_string(),
+            code => format!("{code:?}"),
+        };
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            format!("^{name}")
+        } else if self.modifiers.contains(KeyModifiers::ALT) {
+            format!("M-{name}")
+        } else {
+            name
+        }
+    }
+}
+
+const fn key(code: KeyCode) -> Key {
+    Key {
+        code,
+        modifiers: KeyModifiers::NONE,
+    }
+}
+
+const fn char(c: char) -> Key {
+    key(KeyCode::Char(c))
+}
+
+const fn ctrl(c: char) -> Key {
+    Key {
+        code: KeyCode::Char(c),
+        modifiers: KeyModifiers::CONTROL,
+    }
+}
+
+/// Alt with a key; plain digits are taken by count prefixes
+const fn alt(c: char) -> Key {
+    Key {
+        code: KeyCode::Char(c),
+        modifiers: KeyModifiers::ALT,
+    }
+}
+
+/// Tabs a keybinding applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tabs {
+    All,
+    Only(&'static [Tab]),
+    Except(&'static [Tab]),
+}
+
+impl Tabs {
+    pub fn includes(&self, tab: Tab) -> bool {
+        match self {
+            Tabs::All => true,
+            Tabs::Only(tabs) => tabs.contains(&tab),
+            Tabs::Except(tabs) => !tabs.contains(&tab),
+        }
+    }
+}
+
+/// A normal-mode keybinding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBinding {
+    /// Keys triggering the action, the first one shown in hints
+    pub keys: &'static [Key],
+
+    pub action: Action,
+
+    /// What the key does, in the help popup
+    pub description: &'static str,
+
+    /// Short label in the status bar, for the most used keys
+    pub hint: Option<&'static str>,
+
+    /// Group it is listed under
+    pub category: Category,
+
+    /// Tabs it is offered on
+    pub tabs: Tabs,
+}
+
+impl KeyBinding {
+    /// Keys as shown in the help popup, e.g. "↑ k"
+    pub fn label(&self) -> String {
+        let labels: Vec<String> = self.keys.iter().map(Key::label).collect();
+        labels.join(" ")
+    }
+
+    /// Show a short label in the status bar
+    const fn hint(mut self, hint: &'static str) -> Self {
+        self.hint = Some(hint);
+        self
+    }
+
+    /// Only offer it on some tabs
+    const fn tabs(mut self, tabs: Tabs) -> Self {
+        self.tabs = tabs;
+        self
+    }
+}
+
+const fn bind(
+    keys: &'static [Key],
+    action: Action,
+    description: &'static str,
+    category: Category,
+) -> KeyBinding {
+    KeyBinding {
+        keys,
+        action,
+        description,
+        hint: None,
+        category,
+        tabs: Tabs::All,
+    }
+}
+
+/// Normal-mode keybindings, in status bar order within each category
+pub const KEYBINDINGS: &[KeyBinding] = &[
+    bind(
+        &[key(KeyCode::Up), char('k')],
+        Action::Up,
+        "Move selection up",
+        Category::Navigation,
+    )
+    .hint("Navigate"),
+    bind(
+        &[key(KeyCode::Down), char('j')],
+        Action::Down,
+        "Move selection down",
+        Category::Navigation,
+    )
+    .hint("Navigate"),
+    bind(
+        &[char('g'), key(KeyCode::Home)],
+        Action::Top,
+        "Go to top",
+        Category::Navigation,
+    ),
+    bind(
+        &[char('G'), key(KeyCode::End)],
+        Action::Bottom,
+        "Go to bottom (5G: to row 5)",
+        Category::Navigation,
+    ),
+    bind(
+        &[key(KeyCode::PageDown)],
+        Action::PageDown,
+        "Page down",
+        Category::Navigation,
+    ),
+    bind(
+        &[key(KeyCode::PageUp)],
+        Action::PageUp,
+        "Page up",
+        Category::Navigation,
+    ),
+    bind(
+        &[ctrl('d')],
+        Action::HalfPageDown,
+        "Half a page down",
+        Category::Navigation,
+    ),
+    bind(
+        &[ctrl('u')],
+        Action::HalfPageUp,
+        "Half a page up",
+        Category::Navigation,
+    ),
+    bind(
+        &[key(KeyCode::Left), char('h'), key(KeyCode::BackTab)],
+        Action::PreviousTab,
+        "Previous tab",
+        Category::Navigation,
+    )
+    .hint("Tabs"),
+    bind(
+        &[key(KeyCode::Right), char('l'), key(KeyCode::Tab)],
+        Action::NextTab,
+        "Next tab",
+        Category::Navigation,
+    )
+    .hint("Tabs"),
+    bind(
+        &[alt('1')],
+        Action::ClickTab(Tab::Kustomizations),
+        "Kustomizations tab",
+        Category::Navigation,
+    ),
+    bind(
+        &[alt('2')],
+        Action::ClickTab(Tab::HelmReleases),
+        "HelmReleases tab",
+        Category::Navigation,
+    ),
+    bind(
+        &[alt('3')],
+        Action::ClickTab(Tab::HelmCharts),
+        "HelmCharts tab",
+        Category::Navigation,
+    ),
+    bind(
+        &[alt('4')],
+        Action::ClickTab(Tab::GitRepositories),
+        "GitRepositories tab",
+        Category::Navigation,
+    ),
+    bind(
+        &[alt('5')],
+        Action::ClickTab(Tab::HelmRepositories),
+        "HelmRepositories tab",
+        Category::Navigation,
+    ),
+    bind(
+        &[key(KeyCode::Enter)],
+        Action::Select,
+        "Resource details",
+        Category::Views,
+    )
+    .hint("Details"),
+    bind(
+        &[char('y')],
+        Action::ShowYaml,
+        "Full YAML manifest",
+        Category::Views,
+    ),
+    bind(
+        &[char('e')],
+        Action::ShowEvents,
+        "Kubernetes Events for the resource",
+        Category::Views,
+    ),
+    bind(
+        &[char('m')],
+        Action::ShowMessage,
+        "Full status message",
+        Category::Views,
+    ),
+    bind(
+        &[char('d')],
+        Action::ShowDependencies,
+        "Kustomization dependsOn tree",
+        Category::Views,
+    ),
+    bind(
+        &[char('t')],
+        Action::Trace,
+        "Trace the resource back to its source",
+        Category::Views,
+    ),
+    bind(
+        &[char('J')],
+        Action::Jump,
+        "Go to the source (a HelmRelease's HelmChart)",
+        Category::Views,
+    )
+    .tabs(Tabs::Only(&[
+        Tab::Kustomizations,
+        Tab::HelmReleases,
+        Tab::HelmCharts,
+    ])),
+    bind(
+        &[char('r')],
+        Action::Reconcile,
+        "Reconcile",
+        Category::Actions,
+    )
+    .hint("Reconcile"),
+    bind(
+        &[char('R')],
+        Action::ReconcileWithSource,
+        "Reconcile with source",
+        Category::Actions,
+    )
+    .hint("+Source"),
+    bind(
+        &[char('F')],
+        Action::ForceReconcile,
+        "Force a HelmRelease upgrade (failed or locked release)",
+        Category::Actions,
+    )
+    .tabs(Tabs::Only(&[Tab::HelmReleases])),
+    // HelmCharts are suspended through their HelmRelease
+    bind(
+        &[char('s')],
+        Action::ToggleSuspend,
+        "Toggle suspend",
+        Category::Actions,
+    )
+    .hint("Suspend")
+    .tabs(Tabs::Except(&[Tab::HelmCharts])),
+    bind(
+        &[ctrl('r')],
+        Action::ResumeAndReconcile,
+        "Resume if suspended, then reconcile",
+        Category::Actions,
+    )
+    .tabs(Tabs::Except(&[Tab::HelmCharts])),
+    bind(
+        &[char('N')],
+        Action::EditNote,
+        "Add or edit a local note",
+        Category::Actions,
+    ),
+    bind(
+        &[char('a')],
+        Action::Acknowledge,
+        "Acknowledge a failing resource",
+        Category::Actions,
+    ),
+    bind(
+        &[char(':')],
+        Action::OpenCommand,
+        "Command palette (suspend -l team=payments)",
+        Category::Actions,
+    ),
+    bind(
+        &[char('b')],
+        Action::BrowsePath,
+        "Browse the Kustomization's path in its source artifact",
+        Category::Views,
+    ),
+    bind(
+        &[char('A')],
+        Action::ToggleActivity,
+        "Recent activity sidebar",
+        Category::Views,
+    ),
+    bind(
+        &[char('p')],
+        Action::ToggleDetailsPane,
+        "Details pane beside the table",
+        Category::Views,
+    ),
+    bind(
+        &[char('M')],
+        Action::ShowControllers,
+        "Flux controller metrics",
+        Category::Views,
+    ),
+    bind(
+        &[char('H')],
+        Action::ShowHelmHistory,
+        "Helm release history and rollback",
+        Category::Actions,
+    )
+    .hint("History")
+    .tabs(Tabs::Only(&[Tab::HelmReleases])),
+    bind(
+        &[char('n')],
+        Action::FilterNamespace,
+        "Filter by namespace",
+        Category::Filters,
+    )
+    .hint("Namespace"),
+    bind(
+        &[char('C')],
+        Action::FilterCluster,
+        "Filter by cluster (multi-cluster mode)",
+        Category::Filters,
+    ),
+    bind(
+        &[char('u')],
+        Action::FilterBySource,
+        "Show what uses the selected source",
+        Category::Filters,
+    ),
+    bind(
+        &[char('/')],
+        Action::StartSearch,
+        "Fuzzy filter the current tab",
+        Category::Filters,
+    )
+    .hint("Filter"),
+    bind(
+        &[ctrl('f')],
+        Action::StartGlobalSearch,
+        "Find a resource of any kind by name",
+        Category::Filters,
+    ),
+    bind(
+        &[char('o')],
+        Action::CycleSort,
+        "Cycle sort column",
+        Category::Filters,
+    )
+    .hint("Sort"),
+    bind(
+        &[char('O')],
+        Action::ReverseSort,
+        "Reverse sort order",
+        Category::Filters,
+    ),
+    bind(
+        &[char('f')],
+        Action::CycleStatusFilter,
+        "Cycle status filter: all, failed, stalled, not ready, suspended",
+        Category::Filters,
+    ),
+    bind(
+        &[char('Z')],
+        Action::ToggleGrouping,
+        "Group by source",
+        Category::Views,
+    )
+    .tabs(Tabs::Only(&[Tab::Kustomizations, Tab::HelmReleases])),
+    bind(
+        &[char('z')],
+        Action::ToggleGroup,
+        "Collapse or expand the source group",
+        Category::Views,
+    )
+    .tabs(Tabs::Only(&[Tab::Kustomizations, Tab::HelmReleases])),
+    bind(
+        &[char('c')],
+        Action::SwitchContext,
+        "Switch kube context",
+        Category::General,
+    ),
+    bind(
+        &[key(KeyCode::F(5))],
+        Action::Refresh,
+        "Refresh data",
+        Category::General,
+    )
+    .hint("Refresh"),
+    bind(
+        &[char('D')],
+        Action::WriteDiagnostics,
+        "Write diagnostic report",
+        Category::General,
+    ),
+    bind(
+        &[char('S')],
+        Action::ExportSnapshot,
+        "Save the resources to a snapshot file (--snapshot)",
+        Category::General,
+    ),
+    bind(
+        &[char('P')],
+        Action::ShowPreflight,
+        "Check the Flux installation",
+        Category::General,
+    ),
+    bind(
+        &[char('V')],
+        Action::ShowFluxVersion,
+        "Flux version, like `flux version`",
+        Category::General,
+    ),
+    bind(
+        &[char('L')],
+        Action::ShowActionLog,
+        "Changes made this session",
+        Category::General,
+    ),
+    bind(
+        &[char('U')],
+        Action::ShowUsage,
+        "Local usage statistics",
+        Category::General,
+    ),
+    bind(
+        &[char('?')],
+        Action::ShowHelp,
+        "This help",
+        Category::General,
+    )
+    .hint("Help"),
+    bind(
+        &[char('q'), key(KeyCode::Esc), ctrl('c')],
+        Action::Quit,
+        "Quit",
+        Category::General,
+    )
+    .hint("Quit"),
+];
+
+/// Action bound to a key press in normal mode
+pub fn action_for(event: &KeyEvent) -> Option<Action> {
+    KEYBINDINGS
+        .iter()
+        .find(|binding| binding.keys.iter().any(|key| key.matches(event)))
+        .map(|binding| binding.action.clone())
+}
+
+/// Status bar hints on a tab: key labels and the hint, with neighbouring
+/// bindings sharing a hint merged (e.g. "↑↓ Navigate")
+pub fn hints(tab: Tab) -> Vec<(String, &'static KeyBinding)> {
+    let mut hints: Vec<(String, &'static KeyBinding)> = Vec::new();
+    for binding in KEYBINDINGS.iter().filter(|b| b.tabs.includes(tab)) {
+        let (Some(hint), Some(key)) = (binding.hint, binding.keys.first()) else {
+            continue;
+        };
+        match hints.last_mut() {
+            Some((keys, last)) if last.hint == Some(hint) => keys.push_str(&key.label()),
+            _ => hints.push((key.label(), binding)),
+        }
+    }
+    hints
+}
+
+/// Keybindings grouped by category, in display order
+pub fn grouped() -> Vec<(Category, Vec<&'static KeyBinding>)> {
+    Category::ALL
+        .iter()
+        .map(|&category| {
+            let bindings = KEYBINDINGS
+                .iter()
+                .filter(|b| b.category == category)
+                .collect();
+            (category, bindings)
+        })
+        .collect()
+}
+
+/// Number of lines in the help popup: a heading, the bindings and a blank
+/// line per category
+pub fn help_line_count() -> usize {
+    Category::ALL.len() * 2 + KEYBINDINGS.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_labels() {
+        assert_eq!(KEYBINDINGS[0].label(), "↑ k");
+        assert_eq!(KEYBINDINGS.last().unwrap().label(), "q Esc ^c");
+        assert_eq!(key(KeyCode::F(5)).label(), "F5");
+        assert_eq!(key(KeyCode::BackTab).label(), "S-Tab");
+        assert_eq!(alt('2').label(), "M-2");
+    }
+
+    #[test]
+    fn test_action_for() {
+        let press = |code, modifiers| KeyEvent::new(code, modifiers);
+        assert_eq!(
+            action_for(&press(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            action_for(&press(KeyCode::Char('c'), KeyModifiers::NONE)),
+            Some(Action::SwitchContext)
+        );
+        assert_eq!(
+            action_for(&press(KeyCode::Char('R'), KeyModifiers::SHIFT)),
+            Some(Action::ReconcileWithSource)
+        );
+        assert_eq!(
+            action_for(&press(KeyCode::Char('x'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(
+            action_for(&press(KeyCode::Char('3'), KeyModifiers::ALT)),
+            Some(Action::ClickTab(Tab::HelmCharts))
+        );
+        assert_eq!(
+            action_for(&press(KeyCode::Char('3'), KeyModifiers::NONE)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_every_key_reaches_its_binding() {
+        // A key listed twice would only ever trigger its first binding
+        for binding in KEYBINDINGS {
+            for key in binding.keys {
+                let event = KeyEvent::new(key.code, key.modifiers);
+                assert_eq!(
+                    action_for(&event).as_ref(),
+                    Some(&binding.action),
+                    "{key:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_hints_merge_and_follow_tabs() {
+        let labels = |tab| -> Vec<String> { hints(tab).into_iter().map(|(k, _)| k).collect() };
+        let kustomizations = labels(Tab::Kustomizations);
+        assert_eq!(kustomizations[..2], ["↑↓", "←→"]);
+        assert!(kustomizations.contains(&"s".to_string()));
+        assert!(!kustomizations.contains(&"H".to_string()));
+        assert!(!labels(Tab::HelmCharts).contains(&"s".to_string()));
+        assert!(labels(Tab::HelmReleases).contains(&"H".to_string()));
+    }
+
+    #[test]
+    fn test_every_binding_is_grouped() {
+        let grouped = grouped();
+        assert_eq!(grouped.len(), Category::ALL.len());
+        assert!(grouped.iter().all(|(_, bindings)| !bindings.is_empty()));
+        assert_eq!(
+            grouped.iter().map(|(_, b)| b.len()).sum::<usize>(),
+            KEYBINDINGS.len()
+        );
+    }
+}
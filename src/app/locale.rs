@@ -0,0 +1,175 @@
+//! Locale-aware formatting of timestamps and counts
+//!
+//! Teams read "03/04/2026" and "1,234" differently, so absolute timestamps and
+//! large counts follow the configured locale, or the system locale from
+//! `LC_ALL`, `LC_TIME` or `LANG`. Relative ages ("3m", "2d") stay as they are.
+
+use std::fmt::Display;
+
+use chrono::{DateTime, TimeZone};
+
+/// How timestamps and numbers are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    /// chrono format of a timestamp, date and time
+    pub datetime: &'static str,
+
+    /// Decimal separator
+    pub decimal: char,
+
+    /// Thousands separator (None = no grouping)
+    pub grouping: Option<char>,
+}
+
+impl Locale {
+    /// ISO 8601 dates and ungrouped numbers, used for the C locale and any
+    /// locale without an entry here
+    pub const ISO: Locale = Locale {
+        datetime: "%Y-%m-%d %H:%M",
+        decimal: '.',
+        grouping: None,
+    };
+
+    /// Locale from a tag such as "de-DE", "en_US.UTF-8" or "fr"
+    ///
+    /// Returns None for the C/POSIX locale and unknown languages.
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let tag = tag.split(['.', '@']).next().unwrap_or_default();
+        let mut parts = tag.split(['_', '-']);
+        let language = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let region = parts.next().unwrap_or_default().to_ascii_uppercase();
+
+        let locale = |datetime, decimal, grouping| Locale {
+            datetime,
+            decimal,
+            grouping: Some(grouping),
+        };
+        match (language.as_str(), region.as_str()) {
+            ("en", "US" | "") => Some(locale("%-m/%-d/%Y %-I:%M %p", '.', ',')),
+            ("en", _) => Some(locale("%d/%m/%Y %H:%M", '.', ',')),
+            ("de", "CH") => Some(locale("%d.%m.%Y %H:%M", '.', '\'')),
+            ("de", _) => Some(locale("%d.%m.%Y %H:%M", ',', '.')),
+            ("fr", _) => Some(locale("%d/%m/%Y %H:%M", ',', ' ')),
+            ("nl", _) => Some(locale("%d-%m-%Y %H:%M", ',', '.')),
+            ("es" | "it" | "pt", _) => Some(locale("%d/%m/%Y %H:%M", ',', '.')),
+            ("sv" | "fi" | "nb" | "no" | "da" | "pl" | "cs" | "ru", _) => {
+                Some(locale("%Y-%m-%d %H:%M", ',', ' '))
+            }
+            ("ja" | "zh" | "ko", _) => Some(locale("%Y/%m/%d %H:%M", '.', ',')),
+            _ => None,
+        }
+    }
+
+    /// The configured locale, else the system locale, else [`Locale::ISO`]
+    pub fn resolve(configured: Option<&str>) -> Locale {
+        configured
+            .map(str::to_string)
+            .or_else(system_tag)
+            .and_then(|tag| Locale::from_tag(&tag))
+            .unwrap_or(Locale::ISO)
+    }
+
+    /// Format a timestamp in its own time zone
+    pub fn datetime<Tz: TimeZone>(&self, at: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: Display,
+    {
+        at.format(self.datetime).to_string()
+    }
+
+    /// Format a count with thousands separators (e.g. "12,345")
+    pub fn count(&self, n: usize) -> String {
+        let digits = n.to_string();
+        let Some(separator) = self.grouping else {
+            return digits;
+        };
+        let mut out = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                out.push(separator);
+            }
+            out.push(digit);
+        }
+        out
+    }
+
+    /// Format a count in short form (e.g. "950", "1.2k", "3.4M")
+    pub fn compact(&self, n: usize) -> String {
+        let (value, suffix) = match n {
+            n if n < 1_000 => return n.to_string(),
+            n if n < 1_000_000 => (n as f64 / 1_000.0, "k"),
+            n => (n as f64 / 1_000_000.0, "M"),
+        };
+        let text = if value < 10.0 {
+            format!("{value:.1}")
+                .trim_end_matches(".0")
+                .replace('.', &self.decimal.to_string())
+        } else {
+            format!("{value:.0}")
+        };
+        format!("{text}{suffix}")
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale::ISO
+    }
+}
+
+/// Locale tag from the environment, in POSIX precedence
+fn system_tag() -> Option<String> {
+    ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_from_tag() {
+        assert_eq!(Locale::from_tag("de_DE.UTF-8").unwrap().decimal, ',');
+        assert_eq!(
+            Locale::from_tag("en-gb").unwrap().datetime,
+            "%d/%m/%Y %H:%M"
+        );
+        assert_eq!(Locale::from_tag("C"), None);
+        assert_eq!(Locale::from_tag("POSIX"), None);
+        assert_eq!(Locale::resolve(Some("tlh")), Locale::ISO);
+    }
+
+    #[test]
+    fn test_datetime() {
+        let at = Utc.with_ymd_and_hms(2026, 3, 4, 14, 5, 0).unwrap();
+        assert_eq!(Locale::ISO.datetime(&at), "2026-03-04 14:05");
+        assert_eq!(
+            Locale::from_tag("en_US").unwrap().datetime(&at),
+            "3/4/2026 2:05 PM"
+        );
+        assert_eq!(
+            Locale::from_tag("de").unwrap().datetime(&at),
+            "04.03.2026 14:05"
+        );
+    }
+
+    #[test]
+    fn test_counts() {
+        let us = Locale::from_tag("en_US").unwrap();
+        let de = Locale::from_tag("de_DE").unwrap();
+        assert_eq!(us.count(1234567), "1,234,567");
+        assert_eq!(de.count(1234), "1.234");
+        assert_eq!(us.count(999), "999");
+        assert_eq!(Locale::ISO.count(1234), "1234");
+
+        assert_eq!(us.compact(950), "950");
+        assert_eq!(us.compact(1200), "1.2k");
+        assert_eq!(de.compact(1200), "1,2k");
+        assert_eq!(us.compact(2000), "2k");
+        assert_eq!(us.compact(45_600), "46k");
+        assert_eq!(us.compact(3_400_000), "3.4M");
+    }
+}
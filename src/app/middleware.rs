@@ -0,0 +1,111 @@
+//! Middleware around `App::update`
+//!
+//! Cross-cutting concerns see every action before it is applied and can let
+//! it through, replace it or stop it, instead of each action arm checking
+//! them. The chain runs in the order it was built.
+
+use super::{
+    actions::Action,
+    command::Command,
+    config::Config,
+    state::{App, Popup},
+};
+
+/// A step every action passes through before `App::update` applies it
+pub trait Middleware: Send {
+    /// The action to apply, or None to stop it here
+    fn before(&mut self, app: &mut App, action: Action) -> Option<Action>;
+}
+
+/// The middleware chain for a configuration
+pub fn chain(config: &Config) -> Vec<Box<dyn Middleware>> {
+    let mut chain: Vec<Box<dyn Middleware>> = vec![Box::new(RecordUsage)];
+    if config.read_only {
+        chain.push(Box::new(ReadOnly));
+    }
+    chain
+}
+
+/// Counts actions into the local usage statistics
+struct RecordUsage;
+
+impl Middleware for RecordUsage {
+    fn before(&mut self, app: &mut App, action: Action) -> Option<Action> {
+        app.usage.record(&action);
+        Some(action)
+    }
+}
+
+/// Refuses actions that change the cluster (`--read-only`)
+struct ReadOnly;
+
+impl ReadOnly {
+    /// Whether the action changes the cluster; a palette command is judged
+    /// by the command typed, so read-only ones like `changes` still run
+    fn refuses(app: &App, action: &Action) -> bool {
+        match (action, &app.popup) {
+            (Action::RunCommand, Popup::Command { text }) => {
+                Command::parse(text).is_ok_and(|command| command.is_mutating())
+            }
+            _ => action.is_mutating(),
+        }
+    }
+}
+
+impl Middleware for ReadOnly {
+    fn before(&mut self, app: &mut App, action: Action) -> Option<Action> {
+        if !Self::refuses(app, &action) {
+            return Some(action);
+        }
+        app.popup = Popup::Error {
+            message: "Read-only mode: reconciling, suspending, rollbacks and changing \
+                      commands are disabled"
+                .to_string(),
+        };
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::state::Tab;
+
+    #[test]
+    fn test_chain() {
+        assert_eq!(chain(&Config::default()).len(), 1);
+        let read_only = Config {
+            read_only: true,
+            ..Config::default()
+        };
+        assert_eq!(chain(&read_only).len(), 2);
+    }
+
+    #[test]
+    fn test_read_only_stops_mutating_actions() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        assert_eq!(ReadOnly.before(&mut app, Action::Down), Some(Action::Down));
+        assert!(matches!(app.popup, Popup::None));
+
+        assert_eq!(ReadOnly.before(&mut app, Action::Reconcile), None);
+        assert!(matches!(app.popup, Popup::Error { .. }));
+    }
+
+    #[test]
+    fn test_read_only_judges_each_command() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.popup = Popup::Command {
+            text: "changes".to_string(),
+        };
+        assert_eq!(
+            ReadOnly.before(&mut app, Action::RunCommand),
+            Some(Action::RunCommand)
+        );
+
+        app.popup = Popup::Command {
+            text: "suspend -l team=payments".to_string(),
+        };
+        assert_eq!(ReadOnly.before(&mut app, Action::RunCommand), None);
+        assert!(matches!(app.popup, Popup::Error { .. }));
+    }
+}
@@ -1,5 +1,41 @@
 //! Application state and logic module
 
+pub mod action_log;
 pub mod actions;
+pub mod activity;
+pub mod buffer;
+pub mod bus;
+pub mod changes;
+pub mod command;
+pub mod completion;
 pub mod config;
+pub mod daemon;
+pub mod details;
+pub mod diagnostics;
+pub mod export;
+pub mod finder;
+pub mod get;
+pub mod graph;
+pub mod group;
+pub mod index;
+pub mod keymap;
+pub mod locale;
+pub mod middleware;
+pub mod progress;
+pub mod reconcile_progress;
+pub mod script;
+pub mod search;
+pub mod snapshot;
+pub mod sort;
 pub mod state;
+pub mod state_file;
+pub mod status_filter;
+pub mod storm;
+pub mod streaks;
+pub mod ticker;
+pub mod timeline;
+pub mod tour;
+pub mod trace;
+pub mod tray;
+pub mod usage;
+pub mod version_check;
@@ -0,0 +1,79 @@
+//! Per-kind progress of the current or last refresh
+
+use super::state::Tab;
+
+/// How listing one resource kind went
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum LoadState {
+    /// Request in flight
+    #[default]
+    Loading,
+    /// Listed successfully
+    Loaded,
+    /// Listing failed, with a short reason (e.g. "forbidden")
+    Failed(String),
+}
+
+/// Load state of every kind, indexed like the tabs
+#[derive(Debug, Clone, Default)]
+pub struct LoadProgress {
+    states: [LoadState; 5],
+}
+
+impl LoadProgress {
+    /// Mark every kind as loading
+    pub fn start(&mut self) {
+        self.states = Default::default();
+    }
+
+    /// Mark a kind as listed, unless another cluster already failed it
+    pub fn loaded(&mut self, tab: Tab) {
+        let state = &mut self.states[tab as usize];
+        if *state == LoadState::Loading {
+            *state = LoadState::Loaded;
+        }
+    }
+
+    /// Mark a kind as failed
+    pub fn failed(&mut self, tab: Tab, reason: String) {
+        self.states[tab as usize] = LoadState::Failed(reason);
+    }
+
+    /// Load state of a kind
+    pub fn get(&self, tab: Tab) -> &LoadState {
+        &self.states[tab as usize]
+    }
+
+    /// Whether any kind failed to load
+    pub fn has_failures(&self) -> bool {
+        self.states
+            .iter()
+            .any(|s| matches!(s, LoadState::Failed(_)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_progress() {
+        let mut progress = LoadProgress::default();
+        progress.start();
+        assert_eq!(progress.get(Tab::HelmCharts), &LoadState::Loading);
+
+        progress.loaded(Tab::Kustomizations);
+        progress.failed(Tab::HelmCharts, "forbidden".to_string());
+        progress.loaded(Tab::HelmCharts);
+
+        assert_eq!(progress.get(Tab::Kustomizations), &LoadState::Loaded);
+        assert_eq!(
+            progress.get(Tab::HelmCharts),
+            &LoadState::Failed("forbidden".to_string())
+        );
+        assert!(progress.has_failures());
+
+        progress.start();
+        assert!(!progress.has_failures());
+    }
+}
@@ -0,0 +1,268 @@
+//! Live progress of a reconcile started from the app
+//!
+//! The reconcile backend runs in a background task while the resource's
+//! conditions and Events are polled, so the popup shows steps like "fetching
+//! source → building → applying" as the controller reports them, then the
+//! outcome. A suspended resource can be resumed first, in the same popup.
+
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::eyre;
+use tokio::sync::mpsc;
+
+use crate::kubernetes::{
+    api::FluxApi,
+    client::KubeEvent,
+    reconcile::{self, Backend, Mode, ResourceRef},
+    resources::{Condition, SourceRef},
+};
+
+/// How often conditions and Events are polled while reconciling
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Events fetched per poll
+const EVENT_LIMIT: usize = 20;
+
+/// A line of progress
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub at: DateTime<Utc>,
+    pub text: String,
+    /// Reported by a Warning Event or a failed Ready condition
+    pub warning: bool,
+}
+
+/// Steps observed so far and, once the CLI exits, the outcome
+#[derive(Debug, Clone, Default)]
+pub struct ReconcileProgress {
+    pub started: DateTime<Utc>,
+    pub steps: Vec<Step>,
+    pub outcome: Option<Result<(), String>>,
+    seen: HashSet<String>,
+}
+
+impl ReconcileProgress {
+    pub fn new(started: DateTime<Utc>) -> Self {
+        Self {
+            started,
+            ..Default::default()
+        }
+    }
+
+    /// Whether the reconcile has finished
+    pub fn is_done(&self) -> bool {
+        self.outcome.is_some()
+    }
+
+    /// Add steps for conditions and Events not seen before
+    ///
+    /// Only what changed since the reconcile started counts; condition
+    /// timestamps have second precision, so the start is rounded down.
+    pub fn observe(&mut self, conditions: &[Condition], events: &[KubeEvent]) {
+        let since = self.started - chrono::Duration::seconds(1);
+        let mut steps: Vec<Step> = Vec::new();
+
+        for condition in conditions {
+            let Some(at) = condition.last_transition.filter(|at| *at >= since) else {
+                continue;
+            };
+            let text = match condition.condition_type.as_str() {
+                "Reconciling" if condition.status == "True" => condition.message.clone(),
+                "Ready" => format!("Ready={}: {}", condition.status, condition.message),
+                _ => continue,
+            };
+            steps.push(Step {
+                at,
+                text,
+                warning: condition.condition_type == "Ready" && condition.status == "False",
+            });
+        }
+        for event in events {
+            let Some(at) = event.last_seen.filter(|at| *at >= since) else {
+                continue;
+            };
+            steps.push(Step {
+                at,
+                text: format!("{}: {}", event.reason, event.message),
+                warning: event.is_warning(),
+            });
+        }
+
+        steps.sort_by_key(|step| step.at);
+        for step in steps {
+            if !step.text.trim().is_empty() && self.seen.insert(step.text.clone()) {
+                self.steps.push(step);
+            }
+        }
+    }
+
+    /// Add the step reporting whether the resource was resumed
+    pub fn resumed(&mut self, at: DateTime<Utc>, result: &Result<(), String>) {
+        let (text, warning) = match result {
+            Ok(()) => ("Resumed".to_string(), false),
+            Err(e) => (format!("Resume failed: {e}"), true),
+        };
+        self.steps.push(Step { at, text, warning });
+    }
+}
+
+/// What the background task reports
+#[derive(Debug)]
+pub enum ReconcileUpdate {
+    /// Current conditions and recent Events of the resource
+    Observed {
+        conditions: Vec<Condition>,
+        events: Vec<KubeEvent>,
+    },
+    /// The suspended resource was resumed, or couldn't be
+    Resumed(Result<(), String>),
+    /// The reconcile finished
+    Done(Result<(), String>),
+}
+
+/// The resource to reconcile
+#[derive(Debug, Clone)]
+pub struct Target {
+    /// Kind as Kubernetes names it (e.g. "Kustomization")
+    pub kind: String,
+    pub name: String,
+    pub namespace: String,
+    pub source: Option<SourceRef>,
+    pub mode: Mode,
+    pub backend: Backend,
+    /// Resume the resource before reconciling it
+    pub resume: bool,
+}
+
+/// Run the reconcile in the background, reporting progress until it exits
+///
+/// A resource that fails to resume isn't reconciled. Polling stops early when
+/// the receiver is dropped (the popup was closed).
+pub fn watch(client: Arc<dyn FluxApi>, target: Target) -> mpsc::UnboundedReceiver<ReconcileUpdate> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let request = {
+            let target = target.clone();
+            let backend = reconcile::backend(target.backend, &client);
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let resource = ResourceRef {
+                    kind: &target.kind,
+                    name: &target.name,
+                    namespace: &target.namespace,
+                    source: target.source.as_ref(),
+                };
+                if target.resume {
+                    let resumed = backend
+                        .set_suspended(resource, false)
+                        .await
+                        .map_err(|e| e.to_string());
+                    let failed = resumed.is_err();
+                    let _ = tx.send(ReconcileUpdate::Resumed(resumed));
+                    if failed {
+                        return Err(eyre!("still suspended, not reconciled"));
+                    }
+                }
+                backend.reconcile(resource, target.mode).await
+            })
+        };
+        loop {
+            let finished = request.is_finished();
+            let conditions = client
+                .get_conditions(&target.kind, &target.namespace, &target.name)
+                .await
+                .unwrap_or_default();
+            let events = client
+                .list_events_for(&target.kind, &target.namespace, &target.name, EVENT_LIMIT)
+                .await
+                .unwrap_or_default();
+            if tx
+                .send(ReconcileUpdate::Observed { conditions, events })
+                .is_err()
+            {
+                return;
+            }
+            if finished {
+                break;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        let result = match request.await {
+            Ok(result) => result.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        let _ = tx.send(ReconcileUpdate::Done(result));
+    });
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + seconds, 0).unwrap()
+    }
+
+    fn condition(condition_type: &str, status: &str, message: &str, seconds: i64) -> Condition {
+        Condition {
+            condition_type: condition_type.to_string(),
+            status: status.to_string(),
+            message: message.to_string(),
+            last_transition: Some(at(seconds)),
+            ..Default::default()
+        }
+    }
+
+    fn event(event_type: &str, reason: &str, seconds: i64) -> KubeEvent {
+        KubeEvent {
+            event_type: event_type.to_string(),
+            reason: reason.to_string(),
+            message: "msg".to_string(),
+            count: 1,
+            last_seen: Some(at(seconds)),
+        }
+    }
+
+    #[test]
+    fn test_observe_collects_new_steps_in_order() {
+        let mut progress = ReconcileProgress::new(at(10));
+
+        progress.observe(
+            &[
+                condition("Reconciling", "True", "building manifests", 12),
+                condition("Ready", "True", "applied revision main@abc", 5),
+            ],
+            &[
+                event("Normal", "Progressing", 11),
+                event("Normal", "Old", 0),
+            ],
+        );
+        let texts: Vec<&str> = progress.steps.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["Progressing: msg", "building manifests"]);
+
+        // Already seen steps are not repeated
+        progress.observe(
+            &[
+                condition("Reconciling", "True", "building manifests", 12),
+                condition("Ready", "False", "kustomize build failed", 14),
+            ],
+            &[event("Warning", "BuildFailed", 14)],
+        );
+        assert_eq!(progress.steps.len(), 4);
+        assert!(progress.steps[2..].iter().all(|s| s.warning));
+        assert!(!progress.is_done());
+    }
+
+    #[test]
+    fn test_resumed_step() {
+        let mut progress = ReconcileProgress::new(at(10));
+        progress.resumed(at(10), &Ok(()));
+        progress.resumed(at(11), &Err("forbidden".to_string()));
+        assert_eq!(progress.steps[0].text, "Resumed");
+        assert!(!progress.steps[0].warning);
+        assert_eq!(progress.steps[1].text, "Resume failed: forbidden");
+        assert!(progress.steps[1].warning);
+    }
+}
@@ -0,0 +1,495 @@
+//! Scripted sessions (`--script`)
+//!
+//! With `--script`, commands are read from stdin, one per line, instead of
+//! keys. They drive the same app loop a user would, for reproducible demos
+//! and smoke tests of a cluster:
+//!
+//! ```text
+//! ns flux-system
+//! reconcile kustomization apps
+//! wait ready
+//! quit
+//! ```
+//!
+//! A failing command (an unknown resource, an error popup, a failed
+//! reconcile or a `wait` running into its timeout) ends the script with an
+//! error naming its line. Blank lines and `#` comments are skipped; the end
+//! of the input quits like `quit`.
+
+use std::time::{Duration, Instant};
+
+use clap::ValueEnum;
+use color_eyre::{eyre::eyre, Result};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::mpsc::{self, error::TryRecvError};
+
+use crate::kubernetes::resources::{FluxResource, SourceRef};
+
+use super::{
+    actions::Action,
+    get::Kind,
+    state::{App, Popup},
+};
+
+/// How long `wait ready` waits unless given a timeout
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A parsed script line
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Filter to a namespace (None = all namespaces) and wait for the data
+    Namespace(Option<String>),
+
+    /// Select a resource and reconcile it, without waiting for the result
+    ///
+    /// Without a namespace the name must be unique among the loaded
+    /// resources of the kind.
+    Reconcile {
+        kind: Kind,
+        namespace: Option<String>,
+        name: String,
+    },
+
+    /// Wait until the data is loaded, the last reconcile finished and every
+    /// loaded resource that isn't suspended is Ready
+    WaitReady { timeout: Duration },
+
+    /// Pause, e.g. to let the audience of a demo read the screen
+    Sleep(Duration),
+
+    /// End the script and quit
+    Quit,
+}
+
+impl Command {
+    /// Parse a script line, None for a blank line or a comment, or a usage
+    /// message on error
+    pub fn parse(line: &str) -> Result<Option<Self>, String> {
+        let line = line.split('#').next().unwrap_or_default();
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let command = match words.as_slice() {
+            [] => return Ok(None),
+            ["ns", "all"] => Command::Namespace(None),
+            ["ns", namespace] => Command::Namespace(Some(namespace.to_string())),
+            ["ns", ..] => return Err(format!("Usage: {NS_USAGE}")),
+            ["reconcile", kind, resource] => {
+                let kind = Kind::from_str(kind, true).map_err(|_| {
+                    format!(
+                        "Unknown kind \"{kind}\". Use one of {}",
+                        Kind::value_variants()
+                            .iter()
+                            .filter_map(|kind| kind.to_possible_value())
+                            .map(|value| value.get_name().to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
+                })?;
+                let (namespace, name) = match resource.split_once('/') {
+                    Some((namespace, name)) => (Some(namespace.to_string()), name),
+                    None => (None, *resource),
+                };
+                Command::Reconcile {
+                    kind,
+                    namespace,
+                    name: name.to_string(),
+                }
+            }
+            ["reconcile", ..] => return Err(format!("Usage: {RECONCILE_USAGE}")),
+            ["wait", "ready"] => Command::WaitReady {
+                timeout: DEFAULT_TIMEOUT,
+            },
+            ["wait", "ready", seconds] => Command::WaitReady {
+                timeout: seconds_arg(seconds).ok_or_else(|| format!("Usage: {WAIT_USAGE}"))?,
+            },
+            ["wait", ..] => return Err(format!("Usage: {WAIT_USAGE}")),
+            ["sleep", seconds] => {
+                Command::Sleep(seconds_arg(seconds).ok_or_else(|| format!("Usage: {SLEEP_USAGE}"))?)
+            }
+            ["sleep", ..] => return Err(format!("Usage: {SLEEP_USAGE}")),
+            ["quit"] => Command::Quit,
+            [other, ..] => {
+                return Err(format!(
+                    "Unknown command \"{other}\". {NS_USAGE}, {RECONCILE_USAGE}, {WAIT_USAGE}, \
+                     {SLEEP_USAGE} or quit"
+                ))
+            }
+        };
+        Ok(Some(command))
+    }
+}
+
+const NS_USAGE: &str = "ns <namespace>|all";
+
+const RECONCILE_USAGE: &str = "reconcile <kind> [<namespace>/]<name>";
+
+const WAIT_USAGE: &str = "wait ready [<timeout seconds>]";
+
+const SLEEP_USAGE: &str = "sleep <seconds>";
+
+/// A non-negative number of seconds, fractions allowed
+fn seconds_arg(arg: &str) -> Option<Duration> {
+    Duration::try_from_secs_f64(arg.parse().ok()?).ok()
+}
+
+/// Whether the script goes on after a step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    Continue,
+    Quit,
+}
+
+/// What the script is waiting for before the next command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Wait {
+    /// The data is loaded and nothing runs in the background
+    Settled,
+
+    /// Settled, and every resource is Ready, by the deadline
+    Ready { deadline: Instant },
+
+    /// A point in time
+    Until(Instant),
+}
+
+/// A running script, fed line by line
+pub struct Script {
+    lines: mpsc::UnboundedReceiver<String>,
+
+    /// Number of the last line read, for errors
+    line: usize,
+    waiting: Option<Wait>,
+}
+
+impl Script {
+    /// A script read from stdin as it arrives, so commands can also be piped
+    /// in one by one
+    pub fn stdin() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(tokio::io::stdin()).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Self::new(rx)
+    }
+
+    fn new(lines: mpsc::UnboundedReceiver<String>) -> Self {
+        Self {
+            lines,
+            line: 0,
+            waiting: None,
+        }
+    }
+
+    /// Run the commands that are due, without blocking
+    ///
+    /// Called once per iteration of the app loop: stops at a command that
+    /// waits, and continues there on the next call.
+    pub fn step(&mut self, app: &mut App) -> Result<Flow> {
+        loop {
+            if let Some(wait) = self.waiting {
+                if !self.is_over(wait, app)? {
+                    return Ok(Flow::Continue);
+                }
+                self.waiting = None;
+                if matches!(app.popup, Popup::Reconciling { .. }) {
+                    app.update(Action::ClosePopup)?;
+                }
+            }
+
+            let line = match self.lines.try_recv() {
+                Ok(line) => line,
+                Err(TryRecvError::Empty) => return Ok(Flow::Continue),
+                Err(TryRecvError::Disconnected) => return Ok(Flow::Quit),
+            };
+            self.line += 1;
+            match Command::parse(&line).map_err(|e| self.error(&e))? {
+                None => {}
+                Some(Command::Quit) => return Ok(Flow::Quit),
+                Some(command) => self.run(command, app)?,
+            }
+        }
+    }
+
+    /// Start a command
+    fn run(&mut self, command: Command, app: &mut App) -> Result<()> {
+        match command {
+            Command::Namespace(namespace) => {
+                app.update(Action::SetNamespace(namespace))?;
+                self.waiting = Some(Wait::Settled);
+            }
+            Command::Reconcile {
+                kind,
+                namespace,
+                name,
+            } => {
+                let tab = kind.tab();
+                let found: Vec<(String, SourceRef)> = app
+                    .all_resources()
+                    .into_iter()
+                    .filter(|resource| {
+                        resource.kind() == tab.kind()
+                            && resource.name() == name
+                            && namespace
+                                .as_deref()
+                                .is_none_or(|namespace| resource.namespace() == namespace)
+                    })
+                    .map(|resource| {
+                        let target = SourceRef {
+                            kind: resource.kind().to_string(),
+                            name: resource.name().to_string(),
+                            namespace: resource.namespace().to_string(),
+                        };
+                        (resource.cluster().to_string(), target)
+                    })
+                    .collect();
+                let (cluster, target) = match found.as_slice() {
+                    [found] => found,
+                    [] => return Err(self.error(&format!("{} {name} is not loaded", tab.kind()))),
+                    _ => {
+                        return Err(self.error(&format!(
+                            "Several {} are named {name}, write <namespace>/{name}",
+                            tab.name()
+                        )))
+                    }
+                };
+                app.jump_to(tab, cluster, target);
+                app.update(Action::Reconcile)?;
+                self.check_popup(app)?;
+            }
+            Command::WaitReady { timeout } => {
+                self.waiting = Some(Wait::Ready {
+                    deadline: Instant::now() + timeout,
+                });
+            }
+            Command::Sleep(duration) => {
+                self.waiting = Some(Wait::Until(Instant::now() + duration));
+            }
+            Command::Quit => {}
+        }
+        Ok(())
+    }
+
+    /// Whether a wait is over, failing on errors and timeouts
+    fn is_over(&self, wait: Wait, app: &App) -> Result<bool> {
+        self.check_popup(app)?;
+        let settled = !app.loading && !app.is_busy() && !app.is_reconciling();
+        match wait {
+            Wait::Settled => Ok(settled),
+            Wait::Until(at) => Ok(Instant::now() >= at),
+            Wait::Ready { deadline } => {
+                let not_ready = not_ready(app);
+                if settled && not_ready.is_empty() {
+                    Ok(true)
+                } else if Instant::now() >= deadline {
+                    Err(self.error(&format!(
+                        "Timed out waiting for {}",
+                        if not_ready.is_empty() {
+                            "the data to load".to_string()
+                        } else {
+                            not_ready.join(", ")
+                        }
+                    )))
+                } else {
+                    Ok(false)
+                }
+            }
+        }
+    }
+
+    /// Fail on an error popup or a failed reconcile
+    fn check_popup(&self, app: &App) -> Result<()> {
+        match &app.popup {
+            Popup::Error { message } => Err(self.error(message)),
+            Popup::Reconciling { progress, .. } => match &progress.outcome {
+                Some(Err(e)) => Err(self.error(&format!("Reconcile failed: {e}"))),
+                _ => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    fn error(&self, message: &str) -> color_eyre::Report {
+        eyre!("Script line {}: {message}", self.line)
+    }
+}
+
+/// Resources that aren't suspended and aren't Ready, e.g.
+/// "Kustomization flux-system/apps"
+fn not_ready(app: &App) -> Vec<String> {
+    app.all_resources()
+        .into_iter()
+        .filter(|resource| !resource.is_suspended() && !resource.is_ready())
+        .map(|resource: &dyn FluxResource| {
+            format!(
+                "{} {}/{}",
+                resource.kind(),
+                resource.namespace(),
+                resource.name()
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::state::Tab;
+    use crate::kubernetes::mock::MOCK_CLUSTER;
+    use crate::kubernetes::resources::{Kustomization, ResourceStatus};
+
+    fn kustomization(name: &str, namespace: &str, status: ResourceStatus) -> Kustomization {
+        Kustomization {
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+            cluster: MOCK_CLUSTER.to_string(),
+            status,
+            ..Default::default()
+        }
+    }
+
+    /// A script of these lines, with the input already closed
+    fn script(lines: &[&str]) -> Script {
+        let (tx, rx) = mpsc::unbounded_channel();
+        for line in lines {
+            tx.send(line.to_string()).unwrap();
+        }
+        Script::new(rx)
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Command::parse("  "), Ok(None));
+        assert_eq!(Command::parse("# a demo"), Ok(None));
+        assert_eq!(
+            Command::parse("ns flux-system # the Flux namespace"),
+            Ok(Some(Command::Namespace(Some("flux-system".to_string()))))
+        );
+        assert_eq!(Command::parse("ns all"), Ok(Some(Command::Namespace(None))));
+        assert_eq!(
+            Command::parse("reconcile ks apps"),
+            Ok(Some(Command::Reconcile {
+                kind: Kind::Kustomizations,
+                namespace: None,
+                name: "apps".to_string(),
+            }))
+        );
+        assert_eq!(
+            Command::parse("reconcile HelmRelease podinfo/podinfo"),
+            Ok(Some(Command::Reconcile {
+                kind: Kind::HelmReleases,
+                namespace: Some("podinfo".to_string()),
+                name: "podinfo".to_string(),
+            }))
+        );
+        assert_eq!(
+            Command::parse("wait ready"),
+            Ok(Some(Command::WaitReady {
+                timeout: DEFAULT_TIMEOUT
+            }))
+        );
+        assert_eq!(
+            Command::parse("wait ready 30"),
+            Ok(Some(Command::WaitReady {
+                timeout: Duration::from_secs(30)
+            }))
+        );
+        assert_eq!(
+            Command::parse("sleep 0.5"),
+            Ok(Some(Command::Sleep(Duration::from_millis(500))))
+        );
+        assert_eq!(Command::parse("quit"), Ok(Some(Command::Quit)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Command::parse("ns").unwrap_err().contains("ns <namespace>"));
+        assert!(Command::parse("reconcile pods x")
+            .unwrap_err()
+            .contains("kustomizations"));
+        assert!(Command::parse("wait ready soon")
+            .unwrap_err()
+            .contains("Usage"));
+        assert!(Command::parse("sleep -1").unwrap_err().contains("Usage"));
+        assert!(Command::parse("deploy")
+            .unwrap_err()
+            .starts_with("Unknown command \"deploy\""));
+    }
+
+    #[tokio::test]
+    async fn test_namespace_waits_for_the_data() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        let mut script = script(&["ns flux-system", "quit"]);
+
+        assert_eq!(script.step(&mut app).unwrap(), Flow::Continue);
+        assert_eq!(app.namespace_filter.as_deref(), Some("flux-system"));
+        assert_eq!(script.waiting, Some(Wait::Settled));
+
+        app.settle().await;
+        assert_eq!(script.step(&mut app).unwrap(), Flow::Quit);
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_selects_the_resource() {
+        let mut app = App::for_testing(
+            Tab::HelmReleases,
+            vec![
+                kustomization("infra", "flux-system", ResourceStatus::Ready),
+                kustomization("apps", "flux-system", ResourceStatus::Ready),
+                kustomization("apps", "team-a", ResourceStatus::Ready),
+            ],
+            vec![],
+            vec![],
+        );
+
+        let error = script(&["reconcile ks apps"]).step(&mut app).unwrap_err();
+        assert!(error.to_string().starts_with("Script line 1: Several"));
+        let error = script(&["", "reconcile ks gone"])
+            .step(&mut app)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Script line 2: Kustomization gone is not loaded"
+        );
+
+        let mut script = script(&["reconcile ks team-a/apps"]);
+        assert_eq!(script.step(&mut app).unwrap(), Flow::Quit);
+        assert_eq!(app.tab, Tab::Kustomizations);
+        let selected = app.get_selected_resource().unwrap();
+        assert_eq!((selected.namespace(), selected.name()), ("team-a", "apps"));
+        assert!(matches!(app.popup, Popup::Reconciling { .. }));
+    }
+
+    #[test]
+    fn test_wait_ready() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![
+                kustomization("apps", "flux-system", ResourceStatus::Ready),
+                kustomization("infra", "flux-system", ResourceStatus::Failed),
+            ],
+            vec![],
+            vec![],
+        );
+        let error = script(&["wait ready 0"]).step(&mut app).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Script line 1: Timed out waiting for Kustomization flux-system/infra"
+        );
+
+        app.kustomizations[1].status = ResourceStatus::Ready;
+        assert_eq!(
+            script(&["wait ready 0", "sleep 0"]).step(&mut app).unwrap(),
+            Flow::Quit
+        );
+
+        app.popup = Popup::Error {
+            message: "Forbidden".to_string(),
+        };
+        let error = script(&["wait ready"]).step(&mut app).unwrap_err();
+        assert_eq!(error.to_string(), "Script line 1: Forbidden");
+    }
+}
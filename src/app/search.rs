@@ -0,0 +1,65 @@
+//! Fuzzy search used by the `/` table filter
+
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+
+/// Fuzzy filter matching a query against resource fields
+pub struct FuzzyFilter<'a> {
+    matcher: SkimMatcherV2,
+    query: &'a str,
+}
+
+impl<'a> FuzzyFilter<'a> {
+    /// Create a filter for a query (smart case: case-insensitive unless it has uppercase)
+    pub fn new(query: &'a str) -> Self {
+        Self {
+            matcher: SkimMatcherV2::default().smart_case(),
+            query,
+        }
+    }
+
+    /// Check whether any field matches; an empty query matches everything
+    pub fn matches(&self, fields: &[&str]) -> bool {
+        self.query.is_empty()
+            || fields
+                .iter()
+                .any(|field| self.matcher.fuzzy_match(field, self.query).is_some())
+    }
+
+    /// How well a field matches, higher is better; None if it doesn't
+    pub fn score(&self, field: &str) -> Option<i64> {
+        if self.query.is_empty() {
+            return Some(0);
+        }
+        self.matcher.fuzzy_match(field, self.query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_query_matches_everything() {
+        assert!(FuzzyFilter::new("").matches(&["anything"]));
+        assert!(FuzzyFilter::new("").matches(&[]));
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        let filter = FuzzyFilter::new("ingngx");
+        assert!(filter.matches(&["ingress-nginx", "kube-system"]));
+        assert!(!filter.matches(&["cert-manager", "kube-system"]));
+    }
+
+    #[test]
+    fn test_matches_any_field() {
+        let filter = FuzzyFilter::new("podinfo");
+        assert!(filter.matches(&["frontend", "apps", "podinfo"]));
+    }
+
+    #[test]
+    fn test_smart_case() {
+        assert!(FuzzyFilter::new("apps").matches(&["Apps"]));
+        assert!(!FuzzyFilter::new("Apps").matches(&["apps"]));
+    }
+}
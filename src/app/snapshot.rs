@@ -0,0 +1,196 @@
+//! Snapshot files (`S`, `flux-tui export --output snapshot`, `--snapshot`)
+//!
+//! A snapshot saves the loaded resources of every shown cluster as JSON.
+//! `flux-tui --snapshot <file>` opens it later without a cluster, read-only,
+//! e.g. to look back at an incident or to demo the UI.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::kubernetes::{
+    api::FluxApi,
+    client::ClusterSnapshot,
+    resources::{
+        FluxResource, GitRepository, HelmChart, HelmRelease, HelmRepository, Kustomization,
+    },
+    snapshot::SnapshotClient,
+};
+
+use super::state::App;
+
+/// Version of the file format, raised on incompatible changes
+const VERSION: u32 = 1;
+
+/// The resources of every shown cluster at one point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub taken_at: DateTime<Utc>,
+
+    /// Primary cluster first
+    pub clusters: Vec<SnapshotCluster>,
+}
+
+/// The resources of one cluster
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapshotCluster {
+    pub cluster_name: String,
+    pub context: Option<String>,
+    pub namespaces: Vec<String>,
+    pub kustomizations: Vec<Kustomization>,
+    pub helm_releases: Vec<HelmRelease>,
+    pub helm_charts: Vec<HelmChart>,
+    pub git_repositories: Vec<GitRepository>,
+    pub helm_repositories: Vec<HelmRepository>,
+}
+
+impl Snapshot {
+    /// Capture the loaded resources of the app
+    ///
+    /// A snapshot being viewed keeps the time it was taken.
+    pub fn of(app: &App, now: DateTime<Utc>) -> Self {
+        fn of_cluster<T: FluxResource + Clone>(resources: &[T], label: &str) -> Vec<T> {
+            resources
+                .iter()
+                .filter(|r| r.cluster() == label)
+                .cloned()
+                .collect()
+        }
+        let clusters = app
+            .clients()
+            .into_iter()
+            .map(|client| {
+                let label = client.label();
+                SnapshotCluster {
+                    cluster_name: client.cluster_name().to_string(),
+                    context: client.context().map(String::from),
+                    namespaces: app.namespaces.clone(),
+                    kustomizations: of_cluster(&app.kustomizations, label),
+                    helm_releases: of_cluster(&app.helm_releases, label),
+                    helm_charts: of_cluster(&app.helm_charts, label),
+                    git_repositories: of_cluster(&app.git_repositories, label),
+                    helm_repositories: of_cluster(&app.helm_repositories, label),
+                }
+            })
+            .collect();
+        Self {
+            version: VERSION,
+            taken_at: app.snapshot_taken.unwrap_or(now),
+            clusters,
+        }
+    }
+
+    /// Read a snapshot file
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read snapshot {}", path.display()))?;
+        Self::parse(&json).wrap_err_with(|| format!("Invalid snapshot {}", path.display()))
+    }
+
+    fn parse(json: &str) -> Result<Self> {
+        let snapshot: Self = serde_json::from_str(json)?;
+        if snapshot.version != VERSION {
+            return Err(eyre!(
+                "Unsupported snapshot version {} (this flux-tui reads version {VERSION})",
+                snapshot.version
+            ));
+        }
+        if snapshot.clusters.is_empty() {
+            return Err(eyre!("The snapshot holds no clusters"));
+        }
+        Ok(snapshot)
+    }
+
+    /// The snapshot as JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)? + "\n")
+    }
+
+    /// Write the snapshot to a new file in the system temp directory
+    pub fn write(&self) -> Result<PathBuf> {
+        let file_name = format!(
+            "flux-tui-snapshot-{}.json",
+            Utc::now().format("%Y%m%d-%H%M%S%.3f")
+        );
+        let path = std::env::temp_dir().join(file_name);
+        std::fs::write(&path, self.to_json()?)?;
+        Ok(path)
+    }
+
+    /// A client serving each cluster, the primary one first
+    pub fn into_clients(self) -> (Arc<dyn FluxApi>, Vec<Arc<dyn FluxApi>>) {
+        let mut clients = self.clusters.into_iter().map(|cluster| {
+            let resources = ClusterSnapshot {
+                kustomizations: cluster.kustomizations,
+                helm_releases: cluster.helm_releases,
+                helm_charts: cluster.helm_charts,
+                git_repositories: cluster.git_repositories,
+                helm_repositories: cluster.helm_repositories,
+                namespaces: cluster.namespaces,
+                failures: Vec::new(),
+            };
+            Arc::new(SnapshotClient::new(
+                cluster.cluster_name,
+                cluster.context,
+                resources,
+            )) as Arc<dyn FluxApi>
+        });
+        // `parse` rejects snapshots without clusters
+        let primary = clients.next().expect("a snapshot holds a cluster");
+        (primary, clients.collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::state::Tab;
+    use crate::kubernetes::mock::MOCK_CLUSTER;
+
+    fn apps() -> Kustomization {
+        Kustomization {
+            name: "apps".to_string(),
+            namespace: "flux-system".to_string(),
+            cluster: MOCK_CLUSTER.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let app = App::for_testing(Tab::Kustomizations, vec![apps()], vec![], vec![]);
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let snapshot = Snapshot::of(&app, now);
+        assert_eq!(snapshot.clusters.len(), 1);
+        assert_eq!(snapshot.clusters[0].cluster_name, MOCK_CLUSTER);
+        assert_eq!(snapshot.clusters[0].kustomizations.len(), 1);
+
+        let parsed = Snapshot::parse(&snapshot.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.taken_at, now);
+        let (client, others) = parsed.into_clients();
+        assert_eq!(client.label(), MOCK_CLUSTER);
+        assert!(others.is_empty());
+    }
+
+    #[test]
+    fn test_parse_rejects_other_versions() {
+        let error = Snapshot::parse(
+            r#"{"version": 2, "taken_at": "2026-01-01T00:00:00Z", "clusters": []}"#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("Unsupported snapshot version 2"));
+        let error = Snapshot::parse(
+            r#"{"version": 1, "taken_at": "2026-01-01T00:00:00Z", "clusters": []}"#,
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "The snapshot holds no clusters");
+    }
+}
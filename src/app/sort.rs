@@ -0,0 +1,193 @@
+//! Per-tab sorting of resource tables
+
+use std::cmp::Ordering;
+
+use crate::kubernetes::resources::{FluxResource, ResourceStatus};
+
+/// Column a table can be sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Name,
+    Namespace,
+    /// Failures first, healthy resources last
+    Status,
+}
+
+impl SortKey {
+    /// Header title of the column this key sorts by
+    pub fn column(&self) -> &'static str {
+        match self {
+            SortKey::Name => "NAME",
+            SortKey::Namespace => "NAMESPACE",
+            SortKey::Status => "READY",
+        }
+    }
+
+    /// Compare two resources by this key
+    fn compare(&self, a: &dyn FluxResource, b: &dyn FluxResource) -> Ordering {
+        match self {
+            SortKey::Name => a.name().cmp(b.name()),
+            SortKey::Namespace => a.namespace().cmp(b.namespace()),
+            SortKey::Status => status_rank(a.status()).cmp(&status_rank(b.status())),
+        }
+    }
+}
+
+/// Sort state of a single tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SortState {
+    /// Active sort key (None = order returned by the API)
+    pub key: Option<SortKey>,
+
+    /// Reverse the sort order
+    pub reversed: bool,
+}
+
+impl SortState {
+    /// Cycle to the next sort key: unsorted → name → namespace → status → unsorted
+    pub fn cycle(&mut self) {
+        self.key = match self.key {
+            None => Some(SortKey::Name),
+            Some(SortKey::Name) => Some(SortKey::Namespace),
+            Some(SortKey::Namespace) => Some(SortKey::Status),
+            Some(SortKey::Status) => None,
+        };
+    }
+
+    /// Sort resources in place; ties keep their API order
+    pub fn apply<T: FluxResource>(&self, items: &mut [&T]) {
+        if let Some(key) = self.key {
+            items.sort_by(|a, b| {
+                let ordering = key.compare(*a, *b);
+                if self.reversed {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+        }
+    }
+
+    /// Header indicator for a column (e.g. "▲" when sorted ascending by it)
+    pub fn indicator(&self, column: &str) -> Option<&'static str> {
+        match self.key {
+            Some(key) if key.column() == column => Some(if self.reversed { "▼" } else { "▲" }),
+            _ => None,
+        }
+    }
+}
+
+/// Rank statuses so the ones needing attention come first
+fn status_rank(status: &ResourceStatus) -> u8 {
+    match status {
+        ResourceStatus::Failed => 0,
+        ResourceStatus::Stalled => 1,
+        ResourceStatus::Reconciling => 2,
+        ResourceStatus::Pending => 3,
+        ResourceStatus::Unknown => 4,
+        ResourceStatus::SuspendedFailed => 5,
+        ResourceStatus::Suspended => 6,
+        ResourceStatus::Ready => 7,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::Kustomization;
+
+    fn kustomization(name: &str, namespace: &str, status: ResourceStatus) -> Kustomization {
+        Kustomization {
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+            status,
+            ..Default::default()
+        }
+    }
+
+    fn sorted_names(sort: SortState, items: &[Kustomization]) -> Vec<&str> {
+        let mut refs: Vec<&Kustomization> = items.iter().collect();
+        sort.apply(&mut refs);
+        refs.iter().map(|k| k.name.as_str()).collect()
+    }
+
+    fn items() -> Vec<Kustomization> {
+        vec![
+            kustomization("infra", "flux-system", ResourceStatus::Ready),
+            kustomization("apps", "tenants", ResourceStatus::Reconciling),
+            kustomization("monitoring", "default", ResourceStatus::Failed),
+        ]
+    }
+
+    #[test]
+    fn test_cycle() {
+        let mut sort = SortState::default();
+        let mut keys = Vec::new();
+        for _ in 0..4 {
+            sort.cycle();
+            keys.push(sort.key);
+        }
+        assert_eq!(
+            keys,
+            vec![
+                Some(SortKey::Name),
+                Some(SortKey::Namespace),
+                Some(SortKey::Status),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unsorted_keeps_api_order() {
+        assert_eq!(
+            sorted_names(SortState::default(), &items()),
+            vec!["infra", "apps", "monitoring"]
+        );
+    }
+
+    #[test]
+    fn test_sort_by_each_key() {
+        let sort = |key| SortState {
+            key: Some(key),
+            reversed: false,
+        };
+        assert_eq!(
+            sorted_names(sort(SortKey::Name), &items()),
+            vec!["apps", "infra", "monitoring"]
+        );
+        assert_eq!(
+            sorted_names(sort(SortKey::Namespace), &items()),
+            vec!["monitoring", "infra", "apps"]
+        );
+        assert_eq!(
+            sorted_names(sort(SortKey::Status), &items()),
+            vec!["monitoring", "apps", "infra"]
+        );
+    }
+
+    #[test]
+    fn test_sort_reversed() {
+        let sort = SortState {
+            key: Some(SortKey::Status),
+            reversed: true,
+        };
+        assert_eq!(
+            sorted_names(sort, &items()),
+            vec!["infra", "apps", "monitoring"]
+        );
+    }
+
+    #[test]
+    fn test_indicator() {
+        let mut sort = SortState {
+            key: Some(SortKey::Status),
+            reversed: false,
+        };
+        assert_eq!(sort.indicator("READY"), Some("▲"));
+        assert_eq!(sort.indicator("NAME"), None);
+        sort.reversed = true;
+        assert_eq!(sort.indicator("READY"), Some("▼"));
+        assert_eq!(SortState::default().indicator("NAME"), None);
+    }
+}
@@ -1,13 +1,79 @@
 //! Application state (Model in The Elm Architecture)
 
-use color_eyre::Result;
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    mem::Discriminant,
+    path::PathBuf,
+    sync::Arc,
+    time::Instant,
+};
+
+use chrono::{DateTime, Duration, Utc};
+use color_eyre::{eyre::eyre, Result};
+use futures::StreamExt;
+use tokio::sync::mpsc;
 
+#[cfg(test)]
+use crate::kubernetes::mock::MockClient;
 use crate::kubernetes::{
-    client::KubeClient,
-    resources::{FluxResource, HelmChart, HelmRelease, Kustomization},
+    api::FluxApi,
+    artifact::{self, ManifestFile},
+    client::{is_unauthorized, list_contexts, ApiLog, ClusterSnapshot, KubeClient, KubeEvent},
+    flux_version::FluxVersion,
+    helm::{self, Revision},
+    metrics::{self, ControllerMetrics},
+    portforward::PortForward,
+    preflight::{self, Check},
+    reconcile::{self, Mode, ResourceRef},
+    resources::{
+        kind::KINDS, FluxResource, GitRef, GitRepository, HelmChart, HelmRelease, HelmRepository,
+        KindInfo, Kustomization, SourceRef,
+    },
+};
+use crate::ui::{
+    theme::Theme,
+    widgets::{message, resource_table},
+};
+
+use super::{
+    action_log::{ActionLog, Outcome},
+    actions::Action,
+    buffer::BoundedBuffer,
+    bus::{Bus, Clients, Progress, StateUpdate},
+    changes::{self, ChangeSet, Remote, RevisionHistory},
+    command::{Command, LabelSelector},
+    completion::{self, Completion},
+    config::{Config, Environment},
+    daemon::{self, DaemonState},
+    details, diagnostics, finder,
+    graph::{dependency_tree, GraphNode},
+    group::{self, TableRow},
+    index::ConsumerIndex,
+    keymap,
+    locale::Locale,
+    middleware::{self, Middleware},
+    progress::LoadProgress,
+    reconcile_progress::{self, ReconcileProgress, ReconcileUpdate},
+    search::FuzzyFilter,
+    snapshot::Snapshot,
+    sort::SortState,
+    state_file::StateFile,
+    status_filter::StatusFilter,
+    storm::{self, ReconcileStorm},
+    streaks::FailureStreaks,
+    timeline::{self, StatusTimelines},
+    tour,
+    trace::{self, Sources, TraceHop},
+    usage::Usage,
 };
 
-use super::actions::Action;
+/// How many resources a tab lists, and how many of them failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TabCount {
+    pub total: usize,
+    pub failed: usize,
+}
 
 /// The currently active tab
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -16,12 +82,20 @@ pub enum Tab {
     Kustomizations,
     HelmReleases,
     HelmCharts,
+    GitRepositories,
+    HelmRepositories,
 }
 
 impl Tab {
     /// Get all tabs in order
     pub fn all() -> &'static [Tab] {
-        &[Tab::Kustomizations, Tab::HelmReleases, Tab::HelmCharts]
+        &[
+            Tab::Kustomizations,
+            Tab::HelmReleases,
+            Tab::HelmCharts,
+            Tab::GitRepositories,
+            Tab::HelmRepositories,
+        ]
     }
 
     /// Get the display name for this tab
@@ -30,6 +104,8 @@ impl Tab {
             Tab::Kustomizations => "Kustomizations",
             Tab::HelmReleases => "HelmReleases",
             Tab::HelmCharts => "HelmCharts",
+            Tab::GitRepositories => "GitRepositories",
+            Tab::HelmRepositories => "HelmRepositories",
         }
     }
 
@@ -38,20 +114,142 @@ impl Tab {
         match self {
             Tab::Kustomizations => Tab::HelmReleases,
             Tab::HelmReleases => Tab::HelmCharts,
-            Tab::HelmCharts => Tab::Kustomizations,
+            Tab::HelmCharts => Tab::GitRepositories,
+            Tab::GitRepositories => Tab::HelmRepositories,
+            Tab::HelmRepositories => Tab::Kustomizations,
         }
     }
 
+    /// API location and supported actions of the kind listed on this tab
+    pub fn info(&self) -> &'static KindInfo {
+        &KINDS[*self as usize]
+    }
+
+    /// Kubernetes kind listed on this tab
+    pub fn kind(&self) -> &'static str {
+        self.info().kind
+    }
+
+    /// Tab listing a kind, None for kinds without one (e.g. OCIRepository)
+    pub fn of_kind(kind: &str) -> Option<Tab> {
+        Tab::all().iter().copied().find(|tab| tab.kind() == kind)
+    }
+
+    /// Whether this tab lists sources that can be suspended
+    pub fn is_source(&self) -> bool {
+        self.info().is_source
+    }
+
+    /// Whether this tab lists resources that consume sources
+    pub fn consumes_sources(&self) -> bool {
+        self.info().consumes_sources
+    }
+
     /// Get the previous tab
     pub fn previous(&self) -> Tab {
         match self {
-            Tab::Kustomizations => Tab::HelmCharts,
+            Tab::Kustomizations => Tab::HelmRepositories,
             Tab::HelmReleases => Tab::Kustomizations,
             Tab::HelmCharts => Tab::HelmReleases,
+            Tab::GitRepositories => Tab::HelmCharts,
+            Tab::HelmRepositories => Tab::GitRepositories,
+        }
+    }
+}
+
+/// Restricts the consumer tabs to resources referencing one source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFilter {
+    /// Cluster the source lives in
+    pub cluster: String,
+
+    /// The source, as its consumers reference it
+    pub source: SourceRef,
+
+    /// Only consumers whose path has changes (`:changes`)
+    pub changes: Option<ChangeSet>,
+}
+
+impl SourceFilter {
+    /// Filter for consumers of the given source resource
+    pub fn of(source: &dyn FluxResource) -> Self {
+        Self {
+            cluster: source.cluster().to_string(),
+            source: SourceRef {
+                kind: source.kind().to_string(),
+                name: source.name().to_string(),
+                namespace: source.namespace().to_string(),
+            },
+            changes: None,
+        }
+    }
+
+    /// Check whether a resource consumes the source (and is affected by the
+    /// changes)
+    pub fn matches(&self, resource: &dyn FluxResource) -> bool {
+        resource.cluster() == self.cluster
+            && resource.source() == Some(&self.source)
+            && self.changes.as_ref().is_none_or(|changes| {
+                resource
+                    .source_path()
+                    .is_some_and(|path| changes.affects(path))
+            })
+    }
+
+    /// Label shown in the table title
+    pub fn label(&self) -> String {
+        let consumers = format!(
+            "consumers of {} {}/{}",
+            self.source.kind, self.source.namespace, self.source.name
+        );
+        match &self.changes {
+            Some(changes) => {
+                let files = match changes.paths.len() {
+                    1 => "1 file".to_string(),
+                    n => format!("{n} files"),
+                };
+                format!(
+                    "{consumers} changed in {}..{} ({files})",
+                    changes.from, changes.to
+                )
+            }
+            None => consumers,
         }
     }
 }
 
+/// Entry of the namespace popup that clears the namespace filter
+pub const ALL_NAMESPACES: &str = "All namespaces";
+
+/// Rows a page moves before the table was first drawn
+const DEFAULT_PAGE_ROWS: usize = 10;
+
+/// How long the header flashes after a resource failed
+const FLASH_DURATION: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// New failures kept until notified of
+const MAX_NEW_FAILURES: usize = 100;
+
+/// Largest count prefix kept, far beyond any table
+const MAX_COUNT: usize = 100_000;
+
+/// Entries of the namespace popup matching its query: all namespaces (None)
+/// while nothing is typed, then the matching namespaces
+pub fn namespace_choices<'a>(namespaces: &'a [String], query: &str) -> Vec<Option<&'a str>> {
+    let filter = FuzzyFilter::new(query);
+    query
+        .is_empty()
+        .then_some(None)
+        .into_iter()
+        .chain(
+            namespaces
+                .iter()
+                .filter(|ns| filter.matches(&[ns.as_str()]))
+                .map(|ns| Some(ns.as_str())),
+        )
+        .collect()
+}
+
 /// Popup state
 #[derive(Debug, Clone, Default)]
 pub enum Popup {
@@ -59,27 +257,235 @@ pub enum Popup {
     None,
     NamespaceFilter {
         namespaces: Vec<String>,
+        /// Typed text narrowing the list
+        query: String,
+        /// Index into the matching entries
+        selected: usize,
+    },
+    ContextSwitcher {
+        contexts: Vec<String>,
+        selected: usize,
+    },
+    ClusterFilter {
+        clusters: Vec<String>,
         selected: usize,
     },
     ResourceDetails {
         resource: Box<dyn FluxResource>,
+        scroll: u16,
+    },
+    Yaml {
+        title: String,
+        content: String,
+        scroll: u16,
+    },
+    Events {
+        title: String,
+        events: Vec<KubeEvent>,
+        scroll: u16,
+    },
+    /// Complete status message of a resource
+    Message {
+        title: String,
+        message: String,
+        scroll: u16,
+    },
+    Dependencies {
+        nodes: Vec<GraphNode>,
+        scroll: u16,
+    },
+    Trace {
+        hops: Vec<TraceHop>,
+    },
+    /// Read-only files at a Kustomization's path in its source artifact
+    PathBrowser {
+        title: String,
+        files: Vec<ManifestFile>,
+        selected: usize,
+        /// Whether the selected file is shown instead of the list
+        open: bool,
+        scroll: u16,
+    },
+    /// Metrics scraped from the Flux controllers
+    Controllers {
+        controllers: Vec<ControllerMetrics>,
+        scroll: u16,
+    },
+    /// Helm release revisions of a HelmRelease, newest first
+    HelmHistory {
+        release: Box<HelmRelease>,
+        revisions: Vec<Revision>,
+        selected: usize,
+    },
+    Help {
+        scroll: u16,
+    },
+    /// Local usage statistics
+    Usage {
+        scroll: u16,
+    },
+    /// Changes made from the app this session
+    ActionLog {
+        scroll: u16,
+    },
+    /// Results of the Flux installation checks
+    Preflight {
+        checks: Vec<Check>,
+        scroll: u16,
+    },
+    /// Flux distribution and controller versions, from `App.flux_version`
+    FluxVersion {
+        scroll: u16,
+    },
+    EditNote {
+        resource: Box<dyn FluxResource>,
+        text: String,
+    },
+    ConfirmSuspend {
+        resource: Box<dyn FluxResource>,
+        consumers: Vec<String>,
+    },
+    Command {
+        text: String,
+    },
+    /// Onboarding tour, at an index into `tour::STEPS`
+    Tour {
+        step: usize,
+    },
+    /// Search across all tabs, listing the matches of its query
+    GlobalSearch {
+        query: String,
+        /// Index into the matches
+        selected: usize,
+    },
+    /// Dry-run preview of `suspend -l <selector>`
+    ConfirmBulkSuspend {
+        selector: String,
+        resources: Vec<Box<dyn FluxResource>>,
+    },
+    /// Warning before `ref <ref>` makes a GitRepository diverge from Git
+    ConfirmRefOverride {
+        repository: Box<GitRepository>,
+        reference: GitRef,
+    },
+    /// Confirmation before rolling a Helm release back
+    ConfirmRollback {
+        release: Box<HelmRelease>,
+        revision: Revision,
     },
+    /// Live progress of a reconcile running in the background
     Reconciling {
         name: String,
         namespace: String,
+        progress: ReconcileProgress,
     },
     Error {
         message: String,
     },
+    Info {
+        message: String,
+    },
+}
+
+/// Cached resources and UI state of a context that isn't active
+///
+/// Stashed on a context switch and restored when switching back, so the
+/// previous view reappears immediately instead of reloading from scratch.
+#[derive(Debug, Default)]
+struct ClusterView {
+    kustomizations: Vec<Kustomization>,
+    helm_releases: Vec<HelmRelease>,
+    helm_charts: Vec<HelmChart>,
+    git_repositories: Vec<GitRepository>,
+    helm_repositories: Vec<HelmRepository>,
+    namespaces: Vec<String>,
+    selected: [usize; 5],
+    sort: [SortState; 5],
+    status_filter: [StatusFilter; 5],
+    namespace_filter: Option<String>,
+    source_filter: Option<SourceFilter>,
+    failure_streaks: FailureStreaks,
+    timelines: StatusTimelines,
+    consumers: ConsumerIndex,
+}
+
+/// Data fetched by [`App::fetch`], for the view it was fetched for
+pub struct Fetched {
+    /// Clusters and namespace filter at the time of the fetch
+    for_view: (Vec<String>, Option<String>),
+    data: FetchedData,
+}
+
+/// One allow-listed namespace of a cluster, reported by a refresh that is
+/// still fetching the others
+pub struct FetchedNamespace {
+    for_view: (Vec<String>, Option<String>),
+    cluster: String,
+    namespace: String,
+    snapshot: ClusterSnapshot,
+    multi_cluster: bool,
+}
+
+/// Where fetched data came from
+enum FetchedData {
+    /// State of the daemon, when attached
+    Daemon(Box<Result<DaemonState>>),
+
+    /// Each cluster's resources in all namespaces, or the filtered one
+    All(Vec<(String, Result<ClusterSnapshot>)>),
+
+    /// Each allow-listed namespace's resources, per cluster
+    Namespaces {
+        multi_cluster: bool,
+        results: Vec<(String, String, Result<ClusterSnapshot>)>,
+    },
+}
+
+impl FetchedData {
+    /// Whether a cluster rejected the credentials
+    fn is_unauthorized(&self) -> bool {
+        let rejected = |result: &Result<ClusterSnapshot>| match result {
+            Ok(snapshot) => snapshot.is_unauthorized(),
+            Err(e) => is_unauthorized(e),
+        };
+        match self {
+            Self::Daemon(_) => false,
+            Self::All(results) => results.iter().any(|(_, result)| rejected(result)),
+            Self::Namespaces { results, .. } => {
+                results.iter().any(|(_, _, result)| rejected(result))
+            }
+        }
+    }
+}
+
+/// Recovery from credentials the API server rejected
+///
+/// Exec plugins (`aws eks get-token`, `gcloud`) hand out short-lived tokens.
+/// Once one expires, the clients are rebuilt so config inference runs the
+/// plugin again, and the refresh is retried before anything is reported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Reauth {
+    /// Credentials accepted
+    #[default]
+    Idle,
+
+    /// A refresh was rejected; rebuild the clients and refresh again
+    Pending,
+
+    /// Rebuilding the clients; rejections wait for the new ones
+    Reloading,
+
+    /// Rebuilt after a rejection; another one is reported
+    Retried,
 }
 
 /// Main application state
 pub struct App {
-    /// Kubernetes client (None only in test mode)
-    #[cfg(not(test))]
-    pub client: KubeClient,
-    #[cfg(test)]
-    pub client: Option<KubeClient>,
+    /// Client of the primary context
+    pub client: Arc<dyn FluxApi>,
+
+    /// Clients for additional contexts in multi-cluster mode
+    pub other_clients: Vec<Arc<dyn FluxApi>>,
 
     /// Current active tab
     pub tab: Tab,
@@ -93,84 +499,308 @@ pub struct App {
     /// HelmChart resources
     pub helm_charts: Vec<HelmChart>,
 
+    /// GitRepository resources
+    pub git_repositories: Vec<GitRepository>,
+
+    /// HelmRepository resources
+    pub helm_repositories: Vec<HelmRepository>,
+
     /// Currently selected index for each tab
-    pub selected: [usize; 3],
+    pub selected: [usize; 5],
+
+    /// First row shown in each tab's table
+    pub scroll: [usize; 5],
+
+    /// Table rows on screen when last drawn, the distance paging moves
+    page_rows: usize,
+
+    /// Count typed before a movement (e.g. the 5 of `5j`)
+    pub count: Option<usize>,
 
     /// Current namespace filter (None = all namespaces)
     pub namespace_filter: Option<String>,
 
+    /// Current cluster filter in multi-cluster mode (None = all clusters)
+    pub cluster_filter: Option<String>,
+
+    /// Show only consumers of this source on the consumer tabs
+    pub source_filter: Option<SourceFilter>,
+
+    /// Sort state for each tab
+    pub sort: [SortState; 5],
+
+    /// Status filter for each tab
+    pub status_filter: [StatusFilter; 5],
+
+    /// Whether the consumer tabs are grouped under their sources
+    pub grouped: bool,
+
+    /// Keys of the collapsed source groups
+    pub collapsed_groups: HashSet<String>,
+
+    /// Fuzzy filter for the current tab (empty = no filter)
+    pub search_query: String,
+
+    /// Whether the fuzzy filter is being typed
+    pub search_editing: bool,
+
+    /// Tab completion in the text input being typed
+    pub completion: Option<Completion>,
+
+    /// Port-forwards held open while the kind of popup that opened them is shown
+    forwards: HashMap<String, PortForward>,
+
+    /// Kind of popup the port-forwards are held for
+    forwards_for: Option<Discriminant<Popup>>,
+
+    /// Progress reported by the reconcile shown in the Reconciling popup
+    reconcile_updates: Option<mpsc::UnboundedReceiver<ReconcileUpdate>>,
+
     /// All available namespaces
     pub namespaces: Vec<String>,
 
     /// Current popup state
     pub popup: Popup,
 
+    /// Whether the recent activity sidebar is shown
+    pub show_activity: bool,
+
+    /// Whether the selected resource's details are shown beside the table
+    /// (split-pane layout) instead of only in a popup
+    pub show_details: bool,
+
+    /// Whether the details popup lists labels and annotations in full
+    pub show_metadata: bool,
+
     /// Loading state
     pub loading: bool,
 
+    /// When data was last refreshed without errors
+    pub last_refreshed: Option<DateTime<Utc>>,
+
+    /// Per-kind outcome of the current or last refresh
+    pub load_progress: LoadProgress,
+
+    /// State kept across sessions (notes)
+    pub state: StateFile,
+
+    /// Where `state` is saved (None = not persisted)
+    state_path: Option<PathBuf>,
+
+    /// Socket of the daemon resources are read from (None = list them directly)
+    daemon_socket: Option<PathBuf>,
+
     /// Last error message
     pub last_error: Option<String>,
 
+    /// Consecutive failing refreshes per resource
+    pub failure_streaks: FailureStreaks,
+
+    /// Status of each resource at every refresh this session
+    pub timelines: StatusTimelines,
+
+    /// Revision each GitRepository had before its current one, for `:changes`
+    revisions: RevisionHistory,
+
+    /// Resources that went from Ready to Failed, not yet notified of
+    pub new_failures: Vec<String>,
+
+    /// When the header started flashing for a new failure
+    flash_since: Option<Instant>,
+
+    /// Ongoing reconcile storm, shown as a banner
+    pub storm: Option<ReconcileStorm>,
+
+    /// How timestamps and counts are formatted
+    pub locale: Locale,
+
+    /// Colors to draw with
+    pub theme: Theme,
+
+    /// Views of previously active contexts, keyed by context name
+    saved_views: HashMap<String, ClusterView>,
+
+    /// Consumers of each source, rebuilt on refresh
+    pub consumers: ConsumerIndex,
+
+    /// Recent errors with the time they occurred (oldest first)
+    pub recent_errors: BoundedBuffer<(DateTime<Utc>, String)>,
+
+    /// Recent Kubernetes API requests (shared with the client)
+    pub api_log: ApiLog,
+
     /// Current cluster name
     pub cluster_name: String,
+
+    /// When the snapshot shown was taken (`--snapshot`), None for live
+    /// clusters
+    pub snapshot_taken: Option<DateTime<Utc>>,
+
+    /// Effective configuration (updated when switching contexts)
+    pub config: Config,
+
+    /// Environment banner of the shown context, if one matches
+    pub environment: Option<Environment>,
+
+    /// Recovery from rejected credentials
+    pub reauth: Reauth,
+
+    /// Newer flux-tui release, if the update check found one
+    pub latest_version: Option<String>,
+
+    /// Flux version of the first cluster, once read
+    pub flux_version: Option<FluxVersion>,
+
+    /// Local usage statistics
+    pub usage: Usage,
+
+    /// Changes made from the app this session
+    pub action_log: ActionLog,
+
+    /// Steps every action passes through before it is applied
+    middleware: Vec<Box<dyn Middleware>>,
+
+    /// Background work reporting back to the UI loop
+    bus: Bus,
+
+    /// Whether a refresh is running on the bus
+    refreshing: bool,
+
+    /// Clusters the running refresh already stored namespaces of
+    namespaces_streamed: Vec<String>,
+
+    /// Refresh once more when the running refresh lands, e.g. because the
+    /// namespace filter changed while it was in flight
+    refresh_again: bool,
+
+    /// When the last refresh landed
+    last_fetch: Instant,
 }
 
 impl App {
     /// Create a new App instance
-    #[cfg(not(test))]
-    pub async fn new() -> Result<Self> {
-        let client = KubeClient::new().await?;
-        let cluster_name = client.cluster_name().to_string();
+    pub async fn new(config: &Config) -> Result<Self> {
+        let (client, other_clients) = connect(config).await?;
+        Self::start(config, client, other_clients).await
+    }
 
-        let mut app = Self {
-            client,
-            tab: Tab::default(),
-            kustomizations: Vec::new(),
-            helm_releases: Vec::new(),
-            helm_charts: Vec::new(),
-            selected: [0; 3],
-            namespace_filter: None,
-            namespaces: Vec::new(),
-            popup: Popup::None,
-            loading: true,
-            last_error: None,
-            cluster_name,
+    /// Create an App showing the clusters saved in a snapshot
+    pub async fn from_snapshot(config: &Config, snapshot: Snapshot) -> Result<Self> {
+        let taken_at = snapshot.taken_at;
+        let (client, other_clients) = snapshot.into_clients();
+        let mut app = Self::start(config, client, other_clients).await?;
+        app.snapshot_taken = Some(taken_at);
+        Ok(app)
+    }
+
+    /// An App on these clients, with the state file and usage statistics
+    /// loaded and the first data fetched
+    async fn start(
+        config: &Config,
+        client: Arc<dyn FluxApi>,
+        other_clients: Vec<Arc<dyn FluxApi>>,
+    ) -> Result<Self> {
+        let theme = Theme::resolve(config.theme.as_deref(), config.no_color)?;
+        let state_path = StateFile::path();
+        let state = match &state_path {
+            Some(path) => StateFile::load(path)?,
+            None => StateFile::default(),
         };
 
+        let mut app = Self::with_clients(config, client, other_clients, theme);
+        app.state = state;
+        app.state_path = state_path;
+        app.daemon_socket = config.attach.then(daemon::socket_path).flatten();
+        app.usage = Usage::start(config.usage_stats, Utc::now());
+        app.environment = app.match_environment();
+
         // Initial data load
         app.refresh_data().await?;
 
         Ok(app)
     }
 
-    /// Create a new App instance (test version)
-    #[cfg(test)]
-    pub async fn new() -> Result<Self> {
-        let client = KubeClient::new().await?;
+    /// An App showing the clusters of these clients, before its first
+    /// refresh and without reading or writing any files
+    fn with_clients(
+        config: &Config,
+        client: Arc<dyn FluxApi>,
+        other_clients: Vec<Arc<dyn FluxApi>>,
+        theme: Theme,
+    ) -> Self {
         let cluster_name = client.cluster_name().to_string();
+        let api_log = client.api_log();
 
-        let mut app = Self {
-            client: Some(client),
+        Self {
+            client,
+            other_clients,
             tab: Tab::default(),
             kustomizations: Vec::new(),
             helm_releases: Vec::new(),
             helm_charts: Vec::new(),
-            selected: [0; 3],
-            namespace_filter: None,
+            git_repositories: Vec::new(),
+            helm_repositories: Vec::new(),
+            selected: [0; 5],
+            scroll: [0; 5],
+            page_rows: DEFAULT_PAGE_ROWS,
+            count: None,
+            namespace_filter: config.namespace.clone(),
+            cluster_filter: None,
+            source_filter: None,
+            sort: [SortState::default(); 5],
+            status_filter: [StatusFilter::default(); 5],
+            grouped: false,
+            collapsed_groups: HashSet::new(),
+            search_query: String::new(),
+            search_editing: false,
+            completion: None,
+            forwards: HashMap::new(),
+            forwards_for: None,
+            reconcile_updates: None,
             namespaces: Vec::new(),
             popup: Popup::None,
+            show_activity: false,
+            show_details: false,
+            show_metadata: false,
             loading: true,
+            last_refreshed: None,
             last_error: None,
+            failure_streaks: FailureStreaks::default(),
+            timelines: StatusTimelines::new(config.buffers.history),
+            revisions: RevisionHistory::default(),
+            new_failures: Vec::new(),
+            flash_since: None,
+            storm: None,
+            locale: Locale::resolve(config.locale.as_deref()),
+            theme,
+            saved_views: HashMap::new(),
+            load_progress: LoadProgress::default(),
+            state: StateFile::default(),
+            state_path: None,
+            daemon_socket: None,
+            consumers: ConsumerIndex::default(),
+            recent_errors: BoundedBuffer::new(config.buffers.errors),
+            api_log,
             cluster_name,
-        };
-
-        // Initial data load
-        app.refresh_data().await?;
-
-        Ok(app)
+            snapshot_taken: None,
+            config: config.clone(),
+            environment: None,
+            reauth: Reauth::Idle,
+            latest_version: None,
+            flux_version: None,
+            usage: Usage::start(false, Utc::now()),
+            action_log: ActionLog::new(config.buffers.actions),
+            middleware: middleware::chain(config),
+            bus: Bus::default(),
+            refreshing: false,
+            namespaces_streamed: Vec::new(),
+            refresh_again: false,
+            last_fetch: Instant::now(),
+        }
     }
 
-    /// Create an App for testing without requiring a KubeClient
+    /// Create an App for testing, backed by a [`MockClient`] serving the
+    /// given resources
     #[cfg(test)]
     pub fn for_testing(
         tab: Tab,
@@ -178,1056 +808,5395 @@ impl App {
         helm_releases: Vec<HelmRelease>,
         helm_charts: Vec<HelmChart>,
     ) -> Self {
-        Self {
-            client: None,
-            tab,
-            kustomizations,
-            helm_releases,
-            helm_charts,
-            selected: [0; 3],
-            namespace_filter: None,
-            namespaces: Vec::new(),
-            popup: Popup::None,
-            loading: false,
-            last_error: None,
-            cluster_name: "test-cluster".to_string(),
-        }
+        let client = MockClient::new(ClusterSnapshot {
+            kustomizations: kustomizations.clone(),
+            helm_releases: helm_releases.clone(),
+            helm_charts: helm_charts.clone(),
+            ..Default::default()
+        });
+        let mut app = Self::with_clients(&Config::default(), client, Vec::new(), Theme::default());
+        app.tab = tab;
+        app.kustomizations = kustomizations;
+        app.helm_releases = helm_releases;
+        app.helm_charts = helm_charts;
+        app.locale = Locale::ISO;
+        app.loading = false;
+        app
     }
 
-    /// Refresh all data from the cluster
-    #[cfg(not(test))]
+    /// Refresh all data, fetching from every connected cluster in parallel
+    ///
+    /// Waits for the data; the UI refreshes with [`App::start_refresh`].
     pub async fn refresh_data(&mut self) -> Result<()> {
         self.loading = true;
-
-        // Fetch all resources in parallel
-        let ns = self.namespace_filter.as_deref();
-
-        match tokio::try_join!(
-            self.client.list_kustomizations(ns),
-            self.client.list_helm_releases(ns),
-            self.client.list_helm_charts(ns),
-            self.client.list_namespaces(),
-        ) {
-            Ok((kustomizations, helm_releases, helm_charts, namespaces)) => {
-                self.kustomizations = kustomizations;
-                self.helm_releases = helm_releases;
-                self.helm_charts = helm_charts;
-                self.namespaces = namespaces;
-                self.last_error = None;
-            }
-            Err(e) => {
-                self.last_error = Some(format!("Failed to fetch resources: {e}"));
-            }
-        }
-
-        self.loading = false;
+        let fetched = self.fetch(None).await;
+        self.apply_refresh(fetched);
         Ok(())
     }
 
-    /// Refresh all data from the cluster (test version)
-    #[cfg(test)]
-    pub async fn refresh_data(&mut self) -> Result<()> {
-        self.loading = true;
-
-        // In test mode, only refresh if we have a real client
-        if let Some(ref client) = self.client {
-            let ns = self.namespace_filter.as_deref();
-
-            match tokio::try_join!(
-                client.list_kustomizations(ns),
-                client.list_helm_releases(ns),
-                client.list_helm_charts(ns),
-                client.list_namespaces(),
-            ) {
-                Ok((kustomizations, helm_releases, helm_charts, namespaces)) => {
-                    self.kustomizations = kustomizations;
-                    self.helm_releases = helm_releases;
-                    self.helm_charts = helm_charts;
-                    self.namespaces = namespaces;
-                    self.last_error = None;
+    /// Fetch fresh data without touching the app, so it can run in a
+    /// background task while the UI keeps drawing
+    ///
+    /// Apply the result with [`App::apply_refresh`]. With `progress`, each
+    /// allow-listed namespace is reported as soon as it arrives; only the
+    /// failed ones are left for the result.
+    pub fn fetch(
+        &self,
+        progress: Option<Progress>,
+    ) -> impl Future<Output = Fetched> + Send + 'static {
+        let clients: Vec<Arc<dyn FluxApi>> = self.clients().into_iter().cloned().collect();
+        let namespace = self.namespace_filter.clone();
+        let allowed = self.config.namespaces.clone();
+        let concurrency = self.config.fetch_concurrency.max(1);
+        let daemon_socket = self.daemon_socket.clone();
+        let for_view = self.view_key();
+
+        async move {
+            let data = if let Some(path) = daemon_socket {
+                FetchedData::Daemon(Box::new(daemon::fetch(&path).await))
+            } else if namespace.is_none() && !allowed.is_empty() {
+                // Futures do nothing until polled, so at most `concurrency`
+                // requests run at once
+                let requests: Vec<_> = clients
+                    .iter()
+                    .flat_map(|client| {
+                        allowed
+                            .iter()
+                            .map(|ns| fetch_namespace(Arc::clone(client), ns.clone()))
+                    })
+                    .collect();
+                let mut arrivals = futures::stream::iter(requests).buffer_unordered(concurrency);
+                let mut results = Vec::new();
+                while let Some((cluster, ns, result)) = arrivals.next().await {
+                    // Rejected credentials are left for the retry
+                    match (&progress, result) {
+                        (Some(progress), Ok(snapshot)) if !snapshot.is_unauthorized() => {
+                            progress.send(StateUpdate::FetchedNamespace(Box::new(
+                                FetchedNamespace {
+                                    for_view: for_view.clone(),
+                                    cluster,
+                                    namespace: ns,
+                                    snapshot,
+                                    multi_cluster: clients.len() > 1,
+                                },
+                            )));
+                        }
+                        (_, result) => results.push((cluster, ns, result)),
+                    }
                 }
-                Err(e) => {
-                    self.last_error = Some(format!("Failed to fetch resources: {e}"));
+                FetchedData::Namespaces {
+                    multi_cluster: clients.len() > 1,
+                    results,
                 }
-            }
+            } else {
+                let ns = namespace.as_deref();
+                let results = futures::future::join_all(clients.iter().map(|client| async move {
+                    (client.label().to_string(), client.fetch_all(ns).await)
+                }))
+                .await;
+                FetchedData::All(results)
+            };
+            Fetched { for_view, data }
         }
-
-        self.loading = false;
-        Ok(())
     }
 
-    /// Get the current tab index
-    pub fn tab_index(&self) -> usize {
-        match self.tab {
-            Tab::Kustomizations => 0,
-            Tab::HelmReleases => 1,
-            Tab::HelmCharts => 2,
+    /// Refresh in the background; the data is applied once it arrives
+    ///
+    /// While a refresh is in flight another one is not started, but runs
+    /// once it lands, so holding F5 costs one extra refresh and a changed
+    /// view is still fetched.
+    pub fn start_refresh(&mut self) {
+        if self.refreshing {
+            self.refresh_again = true;
+            return;
         }
+        let fetch = self.fetch(Some(self.bus.progress()));
+        self.bus
+            .spawn(async move { StateUpdate::Fetched(fetch.await) });
+        self.loading = true;
+        self.refreshing = true;
     }
 
-    /// Get the selected index for the current tab
-    pub fn current_selected(&self) -> usize {
-        self.selected[self.tab_index()]
+    /// Whether the auto-refresh is due
+    pub fn refresh_due(&self, interval: std::time::Duration) -> bool {
+        !self.refreshing && self.last_fetch.elapsed() >= interval
     }
 
-    /// Set the selected index for the current tab
-    pub fn set_current_selected(&mut self, index: usize) {
-        self.selected[self.tab_index()] = index;
+    /// Whether background work is running, so the spinner keeps turning
+    pub fn is_busy(&self) -> bool {
+        self.bus.is_busy()
     }
 
-    /// Get the number of items in the current tab
-    pub fn current_item_count(&self) -> usize {
-        match self.tab {
-            Tab::Kustomizations => self.kustomizations.len(),
-            Tab::HelmReleases => self.helm_releases.len(),
-            Tab::HelmCharts => self.helm_charts.len(),
+    /// Apply every update background work reported since the last call
+    ///
+    /// Returns whether there were any.
+    pub fn apply_updates(&mut self) -> bool {
+        let mut applied = false;
+        while let Some(update) = self.bus.try_recv() {
+            self.apply(update);
+            applied = true;
         }
+        applied
     }
 
-    /// Update the application state based on an action
-    pub async fn update(&mut self, action: Action) -> Result<()> {
-        match action {
-            Action::Quit => {} // Handled in main loop
-            Action::NextTab => {
-                self.tab = self.tab.next();
-            }
-            Action::PreviousTab => {
-                self.tab = self.tab.previous();
-            }
-            Action::Up => {
-                let selected = self.current_selected();
-                if selected > 0 {
-                    self.set_current_selected(selected - 1);
+    /// Apply the result of background work
+    pub fn apply(&mut self, update: StateUpdate) {
+        match update {
+            StateUpdate::Fetched(fetched) => {
+                self.apply_refresh(fetched);
+                self.refreshing = false;
+                self.last_fetch = Instant::now();
+                if std::mem::take(&mut self.refresh_again) {
+                    self.start_refresh();
                 }
             }
-            Action::Down => {
-                let selected = self.current_selected();
-                let count = self.current_item_count();
-                if count > 0 && selected < count - 1 {
-                    self.set_current_selected(selected + 1);
+            StateUpdate::FetchedNamespace(fetched) => self.apply_namespace(*fetched),
+            StateUpdate::Popup { popup, forwards } => {
+                self.popup = popup;
+                for (key, forward) in forwards {
+                    self.hold_forward(key, forward);
                 }
             }
-            Action::Top => {
-                self.set_current_selected(0);
+            StateUpdate::Failed(message) => {
+                self.recent_errors.push((Utc::now(), message.clone()));
+                self.popup = Popup::Error { message };
             }
-            Action::Bottom => {
-                let count = self.current_item_count();
-                if count > 0 {
-                    self.set_current_selected(count - 1);
-                }
+            StateUpdate::Panicked(message) => {
+                // It may have been the refresh, which would then never
+                // finish; a duplicate refresh is the lesser evil
+                self.refreshing = false;
+                self.loading = false;
+                self.recent_errors.push((Utc::now(), message.clone()));
+                self.popup = Popup::Error { message };
             }
-            Action::Select => {
-                // View details of selected resource
-                if let Some(resource) = self.get_selected_resource() {
-                    self.popup = Popup::ResourceDetails { resource };
+            StateUpdate::Reloaded(result) => self.reloaded(result),
+            StateUpdate::Changed { logged, popup } => {
+                let now = Utc::now();
+                let mut changed = false;
+                for (action, resource, outcome) in logged {
+                    changed |= outcome == Outcome::Succeeded;
+                    self.action_log
+                        .record(now, action, resource.as_ref(), outcome);
+                }
+                if changed {
+                    self.start_refresh();
+                }
+                if let Some(popup) = popup {
+                    if let Popup::Error { message } = &popup {
+                        self.recent_errors.push((now, message.clone()));
+                    }
+                    self.popup = popup;
                 }
             }
-            Action::Reconcile => {
-                self.reconcile_selected(false).await?;
-            }
-            Action::ReconcileWithSource => {
-                self.reconcile_selected(true).await?;
-            }
-            Action::FilterNamespace => {
-                let mut namespaces = vec!["All namespaces".to_string()];
-                namespaces.extend(self.namespaces.clone());
-                self.popup = Popup::NamespaceFilter {
-                    namespaces,
-                    selected: 0,
-                };
-            }
-            Action::SetNamespace(ns) => {
-                self.namespace_filter = ns;
-                self.popup = Popup::None;
-                self.refresh_data().await?;
-            }
-            Action::ClosePopup => {
-                self.popup = Popup::None;
-            }
-            Action::Refresh => {
-                self.refresh_data().await?;
-            }
-            Action::ToggleSuspend => {
-                self.toggle_suspend_selected().await?;
+            StateUpdate::Connected {
+                context,
+                config,
+                client,
+            } => self.connected(context, *config, client),
+            StateUpdate::Changes(filter) => self.show_consumers(Tab::Kustomizations, filter),
+            StateUpdate::FluxVersion { version, show } => {
+                self.flux_version = Some(version);
+                if show {
+                    self.popup = Popup::FluxVersion { scroll: 0 };
+                }
             }
-            Action::Noop => {}
+            StateUpdate::Idle => {}
         }
+        self.release_forwards();
+    }
 
-        Ok(())
+    /// Wait for all background work and apply it
+    #[cfg(test)]
+    pub async fn settle(&mut self) {
+        while let Some(update) = self.bus.recv().await {
+            self.apply(update);
+        }
     }
 
-    /// Get the currently selected resource (as a trait object)
-    fn get_selected_resource(&self) -> Option<Box<dyn FluxResource>> {
-        let selected = self.current_selected();
-        match self.tab {
-            Tab::Kustomizations => self
-                .kustomizations
-                .get(selected)
-                .map(|k| Box::new(k.clone()) as Box<dyn FluxResource>),
-            Tab::HelmReleases => self
-                .helm_releases
-                .get(selected)
-                .map(|h| Box::new(h.clone()) as Box<dyn FluxResource>),
-            Tab::HelmCharts => self
-                .helm_charts
-                .get(selected)
-                .map(|h| Box::new(h.clone()) as Box<dyn FluxResource>),
+    /// Store fetched data
+    ///
+    /// Data fetched for another context or namespace filter than the current
+    /// one (the user switched while it was in flight) is dropped.
+    pub fn apply_refresh(&mut self, fetched: Fetched) {
+        self.loading = false;
+        let streamed = std::mem::take(&mut self.namespaces_streamed);
+        if fetched.for_view != self.view_key() {
+            return;
+        }
+        // Rejected credentials get one retry with rebuilt clients before
+        // they are reported; the current data stays until then
+        if fetched.data.is_unauthorized() && self.reauth != Reauth::Retried {
+            if self.reauth != Reauth::Reloading {
+                self.reauth = Reauth::Pending;
+            }
+            return;
+        }
+        self.reauth = Reauth::Idle;
+        match fetched.data {
+            FetchedData::Daemon(result) => match *result {
+                Ok(state) => self.apply_daemon_state(state),
+                Err(e) => self.record_error(format!("Failed to read from the daemon: {e}")),
+            },
+            FetchedData::Namespaces {
+                multi_cluster,
+                results,
+            } => self.apply_namespace_results(multi_cluster, results, streamed),
+            FetchedData::All(results) => self.apply_fetch_results(results),
         }
     }
 
-    /// Reconcile the selected resource
-    async fn reconcile_selected(&mut self, with_source: bool) -> Result<()> {
-        let selected = self.current_selected();
+    /// What a fetch depends on: the connected clusters and namespace filter
+    fn view_key(&self) -> (Vec<String>, Option<String>) {
+        (
+            self.clients()
+                .iter()
+                .map(|client| client.label().to_string())
+                .collect(),
+            self.namespace_filter.clone(),
+        )
+    }
 
-        let (name, namespace, kind) = match self.tab {
-            Tab::Kustomizations => {
-                if let Some(k) = self.kustomizations.get(selected) {
-                    (k.name.clone(), k.namespace.clone(), "kustomization")
-                } else {
-                    return Ok(());
+    /// Store the allow-listed namespaces' resources
+    ///
+    /// A namespace that failed to respond keeps its previous resources.
+    fn apply_namespace_results(
+        &mut self,
+        multi_cluster: bool,
+        results: Vec<(String, String, Result<ClusterSnapshot>)>,
+        streamed: Vec<String>,
+    ) {
+        if results.is_empty() && streamed.is_empty() {
+            return;
+        }
+        let allowed = self.config.namespaces.clone();
+        if streamed.is_empty() {
+            self.retain_namespaces(&allowed);
+            self.load_progress.start();
+        }
+
+        let mut refreshed = streamed;
+        let mut errors = Vec::new();
+        for (cluster, ns, result) in results {
+            match result {
+                Ok(snapshot) => {
+                    self.store_snapshot(&cluster, Some(&ns), snapshot, multi_cluster);
+                    refreshed.push(cluster);
+                }
+                Err(e) if multi_cluster => {
+                    self.fail_all_kinds(&format!("{cluster}: {ns} unreachable"));
+                    errors.push(format!(
+                        "Failed to fetch resources from {cluster} in {ns}: {e}"
+                    ));
+                }
+                Err(e) => {
+                    self.fail_all_kinds(&format!("{ns} unreachable"));
+                    errors.push(format!("Failed to fetch resources in {ns}: {e}"));
                 }
             }
-            Tab::HelmReleases => {
-                if let Some(h) = self.helm_releases.get(selected) {
-                    (h.name.clone(), h.namespace.clone(), "helmrelease")
-                } else {
-                    return Ok(());
+        }
+
+        refreshed.sort();
+        refreshed.dedup();
+        for cluster in &refreshed {
+            self.record_streaks(cluster);
+        }
+        self.index_consumers();
+        self.namespaces = allowed;
+        if errors.is_empty() {
+            self.last_error = None;
+            self.last_refreshed = Some(Utc::now());
+        }
+        for error in errors {
+            self.record_error(error);
+        }
+    }
+
+    /// Store a namespace of the running refresh as soon as it arrives
+    ///
+    /// Streaks, errors and the refresh time wait for the whole refresh.
+    fn apply_namespace(&mut self, fetched: FetchedNamespace) {
+        if fetched.for_view != self.view_key() {
+            return;
+        }
+        if self.namespaces_streamed.is_empty() {
+            let allowed = self.config.namespaces.clone();
+            self.retain_namespaces(&allowed);
+            self.load_progress.start();
+        }
+        self.store_snapshot(
+            &fetched.cluster,
+            Some(&fetched.namespace),
+            fetched.snapshot,
+            fetched.multi_cluster,
+        );
+        self.namespaces_streamed.push(fetched.cluster);
+        self.index_consumers();
+    }
+
+    /// Store a snapshot's resources for a cluster, or one of its namespaces
+    ///
+    /// Kinds that failed to list keep their previous resources, and are
+    /// flagged in the load progress.
+    fn store_snapshot(
+        &mut self,
+        cluster: &str,
+        namespace: Option<&str>,
+        snapshot: ClusterSnapshot,
+        multi_cluster: bool,
+    ) {
+        let mut listed = [true; 5];
+        for tab in Tab::all() {
+            match snapshot.failure(tab.kind()) {
+                Some(reason) => {
+                    listed[*tab as usize] = false;
+                    let reason = if multi_cluster {
+                        format!("{cluster}: {reason}")
+                    } else {
+                        reason.to_string()
+                    };
+                    self.load_progress.failed(*tab, reason);
                 }
+                None => self.load_progress.loaded(*tab),
             }
-            Tab::HelmCharts => {
-                if let Some(h) = self.helm_charts.get(selected) {
-                    (h.name.clone(), h.namespace.clone(), "helmchart")
-                } else {
-                    return Ok(());
+        }
+
+        if listed[Tab::Kustomizations as usize] {
+            replace_items(
+                &mut self.kustomizations,
+                cluster,
+                namespace,
+                snapshot.kustomizations,
+            );
+        }
+        if listed[Tab::HelmReleases as usize] {
+            replace_items(
+                &mut self.helm_releases,
+                cluster,
+                namespace,
+                snapshot.helm_releases,
+            );
+        }
+        if listed[Tab::HelmCharts as usize] {
+            replace_items(
+                &mut self.helm_charts,
+                cluster,
+                namespace,
+                snapshot.helm_charts,
+            );
+        }
+        if listed[Tab::GitRepositories as usize] {
+            replace_items(
+                &mut self.git_repositories,
+                cluster,
+                namespace,
+                snapshot.git_repositories,
+            );
+        }
+        if listed[Tab::HelmRepositories as usize] {
+            replace_items(
+                &mut self.helm_repositories,
+                cluster,
+                namespace,
+                snapshot.helm_repositories,
+            );
+        }
+    }
+
+    /// Flag every kind as failed, e.g. when a cluster doesn't respond
+    fn fail_all_kinds(&mut self, reason: &str) {
+        for tab in Tab::all() {
+            self.load_progress.failed(*tab, reason.to_string());
+        }
+    }
+
+    /// Drop resources from namespaces outside the allow-list
+    fn retain_namespaces(&mut self, allowed: &[String]) {
+        let keep = |ns: &str| allowed.iter().any(|a| a == ns);
+        self.kustomizations.retain(|r| keep(&r.namespace));
+        self.helm_releases.retain(|r| keep(&r.namespace));
+        self.helm_charts.retain(|r| keep(&r.namespace));
+        self.git_repositories.retain(|r| keep(&r.namespace));
+        self.helm_repositories.retain(|r| keep(&r.namespace));
+    }
+
+    /// All connected clients, primary first
+    pub fn clients(&self) -> Vec<&Arc<dyn FluxApi>> {
+        std::iter::once(&self.client)
+            .chain(&self.other_clients)
+            .collect()
+    }
+
+    /// Replace each cluster's resources with its fetch result
+    ///
+    /// A cluster that fails to respond keeps its previous resources, so one
+    /// unreachable cluster doesn't blank the others.
+    fn apply_fetch_results(&mut self, results: Vec<(String, Result<ClusterSnapshot>)>) {
+        let multi_cluster = results.len() > 1;
+        let mut namespaces = Vec::new();
+        let mut errors = Vec::new();
+        self.load_progress.start();
+
+        for (cluster, result) in results {
+            match result {
+                Ok(mut snapshot) => {
+                    namespaces.append(&mut snapshot.namespaces);
+                    self.store_snapshot(&cluster, None, snapshot, multi_cluster);
+                    self.record_streaks(&cluster);
+                }
+                Err(e) if multi_cluster => {
+                    self.fail_all_kinds(&format!("{cluster} unreachable"));
+                    errors.push(format!("Failed to fetch resources from {cluster}: {e}"))
+                }
+                Err(e) => {
+                    self.fail_all_kinds("unreachable");
+                    errors.push(format!("Failed to fetch resources: {e}"))
                 }
             }
-        };
+        }
 
-        self.popup = Popup::Reconciling {
-            name: name.clone(),
-            namespace: namespace.clone(),
+        self.index_consumers();
+
+        namespaces.sort();
+        namespaces.dedup();
+        if !namespaces.is_empty() {
+            self.namespaces = namespaces;
+        }
+
+        if errors.is_empty() {
+            self.last_error = None;
+            self.last_refreshed = Some(Utc::now());
+        }
+        for error in errors {
+            self.record_error(error);
+        }
+    }
+
+    /// Take over the resources and session history of the daemon
+    ///
+    /// The daemon lists every namespace it was started with; a namespace
+    /// filter is applied here instead.
+    fn apply_daemon_state(&mut self, state: DaemonState) {
+        self.kustomizations = state.kustomizations;
+        self.helm_releases = state.helm_releases;
+        self.helm_charts = state.helm_charts;
+        self.git_repositories = state.git_repositories;
+        self.helm_repositories = state.helm_repositories;
+        if let Some(ns) = self.namespace_filter.clone() {
+            self.retain_namespaces(&[ns]);
+        }
+        self.namespaces = state.namespaces;
+        self.failure_streaks = FailureStreaks::from_entries(state.streaks);
+        self.timelines.replace(state.timelines);
+        self.storm = state.storm;
+        if state.last_error.is_none() {
+            self.last_refreshed = Some(state.refreshed_at);
+        }
+        self.last_error = state.last_error;
+
+        self.load_progress.start();
+        for tab in Tab::all() {
+            self.load_progress.loaded(*tab);
+        }
+        self.index_consumers();
+    }
+
+    /// Update failure streaks, status timelines, GitRepository revisions,
+    /// new failures and reconcile storm detection after a cluster was
+    /// refreshed
+    ///
+    /// Acknowledged resources don't count towards failure streaks.
+    fn record_streaks(&mut self, cluster: &str) {
+        let now = Utc::now();
+        let resources: Vec<&dyn FluxResource> = self
+            .kustomizations
+            .iter()
+            .map(|r| r as &dyn FluxResource)
+            .chain(self.helm_releases.iter().map(|r| r as &dyn FluxResource))
+            .chain(self.helm_charts.iter().map(|r| r as &dyn FluxResource))
+            .chain(self.git_repositories.iter().map(|r| r as &dyn FluxResource))
+            .chain(
+                self.helm_repositories
+                    .iter()
+                    .map(|r| r as &dyn FluxResource),
+            )
+            .filter(|r| r.cluster() == cluster)
+            .collect();
+        self.timelines.record(cluster, resources.iter().copied());
+        let failed: Vec<String> = resources
+            .iter()
+            .filter(|r| timeline::just_failed(&self.timelines.get(**r)))
+            .map(|r| format!("{} {}/{}", r.kind(), r.namespace(), r.name()))
+            .collect();
+        if !failed.is_empty() && self.config.notifications.flash {
+            self.flash_since = Some(Instant::now());
+        }
+        // Nothing takes them in the daemon and ticker, so keep the latest only
+        self.new_failures.extend(failed);
+        let excess = self.new_failures.len().saturating_sub(MAX_NEW_FAILURES);
+        self.new_failures.drain(..excess);
+        self.revisions.record(
+            resources
+                .iter()
+                .copied()
+                .filter(|r| r.kind() == Tab::GitRepositories.kind()),
+        );
+
+        let sources = Sources {
+            helm_charts: &self.helm_charts,
+            git_repositories: &self.git_repositories,
+            helm_repositories: &self.helm_repositories,
         };
+        let threshold = self.config.storm_threshold;
+        if let Some(storm) =
+            storm::detect(cluster, &resources, &self.timelines, &sources, threshold)
+        {
+            self.storm = Some(storm);
+        } else if self.storm.as_ref().is_some_and(|s| s.cluster == cluster)
+            && !storm::is_ongoing(&resources, threshold)
+        {
+            self.storm = None;
+        }
 
-        match crate::kubernetes::reconcile::reconcile(&name, &namespace, kind, with_source).await {
-            Ok(_) => {
-                self.popup = Popup::None;
-                // Refresh to show updated status
-                self.refresh_data().await?;
+        self.failure_streaks.record(
+            cluster,
+            resources
+                .into_iter()
+                .filter(|r| self.state.acknowledged_until(*r, now).is_none()),
+        );
+    }
+
+    /// Rebuild the reverse index from sources to their consumers
+    fn index_consumers(&mut self) {
+        self.consumers = ConsumerIndex::build(
+            self.kustomizations
+                .iter()
+                .map(|r| r as &dyn FluxResource)
+                .chain(self.helm_releases.iter().map(|r| r as &dyn FluxResource)),
+        );
+    }
+
+    /// Whether the header flashes for a new failure at this moment: on and
+    /// off every half second for a few seconds
+    pub fn is_flashing(&self, now: Instant) -> bool {
+        self.flash_since.is_some_and(|since| {
+            let elapsed = now.saturating_duration_since(since);
+            elapsed < FLASH_DURATION && (elapsed.as_millis() / 500).is_multiple_of(2)
+        })
+    }
+
+    /// Whether several clusters are connected at once
+    pub fn is_multi_cluster(&self) -> bool {
+        !self.other_clients.is_empty()
+    }
+
+    /// Resources and failures per tab, in tab order, for the tab bar
+    ///
+    /// Counts the shown clusters, before each tab's search and status filter.
+    pub fn tab_counts(&self) -> [TabCount; 5] {
+        let mut counts = [TabCount::default(); 5];
+        for resource in self.all_resources() {
+            let shown = self
+                .cluster_filter
+                .as_deref()
+                .is_none_or(|cluster| resource.cluster() == cluster);
+            let Some(tab) = Tab::of_kind(resource.kind()).filter(|_| shown) else {
+                continue;
+            };
+            let count = &mut counts[tab as usize];
+            count.total += 1;
+            if StatusFilter::Failed.matches(resource) {
+                count.failed += 1;
             }
-            Err(e) => {
-                self.popup = Popup::Error {
-                    message: format!("Reconcile failed: {e}"),
-                };
+        }
+        counts
+    }
+
+    /// Every loaded resource, of all kinds
+    pub fn all_resources(&self) -> Vec<&dyn FluxResource> {
+        self.kustomizations
+            .iter()
+            .map(|r| r as &dyn FluxResource)
+            .chain(self.helm_releases.iter().map(|r| r as &dyn FluxResource))
+            .chain(self.helm_charts.iter().map(|r| r as &dyn FluxResource))
+            .chain(self.git_repositories.iter().map(|r| r as &dyn FluxResource))
+            .chain(
+                self.helm_repositories
+                    .iter()
+                    .map(|r| r as &dyn FluxResource),
+            )
+            .collect()
+    }
+
+    /// Labels of all connected clusters, primary first
+    pub fn cluster_labels(&self) -> Vec<String> {
+        self.clients()
+            .into_iter()
+            .map(|c| c.label().to_string())
+            .collect()
+    }
+
+    /// Items of the current tab that pass the cluster, source, status and fuzzy
+    /// filters, in sort order
+    pub fn visible<'a, T: FluxResource>(&self, items: &'a [T]) -> Vec<&'a T> {
+        let search = FuzzyFilter::new(&self.search_query);
+        let source_filter = self.active_source_filter();
+        let status_filter = self.current_status_filter();
+        let mut visible: Vec<&T> = items
+            .iter()
+            .filter(|item| {
+                self.cluster_filter
+                    .as_deref()
+                    .is_none_or(|cluster| item.cluster() == cluster)
+            })
+            .filter(|item| source_filter.is_none_or(|filter| filter.matches(*item)))
+            .filter(|item| status_filter.matches(*item))
+            .filter(|item| search.matches(&item.search_fields()))
+            .collect();
+        self.current_sort().apply(&mut visible);
+        visible
+    }
+
+    /// Rows of the current tab's table: the visible items, under a header per
+    /// source while grouped
+    pub fn rows<'a, T: FluxResource>(&self, items: &'a [T]) -> Vec<TableRow<'a, T>> {
+        let visible = self.visible(items);
+        if self.is_grouped() {
+            group::rows(visible, &self.collapsed_groups, self.is_multi_cluster())
+        } else {
+            visible.into_iter().map(TableRow::Resource).collect()
+        }
+    }
+
+    /// Whether the current tab is grouped under sources
+    pub fn is_grouped(&self) -> bool {
+        self.grouped && self.tab.consumes_sources()
+    }
+
+    /// The selected item of the current tab, None on a group header
+    fn selected_in<'a, T: FluxResource>(&self, items: &'a [T]) -> Option<&'a T> {
+        self.rows(items)
+            .get(self.current_selected())
+            .and_then(TableRow::resource)
+    }
+
+    /// Rows of the current tab, with the resources as trait objects
+    fn current_rows(&self) -> Vec<TableRow<'_, dyn FluxResource>> {
+        fn erase<T: FluxResource + 'static>(
+            rows: Vec<TableRow<'_, T>>,
+        ) -> Vec<TableRow<'_, dyn FluxResource>> {
+            rows.into_iter()
+                .map(|row| match row {
+                    TableRow::Group(group) => TableRow::Group(group),
+                    TableRow::Resource(resource) => {
+                        TableRow::Resource(resource as &dyn FluxResource)
+                    }
+                })
+                .collect()
+        }
+        match self.tab {
+            Tab::Kustomizations => erase(self.rows(&self.kustomizations)),
+            Tab::HelmReleases => erase(self.rows(&self.helm_releases)),
+            Tab::HelmCharts => erase(self.rows(&self.helm_charts)),
+            Tab::GitRepositories => erase(self.rows(&self.git_repositories)),
+            Tab::HelmRepositories => erase(self.rows(&self.helm_repositories)),
+        }
+    }
+
+    /// Select the first row of the current tab matching `predicate`, if any
+    fn select_row(&mut self, predicate: impl Fn(&TableRow<'_, dyn FluxResource>) -> bool) -> bool {
+        let Some(index) = self.current_rows().iter().position(predicate) else {
+            return false;
+        };
+        self.set_current_selected(index);
+        true
+    }
+
+    /// Collapse or expand the source group of the selected row, keeping its
+    /// header selected
+    fn toggle_selected_group(&mut self) {
+        let key = match self.current_rows().get(self.current_selected()) {
+            Some(TableRow::Group(group)) => group.key.clone(),
+            Some(TableRow::Resource(resource)) if self.is_grouped() => group::key(*resource),
+            _ => return,
+        };
+        if !self.collapsed_groups.remove(&key) {
+            self.collapsed_groups.insert(key.clone());
+        }
+        self.select_row(|row| matches!(row, TableRow::Group(group) if group.key == key));
+    }
+
+    /// Group the consumer tabs under their sources, or stop, keeping the
+    /// selected resource selected
+    fn toggle_grouping(&mut self) {
+        let selected = self.get_selected_resource();
+        self.grouped = !self.grouped;
+        let found = selected.is_some_and(|selected| {
+            self.select_row(|row| {
+                row.resource().is_some_and(|resource| {
+                    resource.cluster() == selected.cluster()
+                        && resource.namespace() == selected.namespace()
+                        && resource.name() == selected.name()
+                })
+            })
+        });
+        if !found {
+            self.set_current_selected(0);
+        }
+    }
+
+    /// Source filter, if it applies to the current tab
+    pub fn active_source_filter(&self) -> Option<&SourceFilter> {
+        self.source_filter
+            .as_ref()
+            .filter(|_| self.tab.consumes_sources())
+    }
+
+    /// Scroll the details, YAML, events or dependencies popup, keeping at least its last line in view
+    fn scroll_popup(&mut self, delta: i32) {
+        let (lines, scroll) = match &mut self.popup {
+            Popup::ResourceDetails { resource, scroll } => {
+                let streak = self.failure_streaks.get(resource.as_ref());
+                (
+                    details::line_count(
+                        resource.as_ref(),
+                        streak,
+                        &self.state,
+                        &self.locale,
+                        self.show_metadata,
+                    ),
+                    scroll,
+                )
             }
+            Popup::Yaml {
+                content, scroll, ..
+            } => (content.lines().count(), scroll),
+            Popup::Events { events, scroll, .. } => (events.len(), scroll),
+            Popup::Message {
+                message, scroll, ..
+            } => (message::wrap(message, message::WIDTH).len(), scroll),
+            Popup::Dependencies { nodes, scroll } => (nodes.len(), scroll),
+            Popup::Help { scroll } => (keymap::help_line_count(), scroll),
+            Popup::Usage { scroll } => (self.usage.report(Utc::now()).len(), scroll),
+            Popup::ActionLog { scroll } => (self.action_log.len(), scroll),
+            Popup::Preflight { checks, scroll } => (checks.len(), scroll),
+            Popup::FluxVersion { scroll } => (
+                self.flux_version
+                    .as_ref()
+                    .map_or(0, |version| version.controllers.len() + 2),
+                scroll,
+            ),
+            Popup::Controllers {
+                controllers,
+                scroll,
+            } => (
+                controllers
+                    .iter()
+                    .map(|c| 1 + c.reconcilers.as_ref().map_or(1, Vec::len))
+                    .sum(),
+                scroll,
+            ),
+            Popup::PathBrowser {
+                files,
+                selected,
+                open: true,
+                scroll,
+                ..
+            } => (
+                files
+                    .get(*selected)
+                    .map_or(0, |f| f.content.lines().count()),
+                scroll,
+            ),
+            _ => return,
+        };
+        let max = i32::try_from(lines.saturating_sub(1)).unwrap_or(i32::MAX);
+        *scroll = i32::from(*scroll).saturating_add(delta).clamp(0, max) as u16;
+    }
+
+    /// Download the source artifact of the selected Kustomization and browse
+    /// the manifests at its path
+    ///
+    /// Only GitRepository sources are loaded, so only their artifacts can be found.
+    fn browse_path_selected(&mut self) {
+        if self.tab != Tab::Kustomizations {
+            return;
         }
+        let Some(kustomization) = self.selected_in(&self.kustomizations).cloned() else {
+            return;
+        };
+        let Some(source) = &kustomization.source else {
+            return;
+        };
+        if source.kind != "GitRepository" {
+            self.popup = Popup::Error {
+                message: format!("Browsing {} artifacts is not supported", source.kind),
+            };
+            return;
+        }
+        let url = self
+            .git_repositories
+            .iter()
+            .find(|g| {
+                g.name == source.name
+                    && g.namespace == source.namespace
+                    && g.cluster == kustomization.cluster
+            })
+            .and_then(|g| g.artifact_url.clone());
+        let Some(url) = url else {
+            self.popup = Popup::Error {
+                message: format!(
+                    "GitRepository {}/{} has no artifact yet",
+                    source.namespace, source.name
+                ),
+            };
+            return;
+        };
+        let Some(client) = self.client_for(&kustomization) else {
+            return;
+        };
 
-        Ok(())
+        let title = format!(
+            "{}/{} {}",
+            source.namespace, source.name, kustomization.path
+        );
+        let path = kustomization.path;
+        self.bus.spawn(async move {
+            let download = async {
+                let forward = client.artifact_forward(&url).await?;
+                let tarball = client.download_artifact(&url, forward.as_ref()).await?;
+                Ok::<_, color_eyre::Report>((artifact::manifests(&tarball, &path)?, forward))
+            };
+            match download.await {
+                Ok((files, _)) if files.is_empty() => StateUpdate::popup(Popup::Info {
+                    message: format!("No manifests at {path} in {url}"),
+                }),
+                // The forward is closed once the browser is
+                Ok((files, forward)) => StateUpdate::Popup {
+                    popup: Popup::PathBrowser {
+                        title,
+                        files,
+                        selected: 0,
+                        open: false,
+                        scroll: 0,
+                    },
+                    forwards: forward
+                        .map(|forward| ("artifact".to_string(), forward))
+                        .into_iter()
+                        .collect(),
+                },
+                Err(e) => StateUpdate::popup(Popup::Error {
+                    message: format!("Failed to download the source artifact: {e}"),
+                }),
+            }
+        });
     }
 
-    /// Toggle suspend on the selected resource
-    async fn toggle_suspend_selected(&mut self) -> Result<()> {
-        let selected = self.current_selected();
+    /// List the Helm release revisions of the selected HelmRelease
+    fn show_helm_history_selected(&mut self) {
+        if self.tab != Tab::HelmReleases {
+            return;
+        }
+        let Some(release) = self.selected_in(&self.helm_releases).cloned() else {
+            return;
+        };
+        let Some(client) = self.client_for(&release) else {
+            return;
+        };
+        self.bus.spawn(async move {
+            StateUpdate::popup(
+                match client
+                    .helm_history(&release.storage_namespace, &release.release_name)
+                    .await
+                {
+                    Ok(revisions) => Popup::HelmHistory {
+                        release: Box::new(release),
+                        revisions,
+                        selected: 0,
+                    },
+                    Err(e) => Popup::Error {
+                        message: format!("Failed to read the Helm history: {e}"),
+                    },
+                },
+            )
+        });
+    }
 
-        let (name, namespace, kind, is_suspended) = match self.tab {
-            Tab::Kustomizations => {
-                if let Some(k) = self.kustomizations.get(selected) {
-                    (
-                        k.name.clone(),
-                        k.namespace.clone(),
-                        "kustomization",
-                        k.suspended,
-                    )
-                } else {
+    /// Confirm rolling back to the highlighted revision
+    fn ask_rollback(&mut self) {
+        let Popup::HelmHistory {
+            release,
+            revisions,
+            selected,
+        } = std::mem::take(&mut self.popup)
+        else {
+            return;
+        };
+        let Some(revision) = revisions.get(selected).cloned() else {
+            return;
+        };
+        self.popup = if revision.is_deployed() {
+            Popup::Info {
+                message: format!("Revision {} is already deployed", revision.revision),
+            }
+        } else {
+            Popup::ConfirmRollback { release, revision }
+        };
+    }
+
+    /// Suspend the HelmRelease so helm-controller doesn't upgrade it again,
+    /// then roll the release back with the helm CLI
+    fn confirm_rollback(&mut self) {
+        let Popup::ConfirmRollback { release, revision } = std::mem::take(&mut self.popup) else {
+            return;
+        };
+        let Some(client) = self.client_for(release.as_ref()) else {
+            return;
+        };
+        let backend = self.config.reconcile_backend;
+        self.bus.spawn(async move {
+            let suspend = async {
+                if release.suspended {
                     return Ok(());
                 }
-            }
-            Tab::HelmReleases => {
-                if let Some(h) = self.helm_releases.get(selected) {
-                    (
-                        h.name.clone(),
-                        h.namespace.clone(),
-                        "helmrelease",
-                        h.suspended,
+                reconcile::backend(backend, &client)
+                    .set_suspended(ResourceRef::of(release.as_ref()), true)
+                    .await
+            };
+            let result = match suspend.await {
+                Ok(()) => {
+                    helm::rollback(
+                        &release.release_name,
+                        &release.storage_namespace,
+                        revision.revision,
+                        client.context(),
                     )
-                } else {
-                    return Ok(());
+                    .await
                 }
+                Err(e) => Err(e),
+            };
+            let popup = match &result {
+                Ok(()) => Popup::Info {
+                    message: format!(
+                        "Rolled {} back to revision {} ({}). HelmRelease {}/{} stays \
+                         suspended; resume it with `s` to return to what Git declares.",
+                        release.release_name,
+                        revision.revision,
+                        revision.chart,
+                        release.namespace,
+                        release.name
+                    ),
+                },
+                Err(e) => Popup::Error {
+                    message: format!("Rollback failed: {e}"),
+                },
+            };
+            StateUpdate::Changed {
+                logged: vec![(
+                    format!("Roll back to revision {}", revision.revision),
+                    release,
+                    Outcome::of(&result),
+                )],
+                popup: Some(popup),
             }
-            Tab::HelmCharts => {
-                // HelmCharts cannot be suspended directly
-                return Ok(());
-            }
+        });
+    }
+
+    /// Check the Flux installation of the first cluster
+    ///
+    /// At startup the results are only shown when a check failed, so a
+    /// missing Flux is explained before the tables fill with list errors.
+    pub fn run_preflight(&mut self, show_passing: bool) {
+        let Some(client) = self.clients().first().map(|c| (*c).clone()) else {
+            return;
         };
+        self.bus.spawn(async move {
+            let checks = preflight::run(client.as_ref()).await;
+            if show_passing || checks.iter().any(|check| !check.passed()) {
+                StateUpdate::popup(Popup::Preflight { checks, scroll: 0 })
+            } else {
+                StateUpdate::Idle
+            }
+        });
+    }
 
-        match crate::kubernetes::reconcile::toggle_suspend(&name, &namespace, kind, is_suspended)
-            .await
-        {
-            Ok(_) => {
-                self.refresh_data().await?;
+    /// Read the Flux version of the first cluster for the header, and with
+    /// `show` open the version popup once it is read
+    pub fn fetch_flux_version(&mut self, show: bool) {
+        let Some(client) = self.clients().first().map(|c| (*c).clone()) else {
+            return;
+        };
+        self.bus.spawn(async move {
+            match client.flux_deployments().await {
+                Ok(deployments) => StateUpdate::FluxVersion {
+                    version: FluxVersion::of(&deployments),
+                    show,
+                },
+                Err(e) if show => {
+                    StateUpdate::Failed(format!("Failed to read the Flux version: {e}"))
+                }
+                // The header goes without it
+                Err(_) => StateUpdate::Idle,
             }
-            Err(e) => {
-                self.popup = Popup::Error {
-                    message: format!("Toggle suspend failed: {e}"),
+        });
+    }
+
+    /// Scrape the Flux controllers' metrics into the Controllers popup
+    ///
+    /// The port-forwards stay open while the popup is, so refreshing it only
+    /// scrapes again.
+    fn show_controllers(&mut self) {
+        let Some(client) = self.clients().first().map(|c| (*c).clone()) else {
+            return;
+        };
+        let (mut forwards, scroll) = match &self.popup {
+            Popup::Controllers { scroll, .. } => (std::mem::take(&mut self.forwards), *scroll),
+            _ => (HashMap::new(), 0),
+        };
+
+        self.bus.spawn(async move {
+            let mut held = Vec::new();
+            let mut controllers = Vec::new();
+            for controller in metrics::CONTROLLERS {
+                let forward = match forwards.remove(controller) {
+                    Some(forward) => Ok(forward),
+                    None => client.metrics_forward(controller).await,
+                };
+                let reconcilers = match forward {
+                    Ok(forward) => {
+                        let reconcilers = client.scrape_metrics(&forward).await;
+                        held.push((controller.to_string(), forward));
+                        reconcilers.map_err(|e| e.to_string())
+                    }
+                    Err(e) => Err(e.to_string()),
                 };
+                controllers.push(ControllerMetrics {
+                    controller: controller.to_string(),
+                    reconcilers,
+                });
+            }
+            StateUpdate::Popup {
+                popup: Popup::Controllers {
+                    controllers,
+                    scroll,
+                },
+                forwards: held,
+            }
+        });
+    }
+
+    /// Keep a port-forward open while the current kind of popup is shown
+    fn hold_forward(&mut self, key: String, forward: PortForward) {
+        let kind = std::mem::discriminant(&self.popup);
+        if self.forwards_for != Some(kind) {
+            self.forwards.clear();
+            self.forwards_for = Some(kind);
+        }
+        self.forwards.insert(key, forward);
+    }
+
+    /// Close the port-forwards once their popup is gone
+    fn release_forwards(&mut self) {
+        if self.forwards_for != Some(std::mem::discriminant(&self.popup)) {
+            self.forwards.clear();
+            self.forwards_for = None;
+        }
+    }
+
+    /// Fetch the selected resource's manifest and show it in the YAML popup
+    fn show_yaml_selected(&mut self) {
+        let Some(resource) = self.get_selected_resource() else {
+            return;
+        };
+        let Some(client) = self.client_for(resource.as_ref()) else {
+            return;
+        };
+
+        self.bus.spawn(async move {
+            let manifest = client
+                .get_manifest(resource.kind(), resource.namespace(), resource.name())
+                .await;
+            match manifest {
+                Ok(content) => StateUpdate::popup(Popup::Yaml {
+                    title: format!(
+                        "{} {}/{}",
+                        resource.kind(),
+                        resource.namespace(),
+                        resource.name()
+                    ),
+                    content,
+                    scroll: 0,
+                }),
+                Err(e) => StateUpdate::Failed(format!("Failed to fetch manifest: {e}")),
+            }
+        });
+    }
+
+    /// Fetch Events about the selected resource and show them in a popup
+    fn show_events_selected(&mut self) {
+        let Some(resource) = self.get_selected_resource() else {
+            return;
+        };
+        let Some(client) = self.client_for(resource.as_ref()) else {
+            return;
+        };
+
+        let limit = self.config.buffers.events;
+        self.bus.spawn(async move {
+            let events = client
+                .list_events_for(
+                    resource.kind(),
+                    resource.namespace(),
+                    resource.name(),
+                    limit,
+                )
+                .await;
+            match events {
+                Ok(events) => StateUpdate::popup(Popup::Events {
+                    title: format!(
+                        "Events: {} {}/{}",
+                        resource.kind(),
+                        resource.namespace(),
+                        resource.name()
+                    ),
+                    events,
+                    scroll: 0,
+                }),
+                Err(e) => StateUpdate::Failed(format!("Failed to list events: {e}")),
             }
+        });
+    }
+
+    /// Client for the cluster a resource was listed from
+    ///
+    /// Shows an error popup when that cluster isn't connected.
+    fn client_for(&mut self, resource: &dyn FluxResource) -> Option<Arc<dyn FluxApi>> {
+        let client = self
+            .clients()
+            .into_iter()
+            .find(|c| c.label() == resource.cluster())
+            .cloned();
+        if client.is_none() {
+            self.popup = Popup::Error {
+                message: format!("Not connected to cluster {}", resource.cluster()),
+            };
+        }
+        client
+    }
+
+    /// Select what the selected resource is built from: a HelmRelease's
+    /// HelmChart, or the source of a Kustomization or HelmChart
+    fn jump_upstream(&mut self) {
+        let Some(resource) = self.get_selected_resource() else {
+            return;
+        };
+        let (upstream, missing) = match self.tab {
+            Tab::HelmReleases => (trace::chart_ref(resource.as_ref()), "HelmChart"),
+            Tab::Kustomizations => (resource.source().cloned(), "source"),
+            Tab::HelmCharts => (
+                self.selected_in(&self.helm_charts)
+                    .and_then(trace::chart_source),
+                "source",
+            ),
+            // Sources are where the chain starts
+            Tab::GitRepositories | Tab::HelmRepositories => return,
+        };
+        let Some(upstream) = upstream else {
+            self.popup = Popup::Error {
+                message: format!(
+                    "{} {}/{} has no {missing}",
+                    resource.kind(),
+                    resource.namespace(),
+                    resource.name()
+                ),
+            };
+            return;
+        };
+        let Some(tab) = Tab::of_kind(&upstream.kind) else {
+            self.popup = Popup::Error {
+                message: format!(
+                    "{} {}/{} has no tab to show it on",
+                    upstream.kind, upstream.namespace, upstream.name
+                ),
+            };
+            return;
+        };
+        self.jump_to(tab, resource.cluster(), &upstream);
+    }
+
+    /// Switch to a tab and select a resource on it
+    ///
+    /// The search is cleared so the resource is listed. When it isn't
+    /// loaded, an error is shown and the current tab stays.
+    pub fn jump_to(&mut self, tab: Tab, cluster: &str, target: &SourceRef) {
+        let is_target = |resource: &dyn FluxResource| {
+            resource.cluster() == cluster
+                && resource.kind() == target.kind
+                && resource.namespace() == target.namespace
+                && resource.name() == target.name
+        };
+        let Some(key) = self
+            .all_resources()
+            .into_iter()
+            .find(|resource| is_target(*resource))
+            .map(group::key)
+        else {
+            self.popup = Popup::Error {
+                message: format!(
+                    "{} {}/{} is not loaded",
+                    target.kind, target.namespace, target.name
+                ),
+            };
+            return;
+        };
+
+        self.clear_search();
+        self.tab = tab;
+        self.collapsed_groups.remove(&key);
+        if !self.select_row(|row| row.resource().is_some_and(is_target)) {
+            // Hidden by the tab's status or source filter
+            self.status_filter[self.tab_index()] = StatusFilter::All;
+            self.source_filter = None;
+            self.select_row(|row| row.resource().is_some_and(is_target));
+        }
+    }
+
+    /// Resources of the shown clusters whose name matches a query of the
+    /// search across tabs, best match first
+    pub fn global_matches(&self, query: &str) -> Vec<&dyn FluxResource> {
+        let resources = self
+            .all_resources()
+            .into_iter()
+            .filter(|resource| {
+                self.cluster_filter
+                    .as_deref()
+                    .is_none_or(|cluster| resource.cluster() == cluster)
+            })
+            .collect();
+        finder::matches(resources, query)
+    }
+
+    /// Go to the tab and row of the highlighted match of the search across tabs
+    fn go_to_match(&mut self) {
+        let Popup::GlobalSearch { query, selected } = &self.popup else {
+            return;
+        };
+        let Some((tab, cluster, target)) =
+            self.global_matches(query)
+                .get(*selected)
+                .and_then(|resource| {
+                    let target = SourceRef {
+                        kind: resource.kind().to_string(),
+                        name: resource.name().to_string(),
+                        namespace: resource.namespace().to_string(),
+                    };
+                    Some((
+                        Tab::of_kind(resource.kind())?,
+                        resource.cluster().to_string(),
+                        target,
+                    ))
+                })
+        else {
+            return;
+        };
+        self.popup = Popup::None;
+        self.jump_to(tab, &cluster, &target);
+    }
+
+    /// Show consumers of the selected source on the tab listing them
+    fn filter_by_selected_source(&mut self) {
+        let consumers = match self.tab {
+            Tab::GitRepositories => Tab::Kustomizations,
+            Tab::HelmRepositories | Tab::HelmCharts => Tab::HelmReleases,
+            Tab::Kustomizations | Tab::HelmReleases => return,
+        };
+        let Some(source) = self.get_selected_resource() else {
+            return;
+        };
+        self.show_consumers(consumers, SourceFilter::of(source.as_ref()));
+    }
+
+    /// Switch to a consumer tab, filtered to consumers of a source
+    fn show_consumers(&mut self, tab: Tab, filter: SourceFilter) {
+        self.source_filter = Some(filter);
+        self.clear_search();
+        self.tab = tab;
+        self.set_current_selected(0);
+    }
+
+    /// Get the sort state of the current tab
+    pub fn current_sort(&self) -> SortState {
+        self.sort[self.tab_index()]
+    }
+
+    /// Status filter of the current tab
+    pub fn current_status_filter(&self) -> StatusFilter {
+        self.status_filter[self.tab_index()]
+    }
+
+    /// Stop filtering; the filter only applies to the tab it was typed in
+    fn clear_search(&mut self) {
+        self.search_query.clear();
+        self.search_editing = false;
+    }
+
+    /// Store the note being edited and persist the state file
+    fn save_note(&mut self) {
+        let Popup::EditNote { resource, text } = std::mem::take(&mut self.popup) else {
+            return;
+        };
+        self.state.set_note(resource.as_ref(), &text, Utc::now());
+        self.save_state();
+    }
+
+    /// Show the onboarding tour from its first step
+    pub fn start_tour(&mut self) {
+        self.popup = Popup::Tour { step: 0 };
+    }
+
+    /// Close the tour and remember not to show it on the next start
+    fn end_tour(&mut self) {
+        self.popup = Popup::None;
+        if !self.state.tour_done {
+            self.state.tour_done = true;
+            self.save_state();
+        }
+    }
+
+    /// Write the state file, if this session persists one
+    fn save_state(&mut self) {
+        let Some(path) = &self.state_path else {
+            return;
+        };
+        if let Err(e) = self.state.save(path) {
+            self.record_error(format!("Failed to save state file: {e}"));
+        }
+    }
+
+    /// Set the status bar error and keep it in the recent error log
+    fn record_error(&mut self, message: String) {
+        self.recent_errors.push((Utc::now(), message.clone()));
+        self.last_error = Some(message);
+    }
+
+    /// Get the current tab index
+    pub fn tab_index(&self) -> usize {
+        match self.tab {
+            Tab::Kustomizations => 0,
+            Tab::HelmReleases => 1,
+            Tab::HelmCharts => 2,
+            Tab::GitRepositories => 3,
+            Tab::HelmRepositories => 4,
         }
+    }
+
+    /// Get the selected index for the current tab
+    pub fn current_selected(&self) -> usize {
+        self.selected[self.tab_index()]
+    }
+
+    /// First row shown in the current tab's table
+    pub fn current_scroll(&self) -> usize {
+        self.scroll[self.tab_index()]
+    }
+
+    /// Scroll the current tab's table just far enough to show the selection
+    /// within `rows` visible rows
+    ///
+    /// `rows` is also what paging moves by.
+    pub fn scroll_to_selection(&mut self, rows: usize) {
+        self.page_rows = rows.max(1);
+        let selected = self.current_selected();
+        let offset = &mut self.scroll[self.tab_index()];
+        *offset = resource_table::scroll_offset(*offset, selected, rows);
+    }
+
+    /// Set the selected index for the current tab
+    pub fn set_current_selected(&mut self, index: usize) {
+        self.selected[self.tab_index()] = index;
+    }
+
+    /// Move the selection by `rows`, stopping at the first and last row
+    fn move_selection(&mut self, rows: isize) {
+        let last = self.current_item_count().saturating_sub(1);
+        let selected = self.current_selected().saturating_add_signed(rows);
+        self.set_current_selected(selected.min(last));
+    }
+
+    /// Get the number of rows in the current tab, group headers included
+    pub fn current_item_count(&self) -> usize {
+        self.current_rows().len()
+    }
+
+    /// Update the application state based on an action
+    ///
+    /// Never waits: work that talks to a cluster runs on the bus and is
+    /// applied by [`App::apply`] once done.
+    pub fn update(&mut self, action: Action) -> Result<()> {
+        let mut chain = std::mem::take(&mut self.middleware);
+        let action = chain
+            .iter_mut()
+            .try_fold(action, |action, middleware| middleware.before(self, action));
+        self.middleware = chain;
+        let Some(action) = action else {
+            return Ok(());
+        };
+        if !matches!(action, Action::Complete | Action::CompleteBack) {
+            self.completion = None;
+        }
+        // A count applies to the action right after it
+        let count = match action {
+            Action::CountDigit(_) => None,
+            _ => self.count.take(),
+        };
+        let times = count.unwrap_or(1) as isize;
+        match action {
+            Action::Quit => {} // Handled in main loop
+            Action::NextTab => {
+                self.clear_search();
+                self.tab = self.tab.next();
+            }
+            Action::PreviousTab => {
+                self.clear_search();
+                self.tab = self.tab.previous();
+            }
+            Action::ClickTab(tab) => {
+                if tab != self.tab {
+                    self.clear_search();
+                    self.tab = tab;
+                }
+            }
+            Action::ClickRow(index) => {
+                if index < self.current_item_count() {
+                    self.set_current_selected(index);
+                }
+            }
+            Action::Up => self.move_selection(-times),
+            Action::Down => self.move_selection(times),
+            Action::PageUp => self.move_selection(-times * self.page_rows as isize),
+            Action::PageDown => self.move_selection(times * self.page_rows as isize),
+            Action::HalfPageUp => {
+                self.move_selection(-times * (self.page_rows / 2).max(1) as isize)
+            }
+            Action::HalfPageDown => {
+                self.move_selection(times * (self.page_rows / 2).max(1) as isize)
+            }
+            Action::CountDigit(digit) => {
+                let count = self.count.unwrap_or(0) * 10 + digit as usize;
+                self.count = Some(count.min(MAX_COUNT));
+            }
+            Action::Top => {
+                self.set_current_selected(0);
+            }
+            Action::Bottom => {
+                // Like vim, a count goes to that row instead
+                let last = self.current_item_count().saturating_sub(1);
+                let row = count.map_or(last, |row| row.saturating_sub(1).min(last));
+                self.set_current_selected(row);
+            }
+            Action::Select => {
+                // View details of selected resource, or fold a group header
+                if let Some(resource) = self.get_selected_resource() {
+                    self.popup = Popup::ResourceDetails {
+                        resource,
+                        scroll: 0,
+                    };
+                } else {
+                    self.toggle_selected_group();
+                }
+            }
+            Action::ToggleGrouping => self.toggle_grouping(),
+            Action::ToggleGroup => self.toggle_selected_group(),
+            Action::Reconcile => {
+                self.reconcile_selected(Mode::Plain, false);
+            }
+            Action::ReconcileWithSource => {
+                self.reconcile_selected(Mode::WithSource, false);
+            }
+            // Only HelmReleases have a release to force an upgrade of
+            Action::ForceReconcile => {
+                if self.tab == Tab::HelmReleases {
+                    self.reconcile_selected(Mode::Force, false);
+                }
+            }
+            Action::ResumeAndReconcile => {
+                if self.tab.info().suspendable {
+                    self.reconcile_selected(Mode::Plain, true);
+                }
+            }
+            Action::FilterNamespace => {
+                self.popup = Popup::NamespaceFilter {
+                    namespaces: self.namespaces.clone(),
+                    query: String::new(),
+                    selected: 0,
+                };
+            }
+            Action::NamespaceInput(c) => {
+                if let Popup::NamespaceFilter {
+                    query, selected, ..
+                } = &mut self.popup
+                {
+                    query.push(c);
+                    *selected = 0;
+                }
+            }
+            Action::NamespaceBackspace => {
+                if let Popup::NamespaceFilter {
+                    query, selected, ..
+                } = &mut self.popup
+                {
+                    query.pop();
+                    *selected = 0;
+                }
+            }
+            Action::PopupUp => {
+                if let Popup::NamespaceFilter { selected, .. }
+                | Popup::GlobalSearch { selected, .. } = &mut self.popup
+                {
+                    *selected = selected.saturating_sub(1);
+                }
+            }
+            Action::PopupDown => {
+                let choices = match &self.popup {
+                    Popup::NamespaceFilter {
+                        namespaces, query, ..
+                    } => namespace_choices(namespaces, query).len(),
+                    Popup::GlobalSearch { query, .. } => self.global_matches(query).len(),
+                    _ => 0,
+                };
+                if let Popup::NamespaceFilter { selected, .. }
+                | Popup::GlobalSearch { selected, .. } = &mut self.popup
+                {
+                    *selected = (*selected + 1).min(choices.saturating_sub(1));
+                }
+            }
+            Action::SetNamespace(ns) => {
+                self.namespace_filter = ns;
+                self.popup = Popup::None;
+                self.start_refresh();
+            }
+            Action::SwitchContext if self.snapshot_taken.is_some() => {
+                self.popup = Popup::Error {
+                    message: "Showing a snapshot: start flux-tui without --snapshot to \
+                              connect to a cluster"
+                        .to_string(),
+                };
+            }
+            Action::SwitchContext => {
+                self.open_context_switcher();
+            }
+            Action::HighlightItem(index) => {
+                if let Popup::ContextSwitcher { selected, .. }
+                | Popup::ClusterFilter { selected, .. }
+                | Popup::PathBrowser { selected, .. }
+                | Popup::HelmHistory { selected, .. } = &mut self.popup
+                {
+                    *selected = index;
+                }
+            }
+            Action::FilterCluster => {
+                if self.is_multi_cluster() {
+                    let mut clusters = vec!["All clusters".to_string()];
+                    clusters.extend(self.cluster_labels());
+                    self.popup = Popup::ClusterFilter {
+                        clusters,
+                        selected: 0,
+                    };
+                }
+            }
+            Action::SetClusterFilter(cluster) => {
+                self.cluster_filter = cluster;
+                self.selected = [0; 5];
+                self.popup = Popup::None;
+            }
+            Action::SetContext(context) => {
+                self.switch_context(context);
+            }
+            Action::CycleSort => {
+                self.sort[self.tab_index()].cycle();
+                self.set_current_selected(0);
+            }
+            Action::ReverseSort => {
+                let sort = &mut self.sort[self.tab_index()];
+                sort.reversed = !sort.reversed;
+                self.set_current_selected(0);
+            }
+            Action::CycleStatusFilter => {
+                self.status_filter[self.tab_index()].cycle();
+                self.set_current_selected(0);
+            }
+            Action::StartSearch => {
+                self.search_editing = true;
+            }
+            Action::SearchInput(c) => {
+                self.search_query.push(c);
+                self.set_current_selected(0);
+            }
+            Action::SearchBackspace => {
+                self.search_query.pop();
+                self.set_current_selected(0);
+            }
+            Action::ConfirmSearch => {
+                self.search_editing = false;
+            }
+            Action::ClearSearch => {
+                self.clear_search();
+                self.set_current_selected(0);
+            }
+            Action::FilterBySource => {
+                self.filter_by_selected_source();
+            }
+            Action::ClearSourceFilter => {
+                self.source_filter = None;
+                self.set_current_selected(0);
+            }
+            Action::ShowYaml => {
+                self.show_yaml_selected();
+            }
+            Action::ShowEvents => {
+                self.show_events_selected();
+            }
+            Action::ShowMessage => {
+                if let Some(resource) = self.get_selected_resource() {
+                    self.popup = Popup::Message {
+                        title: format!(
+                            "{} {}/{}",
+                            resource.kind(),
+                            resource.namespace(),
+                            resource.name()
+                        ),
+                        message: resource.status_message().to_string(),
+                        scroll: 0,
+                    };
+                }
+            }
+            Action::ShowDependencies => {
+                let kustomizations: Vec<&Kustomization> = self
+                    .kustomizations
+                    .iter()
+                    .filter(|k| {
+                        self.cluster_filter
+                            .as_deref()
+                            .is_none_or(|cluster| k.cluster == cluster)
+                    })
+                    .collect();
+                self.popup = Popup::Dependencies {
+                    nodes: dependency_tree(&kustomizations),
+                    scroll: 0,
+                };
+            }
+            Action::ShowHelp => {
+                self.popup = Popup::Help { scroll: 0 };
+            }
+            Action::ShowUsage => {
+                self.popup = Popup::Usage { scroll: 0 };
+            }
+            Action::ShowPreflight => {
+                self.run_preflight(true);
+            }
+            Action::ShowFluxVersion => {
+                self.fetch_flux_version(true);
+            }
+            Action::ShowActionLog => {
+                self.popup = Popup::ActionLog { scroll: 0 };
+            }
+            Action::WriteActionLog => {
+                self.popup = match self.action_log.write() {
+                    Ok(path) => Popup::Info {
+                        message: format!("Action log written to {}", path.display()),
+                    },
+                    Err(e) => Popup::Error {
+                        message: format!("Failed to write the action log: {e}"),
+                    },
+                };
+            }
+            Action::BrowsePath => {
+                self.browse_path_selected();
+            }
+            Action::OpenFile => {
+                if let Popup::PathBrowser { open, scroll, .. } = &mut self.popup {
+                    *open = true;
+                    *scroll = 0;
+                }
+            }
+            Action::CloseFile => {
+                if let Popup::PathBrowser { open, .. } = &mut self.popup {
+                    *open = false;
+                }
+            }
+            Action::ToggleActivity => {
+                self.show_activity = !self.show_activity;
+            }
+            Action::ToggleDetailsPane => {
+                self.show_details = !self.show_details;
+            }
+            Action::ToggleMetadata => {
+                self.show_metadata = !self.show_metadata;
+                self.scroll_popup(0);
+            }
+            Action::ShowControllers => {
+                self.show_controllers();
+            }
+            Action::ShowHelmHistory => {
+                self.show_helm_history_selected();
+            }
+            Action::Rollback => {
+                self.ask_rollback();
+            }
+            Action::Jump => {
+                self.jump_upstream();
+            }
+            Action::Trace => {
+                if let Some(resource) = self.get_selected_resource() {
+                    let sources = Sources {
+                        helm_charts: &self.helm_charts,
+                        git_repositories: &self.git_repositories,
+                        helm_repositories: &self.helm_repositories,
+                    };
+                    self.popup = Popup::Trace {
+                        hops: sources.trace(resource.as_ref()),
+                    };
+                }
+            }
+            Action::EditNote => {
+                if let Some(resource) = self.get_selected_resource() {
+                    let text = self
+                        .state
+                        .note(resource.as_ref())
+                        .map(|note| note.text.clone())
+                        .unwrap_or_default();
+                    self.popup = Popup::EditNote { resource, text };
+                }
+            }
+            Action::NoteInput(c) => {
+                if let Popup::EditNote { text, .. } = &mut self.popup {
+                    text.push(c);
+                }
+            }
+            Action::NoteBackspace => {
+                if let Popup::EditNote { text, .. } = &mut self.popup {
+                    text.pop();
+                }
+            }
+            Action::SaveNote => {
+                self.save_note();
+            }
+            Action::OpenCommand => {
+                self.popup = Popup::Command {
+                    text: String::new(),
+                };
+            }
+            Action::CommandInput(c) => {
+                if let Popup::Command { text } = &mut self.popup {
+                    text.push(c);
+                }
+            }
+            Action::CommandBackspace => {
+                if let Popup::Command { text } = &mut self.popup {
+                    text.pop();
+                }
+            }
+            Action::RunCommand => {
+                self.run_command();
+            }
+            Action::StartGlobalSearch => {
+                self.popup = Popup::GlobalSearch {
+                    query: String::new(),
+                    selected: 0,
+                };
+            }
+            Action::GlobalSearchInput(c) => {
+                if let Popup::GlobalSearch { query, selected } = &mut self.popup {
+                    query.push(c);
+                    *selected = 0;
+                }
+            }
+            Action::GlobalSearchBackspace => {
+                if let Popup::GlobalSearch { query, selected } = &mut self.popup {
+                    query.pop();
+                    *selected = 0;
+                }
+            }
+            Action::GoToMatch => {
+                self.go_to_match();
+            }
+            Action::TourNext => {
+                if let Popup::Tour { step } = &mut self.popup {
+                    if *step + 1 < tour::STEPS.len() {
+                        *step += 1;
+                    } else {
+                        self.end_tour();
+                    }
+                }
+            }
+            Action::TourBack => {
+                if let Popup::Tour { step } = &mut self.popup {
+                    *step = step.saturating_sub(1);
+                }
+            }
+            Action::EndTour => {
+                self.end_tour();
+            }
+            Action::Complete => {
+                self.complete(false);
+            }
+            Action::CompleteBack => {
+                self.complete(true);
+            }
+            Action::Acknowledge => {
+                if let Some(resource) = self.get_selected_resource() {
+                    let now = Utc::now();
+                    let until = now + Duration::minutes(self.config.snooze_minutes as i64);
+                    if self.state.toggle_ack(resource.as_ref(), until, now) {
+                        self.failure_streaks.forget(resource.as_ref());
+                    }
+                    self.save_state();
+                }
+            }
+            Action::Scroll(delta) => {
+                self.scroll_popup(delta);
+            }
+            Action::ClosePopup => {
+                self.popup = Popup::None;
+            }
+            Action::Refresh => {
+                self.start_refresh();
+            }
+            Action::ToggleSuspend => {
+                self.toggle_suspend_selected();
+            }
+            Action::ConfirmSuspend => match self.popup {
+                Popup::ConfirmBulkSuspend { .. } => self.confirm_bulk_suspend(),
+                Popup::ConfirmRefOverride { .. } => self.confirm_ref_override(),
+                Popup::ConfirmRollback { .. } => self.confirm_rollback(),
+                _ => self.confirm_suspend(),
+            },
+            Action::ExportSnapshot => {
+                self.popup = match Snapshot::of(self, Utc::now()).write() {
+                    Ok(path) => Popup::Info {
+                        message: format!(
+                            "Snapshot written to {} (open it with --snapshot)",
+                            path.display()
+                        ),
+                    },
+                    Err(e) => Popup::Error {
+                        message: format!("Failed to write the snapshot: {e}"),
+                    },
+                };
+            }
+            Action::WriteDiagnostics => {
+                self.popup = match diagnostics::write_report(&diagnostics::report(self)) {
+                    Ok(path) => Popup::Info {
+                        message: format!("Diagnostic report written to {}", path.display()),
+                    },
+                    Err(e) => Popup::Error {
+                        message: format!("Failed to write diagnostic report: {e}"),
+                    },
+                };
+            }
+            Action::Noop => {}
+        }
+
+        self.release_forwards();
+        Ok(())
+    }
+
+    /// Open the context switcher with the active context highlighted
+    fn open_context_switcher(&mut self) {
+        match list_contexts(self.config.kubeconfig.as_deref()) {
+            Ok((contexts, _)) if contexts.is_empty() => {
+                self.popup = Popup::Error {
+                    message: "The kubeconfig has no contexts".to_string(),
+                };
+            }
+            Ok((contexts, current)) => {
+                let active = self.config.context.clone().or(current);
+                let selected = contexts
+                    .iter()
+                    .position(|c| Some(c) == active.as_ref())
+                    .unwrap_or(0);
+                self.popup = Popup::ContextSwitcher { contexts, selected };
+            }
+            Err(e) => {
+                self.popup = Popup::Error {
+                    message: format!("Failed to read kubeconfig: {e}"),
+                };
+            }
+        }
+    }
+
+    /// Build a client for another context in the background
+    fn switch_context(&mut self, context: String) {
+        let mut config = self.config.clone();
+        config.context = Some(context.clone());
+        config.contexts.clear();
+
+        self.bus.spawn(async move {
+            match KubeClient::new(&config).await {
+                Ok(client) => StateUpdate::Connected {
+                    context,
+                    config: Box::new(config),
+                    client: Arc::new(client),
+                },
+                Err(e) => StateUpdate::Failed(format!("Failed to switch context: {e}")),
+            }
+        });
+    }
+
+    /// Switch to the client built for another context and reload all data
+    ///
+    /// The current resources, selections and filters are kept for this
+    /// context. Switching back to a context seen earlier restores its view
+    /// right away and leaves the update to the next auto-refresh.
+    fn connected(&mut self, context: String, config: Config, client: Arc<dyn FluxApi>) {
+        // Aggregated multi-cluster views aren't tied to a single context
+        if self.other_clients.is_empty() {
+            if let Some(previous) = self.active_context() {
+                let view = self.stash_view();
+                self.saved_views.insert(previous, view);
+            }
+        }
+
+        self.cluster_name = client.cluster_name().to_string();
+        self.api_log = client.api_log();
+        self.client = client;
+        self.config = config;
+        self.environment = self.match_environment();
+
+        let saved = self.saved_views.remove(&context);
+        let restored = saved.is_some();
+        self.restore_view(saved.unwrap_or_else(|| ClusterView {
+            timelines: StatusTimelines::new(self.config.buffers.history),
+            ..Default::default()
+        }));
+        self.other_clients.clear();
+        self.cluster_filter = None;
+        self.clear_search();
+        self.last_error = None;
+        self.storm = None;
+        self.popup = Popup::None;
+        self.flux_version = None;
+        self.fetch_flux_version(false);
+
+        if !restored {
+            self.start_refresh();
+        }
+    }
+
+    /// Name of the context currently shown (None in multi-cluster mode
+    /// or when the kubeconfig can't be read)
+    fn active_context(&self) -> Option<String> {
+        self.config.context.clone().or_else(|| {
+            list_contexts(self.config.kubeconfig.as_deref())
+                .ok()
+                .and_then(|(_, current)| current)
+        })
+    }
+
+    /// Rebuild the clients after the kubeconfig changed, keeping all
+    /// resources and UI state
+    ///
+    /// Connects in the background, then refreshes. On failure the previous
+    /// clients stay in use and the error is recorded.
+    pub fn reload_clients(&mut self) {
+        let config = self.config.clone();
+        self.bus.spawn(async move {
+            StateUpdate::Reloaded(connect(&config).await.map_err(|e| e.to_string()))
+        });
+    }
+
+    /// Rebuild the clients after a rejected refresh, for the retry
+    pub fn reauthenticate(&mut self) {
+        self.reauth = Reauth::Reloading;
+        self.reload_clients();
+    }
+
+    /// Switch to rebuilt clients and refresh with them
+    fn reloaded(&mut self, result: Result<Clients, String>) {
+        match result {
+            Ok((client, other_clients)) => {
+                self.cluster_name = client.cluster_name().to_string();
+                self.api_log = client.api_log();
+                self.client = client;
+                self.other_clients = other_clients;
+                self.environment = self.match_environment();
+            }
+            Err(e) => {
+                self.recent_errors
+                    .push((Utc::now(), format!("Failed to reload the kubeconfig: {e}")));
+            }
+        }
+        if self.reauth == Reauth::Reloading {
+            self.reauth = Reauth::Retried;
+        }
+        self.start_refresh();
+    }
+
+    /// Environment banner matching the shown context
+    ///
+    /// In multi-cluster mode any of the contexts counts, so a production
+    /// cluster among them still shows its banner. In-cluster, without a
+    /// kubeconfig, the cluster name is matched instead.
+    fn match_environment(&self) -> Option<Environment> {
+        let contexts = if self.config.contexts.is_empty() {
+            vec![self
+                .active_context()
+                .unwrap_or_else(|| self.cluster_name.clone())]
+        } else {
+            self.config.contexts.clone()
+        };
+        self.config
+            .environment(contexts.iter().map(String::as_str))
+            .cloned()
+    }
+
+    /// Move the per-context resources and UI state out of the app
+    fn stash_view(&mut self) -> ClusterView {
+        ClusterView {
+            kustomizations: std::mem::take(&mut self.kustomizations),
+            helm_releases: std::mem::take(&mut self.helm_releases),
+            helm_charts: std::mem::take(&mut self.helm_charts),
+            git_repositories: std::mem::take(&mut self.git_repositories),
+            helm_repositories: std::mem::take(&mut self.helm_repositories),
+            namespaces: std::mem::take(&mut self.namespaces),
+            selected: std::mem::take(&mut self.selected),
+            sort: std::mem::take(&mut self.sort),
+            status_filter: std::mem::take(&mut self.status_filter),
+            namespace_filter: self.namespace_filter.take(),
+            source_filter: self.source_filter.take(),
+            failure_streaks: std::mem::take(&mut self.failure_streaks),
+            timelines: std::mem::take(&mut self.timelines),
+            consumers: std::mem::take(&mut self.consumers),
+        }
+    }
+
+    /// Replace the per-context resources and UI state with a stashed view
+    fn restore_view(&mut self, view: ClusterView) {
+        self.kustomizations = view.kustomizations;
+        self.helm_releases = view.helm_releases;
+        self.helm_charts = view.helm_charts;
+        self.git_repositories = view.git_repositories;
+        self.helm_repositories = view.helm_repositories;
+        self.namespaces = view.namespaces;
+        self.selected = view.selected;
+        self.sort = view.sort;
+        self.status_filter = view.status_filter;
+        self.namespace_filter = view.namespace_filter;
+        self.source_filter = view.source_filter;
+        self.failure_streaks = view.failure_streaks;
+        self.timelines = view.timelines;
+        self.consumers = view.consumers;
+    }
+
+    /// Get the currently selected resource (as a trait object)
+    pub fn get_selected_resource(&self) -> Option<Box<dyn FluxResource>> {
+        match self.tab {
+            Tab::Kustomizations => self
+                .selected_in(&self.kustomizations)
+                .map(|k| Box::new(k.clone()) as Box<dyn FluxResource>),
+            Tab::HelmReleases => self
+                .selected_in(&self.helm_releases)
+                .map(|h| Box::new(h.clone()) as Box<dyn FluxResource>),
+            Tab::HelmCharts => self
+                .selected_in(&self.helm_charts)
+                .map(|h| Box::new(h.clone()) as Box<dyn FluxResource>),
+            Tab::GitRepositories => self
+                .selected_in(&self.git_repositories)
+                .map(|g| Box::new(g.clone()) as Box<dyn FluxResource>),
+            Tab::HelmRepositories => self
+                .selected_in(&self.helm_repositories)
+                .map(|h| Box::new(h.clone()) as Box<dyn FluxResource>),
+        }
+    }
+
+    /// Reconcile the selected resource, with `resume` resuming it first if
+    /// it is suspended
+    fn reconcile_selected(&mut self, mode: Mode, resume: bool) {
+        let Some(resource) = self.get_selected_resource() else {
+            return;
+        };
+        let resume = resume && resource.is_suspended();
+        let Some(client) = self.client_for(resource.as_ref()) else {
+            return;
+        };
+        let target = reconcile_progress::Target {
+            kind: resource.kind().to_string(),
+            name: resource.name().to_string(),
+            namespace: resource.namespace().to_string(),
+            source: resource.source().cloned(),
+            mode,
+            backend: self.config.reconcile_backend,
+            resume,
+        };
+
+        let action = match mode {
+            _ if resume => "Resume and reconcile",
+            Mode::Plain => "Reconcile",
+            Mode::WithSource => "Reconcile with source",
+            Mode::Force => "Force reconcile",
+        };
+        self.action_log
+            .record(Utc::now(), action, resource.as_ref(), Outcome::Started);
+        self.popup = Popup::Reconciling {
+            name: target.name.clone(),
+            namespace: target.namespace.clone(),
+            progress: ReconcileProgress::new(Utc::now()),
+        };
+        self.reconcile_updates = Some(reconcile_progress::watch(client, target));
+    }
+
+    /// Whether a reconcile is reporting progress to the Reconciling popup
+    pub fn is_reconciling(&self) -> bool {
+        self.reconcile_updates.is_some()
+    }
+
+    /// Apply progress reported by the running reconcile
+    ///
+    /// Returns true once it finished, so the caller can refresh the data.
+    /// Closing the popup stops the progress updates.
+    pub fn poll_reconcile(&mut self) -> bool {
+        let Some(updates) = &mut self.reconcile_updates else {
+            return false;
+        };
+        let Popup::Reconciling { progress, .. } = &mut self.popup else {
+            self.reconcile_updates = None;
+            return false;
+        };
+        while let Ok(update) = updates.try_recv() {
+            match update {
+                ReconcileUpdate::Observed { conditions, events } => {
+                    progress.observe(&conditions, &events);
+                }
+                ReconcileUpdate::Resumed(result) => progress.resumed(Utc::now(), &result),
+                ReconcileUpdate::Done(result) => {
+                    self.action_log.finish_latest(Outcome::of(&result));
+                    if let Err(e) = &result {
+                        self.recent_errors
+                            .push((Utc::now(), format!("Reconcile failed: {e}")));
+                    }
+                    progress.outcome = Some(result);
+                    self.reconcile_updates = None;
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Toggle suspend on the selected resource
+    fn toggle_suspend_selected(&mut self) {
+        if !self.tab.info().suspendable {
+            return;
+        }
+
+        let Some(resource) = self.get_selected_resource() else {
+            return;
+        };
+
+        // Suspending a source freezes everything built from it, so confirm first
+        if self.tab.is_source() && !resource.is_suspended() {
+            let consumers = self.consumers.consumers_of(resource.as_ref()).to_vec();
+            self.popup = Popup::ConfirmSuspend {
+                resource,
+                consumers,
+            };
+            return;
+        }
+
+        self.toggle_suspend(resource);
+    }
+
+    /// Suspend the source awaiting confirmation
+    fn confirm_suspend(&mut self) {
+        let Popup::ConfirmSuspend { resource, .. } = std::mem::take(&mut self.popup) else {
+            return;
+        };
+        self.toggle_suspend(resource);
+    }
+
+    /// Text input being typed into, if any
+    fn input_mut(&mut self) -> Option<&mut String> {
+        match &mut self.popup {
+            Popup::Command { text } | Popup::EditNote { text, .. } => Some(text),
+            Popup::None if self.search_editing => Some(&mut self.search_query),
+            _ => None,
+        }
+    }
+
+    /// Complete the word being typed, or cycle to the next candidate
+    fn complete(&mut self, reverse: bool) {
+        if self.completion.is_none() {
+            let Some(text) = self.input_mut().map(|text| text.clone()) else {
+                return;
+            };
+            let (head, word) = completion::split_word(&text);
+            let resources = self.all_resources();
+            let pool = match &self.popup {
+                Popup::Command { .. } => {
+                    let labels: Vec<_> = resources.iter().map(|r| r.labels()).collect();
+                    completion::command_candidates(head, &labels)
+                }
+                Popup::EditNote { .. } => completion::name_candidates(&resources, &self.namespaces),
+                _ => {
+                    let tab: Vec<_> = resources
+                        .into_iter()
+                        .filter(|r| r.kind() == self.tab.kind())
+                        .collect();
+                    completion::name_candidates(&tab, &self.namespaces)
+                }
+            };
+            self.completion = Completion::new(head, word, pool);
+        }
+        let Some(completion) = &mut self.completion else {
+            return;
+        };
+        let completed = completion.cycle(reverse);
+        if let Some(text) = self.input_mut() {
+            *text = completed;
+        }
+        if self.search_editing {
+            self.set_current_selected(0);
+        }
+    }
+
+    /// Run the command typed into the palette
+    ///
+    /// `suspend -l` only previews the resources it would suspend; they are
+    /// suspended once confirmed. `ref` asks for confirmation too, while
+    /// `ref revert` (back to what Git says) runs right away.
+    fn run_command(&mut self) {
+        let Popup::Command { text } = std::mem::take(&mut self.popup) else {
+            return;
+        };
+        let command = match Command::parse(&text) {
+            Ok(command) => command,
+            Err(message) => {
+                self.popup = Popup::Error { message };
+                return;
+            }
+        };
+        if let Command::Suspend { selector } = command {
+            self.popup = Popup::ConfirmBulkSuspend {
+                resources: self.bulk_suspend_targets(&selector),
+                selector: selector.to_string(),
+            };
+            return;
+        }
+
+        let repository = (self.tab == Tab::GitRepositories)
+            .then(|| self.selected_in(&self.git_repositories).cloned())
+            .flatten();
+        let Some(repository) = repository else {
+            self.popup = Popup::Error {
+                message: format!(
+                    "{} applies to the GitRepository selected on the GitRepositories tab",
+                    command.name()
+                ),
+            };
+            return;
+        };
+        match command {
+            Command::SetRef { reference } => {
+                self.popup = Popup::ConfirmRefOverride {
+                    repository: Box::new(repository),
+                    reference,
+                };
+            }
+            Command::RevertRef => self.revert_ref(repository),
+            Command::Changes { from, to } => self.show_changes(&repository, from, to),
+            Command::Suspend { .. } => {}
+        }
+    }
+
+    /// Ask the Git provider which files changed in a GitRepository between
+    /// two revisions, then list the Kustomizations affected
+    ///
+    /// Without revisions, the current one is compared against the one it had
+    /// before, if it changed while the app was open.
+    fn show_changes(
+        &mut self,
+        repository: &GitRepository,
+        from: Option<String>,
+        to: Option<String>,
+    ) {
+        let name = format!("GitRepository {}/{}", repository.namespace, repository.name);
+        let commit = |revision: &str| changes::commit(revision).to_string();
+        let Some(to) = to.or_else(|| repository.revision.as_deref().map(commit)) else {
+            self.popup = Popup::Error {
+                message: format!("{name} has no artifact yet"),
+            };
+            return;
+        };
+        let Some(from) = from.or_else(|| self.revisions.previous(repository).map(commit)) else {
+            self.popup = Popup::Error {
+                message: format!(
+                    "No earlier revision of {name} was seen this session; \
+                     name one, e.g. changes 1a2b3c4"
+                ),
+            };
+            return;
+        };
+        let remote = match Remote::of_url(&repository.url, &self.config.git_providers) {
+            Ok(remote) => remote,
+            Err(e) => {
+                self.popup = Popup::Error {
+                    message: e.to_string(),
+                };
+                return;
+            }
+        };
+
+        let filter = SourceFilter::of(repository);
+        self.bus.spawn(async move {
+            match remote.changed_paths(&from, &to).await {
+                Ok(paths) => StateUpdate::Changes(SourceFilter {
+                    changes: Some(ChangeSet { from, to, paths }),
+                    ..filter
+                }),
+                Err(e) => StateUpdate::Failed(format!(
+                    "Failed to compare {from}..{to} of {}: {e}",
+                    remote.path
+                )),
+            }
+        });
+    }
+
+    /// Point the GitRepository in the confirmation popup at the new ref
+    ///
+    /// An existing override keeps its original ref, so reverting always
+    /// goes back to what Git says.
+    fn confirm_ref_override(&mut self) {
+        let Popup::ConfirmRefOverride {
+            repository,
+            reference,
+        } = std::mem::take(&mut self.popup)
+        else {
+            return;
+        };
+        let Some(client) = self.client_for(repository.as_ref()) else {
+            return;
+        };
+        self.bus.spawn(async move {
+            let original = match &repository.ref_override {
+                Some(ref_override) => ref_override.original.as_ref(),
+                None => repository.git_ref.as_ref(),
+            };
+            let result = client
+                .override_git_ref(
+                    &repository.namespace,
+                    &repository.name,
+                    &reference,
+                    original,
+                )
+                .await;
+            let popup = match &result {
+                Ok(()) => Popup::Info {
+                    message: format!(
+                        "GitRepository {}/{} now tracks {reference}, diverging from Git. \
+                         Revert with `:ref revert`.",
+                        repository.namespace, repository.name
+                    ),
+                },
+                Err(e) => Popup::Error {
+                    message: format!("Overriding the ref failed: {e}"),
+                },
+            };
+            StateUpdate::Changed {
+                logged: vec![(
+                    format!("Override ref to {reference} on"),
+                    repository,
+                    Outcome::of(&result),
+                )],
+                popup: Some(popup),
+            }
+        });
+    }
+
+    /// Restore the ref a GitRepository had before it was overridden
+    fn revert_ref(&mut self, repository: GitRepository) {
+        let Some(ref_override) = repository.ref_override.clone() else {
+            self.popup = Popup::Error {
+                message: format!(
+                    "GitRepository {}/{} has no ref override to revert",
+                    repository.namespace, repository.name
+                ),
+            };
+            return;
+        };
+        let Some(client) = self.client_for(&repository) else {
+            return;
+        };
+        self.bus.spawn(async move {
+            let result = client
+                .revert_git_ref(
+                    &repository.namespace,
+                    &repository.name,
+                    ref_override.original.as_ref(),
+                )
+                .await;
+            let popup = result.as_ref().err().map(|e| Popup::Error {
+                message: format!("Reverting the ref failed: {e}"),
+            });
+            StateUpdate::Changed {
+                logged: vec![(
+                    "Revert ref override on".to_string(),
+                    Box::new(repository),
+                    Outcome::of(&result),
+                )],
+                popup,
+            }
+        });
+    }
+
+    /// Kustomizations and HelmReleases matching a label selector that are not
+    /// suspended yet, within the cluster filter
+    fn bulk_suspend_targets(&self, selector: &LabelSelector) -> Vec<Box<dyn FluxResource>> {
+        let kustomizations = self
+            .kustomizations
+            .iter()
+            .map(|k| Box::new(k.clone()) as Box<dyn FluxResource>);
+        let helm_releases = self
+            .helm_releases
+            .iter()
+            .map(|h| Box::new(h.clone()) as Box<dyn FluxResource>);
+        kustomizations
+            .chain(helm_releases)
+            .filter(|resource| {
+                self.cluster_filter
+                    .as_deref()
+                    .is_none_or(|cluster| resource.cluster() == cluster)
+            })
+            .filter(|resource| !resource.is_suspended())
+            .filter(|resource| selector.matches(resource.labels()))
+            .collect()
+    }
+
+    /// Suspend every resource in the bulk suspend preview, one after another
+    fn confirm_bulk_suspend(&mut self) {
+        let Popup::ConfirmBulkSuspend { resources, .. } = std::mem::take(&mut self.popup) else {
+            return;
+        };
+        if resources.is_empty() {
+            return;
+        }
+
+        let clients: Vec<Arc<dyn FluxApi>> = self.clients().into_iter().cloned().collect();
+        let backend = self.config.reconcile_backend;
+        self.bus.spawn(async move {
+            let count = resources.len();
+            let mut failures = Vec::new();
+            let mut logged = Vec::new();
+            for resource in resources {
+                let result = match clients.iter().find(|c| c.label() == resource.cluster()) {
+                    Some(client) => {
+                        reconcile::backend(backend, client)
+                            .set_suspended(ResourceRef::of(resource.as_ref()), true)
+                            .await
+                    }
+                    None => Err(eyre!("not connected to cluster {}", resource.cluster())),
+                };
+                if let Err(e) = &result {
+                    failures.push(format!(
+                        "{} {}/{}: {e}",
+                        resource.kind(),
+                        resource.namespace(),
+                        resource.name()
+                    ));
+                }
+                logged.push(("Suspend".to_string(), resource, Outcome::of(&result)));
+            }
+
+            let popup = if failures.is_empty() {
+                Popup::Info {
+                    message: format!("Suspended {count} resource(s)"),
+                }
+            } else {
+                Popup::Error {
+                    message: format!(
+                        "Failed to suspend {} of {count} resource(s):\n{}",
+                        failures.len(),
+                        failures.join("\n")
+                    ),
+                }
+            };
+            StateUpdate::Changed {
+                logged,
+                popup: Some(popup),
+            }
+        });
+    }
+
+    /// Suspend or resume a resource with the configured reconcile backend
+    fn toggle_suspend(&mut self, resource: Box<dyn FluxResource>) {
+        let Some(client) = self.client_for(resource.as_ref()) else {
+            return;
+        };
+
+        let backend = self.config.reconcile_backend;
+        self.bus.spawn(async move {
+            let result = reconcile::backend(backend, &client)
+                .set_suspended(ResourceRef::of(resource.as_ref()), !resource.is_suspended())
+                .await;
+            let action = if resource.is_suspended() {
+                "Resume"
+            } else {
+                "Suspend"
+            };
+            let popup = result.as_ref().err().map(|e| Popup::Error {
+                message: format!("Toggle suspend failed: {e}"),
+            });
+            StateUpdate::Changed {
+                logged: vec![(action.to_string(), resource, Outcome::of(&result))],
+                popup,
+            }
+        });
+    }
+}
+
+/// Fetch one allow-listed namespace of a cluster, tagged with the cluster
+/// and namespace
+async fn fetch_namespace(
+    client: Arc<dyn FluxApi>,
+    ns: String,
+) -> (String, String, Result<ClusterSnapshot>) {
+    let result = client.fetch_namespace(&ns).await;
+    (client.label().to_string(), ns, result)
+}
+
+/// Connect to the configured context, or to every context in multi-cluster mode
+///
+/// Returns the primary client (first context) and clients for the rest.
+async fn connect(config: &Config) -> Result<(Arc<dyn FluxApi>, Vec<Arc<dyn FluxApi>>)> {
+    if config.contexts.is_empty() {
+        return Ok((Arc::new(KubeClient::new(config).await?), Vec::new()));
+    }
+
+    let configs: Vec<Config> = config
+        .contexts
+        .iter()
+        .map(|context| Config {
+            context: Some(context.clone()),
+            ..config.clone()
+        })
+        .collect();
+
+    let mut clients: Vec<Arc<dyn FluxApi>> =
+        futures::future::try_join_all(configs.iter().map(KubeClient::new))
+            .await?
+            .into_iter()
+            .map(|client| Arc::new(client) as Arc<dyn FluxApi>)
+            .collect();
+    let primary = clients.remove(0);
+    Ok((primary, clients))
+}
+
+/// Replace the items belonging to `cluster` (or only its `namespace`) with freshly fetched ones
+fn replace_items<T: FluxResource>(
+    items: &mut Vec<T>,
+    cluster: &str,
+    namespace: Option<&str>,
+    fresh: Vec<T>,
+) {
+    items.retain(|item| {
+        item.cluster() != cluster || namespace.is_some_and(|ns| item.namespace() != ns)
+    });
+    items.extend(fresh);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{progress::LoadState, sort::SortKey};
+    use crate::kubernetes::resources::ResourceStatus;
+
+    fn create_test_kustomization(name: &str, namespace: &str) -> Kustomization {
+        Kustomization {
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+            status: ResourceStatus::Ready,
+            status_message: "Applied".to_string(),
+            revision: Some("main/abc123".to_string()),
+            suspended: false,
+            source_ref: "GitRepository/flux-system".to_string(),
+            path: "./".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn create_test_helm_release(name: &str, namespace: &str) -> HelmRelease {
+        HelmRelease {
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+            status: ResourceStatus::Ready,
+            status_message: "Release reconciled".to_string(),
+            chart: "nginx".to_string(),
+            version: Some("1.0.0".to_string()),
+            suspended: false,
+            revision: Some("1".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn create_test_helm_chart(name: &str, namespace: &str) -> HelmChart {
+        HelmChart {
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+            status: ResourceStatus::Ready,
+            status_message: "Chart pulled".to_string(),
+            chart: "nginx".to_string(),
+            version: Some("1.0.0".to_string()),
+            source_ref: "HelmRepository/bitnami".to_string(),
+            revision: Some("1.0.0".to_string()),
+            ..Default::default()
+        }
+    }
+
+    // ===== Tab Index Tests (using real App) =====
+
+    #[test]
+    fn test_tab_index_kustomizations() {
+        let app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        assert_eq!(app.tab_index(), 0);
+    }
+
+    #[test]
+    fn test_tab_index_helm_releases() {
+        let app = App::for_testing(Tab::HelmReleases, vec![], vec![], vec![]);
+        assert_eq!(app.tab_index(), 1);
+    }
+
+    #[test]
+    fn test_tab_index_helm_charts() {
+        let app = App::for_testing(Tab::HelmCharts, vec![], vec![], vec![]);
+        assert_eq!(app.tab_index(), 2);
+    }
+
+    // ===== Selection Tests (using real App) =====
+
+    #[test]
+    fn test_current_selected_default() {
+        let app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        assert_eq!(app.current_selected(), 0);
+    }
+
+    #[test]
+    fn test_set_current_selected() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![
+                create_test_kustomization("ks1", "default"),
+                create_test_kustomization("ks2", "default"),
+                create_test_kustomization("ks3", "default"),
+            ],
+            vec![],
+            vec![],
+        );
+
+        app.set_current_selected(2);
+        assert_eq!(app.current_selected(), 2);
+    }
+
+    #[test]
+    fn test_selection_per_tab_independent() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![
+                create_test_kustomization("ks1", "default"),
+                create_test_kustomization("ks2", "default"),
+            ],
+            vec![
+                create_test_helm_release("hr1", "default"),
+                create_test_helm_release("hr2", "default"),
+                create_test_helm_release("hr3", "default"),
+            ],
+            vec![],
+        );
+
+        // Set selection for kustomizations tab
+        app.set_current_selected(1);
+        assert_eq!(app.current_selected(), 1);
+
+        // Switch to helm releases tab
+        app.tab = Tab::HelmReleases;
+        assert_eq!(app.current_selected(), 0); // Independent selection
+
+        // Set selection for helm releases
+        app.set_current_selected(2);
+        assert_eq!(app.current_selected(), 2);
+
+        // Switch back to kustomizations - selection preserved
+        app.tab = Tab::Kustomizations;
+        assert_eq!(app.current_selected(), 1);
+    }
+
+    // ===== Item Count Tests (using real App) =====
+
+    #[test]
+    fn test_current_item_count_empty() {
+        let app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        assert_eq!(app.current_item_count(), 0);
+    }
+
+    #[test]
+    fn test_current_item_count_kustomizations() {
+        let app = App::for_testing(
+            Tab::Kustomizations,
+            vec![
+                create_test_kustomization("ks1", "default"),
+                create_test_kustomization("ks2", "kube-system"),
+            ],
+            vec![],
+            vec![],
+        );
+        assert_eq!(app.current_item_count(), 2);
+    }
+
+    #[test]
+    fn test_current_item_count_helm_releases() {
+        let app = App::for_testing(
+            Tab::HelmReleases,
+            vec![],
+            vec![
+                create_test_helm_release("hr1", "default"),
+                create_test_helm_release("hr2", "default"),
+                create_test_helm_release("hr3", "default"),
+            ],
+            vec![],
+        );
+        assert_eq!(app.current_item_count(), 3);
+    }
+
+    #[test]
+    fn test_current_item_count_helm_charts() {
+        let app = App::for_testing(
+            Tab::HelmCharts,
+            vec![],
+            vec![],
+            vec![create_test_helm_chart("hc1", "default")],
+        );
+        assert_eq!(app.current_item_count(), 1);
+    }
+
+    // ===== Get Selected Resource Tests (using real App) =====
+
+    #[test]
+    fn test_get_selected_resource_empty() {
+        let app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        assert!(app.get_selected_resource().is_none());
+    }
+
+    #[test]
+    fn test_get_selected_resource_kustomization() {
+        let app = App::for_testing(
+            Tab::Kustomizations,
+            vec![create_test_kustomization("my-ks", "flux-system")],
+            vec![],
+            vec![],
+        );
+
+        let resource = app.get_selected_resource().expect("should have resource");
+        assert_eq!(resource.name(), "my-ks");
+        assert_eq!(resource.namespace(), "flux-system");
+        assert_eq!(resource.kind(), "Kustomization");
+    }
+
+    #[test]
+    fn test_get_selected_resource_helm_release() {
+        let app = App::for_testing(
+            Tab::HelmReleases,
+            vec![],
+            vec![create_test_helm_release("my-release", "default")],
+            vec![],
+        );
+
+        let resource = app.get_selected_resource().expect("should have resource");
+        assert_eq!(resource.name(), "my-release");
+        assert_eq!(resource.namespace(), "default");
+        assert_eq!(resource.kind(), "HelmRelease");
+    }
+
+    #[test]
+    fn test_get_selected_resource_helm_chart() {
+        let app = App::for_testing(
+            Tab::HelmCharts,
+            vec![],
+            vec![],
+            vec![create_test_helm_chart("my-chart", "flux-system")],
+        );
+
+        let resource = app.get_selected_resource().expect("should have resource");
+        assert_eq!(resource.name(), "my-chart");
+        assert_eq!(resource.namespace(), "flux-system");
+        assert_eq!(resource.kind(), "HelmChart");
+    }
+
+    #[test]
+    fn test_get_selected_resource_out_of_bounds() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![create_test_kustomization("only-one", "default")],
+            vec![],
+            vec![],
+        );
+
+        app.set_current_selected(5); // Out of bounds
+        assert!(app.get_selected_resource().is_none());
+    }
+
+    // ===== App::for_testing Tests =====
+
+    #[test]
+    fn test_for_testing_constructor() {
+        let app = App::for_testing(
+            Tab::HelmReleases,
+            vec![create_test_kustomization("ks1", "default")],
+            vec![create_test_helm_release("hr1", "default")],
+            vec![create_test_helm_chart("hc1", "default")],
+        );
+
+        assert_eq!(app.tab, Tab::HelmReleases);
+        assert_eq!(app.kustomizations.len(), 1);
+        assert_eq!(app.helm_releases.len(), 1);
+        assert_eq!(app.helm_charts.len(), 1);
+        assert_eq!(app.cluster_name, "test-cluster");
+        assert!(!app.loading);
+        assert_eq!(app.client.label(), "test-cluster");
+    }
+
+    #[test]
+    fn test_for_testing_defaults() {
+        let app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+
+        assert_eq!(app.selected, [0; 5]);
+        assert!(app.namespace_filter.is_none());
+        assert!(app.namespaces.is_empty());
+        assert!(matches!(app.popup, Popup::None));
+        assert!(app.last_error.is_none());
+        assert!(app.recent_errors.is_empty());
+    }
+
+    #[test]
+    fn test_record_error_sets_last_error_and_log() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+
+        app.record_error("first".to_string());
+        app.record_error("second".to_string());
+
+        assert_eq!(app.last_error.as_deref(), Some("second"));
+        assert_eq!(app.recent_errors.len(), 2);
+        assert_eq!(app.recent_errors.latest().unwrap().1, "second");
+    }
+
+    // ===== Original Tab Tests =====
+
+    #[test]
+    fn test_tab_default() {
+        let tab = Tab::default();
+        assert_eq!(tab, Tab::Kustomizations);
+    }
+
+    #[test]
+    fn test_tab_all() {
+        let tabs = Tab::all();
+        assert_eq!(tabs.len(), 5);
+        assert_eq!(tabs[0], Tab::Kustomizations);
+        assert_eq!(tabs[1], Tab::HelmReleases);
+        assert_eq!(tabs[2], Tab::HelmCharts);
+        assert_eq!(tabs[3], Tab::GitRepositories);
+        assert_eq!(tabs[4], Tab::HelmRepositories);
+    }
+
+    #[test]
+    fn test_tab_name() {
+        assert_eq!(Tab::Kustomizations.name(), "Kustomizations");
+        assert_eq!(Tab::HelmReleases.name(), "HelmReleases");
+        assert_eq!(Tab::HelmCharts.name(), "HelmCharts");
+        assert_eq!(Tab::GitRepositories.name(), "GitRepositories");
+        assert_eq!(Tab::HelmRepositories.name(), "HelmRepositories");
+    }
+
+    #[test]
+    fn test_tab_next() {
+        assert_eq!(Tab::Kustomizations.next(), Tab::HelmReleases);
+        assert_eq!(Tab::HelmReleases.next(), Tab::HelmCharts);
+        assert_eq!(Tab::HelmCharts.next(), Tab::GitRepositories);
+        assert_eq!(Tab::GitRepositories.next(), Tab::HelmRepositories);
+        assert_eq!(Tab::HelmRepositories.next(), Tab::Kustomizations);
+    }
+
+    #[test]
+    fn test_tab_previous() {
+        assert_eq!(Tab::Kustomizations.previous(), Tab::HelmRepositories);
+        assert_eq!(Tab::HelmReleases.previous(), Tab::Kustomizations);
+        assert_eq!(Tab::HelmCharts.previous(), Tab::HelmReleases);
+        assert_eq!(Tab::GitRepositories.previous(), Tab::HelmCharts);
+        assert_eq!(Tab::HelmRepositories.previous(), Tab::GitRepositories);
+    }
+
+    #[test]
+    fn test_tab_cycle_next() {
+        let mut tab = Tab::Kustomizations;
+        tab = tab.next();
+        assert_eq!(tab, Tab::HelmReleases);
+        tab = tab.next();
+        assert_eq!(tab, Tab::HelmCharts);
+        tab = tab.next();
+        assert_eq!(tab, Tab::GitRepositories);
+        tab = tab.next();
+        assert_eq!(tab, Tab::HelmRepositories);
+        tab = tab.next();
+        assert_eq!(tab, Tab::Kustomizations);
+    }
+
+    #[test]
+    fn test_tab_cycle_previous() {
+        let mut tab = Tab::Kustomizations;
+        tab = tab.previous();
+        assert_eq!(tab, Tab::HelmRepositories);
+        tab = tab.previous();
+        assert_eq!(tab, Tab::GitRepositories);
+        tab = tab.previous();
+        assert_eq!(tab, Tab::HelmCharts);
+        tab = tab.previous();
+        assert_eq!(tab, Tab::HelmReleases);
+        tab = tab.previous();
+        assert_eq!(tab, Tab::Kustomizations);
+    }
+
+    #[test]
+    fn test_popup_default() {
+        let popup = Popup::default();
+        assert!(matches!(popup, Popup::None));
+    }
+
+    // ===== Async Update Tests =====
+
+    #[tokio::test]
+    async fn test_update_next_tab() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+
+        app.update(Action::NextTab).unwrap();
+        assert_eq!(app.tab, Tab::HelmReleases);
+
+        app.update(Action::NextTab).unwrap();
+        assert_eq!(app.tab, Tab::HelmCharts);
+
+        app.update(Action::NextTab).unwrap();
+        assert_eq!(app.tab, Tab::GitRepositories);
+
+        app.update(Action::NextTab).unwrap();
+        assert_eq!(app.tab, Tab::HelmRepositories);
+
+        app.update(Action::NextTab).unwrap();
+        assert_eq!(app.tab, Tab::Kustomizations);
+    }
+
+    #[tokio::test]
+    async fn test_update_previous_tab() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+
+        app.update(Action::PreviousTab).unwrap();
+        assert_eq!(app.tab, Tab::HelmRepositories);
+
+        app.update(Action::PreviousTab).unwrap();
+        assert_eq!(app.tab, Tab::GitRepositories);
+
+        app.update(Action::PreviousTab).unwrap();
+        assert_eq!(app.tab, Tab::HelmCharts);
+
+        app.update(Action::PreviousTab).unwrap();
+        assert_eq!(app.tab, Tab::HelmReleases);
+
+        app.update(Action::PreviousTab).unwrap();
+        assert_eq!(app.tab, Tab::Kustomizations);
+    }
+
+    #[tokio::test]
+    async fn test_update_navigation_up() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![
+                create_test_kustomization("ks1", "default"),
+                create_test_kustomization("ks2", "default"),
+                create_test_kustomization("ks3", "default"),
+            ],
+            vec![],
+            vec![],
+        );
+
+        // Start at index 2
+        app.set_current_selected(2);
+        assert_eq!(app.current_selected(), 2);
+
+        // Move up
+        app.update(Action::Up).unwrap();
+        assert_eq!(app.current_selected(), 1);
+
+        app.update(Action::Up).unwrap();
+        assert_eq!(app.current_selected(), 0);
+
+        // Can't go below 0
+        app.update(Action::Up).unwrap();
+        assert_eq!(app.current_selected(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_update_navigation_down() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![
+                create_test_kustomization("ks1", "default"),
+                create_test_kustomization("ks2", "default"),
+                create_test_kustomization("ks3", "default"),
+            ],
+            vec![],
+            vec![],
+        );
+
+        assert_eq!(app.current_selected(), 0);
+
+        app.update(Action::Down).unwrap();
+        assert_eq!(app.current_selected(), 1);
+
+        app.update(Action::Down).unwrap();
+        assert_eq!(app.current_selected(), 2);
+
+        // Can't go beyond last item
+        app.update(Action::Down).unwrap();
+        assert_eq!(app.current_selected(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_update_navigation_down_empty() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+
+        // Down on empty list should do nothing
+        app.update(Action::Down).unwrap();
+        assert_eq!(app.current_selected(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_update_top() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![
+                create_test_kustomization("ks1", "default"),
+                create_test_kustomization("ks2", "default"),
+                create_test_kustomization("ks3", "default"),
+            ],
+            vec![],
+            vec![],
+        );
+
+        app.set_current_selected(2);
+
+        app.update(Action::Top).unwrap();
+        assert_eq!(app.current_selected(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_update_bottom() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![
+                create_test_kustomization("ks1", "default"),
+                create_test_kustomization("ks2", "default"),
+                create_test_kustomization("ks3", "default"),
+            ],
+            vec![],
+            vec![],
+        );
+
+        app.update(Action::Bottom).unwrap();
+        assert_eq!(app.current_selected(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_update_bottom_empty() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+
+        // Bottom on empty list should stay at 0
+        app.update(Action::Bottom).unwrap();
+        assert_eq!(app.current_selected(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_update_count_and_paging() {
+        let kustomizations = (0..50)
+            .map(|i| create_test_kustomization(&format!("ks{i}"), "default"))
+            .collect();
+        let mut app = App::for_testing(Tab::Kustomizations, kustomizations, vec![], vec![]);
+        app.scroll_to_selection(20);
+
+        app.update(Action::CountDigit(1)).unwrap();
+        app.update(Action::CountDigit(2)).unwrap();
+        app.update(Action::Down).unwrap();
+        assert_eq!(app.current_selected(), 12);
+        assert_eq!(app.count, None);
+
+        // The count only applies to the next action
+        app.update(Action::Down).unwrap();
+        assert_eq!(app.current_selected(), 13);
+
+        app.update(Action::PageDown).unwrap();
+        assert_eq!(app.current_selected(), 33);
+        app.update(Action::HalfPageUp).unwrap();
+        assert_eq!(app.current_selected(), 23);
+        app.update(Action::CountDigit(3)).unwrap();
+        app.update(Action::PageDown).unwrap();
+        assert_eq!(app.current_selected(), 49);
+        app.update(Action::CountDigit(9)).unwrap();
+        app.update(Action::Up).unwrap();
+        assert_eq!(app.current_selected(), 40);
+        app.update(Action::PageUp).unwrap();
+        assert_eq!(app.current_selected(), 20);
+        app.update(Action::HalfPageUp).unwrap();
+        app.update(Action::PageUp).unwrap();
+        assert_eq!(app.current_selected(), 0);
+
+        app.update(Action::CountDigit(5)).unwrap();
+        app.update(Action::Bottom).unwrap();
+        assert_eq!(app.current_selected(), 4);
+        app.update(Action::CountDigit(9)).unwrap();
+        app.update(Action::CountDigit(9)).unwrap();
+        app.update(Action::Bottom).unwrap();
+        assert_eq!(app.current_selected(), 49);
+
+        // Any other action drops a count
+        app.update(Action::CountDigit(5)).unwrap();
+        app.update(Action::ToggleActivity).unwrap();
+        app.update(Action::Top).unwrap();
+        app.update(Action::Down).unwrap();
+        assert_eq!(app.current_selected(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_update_select_opens_details() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![create_test_kustomization("my-ks", "flux-system")],
+            vec![],
+            vec![],
+        );
+
+        app.update(Action::Select).unwrap();
+
+        match &app.popup {
+            Popup::ResourceDetails { resource, .. } => {
+                assert_eq!(resource.name(), "my-ks");
+            }
+            _ => panic!("Expected ResourceDetails popup"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_select_empty_no_popup() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+
+        app.update(Action::Select).unwrap();
+
+        assert!(matches!(app.popup, Popup::None));
+    }
+
+    #[tokio::test]
+    async fn test_update_filter_namespace() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.namespaces = vec!["default".to_string(), "kube-system".to_string()];
+
+        app.update(Action::FilterNamespace).unwrap();
+
+        match &app.popup {
+            Popup::NamespaceFilter {
+                namespaces,
+                query,
+                selected,
+            } => {
+                assert_eq!(*selected, 0);
+                assert!(query.is_empty());
+                assert_eq!(
+                    namespace_choices(namespaces, query),
+                    vec![None, Some("default"), Some("kube-system")]
+                );
+            }
+            _ => panic!("Expected NamespaceFilter popup"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_namespace_type_ahead() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.namespaces = ["apps", "apps-staging", "infra", "kube-system"]
+            .map(String::from)
+            .to_vec();
+        app.update(Action::FilterNamespace).unwrap();
+
+        app.update(Action::PopupDown).unwrap();
+        for c in "aps".chars() {
+            app.update(Action::NamespaceInput(c)).unwrap();
+        }
+        let Popup::NamespaceFilter {
+            namespaces,
+            query,
+            selected,
+        } = &app.popup
+        else {
+            panic!("Expected NamespaceFilter popup");
+        };
+        assert_eq!(*selected, 0);
+        assert_eq!(
+            namespace_choices(namespaces, query),
+            vec![Some("apps"), Some("apps-staging")]
+        );
+
+        // Moving stays within the matches
+        for _ in 0..3 {
+            app.update(Action::PopupDown).unwrap();
+        }
+        assert!(matches!(
+            app.popup,
+            Popup::NamespaceFilter { selected: 1, .. }
+        ));
+        app.update(Action::PopupUp).unwrap();
+        assert!(matches!(
+            app.popup,
+            Popup::NamespaceFilter { selected: 0, .. }
+        ));
+
+        app.update(Action::NamespaceBackspace).unwrap();
+        match &app.popup {
+            Popup::NamespaceFilter { query, .. } => assert_eq!(query, "ap"),
+            _ => panic!("Expected NamespaceFilter popup"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_close_popup() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![create_test_kustomization("ks1", "default")],
+            vec![],
+            vec![],
+        );
+
+        // Open a popup first
+        app.update(Action::Select).unwrap();
+        assert!(matches!(app.popup, Popup::ResourceDetails { .. }));
+
+        // Close it
+        app.update(Action::ClosePopup).unwrap();
+        assert!(matches!(app.popup, Popup::None));
+    }
+
+    #[tokio::test]
+    async fn test_update_quit_noop() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+
+        // Quit action does nothing (handled in main loop)
+        app.update(Action::Quit).unwrap();
+        // Should not panic, just return Ok
+    }
+
+    #[tokio::test]
+    async fn test_update_noop() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+
+        // Noop does nothing
+        app.update(Action::Noop).unwrap();
+        assert_eq!(app.tab, Tab::Kustomizations);
+        assert_eq!(app.current_selected(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_update_export_snapshot() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![Kustomization {
+                cluster: "test-cluster".to_string(),
+                ..create_test_kustomization("apps", "flux-system")
+            }],
+            vec![],
+            vec![],
+        );
+
+        app.update(Action::ExportSnapshot).unwrap();
+
+        let Popup::Info { message } = &app.popup else {
+            panic!("Expected Info popup");
+        };
+        let path = message
+            .strip_prefix("Snapshot written to ")
+            .and_then(|rest| rest.strip_suffix(" (open it with --snapshot)"))
+            .expect("snapshot path");
+        let snapshot = Snapshot::load(std::path::Path::new(path)).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        let mut viewer = App::from_snapshot(&Config::default(), snapshot)
+            .await
+            .unwrap();
+        assert_eq!(viewer.kustomizations.len(), 1);
+        assert_eq!(viewer.cluster_name, "test-cluster");
+        assert!(viewer.snapshot_taken.is_some());
+        viewer.update(Action::SwitchContext).unwrap();
+        assert!(matches!(viewer.popup, Popup::Error { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_update_set_namespace() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.popup = Popup::NamespaceFilter {
+            namespaces: vec!["default".to_string()],
+            query: String::new(),
+            selected: 0,
+        };
+
+        app.update(Action::SetNamespace(Some("default".to_string())))
+            .unwrap();
+
+        assert_eq!(app.namespace_filter, Some("default".to_string()));
+        assert!(matches!(app.popup, Popup::None));
+    }
+
+    #[tokio::test]
+    async fn test_update_set_namespace_all() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.namespace_filter = Some("default".to_string());
+
+        app.update(Action::SetNamespace(None)).unwrap();
+
+        assert!(app.namespace_filter.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_update_write_diagnostics() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+
+        app.update(Action::WriteDiagnostics).unwrap();
+
+        match &app.popup {
+            Popup::Info { message } => {
+                let path = message
+                    .strip_prefix("Diagnostic report written to ")
+                    .expect("report path");
+                assert!(std::fs::read_to_string(path)
+                    .unwrap()
+                    .contains("flux-tui diagnostic report"));
+                std::fs::remove_file(path).unwrap();
+            }
+            _ => panic!("Expected Info popup"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refresh_data_from_client() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![create_test_kustomization("apps", "flux-system")],
+            vec![],
+            vec![],
+        );
+        app.kustomizations.clear();
+        app.loading = true;
+
+        app.refresh_data().await.unwrap();
+
+        assert!(!app.loading);
+        assert_eq!(app.kustomizations.len(), 1);
+        assert!(app.last_refreshed.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_suspend_patches_through_the_client() {
+        let mock = MockClient::new(ClusterSnapshot {
+            kustomizations: vec![cluster_kustomization("apps", "test-cluster")],
+            ..Default::default()
+        });
+        let config = Config {
+            reconcile_backend: reconcile::Backend::Native,
+            ..Default::default()
+        };
+        let mut app = App::with_clients(&config, mock.clone(), Vec::new(), Theme::default());
+        app.refresh_data().await.unwrap();
+
+        app.update(Action::ToggleSuspend).unwrap();
+        app.settle().await;
+
+        assert_eq!(
+            mock.patches(),
+            vec![(
+                "Kustomization flux-system/apps".to_string(),
+                serde_json::json!({"spec": {"suspend": true}})
+            )]
+        );
+        assert!(matches!(app.popup, Popup::None));
+    }
+
+    // ===== Popup Variant Tests =====
+
+    #[test]
+    fn test_popup_namespace_filter() {
+        let popup = Popup::NamespaceFilter {
+            namespaces: vec!["default".to_string(), "kube-system".to_string()],
+            query: String::new(),
+            selected: 1,
+        };
+
+        if let Popup::NamespaceFilter {
+            namespaces,
+            selected,
+            ..
+        } = popup
+        {
+            assert_eq!(namespaces.len(), 2);
+            assert_eq!(selected, 1);
+        } else {
+            panic!("Expected NamespaceFilter");
+        }
+    }
+
+    #[test]
+    fn test_popup_resource_details() {
+        let ks = create_test_kustomization("test-ks", "default");
+        let popup = Popup::ResourceDetails {
+            resource: Box::new(ks),
+            scroll: 0,
+        };
+
+        if let Popup::ResourceDetails { resource, .. } = popup {
+            assert_eq!(resource.name(), "test-ks");
+            assert_eq!(resource.namespace(), "default");
+        } else {
+            panic!("Expected ResourceDetails");
+        }
+    }
+
+    #[test]
+    fn test_popup_reconciling() {
+        let popup = Popup::Reconciling {
+            name: "my-resource".to_string(),
+            namespace: "flux-system".to_string(),
+            progress: ReconcileProgress::default(),
+        };
+
+        if let Popup::Reconciling {
+            name, namespace, ..
+        } = popup
+        {
+            assert_eq!(name, "my-resource");
+            assert_eq!(namespace, "flux-system");
+        } else {
+            panic!("Expected Reconciling");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_show_flux_version() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.update(Action::ShowFluxVersion).unwrap();
+        app.settle().await;
+        assert!(matches!(app.popup, Popup::FluxVersion { .. }));
+        assert_eq!(app.flux_version, Some(FluxVersion::default()));
+
+        // Read again in the background, e.g. after switching context
+        app.popup = Popup::None;
+        app.apply(StateUpdate::FluxVersion {
+            version: FluxVersion {
+                distribution: Some("v2.3.0".to_string()),
+                ..Default::default()
+            },
+            show: false,
+        });
+        assert!(matches!(app.popup, Popup::None));
+        assert_eq!(
+            app.flux_version.unwrap().distribution.as_deref(),
+            Some("v2.3.0")
+        );
+    }
+
+    #[test]
+    fn test_poll_reconcile_applies_progress() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        assert!(!app.poll_reconcile());
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let started = Utc::now();
+        app.popup = Popup::Reconciling {
+            name: "apps".to_string(),
+            namespace: "flux-system".to_string(),
+            progress: ReconcileProgress::new(started),
+        };
+        app.reconcile_updates = Some(rx);
+        assert!(app.is_reconciling());
+
+        tx.send(ReconcileUpdate::Observed {
+            conditions: vec![crate::kubernetes::resources::Condition {
+                condition_type: "Reconciling".to_string(),
+                status: "True".to_string(),
+                message: "Building manifests".to_string(),
+                last_transition: Some(started),
+                ..Default::default()
+            }],
+            events: vec![],
+        })
+        .unwrap();
+        assert!(!app.poll_reconcile());
+
+        tx.send(ReconcileUpdate::Done(Err("timeout".to_string())))
+            .unwrap();
+        assert!(app.poll_reconcile());
+        assert!(!app.is_reconciling());
+        match &app.popup {
+            Popup::Reconciling { progress, .. } => {
+                assert_eq!(progress.steps[0].text, "Building manifests");
+                assert_eq!(progress.outcome, Some(Err("timeout".to_string())));
+            }
+            _ => panic!("Expected Reconciling popup"),
+        }
+        assert_eq!(app.recent_errors.len(), 1);
+    }
+
+    #[test]
+    fn test_poll_reconcile_reports_resume() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        let (tx, rx) = mpsc::unbounded_channel();
+        app.popup = Popup::Reconciling {
+            name: "apps".to_string(),
+            namespace: "flux-system".to_string(),
+            progress: ReconcileProgress::new(Utc::now()),
+        };
+        app.reconcile_updates = Some(rx);
+
+        tx.send(ReconcileUpdate::Resumed(Ok(()))).unwrap();
+        tx.send(ReconcileUpdate::Done(Ok(()))).unwrap();
+        assert!(app.poll_reconcile());
+        match &app.popup {
+            Popup::Reconciling { progress, .. } => {
+                assert_eq!(progress.steps[0].text, "Resumed");
+                assert_eq!(progress.outcome, Some(Ok(())));
+            }
+            _ => panic!("Expected Reconciling popup"),
+        }
+    }
+
+    #[test]
+    fn test_finished_reconcile_is_logged() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        let apps = Kustomization {
+            name: "apps".to_string(),
+            namespace: "flux-system".to_string(),
+            ..Default::default()
+        };
+        app.action_log
+            .record(Utc::now(), "Reconcile", &apps, Outcome::Started);
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        app.popup = Popup::Reconciling {
+            name: "apps".to_string(),
+            namespace: "flux-system".to_string(),
+            progress: ReconcileProgress::new(Utc::now()),
+        };
+        app.reconcile_updates = Some(rx);
+        tx.send(ReconcileUpdate::Done(Err("timeout".to_string())))
+            .unwrap();
+        assert!(app.poll_reconcile());
+        let entry = app.action_log.entries().last().unwrap();
+        assert_eq!(entry.outcome, Outcome::Failed("timeout".to_string()));
+    }
+
+    #[test]
+    fn test_popup_error() {
+        let popup = Popup::Error {
+            message: "Something went wrong".to_string(),
+        };
+
+        if let Popup::Error { message } = popup {
+            assert_eq!(message, "Something went wrong");
+        } else {
+            panic!("Expected Error");
+        }
+    }
+
+    // ===== Additional App Field Tests =====
+
+    #[test]
+    fn test_app_loading_state() {
+        let app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        assert!(!app.loading);
+    }
+
+    #[test]
+    fn test_app_last_error() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        assert!(app.last_error.is_none());
+
+        app.last_error = Some("Test error".to_string());
+        assert_eq!(app.last_error, Some("Test error".to_string()));
+    }
+
+    #[test]
+    fn test_app_cluster_name() {
+        let app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        assert_eq!(app.cluster_name, "test-cluster");
+    }
+
+    #[test]
+    fn test_app_multiple_resources() {
+        let app = App::for_testing(
+            Tab::Kustomizations,
+            vec![
+                create_test_kustomization("ks1", "ns1"),
+                create_test_kustomization("ks2", "ns2"),
+            ],
+            vec![create_test_helm_release("hr1", "ns1")],
+            vec![
+                create_test_helm_chart("hc1", "ns1"),
+                create_test_helm_chart("hc2", "ns2"),
+                create_test_helm_chart("hc3", "ns3"),
+            ],
+        );
+
+        assert_eq!(app.kustomizations.len(), 2);
+        assert_eq!(app.helm_releases.len(), 1);
+        assert_eq!(app.helm_charts.len(), 3);
+    }
+
+    // ===== Tab Edge Cases =====
+
+    #[test]
+    fn test_tab_as_usize() {
+        // Verify Tab can be converted to usize for indexing
+        assert_eq!(Tab::Kustomizations as usize, 0);
+        assert_eq!(Tab::HelmReleases as usize, 1);
+        assert_eq!(Tab::HelmCharts as usize, 2);
+        assert_eq!(Tab::GitRepositories as usize, 3);
+        assert_eq!(Tab::HelmRepositories as usize, 4);
+    }
+
+    #[test]
+    fn test_tab_kind_info() {
+        let kinds: Vec<&str> = Tab::all().iter().map(|tab| tab.kind()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "Kustomization",
+                "HelmRelease",
+                "HelmChart",
+                "GitRepository",
+                "HelmRepository"
+            ]
+        );
+        assert!(!Tab::HelmCharts.info().suspendable);
+        assert!(Tab::GitRepositories.is_source());
+        assert!(Tab::HelmReleases.consumes_sources());
+        assert!(!Tab::HelmRepositories.consumes_sources());
+    }
+
+    #[test]
+    fn test_app_selected_array_access() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+
+        // Direct access to selected array
+        app.selected[0] = 5;
+        app.selected[1] = 10;
+        app.selected[2] = 15;
+
+        assert_eq!(app.selected[0], 5);
+        assert_eq!(app.selected[1], 10);
+        assert_eq!(app.selected[2], 15);
+
+        // Verify current_selected works with direct access
+        app.tab = Tab::Kustomizations;
+        assert_eq!(app.current_selected(), 5);
+
+        app.tab = Tab::HelmReleases;
+        assert_eq!(app.current_selected(), 10);
+
+        app.tab = Tab::HelmCharts;
+        assert_eq!(app.current_selected(), 15);
+    }
+
+    #[tokio::test]
+    async fn test_update_select_helm_release() {
+        let mut app = App::for_testing(
+            Tab::HelmReleases,
+            vec![],
+            vec![create_test_helm_release("my-release", "default")],
+            vec![],
+        );
+
+        app.update(Action::Select).unwrap();
+
+        if let Popup::ResourceDetails { resource, .. } = &app.popup {
+            assert_eq!(resource.name(), "my-release");
+            assert_eq!(resource.kind(), "HelmRelease");
+        } else {
+            panic!("Expected ResourceDetails popup");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_highlight_context() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.popup = Popup::ContextSwitcher {
+            contexts: vec!["dev".to_string(), "prod".to_string()],
+            selected: 0,
+        };
+
+        app.update(Action::HighlightItem(1)).unwrap();
+
+        match &app.popup {
+            Popup::ContextSwitcher { selected, .. } => assert_eq!(*selected, 1),
+            _ => panic!("Expected ContextSwitcher popup"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_set_context_failure_keeps_state() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![create_test_kustomization("apps", "flux-system")],
+            vec![],
+            vec![],
+        );
+        app.config.kubeconfig = Some(std::path::PathBuf::from("/nonexistent/kubeconfig"));
+
+        app.update(Action::SetContext("prod".to_string())).unwrap();
+        // The client is built in the background
+        assert!(app.is_busy());
+        app.settle().await;
+
+        assert!(matches!(app.popup, Popup::Error { .. }));
+        assert_eq!(app.recent_errors.len(), 1);
+        assert_eq!(app.kustomizations.len(), 1);
+        assert_eq!(app.cluster_name, "test-cluster");
+        assert!(app.config.context.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_jump_to_chart() {
+        let release = HelmRelease {
+            source: Some(SourceRef {
+                kind: "HelmRepository".to_string(),
+                name: "bitnami".to_string(),
+                namespace: "flux-system".to_string(),
+            }),
+            ..create_test_helm_release("nginx", "web")
+        };
+        let orphan = HelmRelease {
+            source: None,
+            ..create_test_helm_release("orphan", "web")
+        };
+        let mut app = App::for_testing(
+            Tab::HelmReleases,
+            vec![],
+            vec![release, orphan],
+            vec![
+                create_test_helm_chart("web-api", "flux-system"),
+                create_test_helm_chart("web-nginx", "flux-system"),
+            ],
+        );
+        app.search_query = "nginx".to_string();
+
+        app.update(Action::Jump).unwrap();
+        assert_eq!(app.tab, Tab::HelmCharts);
+        assert!(app.search_query.is_empty());
+        assert_eq!(app.get_selected_resource().unwrap().name(), "web-nginx");
+
+        app.tab = Tab::HelmReleases;
+        app.set_current_selected(1);
+        app.update(Action::Jump).unwrap();
+        assert_eq!(app.tab, Tab::HelmReleases);
+        assert!(matches!(app.popup, Popup::Error { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_jump_to_source() {
+        let source = |kind: &str, name: &str| SourceRef {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            namespace: "flux-system".to_string(),
+        };
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![
+                Kustomization {
+                    source: Some(source("GitRepository", "fleet")),
+                    ..create_test_kustomization("apps", "flux-system")
+                },
+                Kustomization {
+                    source: Some(source("OCIRepository", "manifests")),
+                    ..create_test_kustomization("oci", "flux-system")
+                },
+            ],
+            vec![],
+            vec![create_test_helm_chart("web-nginx", "flux-system")],
+        );
+        app.git_repositories = vec![
+            GitRepository {
+                name: "another".to_string(),
+                namespace: "flux-system".to_string(),
+                ..Default::default()
+            },
+            GitRepository {
+                name: "fleet".to_string(),
+                namespace: "flux-system".to_string(),
+                ..Default::default()
+            },
+        ];
+        app.helm_repositories = vec![HelmRepository {
+            name: "bitnami".to_string(),
+            namespace: "flux-system".to_string(),
+            ..Default::default()
+        }];
+
+        app.update(Action::Jump).unwrap();
+        assert_eq!(app.tab, Tab::GitRepositories);
+        assert_eq!(app.get_selected_resource().unwrap().name(), "fleet");
+
+        // A HelmChart's source comes from its "Kind/name" reference
+        app.tab = Tab::HelmCharts;
+        app.update(Action::Jump).unwrap();
+        assert_eq!(app.tab, Tab::HelmRepositories);
+        assert_eq!(app.get_selected_resource().unwrap().name(), "bitnami");
+
+        // OCIRepositories have no tab
+        app.tab = Tab::Kustomizations;
+        app.set_current_selected(1);
+        app.update(Action::Jump).unwrap();
+        assert_eq!(app.tab, Tab::Kustomizations);
+        assert!(matches!(app.popup, Popup::Error { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_tour() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.start_tour();
+        app.update(Action::TourBack).unwrap();
+        assert!(matches!(app.popup, Popup::Tour { step: 0 }));
+        app.update(Action::TourNext).unwrap();
+        app.update(Action::TourNext).unwrap();
+        app.update(Action::TourBack).unwrap();
+        assert!(matches!(app.popup, Popup::Tour { step: 1 }));
+
+        // Finishing the last step closes the tour for good
+        for _ in 1..tour::STEPS.len() {
+            assert!(!app.state.tour_done);
+            app.update(Action::TourNext).unwrap();
+        }
+        assert!(matches!(app.popup, Popup::None));
+        assert!(app.state.tour_done);
+
+        // So does skipping it
+        app.state.tour_done = false;
+        app.start_tour();
+        app.update(Action::EndTour).unwrap();
+        assert!(matches!(app.popup, Popup::None));
+        assert!(app.state.tour_done);
+    }
+
+    #[tokio::test]
+    async fn test_global_search() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![
+                create_test_kustomization("apps", "flux-system"),
+                create_test_kustomization("infra", "flux-system"),
+            ],
+            vec![
+                create_test_helm_release("cert-manager", "default"),
+                create_test_helm_release("podinfo", "default"),
+            ],
+            vec![],
+        );
+        // A filter hiding the match is dropped
+        app.status_filter[Tab::HelmReleases as usize] = StatusFilter::Suspended;
+
+        app.update(Action::StartGlobalSearch).unwrap();
+        for c in "podnfo".chars() {
+            app.update(Action::GlobalSearchInput(c)).unwrap();
+        }
+        assert_eq!(app.global_matches("podnfo").len(), 1);
+        app.update(Action::PopupDown).unwrap();
+        assert!(matches!(app.popup, Popup::GlobalSearch { selected: 0, .. }));
+
+        app.update(Action::GoToMatch).unwrap();
+        assert!(matches!(app.popup, Popup::None));
+        assert_eq!(app.tab, Tab::HelmReleases);
+        assert_eq!(app.current_status_filter(), StatusFilter::All);
+        assert_eq!(app.get_selected_resource().unwrap().name(), "podinfo");
+
+        // Nothing to go to without a match
+        app.update(Action::StartGlobalSearch).unwrap();
+        app.update(Action::GlobalSearchInput('x')).unwrap();
+        app.update(Action::GoToMatch).unwrap();
+        assert!(matches!(app.popup, Popup::GlobalSearch { .. }));
+        assert_eq!(app.tab, Tab::HelmReleases);
+    }
+
+    #[tokio::test]
+    async fn test_group_by_source() {
+        let source = |name: &str| SourceRef {
+            kind: "GitRepository".to_string(),
+            name: name.to_string(),
+            namespace: "flux-system".to_string(),
+        };
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![
+                Kustomization {
+                    source: Some(source("fleet")),
+                    ..create_test_kustomization("apps", "flux-system")
+                },
+                Kustomization {
+                    source: Some(source("addons")),
+                    ..create_test_kustomization("addons", "flux-system")
+                },
+                Kustomization {
+                    source: Some(source("fleet")),
+                    ..create_test_kustomization("infra", "flux-system")
+                },
+            ],
+            vec![],
+            vec![],
+        );
+        app.set_current_selected(2);
+
+        // The selected resource stays selected: addons, fleet, apps, infra
+        app.update(Action::ToggleGrouping).unwrap();
+        assert_eq!(app.current_item_count(), 5);
+        assert_eq!(app.current_selected(), 4);
+        assert_eq!(app.get_selected_resource().unwrap().name(), "infra");
+
+        // Collapsing from a member selects the group's header
+        app.update(Action::ToggleGroup).unwrap();
+        assert_eq!(app.current_item_count(), 3);
+        assert_eq!(app.current_selected(), 2);
+        assert!(app.get_selected_resource().is_none());
+
+        // Enter on a header expands it again
+        app.update(Action::Select).unwrap();
+        assert!(matches!(app.popup, Popup::None));
+        assert_eq!(app.current_item_count(), 5);
+
+        // Only the consumer tabs are grouped
+        app.update(Action::NextTab).unwrap();
+        app.update(Action::NextTab).unwrap();
+        assert!(!app.is_grouped());
+
+        app.update(Action::PreviousTab).unwrap();
+        app.update(Action::PreviousTab).unwrap();
+        app.update(Action::ToggleGrouping).unwrap();
+        assert_eq!(app.current_item_count(), 3);
+        assert_eq!(app.current_selected(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_force_reconcile_only_on_helm_releases() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![create_test_kustomization("apps", "flux-system")],
+            vec![],
+            vec![],
+        );
+        app.update(Action::ForceReconcile).unwrap();
+        assert!(matches!(app.popup, Popup::None));
+        assert_eq!(app.action_log.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_runs_again_when_requested_in_flight() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.update(Action::Refresh).unwrap();
+        app.update(Action::Refresh).unwrap();
+        app.update(Action::Refresh).unwrap();
+        assert!(app.loading);
+        assert!(!app.refresh_due(std::time::Duration::ZERO));
+
+        app.settle().await;
+        assert!(!app.loading);
+        assert!(!app.is_busy());
+        assert!(app.refresh_due(std::time::Duration::ZERO));
+    }
 
-        Ok(())
+    #[tokio::test]
+    async fn test_apply_changed() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        let apps = || Box::new(create_test_kustomization("apps", "flux-system"));
+
+        app.apply(StateUpdate::Changed {
+            logged: vec![(
+                "Suspend".to_string(),
+                apps(),
+                Outcome::Failed("forbidden".to_string()),
+            )],
+            popup: Some(Popup::Error {
+                message: "Toggle suspend failed: forbidden".to_string(),
+            }),
+        });
+        assert_eq!(app.action_log.len(), 1);
+        assert_eq!(app.recent_errors.len(), 1);
+        assert!(matches!(app.popup, Popup::Error { .. }));
+        // Nothing changed, so nothing to refresh
+        assert!(!app.is_busy());
+
+        app.apply(StateUpdate::Changed {
+            logged: vec![("Resume".to_string(), apps(), Outcome::Succeeded)],
+            popup: None,
+        });
+        assert_eq!(app.action_log.len(), 2);
+        assert!(app.is_busy());
+        app.settle().await;
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::kubernetes::resources::ResourceStatus;
+    #[tokio::test]
+    async fn test_update_switch_context_without_kubeconfig() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.config.kubeconfig = Some(std::path::PathBuf::from("/nonexistent/kubeconfig"));
 
-    fn create_test_kustomization(name: &str, namespace: &str) -> Kustomization {
-        Kustomization {
-            name: name.to_string(),
-            namespace: namespace.to_string(),
-            status: ResourceStatus::Ready,
-            status_message: "Applied".to_string(),
-            revision: Some("main/abc123".to_string()),
-            suspended: false,
-            source_ref: "GitRepository/flux-system".to_string(),
-            path: "./".to_string(),
-        }
-    }
+        app.update(Action::SwitchContext).unwrap();
 
-    fn create_test_helm_release(name: &str, namespace: &str) -> HelmRelease {
-        HelmRelease {
-            name: name.to_string(),
-            namespace: namespace.to_string(),
-            status: ResourceStatus::Ready,
-            status_message: "Release reconciled".to_string(),
-            chart: "nginx".to_string(),
-            version: Some("1.0.0".to_string()),
-            suspended: false,
-            revision: Some("1".to_string()),
-        }
+        assert!(matches!(app.popup, Popup::Error { .. }));
     }
 
-    fn create_test_helm_chart(name: &str, namespace: &str) -> HelmChart {
-        HelmChart {
-            name: name.to_string(),
-            namespace: namespace.to_string(),
-            status: ResourceStatus::Ready,
-            status_message: "Chart pulled".to_string(),
-            chart: "nginx".to_string(),
-            version: Some("1.0.0".to_string()),
-            source_ref: "HelmRepository/bitnami".to_string(),
-            revision: Some("1.0.0".to_string()),
-        }
+    #[tokio::test]
+    async fn test_panicked_task_lets_refreshes_run_again() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.refreshing = true;
+        app.apply(StateUpdate::Panicked("Background task failed".to_string()));
+        assert!(app.refresh_due(std::time::Duration::ZERO));
+        assert!(matches!(app.popup, Popup::Error { .. }));
     }
 
-    // ===== Tab Index Tests (using real App) =====
+    #[tokio::test]
+    async fn test_reload_clients_failure_keeps_clients() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.config.kubeconfig = Some(std::path::PathBuf::from("/nonexistent/kubeconfig"));
 
-    #[test]
-    fn test_tab_index_kustomizations() {
-        let app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
-        assert_eq!(app.tab_index(), 0);
+        app.reload_clients();
+        app.settle().await;
+
+        assert_eq!(app.cluster_name, "test-cluster");
+        let errors: Vec<_> = app.recent_errors.iter().collect();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].1.starts_with("Failed to reload the kubeconfig"));
     }
 
     #[test]
-    fn test_tab_index_helm_releases() {
-        let app = App::for_testing(Tab::HelmReleases, vec![], vec![], vec![]);
-        assert_eq!(app.tab_index(), 1);
+    fn test_match_environment() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.config.environments = vec![Environment {
+            pattern: "prod-*".to_string(),
+            label: "PRODUCTION".to_string(),
+            color: "red".to_string(),
+        }];
+
+        app.config.context = Some("prod-eu".to_string());
+        assert_eq!(
+            app.match_environment().map(|env| env.label),
+            Some("PRODUCTION".to_string())
+        );
+
+        app.config.context = Some("dev".to_string());
+        assert!(app.match_environment().is_none());
+
+        // Any of the aggregated contexts
+        app.config.contexts = vec!["dev".to_string(), "prod-us".to_string()];
+        assert!(app.match_environment().is_some());
     }
 
     #[test]
-    fn test_tab_index_helm_charts() {
-        let app = App::for_testing(Tab::HelmCharts, vec![], vec![], vec![]);
-        assert_eq!(app.tab_index(), 2);
-    }
+    fn test_stash_and_restore_view() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![
+                create_test_kustomization("apps", "flux-system"),
+                create_test_kustomization("infra", "flux-system"),
+            ],
+            vec![],
+            vec![],
+        );
+        app.selected[0] = 1;
+        app.namespace_filter = Some("flux-system".to_string());
+        app.sort[0].cycle();
 
-    // ===== Selection Tests (using real App) =====
+        let view = app.stash_view();
+        assert!(app.kustomizations.is_empty());
+        assert_eq!(app.selected, [0; 5]);
+        assert!(app.namespace_filter.is_none());
 
-    #[test]
-    fn test_current_selected_default() {
-        let app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
-        assert_eq!(app.current_selected(), 0);
+        app.restore_view(view);
+        assert_eq!(app.kustomizations.len(), 2);
+        assert_eq!(app.selected[0], 1);
+        assert_eq!(app.namespace_filter.as_deref(), Some("flux-system"));
+        assert!(app.sort[0].key.is_some());
+    }
+
+    fn cluster_kustomization(name: &str, cluster: &str) -> Kustomization {
+        Kustomization {
+            cluster: cluster.to_string(),
+            ..create_test_kustomization(name, "flux-system")
+        }
     }
 
     #[test]
-    fn test_set_current_selected() {
+    fn test_apply_fetch_results_keeps_failed_cluster() {
         let mut app = App::for_testing(
             Tab::Kustomizations,
             vec![
-                create_test_kustomization("ks1", "default"),
-                create_test_kustomization("ks2", "default"),
-                create_test_kustomization("ks3", "default"),
+                cluster_kustomization("old-eu", "prod-eu"),
+                cluster_kustomization("old-us", "prod-us"),
             ],
             vec![],
             vec![],
         );
 
-        app.set_current_selected(2);
-        assert_eq!(app.current_selected(), 2);
+        app.apply_fetch_results(vec![
+            (
+                "prod-eu".to_string(),
+                Ok(ClusterSnapshot {
+                    kustomizations: vec![cluster_kustomization("new-eu", "prod-eu")],
+                    namespaces: vec!["flux-system".to_string()],
+                    ..Default::default()
+                }),
+            ),
+            (
+                "prod-us".to_string(),
+                Err(color_eyre::eyre::eyre!("connection refused")),
+            ),
+        ]);
+
+        let names: Vec<&str> = app.kustomizations.iter().map(|k| k.name.as_str()).collect();
+        assert_eq!(names, vec!["old-us", "new-eu"]);
+        assert_eq!(app.namespaces, vec!["flux-system"]);
+        assert_eq!(
+            app.last_error.as_deref(),
+            Some("Failed to fetch resources from prod-us: connection refused")
+        );
     }
 
     #[test]
-    fn test_selection_per_tab_independent() {
+    fn test_apply_namespace_snapshot_keeps_other_namespaces() {
         let mut app = App::for_testing(
             Tab::Kustomizations,
             vec![
-                create_test_kustomization("ks1", "default"),
-                create_test_kustomization("ks2", "default"),
+                create_test_kustomization("old-apps", "apps"),
+                create_test_kustomization("infra", "infra"),
+                create_test_kustomization("stray", "default"),
             ],
+            vec![],
+            vec![],
+        );
+
+        app.retain_namespaces(&["apps".to_string(), "infra".to_string()]);
+        app.store_snapshot(
+            "",
+            Some("apps"),
+            ClusterSnapshot {
+                kustomizations: vec![create_test_kustomization("new-apps", "apps")],
+                ..Default::default()
+            },
+            false,
+        );
+
+        let names: Vec<&str> = app.kustomizations.iter().map(|k| k.name.as_str()).collect();
+        assert_eq!(names, vec!["infra", "new-apps"]);
+    }
+
+    #[test]
+    fn test_fetched_namespace_is_shown_before_the_others_arrive() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
             vec![
-                create_test_helm_release("hr1", "default"),
-                create_test_helm_release("hr2", "default"),
-                create_test_helm_release("hr3", "default"),
+                create_test_kustomization("old-apps", "apps"),
+                create_test_kustomization("stray", "default"),
             ],
             vec![],
+            vec![],
         );
+        app.config.namespaces = vec!["apps".to_string(), "infra".to_string()];
+
+        let fetched = |namespace: &str, name: &str| {
+            StateUpdate::FetchedNamespace(Box::new(FetchedNamespace {
+                for_view: app.view_key(),
+                cluster: String::new(),
+                namespace: namespace.to_string(),
+                snapshot: ClusterSnapshot {
+                    kustomizations: vec![create_test_kustomization(name, namespace)],
+                    ..Default::default()
+                },
+                multi_cluster: false,
+            }))
+        };
+        let (apps, infra) = (fetched("apps", "new-apps"), fetched("infra", "infra"));
 
-        // Set selection for kustomizations tab
-        app.set_current_selected(1);
-        assert_eq!(app.current_selected(), 1);
+        app.apply(apps);
+        let names: Vec<&str> = app.kustomizations.iter().map(|k| k.name.as_str()).collect();
+        assert_eq!(names, vec!["new-apps"]);
 
-        // Switch to helm releases tab
-        app.tab = Tab::HelmReleases;
-        assert_eq!(app.current_selected(), 0); // Independent selection
+        app.apply(infra);
+        let names: Vec<&str> = app.kustomizations.iter().map(|k| k.name.as_str()).collect();
+        assert_eq!(names, vec!["new-apps", "infra"]);
+    }
 
-        // Set selection for helm releases
-        app.set_current_selected(2);
-        assert_eq!(app.current_selected(), 2);
+    #[test]
+    fn test_apply_fetch_results_keeps_kinds_that_failed_to_list() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![create_test_kustomization("old", "flux-system")],
+            vec![],
+            vec![],
+        );
 
-        // Switch back to kustomizations - selection preserved
-        app.tab = Tab::Kustomizations;
-        assert_eq!(app.current_selected(), 1);
+        app.apply_fetch_results(vec![(
+            String::new(),
+            Ok(ClusterSnapshot {
+                failures: vec![("Kustomization", "forbidden".to_string())],
+                ..Default::default()
+            }),
+        )]);
+
+        assert_eq!(app.kustomizations[0].name, "old");
+        assert_eq!(
+            app.load_progress.get(Tab::Kustomizations),
+            &LoadState::Failed("forbidden".to_string())
+        );
+        assert_eq!(app.load_progress.get(Tab::HelmReleases), &LoadState::Loaded);
+
+        app.apply_fetch_results(vec![(
+            String::new(),
+            Err(color_eyre::eyre::eyre!("connection refused")),
+        )]);
+        assert_eq!(
+            app.load_progress.get(Tab::HelmReleases),
+            &LoadState::Failed("unreachable".to_string())
+        );
     }
 
-    // ===== Item Count Tests (using real App) =====
-
     #[test]
-    fn test_current_item_count_empty() {
-        let app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
-        assert_eq!(app.current_item_count(), 0);
+    fn test_apply_fetch_results_tracks_failure_streaks() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        let failed = Kustomization {
+            status: ResourceStatus::Failed,
+            ..cluster_kustomization("apps", "prod")
+        };
+        let snapshot = || ClusterSnapshot {
+            kustomizations: vec![failed.clone()],
+            ..Default::default()
+        };
+
+        app.apply_fetch_results(vec![("prod".to_string(), Ok(snapshot()))]);
+        app.apply_fetch_results(vec![("prod".to_string(), Ok(snapshot()))]);
+        assert_eq!(app.failure_streaks.get(&failed), 2);
+
+        app.apply_fetch_results(vec![(
+            "prod".to_string(),
+            Ok(ClusterSnapshot {
+                kustomizations: vec![cluster_kustomization("apps", "prod")],
+                ..Default::default()
+            }),
+        )]);
+        assert_eq!(app.failure_streaks.get(&failed), 0);
+        assert_eq!(
+            app.timelines.get(&failed),
+            [
+                &ResourceStatus::Failed,
+                &ResourceStatus::Failed,
+                &ResourceStatus::Ready
+            ]
+        );
     }
 
-    #[test]
-    fn test_current_item_count_kustomizations() {
-        let app = App::for_testing(
+    #[tokio::test]
+    async fn test_cluster_filter_limits_visible_items() {
+        let mut app = App::for_testing(
             Tab::Kustomizations,
             vec![
-                create_test_kustomization("ks1", "default"),
-                create_test_kustomization("ks2", "kube-system"),
+                cluster_kustomization("eu-apps", "prod-eu"),
+                cluster_kustomization("us-apps", "prod-us"),
+                cluster_kustomization("us-infra", "prod-us"),
             ],
             vec![],
             vec![],
         );
+        assert_eq!(app.current_item_count(), 3);
+
+        app.set_current_selected(2);
+        app.update(Action::SetClusterFilter(Some("prod-us".to_string())))
+            .unwrap();
+
         assert_eq!(app.current_item_count(), 2);
+        assert_eq!(app.current_selected(), 0);
+
+        app.update(Action::Bottom).unwrap();
+        app.update(Action::Select).unwrap();
+        match &app.popup {
+            Popup::ResourceDetails { resource, .. } => assert_eq!(resource.name(), "us-infra"),
+            _ => panic!("Expected ResourceDetails popup"),
+        }
     }
 
     #[test]
-    fn test_current_item_count_helm_releases() {
-        let app = App::for_testing(
+    fn test_tab_counts() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![
+                Kustomization {
+                    status: ResourceStatus::Failed,
+                    ..cluster_kustomization("eu-apps", "prod-eu")
+                },
+                cluster_kustomization("us-apps", "prod-us"),
+                Kustomization {
+                    status: ResourceStatus::Stalled,
+                    ..cluster_kustomization("us-infra", "prod-us")
+                },
+            ],
+            vec![create_test_helm_release("frontend", "apps")],
+            vec![],
+        );
+        let counts = app.tab_counts();
+        assert_eq!(
+            counts[0],
+            TabCount {
+                total: 3,
+                failed: 2
+            }
+        );
+        assert_eq!(
+            counts[1],
+            TabCount {
+                total: 1,
+                failed: 0
+            }
+        );
+        assert_eq!(counts[2], TabCount::default());
+
+        app.cluster_filter = Some("prod-us".to_string());
+        assert_eq!(
+            app.tab_counts()[0],
+            TabCount {
+                total: 2,
+                failed: 1
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_filters_current_tab() {
+        let mut app = App::for_testing(
             Tab::HelmReleases,
             vec![],
             vec![
-                create_test_helm_release("hr1", "default"),
-                create_test_helm_release("hr2", "default"),
-                create_test_helm_release("hr3", "default"),
+                create_test_helm_release("frontend", "apps"),
+                HelmRelease {
+                    chart: "podinfo".to_string(),
+                    ..create_test_helm_release("backend", "apps")
+                },
+                create_test_helm_release("ingress", "kube-system"),
             ],
             vec![],
         );
+        app.set_current_selected(2);
+
+        app.update(Action::StartSearch).unwrap();
+        assert!(app.search_editing);
+        for c in "podinf".chars() {
+            app.update(Action::SearchInput(c)).unwrap();
+        }
+        assert_eq!(app.search_query, "podinf");
+        assert_eq!(app.current_item_count(), 1);
+        assert_eq!(app.current_selected(), 0);
+
+        app.update(Action::ConfirmSearch).unwrap();
+        assert!(!app.search_editing);
+        app.update(Action::Select).unwrap();
+        match &app.popup {
+            Popup::ResourceDetails { resource, .. } => assert_eq!(resource.name(), "backend"),
+            _ => panic!("Expected ResourceDetails popup"),
+        }
+        app.popup = Popup::None;
+
+        app.update(Action::ClearSearch).unwrap();
+        assert!(app.search_query.is_empty());
         assert_eq!(app.current_item_count(), 3);
     }
 
-    #[test]
-    fn test_current_item_count_helm_charts() {
-        let app = App::for_testing(
-            Tab::HelmCharts,
+    #[tokio::test]
+    async fn test_search_matches_namespace_and_backspace() {
+        let mut app = App::for_testing(
+            Tab::HelmReleases,
             vec![],
+            vec![
+                create_test_helm_release("frontend", "apps"),
+                create_test_helm_release("ingress", "kube-system"),
+            ],
             vec![],
-            vec![create_test_helm_chart("hc1", "default")],
         );
+        app.search_query = "kubesys".to_string();
+        assert_eq!(app.current_item_count(), 1);
+
+        app.update(Action::SearchBackspace).unwrap();
+        assert_eq!(app.search_query, "kubesy");
         assert_eq!(app.current_item_count(), 1);
     }
 
-    // ===== Get Selected Resource Tests (using real App) =====
+    fn consumer(name: &str, source: &str) -> Kustomization {
+        Kustomization {
+            source: Some(SourceRef {
+                kind: "GitRepository".to_string(),
+                name: source.to_string(),
+                namespace: "flux-system".to_string(),
+            }),
+            ..create_test_kustomization(name, "flux-system")
+        }
+    }
 
-    #[test]
-    fn test_get_selected_resource_empty() {
-        let app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
-        assert!(app.get_selected_resource().is_none());
+    #[tokio::test]
+    async fn test_filter_by_source_shows_consumers() {
+        let mut app = App::for_testing(
+            Tab::GitRepositories,
+            vec![
+                consumer("infra", "flux-system"),
+                consumer("apps", "apps-repo"),
+                consumer("tenants", "apps-repo"),
+            ],
+            vec![],
+            vec![],
+        );
+        app.git_repositories = vec![
+            GitRepository {
+                name: "flux-system".to_string(),
+                namespace: "flux-system".to_string(),
+                ..Default::default()
+            },
+            GitRepository {
+                name: "apps-repo".to_string(),
+                namespace: "flux-system".to_string(),
+                ..Default::default()
+            },
+        ];
+        app.set_current_selected(1);
+
+        app.update(Action::FilterBySource).unwrap();
+        assert_eq!(app.tab, Tab::Kustomizations);
+        let names: Vec<&str> = app
+            .visible(&app.kustomizations)
+            .iter()
+            .map(|k| k.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["apps", "tenants"]);
+        assert_eq!(
+            app.active_source_filter().unwrap().label(),
+            "consumers of GitRepository flux-system/apps-repo"
+        );
+
+        // The filter only applies to consumer tabs
+        app.tab = Tab::GitRepositories;
+        assert!(app.active_source_filter().is_none());
+        assert_eq!(app.current_item_count(), 2);
+
+        app.tab = Tab::Kustomizations;
+        app.update(Action::ClearSourceFilter).unwrap();
+        assert_eq!(app.current_item_count(), 3);
     }
 
-    #[test]
-    fn test_get_selected_resource_kustomization() {
-        let app = App::for_testing(
-            Tab::Kustomizations,
-            vec![create_test_kustomization("my-ks", "flux-system")],
+    #[tokio::test]
+    async fn test_changes_show_affected_consumers() {
+        let at = |name: &str, path: &str| Kustomization {
+            path: path.to_string(),
+            ..consumer(name, "fleet")
+        };
+        let mut app = App::for_testing(
+            Tab::GitRepositories,
+            vec![
+                at("prod", "./apps/prod"),
+                at("staging", "./apps/staging"),
+                consumer("other", "other-repo"),
+            ],
+            vec![],
             vec![],
+        );
+        let repository = GitRepository {
+            name: "fleet".to_string(),
+            namespace: "flux-system".to_string(),
+            url: "https://github.com/org/fleet".to_string(),
+            revision: Some("main@5d6e7f8".to_string()),
+            ..Default::default()
+        };
+        app.git_repositories = vec![repository.clone()];
+
+        // The revision before the current one is only known once it changed
+        app.popup = Popup::Command {
+            text: "changes".to_string(),
+        };
+        app.update(Action::RunCommand).unwrap();
+        let Popup::Error { message } = &app.popup else {
+            panic!("Expected an error, got {:?}", app.popup);
+        };
+        assert!(message.starts_with("No earlier revision of GitRepository flux-system/fleet"));
+
+        app.apply(StateUpdate::Changes(SourceFilter {
+            changes: Some(ChangeSet {
+                from: "1a2b3c4".to_string(),
+                to: "5d6e7f8".to_string(),
+                paths: vec!["apps/prod/podinfo.yaml".to_string()],
+            }),
+            ..SourceFilter::of(&repository)
+        }));
+        assert_eq!(app.tab, Tab::Kustomizations);
+        assert_eq!(app.get_selected_resource().unwrap().name(), "prod");
+        assert_eq!(app.current_item_count(), 1);
+        assert_eq!(
+            app.active_source_filter().unwrap().label(),
+            "consumers of GitRepository flux-system/fleet changed in 1a2b3c4..5d6e7f8 (1 file)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_suspend_command_previews_matching_resources() {
+        let labelled = |name: &str, team: &str, suspended: bool| Kustomization {
+            name: name.to_string(),
+            namespace: "flux-system".to_string(),
+            suspended,
+            labels: [("team".to_string(), team.to_string())].into(),
+            ..Default::default()
+        };
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![
+                labelled("payments-api", "payments", false),
+                labelled("payments-db", "payments", true),
+                labelled("search", "search", false),
+            ],
+            vec![HelmRelease {
+                name: "payments-ui".to_string(),
+                labels: [("team".to_string(), "payments".to_string())].into(),
+                ..Default::default()
+            }],
             vec![],
         );
 
-        let resource = app.get_selected_resource().expect("should have resource");
-        assert_eq!(resource.name(), "my-ks");
-        assert_eq!(resource.namespace(), "flux-system");
-        assert_eq!(resource.kind(), "Kustomization");
+        app.update(Action::OpenCommand).unwrap();
+        for c in "suspend -l team=payments".chars() {
+            app.update(Action::CommandInput(c)).unwrap();
+        }
+        app.update(Action::RunCommand).unwrap();
+        match &app.popup {
+            Popup::ConfirmBulkSuspend {
+                selector,
+                resources,
+            } => {
+                assert_eq!(selector, "team=payments");
+                let names: Vec<&str> = resources.iter().map(|r| r.name()).collect();
+                assert_eq!(names, vec!["payments-api", "payments-ui"]);
+            }
+            _ => panic!("Expected ConfirmBulkSuspend popup"),
+        }
+
+        app.update(Action::ClosePopup).unwrap();
+        app.update(Action::OpenCommand).unwrap();
+        app.update(Action::CommandInput('x')).unwrap();
+        app.update(Action::RunCommand).unwrap();
+        assert!(matches!(app.popup, Popup::Error { .. }));
     }
 
-    #[test]
-    fn test_get_selected_resource_helm_release() {
-        let app = App::for_testing(
-            Tab::HelmReleases,
-            vec![],
-            vec![create_test_helm_release("my-release", "default")],
-            vec![],
-        );
+    #[tokio::test]
+    async fn test_ref_command_needs_selected_git_repository() {
+        async fn run(app: &mut App, command: &str) {
+            app.update(Action::OpenCommand).unwrap();
+            for c in command.chars() {
+                app.update(Action::CommandInput(c)).unwrap();
+            }
+            app.update(Action::RunCommand).unwrap();
+        }
 
-        let resource = app.get_selected_resource().expect("should have resource");
-        assert_eq!(resource.name(), "my-release");
-        assert_eq!(resource.namespace(), "default");
-        assert_eq!(resource.kind(), "HelmRelease");
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.git_repositories = vec![GitRepository {
+            name: "fleet".to_string(),
+            namespace: "flux-system".to_string(),
+            git_ref: Some(GitRef::Branch("main".to_string())),
+            ..Default::default()
+        }];
+
+        run(&mut app, "ref feature/x").await;
+        assert!(matches!(app.popup, Popup::Error { .. }));
+
+        app.tab = Tab::GitRepositories;
+        run(&mut app, "ref feature/x").await;
+        match &app.popup {
+            Popup::ConfirmRefOverride {
+                repository,
+                reference,
+            } => {
+                assert_eq!(repository.name, "fleet");
+                assert_eq!(reference, &GitRef::Branch("feature/x".to_string()));
+            }
+            _ => panic!("Expected ConfirmRefOverride popup"),
+        }
+
+        app.update(Action::ClosePopup).unwrap();
+        run(&mut app, "ref revert").await;
+        match &app.popup {
+            Popup::Error { message } => assert!(message.contains("no ref override")),
+            _ => panic!("Expected Error popup"),
+        }
     }
 
-    #[test]
-    fn test_get_selected_resource_helm_chart() {
-        let app = App::for_testing(
-            Tab::HelmCharts,
+    #[tokio::test]
+    async fn test_browse_path_needs_git_repository_artifact() {
+        let kustomization = |kind: &str| Kustomization {
+            name: "apps".to_string(),
+            namespace: "flux-system".to_string(),
+            path: "./apps".to_string(),
+            source: Some(SourceRef {
+                kind: kind.to_string(),
+                name: "fleet".to_string(),
+                namespace: "flux-system".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![kustomization("OCIRepository")],
             vec![],
             vec![],
-            vec![create_test_helm_chart("my-chart", "flux-system")],
         );
+        app.update(Action::BrowsePath).unwrap();
+        match &app.popup {
+            Popup::Error { message } => {
+                assert_eq!(message, "Browsing OCIRepository artifacts is not supported")
+            }
+            _ => panic!("Expected Error popup"),
+        }
 
-        let resource = app.get_selected_resource().expect("should have resource");
-        assert_eq!(resource.name(), "my-chart");
-        assert_eq!(resource.namespace(), "flux-system");
-        assert_eq!(resource.kind(), "HelmChart");
-    }
-
-    #[test]
-    fn test_get_selected_resource_out_of_bounds() {
         let mut app = App::for_testing(
             Tab::Kustomizations,
-            vec![create_test_kustomization("only-one", "default")],
+            vec![kustomization("GitRepository")],
             vec![],
             vec![],
         );
-
-        app.set_current_selected(5); // Out of bounds
-        assert!(app.get_selected_resource().is_none());
+        app.git_repositories = vec![GitRepository {
+            name: "fleet".to_string(),
+            namespace: "flux-system".to_string(),
+            ..Default::default()
+        }];
+        app.update(Action::BrowsePath).unwrap();
+        match &app.popup {
+            Popup::Error { message } => {
+                assert_eq!(
+                    message,
+                    "GitRepository flux-system/fleet has no artifact yet"
+                )
+            }
+            _ => panic!("Expected Error popup"),
+        }
     }
 
-    // ===== App::for_testing Tests =====
-
-    #[test]
-    fn test_for_testing_constructor() {
-        let app = App::for_testing(
-            Tab::HelmReleases,
-            vec![create_test_kustomization("ks1", "default")],
-            vec![create_test_helm_release("hr1", "default")],
-            vec![create_test_helm_chart("hc1", "default")],
-        );
+    #[tokio::test]
+    async fn test_path_browser_opens_and_scrolls_files() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        let file = |path: &str, content: &str| ManifestFile {
+            path: path.to_string(),
+            content: content.to_string(),
+        };
+        app.popup = Popup::PathBrowser {
+            title: "flux-system/fleet ./apps".to_string(),
+            files: vec![file("a.yaml", "a: 1"), file("b.yaml", "a: 1\nb: 2\nc: 3")],
+            selected: 0,
+            open: false,
+            scroll: 0,
+        };
 
-        assert_eq!(app.tab, Tab::HelmReleases);
-        assert_eq!(app.kustomizations.len(), 1);
-        assert_eq!(app.helm_releases.len(), 1);
-        assert_eq!(app.helm_charts.len(), 1);
-        assert_eq!(app.cluster_name, "test-cluster");
-        assert!(!app.loading);
-        assert!(app.client.is_none());
+        app.update(Action::HighlightItem(1)).unwrap();
+        app.update(Action::OpenFile).unwrap();
+        app.update(Action::Scroll(10)).unwrap();
+        assert!(matches!(
+            app.popup,
+            Popup::PathBrowser {
+                selected: 1,
+                open: true,
+                scroll: 2,
+                ..
+            }
+        ));
+
+        app.update(Action::CloseFile).unwrap();
+        assert!(matches!(
+            app.popup,
+            Popup::PathBrowser {
+                selected: 1,
+                open: false,
+                ..
+            }
+        ));
     }
 
-    #[test]
-    fn test_for_testing_defaults() {
-        let app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+    #[tokio::test]
+    async fn test_rollback_asks_for_confirmation() {
+        let mut app = App::for_testing(Tab::HelmReleases, vec![], vec![], vec![]);
+        let revision = |revision: u32, status: &str| Revision {
+            revision,
+            status: status.to_string(),
+            chart: format!("podinfo-6.{revision}.0"),
+            ..Default::default()
+        };
+        let history = Popup::HelmHistory {
+            release: Box::default(),
+            revisions: vec![
+                revision(3, "failed"),
+                revision(2, "deployed"),
+                revision(1, "superseded"),
+            ],
+            selected: 0,
+        };
 
-        assert_eq!(app.selected, [0; 3]);
-        assert!(app.namespace_filter.is_none());
-        assert!(app.namespaces.is_empty());
-        assert!(matches!(app.popup, Popup::None));
-        assert!(app.last_error.is_none());
-    }
+        app.popup = history.clone();
+        app.update(Action::HighlightItem(1)).unwrap();
+        app.update(Action::Rollback).unwrap();
+        match &app.popup {
+            Popup::Info { message } => assert_eq!(message, "Revision 2 is already deployed"),
+            _ => panic!("Expected Info popup"),
+        }
 
-    // ===== Original Tab Tests =====
+        app.popup = history;
+        app.update(Action::HighlightItem(2)).unwrap();
+        app.update(Action::Rollback).unwrap();
+        match &app.popup {
+            Popup::ConfirmRollback { revision, .. } => assert_eq!(revision.revision, 1),
+            _ => panic!("Expected ConfirmRollback popup"),
+        }
 
-    #[test]
-    fn test_tab_default() {
-        let tab = Tab::default();
-        assert_eq!(tab, Tab::Kustomizations);
+        app.update(Action::ClosePopup).unwrap();
+        assert!(matches!(app.popup, Popup::None));
     }
 
     #[test]
-    fn test_tab_all() {
-        let tabs = Tab::all();
-        assert_eq!(tabs.len(), 3);
-        assert_eq!(tabs[0], Tab::Kustomizations);
-        assert_eq!(tabs[1], Tab::HelmReleases);
-        assert_eq!(tabs[2], Tab::HelmCharts);
-    }
+    fn test_apply_refresh_drops_stale_fetches() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        let fetched = |namespace: Option<&str>| Fetched {
+            for_view: (
+                vec!["test-cluster".to_string()],
+                namespace.map(String::from),
+            ),
+            data: FetchedData::All(vec![(
+                String::new(),
+                Ok(ClusterSnapshot {
+                    kustomizations: vec![Kustomization {
+                        name: "apps".to_string(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }),
+            )]),
+        };
 
-    #[test]
-    fn test_tab_name() {
-        assert_eq!(Tab::Kustomizations.name(), "Kustomizations");
-        assert_eq!(Tab::HelmReleases.name(), "HelmReleases");
-        assert_eq!(Tab::HelmCharts.name(), "HelmCharts");
-    }
+        // Fetched before switching to another namespace
+        app.apply_refresh(fetched(Some("flux-system")));
+        assert!(app.kustomizations.is_empty());
 
-    #[test]
-    fn test_tab_next() {
-        assert_eq!(Tab::Kustomizations.next(), Tab::HelmReleases);
-        assert_eq!(Tab::HelmReleases.next(), Tab::HelmCharts);
-        assert_eq!(Tab::HelmCharts.next(), Tab::Kustomizations);
-    }
+        assert_eq!(app.last_refreshed, None);
 
-    #[test]
-    fn test_tab_previous() {
-        assert_eq!(Tab::Kustomizations.previous(), Tab::HelmCharts);
-        assert_eq!(Tab::HelmReleases.previous(), Tab::Kustomizations);
-        assert_eq!(Tab::HelmCharts.previous(), Tab::HelmReleases);
+        app.loading = true;
+        app.apply_refresh(fetched(None));
+        assert_eq!(app.kustomizations.len(), 1);
+        assert!(!app.loading);
+        assert!(app.last_refreshed.is_some());
     }
 
     #[test]
-    fn test_tab_cycle_next() {
-        let mut tab = Tab::Kustomizations;
-        tab = tab.next();
-        assert_eq!(tab, Tab::HelmReleases);
-        tab = tab.next();
-        assert_eq!(tab, Tab::HelmCharts);
-        tab = tab.next();
-        assert_eq!(tab, Tab::Kustomizations);
-    }
+    fn test_apply_refresh_detects_new_failures() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        let fetched = |status: ResourceStatus| Fetched {
+            for_view: (vec!["test-cluster".to_string()], None),
+            data: FetchedData::All(vec![(
+                String::new(),
+                Ok(ClusterSnapshot {
+                    kustomizations: vec![Kustomization {
+                        name: "apps".to_string(),
+                        namespace: "flux-system".to_string(),
+                        status,
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }),
+            )]),
+        };
 
-    #[test]
-    fn test_tab_cycle_previous() {
-        let mut tab = Tab::Kustomizations;
-        tab = tab.previous();
-        assert_eq!(tab, Tab::HelmCharts);
-        tab = tab.previous();
-        assert_eq!(tab, Tab::HelmReleases);
-        tab = tab.previous();
-        assert_eq!(tab, Tab::Kustomizations);
-    }
+        // Failing from the start is no news
+        app.apply_refresh(fetched(ResourceStatus::Failed));
+        app.apply_refresh(fetched(ResourceStatus::Ready));
+        assert!(app.new_failures.is_empty());
+        assert!(!app.is_flashing(Instant::now()));
 
-    #[test]
-    fn test_popup_default() {
-        let popup = Popup::default();
-        assert!(matches!(popup, Popup::None));
-    }
+        app.apply_refresh(fetched(ResourceStatus::Failed));
+        assert_eq!(app.new_failures, vec!["Kustomization flux-system/apps"]);
+        assert!(app.is_flashing(Instant::now()));
+        assert!(!app.is_flashing(Instant::now() + FLASH_DURATION));
 
-    // ===== Async Update Tests =====
+        app.apply_refresh(fetched(ResourceStatus::Failed));
+        assert_eq!(app.new_failures.len(), 1);
+    }
 
-    #[tokio::test]
-    async fn test_update_next_tab() {
+    #[test]
+    fn test_apply_refresh_retries_rejected_credentials() {
         let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        let rejected = || Fetched {
+            for_view: (vec!["test-cluster".to_string()], None),
+            data: FetchedData::All(vec![(
+                String::new(),
+                Err(kube::Error::Api(kube::core::ErrorResponse {
+                    status: "Failure".to_string(),
+                    message: "Unauthorized".to_string(),
+                    reason: "Unauthorized".to_string(),
+                    code: 401,
+                })
+                .into()),
+            )]),
+        };
 
-        app.update(Action::NextTab).await.unwrap();
-        assert_eq!(app.tab, Tab::HelmReleases);
+        // The first rejection asks for new credentials without reporting
+        app.apply_refresh(rejected());
+        assert_eq!(app.reauth, Reauth::Pending);
+        assert!(app.last_error.is_none());
 
-        app.update(Action::NextTab).await.unwrap();
-        assert_eq!(app.tab, Tab::HelmCharts);
+        // Refreshes rejected while the clients are rebuilt wait for them
+        app.reauth = Reauth::Reloading;
+        app.apply_refresh(rejected());
+        assert_eq!(app.reauth, Reauth::Reloading);
+        assert!(app.last_error.is_none());
 
-        app.update(Action::NextTab).await.unwrap();
-        assert_eq!(app.tab, Tab::Kustomizations);
+        // Rejected again after rebuilding the clients: reported
+        app.reauth = Reauth::Retried;
+        app.apply_refresh(rejected());
+        assert_eq!(app.reauth, Reauth::Idle);
+        assert!(app.last_error.is_some());
     }
 
-    #[tokio::test]
-    async fn test_update_previous_tab() {
+    #[test]
+    fn test_scroll_follows_selection() {
         let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.set_current_selected(12);
+        app.scroll_to_selection(10);
+        assert_eq!(app.current_scroll(), 3);
+
+        // Moving back up within the window keeps the table still
+        app.set_current_selected(5);
+        app.scroll_to_selection(10);
+        assert_eq!(app.current_scroll(), 3);
+        app.set_current_selected(1);
+        app.scroll_to_selection(10);
+        assert_eq!(app.current_scroll(), 1);
+        assert_eq!(app.scroll[Tab::HelmReleases as usize], 0);
+    }
 
-        app.update(Action::PreviousTab).await.unwrap();
-        assert_eq!(app.tab, Tab::HelmCharts);
-
-        app.update(Action::PreviousTab).await.unwrap();
-        assert_eq!(app.tab, Tab::HelmReleases);
+    #[tokio::test]
+    async fn test_tab_completes_inputs() {
+        let kustomization = |name: &str| Kustomization {
+            name: name.to_string(),
+            namespace: "flux-system".to_string(),
+            labels: [("team".to_string(), "payments".to_string())].into(),
+            ..Default::default()
+        };
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![kustomization("apps"), kustomization("apps-staging")],
+            vec![],
+            vec![],
+        );
 
-        app.update(Action::PreviousTab).await.unwrap();
-        assert_eq!(app.tab, Tab::Kustomizations);
+        app.update(Action::StartSearch).unwrap();
+        for c in "ap".chars() {
+            app.update(Action::SearchInput(c)).unwrap();
+        }
+        app.update(Action::Complete).unwrap();
+        assert_eq!(app.search_query, "apps");
+        app.update(Action::Complete).unwrap();
+        assert_eq!(app.search_query, "apps-staging");
+        app.update(Action::CompleteBack).unwrap();
+        assert_eq!(app.search_query, "apps");
+        app.update(Action::ClearSearch).unwrap();
+
+        app.update(Action::OpenCommand).unwrap();
+        for _ in 0..3 {
+            app.update(Action::Complete).unwrap();
+        }
+        for c in " -l t".chars() {
+            app.update(Action::CommandInput(c)).unwrap();
+        }
+        app.update(Action::Complete).unwrap();
+        app.update(Action::CommandInput('=')).unwrap();
+        app.update(Action::Complete).unwrap();
+        match &app.popup {
+            Popup::Command { text } => assert_eq!(text, "suspend -l team=payments"),
+            _ => panic!("Expected Command popup"),
+        }
     }
 
     #[tokio::test]
-    async fn test_update_navigation_up() {
+    async fn test_suspend_source_asks_for_confirmation() {
         let mut app = App::for_testing(
-            Tab::Kustomizations,
+            Tab::GitRepositories,
             vec![
-                create_test_kustomization("ks1", "default"),
-                create_test_kustomization("ks2", "default"),
-                create_test_kustomization("ks3", "default"),
+                consumer("infra", "fleet"),
+                consumer("apps", "fleet"),
+                consumer("other", "other"),
             ],
             vec![],
             vec![],
         );
+        app.git_repositories = vec![GitRepository {
+            name: "fleet".to_string(),
+            namespace: "flux-system".to_string(),
+            ..Default::default()
+        }];
+        app.index_consumers();
 
-        // Start at index 2
-        app.set_current_selected(2);
-        assert_eq!(app.current_selected(), 2);
-
-        // Move up
-        app.update(Action::Up).await.unwrap();
-        assert_eq!(app.current_selected(), 1);
-
-        app.update(Action::Up).await.unwrap();
-        assert_eq!(app.current_selected(), 0);
+        app.update(Action::ToggleSuspend).unwrap();
+        match &app.popup {
+            Popup::ConfirmSuspend {
+                resource,
+                consumers,
+            } => {
+                assert_eq!(resource.name(), "fleet");
+                assert_eq!(
+                    consumers,
+                    &vec![
+                        "Kustomization flux-system/apps".to_string(),
+                        "Kustomization flux-system/infra".to_string()
+                    ]
+                );
+            }
+            _ => panic!("Expected ConfirmSuspend popup"),
+        }
 
-        // Can't go below 0
-        app.update(Action::Up).await.unwrap();
-        assert_eq!(app.current_selected(), 0);
+        app.update(Action::ClosePopup).unwrap();
+        assert!(matches!(app.popup, Popup::None));
     }
 
     #[tokio::test]
-    async fn test_update_navigation_down() {
+    async fn test_filter_by_source_ignored_on_consumer_tab() {
         let mut app = App::for_testing(
             Tab::Kustomizations,
-            vec![
-                create_test_kustomization("ks1", "default"),
-                create_test_kustomization("ks2", "default"),
-                create_test_kustomization("ks3", "default"),
-            ],
+            vec![consumer("infra", "flux-system")],
             vec![],
             vec![],
         );
+        app.update(Action::FilterBySource).unwrap();
+        assert!(app.source_filter.is_none());
+        assert_eq!(app.tab, Tab::Kustomizations);
+    }
 
-        assert_eq!(app.current_selected(), 0);
+    #[tokio::test]
+    async fn test_scroll_details_clamped() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![create_test_kustomization("apps", "flux-system")],
+            vec![],
+            vec![],
+        );
+        app.update(Action::Select).unwrap();
+        let scroll = |app: &App| match &app.popup {
+            Popup::ResourceDetails { scroll, .. } => *scroll,
+            _ => panic!("Expected ResourceDetails popup"),
+        };
 
-        app.update(Action::Down).await.unwrap();
-        assert_eq!(app.current_selected(), 1);
+        app.update(Action::Scroll(-1)).unwrap();
+        assert_eq!(scroll(&app), 0);
+        app.update(Action::Scroll(2)).unwrap();
+        assert_eq!(scroll(&app), 2);
 
-        app.update(Action::Down).await.unwrap();
-        assert_eq!(app.current_selected(), 2);
+        app.update(Action::Scroll(i32::MAX)).unwrap();
+        let last =
+            details::line_count(&app.kustomizations[0], 0, &app.state, &app.locale, false) - 1;
+        assert_eq!(usize::from(scroll(&app)), last);
 
-        // Can't go beyond last item
-        app.update(Action::Down).await.unwrap();
-        assert_eq!(app.current_selected(), 2);
+        app.update(Action::Scroll(i32::MIN)).unwrap();
+        assert_eq!(scroll(&app), 0);
     }
 
     #[tokio::test]
-    async fn test_update_navigation_down_empty() {
+    async fn test_scroll_yaml_clamped() {
         let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.popup = Popup::Yaml {
+            title: "Kustomization flux-system/apps".to_string(),
+            content: "a: 1\nb: 2\nc: 3".to_string(),
+            scroll: 0,
+        };
 
-        // Down on empty list should do nothing
-        app.update(Action::Down).await.unwrap();
-        assert_eq!(app.current_selected(), 0);
+        app.update(Action::Scroll(10)).unwrap();
+        assert!(matches!(app.popup, Popup::Yaml { scroll: 2, .. }));
+        app.update(Action::Scroll(-1)).unwrap();
+        assert!(matches!(app.popup, Popup::Yaml { scroll: 1, .. }));
     }
 
     #[tokio::test]
-    async fn test_update_top() {
+    async fn test_show_yaml_without_client() {
         let mut app = App::for_testing(
             Tab::Kustomizations,
-            vec![
-                create_test_kustomization("ks1", "default"),
-                create_test_kustomization("ks2", "default"),
-                create_test_kustomization("ks3", "default"),
-            ],
+            vec![cluster_kustomization("apps", "prod")],
             vec![],
             vec![],
         );
-
-        app.set_current_selected(2);
-
-        app.update(Action::Top).await.unwrap();
-        assert_eq!(app.current_selected(), 0);
+        app.update(Action::ShowYaml).unwrap();
+        match &app.popup {
+            Popup::Error { message } => assert_eq!(message, "Not connected to cluster prod"),
+            _ => panic!("Expected Error popup"),
+        }
     }
 
     #[tokio::test]
-    async fn test_update_bottom() {
+    async fn test_show_events_without_client() {
         let mut app = App::for_testing(
             Tab::Kustomizations,
-            vec![
-                create_test_kustomization("ks1", "default"),
-                create_test_kustomization("ks2", "default"),
-                create_test_kustomization("ks3", "default"),
-            ],
+            vec![cluster_kustomization("apps", "prod")],
             vec![],
             vec![],
         );
-
-        app.update(Action::Bottom).await.unwrap();
-        assert_eq!(app.current_selected(), 2);
+        app.update(Action::ShowEvents).unwrap();
+        match &app.popup {
+            Popup::Error { message } => assert_eq!(message, "Not connected to cluster prod"),
+            _ => panic!("Expected Error popup"),
+        }
     }
 
     #[tokio::test]
-    async fn test_update_bottom_empty() {
+    async fn test_scroll_events_popup() {
         let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.popup = Popup::Events {
+            title: "Events".to_string(),
+            events: vec![KubeEvent::default(); 3],
+            scroll: 0,
+        };
 
-        // Bottom on empty list should stay at 0
-        app.update(Action::Bottom).await.unwrap();
-        assert_eq!(app.current_selected(), 0);
+        app.update(Action::Scroll(i32::MAX)).unwrap();
+        assert!(matches!(app.popup, Popup::Events { scroll: 2, .. }));
+        app.update(Action::Scroll(-5)).unwrap();
+        assert!(matches!(app.popup, Popup::Events { scroll: 0, .. }));
     }
 
     #[tokio::test]
-    async fn test_update_select_opens_details() {
+    async fn test_show_dependencies() {
         let mut app = App::for_testing(
-            Tab::Kustomizations,
-            vec![create_test_kustomization("my-ks", "flux-system")],
+            Tab::HelmReleases,
+            vec![
+                Kustomization {
+                    depends_on: vec!["flux-system/infra".to_string()],
+                    ..create_test_kustomization("apps", "flux-system")
+                },
+                create_test_kustomization("infra", "flux-system"),
+            ],
             vec![],
             vec![],
         );
-
-        app.update(Action::Select).await.unwrap();
-
+        app.update(Action::ShowDependencies).unwrap();
         match &app.popup {
-            Popup::ResourceDetails { resource } => {
-                assert_eq!(resource.name(), "my-ks");
+            Popup::Dependencies { nodes, scroll } => {
+                assert_eq!(*scroll, 0);
+                let names: Vec<_> = nodes.iter().map(|n| (n.depth, n.name.as_str())).collect();
+                assert_eq!(names, [(0, "flux-system/infra"), (1, "flux-system/apps")]);
             }
-            _ => panic!("Expected ResourceDetails popup"),
+            _ => panic!("Expected Dependencies popup"),
         }
     }
 
     #[tokio::test]
-    async fn test_update_select_empty_no_popup() {
-        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
-
-        app.update(Action::Select).await.unwrap();
-
-        assert!(matches!(app.popup, Popup::None));
-    }
-
-    #[tokio::test]
-    async fn test_update_filter_namespace() {
-        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
-        app.namespaces = vec!["default".to_string(), "kube-system".to_string()];
-
-        app.update(Action::FilterNamespace).await.unwrap();
-
+    async fn test_show_message_selected() {
+        let message = "kustomize build failed: ".repeat(10);
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![Kustomization {
+                status_message: message.clone(),
+                ..create_test_kustomization("apps", "flux-system")
+            }],
+            vec![],
+            vec![],
+        );
+        app.update(Action::ShowMessage).unwrap();
+        app.update(Action::Scroll(i32::MAX)).unwrap();
         match &app.popup {
-            Popup::NamespaceFilter {
-                namespaces,
-                selected,
+            Popup::Message {
+                title,
+                message: shown,
+                scroll,
             } => {
-                assert_eq!(*selected, 0);
-                assert_eq!(namespaces.len(), 3); // "All namespaces" + 2 namespaces
-                assert_eq!(namespaces[0], "All namespaces");
-                assert_eq!(namespaces[1], "default");
-                assert_eq!(namespaces[2], "kube-system");
+                assert_eq!(title, "Kustomization flux-system/apps");
+                assert_eq!(shown, &message);
+                assert_eq!(
+                    usize::from(*scroll),
+                    message::wrap(&message, message::WIDTH).len() - 1
+                );
             }
-            _ => panic!("Expected NamespaceFilter popup"),
+            _ => panic!("Expected Message popup"),
         }
     }
 
     #[tokio::test]
-    async fn test_update_close_popup() {
+    async fn test_trace_selected() {
         let mut app = App::for_testing(
             Tab::Kustomizations,
-            vec![create_test_kustomization("ks1", "default")],
+            vec![Kustomization {
+                source: Some(SourceRef {
+                    kind: "GitRepository".to_string(),
+                    name: "fleet".to_string(),
+                    namespace: "flux-system".to_string(),
+                }),
+                ..create_test_kustomization("apps", "flux-system")
+            }],
             vec![],
             vec![],
         );
-
-        // Open a popup first
-        app.update(Action::Select).await.unwrap();
-        assert!(matches!(app.popup, Popup::ResourceDetails { .. }));
-
-        // Close it
-        app.update(Action::ClosePopup).await.unwrap();
-        assert!(matches!(app.popup, Popup::None));
+        app.update(Action::Trace).unwrap();
+        match &app.popup {
+            Popup::Trace { hops } => {
+                assert_eq!(hops.len(), 2);
+                assert_eq!(hops[1].name, "flux-system/fleet");
+            }
+            _ => panic!("Expected Trace popup"),
+        }
     }
 
     #[tokio::test]
-    async fn test_update_quit_noop() {
-        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+    async fn test_edit_note() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![create_test_kustomization("apps", "flux-system")],
+            vec![],
+            vec![],
+        );
 
-        // Quit action does nothing (handled in main loop)
-        app.update(Action::Quit).await.unwrap();
-        // Should not panic, just return Ok
-    }
+        app.update(Action::EditNote).unwrap();
+        for c in "known issuex".chars() {
+            app.update(Action::NoteInput(c)).unwrap();
+        }
+        app.update(Action::NoteBackspace).unwrap();
+        app.update(Action::SaveNote).unwrap();
 
-    #[tokio::test]
-    async fn test_update_noop() {
-        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        assert!(matches!(app.popup, Popup::None));
+        let note = app.state.note(&app.kustomizations[0]).unwrap();
+        assert_eq!(note.text, "known issue");
 
-        // Noop does nothing
-        app.update(Action::Noop).await.unwrap();
-        assert_eq!(app.tab, Tab::Kustomizations);
-        assert_eq!(app.current_selected(), 0);
+        // Reopening the editor starts from the saved text
+        app.update(Action::EditNote).unwrap();
+        match &app.popup {
+            Popup::EditNote { text, .. } => assert_eq!(text, "known issue"),
+            _ => panic!("Expected EditNote popup"),
+        }
     }
 
     #[tokio::test]
-    async fn test_update_set_namespace() {
-        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
-        app.popup = Popup::NamespaceFilter {
-            namespaces: vec!["All".to_string(), "default".to_string()],
-            selected: 0,
+    async fn test_acknowledge_snoozes_failure_streak() {
+        let failed = Kustomization {
+            status: ResourceStatus::Failed,
+            ..create_test_kustomization("apps", "flux-system")
+        };
+        let mut app = App::for_testing(Tab::Kustomizations, vec![failed.clone()], vec![], vec![]);
+        let refresh = |app: &mut App| {
+            app.apply_fetch_results(vec![(
+                String::new(),
+                Ok(ClusterSnapshot {
+                    kustomizations: vec![failed.clone()],
+                    ..Default::default()
+                }),
+            )])
         };
 
-        app.update(Action::SetNamespace(Some("default".to_string())))
-            .await
-            .unwrap();
-
-        assert_eq!(app.namespace_filter, Some("default".to_string()));
-        assert!(matches!(app.popup, Popup::None));
-    }
-
-    #[tokio::test]
-    async fn test_update_set_namespace_all() {
-        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
-        app.namespace_filter = Some("default".to_string());
+        refresh(&mut app);
+        assert_eq!(app.failure_streaks.get(&failed), 1);
 
-        app.update(Action::SetNamespace(None)).await.unwrap();
+        app.update(Action::Acknowledge).unwrap();
+        assert!(app.state.acknowledged_until(&failed, Utc::now()).is_some());
+        assert_eq!(app.failure_streaks.get(&failed), 0);
+        refresh(&mut app);
+        assert_eq!(app.failure_streaks.get(&failed), 0);
 
-        assert!(app.namespace_filter.is_none());
+        app.update(Action::Acknowledge).unwrap();
+        refresh(&mut app);
+        assert_eq!(app.failure_streaks.get(&failed), 1);
     }
 
     #[tokio::test]
-    async fn test_refresh_data_without_client() {
-        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
-        app.loading = true;
+    async fn test_click_tab_and_row() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![
+                create_test_kustomization("apps", "flux-system"),
+                create_test_kustomization("infra", "flux-system"),
+            ],
+            vec![],
+            vec![],
+        );
 
-        app.refresh_data().await.unwrap();
+        app.update(Action::ClickRow(1)).unwrap();
+        assert_eq!(app.current_selected(), 1);
+        // Clicks below the last row leave the selection alone
+        app.update(Action::ClickRow(5)).unwrap();
+        assert_eq!(app.current_selected(), 1);
 
-        // Without a client, refresh just sets loading to false
-        assert!(!app.loading);
+        app.update(Action::ClickTab(Tab::HelmCharts)).unwrap();
+        assert_eq!(app.tab, Tab::HelmCharts);
     }
 
-    // ===== Popup Variant Tests =====
-
     #[test]
-    fn test_popup_namespace_filter() {
-        let popup = Popup::NamespaceFilter {
-            namespaces: vec!["default".to_string(), "kube-system".to_string()],
-            selected: 1,
+    fn test_apply_daemon_state() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.namespace_filter = Some("apps".to_string());
+        let failed = Kustomization {
+            status: ResourceStatus::Failed,
+            ..create_test_kustomization("podinfo", "apps")
         };
+        let key = crate::kubernetes::resources::ResourceKey::of(&failed);
 
-        if let Popup::NamespaceFilter {
-            namespaces,
-            selected,
-        } = popup
-        {
-            assert_eq!(namespaces.len(), 2);
-            assert_eq!(selected, 1);
-        } else {
-            panic!("Expected NamespaceFilter");
-        }
-    }
-
-    #[test]
-    fn test_popup_resource_details() {
-        let ks = create_test_kustomization("test-ks", "default");
-        let popup = Popup::ResourceDetails {
-            resource: Box::new(ks),
-        };
+        app.apply_daemon_state(DaemonState {
+            kustomizations: vec![
+                failed.clone(),
+                create_test_kustomization("infra", "flux-system"),
+            ],
+            streaks: vec![(key.clone(), 4)],
+            timelines: vec![(key, vec![ResourceStatus::Ready, ResourceStatus::Failed])],
+            ..Default::default()
+        });
 
-        if let Popup::ResourceDetails { resource } = popup {
-            assert_eq!(resource.name(), "test-ks");
-            assert_eq!(resource.namespace(), "default");
-        } else {
-            panic!("Expected ResourceDetails");
-        }
+        assert_eq!(app.kustomizations.len(), 1);
+        assert_eq!(app.failure_streaks.get(&failed), 4);
+        assert_eq!(app.timelines.get(&failed).len(), 2);
     }
 
     #[test]
-    fn test_popup_reconciling() {
-        let popup = Popup::Reconciling {
-            name: "my-resource".to_string(),
-            namespace: "flux-system".to_string(),
+    fn test_reconcile_storm_banner_set_and_cleared() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.config.storm_threshold = 2;
+        let refresh = |app: &mut App, status: ResourceStatus| {
+            let kustomizations = ["apps", "infra"]
+                .iter()
+                .map(|name| Kustomization {
+                    status: status.clone(),
+                    ..create_test_kustomization(name, "flux-system")
+                })
+                .collect();
+            app.apply_fetch_results(vec![(
+                String::new(),
+                Ok(ClusterSnapshot {
+                    kustomizations,
+                    ..Default::default()
+                }),
+            )]);
         };
 
-        if let Popup::Reconciling { name, namespace } = popup {
-            assert_eq!(name, "my-resource");
-            assert_eq!(namespace, "flux-system");
-        } else {
-            panic!("Expected Reconciling");
-        }
+        refresh(&mut app, ResourceStatus::Ready);
+        assert!(app.storm.is_none());
+        refresh(&mut app, ResourceStatus::Reconciling);
+        assert_eq!(app.storm.as_ref().map(|s| s.count), Some(2));
+        refresh(&mut app, ResourceStatus::Reconciling);
+        assert!(app.storm.is_some());
+        refresh(&mut app, ResourceStatus::Ready);
+        assert!(app.storm.is_none());
     }
 
-    #[test]
-    fn test_popup_error() {
-        let popup = Popup::Error {
-            message: "Something went wrong".to_string(),
-        };
+    #[tokio::test]
+    async fn test_sort_is_per_tab() {
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![
+                create_test_kustomization("infra", "flux-system"),
+                Kustomization {
+                    status: ResourceStatus::Failed,
+                    ..create_test_kustomization("apps", "flux-system")
+                },
+            ],
+            vec![],
+            vec![],
+        );
+        app.set_current_selected(1);
 
-        if let Popup::Error { message } = popup {
-            assert_eq!(message, "Something went wrong");
-        } else {
-            panic!("Expected Error");
+        app.update(Action::CycleSort).unwrap();
+        app.update(Action::CycleSort).unwrap();
+        app.update(Action::CycleSort).unwrap();
+        assert_eq!(app.current_sort().key, Some(SortKey::Status));
+        assert_eq!(app.current_selected(), 0);
+        let names: Vec<&str> = app
+            .visible(&app.kustomizations)
+            .iter()
+            .map(|k| k.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["apps", "infra"]);
+
+        app.update(Action::ReverseSort).unwrap();
+        app.update(Action::Select).unwrap();
+        match &app.popup {
+            Popup::ResourceDetails { resource, .. } => assert_eq!(resource.name(), "infra"),
+            _ => panic!("Expected ResourceDetails popup"),
         }
-    }
-
-    // ===== Additional App Field Tests =====
-
-    #[test]
-    fn test_app_loading_state() {
-        let app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
-        assert!(!app.loading);
-    }
-
-    #[test]
-    fn test_app_last_error() {
-        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
-        assert!(app.last_error.is_none());
-
-        app.last_error = Some("Test error".to_string());
-        assert_eq!(app.last_error, Some("Test error".to_string()));
-    }
+        app.popup = Popup::None;
 
-    #[test]
-    fn test_app_cluster_name() {
-        let app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
-        assert_eq!(app.cluster_name, "test-cluster");
+        app.update(Action::NextTab).unwrap();
+        assert_eq!(app.current_sort(), SortState::default());
     }
 
-    #[test]
-    fn test_app_multiple_resources() {
-        let app = App::for_testing(
+    #[tokio::test]
+    async fn test_status_filter_is_per_tab() {
+        let mut app = App::for_testing(
             Tab::Kustomizations,
             vec![
-                create_test_kustomization("ks1", "ns1"),
-                create_test_kustomization("ks2", "ns2"),
-            ],
-            vec![create_test_helm_release("hr1", "ns1")],
-            vec![
-                create_test_helm_chart("hc1", "ns1"),
-                create_test_helm_chart("hc2", "ns2"),
-                create_test_helm_chart("hc3", "ns3"),
+                create_test_kustomization("infra", "flux-system"),
+                Kustomization {
+                    status: ResourceStatus::Failed,
+                    ..create_test_kustomization("apps", "flux-system")
+                },
             ],
+            vec![],
+            vec![],
         );
+        app.set_current_selected(1);
 
-        assert_eq!(app.kustomizations.len(), 2);
-        assert_eq!(app.helm_releases.len(), 1);
-        assert_eq!(app.helm_charts.len(), 3);
+        app.update(Action::CycleStatusFilter).unwrap();
+        assert_eq!(app.current_status_filter(), StatusFilter::Failed);
+        assert_eq!(app.current_selected(), 0);
+        let names: Vec<&str> = app
+            .visible(&app.kustomizations)
+            .iter()
+            .map(|k| k.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["apps"]);
+
+        app.update(Action::NextTab).unwrap();
+        assert_eq!(app.current_status_filter(), StatusFilter::All);
+        app.update(Action::PreviousTab).unwrap();
+        assert_eq!(app.current_status_filter(), StatusFilter::Failed);
     }
 
-    // ===== Tab Edge Cases =====
+    #[tokio::test]
+    async fn test_search_cleared_on_tab_switch() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        app.search_query = "apps".to_string();
+        app.search_editing = true;
 
-    #[test]
-    fn test_tab_as_usize() {
-        // Verify Tab can be converted to usize for indexing
-        assert_eq!(Tab::Kustomizations as usize, 0);
-        assert_eq!(Tab::HelmReleases as usize, 1);
-        assert_eq!(Tab::HelmCharts as usize, 2);
+        app.update(Action::NextTab).unwrap();
+        assert!(app.search_query.is_empty());
+        assert!(!app.search_editing);
     }
 
-    #[test]
-    fn test_app_selected_array_access() {
+    #[tokio::test]
+    async fn test_filter_cluster_requires_multi_cluster() {
         let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
 
-        // Direct access to selected array
-        app.selected[0] = 5;
-        app.selected[1] = 10;
-        app.selected[2] = 15;
-
-        assert_eq!(app.selected[0], 5);
-        assert_eq!(app.selected[1], 10);
-        assert_eq!(app.selected[2], 15);
-
-        // Verify current_selected works with direct access
-        app.tab = Tab::Kustomizations;
-        assert_eq!(app.current_selected(), 5);
-
-        app.tab = Tab::HelmReleases;
-        assert_eq!(app.current_selected(), 10);
+        app.update(Action::FilterCluster).unwrap();
 
-        app.tab = Tab::HelmCharts;
-        assert_eq!(app.current_selected(), 15);
+        assert!(matches!(app.popup, Popup::None));
     }
 
     #[tokio::test]
-    async fn test_update_select_helm_release() {
-        let mut app = App::for_testing(
-            Tab::HelmReleases,
-            vec![],
-            vec![create_test_helm_release("my-release", "default")],
-            vec![],
-        );
+    async fn test_update_select_git_repository() {
+        let mut app = App::for_testing(Tab::GitRepositories, vec![], vec![], vec![]);
+        app.git_repositories = vec![GitRepository {
+            name: "fleet".to_string(),
+            namespace: "flux-system".to_string(),
+            ..Default::default()
+        }];
 
-        app.update(Action::Select).await.unwrap();
+        app.update(Action::Select).unwrap();
 
-        if let Popup::ResourceDetails { resource } = &app.popup {
-            assert_eq!(resource.name(), "my-release");
-            assert_eq!(resource.kind(), "HelmRelease");
+        if let Popup::ResourceDetails { resource, .. } = &app.popup {
+            assert_eq!(resource.name(), "fleet");
+            assert_eq!(resource.kind(), "GitRepository");
+            assert!(!resource.details().is_empty());
         } else {
             panic!("Expected ResourceDetails popup");
         }
@@ -1242,9 +6211,9 @@ mod tests {
             vec![create_test_helm_chart("my-chart", "flux-system")],
         );
 
-        app.update(Action::Select).await.unwrap();
+        app.update(Action::Select).unwrap();
 
-        if let Popup::ResourceDetails { resource } = &app.popup {
+        if let Popup::ResourceDetails { resource, .. } = &app.popup {
             assert_eq!(resource.name(), "my-chart");
             assert_eq!(resource.kind(), "HelmChart");
         } else {
@@ -0,0 +1,217 @@
+//! Local state kept across sessions
+//!
+//! Stored in `<state dir>/flux-tui/state.toml` (e.g.
+//! `~/.local/state/flux-tui/state.toml` on Linux). Unlike the config file,
+//! flux-tui writes this file itself.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use color_eyre::{eyre::eyre, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::kubernetes::resources::{FluxResource, ResourceKey};
+
+/// State persisted between sessions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StateFile {
+    /// Operator notes attached to resources
+    pub notes: Vec<Note>,
+
+    /// Failing resources acknowledged as known issues
+    pub acks: Vec<Ack>,
+
+    /// Whether the onboarding tour was finished or dismissed
+    pub tour_done: bool,
+}
+
+/// A local note attached to a resource (e.g. "known issue, waiting on upstream")
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Note {
+    /// The annotated resource
+    pub resource: ResourceKey,
+
+    /// Note text
+    pub text: String,
+
+    /// When the note was last edited
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A resource snoozed from failure counts until a point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ack {
+    /// The acknowledged resource
+    pub resource: ResourceKey,
+
+    /// When the acknowledgement expires
+    pub until: DateTime<Utc>,
+}
+
+impl StateFile {
+    /// Default location of the state file
+    pub fn path() -> Option<PathBuf> {
+        dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .map(|dir| dir.join("flux-tui").join("state.toml"))
+    }
+
+    /// Load the state file, falling back to empty state if it doesn't exist
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(path)?;
+        toml::from_str(&contents).map_err(|e| eyre!("Invalid state file {}: {}", path.display(), e))
+    }
+
+    /// Write the state file, creating its directory if needed
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Note attached to a resource
+    pub fn note(&self, resource: &dyn FluxResource) -> Option<&Note> {
+        let key = ResourceKey::of(resource);
+        self.notes.iter().find(|note| note.resource == key)
+    }
+
+    /// Attach a note to a resource, replacing any previous one
+    ///
+    /// Blank text removes the note.
+    pub fn set_note(&mut self, resource: &dyn FluxResource, text: &str, now: DateTime<Utc>) {
+        let key = ResourceKey::of(resource);
+        self.notes.retain(|note| note.resource != key);
+
+        let text = text.trim();
+        if !text.is_empty() {
+            self.notes.push(Note {
+                resource: key,
+                text: text.to_string(),
+                updated_at: now,
+            });
+        }
+    }
+
+    /// When the acknowledgement of a resource expires, if it is acknowledged
+    pub fn acknowledged_until(
+        &self,
+        resource: &dyn FluxResource,
+        now: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let key = ResourceKey::of(resource);
+        self.acks
+            .iter()
+            .find(|ack| ack.resource == key && ack.until > now)
+            .map(|ack| ack.until)
+    }
+
+    /// Acknowledge a resource until `until`, or lift a current acknowledgement
+    ///
+    /// Expired acknowledgements are dropped. Returns whether the resource is
+    /// acknowledged afterwards.
+    pub fn toggle_ack(
+        &mut self,
+        resource: &dyn FluxResource,
+        until: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        let acknowledged = self.acknowledged_until(resource, now).is_some();
+        let key = ResourceKey::of(resource);
+        self.acks
+            .retain(|ack| ack.until > now && ack.resource != key);
+
+        if !acknowledged {
+            self.acks.push(Ack {
+                resource: key,
+                until,
+            });
+        }
+        !acknowledged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::Kustomization;
+
+    fn kustomization(name: &str) -> Kustomization {
+        Kustomization {
+            name: name.to_string(),
+            namespace: "flux-system".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_set_note_replaces_and_removes() {
+        let mut state = StateFile::default();
+        let apps = kustomization("apps");
+        let now = Utc::now();
+
+        state.set_note(&apps, "known issue", now);
+        state.set_note(&apps, "  waiting on upstream fix ", now);
+        assert_eq!(state.notes.len(), 1);
+        assert_eq!(state.note(&apps).unwrap().text, "waiting on upstream fix");
+        assert!(state.note(&kustomization("infra")).is_none());
+
+        state.set_note(&apps, "   ", now);
+        assert!(state.note(&apps).is_none());
+    }
+
+    #[test]
+    fn test_toggle_ack() {
+        let mut state = StateFile::default();
+        let apps = kustomization("apps");
+        let now = Utc::now();
+        let until = now + chrono::Duration::hours(4);
+
+        assert!(state.toggle_ack(&apps, until, now));
+        assert_eq!(state.acknowledged_until(&apps, now), Some(until));
+        assert!(state
+            .acknowledged_until(&kustomization("infra"), now)
+            .is_none());
+
+        // Expired acknowledgements no longer count, and are dropped
+        let later = until + chrono::Duration::minutes(1);
+        assert!(state.acknowledged_until(&apps, later).is_none());
+        assert!(state.toggle_ack(
+            &kustomization("infra"),
+            later + chrono::Duration::hours(1),
+            later
+        ));
+        assert_eq!(state.acks.len(), 1);
+
+        assert!(!state.toggle_ack(&kustomization("infra"), until, later));
+        assert!(state.acks.is_empty());
+    }
+
+    #[test]
+    fn test_round_trip_through_toml() {
+        let mut state = StateFile::default();
+        state.set_note(&kustomization("apps"), "known issue", Utc::now());
+        state.toggle_ack(&kustomization("apps"), Utc::now(), Utc::now());
+
+        let parsed: StateFile = toml::from_str(&toml::to_string(&state).unwrap()).unwrap();
+        assert_eq!(parsed, state);
+    }
+
+    #[test]
+    fn test_path_is_under_flux_tui() {
+        if let Some(path) = StateFile::path() {
+            assert!(path.ends_with("flux-tui/state.toml"));
+        }
+    }
+
+    #[test]
+    fn test_load_missing_file_is_empty() {
+        let state = StateFile::load(Path::new("/nonexistent/flux-tui/state.toml")).unwrap();
+        assert_eq!(state, StateFile::default());
+    }
+}
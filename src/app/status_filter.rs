@@ -0,0 +1,131 @@
+//! Per-tab status filter, to hide healthy rows during incidents
+
+use crate::kubernetes::resources::{FluxResource, ResourceStatus};
+
+/// Statuses a tab shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatusFilter {
+    #[default]
+    All,
+    /// Failed or stalled, including suspended resources whose last reconcile failed
+    Failed,
+    /// Stalled: the controller gave up until the spec changes
+    Stalled,
+    /// Anything but Ready
+    NotReady,
+    Suspended,
+}
+
+impl StatusFilter {
+    /// Cycle to the next filter: all → failed → stalled → not ready →
+    /// suspended → all
+    pub fn cycle(&mut self) {
+        *self = match self {
+            StatusFilter::All => StatusFilter::Failed,
+            StatusFilter::Failed => StatusFilter::Stalled,
+            StatusFilter::Stalled => StatusFilter::NotReady,
+            StatusFilter::NotReady => StatusFilter::Suspended,
+            StatusFilter::Suspended => StatusFilter::All,
+        };
+    }
+
+    /// Whether a resource passes the filter
+    pub fn matches(&self, resource: &dyn FluxResource) -> bool {
+        match self {
+            StatusFilter::All => true,
+            StatusFilter::Failed => matches!(
+                resource.status(),
+                ResourceStatus::Failed | ResourceStatus::Stalled | ResourceStatus::SuspendedFailed
+            ),
+            StatusFilter::Stalled => *resource.status() == ResourceStatus::Stalled,
+            StatusFilter::NotReady => !resource.is_ready(),
+            StatusFilter::Suspended => resource.is_suspended(),
+        }
+    }
+
+    /// Table title while filtering (None = all statuses shown)
+    pub fn label(&self) -> Option<&'static str> {
+        match self {
+            StatusFilter::All => None,
+            StatusFilter::Failed => Some("Failed"),
+            StatusFilter::Stalled => Some("Stalled"),
+            StatusFilter::NotReady => Some("Not Ready"),
+            StatusFilter::Suspended => Some("Suspended"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::Kustomization;
+
+    fn kustomization(status: ResourceStatus, suspended: bool) -> Kustomization {
+        Kustomization {
+            status,
+            suspended,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cycle() {
+        let mut filter = StatusFilter::default();
+        let mut labels = vec![];
+        for _ in 0..5 {
+            filter.cycle();
+            labels.push(filter.label());
+        }
+        assert_eq!(
+            labels,
+            vec![
+                Some("Failed"),
+                Some("Stalled"),
+                Some("Not Ready"),
+                Some("Suspended"),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_matches() {
+        let ready = kustomization(ResourceStatus::Ready, false);
+        let failed = kustomization(ResourceStatus::Failed, false);
+        let stalled = kustomization(ResourceStatus::Stalled, false);
+        let reconciling = kustomization(ResourceStatus::Reconciling, false);
+        let suspended = kustomization(ResourceStatus::Suspended, true);
+        let suspended_failed = kustomization(ResourceStatus::SuspendedFailed, true);
+
+        let shown = |filter: StatusFilter| {
+            [
+                &ready,
+                &failed,
+                &stalled,
+                &reconciling,
+                &suspended,
+                &suspended_failed,
+            ]
+            .iter()
+            .map(|k| filter.matches(*k))
+            .collect::<Vec<_>>()
+        };
+        assert_eq!(shown(StatusFilter::All), vec![true; 6]);
+        assert_eq!(
+            shown(StatusFilter::Failed),
+            vec![false, true, true, false, false, true]
+        );
+        assert_eq!(
+            shown(StatusFilter::Stalled),
+            vec![false, false, true, false, false, false]
+        );
+        assert_eq!(
+            shown(StatusFilter::NotReady),
+            vec![false, true, true, true, true, true]
+        );
+        assert_eq!(
+            shown(StatusFilter::Suspended),
+            vec![false, false, false, false, true, true]
+        );
+    }
+}
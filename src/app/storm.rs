@@ -0,0 +1,177 @@
+//! Reconcile storm detection
+//!
+//! A change to a shared base (e.g. a commit to the fleet repository) makes
+//! many resources start reconciling at once. Spotting that, and the source
+//! most of them consume, helps correlate the churn to a commit.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::kubernetes::resources::{FluxResource, ResourceStatus, SourceRef};
+
+use super::{timeline::StatusTimelines, trace::Sources};
+
+/// Many resources of a cluster started reconciling in the same refresh
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReconcileStorm {
+    /// Cluster the storm happened in
+    pub cluster: String,
+
+    /// Resources that started reconciling
+    pub count: usize,
+
+    /// Source consumed by most of them, as "Kind namespace/name"
+    pub source: Option<String>,
+
+    /// Revision of that source
+    pub revision: Option<String>,
+}
+
+impl ReconcileStorm {
+    /// Banner text, e.g. "Reconcile storm: 14 resources started reconciling
+    /// after GitRepository flux-system/fleet main@sha1:abc"
+    pub fn message(&self) -> String {
+        let mut message = format!(
+            "Reconcile storm: {} resources started reconciling",
+            self.count
+        );
+        if let Some(source) = &self.source {
+            message.push_str(&format!(" after {source}"));
+            if let Some(revision) = &self.revision {
+                message.push_str(&format!(" {revision}"));
+            }
+        }
+        message
+    }
+}
+
+/// Detect a storm among a cluster's resources, after their latest status was
+/// recorded in `timelines`
+///
+/// Only resources that were seen in the previous refresh and not reconciling
+/// then count, so the first load of a busy cluster is not a storm. A threshold
+/// of 0 disables detection.
+pub fn detect(
+    cluster: &str,
+    resources: &[&dyn FluxResource],
+    timelines: &StatusTimelines,
+    sources: &Sources,
+    threshold: usize,
+) -> Option<ReconcileStorm> {
+    let started: Vec<&dyn FluxResource> = resources
+        .iter()
+        .copied()
+        .filter(|resource| {
+            let timeline = timelines.get(*resource);
+            matches!(
+                timeline[..],
+                [.., previous, ResourceStatus::Reconciling]
+                    if *previous != ResourceStatus::Reconciling
+            )
+        })
+        .collect();
+    if threshold == 0 || started.len() < threshold {
+        return None;
+    }
+
+    // The most consumed source, and a consumer to trace it from
+    let mut consumers: HashMap<&SourceRef, (usize, &dyn FluxResource)> = HashMap::new();
+    for resource in &started {
+        if let Some(source) = resource.source() {
+            consumers.entry(source).or_insert((0, *resource)).0 += 1;
+        }
+    }
+    let top = consumers
+        .into_iter()
+        .max_by_key(|(source, (count, _))| (*count, std::cmp::Reverse(source.name.clone())));
+
+    Some(ReconcileStorm {
+        cluster: cluster.to_string(),
+        count: started.len(),
+        source: top
+            .as_ref()
+            .map(|(source, _)| format!("{} {}/{}", source.kind, source.namespace, source.name)),
+        revision: top
+            .and_then(|(_, (_, consumer))| sources.trace(consumer).last()?.revision.clone()),
+    })
+}
+
+/// Whether a storm is still going on: enough of the cluster's resources are
+/// still reconciling
+pub fn is_ongoing(resources: &[&dyn FluxResource], threshold: usize) -> bool {
+    resources
+        .iter()
+        .filter(|r| *r.status() == ResourceStatus::Reconciling)
+        .count()
+        >= threshold.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::{GitRepository, Kustomization};
+
+    fn kustomization(name: &str, status: ResourceStatus) -> Kustomization {
+        Kustomization {
+            name: name.to_string(),
+            namespace: "flux-system".to_string(),
+            status,
+            source: Some(SourceRef {
+                kind: "GitRepository".to_string(),
+                name: "fleet".to_string(),
+                namespace: "flux-system".to_string(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn as_dyn(items: &[Kustomization]) -> Vec<&dyn FluxResource> {
+        items.iter().map(|k| k as &dyn FluxResource).collect()
+    }
+
+    #[test]
+    fn test_detect_storm_with_source_revision() {
+        let repositories = [GitRepository {
+            name: "fleet".to_string(),
+            namespace: "flux-system".to_string(),
+            revision: Some("main@sha1:abc".to_string()),
+            ..Default::default()
+        }];
+        let sources = Sources {
+            helm_charts: &[],
+            git_repositories: &repositories,
+            helm_repositories: &[],
+        };
+        let ready: Vec<Kustomization> = ["a", "b", "c"]
+            .iter()
+            .map(|n| kustomization(n, ResourceStatus::Ready))
+            .collect();
+        let reconciling: Vec<Kustomization> = ["a", "b", "c"]
+            .iter()
+            .map(|n| kustomization(n, ResourceStatus::Reconciling))
+            .collect();
+
+        // The first load of a busy cluster is not a storm
+        let mut timelines = StatusTimelines::new(10);
+        timelines.record("", as_dyn(&reconciling));
+        assert_eq!(
+            detect("", &as_dyn(&reconciling), &timelines, &sources, 3),
+            None
+        );
+
+        let mut timelines = StatusTimelines::new(10);
+        timelines.record("", as_dyn(&ready));
+        timelines.record("", as_dyn(&reconciling));
+        let storm = detect("", &as_dyn(&reconciling), &timelines, &sources, 3).unwrap();
+        assert_eq!(storm.count, 3);
+        assert_eq!(
+            storm.message(),
+            "Reconcile storm: 3 resources started reconciling after GitRepository flux-system/fleet main@sha1:abc"
+        );
+        assert!(detect("", &as_dyn(&reconciling), &timelines, &sources, 4).is_none());
+
+        assert!(is_ongoing(&as_dyn(&reconciling), 3));
+        assert!(!is_ongoing(&as_dyn(&ready), 3));
+    }
+}
@@ -0,0 +1,181 @@
+//! Per-resource failure streaks
+//!
+//! Counts how many refreshes in a row a resource has been seen failing while
+//! the TUI is open, so a one-off blip can be told apart from a persistent
+//! failure. A resource counts as failing when it is Failed or Stalled, or
+//! when its controller has not observed the latest spec generation.
+
+use std::collections::HashMap;
+
+use crate::kubernetes::resources::{FluxResource, ResourceKey, ResourceStatus};
+
+/// Streak length from which a failure is considered persistent
+pub const PERSISTENT_STREAK: u32 = 3;
+
+/// Consecutive failing refreshes per resource
+#[derive(Debug, Default)]
+pub struct FailureStreaks {
+    counts: HashMap<ResourceKey, u32>,
+}
+
+impl FailureStreaks {
+    /// Record one refresh of a cluster's resources
+    ///
+    /// Streaks of failing resources grow by one; healthy resources and
+    /// resources that no longer exist are dropped. Other clusters are left
+    /// untouched so a cluster that failed to refresh keeps its streaks.
+    pub fn record<'a>(
+        &mut self,
+        cluster: &str,
+        resources: impl IntoIterator<Item = &'a dyn FluxResource>,
+    ) {
+        let updated: Vec<(ResourceKey, u32)> = resources
+            .into_iter()
+            .filter(|resource| is_failing(*resource))
+            .map(|resource| {
+                let key = ResourceKey::of(resource);
+                let count = self.counts.get(&key).copied().unwrap_or(0) + 1;
+                (key, count)
+            })
+            .collect();
+
+        self.counts.retain(|key, _| key.cluster != cluster);
+        self.counts.extend(updated);
+    }
+
+    /// Reset the streak of a resource
+    pub fn forget(&mut self, resource: &dyn FluxResource) {
+        self.counts.remove(&ResourceKey::of(resource));
+    }
+
+    /// All streaks, to hand over to an attached TUI
+    pub fn entries(&self) -> Vec<(ResourceKey, u32)> {
+        self.counts
+            .iter()
+            .map(|(key, count)| (key.clone(), *count))
+            .collect()
+    }
+
+    /// Streaks handed over by the daemon
+    pub fn from_entries(entries: Vec<(ResourceKey, u32)>) -> Self {
+        Self {
+            counts: entries.into_iter().collect(),
+        }
+    }
+
+    /// Current streak for a resource (0 = not failing)
+    pub fn get(&self, resource: &dyn FluxResource) -> u32 {
+        self.counts
+            .get(&ResourceKey::of(resource))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+/// Check whether a resource counts towards its failure streak
+fn is_failing(resource: &dyn FluxResource) -> bool {
+    if resource.is_suspended() {
+        return false;
+    }
+    matches!(
+        resource.status(),
+        ResourceStatus::Failed | ResourceStatus::Stalled
+    ) || resource.has_unobserved_generation()
+}
+
+/// Describe a streak for the details popup (e.g. "4 refreshes (persistent)")
+pub fn describe(streak: u32) -> String {
+    match streak {
+        0 => "none".to_string(),
+        1 => "1 refresh (blip)".to_string(),
+        n if n < PERSISTENT_STREAK => format!("{n} refreshes (blip)"),
+        n => format!("{n} refreshes (persistent)"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::Kustomization;
+
+    fn kustomization(name: &str, cluster: &str, status: ResourceStatus) -> Kustomization {
+        Kustomization {
+            name: name.to_string(),
+            namespace: "flux-system".to_string(),
+            cluster: cluster.to_string(),
+            status,
+            ..Default::default()
+        }
+    }
+
+    fn record(streaks: &mut FailureStreaks, cluster: &str, items: &[Kustomization]) {
+        streaks.record(cluster, items.iter().map(|k| k as &dyn FluxResource));
+    }
+
+    #[test]
+    fn test_streak_grows_while_failing() {
+        let mut streaks = FailureStreaks::default();
+        let failed = [kustomization("apps", "prod", ResourceStatus::Failed)];
+        for _ in 0..3 {
+            record(&mut streaks, "prod", &failed);
+        }
+        assert_eq!(streaks.get(&failed[0]), 3);
+    }
+
+    #[test]
+    fn test_streak_resets_when_healthy() {
+        let mut streaks = FailureStreaks::default();
+        let failed = [kustomization("apps", "prod", ResourceStatus::Failed)];
+        record(&mut streaks, "prod", &failed);
+        record(&mut streaks, "prod", &failed);
+
+        let ready = [kustomization("apps", "prod", ResourceStatus::Ready)];
+        record(&mut streaks, "prod", &ready);
+        assert_eq!(streaks.get(&ready[0]), 0);
+
+        record(&mut streaks, "prod", &failed);
+        assert_eq!(streaks.get(&failed[0]), 1);
+    }
+
+    #[test]
+    fn test_streak_counts_stalled_and_unobserved_generation() {
+        let mut streaks = FailureStreaks::default();
+        let items = [
+            kustomization("stalled", "prod", ResourceStatus::Stalled),
+            Kustomization {
+                generation: Some(5),
+                observed_generation: Some(4),
+                ..kustomization("wedged", "prod", ResourceStatus::Ready)
+            },
+            Kustomization {
+                suspended: true,
+                ..kustomization("paused", "prod", ResourceStatus::Failed)
+            },
+        ];
+        record(&mut streaks, "prod", &items);
+        assert_eq!(streaks.get(&items[0]), 1);
+        assert_eq!(streaks.get(&items[1]), 1);
+        assert_eq!(streaks.get(&items[2]), 0);
+    }
+
+    #[test]
+    fn test_streak_other_clusters_untouched() {
+        let mut streaks = FailureStreaks::default();
+        let eu = [kustomization("apps", "prod-eu", ResourceStatus::Failed)];
+        let us = [kustomization("apps", "prod-us", ResourceStatus::Failed)];
+        record(&mut streaks, "prod-eu", &eu);
+        record(&mut streaks, "prod-us", &us);
+        record(&mut streaks, "prod-us", &us);
+
+        assert_eq!(streaks.get(&eu[0]), 1);
+        assert_eq!(streaks.get(&us[0]), 2);
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(describe(0), "none");
+        assert_eq!(describe(1), "1 refresh (blip)");
+        assert_eq!(describe(2), "2 refreshes (blip)");
+        assert_eq!(describe(3), "3 refreshes (persistent)");
+    }
+}
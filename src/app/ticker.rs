@@ -0,0 +1,245 @@
+//! One-line status ticker (`flux-tui --ticker`)
+//!
+//! Refreshes like the TUI and prints a single summary line, e.g.
+//! "prod: 41 ready, 2 failing, 3 suspended · HelmRelease apps/podinfo: install
+//! retries exhausted", for a tmux status line or a narrow pane. A new line is
+//! only printed when the summary changes. In a terminal it overwrites the
+//! previous one; piped (tmux `#()` reads the latest line) each is printed on
+//! its own line.
+
+use std::io::{IsTerminal, Write};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use color_eyre::Result;
+use serde::Serialize;
+
+use crate::kubernetes::resources::{kind::truncate, FluxResource, ResourceStatus};
+
+use super::{config::Config, state::App};
+
+/// Longest status message shown for the last failure
+const MESSAGE_LEN: usize = 60;
+
+/// Refresh and print the summary until interrupted
+pub async fn run(config: &Config) -> Result<()> {
+    let mut app = App::new(config).await?;
+    let mut out = std::io::stdout();
+    let overwrite = out.is_terminal();
+    let mut last = None;
+
+    let mut interval = tokio::time::interval(Duration::from_secs(config.refresh_interval.max(1)));
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                app.refresh_data().await?;
+                let summary = Summary::of(&app).line();
+                if last.as_ref() != Some(&summary) {
+                    if overwrite {
+                        // Back to the line start and clear it
+                        write!(out, "\r\x1b[2K{summary}")?;
+                    } else {
+                        writeln!(out, "{summary}")?;
+                    }
+                    out.flush()?;
+                    last = Some(summary);
+                }
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+    if overwrite {
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Resource counts and the latest failure of the shown clusters
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Summary {
+    pub cluster: String,
+    pub ready: usize,
+    pub failing: usize,
+    pub reconciling: usize,
+    pub suspended: usize,
+
+    /// The failure that started most recently
+    pub last_failure: Option<Failure>,
+
+    /// Why the last refresh failed
+    pub error: Option<String>,
+}
+
+/// A failing resource
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Failure {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+
+    /// First line of the status message
+    pub message: String,
+}
+
+impl Summary {
+    /// Summary of the app's current state
+    pub fn of(app: &App) -> Self {
+        let resources = app.all_resources();
+        let count = |matches: fn(&dyn FluxResource) -> bool| {
+            resources.iter().filter(|r| matches(**r)).count()
+        };
+        Self {
+            cluster: app
+                .cluster_filter
+                .as_deref()
+                .unwrap_or(&app.cluster_name)
+                .to_string(),
+            ready: count(|r| !r.is_suspended() && r.is_ready()),
+            failing: count(is_failing),
+            reconciling: count(|r| !r.is_suspended() && *r.status() == ResourceStatus::Reconciling),
+            suspended: count(|r| r.is_suspended()),
+            last_failure: last_failure(&resources).map(|r| Failure {
+                kind: r.kind().to_string(),
+                namespace: r.namespace().to_string(),
+                name: r.name().to_string(),
+                message: first_line(r.status_message()).to_string(),
+            }),
+            error: app.last_error.as_deref().map(first_line).map(String::from),
+        }
+    }
+
+    /// Counts, e.g. "41 ready, 2 failing"; states no resource is in are left
+    /// out, except ready
+    pub fn counts(&self) -> String {
+        let counts = [
+            (self.ready, "ready"),
+            (self.failing, "failing"),
+            (self.reconciling, "reconciling"),
+            (self.suspended, "suspended"),
+        ];
+        let counts: Vec<String> = counts
+            .iter()
+            .filter(|(n, label)| *n > 0 || *label == "ready")
+            .map(|(n, label)| format!("{n} {label}"))
+            .collect();
+        counts.join(", ")
+    }
+
+    /// The ticker line, e.g. "prod: 41 ready, 2 failing · HelmRelease
+    /// apps/podinfo: install retries exhausted"
+    pub fn line(&self) -> String {
+        if let Some(error) = &self.error {
+            return format!("{}: {error}", self.cluster);
+        }
+        let mut line = format!("{}: {}", self.cluster, self.counts());
+        if let Some(failure) = &self.last_failure {
+            line.push_str(&format!(
+                " · {} {}/{}: {}",
+                failure.kind,
+                failure.namespace,
+                failure.name,
+                truncate(&failure.message, MESSAGE_LEN)
+            ));
+        }
+        line
+    }
+}
+
+/// Failed or stalled, and not suspended
+fn is_failing(resource: &dyn FluxResource) -> bool {
+    !resource.is_suspended()
+        && matches!(
+            resource.status(),
+            ResourceStatus::Failed | ResourceStatus::Stalled
+        )
+}
+
+/// The failing resource whose Ready condition changed most recently
+fn last_failure<'a>(resources: &[&'a dyn FluxResource]) -> Option<&'a dyn FluxResource> {
+    resources
+        .iter()
+        .copied()
+        .filter(|r| is_failing(*r))
+        .max_by_key(|r| ready_since(*r))
+}
+
+/// When the Ready condition last changed
+fn ready_since(resource: &dyn FluxResource) -> Option<DateTime<Utc>> {
+    resource
+        .reconcile_info()
+        .conditions
+        .iter()
+        .find(|c| c.condition_type == "Ready")
+        .and_then(|c| c.last_transition)
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::state::Tab;
+    use crate::kubernetes::resources::{Condition, HelmRelease, Kustomization, ReconcileInfo};
+
+    fn failed_at(name: &str, seconds: i64, message: &str) -> HelmRelease {
+        HelmRelease {
+            name: name.to_string(),
+            namespace: "apps".to_string(),
+            status: ResourceStatus::Failed,
+            status_message: message.to_string(),
+            reconcile: ReconcileInfo {
+                conditions: vec![Condition {
+                    condition_type: "Ready".to_string(),
+                    status: "False".to_string(),
+                    last_transition: DateTime::from_timestamp(1_700_000_000 + seconds, 0),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_line() {
+        let ready = Kustomization {
+            status: ResourceStatus::Ready,
+            ..Default::default()
+        };
+        let suspended = Kustomization {
+            status: ResourceStatus::Failed,
+            suspended: true,
+            ..Default::default()
+        };
+        let mut app = App::for_testing(
+            Tab::Kustomizations,
+            vec![ready.clone(), ready, suspended],
+            vec![
+                failed_at("old", 0, "old failure"),
+                failed_at("podinfo", 60, "install retries exhausted\nmore detail"),
+            ],
+            vec![],
+        );
+        let summary = Summary::of(&app);
+        assert_eq!(summary.failing, 2);
+        assert_eq!(
+            summary.line(),
+            "test-cluster: 2 ready, 2 failing, 1 suspended · HelmRelease apps/podinfo: \
+             install retries exhausted"
+        );
+
+        app.last_error = Some("Failed to list resources: forbidden".to_string());
+        assert_eq!(
+            Summary::of(&app).line(),
+            "test-cluster: Failed to list resources: forbidden"
+        );
+    }
+
+    #[test]
+    fn test_line_empty() {
+        let app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        assert_eq!(Summary::of(&app).line(), "test-cluster: 0 ready");
+    }
+}
@@ -0,0 +1,156 @@
+//! Per-resource status timelines
+//!
+//! Keeps the status each resource had at every refresh while the TUI is open,
+//! so a flapping resource can be told apart from one that is stably failing.
+
+use std::collections::HashMap;
+
+use crate::kubernetes::resources::{FluxResource, ResourceKey, ResourceStatus};
+
+use super::buffer::BoundedBuffer;
+
+/// Status per refresh, for every resource seen this session
+#[derive(Debug)]
+pub struct StatusTimelines {
+    timelines: HashMap<ResourceKey, BoundedBuffer<ResourceStatus>>,
+
+    /// Refreshes kept per resource
+    capacity: usize,
+}
+
+impl StatusTimelines {
+    /// Create empty timelines keeping at most `capacity` refreshes each
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            timelines: HashMap::new(),
+            capacity,
+        }
+    }
+
+    /// Record one refresh of a cluster's resources
+    ///
+    /// Resources of that cluster that no longer exist are dropped; other
+    /// clusters are left untouched.
+    pub fn record<'a>(
+        &mut self,
+        cluster: &str,
+        resources: impl IntoIterator<Item = &'a dyn FluxResource>,
+    ) {
+        let mut previous = std::mem::take(&mut self.timelines);
+        for resource in resources {
+            let key = ResourceKey::of(resource);
+            let mut timeline = previous
+                .remove(&key)
+                .unwrap_or_else(|| BoundedBuffer::new(self.capacity));
+            timeline.push(resource.status().clone());
+            self.timelines.insert(key, timeline);
+        }
+        self.timelines.extend(
+            previous
+                .into_iter()
+                .filter(|(key, _)| key.cluster != cluster),
+        );
+    }
+
+    /// All timelines, oldest status first, to hand over to an attached TUI
+    pub fn entries(&self) -> Vec<(ResourceKey, Vec<ResourceStatus>)> {
+        self.timelines
+            .iter()
+            .map(|(key, timeline)| (key.clone(), timeline.iter().cloned().collect()))
+            .collect()
+    }
+
+    /// Replace all timelines with ones handed over by the daemon
+    pub fn replace(&mut self, entries: Vec<(ResourceKey, Vec<ResourceStatus>)>) {
+        self.timelines = entries
+            .into_iter()
+            .map(|(key, statuses)| {
+                let mut timeline = BoundedBuffer::new(self.capacity);
+                for status in statuses {
+                    timeline.push(status);
+                }
+                (key, timeline)
+            })
+            .collect();
+    }
+
+    /// Statuses of a resource, oldest first
+    pub fn get(&self, resource: &dyn FluxResource) -> Vec<&ResourceStatus> {
+        self.timelines
+            .get(&ResourceKey::of(resource))
+            .map(|timeline| timeline.iter().collect())
+            .unwrap_or_default()
+    }
+}
+
+impl Default for StatusTimelines {
+    fn default() -> Self {
+        Self::new(super::config::BufferLimits::default().history)
+    }
+}
+
+/// Whether a resource failed in the last refresh after being Ready in the
+/// one before
+pub fn just_failed(timeline: &[&ResourceStatus]) -> bool {
+    matches!(
+        timeline,
+        [
+            ..,
+            ResourceStatus::Ready,
+            ResourceStatus::Failed | ResourceStatus::Stalled
+        ]
+    )
+}
+
+/// Number of times the status changed along a timeline
+pub fn changes(timeline: &[&ResourceStatus]) -> usize {
+    timeline.windows(2).filter(|w| w[0] != w[1]).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::Kustomization;
+
+    fn kustomization(name: &str, cluster: &str, status: ResourceStatus) -> Kustomization {
+        Kustomization {
+            name: name.to_string(),
+            namespace: "flux-system".to_string(),
+            cluster: cluster.to_string(),
+            status,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_record_appends_and_drops_missing() {
+        let mut timelines = StatusTimelines::new(3);
+        let apps = kustomization("apps", "", ResourceStatus::Ready);
+        let failed = kustomization("apps", "", ResourceStatus::Failed);
+        let other = kustomization("infra", "prod-us", ResourceStatus::Ready);
+
+        timelines.record("prod-us", [&other as &dyn FluxResource]);
+        for resource in [&apps, &failed, &apps, &failed] {
+            timelines.record("", [resource as &dyn FluxResource]);
+        }
+
+        // Capped at 3 refreshes, oldest first
+        let timeline = timelines.get(&apps);
+        assert_eq!(
+            timeline,
+            [
+                &ResourceStatus::Failed,
+                &ResourceStatus::Ready,
+                &ResourceStatus::Failed
+            ]
+        );
+        assert_eq!(changes(&timeline), 2);
+        assert!(just_failed(&timeline));
+        assert!(!just_failed(&timeline[..2]));
+        assert!(!just_failed(&timeline[2..]));
+
+        timelines.record("", []);
+        assert!(timelines.get(&apps).is_empty());
+        assert_eq!(timelines.get(&other).len(), 1);
+    }
+}
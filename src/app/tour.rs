@@ -0,0 +1,70 @@
+//! First-run onboarding tour
+//!
+//! A few cards walking through the screen: each highlights the area it
+//! describes. Shown on the first start (until finished or dismissed, which is
+//! remembered in the state file) and again with `--tour`.
+
+/// Screen area a tour step highlights
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Header,
+    Tabs,
+    Table,
+    StatusBar,
+}
+
+/// One card of the tour
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub title: &'static str,
+    pub text: &'static str,
+    pub target: Target,
+}
+
+/// The tour, in order
+pub const STEPS: &[Step] = &[
+    Step {
+        title: "Welcome to flux-tui",
+        text: "The header shows the cluster, the namespace filter and how many \
+               resources there are. Everything refreshes on its own.",
+        target: Target::Header,
+    },
+    Step {
+        title: "Tabs",
+        text: "One tab per Flux kind. Switch with Tab/Shift+Tab, ←/→ or a click.",
+        target: Target::Tabs,
+    },
+    Step {
+        title: "Resources",
+        text: "Move with ↑/↓ or j/k and press Enter for details. / filters by \
+               name and f cycles through failed, not ready and suspended.",
+        target: Target::Table,
+    },
+    Step {
+        title: "Reconciling",
+        text: "r reconciles the selected resource, R its source first. A popup \
+               follows the reconcile until it's Ready or fails. s suspends or \
+               resumes.",
+        target: Target::Table,
+    },
+    Step {
+        title: "Keybindings",
+        text: "The status bar lists the keys that apply right now, ? shows them \
+               all and q quits. Replay this tour with `flux-tui --tour`.",
+        target: Target::StatusBar,
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps() {
+        assert_eq!(STEPS.first().map(|step| step.target), Some(Target::Header));
+        assert!(STEPS.iter().all(|step| !step.text.is_empty()));
+        assert!(STEPS
+            .last()
+            .is_some_and(|step| step.text.contains("--tour")));
+    }
+}
@@ -0,0 +1,297 @@
+//! Trace a resource back to its source, like `flux trace`
+
+use crate::kubernetes::resources::{
+    FluxResource, GitRepository, HelmChart, HelmRepository, ResourceStatus, SourceRef,
+};
+
+/// One hop of a trace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceHop {
+    /// Resource kind
+    pub kind: String,
+
+    /// Resource as "namespace/name"
+    pub name: String,
+
+    /// Current status (None = not found in the loaded resources)
+    pub status: Option<ResourceStatus>,
+
+    /// Last applied or fetched revision
+    pub revision: Option<String>,
+
+    /// Status message, or why the hop couldn't be resolved
+    pub message: String,
+}
+
+impl TraceHop {
+    fn of(resource: &dyn FluxResource) -> Self {
+        Self {
+            kind: resource.kind().to_string(),
+            name: format!("{}/{}", resource.namespace(), resource.name()),
+            status: Some(resource.status().clone()),
+            revision: resource.revision().map(String::from),
+            message: resource.status_message().to_string(),
+        }
+    }
+
+    fn missing(source: &SourceRef, message: &str) -> Self {
+        Self {
+            kind: source.kind.clone(),
+            name: format!("{}/{}", source.namespace, source.name),
+            status: None,
+            revision: None,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Sources loaded in the app, to resolve trace hops from
+pub struct Sources<'a> {
+    pub helm_charts: &'a [HelmChart],
+    pub git_repositories: &'a [GitRepository],
+    pub helm_repositories: &'a [HelmRepository],
+}
+
+impl Sources<'_> {
+    /// Trace a resource to its source
+    ///
+    /// Kustomization → source, HelmRelease → HelmChart → source and
+    /// HelmChart → source. Sources themselves trace to just one hop.
+    pub fn trace(&self, resource: &dyn FluxResource) -> Vec<TraceHop> {
+        let mut hops = vec![TraceHop::of(resource)];
+        let cluster = resource.cluster();
+
+        let chart = match (resource.kind(), resource.source()) {
+            ("HelmRelease", Some(source)) if source.kind == "OCIRepository" => {
+                hops.push(self.source_hop(cluster, source));
+                None
+            }
+            ("HelmRelease", _) => chart_ref(resource),
+            ("Kustomization", Some(source)) => {
+                hops.push(self.source_hop(cluster, source));
+                None
+            }
+            ("HelmChart", _) => Some(SourceRef {
+                kind: "HelmChart".to_string(),
+                name: resource.name().to_string(),
+                namespace: resource.namespace().to_string(),
+            }),
+            _ => None,
+        };
+
+        if let Some(chart_ref) = chart {
+            let chart = self.helm_charts.iter().find(|c| {
+                c.cluster == cluster
+                    && c.namespace == chart_ref.namespace
+                    && c.name == chart_ref.name
+            });
+            match chart {
+                Some(chart) => {
+                    if resource.kind() != "HelmChart" {
+                        hops.push(TraceHop::of(chart));
+                    }
+                    if let Some(source) = chart_source(chart) {
+                        hops.push(self.source_hop(cluster, &source));
+                    }
+                }
+                None => hops.push(TraceHop::missing(&chart_ref, "not found")),
+            }
+        }
+
+        hops
+    }
+
+    /// Hop for a source, looked up among the loaded sources
+    fn source_hop(&self, cluster: &str, source: &SourceRef) -> TraceHop {
+        let found = match source.kind.as_str() {
+            "GitRepository" => find(self.git_repositories, cluster, source),
+            "HelmRepository" => find(self.helm_repositories, cluster, source),
+            _ => return TraceHop::missing(source, "kind not listed by flux-tui"),
+        };
+        found.unwrap_or_else(|| TraceHop::missing(source, "not found"))
+    }
+}
+
+/// Hop for the resource of a list matching a source reference
+fn find<T: FluxResource>(items: &[T], cluster: &str, source: &SourceRef) -> Option<TraceHop> {
+    items
+        .iter()
+        .find(|item| {
+            item.cluster() == cluster
+                && item.namespace() == source.namespace
+                && item.name() == source.name
+        })
+        .map(|item| TraceHop::of(item))
+}
+
+/// HelmChart a HelmRelease is installed from, None when it installs from an
+/// OCIRepository directly
+///
+/// The controller names the charts it generates "<namespace>-<name>", in the
+/// namespace of the chart's source.
+pub fn chart_ref(release: &dyn FluxResource) -> Option<SourceRef> {
+    match release.source() {
+        Some(source) if source.kind == "HelmChart" => Some(source.clone()),
+        Some(source) if source.kind == "OCIRepository" => None,
+        Some(source) => Some(SourceRef {
+            kind: "HelmChart".to_string(),
+            name: format!("{}-{}", release.namespace(), release.name()),
+            namespace: source.namespace.clone(),
+        }),
+        None => None,
+    }
+}
+
+/// Source of a HelmChart, from its "Kind/name" reference
+pub fn chart_source(chart: &HelmChart) -> Option<SourceRef> {
+    let (kind, name) = chart.source_ref.split_once('/')?;
+    Some(SourceRef {
+        kind: kind.to_string(),
+        name: name.to_string(),
+        namespace: chart.namespace.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::{HelmRelease, Kustomization};
+
+    fn source(kind: &str, name: &str) -> SourceRef {
+        SourceRef {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            namespace: "flux-system".to_string(),
+        }
+    }
+
+    fn chart() -> HelmChart {
+        HelmChart {
+            name: "apps-podinfo".to_string(),
+            namespace: "flux-system".to_string(),
+            status: ResourceStatus::Ready,
+            source_ref: "HelmRepository/podinfo".to_string(),
+            revision: Some("6.5.0".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn repository() -> HelmRepository {
+        HelmRepository {
+            name: "podinfo".to_string(),
+            namespace: "flux-system".to_string(),
+            status: ResourceStatus::Failed,
+            status_message: "index fetch failed".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn kinds(hops: &[TraceHop]) -> Vec<&str> {
+        hops.iter().map(|h| h.kind.as_str()).collect()
+    }
+
+    #[test]
+    fn test_trace_helm_release_through_chart() {
+        let charts = [chart()];
+        let repositories = [repository()];
+        let sources = Sources {
+            helm_charts: &charts,
+            git_repositories: &[],
+            helm_repositories: &repositories,
+        };
+        let release = HelmRelease {
+            name: "podinfo".to_string(),
+            namespace: "apps".to_string(),
+            source: Some(source("HelmRepository", "podinfo")),
+            ..Default::default()
+        };
+
+        let hops = sources.trace(&release);
+        assert_eq!(kinds(&hops), ["HelmRelease", "HelmChart", "HelmRepository"]);
+        assert_eq!(hops[1].revision.as_deref(), Some("6.5.0"));
+        assert_eq!(hops[2].status, Some(ResourceStatus::Failed));
+        assert_eq!(hops[2].message, "index fetch failed");
+    }
+
+    #[test]
+    fn test_chart_ref() {
+        let release = HelmRelease {
+            name: "podinfo".to_string(),
+            namespace: "apps".to_string(),
+            source: Some(source("HelmRepository", "podinfo")),
+            ..Default::default()
+        };
+        assert_eq!(
+            chart_ref(&release),
+            Some(source("HelmChart", "apps-podinfo"))
+        );
+
+        let chart_ref_release = HelmRelease {
+            source: Some(source("HelmChart", "shared")),
+            ..release.clone()
+        };
+        assert_eq!(
+            chart_ref(&chart_ref_release),
+            Some(source("HelmChart", "shared"))
+        );
+
+        let oci = HelmRelease {
+            source: Some(source("OCIRepository", "podinfo")),
+            ..release
+        };
+        assert_eq!(chart_ref(&oci), None);
+    }
+
+    #[test]
+    fn test_trace_kustomization_missing_source() {
+        let sources = Sources {
+            helm_charts: &[],
+            git_repositories: &[],
+            helm_repositories: &[],
+        };
+        let kustomization = Kustomization {
+            name: "apps".to_string(),
+            namespace: "flux-system".to_string(),
+            source: Some(source("GitRepository", "fleet")),
+            ..Default::default()
+        };
+
+        let hops = sources.trace(&kustomization);
+        assert_eq!(kinds(&hops), ["Kustomization", "GitRepository"]);
+        assert_eq!(hops[1].name, "flux-system/fleet");
+        assert_eq!(hops[1].status, None);
+        assert_eq!(hops[1].message, "not found");
+
+        let oci = Kustomization {
+            source: Some(source("OCIRepository", "manifests")),
+            ..kustomization
+        };
+        assert_eq!(
+            sources.trace(&oci)[1].message,
+            "kind not listed by flux-tui"
+        );
+    }
+
+    #[test]
+    fn test_trace_helm_chart_to_repository() {
+        let repositories = [repository()];
+        let sources = Sources {
+            helm_charts: &[chart()],
+            git_repositories: &[],
+            helm_repositories: &repositories,
+        };
+
+        let hops = sources.trace(&chart());
+        assert_eq!(kinds(&hops), ["HelmChart", "HelmRepository"]);
+    }
+
+    #[test]
+    fn test_trace_source_is_single_hop() {
+        let sources = Sources {
+            helm_charts: &[],
+            git_repositories: &[],
+            helm_repositories: &[],
+        };
+        assert_eq!(kinds(&sources.trace(&repository())), ["HelmRepository"]);
+    }
+}
@@ -0,0 +1,87 @@
+//! Status file for desktop bars (`flux-tui daemon --tray-file`)
+//!
+//! The daemon rewrites a small JSON document whenever the summary changes, so
+//! tray and status bar widgets read it instead of each querying the cluster.
+//! `text`, `tooltip` and `class` follow the waybar custom module format
+//! (`"return-type": "json"`); the summary fields are there for other widgets,
+//! e.g. polybar with `jq`.
+
+use color_eyre::Result;
+use serde::Serialize;
+
+use super::ticker::Summary;
+
+/// The document written to the tray file
+#[derive(Serialize)]
+struct Tray<'a> {
+    /// Counts, e.g. "41 ready, 2 failing"
+    text: String,
+
+    /// The full ticker line
+    tooltip: String,
+
+    /// "ok", "failing" or "error", for styling
+    class: &'static str,
+
+    #[serde(flatten)]
+    summary: &'a Summary,
+}
+
+/// The tray file contents for a summary
+pub fn render(summary: &Summary) -> Result<String> {
+    let class = if summary.error.is_some() {
+        "error"
+    } else if summary.failing > 0 {
+        "failing"
+    } else {
+        "ok"
+    };
+    let tray = Tray {
+        text: summary.counts(),
+        tooltip: summary.line(),
+        class,
+        summary,
+    };
+    Ok(serde_json::to_string(&tray)? + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary() -> Summary {
+        Summary {
+            cluster: "prod".to_string(),
+            ready: 41,
+            failing: 0,
+            reconciling: 0,
+            suspended: 0,
+            last_failure: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let json: serde_json::Value = serde_json::from_str(&render(&summary()).unwrap()).unwrap();
+        assert_eq!(json["text"], "41 ready");
+        assert_eq!(json["tooltip"], "prod: 41 ready");
+        assert_eq!(json["class"], "ok");
+        assert_eq!(json["cluster"], "prod");
+        assert_eq!(json["ready"], 41);
+
+        let failing = Summary {
+            failing: 2,
+            ..summary()
+        };
+        let json: serde_json::Value = serde_json::from_str(&render(&failing).unwrap()).unwrap();
+        assert_eq!(json["class"], "failing");
+
+        let error = Summary {
+            error: Some("forbidden".to_string()),
+            ..failing
+        };
+        let json: serde_json::Value = serde_json::from_str(&render(&error).unwrap()).unwrap();
+        assert_eq!(json["class"], "error");
+    }
+}
@@ -0,0 +1,344 @@
+//! Local usage statistics
+//!
+//! Counts which actions are used and how long sessions last, so users (and,
+//! if they choose to share the file, maintainers) can see which features
+//! matter. Stored in `<state dir>/flux-tui/usage.toml` next to the state
+//! file and never sent anywhere. Disable with `usage_stats = false`.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use color_eyre::{eyre::eyre, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::kubernetes::resources::format_duration;
+
+use super::actions::Action;
+
+/// Actions listed on the stats screen
+const TOP_ACTIONS: usize = 15;
+
+/// Counts kept in the usage file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageStats {
+    /// When counting started
+    pub since: Option<DateTime<Utc>>,
+
+    /// Finished sessions
+    pub sessions: u64,
+
+    /// Length of all finished sessions together
+    pub session_seconds: u64,
+
+    /// Length of the longest finished session
+    pub longest_session_seconds: u64,
+
+    /// How often each action was used, by name
+    pub actions: BTreeMap<String, u64>,
+}
+
+impl UsageStats {
+    /// Default location of the usage file
+    pub fn path() -> Option<PathBuf> {
+        dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .map(|dir| dir.join("flux-tui").join("usage.toml"))
+    }
+
+    /// Load the usage file, falling back to no counts if it doesn't exist
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(path)?;
+        toml::from_str(&contents).map_err(|e| eyre!("Invalid usage file {}: {}", path.display(), e))
+    }
+
+    /// Write the usage file, creating its directory if needed
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Add another set of counts to these
+    pub fn merge(&mut self, other: &UsageStats) {
+        self.since = match (self.since, other.since) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.sessions += other.sessions;
+        self.session_seconds += other.session_seconds;
+        self.longest_session_seconds = self
+            .longest_session_seconds
+            .max(other.longest_session_seconds);
+        for (name, count) in &other.actions {
+            *self.actions.entry(name.clone()).or_default() += count;
+        }
+    }
+
+    /// Most used actions, most used first (ties by name)
+    pub fn most_used(&self) -> Vec<(&str, u64)> {
+        let mut actions: Vec<(&str, u64)> = self
+            .actions
+            .iter()
+            .map(|(name, count)| (name.as_str(), *count))
+            .collect();
+        actions.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        actions.truncate(TOP_ACTIONS);
+        actions
+    }
+}
+
+/// Usage of this session on top of the counts from earlier ones
+#[derive(Debug, Clone)]
+pub struct Usage {
+    /// Counts of earlier sessions, as loaded at startup
+    earlier: UsageStats,
+
+    /// Counts of this session
+    session: UsageStats,
+
+    started: DateTime<Utc>,
+
+    /// Where counts are saved (None = not persisted)
+    path: Option<PathBuf>,
+}
+
+impl Usage {
+    /// Start counting a session, saved to the default usage file if enabled
+    pub fn start(enabled: bool, now: DateTime<Utc>) -> Self {
+        Self::new(enabled.then(UsageStats::path).flatten(), now)
+    }
+
+    /// Start counting a session, persisted to `path` when given
+    ///
+    /// An unreadable usage file starts the counts over rather than keeping
+    /// the app from starting.
+    pub fn new(path: Option<PathBuf>, now: DateTime<Utc>) -> Self {
+        let earlier = path
+            .as_deref()
+            .and_then(|path| UsageStats::load(path).ok())
+            .unwrap_or_default();
+        Self {
+            earlier,
+            session: UsageStats {
+                since: Some(now),
+                ..Default::default()
+            },
+            started: now,
+            path,
+        }
+    }
+
+    /// Count an action, unless it is navigation or typing
+    pub fn record(&mut self, action: &Action) {
+        if let Some(name) = action_name(action) {
+            *self.session.actions.entry(name).or_default() += 1;
+        }
+    }
+
+    /// Counts of all sessions including this one, as of `now`
+    pub fn totals(&self, now: DateTime<Utc>) -> UsageStats {
+        let mut totals = self.earlier.clone();
+        totals.merge(&self.finished_session(now));
+        totals
+    }
+
+    /// Add this session to the usage file
+    ///
+    /// The file is read again first, so sessions running side by side don't
+    /// overwrite each other's counts.
+    pub fn save(&self, now: DateTime<Utc>) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut stats = UsageStats::load(path).unwrap_or_default();
+        stats.merge(&self.finished_session(now));
+        stats.save(path)
+    }
+
+    /// This session's counts, as if it ended at `now`
+    fn finished_session(&self, now: DateTime<Utc>) -> UsageStats {
+        let seconds = (now - self.started).num_seconds().max(0) as u64;
+        UsageStats {
+            sessions: 1,
+            session_seconds: seconds,
+            longest_session_seconds: seconds,
+            ..self.session.clone()
+        }
+    }
+
+    /// Lines of the stats screen
+    pub fn report(&self, now: DateTime<Utc>) -> Vec<String> {
+        let totals = self.totals(now);
+        let duration = |seconds: u64| format_duration(chrono::Duration::seconds(seconds as i64));
+        let mut lines = vec![
+            format!(
+                "Sessions: {} (this one {}, average {}, longest {})",
+                totals.sessions,
+                duration((now - self.started).num_seconds().max(0) as u64),
+                duration(totals.session_seconds / totals.sessions.max(1)),
+                duration(totals.longest_session_seconds)
+            ),
+            format!(
+                "Counting since: {}",
+                totals
+                    .since
+                    .map(|since| since.format("%Y-%m-%d").to_string())
+                    .unwrap_or_else(|| "-".to_string())
+            ),
+            String::new(),
+            "Most used actions:".to_string(),
+        ];
+        let most_used = totals.most_used();
+        if most_used.is_empty() {
+            lines.push("  none yet".to_string());
+        }
+        let width = most_used
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or(0);
+        lines.extend(
+            most_used
+                .iter()
+                .map(|(name, count)| format!("  {name:<width$}  {count:>6}")),
+        );
+        lines.push(String::new());
+        lines.push(match &self.path {
+            Some(path) => format!(
+                "Stored only in {}; never sent anywhere. Delete it to start over.",
+                path.display()
+            ),
+            None => "Not saved (usage_stats = false).".to_string(),
+        });
+        lines
+    }
+}
+
+/// Name an action is counted under, None for navigation, scrolling and
+/// typed characters, which say little about which features matter
+fn action_name(action: &Action) -> Option<String> {
+    let uncounted = matches!(
+        action,
+        Action::Quit
+            | Action::Noop
+            | Action::Up
+            | Action::Down
+            | Action::Top
+            | Action::Bottom
+            | Action::PageDown
+            | Action::PageUp
+            | Action::HalfPageDown
+            | Action::HalfPageUp
+            | Action::CountDigit(_)
+            | Action::ClickRow(_)
+            | Action::HighlightItem(_)
+            | Action::PopupUp
+            | Action::PopupDown
+            | Action::Scroll(_)
+            | Action::ClosePopup
+            | Action::NamespaceInput(_)
+            | Action::NamespaceBackspace
+            | Action::SearchInput(_)
+            | Action::SearchBackspace
+            | Action::NoteInput(_)
+            | Action::NoteBackspace
+            | Action::CommandInput(_)
+            | Action::CommandBackspace
+            | Action::GlobalSearchInput(_)
+            | Action::GlobalSearchBackspace
+            | Action::TourNext
+            | Action::TourBack
+            | Action::Complete
+            | Action::CompleteBack
+    );
+    if uncounted {
+        return None;
+    }
+    // The variant name, without its payload (e.g. a context name)
+    let name = format!("{action:?}");
+    let end = name.find('(').unwrap_or(name.len());
+    Some(name[..end].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + seconds, 0).unwrap()
+    }
+
+    #[test]
+    fn test_action_name() {
+        assert_eq!(
+            action_name(&Action::Reconcile).as_deref(),
+            Some("Reconcile")
+        );
+        assert_eq!(
+            action_name(&Action::SetContext("prod".to_string())).as_deref(),
+            Some("SetContext")
+        );
+        assert_eq!(action_name(&Action::Down), None);
+        assert_eq!(action_name(&Action::SearchInput('a')), None);
+    }
+
+    #[test]
+    fn test_session_counts_and_totals() {
+        let mut usage = Usage::new(None, at(0));
+        usage.earlier = UsageStats {
+            since: Some(at(-86_400)),
+            sessions: 2,
+            session_seconds: 600,
+            longest_session_seconds: 500,
+            actions: BTreeMap::from([("Reconcile".to_string(), 3)]),
+        };
+        usage.record(&Action::Reconcile);
+        usage.record(&Action::ShowYaml);
+        usage.record(&Action::Down);
+
+        let totals = usage.totals(at(900));
+        assert_eq!(totals.since, Some(at(-86_400)));
+        assert_eq!(totals.sessions, 3);
+        assert_eq!(totals.session_seconds, 1500);
+        assert_eq!(totals.longest_session_seconds, 900);
+        assert_eq!(totals.most_used(), vec![("Reconcile", 4), ("ShowYaml", 1)]);
+
+        let report = usage.report(at(900));
+        assert_eq!(
+            report[0],
+            "Sessions: 3 (this one 15m, average 8m, longest 15m)"
+        );
+        assert!(report.contains(&"  Reconcile       4".to_string()));
+        assert_eq!(report.last().unwrap(), "Not saved (usage_stats = false).");
+    }
+
+    #[test]
+    fn test_save_adds_to_the_file() {
+        let path = std::env::temp_dir().join(format!("flux-tui-usage-{}.toml", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut first = Usage::new(Some(path.clone()), at(0));
+        let second = Usage::new(Some(path.clone()), at(10));
+        first.record(&Action::ShowEvents);
+        first.save(at(60)).unwrap();
+        second.save(at(40)).unwrap();
+
+        let stats = UsageStats::load(&path).unwrap();
+        assert_eq!(stats.sessions, 2);
+        assert_eq!(stats.session_seconds, 90);
+        assert_eq!(stats.longest_session_seconds, 60);
+        assert_eq!(stats.since, Some(at(0)));
+        assert_eq!(stats.actions.get("ShowEvents"), Some(&1));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,111 @@
+//! Opt-in check for newer flux-tui releases on GitHub
+
+use std::time::Duration;
+
+use color_eyre::Result;
+use serde::Deserialize;
+
+/// GitHub API endpoint for the latest release
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/frank-bee/flux-tui/releases/latest";
+
+/// Give up quickly so a slow network never delays anything noticeable
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Subset of the GitHub release payload we care about
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+}
+
+/// Return the latest released version if it is newer than the running one
+///
+/// Any network or parse error is logged and treated as "no update".
+pub async fn newer_release() -> Option<String> {
+    match fetch_latest_tag().await {
+        Ok(tag) => {
+            let latest = version_from_tag(&tag)?;
+            is_newer(latest, env!("CARGO_PKG_VERSION")).then(|| latest.to_string())
+        }
+        Err(e) => {
+            tracing::debug!("Update check failed: {e}");
+            None
+        }
+    }
+}
+
+/// Fetch the tag name of the latest GitHub release
+async fn fetch_latest_tag() -> Result<String> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(concat!("flux-tui/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+
+    let release: Release = client
+        .get(LATEST_RELEASE_URL)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(release.tag_name)
+}
+
+/// Extract the version from a release tag (`v0.1.2` or `flux-tui-v0.1.2`)
+fn version_from_tag(tag: &str) -> Option<&str> {
+    let tag = tag.trim_start_matches("flux-tui-");
+    let version = tag.strip_prefix('v').unwrap_or(tag);
+    parse_version(version).map(|_| version)
+}
+
+/// Parse a `major.minor.patch` version, ignoring any pre-release suffix
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+    let version = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(version)
+}
+
+/// Check whether `latest` is a higher version than `current`
+fn is_newer(latest: &str, current: &str) -> bool {
+    match (parse_version(latest), parse_version(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_from_tag() {
+        assert_eq!(version_from_tag("v0.1.2"), Some("0.1.2"));
+        assert_eq!(version_from_tag("flux-tui-v0.2.0"), Some("0.2.0"));
+        assert_eq!(version_from_tag("latest"), None);
+        assert_eq!(version_from_tag("v1.2.0-dev"), Some("1.2.0-dev"));
+        assert_eq!(
+            version_from_tag("flux-tui-v1.2.0-preview"),
+            Some("1.2.0-preview")
+        );
+        assert_eq!(version_from_tag("1.2.0"), Some("1.2.0"));
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("1.2.3"), Some((1, 2, 3)));
+        assert_eq!(parse_version("1.2.3-rc.1"), Some((1, 2, 3)));
+        assert_eq!(parse_version("1.2"), None);
+        assert_eq!(parse_version("1.2.3.4"), None);
+        assert_eq!(parse_version("a.b.c"), None);
+    }
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("0.2.0", "0.1.2"));
+        assert!(is_newer("0.1.10", "0.1.9"));
+        assert!(!is_newer("0.1.2", "0.1.2"));
+        assert!(!is_newer("0.1.1", "0.1.2"));
+        assert!(!is_newer("garbage", "0.1.2"));
+    }
+}
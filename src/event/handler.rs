@@ -1,79 +1,317 @@
-//! Key event handler - maps keyboard input to actions
+//! Key event handler - maps keyboard and mouse input to actions
 
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
 
 use crate::app::{
     actions::Action,
-    state::{App, Popup},
+    keymap,
+    state::{namespace_choices, App, Popup},
 };
+use crate::ui::{
+    layout::{table_area, MainLayout},
+    widgets::{resource_table, tabs},
+};
+
+/// Lines scrolled per mouse wheel step in popups
+const WHEEL_LINES: i32 = 3;
 
 /// Convert a key event to an application action
 pub fn handle_key_event(key: KeyEvent, app: &App) -> Action {
     // Handle popup-specific keys first
     match &app.popup {
-        Popup::None => handle_normal_keys(key),
+        Popup::None if app.search_editing => handle_search_keys(key),
+        Popup::None if key.code == KeyCode::Esc && !app.search_query.is_empty() => {
+            Action::ClearSearch
+        }
+        Popup::None if key.code == KeyCode::Esc && app.active_source_filter().is_some() => {
+            Action::ClearSourceFilter
+        }
+        Popup::None => count_digit(&key, app.count.is_some())
+            .map_or_else(|| handle_normal_keys(key), Action::CountDigit),
         Popup::NamespaceFilter {
             namespaces,
+            query,
+            selected,
+        } => handle_namespace_popup_keys(key, &namespace_choices(namespaces, query), *selected),
+        Popup::ContextSwitcher { contexts, selected } => {
+            handle_context_popup_keys(key, contexts, *selected)
+        }
+        Popup::ClusterFilter { clusters, selected } => {
+            handle_cluster_popup_keys(key, clusters, *selected)
+        }
+        Popup::ResourceDetails { .. } => match key.code {
+            KeyCode::Char('m') => Action::ToggleMetadata,
+            _ => handle_details_popup_keys(key),
+        },
+        Popup::Yaml { .. }
+        | Popup::Events { .. }
+        | Popup::Message { .. }
+        | Popup::Dependencies { .. }
+        | Popup::Help { .. }
+        | Popup::Usage { .. } => handle_details_popup_keys(key),
+        Popup::ActionLog { .. } => match key.code {
+            KeyCode::Char('w') => Action::WriteActionLog,
+            _ => handle_details_popup_keys(key),
+        },
+        Popup::ConfirmSuspend { .. }
+        | Popup::ConfirmBulkSuspend { .. }
+        | Popup::ConfirmRefOverride { .. }
+        | Popup::ConfirmRollback { .. } => handle_confirm_popup_keys(key),
+        Popup::HelmHistory {
+            revisions,
             selected,
-        } => handle_namespace_popup_keys(key, namespaces, *selected),
-        Popup::ResourceDetails { .. } => handle_details_popup_keys(key),
+            ..
+        } => handle_helm_history_keys(key, revisions.len(), *selected),
+        Popup::PathBrowser {
+            files,
+            selected,
+            open: false,
+            ..
+        } => handle_path_browser_keys(key, files.len(), *selected),
+        Popup::PathBrowser { open: true, .. } => match handle_details_popup_keys(key) {
+            Action::ClosePopup => Action::CloseFile,
+            action => action,
+        },
+        Popup::Controllers { .. } => match key.code {
+            KeyCode::Char('r') => Action::ShowControllers,
+            _ => handle_details_popup_keys(key),
+        },
+        Popup::Preflight { .. } => match key.code {
+            KeyCode::Char('r') => Action::ShowPreflight,
+            _ => handle_details_popup_keys(key),
+        },
+        Popup::FluxVersion { .. } => match key.code {
+            KeyCode::Char('r') => Action::ShowFluxVersion,
+            _ => handle_details_popup_keys(key),
+        },
+        Popup::EditNote { .. } => handle_note_keys(key),
+        Popup::Command { .. } => handle_command_keys(key),
+        Popup::GlobalSearch { .. } => handle_global_search_keys(key),
+        Popup::Tour { .. } => handle_tour_keys(key),
+        Popup::Reconciling { progress, .. } if progress.is_done() => handle_error_popup_keys(key),
         Popup::Reconciling { .. } => handle_reconciling_popup_keys(key),
-        Popup::Error { .. } => handle_error_popup_keys(key),
+        Popup::Error { .. } | Popup::Info { .. } | Popup::Trace { .. } => {
+            handle_error_popup_keys(key)
+        }
     }
 }
 
-/// Handle keys in normal mode (no popup)
+/// Convert a mouse event to an application action
+///
+/// `area` is the terminal area the UI was drawn in, to locate tabs and rows.
+pub fn handle_mouse_event(mouse: MouseEvent, app: &App, area: Rect) -> Action {
+    let position = Position::new(mouse.column, mouse.row);
+    match (&app.popup, mouse.kind) {
+        (Popup::None, MouseEventKind::Down(MouseButton::Left)) => {
+            let layout = MainLayout::new(area);
+            if layout.tabs.contains(position) {
+                tabs::tab_at(layout.tabs, mouse.column, &app.tab_counts(), &app.theme)
+                    .map_or(Action::Noop, Action::ClickTab)
+            } else if table_area(layout.content, app.show_details, app.show_activity)
+                .contains(position)
+            {
+                resource_table::row_at(layout.content, app.current_scroll(), mouse.row)
+                    .map_or(Action::Noop, Action::ClickRow)
+            } else {
+                Action::Noop
+            }
+        }
+        (Popup::None, MouseEventKind::ScrollUp) => Action::Up,
+        (Popup::None, MouseEventKind::ScrollDown) => Action::Down,
+        (Popup::NamespaceFilter { .. }, MouseEventKind::ScrollUp) => Action::PopupUp,
+        (Popup::NamespaceFilter { .. }, MouseEventKind::ScrollDown) => Action::PopupDown,
+        (Popup::GlobalSearch { .. }, MouseEventKind::ScrollUp) => Action::PopupUp,
+        (Popup::GlobalSearch { .. }, MouseEventKind::ScrollDown) => Action::PopupDown,
+        (
+            Popup::ResourceDetails { .. }
+            | Popup::Yaml { .. }
+            | Popup::Events { .. }
+            | Popup::Message { .. }
+            | Popup::Dependencies { .. }
+            | Popup::Controllers { .. }
+            | Popup::Help { .. }
+            | Popup::Usage { .. }
+            | Popup::ActionLog { .. }
+            | Popup::Preflight { .. }
+            | Popup::FluxVersion { .. },
+            MouseEventKind::ScrollUp,
+        ) => Action::Scroll(-WHEEL_LINES),
+        (
+            Popup::ResourceDetails { .. }
+            | Popup::Yaml { .. }
+            | Popup::Events { .. }
+            | Popup::Message { .. }
+            | Popup::Dependencies { .. }
+            | Popup::Controllers { .. }
+            | Popup::Help { .. }
+            | Popup::Usage { .. }
+            | Popup::ActionLog { .. }
+            | Popup::Preflight { .. }
+            | Popup::FluxVersion { .. },
+            MouseEventKind::ScrollDown,
+        ) => Action::Scroll(WHEEL_LINES),
+        (
+            Popup::ContextSwitcher {
+                contexts: items,
+                selected,
+            }
+            | Popup::ClusterFilter {
+                clusters: items,
+                selected,
+            },
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown,
+        ) => {
+            let selected = if mouse.kind == MouseEventKind::ScrollUp {
+                selected.saturating_sub(1)
+            } else {
+                (selected + 1).min(items.len().saturating_sub(1))
+            };
+            Action::HighlightItem(selected)
+        }
+        _ => Action::Noop,
+    }
+}
+
+/// Handle keys in normal mode (no popup), as declared in the keymap
 fn handle_normal_keys(key: KeyEvent) -> Action {
+    keymap::action_for(&key).unwrap_or(Action::Noop)
+}
+
+/// The digit of a count prefix (e.g. the 5 of `5j`); 0 only continues a count
+fn count_digit(key: &KeyEvent, counting: bool) -> Option<u8> {
+    if key
+        .modifiers
+        .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+    {
+        return None;
+    }
+    match key.code {
+        KeyCode::Char(c @ '1'..='9') => Some(c as u8 - b'0'),
+        KeyCode::Char('0') if counting => Some(0),
+        _ => None,
+    }
+}
+
+/// Handle keys while typing the fuzzy filter
+fn handle_search_keys(key: KeyEvent) -> Action {
     match key.code {
-        // Quit
-        KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
         KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+        KeyCode::Esc => Action::ClearSearch,
+        KeyCode::Enter => Action::ConfirmSearch,
+        KeyCode::Backspace => Action::SearchBackspace,
+        KeyCode::Tab => Action::Complete,
+        KeyCode::BackTab => Action::CompleteBack,
+        KeyCode::Up => Action::Up,
+        KeyCode::Down => Action::Down,
+        KeyCode::Char(c) => Action::SearchInput(c),
+        _ => Action::Noop,
+    }
+}
 
-        // Navigation
-        KeyCode::Up | KeyCode::Char('k') => Action::Up,
-        KeyCode::Down | KeyCode::Char('j') => Action::Down,
-        KeyCode::Home | KeyCode::Char('g') => Action::Top,
-        KeyCode::End | KeyCode::Char('G') => Action::Bottom,
-
-        // Tab navigation
-        KeyCode::Left | KeyCode::Char('h') => Action::PreviousTab,
-        KeyCode::Right | KeyCode::Char('l') => Action::NextTab,
-        KeyCode::Tab => Action::NextTab,
-        KeyCode::BackTab => Action::PreviousTab,
-
-        // Actions
-        KeyCode::Enter => Action::Select,
-        KeyCode::Char('r') => Action::Reconcile,
-        KeyCode::Char('R') => Action::ReconcileWithSource,
-        KeyCode::Char('s') => Action::ToggleSuspend,
-        KeyCode::Char('n') => Action::FilterNamespace,
-        KeyCode::F(5) => Action::Refresh,
+/// Handle keys while editing a note
+fn handle_note_keys(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+        KeyCode::Esc => Action::ClosePopup,
+        KeyCode::Enter => Action::SaveNote,
+        KeyCode::Backspace => Action::NoteBackspace,
+        KeyCode::Tab => Action::Complete,
+        KeyCode::BackTab => Action::CompleteBack,
+        KeyCode::Char(c) => Action::NoteInput(c),
+        _ => Action::Noop,
+    }
+}
+
+/// Handle keys while typing into the command palette
+fn handle_command_keys(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+        KeyCode::Esc => Action::ClosePopup,
+        KeyCode::Enter => Action::RunCommand,
+        KeyCode::Backspace => Action::CommandBackspace,
+        KeyCode::Tab => Action::Complete,
+        KeyCode::BackTab => Action::CompleteBack,
+        KeyCode::Char(c) => Action::CommandInput(c),
+        _ => Action::Noop,
+    }
+}
 
+/// Handle keys in the namespace popup, where typing narrows the list
+fn handle_namespace_popup_keys(key: KeyEvent, choices: &[Option<&str>], selected: usize) -> Action {
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+        KeyCode::Esc => Action::ClosePopup,
+        KeyCode::Up => Action::PopupUp,
+        KeyCode::Down => Action::PopupDown,
+        KeyCode::Enter => choices
+            .get(selected)
+            .map(|ns| Action::SetNamespace(ns.map(String::from)))
+            .unwrap_or(Action::Noop),
+        KeyCode::Backspace => Action::NamespaceBackspace,
+        KeyCode::Char(c) => Action::NamespaceInput(c),
         _ => Action::Noop,
     }
 }
 
-/// Handle keys in namespace filter popup
-fn handle_namespace_popup_keys(key: KeyEvent, namespaces: &[String], selected: usize) -> Action {
+/// Handle keys in the search across tabs, where typing narrows the matches
+fn handle_global_search_keys(key: KeyEvent) -> Action {
     match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
         KeyCode::Esc => Action::ClosePopup,
-        KeyCode::Up | KeyCode::Char('k') => {
-            // Move selection up in popup (handled via SetNamespace with current selection)
-            if selected > 0 {
-                // For simplicity, we close and re-open with new selection
-                // A more sophisticated approach would have dedicated popup navigation actions
-                Action::Noop
-            } else {
-                Action::Noop
-            }
+        KeyCode::Up => Action::PopupUp,
+        KeyCode::Down => Action::PopupDown,
+        KeyCode::Enter => Action::GoToMatch,
+        KeyCode::Backspace => Action::GlobalSearchBackspace,
+        KeyCode::Char(c) => Action::GlobalSearchInput(c),
+        _ => Action::Noop,
+    }
+}
+
+/// Handle keys in the onboarding tour
+fn handle_tour_keys(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+        KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') | KeyCode::Char(' ') => {
+            Action::TourNext
+        }
+        KeyCode::Left | KeyCode::Char('h') | KeyCode::Backspace => Action::TourBack,
+        KeyCode::Esc | KeyCode::Char('q') => Action::EndTour,
+        _ => Action::Noop,
+    }
+}
+
+/// Handle keys in context switcher popup
+fn handle_context_popup_keys(key: KeyEvent, contexts: &[String], selected: usize) -> Action {
+    match key.code {
+        KeyCode::Esc => Action::ClosePopup,
+        KeyCode::Up | KeyCode::Char('k') => Action::HighlightItem(selected.saturating_sub(1)),
+        KeyCode::Down | KeyCode::Char('j') => {
+            Action::HighlightItem((selected + 1).min(contexts.len().saturating_sub(1)))
+        }
+        KeyCode::Enter => contexts
+            .get(selected)
+            .cloned()
+            .map(Action::SetContext)
+            .unwrap_or(Action::Noop),
+        KeyCode::Char('q') => Action::Quit,
+        _ => Action::Noop,
+    }
+}
+
+/// Handle keys in cluster filter popup
+fn handle_cluster_popup_keys(key: KeyEvent, clusters: &[String], selected: usize) -> Action {
+    match key.code {
+        KeyCode::Esc => Action::ClosePopup,
+        KeyCode::Up | KeyCode::Char('k') => Action::HighlightItem(selected.saturating_sub(1)),
+        KeyCode::Down | KeyCode::Char('j') => {
+            Action::HighlightItem((selected + 1).min(clusters.len().saturating_sub(1)))
         }
-        KeyCode::Down | KeyCode::Char('j') => Action::Noop,
         KeyCode::Enter => {
-            // Apply selected namespace
             if selected == 0 {
-                Action::SetNamespace(None) // "All namespaces"
+                Action::SetClusterFilter(None) // "All clusters"
             } else {
-                Action::SetNamespace(namespaces.get(selected).cloned())
+                Action::SetClusterFilter(clusters.get(selected).cloned())
             }
         }
         KeyCode::Char('q') => Action::Quit,
@@ -81,10 +319,54 @@ fn handle_namespace_popup_keys(key: KeyEvent, namespaces: &[String], selected: u
     }
 }
 
-/// Handle keys in resource details popup
+/// Handle keys in scrollable popups (details, YAML, events, dependencies)
 fn handle_details_popup_keys(key: KeyEvent) -> Action {
     match key.code {
         KeyCode::Esc | KeyCode::Enter => Action::ClosePopup,
+        KeyCode::Down | KeyCode::Char('j') => Action::Scroll(1),
+        KeyCode::Up | KeyCode::Char('k') => Action::Scroll(-1),
+        KeyCode::PageDown | KeyCode::Char(' ') => Action::Scroll(10),
+        KeyCode::PageUp => Action::Scroll(-10),
+        KeyCode::Home | KeyCode::Char('g') => Action::Scroll(i32::MIN),
+        KeyCode::End | KeyCode::Char('G') => Action::Scroll(i32::MAX),
+        KeyCode::Char('q') => Action::Quit,
+        _ => Action::Noop,
+    }
+}
+
+/// Handle keys in the path browser's file list
+fn handle_path_browser_keys(key: KeyEvent, files: usize, selected: usize) -> Action {
+    match key.code {
+        KeyCode::Esc => Action::ClosePopup,
+        KeyCode::Up | KeyCode::Char('k') => Action::HighlightItem(selected.saturating_sub(1)),
+        KeyCode::Down | KeyCode::Char('j') => {
+            Action::HighlightItem((selected + 1).min(files.saturating_sub(1)))
+        }
+        KeyCode::Enter => Action::OpenFile,
+        KeyCode::Char('q') => Action::Quit,
+        _ => Action::Noop,
+    }
+}
+
+/// Handle keys in the Helm history, where Enter offers a rollback
+fn handle_helm_history_keys(key: KeyEvent, revisions: usize, selected: usize) -> Action {
+    match key.code {
+        KeyCode::Esc => Action::ClosePopup,
+        KeyCode::Up | KeyCode::Char('k') => Action::HighlightItem(selected.saturating_sub(1)),
+        KeyCode::Down | KeyCode::Char('j') => {
+            Action::HighlightItem((selected + 1).min(revisions.saturating_sub(1)))
+        }
+        KeyCode::Enter => Action::Rollback,
+        KeyCode::Char('q') => Action::Quit,
+        _ => Action::Noop,
+    }
+}
+
+/// Handle keys in the suspend confirmation popup
+fn handle_confirm_popup_keys(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Enter | KeyCode::Char('y') => Action::ConfirmSuspend,
+        KeyCode::Esc | KeyCode::Char('n') => Action::ClosePopup,
         KeyCode::Char('q') => Action::Quit,
         _ => Action::Noop,
     }
@@ -139,10 +421,43 @@ mod tests {
         assert_eq!(handle_normal_keys(key(KeyCode::Char('g'))), Action::Top);
         assert_eq!(handle_normal_keys(key(KeyCode::End)), Action::Bottom);
         assert_eq!(handle_normal_keys(key(KeyCode::Char('G'))), Action::Bottom);
+        assert_eq!(handle_normal_keys(key(KeyCode::PageDown)), Action::PageDown);
+        assert_eq!(handle_normal_keys(key(KeyCode::PageUp)), Action::PageUp);
+        assert_eq!(
+            handle_normal_keys(key_with_mod(KeyCode::Char('d'), KeyModifiers::CONTROL)),
+            Action::HalfPageDown
+        );
+        assert_eq!(
+            handle_normal_keys(key_with_mod(KeyCode::Char('u'), KeyModifiers::CONTROL)),
+            Action::HalfPageUp
+        );
+    }
+
+    #[test]
+    fn test_count_digit() {
+        assert_eq!(count_digit(&key(KeyCode::Char('5')), false), Some(5));
+        assert_eq!(count_digit(&key(KeyCode::Char('0')), false), None);
+        assert_eq!(count_digit(&key(KeyCode::Char('0')), true), Some(0));
+        assert_eq!(count_digit(&key(KeyCode::Char('j')), true), None);
+        assert_eq!(
+            count_digit(
+                &key_with_mod(KeyCode::Char('5'), KeyModifiers::CONTROL),
+                false
+            ),
+            None
+        );
+        assert_eq!(
+            count_digit(&key_with_mod(KeyCode::Char('2'), KeyModifiers::ALT), true),
+            None
+        );
     }
 
     #[test]
     fn test_handle_normal_keys_tab_navigation() {
+        assert_eq!(
+            handle_normal_keys(key_with_mod(KeyCode::Char('2'), KeyModifiers::ALT)),
+            Action::ClickTab(crate::app::state::Tab::HelmReleases)
+        );
         assert_eq!(handle_normal_keys(key(KeyCode::Left)), Action::PreviousTab);
         assert_eq!(
             handle_normal_keys(key(KeyCode::Char('h'))),
@@ -172,6 +487,136 @@ mod tests {
             handle_normal_keys(key(KeyCode::Char('s'))),
             Action::ToggleSuspend
         );
+        assert_eq!(
+            handle_normal_keys(key_with_mod(KeyCode::Char('r'), KeyModifiers::CONTROL)),
+            Action::ResumeAndReconcile
+        );
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('y'))),
+            Action::ShowYaml
+        );
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('e'))),
+            Action::ShowEvents
+        );
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('d'))),
+            Action::ShowDependencies
+        );
+        assert_eq!(handle_normal_keys(key(KeyCode::Char('t'))), Action::Trace);
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('?'))),
+            Action::ShowHelp
+        );
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('N'))),
+            Action::EditNote
+        );
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('a'))),
+            Action::Acknowledge
+        );
+        assert_eq!(
+            handle_note_keys(key(KeyCode::Char('n'))),
+            Action::NoteInput('n')
+        );
+        assert_eq!(handle_note_keys(key(KeyCode::Enter)), Action::SaveNote);
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char(':'))),
+            Action::OpenCommand
+        );
+        assert_eq!(
+            handle_command_keys(key(KeyCode::Char('q'))),
+            Action::CommandInput('q')
+        );
+        assert_eq!(handle_command_keys(key(KeyCode::Enter)), Action::RunCommand);
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('A'))),
+            Action::ToggleActivity
+        );
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('p'))),
+            Action::ToggleDetailsPane
+        );
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('b'))),
+            Action::BrowsePath
+        );
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('M'))),
+            Action::ShowControllers
+        );
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('m'))),
+            Action::ShowMessage
+        );
+        assert_eq!(
+            handle_path_browser_keys(key(KeyCode::Down), 3, 2),
+            Action::HighlightItem(2)
+        );
+        assert_eq!(
+            handle_path_browser_keys(key(KeyCode::Enter), 3, 0),
+            Action::OpenFile
+        );
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('H'))),
+            Action::ShowHelmHistory
+        );
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('F'))),
+            Action::ForceReconcile
+        );
+        assert_eq!(handle_normal_keys(key(KeyCode::Char('J'))), Action::Jump);
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('U'))),
+            Action::ShowUsage
+        );
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('L'))),
+            Action::ShowActionLog
+        );
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('P'))),
+            Action::ShowPreflight
+        );
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('V'))),
+            Action::ShowFluxVersion
+        );
+        assert_eq!(
+            handle_helm_history_keys(key(KeyCode::Up), 3, 0),
+            Action::HighlightItem(0)
+        );
+        assert_eq!(
+            handle_helm_history_keys(key(KeyCode::Enter), 3, 1),
+            Action::Rollback
+        );
+        assert_eq!(handle_command_keys(key(KeyCode::Tab)), Action::Complete);
+        assert_eq!(handle_search_keys(key(KeyCode::Tab)), Action::Complete);
+        assert_eq!(
+            handle_note_keys(key(KeyCode::BackTab)),
+            Action::CompleteBack
+        );
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('o'))),
+            Action::CycleSort
+        );
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('O'))),
+            Action::ReverseSort
+        );
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('f'))),
+            Action::CycleStatusFilter
+        );
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('Z'))),
+            Action::ToggleGrouping
+        );
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('z'))),
+            Action::ToggleGroup
+        );
         assert_eq!(
             handle_normal_keys(key(KeyCode::Char('n'))),
             Action::FilterNamespace
@@ -187,61 +632,63 @@ mod tests {
 
     #[test]
     fn test_handle_namespace_popup_keys_close() {
-        let namespaces = vec!["All namespaces".to_string(), "default".to_string()];
+        let choices = [None, Some("default")];
         assert_eq!(
-            handle_namespace_popup_keys(key(KeyCode::Esc), &namespaces, 0),
+            handle_namespace_popup_keys(key(KeyCode::Esc), &choices, 0),
             Action::ClosePopup
         );
         assert_eq!(
-            handle_namespace_popup_keys(key(KeyCode::Char('q')), &namespaces, 0),
+            handle_namespace_popup_keys(
+                KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
+                &choices,
+                0
+            ),
             Action::Quit
         );
     }
 
     #[test]
     fn test_handle_namespace_popup_keys_select_all() {
-        let namespaces = vec!["All namespaces".to_string(), "default".to_string()];
+        let choices = [None, Some("default")];
         assert_eq!(
-            handle_namespace_popup_keys(key(KeyCode::Enter), &namespaces, 0),
+            handle_namespace_popup_keys(key(KeyCode::Enter), &choices, 0),
             Action::SetNamespace(None)
         );
     }
 
     #[test]
     fn test_handle_namespace_popup_keys_select_specific() {
-        let namespaces = vec![
-            "All namespaces".to_string(),
-            "default".to_string(),
-            "kube-system".to_string(),
-        ];
+        let choices = [None, Some("default"), Some("kube-system")];
         assert_eq!(
-            handle_namespace_popup_keys(key(KeyCode::Enter), &namespaces, 1),
+            handle_namespace_popup_keys(key(KeyCode::Enter), &choices, 1),
             Action::SetNamespace(Some("default".to_string()))
         );
         assert_eq!(
-            handle_namespace_popup_keys(key(KeyCode::Enter), &namespaces, 2),
+            handle_namespace_popup_keys(key(KeyCode::Enter), &choices, 2),
             Action::SetNamespace(Some("kube-system".to_string()))
         );
+        // Nothing matches the query
+        assert_eq!(
+            handle_namespace_popup_keys(key(KeyCode::Enter), &[], 0),
+            Action::Noop
+        );
     }
 
     #[test]
     fn test_handle_namespace_popup_keys_navigation() {
-        let namespaces = vec!["All namespaces".to_string(), "default".to_string()];
+        let choices = [None, Some("default")];
         assert_eq!(
-            handle_namespace_popup_keys(key(KeyCode::Up), &namespaces, 1),
-            Action::Noop
+            handle_namespace_popup_keys(key(KeyCode::Up), &choices, 1),
+            Action::PopupUp
         );
         assert_eq!(
-            handle_namespace_popup_keys(key(KeyCode::Char('k')), &namespaces, 1),
-            Action::Noop
+            handle_namespace_popup_keys(key(KeyCode::Down), &choices, 0),
+            Action::PopupDown
         );
+        // j and k are typed into the search
         assert_eq!(
-            handle_namespace_popup_keys(key(KeyCode::Down), &namespaces, 0),
-            Action::Noop
-        );
-        assert_eq!(
-            handle_namespace_popup_keys(key(KeyCode::Char('j')), &namespaces, 0),
-            Action::Noop
+            handle_namespace_popup_keys(key(KeyCode::Char('j')), &choices, 0),
+            Action::NamespaceInput('j')
         );
     }
 
@@ -301,16 +748,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_handle_namespace_popup_keys_up_at_zero() {
-        let namespaces = vec!["All namespaces".to_string(), "default".to_string()];
-        // At position 0, up should return Noop (can't go higher)
-        assert_eq!(
-            handle_namespace_popup_keys(key(KeyCode::Up), &namespaces, 0),
-            Action::Noop
-        );
-    }
-
     // ===== Integration tests for handle_key_event =====
 
     #[test]
@@ -330,6 +767,86 @@ mod tests {
         assert_eq!(handle_key_event(key(KeyCode::Enter), &app), Action::Select);
     }
 
+    #[test]
+    fn test_handle_key_event_search() {
+        let mut app = App::for_testing(
+            crate::app::state::Tab::Kustomizations,
+            vec![],
+            vec![],
+            vec![],
+        );
+        assert_eq!(
+            handle_key_event(key(KeyCode::Char('/')), &app),
+            Action::StartSearch
+        );
+
+        // While typing, letters go into the filter instead of triggering actions
+        app.search_editing = true;
+        assert_eq!(
+            handle_key_event(key(KeyCode::Char('q')), &app),
+            Action::SearchInput('q')
+        );
+        assert_eq!(
+            handle_key_event(key(KeyCode::Backspace), &app),
+            Action::SearchBackspace
+        );
+        assert_eq!(
+            handle_key_event(key(KeyCode::Enter), &app),
+            Action::ConfirmSearch
+        );
+        assert_eq!(
+            handle_key_event(key(KeyCode::Esc), &app),
+            Action::ClearSearch
+        );
+        assert_eq!(
+            handle_key_event(
+                key_with_mod(KeyCode::Char('c'), KeyModifiers::CONTROL),
+                &app
+            ),
+            Action::Quit
+        );
+
+        // With a filter applied, Esc clears it instead of quitting
+        app.search_editing = false;
+        app.search_query = "nginx".to_string();
+        assert_eq!(
+            handle_key_event(key(KeyCode::Esc), &app),
+            Action::ClearSearch
+        );
+        assert_eq!(
+            handle_key_event(key(KeyCode::Char('q')), &app),
+            Action::Quit
+        );
+    }
+
+    #[test]
+    fn test_handle_key_event_esc_clears_source_filter() {
+        let mut app = App::for_testing(
+            crate::app::state::Tab::Kustomizations,
+            vec![],
+            vec![],
+            vec![],
+        );
+        assert_eq!(
+            handle_key_event(key(KeyCode::Char('u')), &app),
+            Action::FilterBySource
+        );
+
+        app.source_filter = Some(crate::app::state::SourceFilter {
+            cluster: String::new(),
+            source: Default::default(),
+            changes: None,
+        });
+        assert_eq!(
+            handle_key_event(key(KeyCode::Esc), &app),
+            Action::ClearSourceFilter
+        );
+
+        // Source tabs aren't filtered, so Esc quits there
+        app.tab = crate::app::state::Tab::GitRepositories;
+        assert_eq!(handle_key_event(key(KeyCode::Esc), &app), Action::Quit);
+    }
+
     #[test]
     fn test_handle_key_event_namespace_popup() {
         let mut app = App::for_testing(
@@ -339,7 +856,8 @@ mod tests {
             vec![],
         );
         app.popup = Popup::NamespaceFilter {
-            namespaces: vec!["All".to_string(), "default".to_string()],
+            namespaces: vec!["default".to_string(), "kube-system".to_string()],
+            query: String::new(),
             selected: 1,
         };
 
@@ -351,6 +869,94 @@ mod tests {
             handle_key_event(key(KeyCode::Enter), &app),
             Action::SetNamespace(Some("default".to_string()))
         );
+        assert_eq!(
+            handle_key_event(key(KeyCode::Down), &app),
+            Action::PopupDown
+        );
+        assert_eq!(handle_key_event(key(KeyCode::Up), &app), Action::PopupUp);
+        // Letters are typed, even those bound elsewhere
+        assert_eq!(
+            handle_key_event(key(KeyCode::Char('q')), &app),
+            Action::NamespaceInput('q')
+        );
+        assert_eq!(
+            handle_key_event(key(KeyCode::Backspace), &app),
+            Action::NamespaceBackspace
+        );
+
+        // With a query, entries are the matching namespaces only
+        app.popup = Popup::NamespaceFilter {
+            namespaces: vec!["default".to_string(), "kube-system".to_string()],
+            query: "kube".to_string(),
+            selected: 0,
+        };
+        assert_eq!(
+            handle_key_event(key(KeyCode::Enter), &app),
+            Action::SetNamespace(Some("kube-system".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_handle_key_event_global_search() {
+        let mut app = App::for_testing(
+            crate::app::state::Tab::Kustomizations,
+            vec![],
+            vec![],
+            vec![],
+        );
+        assert_eq!(
+            handle_key_event(
+                key_with_mod(KeyCode::Char('f'), KeyModifiers::CONTROL),
+                &app
+            ),
+            Action::StartGlobalSearch
+        );
+
+        app.popup = Popup::GlobalSearch {
+            query: String::new(),
+            selected: 0,
+        };
+        assert_eq!(
+            handle_key_event(key(KeyCode::Char('q')), &app),
+            Action::GlobalSearchInput('q')
+        );
+        assert_eq!(
+            handle_key_event(key(KeyCode::Backspace), &app),
+            Action::GlobalSearchBackspace
+        );
+        assert_eq!(
+            handle_key_event(key(KeyCode::Enter), &app),
+            Action::GoToMatch
+        );
+        assert_eq!(
+            handle_key_event(key(KeyCode::Esc), &app),
+            Action::ClosePopup
+        );
+    }
+
+    #[test]
+    fn test_handle_key_event_tour() {
+        let mut app = App::for_testing(
+            crate::app::state::Tab::Kustomizations,
+            vec![],
+            vec![],
+            vec![],
+        );
+        app.popup = Popup::Tour { step: 0 };
+        assert_eq!(
+            handle_key_event(key(KeyCode::Enter), &app),
+            Action::TourNext
+        );
+        assert_eq!(
+            handle_key_event(key(KeyCode::Char(' ')), &app),
+            Action::TourNext
+        );
+        assert_eq!(handle_key_event(key(KeyCode::Left), &app), Action::TourBack);
+        assert_eq!(handle_key_event(key(KeyCode::Esc), &app), Action::EndTour);
+        assert_eq!(
+            handle_key_event(key(KeyCode::Char('r')), &app),
+            Action::Noop
+        );
     }
 
     #[test]
@@ -373,7 +979,9 @@ mod tests {
                 suspended: false,
                 source_ref: "".to_string(),
                 path: "".to_string(),
+                ..Default::default()
             }),
+            scroll: 0,
         };
 
         assert_eq!(
@@ -384,6 +992,38 @@ mod tests {
             handle_key_event(key(KeyCode::Enter), &app),
             Action::ClosePopup
         );
+        assert_eq!(
+            handle_key_event(key(KeyCode::Char('j')), &app),
+            Action::Scroll(1)
+        );
+        assert_eq!(
+            handle_key_event(key(KeyCode::PageUp), &app),
+            Action::Scroll(-10)
+        );
+    }
+
+    #[test]
+    fn test_handle_confirm_popup_keys() {
+        assert_eq!(
+            handle_confirm_popup_keys(key(KeyCode::Enter)),
+            Action::ConfirmSuspend
+        );
+        assert_eq!(
+            handle_confirm_popup_keys(key(KeyCode::Char('y'))),
+            Action::ConfirmSuspend
+        );
+        assert_eq!(
+            handle_confirm_popup_keys(key(KeyCode::Char('n'))),
+            Action::ClosePopup
+        );
+        assert_eq!(
+            handle_confirm_popup_keys(key(KeyCode::Esc)),
+            Action::ClosePopup
+        );
+        assert_eq!(
+            handle_confirm_popup_keys(key(KeyCode::Char('s'))),
+            Action::Noop
+        );
     }
 
     #[test]
@@ -397,6 +1037,7 @@ mod tests {
         app.popup = Popup::Reconciling {
             name: "test".to_string(),
             namespace: "ns".to_string(),
+            progress: Default::default(),
         };
 
         assert_eq!(
@@ -404,6 +1045,14 @@ mod tests {
             Action::Quit
         );
         assert_eq!(handle_key_event(key(KeyCode::Esc), &app), Action::Noop);
+
+        if let Popup::Reconciling { progress, .. } = &mut app.popup {
+            progress.outcome = Some(Ok(()));
+        }
+        assert_eq!(
+            handle_key_event(key(KeyCode::Esc), &app),
+            Action::ClosePopup
+        );
     }
 
     #[test]
@@ -431,4 +1080,192 @@ mod tests {
             Action::Quit
         );
     }
+
+    #[test]
+    fn test_handle_key_event_info_popup() {
+        let mut app = App::for_testing(
+            crate::app::state::Tab::Kustomizations,
+            vec![],
+            vec![],
+            vec![],
+        );
+        app.popup = Popup::Info {
+            message: "report written".to_string(),
+        };
+
+        assert_eq!(
+            handle_key_event(key(KeyCode::Enter), &app),
+            Action::ClosePopup
+        );
+        assert_eq!(
+            handle_key_event(key(KeyCode::Char('D')), &app),
+            Action::Noop
+        );
+    }
+
+    #[test]
+    fn test_normal_keys_write_diagnostics() {
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('D'))),
+            Action::WriteDiagnostics
+        );
+    }
+
+    #[test]
+    fn test_normal_keys_export_snapshot() {
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('S'))),
+            Action::ExportSnapshot
+        );
+    }
+
+    #[test]
+    fn test_normal_keys_switch_context() {
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('c'))),
+            Action::SwitchContext
+        );
+    }
+
+    #[test]
+    fn test_handle_context_popup_keys() {
+        let contexts = vec!["dev".to_string(), "prod".to_string()];
+
+        assert_eq!(
+            handle_context_popup_keys(key(KeyCode::Down), &contexts, 0),
+            Action::HighlightItem(1)
+        );
+        assert_eq!(
+            handle_context_popup_keys(key(KeyCode::Down), &contexts, 1),
+            Action::HighlightItem(1)
+        );
+        assert_eq!(
+            handle_context_popup_keys(key(KeyCode::Char('k')), &contexts, 0),
+            Action::HighlightItem(0)
+        );
+        assert_eq!(
+            handle_context_popup_keys(key(KeyCode::Enter), &contexts, 1),
+            Action::SetContext("prod".to_string())
+        );
+        assert_eq!(
+            handle_context_popup_keys(key(KeyCode::Esc), &contexts, 1),
+            Action::ClosePopup
+        );
+    }
+
+    #[test]
+    fn test_handle_cluster_popup_keys() {
+        let clusters = vec![
+            "All clusters".to_string(),
+            "prod-eu".to_string(),
+            "prod-us".to_string(),
+        ];
+
+        assert_eq!(
+            handle_normal_keys(key(KeyCode::Char('C'))),
+            Action::FilterCluster
+        );
+        assert_eq!(
+            handle_cluster_popup_keys(key(KeyCode::Down), &clusters, 0),
+            Action::HighlightItem(1)
+        );
+        assert_eq!(
+            handle_cluster_popup_keys(key(KeyCode::Enter), &clusters, 0),
+            Action::SetClusterFilter(None)
+        );
+        assert_eq!(
+            handle_cluster_popup_keys(key(KeyCode::Enter), &clusters, 2),
+            Action::SetClusterFilter(Some("prod-us".to_string()))
+        );
+    }
+
+    fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn test_handle_mouse_event_clicks() {
+        let app = App::for_testing(
+            crate::app::state::Tab::Kustomizations,
+            vec![],
+            vec![],
+            vec![],
+        );
+        let area = Rect::new(0, 0, 80, 24);
+        let click = |column, row| {
+            handle_mouse_event(
+                mouse(MouseEventKind::Down(MouseButton::Left), column, row),
+                &app,
+                area,
+            )
+        };
+
+        // Tabs are on rows 1-3, the table's first row below its border and header
+        assert_eq!(
+            click(23, 1),
+            Action::ClickTab(crate::app::state::Tab::HelmReleases)
+        );
+        assert_eq!(click(2, 6), Action::ClickRow(0));
+        assert_eq!(click(2, 10), Action::ClickRow(4));
+        assert_eq!(click(2, 5), Action::Noop);
+        assert_eq!(click(2, 23), Action::Noop);
+
+        // The activity sidebar takes the right half of an 80 column terminal
+        let mut app = app;
+        app.show_activity = true;
+        let click = |column, row| {
+            handle_mouse_event(
+                mouse(MouseEventKind::Down(MouseButton::Left), column, row),
+                &app,
+                area,
+            )
+        };
+        assert_eq!(click(2, 6), Action::ClickRow(0));
+        assert_eq!(click(60, 6), Action::Noop);
+    }
+
+    #[test]
+    fn test_handle_mouse_event_wheel() {
+        let mut app = App::for_testing(
+            crate::app::state::Tab::Kustomizations,
+            vec![],
+            vec![],
+            vec![],
+        );
+        let area = Rect::new(0, 0, 80, 24);
+        let wheel = |app: &App, kind| handle_mouse_event(mouse(kind, 10, 10), app, area);
+
+        assert_eq!(wheel(&app, MouseEventKind::ScrollDown), Action::Down);
+        assert_eq!(wheel(&app, MouseEventKind::ScrollUp), Action::Up);
+
+        app.popup = Popup::Help { scroll: 0 };
+        assert_eq!(wheel(&app, MouseEventKind::ScrollDown), Action::Scroll(3));
+        assert_eq!(wheel(&app, MouseEventKind::ScrollUp), Action::Scroll(-3));
+        assert_eq!(
+            handle_mouse_event(
+                mouse(MouseEventKind::Down(MouseButton::Left), 2, 6),
+                &app,
+                area
+            ),
+            Action::Noop
+        );
+
+        app.popup = Popup::ContextSwitcher {
+            contexts: vec!["a".to_string(), "b".to_string()],
+            selected: 1,
+        };
+        assert_eq!(
+            wheel(&app, MouseEventKind::ScrollDown),
+            Action::HighlightItem(1)
+        );
+        assert_eq!(
+            wheel(&app, MouseEventKind::ScrollUp),
+            Action::HighlightItem(0)
+        );
+    }
 }
@@ -0,0 +1,175 @@
+//! The cluster operations the app performs
+//!
+//! [`KubeClient`](super::client::KubeClient) implements [`FluxApi`] against a
+//! real cluster; tests build the app against
+//! [`MockClient`](super::mock::MockClient) instead. Methods return boxed
+//! futures so clients can be shared as `Arc<dyn FluxApi>` with background
+//! tasks.
+
+use color_eyre::Result;
+use futures::future::BoxFuture;
+use serde_json::Value;
+
+use super::client::{ApiLog, ClusterSnapshot, KubeEvent};
+use super::helm::Revision;
+use super::metrics::ReconcilerMetrics;
+use super::portforward::PortForward;
+use super::preflight;
+use super::resources::{
+    Condition, GitRef, GitRepository, KindInfo, ReconcileInfo, RefOverride, ResourceKind,
+    ORIGINAL_REF_ANNOTATION,
+};
+
+/// Reads and changes Flux resources on one cluster
+pub trait FluxApi: Send + Sync {
+    /// Get the cluster name
+    fn cluster_name(&self) -> &str;
+
+    /// Get the kube context this client was created for
+    fn context(&self) -> Option<&str>;
+
+    /// Label used to tag resources: the context name, or the cluster name
+    fn label(&self) -> &str {
+        self.context().unwrap_or(self.cluster_name())
+    }
+
+    /// Get a handle to the log of recent API requests
+    fn api_log(&self) -> ApiLog;
+
+    /// Fetch all Flux resources and namespaces in parallel
+    fn fetch_all<'a>(
+        &'a self,
+        namespace: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<ClusterSnapshot>>;
+
+    /// Fetch all Flux resources in a single namespace, without listing namespaces
+    ///
+    /// Used with a namespace allow-list, where listing namespaces may not
+    /// even be permitted.
+    fn fetch_namespace<'a>(&'a self, namespace: &'a str) -> BoxFuture<'a, Result<ClusterSnapshot>>;
+
+    /// Fetch the full manifest of a resource as YAML, without `managedFields`
+    fn get_manifest<'a>(
+        &'a self,
+        kind: &'a str,
+        namespace: &'a str,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<String>>;
+
+    /// Current reconcile settings and status of a resource
+    fn get_reconcile_info<'a>(
+        &'a self,
+        kind: &'a str,
+        namespace: &'a str,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<ReconcileInfo>>;
+
+    /// Current status conditions of a resource
+    fn get_conditions<'a>(
+        &'a self,
+        kind: &'a str,
+        namespace: &'a str,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Condition>>> {
+        Box::pin(async move {
+            Ok(self
+                .get_reconcile_info(kind, namespace, name)
+                .await?
+                .conditions)
+        })
+    }
+
+    /// Apply a JSON merge patch to a resource
+    fn patch_resource<'a>(
+        &'a self,
+        kind: &'a str,
+        namespace: &'a str,
+        name: &'a str,
+        patch: &'a Value,
+    ) -> BoxFuture<'a, Result<()>>;
+
+    /// Point a GitRepository at another ref, recording `original` so the
+    /// override can be reverted
+    fn override_git_ref<'a>(
+        &'a self,
+        namespace: &'a str,
+        name: &'a str,
+        reference: &'a GitRef,
+        original: Option<&'a GitRef>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let patch = serde_json::json!({
+                "metadata": {"annotations": {ORIGINAL_REF_ANNOTATION: RefOverride::annotation(original)}},
+                "spec": {"ref": GitRef::merge_patch(Some(reference))},
+            });
+            self.patch_resource(GitRepository::INFO.kind, namespace, name, &patch)
+                .await
+        })
+    }
+
+    /// Restore the ref a GitRepository had before it was overridden
+    fn revert_git_ref<'a>(
+        &'a self,
+        namespace: &'a str,
+        name: &'a str,
+        original: Option<&'a GitRef>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let patch = serde_json::json!({
+                "metadata": {"annotations": {ORIGINAL_REF_ANNOTATION: null}},
+                "spec": {"ref": GitRef::merge_patch(original)},
+            });
+            self.patch_resource(GitRepository::INFO.kind, namespace, name, &patch)
+                .await
+        })
+    }
+
+    /// Revisions of a Helm release, newest first, from its storage Secrets
+    fn helm_history<'a>(
+        &'a self,
+        namespace: &'a str,
+        release: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Revision>>>;
+
+    /// Port-forward serving an artifact URL, None inside the cluster where
+    /// the URL resolves directly
+    fn artifact_forward<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<Option<PortForward>>>;
+
+    /// Download a source artifact tarball
+    ///
+    /// Inside the cluster the artifact URL is reachable directly; otherwise
+    /// it is fetched through `forward` (see `artifact_forward`).
+    fn download_artifact<'a>(
+        &'a self,
+        url: &'a str,
+        forward: Option<&'a PortForward>,
+    ) -> BoxFuture<'a, Result<Vec<u8>>>;
+
+    /// Version of the API server, e.g. "v1.29.1"
+    fn server_version(&self) -> BoxFuture<'_, Result<String>>;
+
+    /// Versions the CRD of a kind serves, None when it is not installed
+    fn crd_versions<'a>(&'a self, info: &'a KindInfo)
+        -> BoxFuture<'a, Result<Option<Vec<String>>>>;
+
+    /// Replicas of the Deployments in the Flux namespace
+    fn flux_deployments(&self) -> BoxFuture<'_, Result<Vec<preflight::Deployment>>>;
+
+    /// Port-forward to the metrics port of a Flux controller
+    fn metrics_forward<'a>(&'a self, controller: &'a str) -> BoxFuture<'a, Result<PortForward>>;
+
+    /// Scrape controller metrics through a port-forward
+    fn scrape_metrics<'a>(
+        &'a self,
+        forward: &'a PortForward,
+    ) -> BoxFuture<'a, Result<Vec<ReconcilerMetrics>>>;
+
+    /// List Events whose involvedObject is the given resource, newest first
+    fn list_events_for<'a>(
+        &'a self,
+        kind: &'a str,
+        namespace: &'a str,
+        name: &'a str,
+        limit: usize,
+    ) -> BoxFuture<'a, Result<Vec<KubeEvent>>>;
+}
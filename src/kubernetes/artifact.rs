@@ -0,0 +1,112 @@
+//! Source artifacts served by source-controller
+//!
+//! Artifacts are gzipped tarballs of a fetched source. Inside the cluster
+//! they are downloaded from their URL; from outside, through a port-forward
+//! to source-controller.
+
+use std::io::Read;
+
+use color_eyre::Result;
+use flate2::read::GzDecoder;
+
+/// A file of a source artifact, read-only
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestFile {
+    /// Path relative to the browsed directory
+    pub path: String,
+
+    pub content: String,
+}
+
+/// Manifests (YAML and JSON files) under `dir` in an artifact tarball,
+/// sorted by path
+pub fn manifests(tarball: &[u8], dir: &str) -> Result<Vec<ManifestFile>> {
+    let dir = normalize(dir);
+    let mut files = Vec::new();
+    let mut archive = tar::Archive::new(GzDecoder::new(tarball));
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = normalize(&entry.path()?.to_string_lossy());
+        let path = if dir.is_empty() {
+            name.as_str()
+        } else {
+            match name.strip_prefix(&dir).and_then(|p| p.strip_prefix('/')) {
+                Some(path) => path,
+                None => continue,
+            }
+        };
+        if !is_manifest(path) {
+            continue;
+        }
+        let mut content = String::new();
+        if entry.read_to_string(&mut content).is_ok() {
+            files.push(ManifestFile {
+                path: path.to_string(),
+                content,
+            });
+        }
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Path without "." components and leading or trailing slashes ("./apps/" → "apps")
+fn normalize(path: &str) -> String {
+    path.split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Whether a file is something kustomize-controller would read
+fn is_manifest(path: &str) -> bool {
+    [".yaml", ".yml", ".json"]
+        .iter()
+        .any(|ext| path.ends_with(ext))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+
+    fn tarball(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::fast()));
+        for (path, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, content.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn test_manifests_under_path() {
+        let tarball = tarball(&[
+            (
+                "./apps/prod/kustomization.yaml",
+                "resources: [podinfo.yaml]",
+            ),
+            ("apps/prod/podinfo.yaml", "kind: Deployment"),
+            ("apps/prod/README.md", "docs"),
+            ("apps/production/other.yaml", "kind: Service"),
+            ("infra/base.yaml", "kind: Namespace"),
+        ]);
+
+        let files = manifests(&tarball, "./apps/prod").unwrap();
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["kustomization.yaml", "podinfo.yaml"]);
+        assert_eq!(files[1].content, "kind: Deployment");
+
+        assert_eq!(manifests(&tarball, "./").unwrap().len(), 4);
+        assert!(manifests(&tarball, "missing").unwrap().is_empty());
+        assert!(manifests(b"not a tarball", ".").is_err());
+    }
+}
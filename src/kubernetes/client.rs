@@ -1,37 +1,303 @@
 //! Kubernetes client wrapper using kube-rs
 
-use color_eyre::Result;
-use k8s_openapi::api::core::v1::Namespace;
+use std::{
+    future::Future,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use color_eyre::{eyre::eyre, Result};
+use k8s_openapi::{
+    api::{
+        apps::v1::Deployment,
+        core::v1::{Event, Namespace, Secret},
+    },
+    apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition,
+    apimachinery::pkg::apis::meta::v1::ObjectMeta,
+};
 use kube::{
-    api::{Api, DynamicObject, ListParams},
+    api::{Api, DynamicObject, GroupVersionKind, ListParams, Patch, PatchParams},
+    config::{KubeConfigOptions, Kubeconfig},
     discovery::{ApiCapabilities, ApiResource, Scope},
     Client, Config,
 };
 
-use super::resources::{HelmChart, HelmRelease, Kustomization};
+use futures::future::BoxFuture;
+use serde_json::Value;
+
+use super::api::FluxApi;
+use super::flux_version::VERSION_LABEL;
+use super::helm::{self, Revision};
+use super::metrics::{self, ReconcilerMetrics, FLUX_NAMESPACE, METRICS_PORT};
+use super::portforward::{in_cluster, PortForward, ServiceUrl};
+use super::preflight;
+use super::resources::{
+    kind, FluxResource, GitRepository, HelmChart, HelmRelease, HelmRepository, KindInfo,
+    Kustomization, ReconcileInfo, ResourceKind,
+};
+use crate::app::{buffer::BoundedBuffer, config::Config as AppConfig};
+
+/// Attempts at a list request that keeps failing with transient errors
+const LIST_ATTEMPTS: u32 = 3;
+
+/// Wait before retrying a list request, doubled after every retry
+const RETRY_BACKOFF: Duration = Duration::from_millis(250);
+
+/// A recorded Kubernetes API request, kept for diagnostics
+#[derive(Debug, Clone)]
+pub struct ApiCall {
+    /// When the request started
+    pub at: DateTime<Utc>,
+
+    /// Short description of the request (never includes credentials)
+    pub request: String,
+
+    /// How long the request took
+    pub duration: Duration,
+
+    /// Error message if the request failed
+    pub error: Option<String>,
+}
+
+/// Shared log of recent API requests
+pub type ApiLog = Arc<Mutex<BoundedBuffer<ApiCall>>>;
+
+/// Create an empty API log holding at most `capacity` requests
+pub fn new_api_log(capacity: usize) -> ApiLog {
+    Arc::new(Mutex::new(BoundedBuffer::new(capacity)))
+}
+
+/// A Kubernetes Event about a resource
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KubeEvent {
+    /// Event type ("Normal" or "Warning")
+    pub event_type: String,
+
+    /// Short machine-readable reason (e.g. "ReconciliationSucceeded")
+    pub reason: String,
+
+    /// Human-readable message
+    pub message: String,
+
+    /// How many times the event occurred
+    pub count: i32,
+
+    /// When the event was last seen
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+impl KubeEvent {
+    /// Convert a core/v1 Event, falling back to older timestamps when unset
+    fn from_event(event: Event) -> Self {
+        let last_seen = event
+            .last_timestamp
+            .map(|t| t.0)
+            .or(event.event_time.map(|t| t.0))
+            .or(event.first_timestamp.map(|t| t.0))
+            .or(event.metadata.creation_timestamp.map(|t| t.0));
+
+        Self {
+            event_type: event.type_.unwrap_or_default(),
+            reason: event.reason.unwrap_or_default(),
+            message: event.message.unwrap_or_default(),
+            count: event.count.unwrap_or(1),
+            last_seen,
+        }
+    }
+
+    /// Whether this is a Warning event
+    pub fn is_warning(&self) -> bool {
+        self.event_type == "Warning"
+    }
+}
+
+/// All Flux resources (and namespaces) fetched from one cluster
+#[derive(Debug, Clone, Default)]
+pub struct ClusterSnapshot {
+    pub kustomizations: Vec<Kustomization>,
+    pub helm_releases: Vec<HelmRelease>,
+    pub helm_charts: Vec<HelmChart>,
+    pub git_repositories: Vec<GitRepository>,
+    pub helm_repositories: Vec<HelmRepository>,
+    pub namespaces: Vec<String>,
+
+    /// Kinds that could not be listed, with a short reason (e.g. "forbidden")
+    pub failures: Vec<(&'static str, String)>,
+}
+
+impl ClusterSnapshot {
+    /// Combine per-kind list results, tolerating some kinds failing
+    ///
+    /// Fails only when no kind could be listed at all, which usually means
+    /// the cluster is unreachable.
+    fn from_results(
+        kustomizations: Result<Vec<Kustomization>>,
+        helm_releases: Result<Vec<HelmRelease>>,
+        helm_charts: Result<Vec<HelmChart>>,
+        git_repositories: Result<Vec<GitRepository>>,
+        helm_repositories: Result<Vec<HelmRepository>>,
+    ) -> Result<Self> {
+        let mut errors = Vec::new();
+        let mut snapshot = Self {
+            kustomizations: take(Kustomization::INFO.kind, kustomizations, &mut errors),
+            helm_releases: take(HelmRelease::INFO.kind, helm_releases, &mut errors),
+            helm_charts: take(HelmChart::INFO.kind, helm_charts, &mut errors),
+            git_repositories: take(GitRepository::INFO.kind, git_repositories, &mut errors),
+            helm_repositories: take(HelmRepository::INFO.kind, helm_repositories, &mut errors),
+            ..Default::default()
+        };
+        snapshot.failures = errors
+            .iter()
+            .map(|(kind, e)| (*kind, short_error(e)))
+            .collect();
+
+        if errors.len() == 5 {
+            return Err(errors.swap_remove(0).1);
+        }
+        Ok(snapshot)
+    }
+
+    /// Reason a kind could not be listed, if it failed
+    pub fn failure(&self, kind: &str) -> Option<&str> {
+        self.failures
+            .iter()
+            .find(|(k, _)| *k == kind)
+            .map(|(_, reason)| reason.as_str())
+    }
+
+    /// Whether listing some kind was rejected for the credentials
+    pub fn is_unauthorized(&self) -> bool {
+        self.failures
+            .iter()
+            .any(|(_, reason)| reason == "unauthorized")
+    }
+
+    /// The resources in one namespace
+    pub fn in_namespace(&self, namespace: &str) -> Self {
+        fn keep<T: FluxResource + Clone>(resources: &[T], namespace: &str) -> Vec<T> {
+            resources
+                .iter()
+                .filter(|r| r.namespace() == namespace)
+                .cloned()
+                .collect()
+        }
+        Self {
+            kustomizations: keep(&self.kustomizations, namespace),
+            helm_releases: keep(&self.helm_releases, namespace),
+            helm_charts: keep(&self.helm_charts, namespace),
+            git_repositories: keep(&self.git_repositories, namespace),
+            helm_repositories: keep(&self.helm_repositories, namespace),
+            namespaces: self.namespaces.clone(),
+            failures: Vec::new(),
+        }
+    }
+}
 
-/// API version and kind for Flux Kustomization
-const KUSTOMIZATION_API: (&str, &str) = ("kustomize.toolkit.fluxcd.io/v1", "Kustomization");
+/// Items of a list result, or none with the error set aside
+fn take<T>(
+    kind: &'static str,
+    result: Result<Vec<T>>,
+    errors: &mut Vec<(&'static str, color_eyre::Report)>,
+) -> Vec<T> {
+    result.unwrap_or_else(|e| {
+        errors.push((kind, e));
+        Vec::new()
+    })
+}
 
-/// API version and kind for Flux HelmRelease
-const HELMRELEASE_API: (&str, &str) = ("helm.toolkit.fluxcd.io/v2", "HelmRelease");
+/// Whether a request was rejected for invalid or expired credentials, or
+/// the credentials could not be obtained (e.g. a failing exec plugin)
+pub fn is_unauthorized(error: &color_eyre::Report) -> bool {
+    match error.downcast_ref::<kube::Error>() {
+        Some(kube::Error::Api(response)) => response.code == 401,
+        Some(kube::Error::Auth(_)) => true,
+        _ => false,
+    }
+}
 
-/// API version and kind for Flux HelmChart
-const HELMCHART_API: (&str, &str) = ("source.toolkit.fluxcd.io/v1", "HelmChart");
+/// Whether a request failed in a way that may pass on retry: the API server
+/// was overloaded or restarting, or the connection dropped
+pub fn is_transient(error: &color_eyre::Report) -> bool {
+    match error.downcast_ref::<kube::Error>() {
+        Some(kube::Error::Api(response)) => matches!(response.code, 429 | 500 | 502 | 503 | 504),
+        Some(kube::Error::HyperError(_) | kube::Error::Service(_)) => true,
+        _ => false,
+    }
+}
+
+/// Run a request, retrying transient failures with exponential backoff
+/// starting at `backoff`
+async fn with_retries<T, F, Fut>(backoff: Duration, mut request: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut delay = backoff;
+    for _ in 1..LIST_ATTEMPTS {
+        match request().await {
+            Err(e) if is_transient(&e) => {
+                tracing::debug!("Retrying in {delay:?} after a transient error: {e}");
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+    request().await
+}
+
+/// Short reason for a failed request: the API status reason when there is one
+pub fn short_error(error: &color_eyre::Report) -> String {
+    match error.downcast_ref::<kube::Error>() {
+        Some(kube::Error::Api(response)) if !response.reason.is_empty() => {
+            response.reason.to_lowercase()
+        }
+        _ => error.to_string(),
+    }
+}
 
 /// Kubernetes client wrapper for Flux resources
+#[derive(Clone)]
 pub struct KubeClient {
     /// The underlying kube client
     client: Client,
 
     /// Current cluster name
     cluster_name: String,
+
+    /// Kube context this client was created for (None = current context)
+    context: Option<String>,
+
+    /// Recent API requests
+    api_log: ApiLog,
 }
 
 impl KubeClient {
     /// Create a new KubeClient
-    pub async fn new() -> Result<Self> {
-        let config = Config::infer().await?;
+    ///
+    /// Uses the kubeconfig file and context from the app config when set,
+    /// otherwise infers the configuration (current context or in-cluster).
+    pub async fn new(app_config: &AppConfig) -> Result<Self> {
+        let config = if app_config.kubeconfig.is_some() || app_config.context.is_some() {
+            let kubeconfig = match &app_config.kubeconfig {
+                Some(path) => Kubeconfig::read_from(path)?,
+                None => Kubeconfig::read()?,
+            };
+
+            if let Some(context) = &app_config.context {
+                validate_context(&kubeconfig, context)?;
+            }
+
+            let options = KubeConfigOptions {
+                context: app_config.context.clone(),
+                ..Default::default()
+            };
+            Config::from_custom_kubeconfig(kubeconfig, &options).await?
+        } else {
+            Config::infer().await?
+        };
         let cluster_name = config
             .cluster_url
             .host()
@@ -50,18 +316,40 @@ impl KubeClient {
         Ok(Self {
             client,
             cluster_name,
+            context: app_config.context.clone(),
+            api_log: new_api_log(app_config.buffers.api_calls),
         })
     }
 
-    /// Get the cluster name
-    pub fn cluster_name(&self) -> &str {
-        &self.cluster_name
+    /// Run an API request and record its timing and outcome
+    async fn record<T>(&self, request: String, fut: impl Future<Output = Result<T>>) -> Result<T> {
+        let at = Utc::now();
+        let started = Instant::now();
+        let result = fut.await;
+
+        let call = ApiCall {
+            at,
+            request,
+            duration: started.elapsed(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        };
+        if let Ok(mut log) = self.api_log.lock() {
+            log.push(call);
+        }
+
+        result
     }
 
     /// List all namespaces
     pub async fn list_namespaces(&self) -> Result<Vec<String>> {
+        // Only names are needed, so skip transferring namespace specs/statuses
         let api: Api<Namespace> = Api::all(self.client.clone());
-        let namespaces = api.list(&ListParams::default()).await?;
+        let namespaces = with_retries(RETRY_BACKOFF, || {
+            self.record("list Namespace".to_string(), async {
+                Ok(api.list_metadata(&ListParams::default()).await?)
+            })
+        })
+        .await?;
 
         Ok(namespaces
             .items
@@ -70,61 +358,39 @@ impl KubeClient {
             .collect())
     }
 
-    /// List Kustomizations
-    pub async fn list_kustomizations(&self, namespace: Option<&str>) -> Result<Vec<Kustomization>> {
-        let api = self.create_dynamic_api(KUSTOMIZATION_API.0, KUSTOMIZATION_API.1, namespace);
-        let list = api.list(&ListParams::default()).await?;
-
-        Ok(list
-            .items
-            .into_iter()
-            .filter_map(|obj| {
-                let name = obj.metadata.name.clone()?;
-                let ns = obj.metadata.namespace.clone().unwrap_or_default();
-                let spec = obj.data.get("spec").cloned().unwrap_or_default();
-                let status = obj.data.get("status").cloned().unwrap_or_default();
-
-                Some(Kustomization::from_kube(name, ns, &spec, &status))
+    /// List resources of a kind
+    ///
+    /// Transient failures are retried, each attempt recorded in the API log.
+    pub async fn list<T: ResourceKind>(&self, namespace: Option<&str>) -> Result<Vec<T>> {
+        let info = T::INFO;
+        let api = self.create_dynamic_api(info.api_version, info.kind, namespace);
+        let list = with_retries(RETRY_BACKOFF, || {
+            self.record(list_request(info.kind, namespace), async {
+                Ok(api.list(&ListParams::default()).await?)
             })
-            .collect())
-    }
-
-    /// List HelmReleases
-    pub async fn list_helm_releases(&self, namespace: Option<&str>) -> Result<Vec<HelmRelease>> {
-        let api = self.create_dynamic_api(HELMRELEASE_API.0, HELMRELEASE_API.1, namespace);
-        let list = api.list(&ListParams::default()).await?;
+        })
+        .await?;
 
         Ok(list
             .items
             .into_iter()
-            .filter_map(|obj| {
-                let name = obj.metadata.name.clone()?;
-                let ns = obj.metadata.namespace.clone().unwrap_or_default();
-                let spec = obj.data.get("spec").cloned().unwrap_or_default();
-                let status = obj.data.get("status").cloned().unwrap_or_default();
-
-                Some(HelmRelease::from_kube(name, ns, &spec, &status))
-            })
+            .filter_map(split_object)
+            .map(|(metadata, spec, status)| T::parse(&metadata, &spec, &status, self.label()))
             .collect())
     }
 
-    /// List HelmCharts
-    pub async fn list_helm_charts(&self, namespace: Option<&str>) -> Result<Vec<HelmChart>> {
-        let api = self.create_dynamic_api(HELMCHART_API.0, HELMCHART_API.1, namespace);
-        let list = api.list(&ListParams::default()).await?;
-
-        Ok(list
-            .items
-            .into_iter()
-            .filter_map(|obj| {
-                let name = obj.metadata.name.clone()?;
-                let ns = obj.metadata.namespace.clone().unwrap_or_default();
-                let spec = obj.data.get("spec").cloned().unwrap_or_default();
-                let status = obj.data.get("status").cloned().unwrap_or_default();
-
-                Some(HelmChart::from_kube(name, ns, &spec, &status))
-            })
-            .collect())
+    /// Port-forward to a pod behind a Service, open until the handle is dropped
+    pub async fn port_forward(
+        &self,
+        namespace: &str,
+        service: &str,
+        port: u16,
+    ) -> Result<PortForward> {
+        self.record(
+            format!("portforward Service -n {namespace} {service}:{port}"),
+            PortForward::open(self.client.clone(), namespace, service, port),
+        )
+        .await
     }
 
     /// Create a dynamic API for a custom resource
@@ -136,13 +402,8 @@ impl KubeClient {
     ) -> Api<DynamicObject> {
         let (group, version) = parse_api_version(api_version);
 
-        let ar = ApiResource {
-            group: group.to_string(),
-            version: version.to_string(),
-            kind: kind.to_string(),
-            api_version: api_version.to_string(),
-            plural: format!("{}s", kind.to_lowercase()),
-        };
+        // from_gvk handles irregular plurals (e.g. gitrepositories)
+        let ar = ApiResource::from_gvk(&GroupVersionKind::gvk(group, version, kind));
 
         let _caps = ApiCapabilities {
             scope: Scope::Namespaced,
@@ -157,6 +418,408 @@ impl KubeClient {
     }
 }
 
+impl FluxApi for KubeClient {
+    fn cluster_name(&self) -> &str {
+        &self.cluster_name
+    }
+
+    fn context(&self) -> Option<&str> {
+        self.context.as_deref()
+    }
+
+    fn api_log(&self) -> ApiLog {
+        Arc::clone(&self.api_log)
+    }
+
+    fn fetch_all<'a>(
+        &'a self,
+        namespace: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<ClusterSnapshot>> {
+        Box::pin(async move {
+            let (
+                kustomizations,
+                helm_releases,
+                helm_charts,
+                git_repositories,
+                helm_repositories,
+                namespaces,
+            ) = tokio::join!(
+                self.list::<Kustomization>(namespace),
+                self.list::<HelmRelease>(namespace),
+                self.list::<HelmChart>(namespace),
+                self.list::<GitRepository>(namespace),
+                self.list::<HelmRepository>(namespace),
+                self.list_namespaces(),
+            );
+
+            Ok(ClusterSnapshot {
+                // Listing namespaces may be forbidden even when Flux kinds are not
+                namespaces: namespaces.unwrap_or_default(),
+                ..ClusterSnapshot::from_results(
+                    kustomizations,
+                    helm_releases,
+                    helm_charts,
+                    git_repositories,
+                    helm_repositories,
+                )?
+            })
+        })
+    }
+
+    fn fetch_namespace<'a>(&'a self, namespace: &'a str) -> BoxFuture<'a, Result<ClusterSnapshot>> {
+        Box::pin(async move {
+            let ns = Some(namespace);
+            let (kustomizations, helm_releases, helm_charts, git_repositories, helm_repositories) = tokio::join!(
+                self.list::<Kustomization>(ns),
+                self.list::<HelmRelease>(ns),
+                self.list::<HelmChart>(ns),
+                self.list::<GitRepository>(ns),
+                self.list::<HelmRepository>(ns),
+            );
+
+            Ok(ClusterSnapshot {
+                namespaces: vec![namespace.to_string()],
+                ..ClusterSnapshot::from_results(
+                    kustomizations,
+                    helm_releases,
+                    helm_charts,
+                    git_repositories,
+                    helm_repositories,
+                )?
+            })
+        })
+    }
+
+    fn get_manifest<'a>(
+        &'a self,
+        kind: &'a str,
+        namespace: &'a str,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let (api_version, kind) =
+                api_for_kind(kind).ok_or_else(|| eyre!("Unsupported resource kind: {kind}"))?;
+            let api = self.create_dynamic_api(api_version, kind, Some(namespace));
+            let mut obj = self
+                .record(format!("get {kind} {namespace}/{name}"), async {
+                    Ok(api.get(name).await?)
+                })
+                .await?;
+            obj.metadata.managed_fields = None;
+
+            super::yaml::to_string(&obj)
+        })
+    }
+
+    fn get_reconcile_info<'a>(
+        &'a self,
+        kind: &'a str,
+        namespace: &'a str,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<ReconcileInfo>> {
+        Box::pin(async move {
+            let (api_version, kind) =
+                api_for_kind(kind).ok_or_else(|| eyre!("Unsupported resource kind: {kind}"))?;
+            let api = self.create_dynamic_api(api_version, kind, Some(namespace));
+            let obj = self
+                .record(format!("get {kind} {namespace}/{name}"), async {
+                    Ok(api.get(name).await?)
+                })
+                .await?;
+            let spec = obj.data.get("spec").cloned().unwrap_or_default();
+            let status = obj.data.get("status").cloned().unwrap_or_default();
+            Ok(ReconcileInfo::from_kube(&spec, &status))
+        })
+    }
+
+    fn patch_resource<'a>(
+        &'a self,
+        kind: &'a str,
+        namespace: &'a str,
+        name: &'a str,
+        patch: &'a Value,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let (api_version, kind) =
+                api_for_kind(kind).ok_or_else(|| eyre!("Unsupported resource kind: {kind}"))?;
+            let api = self.create_dynamic_api(api_version, kind, Some(namespace));
+            self.record(format!("patch {kind} {namespace}/{name}"), async {
+                api.patch(name, &PatchParams::default(), &Patch::Merge(patch))
+                    .await?;
+                Ok(())
+            })
+            .await
+        })
+    }
+
+    fn helm_history<'a>(
+        &'a self,
+        namespace: &'a str,
+        release: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Revision>>> {
+        Box::pin(async move {
+            let api: Api<Secret> = Api::namespaced(self.client.clone(), namespace);
+            let params = ListParams::default().labels(&helm::release_selector(release));
+            let secrets = self
+                .record(
+                    format!("list Secret -n {namespace} (helm {release})"),
+                    async { Ok(api.list(&params).await?) },
+                )
+                .await?;
+
+            let mut revisions = secrets
+                .items
+                .iter()
+                .filter_map(|secret| secret.data.as_ref()?.get("release"))
+                .map(|data| helm::parse_release(&data.0))
+                .collect::<Result<Vec<_>>>()?;
+            if revisions.is_empty() {
+                return Err(eyre!(
+                    "No Helm history for release {release} in namespace {namespace}"
+                ));
+            }
+            revisions.sort_by_key(|r| std::cmp::Reverse(r.revision));
+            Ok(revisions)
+        })
+    }
+
+    fn artifact_forward<'a>(&'a self, url: &'a str) -> BoxFuture<'a, Result<Option<PortForward>>> {
+        Box::pin(async move {
+            if in_cluster() {
+                return Ok(None);
+            }
+            let target =
+                ServiceUrl::parse(url).ok_or_else(|| eyre!("Unexpected artifact URL: {url}"))?;
+            self.port_forward(&target.namespace, &target.service, target.port)
+                .await
+                .map(Some)
+        })
+    }
+
+    fn download_artifact<'a>(
+        &'a self,
+        url: &'a str,
+        forward: Option<&'a PortForward>,
+    ) -> BoxFuture<'a, Result<Vec<u8>>> {
+        Box::pin(async move {
+            let url = match forward {
+                Some(forward) => {
+                    let target = ServiceUrl::parse(url)
+                        .ok_or_else(|| eyre!("Unexpected artifact URL: {url}"))?;
+                    forward.url(&target.path)
+                }
+                None => url.to_string(),
+            };
+            self.record(format!("GET {url}"), async {
+                let response = reqwest::get(&url).await?.error_for_status()?;
+                Ok(response.bytes().await?.to_vec())
+            })
+            .await
+        })
+    }
+
+    fn server_version(&self) -> BoxFuture<'_, Result<String>> {
+        Box::pin(self.record("GET /version".to_string(), async {
+            Ok(self.client.apiserver_version().await?.git_version)
+        }))
+    }
+
+    fn crd_versions<'a>(
+        &'a self,
+        info: &'a KindInfo,
+    ) -> BoxFuture<'a, Result<Option<Vec<String>>>> {
+        Box::pin(async move {
+            let (group, version) = parse_api_version(info.api_version);
+            let plural =
+                ApiResource::from_gvk(&GroupVersionKind::gvk(group, version, info.kind)).plural;
+            let name = format!("{plural}.{group}");
+            let api: Api<CustomResourceDefinition> = Api::all(self.client.clone());
+            let crd = self
+                .record(format!("get CustomResourceDefinition {name}"), async {
+                    Ok(api.get_opt(&name).await?)
+                })
+                .await?;
+
+            Ok(crd.map(|crd| {
+                crd.spec
+                    .versions
+                    .into_iter()
+                    .filter(|v| v.served)
+                    .map(|v| v.name)
+                    .collect()
+            }))
+        })
+    }
+
+    fn flux_deployments(&self) -> BoxFuture<'_, Result<Vec<preflight::Deployment>>> {
+        Box::pin(async move {
+            let api: Api<Deployment> = Api::namespaced(self.client.clone(), FLUX_NAMESPACE);
+            let list = self
+                .record(format!("list Deployment -n {FLUX_NAMESPACE}"), async {
+                    Ok(api.list(&ListParams::default()).await?)
+                })
+                .await?;
+
+            Ok(list
+                .items
+                .into_iter()
+                .map(|deployment| {
+                    let status = deployment.status.unwrap_or_default();
+                    let spec = deployment.spec.unwrap_or_default();
+                    preflight::Deployment {
+                        name: deployment.metadata.name.unwrap_or_default(),
+                        ready: status.ready_replicas.unwrap_or(0),
+                        desired: spec.replicas.unwrap_or(1),
+                        version: deployment
+                            .metadata
+                            .labels
+                            .and_then(|mut labels| labels.remove(VERSION_LABEL)),
+                        image: spec
+                            .template
+                            .spec
+                            .and_then(|pod| pod.containers.into_iter().next())
+                            .and_then(|container| container.image),
+                    }
+                })
+                .collect())
+        })
+    }
+
+    fn metrics_forward<'a>(&'a self, controller: &'a str) -> BoxFuture<'a, Result<PortForward>> {
+        Box::pin(self.record(
+            format!("portforward Pod -n {FLUX_NAMESPACE} -l app={controller}"),
+            async move {
+                PortForward::open_pod(
+                    self.client.clone(),
+                    FLUX_NAMESPACE,
+                    &format!("app={controller}"),
+                    METRICS_PORT,
+                )
+                .await
+            },
+        ))
+    }
+
+    fn scrape_metrics<'a>(
+        &'a self,
+        forward: &'a PortForward,
+    ) -> BoxFuture<'a, Result<Vec<ReconcilerMetrics>>> {
+        Box::pin(async move {
+            let url = forward.url("metrics");
+            self.record(format!("GET {url}"), async {
+                let response = reqwest::get(&url).await?.error_for_status()?;
+                Ok(metrics::parse(&response.text().await?))
+            })
+            .await
+        })
+    }
+
+    fn list_events_for<'a>(
+        &'a self,
+        kind: &'a str,
+        namespace: &'a str,
+        name: &'a str,
+        limit: usize,
+    ) -> BoxFuture<'a, Result<Vec<KubeEvent>>> {
+        Box::pin(async move {
+            let api: Api<Event> = Api::namespaced(self.client.clone(), namespace);
+            let params = ListParams::default().fields(&format!(
+                "involvedObject.kind={kind},involvedObject.name={name}"
+            ));
+            let list = self
+                .record(
+                    format!("list Event -n {namespace} for {kind}/{name}"),
+                    async { Ok(api.list(&params).await?) },
+                )
+                .await?;
+
+            let mut events: Vec<KubeEvent> =
+                list.items.into_iter().map(KubeEvent::from_event).collect();
+            events.sort_by_key(|e| std::cmp::Reverse(e.last_seen));
+            events.truncate(limit);
+            Ok(events)
+        })
+    }
+}
+
+/// API version and kind for a resource kind name (e.g. "GitRepository")
+fn api_for_kind(kind: &str) -> Option<(&'static str, &'static str)> {
+    kind::info_for(kind).map(|info| (info.api_version, info.kind))
+}
+
+/// Describe a list request for the API log
+fn list_request(kind: &str, namespace: Option<&str>) -> String {
+    match namespace {
+        Some(ns) => format!("list {kind} -n {ns}"),
+        None => format!("list {kind} (all namespaces)"),
+    }
+}
+
+/// Split a dynamic object into metadata, spec and status without copying
+///
+/// Objects without a name are skipped. `managedFields` is dropped since it is
+/// often the largest part of the metadata and never displayed.
+fn split_object(mut obj: DynamicObject) -> Option<(ObjectMeta, Value, Value)> {
+    obj.metadata.name.as_ref()?;
+    obj.metadata.managed_fields = None;
+
+    let spec = obj
+        .data
+        .get_mut("spec")
+        .map(Value::take)
+        .unwrap_or_default();
+    let status = obj
+        .data
+        .get_mut("status")
+        .map(Value::take)
+        .unwrap_or_default();
+
+    Some((obj.metadata, spec, status))
+}
+
+/// List kubeconfig context names and the current context
+///
+/// Reads the given kubeconfig file, or the default one (honouring `KUBECONFIG`).
+pub fn list_contexts(kubeconfig: Option<&Path>) -> Result<(Vec<String>, Option<String>)> {
+    let kubeconfig = match kubeconfig {
+        Some(path) => Kubeconfig::read_from(path)?,
+        None => Kubeconfig::read()?,
+    };
+    Ok(context_names(&kubeconfig))
+}
+
+/// Context names and current context of a parsed kubeconfig
+fn context_names(kubeconfig: &Kubeconfig) -> (Vec<String>, Option<String>) {
+    let names = kubeconfig.contexts.iter().map(|c| c.name.clone()).collect();
+    (names, kubeconfig.current_context.clone())
+}
+
+/// Ensure a context exists in the kubeconfig, listing the available ones if not
+fn validate_context(kubeconfig: &Kubeconfig, context: &str) -> Result<()> {
+    if kubeconfig.contexts.iter().any(|c| c.name == context) {
+        return Ok(());
+    }
+
+    let available: Vec<&str> = kubeconfig
+        .contexts
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect();
+
+    if available.is_empty() {
+        Err(eyre!(
+            "Context '{}' not found: the kubeconfig has no contexts",
+            context
+        ))
+    } else {
+        Err(eyre!(
+            "Context '{}' not found. Available contexts: {}",
+            context,
+            available.join(", ")
+        ))
+    }
+}
+
 /// Parse an API version string into group and version
 fn parse_api_version(api_version: &str) -> (&str, &str) {
     if let Some(idx) = api_version.rfind('/') {
@@ -170,6 +833,147 @@ fn parse_api_version(api_version: &str) -> (&str, &str) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_snapshot_tolerates_some_kinds_failing() {
+        let forbidden = kube::Error::Api(kube::core::ErrorResponse {
+            status: "Failure".to_string(),
+            message: "helmcharts is forbidden".to_string(),
+            reason: "Forbidden".to_string(),
+            code: 403,
+        });
+        let snapshot = ClusterSnapshot::from_results(
+            Ok(vec![Kustomization::default()]),
+            Ok(vec![]),
+            Err(forbidden.into()),
+            Ok(vec![]),
+            Ok(vec![]),
+        )
+        .unwrap();
+
+        assert_eq!(snapshot.kustomizations.len(), 1);
+        assert_eq!(snapshot.failure("HelmChart"), Some("forbidden"));
+        assert_eq!(snapshot.failure("Kustomization"), None);
+    }
+
+    #[test]
+    fn test_is_unauthorized() {
+        let response = |code| {
+            kube::Error::Api(kube::core::ErrorResponse {
+                status: "Failure".to_string(),
+                message: String::new(),
+                reason: String::new(),
+                code,
+            })
+        };
+        assert!(is_unauthorized(&response(401).into()));
+        assert!(!is_unauthorized(&response(403).into()));
+        assert!(!is_unauthorized(&eyre!("connection refused")));
+    }
+
+    #[test]
+    fn test_is_transient() {
+        let response = |code| {
+            kube::Error::Api(kube::core::ErrorResponse {
+                status: "Failure".to_string(),
+                message: String::new(),
+                reason: String::new(),
+                code,
+            })
+        };
+        assert!(is_transient(&response(503).into()));
+        assert!(is_transient(&response(429).into()));
+        assert!(!is_transient(&response(403).into()));
+        assert!(!is_transient(&response(404).into()));
+        assert!(!is_transient(&eyre!("invalid manifest")));
+    }
+
+    #[tokio::test]
+    async fn test_with_retries() {
+        let unavailable = || -> color_eyre::Report {
+            kube::Error::Api(kube::core::ErrorResponse {
+                status: "Failure".to_string(),
+                message: String::new(),
+                reason: "ServiceUnavailable".to_string(),
+                code: 503,
+            })
+            .into()
+        };
+
+        // Recovers within the attempts
+        let mut calls = 0;
+        let result = with_retries(Duration::ZERO, || {
+            calls += 1;
+            let result = if calls < LIST_ATTEMPTS {
+                Err(unavailable())
+            } else {
+                Ok(calls)
+            };
+            async { result }
+        })
+        .await;
+        assert_eq!(result.unwrap(), LIST_ATTEMPTS);
+
+        // Gives up after the last attempt
+        let mut calls = 0;
+        let result: Result<()> = with_retries(Duration::ZERO, || {
+            calls += 1;
+            let error = unavailable();
+            async { Err(error) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls, LIST_ATTEMPTS);
+
+        // Other errors aren't retried
+        let mut calls = 0;
+        let result: Result<()> = with_retries(Duration::ZERO, || {
+            calls += 1;
+            async { Err(eyre!("forbidden")) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_snapshot_fails_when_every_kind_fails() {
+        let result = ClusterSnapshot::from_results(
+            Err(eyre!("connection refused")),
+            Err(eyre!("connection refused")),
+            Err(eyre!("connection refused")),
+            Err(eyre!("connection refused")),
+            Err(eyre!("connection refused")),
+        );
+        assert_eq!(result.unwrap_err().to_string(), "connection refused");
+    }
+
+    #[test]
+    fn test_kube_event_from_event() {
+        use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+
+        let seen = Utc::now();
+        let event = Event {
+            type_: Some("Warning".to_string()),
+            reason: Some("BuildFailed".to_string()),
+            message: Some("kustomize build failed".to_string()),
+            count: Some(3),
+            first_timestamp: Some(Time(seen)),
+            ..Default::default()
+        };
+
+        let event = KubeEvent::from_event(event);
+        assert!(event.is_warning());
+        assert_eq!(event.reason, "BuildFailed");
+        assert_eq!(event.message, "kustomize build failed");
+        assert_eq!(event.count, 3);
+        assert_eq!(event.last_seen, Some(seen));
+
+        let empty = KubeEvent::from_event(Event::default());
+        assert!(!empty.is_warning());
+        assert_eq!(empty.count, 1);
+        assert_eq!(empty.last_seen, None);
+    }
+
     #[test]
     fn test_parse_api_version_with_group() {
         let (group, version) = parse_api_version("kustomize.toolkit.fluxcd.io/v1");
@@ -198,21 +1002,140 @@ mod tests {
         assert_eq!(version, "v1");
     }
 
+    #[test]
+    fn test_api_for_kind() {
+        assert_eq!(
+            api_for_kind("GitRepository"),
+            Some(("source.toolkit.fluxcd.io/v1", "GitRepository"))
+        );
+        assert_eq!(
+            api_for_kind("HelmRelease"),
+            Some(("helm.toolkit.fluxcd.io/v2", "HelmRelease"))
+        );
+        assert_eq!(api_for_kind("Deployment"), None);
+    }
+
+    #[test]
+    fn test_list_request() {
+        assert_eq!(
+            list_request("Kustomization", Some("flux-system")),
+            "list Kustomization -n flux-system"
+        );
+        assert_eq!(
+            list_request("HelmRelease", None),
+            "list HelmRelease (all namespaces)"
+        );
+    }
+
+    fn dynamic_object(name: Option<&str>, data: Value) -> DynamicObject {
+        let ar = ApiResource::from_gvk(&kube::api::GroupVersionKind::gvk(
+            "kustomize.toolkit.fluxcd.io",
+            "v1",
+            "Kustomization",
+        ));
+        let mut obj = DynamicObject::new(name.unwrap_or_default(), &ar).data(data);
+        obj.metadata.name = name.map(String::from);
+        obj
+    }
+
+    #[test]
+    fn test_split_object_takes_spec_and_status() {
+        let mut obj = dynamic_object(
+            Some("apps"),
+            serde_json::json!({
+                "spec": {"path": "./apps"},
+                "status": {"conditions": []}
+            }),
+        );
+        obj.metadata.managed_fields = Some(vec![Default::default()]);
+
+        let (metadata, spec, status) = split_object(obj).expect("named object");
+        assert_eq!(metadata.name.as_deref(), Some("apps"));
+        assert!(metadata.managed_fields.is_none());
+        assert_eq!(spec["path"], "./apps");
+        assert!(status["conditions"].is_array());
+    }
+
+    #[test]
+    fn test_split_object_missing_fields_default_to_null() {
+        let obj = dynamic_object(Some("bare"), serde_json::json!({}));
+        let (_, spec, status) = split_object(obj).expect("named object");
+        assert!(spec.is_null());
+        assert!(status.is_null());
+    }
+
+    #[test]
+    fn test_split_object_skips_unnamed() {
+        let obj = dynamic_object(None, serde_json::json!({"spec": {}}));
+        assert!(split_object(obj).is_none());
+    }
+
+    fn kubeconfig_with_contexts(names: &[&str]) -> Kubeconfig {
+        Kubeconfig {
+            contexts: names
+                .iter()
+                .map(|name| kube::config::NamedContext {
+                    name: name.to_string(),
+                    context: None,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_context_names() {
+        let mut kubeconfig = kubeconfig_with_contexts(&["dev", "prod"]);
+        kubeconfig.current_context = Some("prod".to_string());
+
+        let (names, current) = context_names(&kubeconfig);
+        assert_eq!(names, vec!["dev", "prod"]);
+        assert_eq!(current.as_deref(), Some("prod"));
+    }
+
+    #[test]
+    fn test_validate_context_exists() {
+        let kubeconfig = kubeconfig_with_contexts(&["dev", "prod"]);
+        assert!(validate_context(&kubeconfig, "prod").is_ok());
+    }
+
+    #[test]
+    fn test_validate_context_missing_lists_available() {
+        let kubeconfig = kubeconfig_with_contexts(&["dev", "prod"]);
+        let err = validate_context(&kubeconfig, "staging").unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("'staging' not found"));
+        assert!(message.contains("dev, prod"));
+    }
+
+    #[test]
+    fn test_validate_context_no_contexts() {
+        let kubeconfig = kubeconfig_with_contexts(&[]);
+        let err = validate_context(&kubeconfig, "dev").unwrap_err();
+        assert!(err.to_string().contains("no contexts"));
+    }
+
     #[test]
     fn test_kustomization_api_constant() {
-        assert_eq!(KUSTOMIZATION_API.0, "kustomize.toolkit.fluxcd.io/v1");
-        assert_eq!(KUSTOMIZATION_API.1, "Kustomization");
+        assert_eq!(
+            api_for_kind("Kustomization"),
+            Some(("kustomize.toolkit.fluxcd.io/v1", "Kustomization"))
+        );
     }
 
     #[test]
     fn test_helmrelease_api_constant() {
-        assert_eq!(HELMRELEASE_API.0, "helm.toolkit.fluxcd.io/v2");
-        assert_eq!(HELMRELEASE_API.1, "HelmRelease");
+        assert_eq!(
+            api_for_kind("HelmRelease"),
+            Some(("helm.toolkit.fluxcd.io/v2", "HelmRelease"))
+        );
     }
 
     #[test]
     fn test_helmchart_api_constant() {
-        assert_eq!(HELMCHART_API.0, "source.toolkit.fluxcd.io/v1");
-        assert_eq!(HELMCHART_API.1, "HelmChart");
+        assert_eq!(
+            api_for_kind("HelmChart"),
+            Some(("source.toolkit.fluxcd.io/v1", "HelmChart"))
+        );
     }
 }
@@ -0,0 +1,149 @@
+//! Version of the Flux installation, like `flux version`
+//!
+//! Read from the Deployments in the Flux namespace: the distribution version
+//! from their `app.kubernetes.io/version` label, which `flux install` and
+//! `flux bootstrap` set, and each controller's version from its image tag.
+
+use super::preflight::Deployment;
+
+/// Label `flux install` puts on its Deployments with the distribution version
+pub const VERSION_LABEL: &str = "app.kubernetes.io/version";
+
+/// Flux distribution and controller versions of a cluster
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FluxVersion {
+    /// Distribution version (e.g. "v2.3.0"), when the Deployments are labeled
+    pub distribution: Option<String>,
+
+    /// The Deployments are labeled with different versions, e.g. during an
+    /// upgrade
+    pub mixed: bool,
+
+    /// Every Deployment in the Flux namespace, sorted by name
+    pub controllers: Vec<ControllerVersion>,
+}
+
+/// Image of one Flux Deployment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControllerVersion {
+    pub name: String,
+
+    /// Image of the first container, if it has one
+    pub image: Option<String>,
+}
+
+impl ControllerVersion {
+    /// Tag of the image (e.g. "v1.3.0"), without a digest
+    pub fn tag(&self) -> Option<&str> {
+        let image = self.image.as_deref()?;
+        let image = image.split_once('@').map_or(image, |(image, _)| image);
+        let (repository, tag) = image.rsplit_once(':')?;
+        // A colon before the last slash is a registry port, not a tag
+        (!tag.contains('/') && !repository.is_empty()).then_some(tag)
+    }
+}
+
+impl FluxVersion {
+    /// Versions of these Deployments
+    ///
+    /// Deployments normally share one distribution label; when they don't,
+    /// the most common one is taken.
+    pub fn of(deployments: &[Deployment]) -> Self {
+        let mut labels: Vec<(&str, usize)> = Vec::new();
+        for version in deployments.iter().filter_map(|d| d.version.as_deref()) {
+            match labels.iter_mut().find(|(label, _)| *label == version) {
+                Some((_, count)) => *count += 1,
+                None => labels.push((version, 1)),
+            }
+        }
+        let distribution = labels
+            .iter()
+            .rev()
+            .max_by_key(|(_, count)| *count)
+            .map(|(label, _)| label.to_string());
+
+        let mut controllers: Vec<ControllerVersion> = deployments
+            .iter()
+            .map(|d| ControllerVersion {
+                name: d.name.clone(),
+                image: d.image.clone(),
+            })
+            .collect();
+        controllers.sort_by(|a, b| a.name.cmp(&b.name));
+        Self {
+            distribution,
+            mixed: labels.len() > 1,
+            controllers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deployment(name: &str, version: Option<&str>, image: &str) -> Deployment {
+        Deployment {
+            name: name.to_string(),
+            ready: 1,
+            desired: 1,
+            version: version.map(String::from),
+            image: Some(image.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_of_deployments() {
+        let version = FluxVersion::of(&[
+            deployment(
+                "source-controller",
+                Some("v2.3.0"),
+                "ghcr.io/fluxcd/source-controller:v1.3.0",
+            ),
+            deployment(
+                "helm-controller",
+                Some("v2.3.0"),
+                "ghcr.io/fluxcd/helm-controller:v1.0.1@sha256:abc",
+            ),
+        ]);
+        assert_eq!(version.distribution.as_deref(), Some("v2.3.0"));
+        assert_eq!(version.controllers[0].name, "helm-controller");
+        assert_eq!(version.controllers[0].tag(), Some("v1.0.1"));
+        assert_eq!(version.controllers[1].tag(), Some("v1.3.0"));
+        assert_eq!(FluxVersion::of(&[]), FluxVersion::default());
+    }
+
+    #[test]
+    fn test_mixed_labels_take_the_most_common() {
+        let deployments = [
+            deployment("a", Some("v2.2.3"), "a:v1"),
+            deployment("b", Some("v2.3.0"), "b:v1"),
+            deployment("c", Some("v2.3.0"), "c:v1"),
+            deployment("d", None, "d:v1"),
+        ];
+        assert_eq!(
+            FluxVersion::of(&deployments).distribution.as_deref(),
+            Some("v2.3.0")
+        );
+        assert!(FluxVersion::of(&deployments).mixed);
+        assert!(!FluxVersion::of(&deployments[1..]).mixed);
+    }
+
+    #[test]
+    fn test_tag() {
+        let tag = |image: &str| {
+            ControllerVersion {
+                name: String::new(),
+                image: Some(image.to_string()),
+            }
+            .tag()
+            .map(String::from)
+        };
+        assert_eq!(tag("registry:5000/fluxcd/source-controller"), None);
+        assert_eq!(
+            tag("registry:5000/fluxcd/source-controller:v1.3.0").as_deref(),
+            Some("v1.3.0")
+        );
+        assert_eq!(tag("source-controller@sha256:abc"), None);
+    }
+}
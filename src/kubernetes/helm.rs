@@ -0,0 +1,216 @@
+//! Helm release history and rollback
+//!
+//! helm-controller stores releases the way the helm CLI does: one Secret per
+//! revision (type `helm.sh/release.v1`) holding the gzipped release JSON, in
+//! the HelmRelease's storage namespace. The history is read from those
+//! Secrets; rolling back runs the `helm` CLI, which knows how to re-apply an
+//! old revision.
+
+use std::{io::Read, time::Duration};
+
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use color_eyre::Result;
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+
+use super::reconcile;
+
+/// How long a `helm rollback` may run before it is killed
+const ROLLBACK_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Start of gzip data, which helm compresses releases with
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// One revision of a Helm release
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Revision {
+    pub revision: u32,
+
+    /// Helm status, e.g. "deployed", "superseded" or "failed"
+    pub status: String,
+
+    /// Chart name and version, e.g. "podinfo-6.5.0"
+    pub chart: String,
+
+    pub app_version: String,
+
+    /// When the revision was deployed
+    pub updated: Option<DateTime<Utc>>,
+
+    /// What Helm did, e.g. "Upgrade complete"
+    pub description: String,
+}
+
+impl Revision {
+    /// Whether this is the revision currently deployed
+    pub fn is_deployed(&self) -> bool {
+        self.status == "deployed"
+    }
+}
+
+/// The parts of helm's release JSON that are shown
+#[derive(Deserialize)]
+struct Release {
+    version: u32,
+    info: ReleaseInfo,
+    chart: Chart,
+}
+
+#[derive(Deserialize)]
+struct ReleaseInfo {
+    last_deployed: Option<DateTime<Utc>>,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    status: String,
+}
+
+#[derive(Deserialize)]
+struct Chart {
+    metadata: ChartMetadata,
+}
+
+#[derive(Deserialize)]
+struct ChartMetadata {
+    name: String,
+    version: String,
+    #[serde(rename = "appVersion", default)]
+    app_version: String,
+}
+
+/// Decode the `release` key of a Helm storage Secret
+///
+/// Secret data arrives base64-decoded; helm encodes the release once more,
+/// usually gzipped.
+pub fn parse_release(data: &[u8]) -> Result<Revision> {
+    let decoded = base64::engine::general_purpose::STANDARD.decode(data)?;
+    let json = if decoded.starts_with(&GZIP_MAGIC) {
+        let mut json = Vec::new();
+        GzDecoder::new(decoded.as_slice()).read_to_end(&mut json)?;
+        json
+    } else {
+        decoded
+    };
+    let release: Release = serde_json::from_slice(&json)?;
+    Ok(Revision {
+        revision: release.version,
+        status: release.info.status,
+        chart: format!(
+            "{}-{}",
+            release.chart.metadata.name, release.chart.metadata.version
+        ),
+        app_version: release.chart.metadata.app_version,
+        updated: release.info.last_deployed,
+        description: release.info.description,
+    })
+}
+
+/// Label selector of the storage Secrets of a release
+pub fn release_selector(release: &str) -> String {
+    format!("owner=helm,name={release}")
+}
+
+/// Roll a release back to `revision` with the helm CLI
+///
+/// `namespace` is the storage namespace; `context` is the kube context (None
+/// = current context).
+pub async fn rollback(
+    release: &str,
+    namespace: &str,
+    revision: u32,
+    context: Option<&str>,
+) -> Result<()> {
+    let revision = revision.to_string();
+    let args = rollback_args(release, namespace, &revision, context);
+    reconcile::run("helm", &reconcile::binary("helm"), &args, ROLLBACK_TIMEOUT)
+        .await
+        .map(drop)
+}
+
+/// Build `helm rollback <release> <revision> -n <namespace> [--kube-context <context>]`
+fn rollback_args<'a>(
+    release: &'a str,
+    namespace: &'a str,
+    revision: &'a str,
+    context: Option<&'a str>,
+) -> Vec<&'a str> {
+    let mut args = vec!["rollback", release, revision, "-n", namespace];
+    if let Some(context) = context {
+        args.extend(["--kube-context", context]);
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    const RELEASE: &str = r#"{
+        "name": "podinfo",
+        "version": 3,
+        "info": {
+            "last_deployed": "2024-05-01T12:00:00Z",
+            "description": "Upgrade complete",
+            "status": "deployed"
+        },
+        "chart": {"metadata": {"name": "podinfo", "version": "6.5.0", "appVersion": "6.5.0"}}
+    }"#;
+
+    fn encode(json: &[u8], gzip: bool) -> Vec<u8> {
+        let data = if gzip {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(json).unwrap();
+            encoder.finish().unwrap()
+        } else {
+            json.to_vec()
+        };
+        base64::engine::general_purpose::STANDARD
+            .encode(data)
+            .into_bytes()
+    }
+
+    #[test]
+    fn test_parse_release() {
+        let expected = Revision {
+            revision: 3,
+            status: "deployed".to_string(),
+            chart: "podinfo-6.5.0".to_string(),
+            app_version: "6.5.0".to_string(),
+            updated: DateTime::from_timestamp(1_714_564_800, 0),
+            description: "Upgrade complete".to_string(),
+        };
+        assert_eq!(
+            parse_release(&encode(RELEASE.as_bytes(), true)).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_release(&encode(RELEASE.as_bytes(), false)).unwrap(),
+            expected
+        );
+        assert!(expected.is_deployed());
+        assert!(parse_release(b"not base64!").is_err());
+    }
+
+    #[test]
+    fn test_rollback_args() {
+        assert_eq!(
+            rollback_args("podinfo", "apps", "2", None),
+            vec!["rollback", "podinfo", "2", "-n", "apps"]
+        );
+        assert_eq!(
+            rollback_args("podinfo", "apps", "2", Some("prod")),
+            vec![
+                "rollback",
+                "podinfo",
+                "2",
+                "-n",
+                "apps",
+                "--kube-context",
+                "prod"
+            ]
+        );
+    }
+}
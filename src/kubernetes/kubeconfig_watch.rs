@@ -0,0 +1,130 @@
+//! Kubeconfig change detection
+//!
+//! External tools rewrite the kubeconfig while the app runs: a login command
+//! refreshes a token, a new cluster adds a context. The files are polled for
+//! a changed modification time so the clients can be rebuilt instead of
+//! failing with auth errors until restart.
+
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+/// How often the files are checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Watches the kubeconfig files the clients were built from
+#[derive(Debug)]
+pub struct KubeconfigWatch {
+    /// Files and their modification time when last checked
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+
+    last_check: Instant,
+}
+
+impl KubeconfigWatch {
+    /// Watch the given kubeconfig, or the files kube reads by default
+    /// (`KUBECONFIG`, else `~/.kube/config`)
+    pub fn new(kubeconfig: Option<&Path>) -> Self {
+        let paths = match kubeconfig {
+            Some(path) => vec![path.to_path_buf()],
+            None => default_paths(std::env::var_os("KUBECONFIG").as_deref()),
+        };
+        Self {
+            files: paths
+                .into_iter()
+                .map(|path| {
+                    let modified = modified(&path);
+                    (path, modified)
+                })
+                .collect(),
+            last_check: Instant::now(),
+        }
+    }
+
+    /// Watch nothing, e.g. while showing a snapshot
+    pub fn none() -> Self {
+        Self {
+            files: Vec::new(),
+            last_check: Instant::now(),
+        }
+    }
+
+    /// Whether any file changed since the last call, checked at most every
+    /// couple of seconds
+    pub fn changed(&mut self) -> bool {
+        if self.last_check.elapsed() < CHECK_INTERVAL {
+            return false;
+        }
+        self.last_check = Instant::now();
+        self.check()
+    }
+
+    /// Compare modification times, remembering the new ones
+    fn check(&mut self) -> bool {
+        let mut changed = false;
+        for (path, last) in &mut self.files {
+            let now = modified(path);
+            if now != *last {
+                *last = now;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+/// Files listed in `KUBECONFIG`, else the default kubeconfig
+fn default_paths(kubeconfig_env: Option<&std::ffi::OsStr>) -> Vec<PathBuf> {
+    match kubeconfig_env.filter(|value| !value.is_empty()) {
+        Some(value) => std::env::split_paths(value).collect(),
+        None => dirs::home_dir()
+            .map(|home| home.join(".kube").join("config"))
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// Modification time, None while the file doesn't exist
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_detects_changes() {
+        let path = std::env::temp_dir().join(format!("flux-tui-kubeconfig-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut watch = KubeconfigWatch::new(Some(&path));
+        assert!(!watch.check());
+
+        // Created
+        std::fs::write(&path, "apiVersion: v1").unwrap();
+        assert!(watch.check());
+        assert!(!watch.check());
+
+        // Rewritten (set the time explicitly, mtime granularity may be coarse)
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(1))
+            .unwrap();
+        assert!(watch.check());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(watch.check());
+    }
+
+    #[test]
+    fn test_default_paths() {
+        let joined = std::env::join_paths(["/a/config", "/b/config"]).unwrap();
+        assert_eq!(
+            default_paths(Some(&joined)),
+            vec![PathBuf::from("/a/config"), PathBuf::from("/b/config")]
+        );
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(default_paths(None), vec![home.join(".kube").join("config")]);
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Flux controller metrics
+//!
+//! Each controller serves Prometheus metrics on its pods. The gauges that
+//! reveal an overloaded controller come from controller-runtime: the work
+//! queue depth and the reconcile counters and durations, per reconciler
+//! (a controller like source-controller runs one per kind it handles).
+
+use std::collections::BTreeMap;
+
+/// Namespace Flux is installed in
+pub const FLUX_NAMESPACE: &str = "flux-system";
+
+/// Flux controllers, selected by their `app` label
+pub const CONTROLLERS: [&str; 4] = [
+    "source-controller",
+    "kustomize-controller",
+    "helm-controller",
+    "notification-controller",
+];
+
+/// Container port serving `/metrics`
+pub const METRICS_PORT: u16 = 8080;
+
+/// Queue depth from which a reconciler is falling behind
+const OVERLOADED_QUEUE_DEPTH: f64 = 10.0;
+
+/// Metrics of one reconciler inside a controller
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReconcilerMetrics {
+    /// Reconciler name, e.g. "gitrepository"
+    pub name: String,
+
+    /// Items waiting in the work queue
+    pub queue_depth: f64,
+
+    /// Reconciles since the controller started
+    pub reconciles: f64,
+
+    /// Failed reconciles since the controller started
+    pub errors: f64,
+
+    /// Total time spent reconciling, in seconds
+    pub seconds: f64,
+}
+
+impl ReconcilerMetrics {
+    /// Mean reconcile duration in seconds
+    pub fn mean_duration(&self) -> Option<f64> {
+        (self.reconciles > 0.0).then(|| self.seconds / self.reconciles)
+    }
+
+    /// Share of reconciles that failed
+    pub fn error_rate(&self) -> Option<f64> {
+        (self.reconciles > 0.0).then(|| self.errors / self.reconciles)
+    }
+
+    /// Whether the work queue is backing up
+    pub fn is_overloaded(&self) -> bool {
+        self.queue_depth >= OVERLOADED_QUEUE_DEPTH
+    }
+}
+
+/// Scraped metrics of a controller, or why they could not be scraped
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControllerMetrics {
+    pub controller: String,
+    pub reconcilers: Result<Vec<ReconcilerMetrics>, String>,
+}
+
+/// Reconciler metrics in a Prometheus text exposition, sorted by name
+pub fn parse(text: &str) -> Vec<ReconcilerMetrics> {
+    let mut reconcilers: BTreeMap<String, ReconcilerMetrics> = BTreeMap::new();
+    for (metric, labels, value) in text.lines().filter_map(sample) {
+        // workqueue_depth names its queue after the reconciler
+        let label = if metric == "workqueue_depth" {
+            "name"
+        } else {
+            "controller"
+        };
+        let Some(name) = labels.get(label) else {
+            continue;
+        };
+        let entry = reconcilers
+            .entry(name.clone())
+            .or_insert_with(|| ReconcilerMetrics {
+                name: name.clone(),
+                ..Default::default()
+            });
+        match metric {
+            "workqueue_depth" => entry.queue_depth += value,
+            "controller_runtime_reconcile_total" => entry.reconciles += value,
+            "controller_runtime_reconcile_errors_total" => entry.errors += value,
+            "controller_runtime_reconcile_time_seconds_sum" => entry.seconds += value,
+            _ => {}
+        }
+    }
+    reconcilers.into_values().collect()
+}
+
+/// Metric name, labels and value of a sample line (comments yield None)
+fn sample(line: &str) -> Option<(&str, BTreeMap<String, String>, f64)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let (series, rest) = if line.contains('{') {
+        line.split_at(line.rfind('}')? + 1)
+    } else {
+        line.split_at(line.find(' ')?)
+    };
+    let value = rest.split_whitespace().next()?.parse().ok()?;
+    let (name, labels) = match series.split_once('{') {
+        Some((name, labels)) => (name, parse_labels(labels.strip_suffix('}')?)),
+        None => (series, BTreeMap::new()),
+    };
+    Some((name, labels, value))
+}
+
+/// `key="value",…` label pairs, unescaping quoted values
+fn parse_labels(text: &str) -> BTreeMap<String, String> {
+    let mut labels = BTreeMap::new();
+    let mut chars = text.chars();
+    loop {
+        let key: String = chars
+            .by_ref()
+            .skip_while(|c| *c == ',' || c.is_whitespace())
+            .take_while(|c| *c != '=')
+            .collect();
+        if key.is_empty() || chars.next() != Some('"') {
+            return labels;
+        }
+        let mut value = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => break,
+                '\\' => match chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some(c) => value.push(c),
+                    None => break,
+                },
+                c => value.push(c),
+            }
+        }
+        labels.insert(key.trim().to_string(), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METRICS: &str = r#"
+# HELP workqueue_depth Current depth of workqueue
+# TYPE workqueue_depth gauge
+workqueue_depth{controller="gitrepository",name="gitrepository"} 12
+workqueue_depth{controller="helmchart",name="helmchart"} 0
+controller_runtime_reconcile_total{controller="gitrepository",result="error"} 5
+controller_runtime_reconcile_total{controller="gitrepository",result="success"} 95
+controller_runtime_reconcile_errors_total{controller="gitrepository"} 5
+controller_runtime_reconcile_time_seconds_sum{controller="gitrepository"} 150.5
+controller_runtime_reconcile_time_seconds_count{controller="gitrepository"} 100
+go_goroutines 42
+"#;
+
+    #[test]
+    fn test_parse() {
+        let reconcilers = parse(METRICS);
+        assert_eq!(reconcilers.len(), 2);
+
+        let git = &reconcilers[0];
+        assert_eq!(git.name, "gitrepository");
+        assert_eq!(git.queue_depth, 12.0);
+        assert_eq!(git.reconciles, 100.0);
+        assert_eq!(git.errors, 5.0);
+        assert_eq!(git.mean_duration(), Some(1.505));
+        assert_eq!(git.error_rate(), Some(0.05));
+        assert!(git.is_overloaded());
+
+        let chart = &reconcilers[1];
+        assert_eq!(chart.name, "helmchart");
+        assert_eq!(chart.mean_duration(), None);
+        assert!(!chart.is_overloaded());
+    }
+
+    #[test]
+    fn test_sample() {
+        let (name, labels, value) = sample(r#"m{a="x,y",b="say \"hi\""} 1.5 1700000000"#).unwrap();
+        assert_eq!(name, "m");
+        assert_eq!(labels["a"], "x,y");
+        assert_eq!(labels["b"], r#"say "hi""#);
+        assert_eq!(value, 1.5);
+
+        assert_eq!(sample("up 1").map(|s| s.2), Some(1.0));
+        assert!(sample("# TYPE up gauge").is_none());
+        assert!(sample("up NaNx").is_none());
+    }
+}
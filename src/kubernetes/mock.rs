@@ -0,0 +1,180 @@
+//! In-memory [`FluxApi`] for tests
+//!
+//! Serves a fixed set of resources and records the patches sent to it, so
+//! the app can be driven without a cluster. Port-forwards, artifacts and Helm
+//! history aren't available.
+
+use std::sync::{Arc, Mutex};
+
+use color_eyre::{eyre::eyre, Result};
+use futures::future::BoxFuture;
+use serde_json::Value;
+
+use super::api::FluxApi;
+use super::client::{new_api_log, ApiLog, ClusterSnapshot, KubeEvent};
+use super::helm::Revision;
+use super::metrics::ReconcilerMetrics;
+use super::portforward::PortForward;
+use super::preflight;
+use super::resources::{KindInfo, ReconcileInfo};
+
+/// Cluster name the mock reports, as `App::for_testing` expects
+pub const MOCK_CLUSTER: &str = "test-cluster";
+
+/// A patch sent to the mock: the resource ("Kind namespace/name") and the
+/// merge patch
+pub type SentPatch = (String, Value);
+
+/// A fake cluster holding a snapshot of resources
+pub struct MockClient {
+    snapshot: ClusterSnapshot,
+    patches: Mutex<Vec<SentPatch>>,
+    api_log: ApiLog,
+}
+
+impl MockClient {
+    /// A cluster holding these resources
+    pub fn new(snapshot: ClusterSnapshot) -> Arc<Self> {
+        Arc::new(Self {
+            snapshot,
+            patches: Mutex::default(),
+            api_log: new_api_log(10),
+        })
+    }
+
+    /// Patches sent so far, oldest first
+    pub fn patches(&self) -> Vec<SentPatch> {
+        self.patches.lock().unwrap().clone()
+    }
+
+    fn unavailable<T>(what: &str) -> BoxFuture<'static, Result<T>> {
+        let error = eyre!("{what} isn't available in the mock client");
+        Box::pin(async move { Err(error) })
+    }
+}
+
+impl FluxApi for MockClient {
+    fn cluster_name(&self) -> &str {
+        MOCK_CLUSTER
+    }
+
+    fn context(&self) -> Option<&str> {
+        None
+    }
+
+    fn api_log(&self) -> ApiLog {
+        Arc::clone(&self.api_log)
+    }
+
+    fn fetch_all<'a>(
+        &'a self,
+        namespace: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<ClusterSnapshot>> {
+        let snapshot = match namespace {
+            Some(namespace) => self.snapshot.in_namespace(namespace),
+            None => self.snapshot.clone(),
+        };
+        Box::pin(async move { Ok(snapshot) })
+    }
+
+    fn fetch_namespace<'a>(&'a self, namespace: &'a str) -> BoxFuture<'a, Result<ClusterSnapshot>> {
+        let snapshot = ClusterSnapshot {
+            namespaces: vec![namespace.to_string()],
+            ..self.snapshot.in_namespace(namespace)
+        };
+        Box::pin(async move { Ok(snapshot) })
+    }
+
+    fn get_manifest<'a>(
+        &'a self,
+        kind: &'a str,
+        namespace: &'a str,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            Ok(format!(
+                "kind: {kind}\nmetadata:\n  name: {name}\n  namespace: {namespace}\n"
+            ))
+        })
+    }
+
+    fn get_reconcile_info<'a>(
+        &'a self,
+        _kind: &'a str,
+        _namespace: &'a str,
+        _name: &'a str,
+    ) -> BoxFuture<'a, Result<ReconcileInfo>> {
+        Box::pin(async { Ok(ReconcileInfo::default()) })
+    }
+
+    fn patch_resource<'a>(
+        &'a self,
+        kind: &'a str,
+        namespace: &'a str,
+        name: &'a str,
+        patch: &'a Value,
+    ) -> BoxFuture<'a, Result<()>> {
+        self.patches
+            .lock()
+            .unwrap()
+            .push((format!("{kind} {namespace}/{name}"), patch.clone()));
+        Box::pin(async { Ok(()) })
+    }
+
+    fn helm_history<'a>(
+        &'a self,
+        _namespace: &'a str,
+        _release: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Revision>>> {
+        Self::unavailable("Helm history")
+    }
+
+    fn artifact_forward<'a>(&'a self, _url: &'a str) -> BoxFuture<'a, Result<Option<PortForward>>> {
+        Self::unavailable("Port-forwarding")
+    }
+
+    fn download_artifact<'a>(
+        &'a self,
+        _url: &'a str,
+        _forward: Option<&'a PortForward>,
+    ) -> BoxFuture<'a, Result<Vec<u8>>> {
+        Self::unavailable("Downloading artifacts")
+    }
+
+    fn server_version(&self) -> BoxFuture<'_, Result<String>> {
+        Box::pin(async { Ok("v1.30.0".to_string()) })
+    }
+
+    fn crd_versions<'a>(
+        &'a self,
+        info: &'a KindInfo,
+    ) -> BoxFuture<'a, Result<Option<Vec<String>>>> {
+        let version = info.api_version.rsplit('/').next().map(String::from);
+        Box::pin(async move { Ok(version.map(|version| vec![version])) })
+    }
+
+    fn flux_deployments(&self) -> BoxFuture<'_, Result<Vec<preflight::Deployment>>> {
+        Box::pin(async { Ok(Vec::new()) })
+    }
+
+    fn metrics_forward<'a>(&'a self, _controller: &'a str) -> BoxFuture<'a, Result<PortForward>> {
+        Self::unavailable("Port-forwarding")
+    }
+
+    fn scrape_metrics<'a>(
+        &'a self,
+        _forward: &'a PortForward,
+    ) -> BoxFuture<'a, Result<Vec<ReconcilerMetrics>>> {
+        Self::unavailable("Scraping metrics")
+    }
+
+    fn list_events_for<'a>(
+        &'a self,
+        _kind: &'a str,
+        _namespace: &'a str,
+        _name: &'a str,
+        _limit: usize,
+    ) -> BoxFuture<'a, Result<Vec<KubeEvent>>> {
+        Box::pin(async { Ok(Vec::new()) })
+    }
+}
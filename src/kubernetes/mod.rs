@@ -1,5 +1,17 @@
 //! Kubernetes client and resource handling
 
+pub mod api;
+pub mod artifact;
 pub mod client;
+pub mod flux_version;
+pub mod helm;
+pub mod kubeconfig_watch;
+pub mod metrics;
+#[cfg(test)]
+pub mod mock;
+pub mod portforward;
+pub mod preflight;
 pub mod reconcile;
 pub mod resources;
+pub mod snapshot;
+pub mod yaml;
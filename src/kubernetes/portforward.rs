@@ -0,0 +1,261 @@
+//! Port-forwards to Flux controllers
+//!
+//! Controllers serve artifacts and metrics on cluster-internal Services.
+//! From outside the cluster they are reached through a local port forwarded
+//! to one of the Service's pods. The forward stays open until its handle is
+//! dropped, which the app ties to the popup that needed it.
+
+use std::{collections::BTreeMap, net::SocketAddr};
+
+use color_eyre::{eyre::eyre, Result};
+use k8s_openapi::{
+    api::core::v1::{Pod, Service, ServicePort},
+    apimachinery::pkg::util::intstr::IntOrString,
+};
+use kube::{
+    api::{Api, ListParams},
+    Client,
+};
+use tokio::{net::TcpListener, task::JoinHandle};
+
+/// Whether the app runs inside a cluster, where Service URLs resolve directly
+pub fn in_cluster() -> bool {
+    std::env::var_os("KUBERNETES_SERVICE_HOST").is_some()
+}
+
+/// A Service port addressed by an in-cluster URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceUrl {
+    pub namespace: String,
+    pub service: String,
+    pub port: u16,
+
+    /// Path after the authority, without the leading slash
+    pub path: String,
+}
+
+impl ServiceUrl {
+    /// Parse `http://<service>.<namespace>.svc…[:port]/<path>`
+    pub fn parse(url: &str) -> Option<Self> {
+        let (scheme, rest) = url.split_once("://")?;
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().ok()?),
+            None => (authority, if scheme == "https" { 443 } else { 80 }),
+        };
+        let mut labels = host.split('.');
+        let service = labels.next().filter(|s| !s.is_empty())?;
+        let namespace = labels.next().filter(|s| !s.is_empty())?;
+        Some(Self {
+            namespace: namespace.to_string(),
+            service: service.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+}
+
+/// An open port-forward, closed when dropped
+#[derive(Debug)]
+pub struct PortForward {
+    /// Local address accepting connections
+    pub local_addr: SocketAddr,
+
+    task: JoinHandle<()>,
+}
+
+impl PortForward {
+    /// Forward a local port to `port` of a pod behind the Service
+    pub async fn open(client: Client, namespace: &str, service: &str, port: u16) -> Result<Self> {
+        let services: Api<Service> = Api::namespaced(client.clone(), namespace);
+        let spec = services
+            .get(service)
+            .await?
+            .spec
+            .ok_or_else(|| eyre!("Service {namespace}/{service} has no spec"))?;
+        let selector = spec
+            .selector
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| eyre!("Service {namespace}/{service} has no selector"))?;
+        let service_port = spec
+            .ports
+            .unwrap_or_default()
+            .into_iter()
+            .find(|p| p.port == i32::from(port))
+            .ok_or_else(|| eyre!("Service {namespace}/{service} has no port {port}"))?;
+
+        let pods: Api<Pod> = Api::namespaced(client, namespace);
+        let params = ListParams::default().labels(&label_selector(&selector));
+        let pod = pods
+            .list(&params)
+            .await?
+            .items
+            .into_iter()
+            .find(is_running)
+            .ok_or_else(|| eyre!("No running pod behind Service {namespace}/{service}"))?;
+        let target = target_port(&service_port, &pod)
+            .ok_or_else(|| eyre!("Cannot resolve the target port of {namespace}/{service}"))?;
+        Self::listen(pods, pod, target).await
+    }
+
+    /// Forward a local port to `port` of a running pod matching the selector
+    pub async fn open_pod(
+        client: Client,
+        namespace: &str,
+        selector: &str,
+        port: u16,
+    ) -> Result<Self> {
+        let pods: Api<Pod> = Api::namespaced(client, namespace);
+        let pod = pods
+            .list(&ListParams::default().labels(selector))
+            .await?
+            .items
+            .into_iter()
+            .find(is_running)
+            .ok_or_else(|| eyre!("No running pod matches {selector} in {namespace}"))?;
+        Self::listen(pods, pod, port).await
+    }
+
+    async fn listen(pods: Api<Pod>, pod: Pod, port: u16) -> Result<Self> {
+        let pod = pod.metadata.name.unwrap_or_default();
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let local_addr = listener.local_addr()?;
+        let task = tokio::spawn(accept(listener, pods, pod, port));
+        Ok(Self { local_addr, task })
+    }
+
+    /// Local URL for a path served by the forwarded port
+    pub fn url(&self, path: &str) -> String {
+        format!(
+            "http://{}/{}",
+            self.local_addr,
+            path.trim_start_matches('/')
+        )
+    }
+}
+
+impl Drop for PortForward {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Forward each local connection over its own port-forward stream
+async fn accept(listener: TcpListener, pods: Api<Pod>, pod: String, port: u16) {
+    while let Ok((mut local, _)) = listener.accept().await {
+        let pods = pods.clone();
+        let pod = pod.clone();
+        tokio::spawn(async move {
+            let Ok(mut forwarder) = pods.portforward(&pod, &[port]).await else {
+                return;
+            };
+            if let Some(mut upstream) = forwarder.take_stream(port) {
+                let _ = tokio::io::copy_bidirectional(&mut local, &mut upstream).await;
+            }
+            forwarder.abort();
+        });
+    }
+}
+
+/// `key=value,…` selector matching a Service's pods
+fn label_selector(selector: &BTreeMap<String, String>) -> String {
+    selector
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn is_running(pod: &Pod) -> bool {
+    pod.status.as_ref().and_then(|s| s.phase.as_deref()) == Some("Running")
+}
+
+/// Container port a Service port targets on the given pod
+///
+/// A named target port is looked up in the pod's container ports; without
+/// a target port the Service port itself is used.
+fn target_port(service_port: &ServicePort, pod: &Pod) -> Option<u16> {
+    match &service_port.target_port {
+        None => u16::try_from(service_port.port).ok(),
+        Some(IntOrString::Int(port)) => u16::try_from(*port).ok(),
+        Some(IntOrString::String(name)) => pod
+            .spec
+            .as_ref()?
+            .containers
+            .iter()
+            .flat_map(|c| c.ports.iter().flatten())
+            .find(|p| p.name.as_deref() == Some(name))
+            .and_then(|p| u16::try_from(p.container_port).ok()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::core::v1::{Container, ContainerPort, PodSpec};
+
+    #[test]
+    fn test_service_url() {
+        let url = ServiceUrl::parse(
+            "http://source-controller.flux-system.svc.cluster.local./gitrepository/flux-system/fleet/abc.tar.gz",
+        )
+        .unwrap();
+        assert_eq!(url.namespace, "flux-system");
+        assert_eq!(url.service, "source-controller");
+        assert_eq!(url.port, 80);
+        assert_eq!(url.path, "gitrepository/flux-system/fleet/abc.tar.gz");
+
+        let url = ServiceUrl::parse("http://source-controller.flux.svc:9090/a.tar.gz").unwrap();
+        assert_eq!((url.namespace.as_str(), url.port), ("flux", 9090));
+
+        assert_eq!(ServiceUrl::parse("source-controller/a.tar.gz"), None);
+        assert_eq!(ServiceUrl::parse("http://localhost/a.tar.gz"), None);
+        assert_eq!(ServiceUrl::parse("http://a.b:http/x"), None);
+    }
+
+    #[test]
+    fn test_target_port() {
+        let pod = Pod {
+            spec: Some(PodSpec {
+                containers: vec![Container {
+                    ports: Some(vec![ContainerPort {
+                        name: Some("http".to_string()),
+                        container_port: 9090,
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let port = |target| ServicePort {
+            port: 80,
+            target_port: target,
+            ..Default::default()
+        };
+
+        assert_eq!(target_port(&port(None), &pod), Some(80));
+        assert_eq!(
+            target_port(&port(Some(IntOrString::Int(8080))), &pod),
+            Some(8080)
+        );
+        assert_eq!(
+            target_port(&port(Some(IntOrString::String("http".into()))), &pod),
+            Some(9090)
+        );
+        assert_eq!(
+            target_port(&port(Some(IntOrString::String("metrics".into()))), &pod),
+            None
+        );
+    }
+
+    #[test]
+    fn test_label_selector() {
+        let selector = BTreeMap::from([
+            ("app".to_string(), "source-controller".to_string()),
+            ("tier".to_string(), "flux".to_string()),
+        ]);
+        assert_eq!(label_selector(&selector), "app=source-controller,tier=flux");
+    }
+}
@@ -0,0 +1,169 @@
+//! Preflight checks, in the spirit of `flux check`
+//!
+//! When Flux is missing or only half installed, every list fails with a
+//! terse "not found". These checks say which part is missing instead: the
+//! API server, a CRD (or the version the app uses), or a controller.
+
+use super::{
+    api::FluxApi,
+    client::short_error,
+    metrics::{CONTROLLERS, FLUX_NAMESPACE},
+    resources::{kind::KINDS, KindInfo},
+};
+
+/// Outcome of one check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    /// What was checked, e.g. "HelmRelease CRD"
+    pub name: String,
+
+    /// What was found when it passed, why it failed otherwise
+    pub result: Result<String, String>,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, result: Result<String, String>) -> Self {
+        Self {
+            name: name.into(),
+            result,
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// Replicas and version of a Deployment in the Flux namespace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deployment {
+    pub name: String,
+    pub ready: i32,
+    pub desired: i32,
+
+    /// Its `app.kubernetes.io/version` label
+    pub version: Option<String>,
+
+    /// Image of its first container
+    pub image: Option<String>,
+}
+
+/// Run all checks against a cluster
+///
+/// Without a reachable API server the other checks would all fail the same
+/// way, so only that one is reported then.
+pub async fn run(client: &dyn FluxApi) -> Vec<Check> {
+    let api = Check::new(
+        "API server",
+        client
+            .server_version()
+            .await
+            .map(|version| format!("reachable, Kubernetes {version}"))
+            .map_err(|e| short_error(&e)),
+    );
+    if !api.passed() {
+        return vec![api];
+    }
+
+    let mut checks = vec![api];
+    for info in &KINDS {
+        let versions = client.crd_versions(info).await.map_err(|e| short_error(&e));
+        checks.push(crd_check(info, versions));
+    }
+    let deployments = client.flux_deployments().await.map_err(|e| short_error(&e));
+    checks.extend(controller_checks(deployments));
+    checks
+}
+
+/// Whether the CRD of a kind is installed and serves the version the app uses
+fn crd_check(info: &KindInfo, versions: Result<Option<Vec<String>>, String>) -> Check {
+    let (_, version) = info
+        .api_version
+        .rsplit_once('/')
+        .unwrap_or(("", info.api_version));
+    let result = match versions {
+        Err(e) => Err(e),
+        Ok(None) => Err("not installed (is Flux installed?)".to_string()),
+        Ok(Some(versions)) if versions.iter().any(|v| v == version) => {
+            Ok(format!("serves {version}"))
+        }
+        Ok(Some(versions)) => Err(format!(
+            "serves {} but not {version} (Flux too old?)",
+            versions.join(", ")
+        )),
+    };
+    Check::new(format!("{} CRD", info.kind), result)
+}
+
+/// Whether each Flux controller has all its replicas ready
+fn controller_checks(deployments: Result<Vec<Deployment>, String>) -> Vec<Check> {
+    let deployments = match deployments {
+        Ok(deployments) => deployments,
+        Err(e) => return vec![Check::new("Controllers", Err(e))],
+    };
+    CONTROLLERS
+        .iter()
+        .map(|controller| {
+            let result = match deployments.iter().find(|d| d.name == *controller) {
+                None => Err(format!("no Deployment in {FLUX_NAMESPACE}")),
+                Some(d) if d.desired == 0 => Err("scaled to zero".to_string()),
+                Some(d) if d.ready < d.desired => {
+                    Err(format!("{}/{} replicas ready", d.ready, d.desired))
+                }
+                Some(d) => Ok(format!("{}/{} replicas ready", d.ready, d.desired)),
+            };
+            Check::new(*controller, result)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::{HelmRelease, ResourceKind};
+
+    #[test]
+    fn test_crd_check() {
+        let info = &HelmRelease::INFO;
+        let check = crd_check(info, Ok(Some(vec!["v2beta2".into(), "v2".into()])));
+        assert_eq!(check.name, "HelmRelease CRD");
+        assert_eq!(check.result, Ok("serves v2".to_string()));
+
+        let old = crd_check(info, Ok(Some(vec!["v2beta1".into()])));
+        assert_eq!(
+            old.result,
+            Err("serves v2beta1 but not v2 (Flux too old?)".to_string())
+        );
+        assert!(!crd_check(info, Ok(None)).passed());
+    }
+
+    #[test]
+    fn test_controller_checks() {
+        let deployment = |name: &str, ready, desired| Deployment {
+            name: name.to_string(),
+            ready,
+            desired,
+            version: None,
+            image: None,
+        };
+        let checks = controller_checks(Ok(vec![
+            deployment("source-controller", 1, 1),
+            deployment("kustomize-controller", 0, 1),
+            deployment("helm-controller", 0, 0),
+        ]));
+        let results: Vec<_> = checks.iter().map(|c| c.result.clone()).collect();
+        assert_eq!(
+            results,
+            vec![
+                Ok("1/1 replicas ready".to_string()),
+                Err("0/1 replicas ready".to_string()),
+                Err("scaled to zero".to_string()),
+                Err("no Deployment in flux-system".to_string()),
+            ]
+        );
+
+        let forbidden = controller_checks(Err("forbidden".to_string()));
+        assert_eq!(forbidden.len(), 1);
+        assert!(!forbidden[0].passed());
+    }
+}
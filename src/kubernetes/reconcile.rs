@@ -1,84 +1,430 @@
-//! Flux CLI wrapper for reconciliation operations
+//! Reconcile and suspend requests
 //!
-//! Uses the `flux` CLI for reconciliation operations because:
-//! 1. It handles the complexity of Flux's reconciliation logic
-//! 2. It properly manages source dependencies
-//! 3. It provides consistent behavior with the official tooling
+//! Two backends implement [`ReconcileBackend`], selected with the
+//! `reconcile_backend` setting or `--reconcile-backend`:
+//! - `cli` (default) runs the `flux` CLI, for environments that trust only
+//!   the official tooling. It handles source dependencies and waits for the
+//!   controller the same way users do from a shell.
+//! - `native` patches the resources through the Kubernetes API like the CLI
+//!   does under the hood, for environments without the flux CLI.
 
+use chrono::Utc;
 use color_eyre::{eyre::eyre, Result};
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
 
-/// Reconcile a Flux resource using the flux CLI
+use super::api::FluxApi;
+use super::resources::{FluxResource, ReconcileInfo, SourceRef};
+
+/// How long a flux command may run before it is killed
 ///
-/// # Arguments
-/// * `name` - Resource name
-/// * `namespace` - Resource namespace
-/// * `kind` - Resource kind (kustomization, helmrelease, helmchart)
-/// * `with_source` - Whether to reconcile the source first
-pub async fn reconcile(name: &str, namespace: &str, kind: &str, with_source: bool) -> Result<()> {
-    let mut args = vec!["reconcile", kind, name, "-n", namespace];
+/// Generous, since `flux reconcile` waits for the controller to finish.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How often the native backend checks whether the controller caught up
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Annotation requesting a reconcile; controllers copy its value to
+/// `status.lastHandledReconcileAt` once they handled it
+const REQUESTED_AT_ANNOTATION: &str = "reconcile.fluxcd.io/requestedAt";
+
+/// Annotation forcing a HelmRelease upgrade; helm-controller acts on it when
+/// it carries the same token as the reconcile request
+const FORCE_AT_ANNOTATION: &str = "reconcile.fluxcd.io/forceAt";
+
+/// Which backend performs reconcile and suspend requests
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// Run the flux CLI
+    #[default]
+    Cli,
+
+    /// Patch resources through the Kubernetes API
+    Native,
+}
+
+/// What a reconcile request asks for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Mode {
+    #[default]
+    Plain,
 
-    if with_source {
-        args.push("--with-source");
+    /// Reconcile the source first
+    WithSource,
+
+    /// Upgrade a HelmRelease even when its release failed or is locked
+    /// (`flux reconcile helmrelease --force`)
+    Force,
+}
+
+impl Mode {
+    /// Flag passed to `flux reconcile`
+    fn flag(self) -> Option<&'static str> {
+        match self {
+            Mode::Plain => None,
+            Mode::WithSource => Some("--with-source"),
+            Mode::Force => Some("--force"),
+        }
     }
+}
+
+/// A resource to reconcile, suspend or resume
+#[derive(Debug, Clone, Copy)]
+pub struct ResourceRef<'a> {
+    /// Kind as Kubernetes names it (e.g. "GitRepository")
+    pub kind: &'a str,
+    pub name: &'a str,
+    pub namespace: &'a str,
 
-    run_flux_command(&args).await
+    /// Source reconciled first with [`Mode::WithSource`]
+    pub source: Option<&'a SourceRef>,
 }
 
-/// Toggle suspend status on a Flux resource
-///
-/// # Arguments
-/// * `name` - Resource name
-/// * `namespace` - Resource namespace
-/// * `kind` - Resource kind (kustomization, helmrelease)
-/// * `is_currently_suspended` - Current suspend status
-pub async fn toggle_suspend(
-    name: &str,
-    namespace: &str,
-    kind: &str,
+impl<'a> ResourceRef<'a> {
+    pub fn of(resource: &'a dyn FluxResource) -> Self {
+        Self {
+            kind: resource.kind(),
+            name: resource.name(),
+            namespace: resource.namespace(),
+            source: resource.source(),
+        }
+    }
+}
+
+/// Performs reconcile and suspend requests, waiting until they took effect
+pub trait ReconcileBackend: Send + Sync {
+    /// Reconcile a resource
+    fn reconcile<'a>(&'a self, resource: ResourceRef<'a>, mode: Mode) -> BoxFuture<'a, Result<()>>;
+
+    /// Suspend a resource, or resume it when `suspend` is false
+    fn set_suspended<'a>(
+        &'a self,
+        resource: ResourceRef<'a>,
+        suspend: bool,
+    ) -> BoxFuture<'a, Result<()>>;
+}
+
+/// The backend of the given type, acting on the cluster of `client`
+pub fn backend(backend: Backend, client: &Arc<dyn FluxApi>) -> Box<dyn ReconcileBackend> {
+    match backend {
+        Backend::Cli => Box::new(CliBackend {
+            context: client.context().map(String::from),
+        }),
+        Backend::Native => Box::new(NativeBackend {
+            client: Arc::clone(client),
+        }),
+    }
+}
+
+/// Runs the flux CLI against a kube context
+struct CliBackend {
+    /// Kube context (None = current context)
+    context: Option<String>,
+}
+
+impl ReconcileBackend for CliBackend {
+    fn reconcile<'a>(&'a self, resource: ResourceRef<'a>, mode: Mode) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let kind = flux_kind(resource.kind)?;
+            run_flux_command(&reconcile_args(
+                resource.name,
+                resource.namespace,
+                kind,
+                self.context.as_deref(),
+                mode,
+            ))
+            .await
+        })
+    }
+
+    fn set_suspended<'a>(
+        &'a self,
+        resource: ResourceRef<'a>,
+        suspend: bool,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let kind = flux_kind(resource.kind)?;
+            run_flux_command(&suspend_args(
+                resource.name,
+                resource.namespace,
+                kind,
+                self.context.as_deref(),
+                !suspend,
+            ))
+            .await
+        })
+    }
+}
+
+/// Patches resources through the Kubernetes API
+struct NativeBackend {
+    client: Arc<dyn FluxApi>,
+}
+
+impl NativeBackend {
+    /// Merge `spec` into the resource while requesting a reconcile, then wait
+    /// until the controller handled the request
+    async fn request(&self, resource: ResourceRef<'_>, spec: Value, force: bool) -> Result<()> {
+        let ResourceRef {
+            kind,
+            name,
+            namespace,
+            ..
+        } = resource;
+        let token = Utc::now().to_rfc3339();
+        self.client
+            .patch_resource(kind, namespace, name, &request_patch(&token, spec, force))
+            .await?;
+
+        let wait = async {
+            loop {
+                tokio::time::sleep(POLL_INTERVAL).await;
+                let info = self
+                    .client
+                    .get_reconcile_info(kind, namespace, name)
+                    .await?;
+                if let Some(outcome) = outcome(&info, &token) {
+                    return outcome;
+                }
+            }
+        };
+        tokio::time::timeout(COMMAND_TIMEOUT, wait)
+            .await
+            .map_err(|_| {
+                eyre!(
+                    "Timed out after {}s waiting for {kind} {namespace}/{name} to reconcile",
+                    COMMAND_TIMEOUT.as_secs()
+                )
+            })?
+    }
+}
+
+impl ReconcileBackend for NativeBackend {
+    fn reconcile<'a>(&'a self, resource: ResourceRef<'a>, mode: Mode) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            if mode == Mode::WithSource {
+                let source = resource.source.ok_or_else(|| {
+                    eyre!(
+                        "{} {}/{} has no source to reconcile",
+                        resource.kind,
+                        resource.namespace,
+                        resource.name
+                    )
+                })?;
+                let source = ResourceRef {
+                    kind: &source.kind,
+                    name: &source.name,
+                    namespace: &source.namespace,
+                    source: None,
+                };
+                self.request(source, json!({}), false).await?;
+            }
+            self.request(resource, json!({}), mode == Mode::Force).await
+        })
+    }
+
+    fn set_suspended<'a>(
+        &'a self,
+        resource: ResourceRef<'a>,
+        suspend: bool,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            if suspend {
+                self.client
+                    .patch_resource(
+                        resource.kind,
+                        resource.namespace,
+                        resource.name,
+                        &json!({"spec": {"suspend": true}}),
+                    )
+                    .await
+            } else {
+                // Like `flux resume`, reconcile right away and wait for it
+                self.request(resource, json!({"suspend": false}), false)
+                    .await
+            }
+        })
+    }
+}
+
+/// Patch requesting a reconcile identified by `token`, merging in `spec`
+fn request_patch(token: &str, spec: Value, force: bool) -> Value {
+    let mut annotations = json!({ REQUESTED_AT_ANNOTATION: token });
+    if force {
+        annotations[FORCE_AT_ANNOTATION] = json!(token);
+    }
+    json!({
+        "metadata": {"annotations": annotations},
+        "spec": spec,
+    })
+}
+
+/// Outcome of a reconcile request, None while the controller hasn't handled
+/// it yet or is still reconciling
+fn outcome(info: &ReconcileInfo, token: &str) -> Option<Result<()>> {
+    if info.last_handled_reconcile_at.as_deref() != Some(token) {
+        return None;
+    }
+    let ready = info
+        .conditions
+        .iter()
+        .find(|c| c.condition_type == "Ready")?;
+    match ready.status.as_str() {
+        "True" => Some(Ok(())),
+        "False" => Some(Err(eyre!("Reconcile failed: {}", ready.message))),
+        _ => None,
+    }
+}
+
+/// Resource kind as the flux CLI names it (source kinds are two words)
+fn flux_kind(kind: &str) -> Result<&'static str> {
+    match kind {
+        "Kustomization" => Ok("kustomization"),
+        "HelmRelease" => Ok("helmrelease"),
+        "HelmChart" => Ok("source chart"),
+        "GitRepository" => Ok("source git"),
+        "HelmRepository" => Ok("source helm"),
+        _ => Err(eyre!("Unsupported resource kind: {kind}")),
+    }
+}
+
+/// Arguments for `flux reconcile`
+fn reconcile_args<'a>(
+    name: &'a str,
+    namespace: &'a str,
+    kind: &'a str,
+    context: Option<&'a str>,
+    mode: Mode,
+) -> Vec<&'a str> {
+    let mut args = command_args("reconcile", kind, name, namespace, context);
+    args.extend(mode.flag());
+    args
+}
+
+/// Arguments for `flux suspend` or, when suspended, `flux resume`
+fn suspend_args<'a>(
+    name: &'a str,
+    namespace: &'a str,
+    kind: &'a str,
+    context: Option<&'a str>,
     is_currently_suspended: bool,
-) -> Result<()> {
+) -> Vec<&'a str> {
     let action = if is_currently_suspended {
         "resume"
     } else {
         "suspend"
     };
+    command_args(action, kind, name, namespace, context)
+}
 
-    let args = vec![action, kind, name, "-n", namespace];
-
-    run_flux_command(&args).await
+/// Build `flux <action> <kind> <name> -n <namespace> [--context <context>]` arguments
+///
+/// Source kinds are two words on the flux CLI (e.g. `source git`), so the
+/// kind is split into separate arguments.
+fn command_args<'a>(
+    action: &'a str,
+    kind: &'a str,
+    name: &'a str,
+    namespace: &'a str,
+    context: Option<&'a str>,
+) -> Vec<&'a str> {
+    let mut args = vec![action];
+    args.extend(kind.split_whitespace());
+    args.extend([name, "-n", namespace]);
+    if let Some(context) = context {
+        args.extend(["--context", context]);
+    }
+    args
 }
 
 /// Run a flux CLI command
 async fn run_flux_command(args: &[&str]) -> Result<()> {
-    // Spawn the command in a blocking task to not block the async runtime
-    let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+    run("flux", &binary("flux"), args, COMMAND_TIMEOUT)
+        .await
+        .map(drop)
+}
 
-    tokio::task::spawn_blocking(move || {
-        let output = Command::new("flux")
-            .args(&args)
-            .output()
-            .map_err(|e| eyre!("Failed to execute flux command: {}", e))?;
+/// Run `binary` with `args`, returning its stdout
+///
+/// A non-zero exit is an error carrying stderr and stdout. The process is
+/// killed when it runs longer than `timeout`. `program` names the CLI in
+/// errors (e.g. "flux").
+pub(crate) async fn run(
+    program: &str,
+    binary: &Path,
+    args: &[&str],
+    timeout: Duration,
+) -> Result<String> {
+    let output = tokio::process::Command::new(binary)
+        .args(args)
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(timeout, output)
+        .await
+        .map_err(|_| {
+            eyre!(
+                "{} command timed out after {}s: {program} {}",
+                capitalize(program),
+                timeout.as_secs_f32(),
+                args.join(" ")
+            )
+        })?
+        .map_err(|e| eyre!("Failed to execute {program} command: {}", e))?;
 
-        if output.status.success() {
-            Ok(())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            Err(eyre!(
-                "Flux command failed: {}\n{}",
-                stderr.trim(),
-                stdout.trim()
-            ))
-        }
-    })
-    .await?
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if output.status.success() {
+        Ok(stdout.into_owned())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(eyre!(
+            "{} command failed: {}\n{}",
+            capitalize(program),
+            stderr.trim(),
+            stdout.trim()
+        ))
+    }
+}
+
+/// "flux" → "Flux", for the start of error messages
+fn capitalize(program: &str) -> String {
+    let mut chars = program.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Locate a CLI such as `flux` on the PATH (`flux.exe` on Windows)
+///
+/// Falls back to the plain name, so a missing CLI is reported by the command
+/// that fails to start.
+pub(crate) fn binary(name: &str) -> PathBuf {
+    std::env::var_os("PATH")
+        .and_then(|path| find_in_path(name, &path))
+        .unwrap_or_else(|| PathBuf::from(name))
+}
+
+/// Find an executable in a PATH-style list of directories
+///
+/// Uses the platform's separator (`;` on Windows) and executable suffix.
+fn find_in_path(name: &str, path: &OsStr) -> Option<PathBuf> {
+    let file = format!("{name}{}", std::env::consts::EXE_SUFFIX);
+    std::env::split_paths(path)
+        .map(|dir| dir.join(&file))
+        .find(|candidate| Path::is_file(candidate))
 }
 
 /// Check if the flux CLI is available
 #[allow(dead_code)]
 pub fn is_flux_available() -> bool {
-    Command::new("flux")
+    Command::new(binary("flux"))
         .arg("--version")
         .output()
         .map(|o| o.status.success())
@@ -88,12 +434,184 @@ pub fn is_flux_available() -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::kubernetes::resources::Condition;
+
+    /// The fake flux CLI in tests/fixtures: echoes its arguments, fails for
+    /// a resource named "broken" and hangs for one named "hang"
+    fn fake_flux() -> PathBuf {
+        let name = if cfg!(windows) {
+            "fake-flux.cmd"
+        } else {
+            "fake-flux"
+        };
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join(name)
+    }
 
     #[test]
     fn test_is_flux_available_returns_bool() {
         // This test will return true if flux is installed, false otherwise
         // Either result is valid - we just want to ensure the function doesn't panic
-        let result = is_flux_available();
-        assert!(result == true || result == false);
+        let _ = is_flux_available();
+    }
+
+    #[test]
+    fn test_find_in_path() {
+        let dir = std::env::temp_dir().join(format!("flux-tui-path-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let binary = dir.join(format!("flux{}", std::env::consts::EXE_SUFFIX));
+        std::fs::write(&binary, "").unwrap();
+
+        let path = std::env::join_paths([Path::new("/nonexistent"), &dir]).unwrap();
+        assert_eq!(find_in_path("flux", &path), Some(binary));
+        assert_eq!(find_in_path("kubectl", &path), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_command_args() {
+        assert_eq!(
+            command_args("reconcile", "kustomization", "apps", "flux-system", None),
+            vec!["reconcile", "kustomization", "apps", "-n", "flux-system"]
+        );
+        assert_eq!(
+            command_args("suspend", "source git", "fleet", "flux-system", None),
+            vec!["suspend", "source", "git", "fleet", "-n", "flux-system"]
+        );
+        assert_eq!(
+            command_args("reconcile", "helmrelease", "app", "apps", Some("prod")),
+            vec![
+                "reconcile",
+                "helmrelease",
+                "app",
+                "-n",
+                "apps",
+                "--context",
+                "prod"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reconcile_args_force() {
+        assert_eq!(
+            reconcile_args("app", "apps", "helmrelease", None, Mode::Force),
+            vec!["reconcile", "helmrelease", "app", "-n", "apps", "--force"]
+        );
+    }
+
+    #[test]
+    fn test_request_patch() {
+        let patch = request_patch("t1", json!({}), false);
+        assert_eq!(
+            patch["metadata"]["annotations"],
+            json!({ REQUESTED_AT_ANNOTATION: "t1" })
+        );
+
+        let forced = request_patch("t2", json!({"suspend": false}), true);
+        assert_eq!(
+            forced["metadata"]["annotations"],
+            json!({ REQUESTED_AT_ANNOTATION: "t2", FORCE_AT_ANNOTATION: "t2" })
+        );
+        assert_eq!(forced["spec"], json!({"suspend": false}));
+    }
+
+    #[test]
+    fn test_flux_kind() {
+        assert_eq!(flux_kind("Kustomization").unwrap(), "kustomization");
+        assert_eq!(flux_kind("GitRepository").unwrap(), "source git");
+        assert!(flux_kind("OCIRepository").is_err());
+    }
+
+    #[test]
+    fn test_outcome() {
+        let info = |handled: Option<&str>, ready: &str| ReconcileInfo {
+            last_handled_reconcile_at: handled.map(String::from),
+            conditions: vec![Condition {
+                condition_type: "Ready".to_string(),
+                status: ready.to_string(),
+                message: "kustomize build failed".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let token = "2024-01-01T00:00:00+00:00";
+
+        // Not handled yet, or still reconciling
+        assert!(outcome(&info(None, "True"), token).is_none());
+        assert!(outcome(&info(Some("2023-12-31T00:00:00+00:00"), "True"), token).is_none());
+        assert!(outcome(&info(Some(token), "Unknown"), token).is_none());
+
+        assert!(outcome(&info(Some(token), "True"), token).unwrap().is_ok());
+        let err = outcome(&info(Some(token), "False"), token)
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Reconcile failed: kustomize build failed");
+    }
+
+    #[tokio::test]
+    async fn test_run_passes_arguments() {
+        let args = reconcile_args(
+            "apps",
+            "flux-system",
+            "source git",
+            Some("prod"),
+            Mode::WithSource,
+        );
+        let stdout = run("flux", &fake_flux(), &args, COMMAND_TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(
+            stdout.trim(),
+            "reconcile source git apps -n flux-system --context prod --with-source"
+        );
+
+        let args = suspend_args("app", "apps", "helmrelease", None, true);
+        let stdout = run("flux", &fake_flux(), &args, COMMAND_TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(stdout.trim(), "resume helmrelease app -n apps");
+    }
+
+    #[tokio::test]
+    async fn test_run_reports_failure() {
+        let args = reconcile_args("broken", "apps", "kustomization", None, Mode::Plain);
+        let err = run("flux", &fake_flux(), &args, COMMAND_TIMEOUT)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("Flux command failed:"), "{err}");
+        assert!(err.contains("kustomize build failed"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_run_times_out() {
+        let args = reconcile_args("hang", "apps", "kustomization", None, Mode::Plain);
+        let err = run("flux", &fake_flux(), &args, Duration::from_millis(200))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("timed out"), "{err}");
+        assert!(
+            err.ends_with("flux reconcile kustomization hang -n apps"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_missing_binary() {
+        let err = run(
+            "flux",
+            Path::new("/nonexistent/flux"),
+            &["version"],
+            COMMAND_TIMEOUT,
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+        assert!(err.starts_with("Failed to execute flux command"), "{err}");
     }
 }
@@ -0,0 +1,722 @@
+//! GitRepository resource definition
+
+use std::{collections::BTreeMap, fmt};
+
+use chrono::{DateTime, Utc};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    annotations, creation_timestamp,
+    kind::{truncate, Column, Emphasis, Field, KindInfo, ResourceKind, Width},
+    observed_generation,
+    status::parse_status,
+    FluxResource, ReconcileInfo, ResourceStatus,
+};
+
+/// Annotation holding the `spec.ref` a GitRepository had before flux-tui
+/// overrode it, so the override can be reverted
+pub const ORIGINAL_REF_ANNOTATION: &str = "flux-tui.io/original-ref";
+
+/// `spec.ref` fields, in the order source-controller prefers them
+const REF_FIELDS: [&str; 5] = ["commit", "name", "semver", "tag", "branch"];
+
+/// Git reference a GitRepository tracks
+///
+/// When several are set, source-controller uses the most specific one:
+/// commit, then name, semver, tag and finally branch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GitRef {
+    Branch(String),
+    Tag(String),
+    Semver(String),
+    Name(String),
+    Commit(String),
+}
+
+impl GitRef {
+    /// Parse `spec.ref`, returning None if no reference is set
+    fn from_spec(reference: &serde_json::Value) -> Option<Self> {
+        let field = |key: &str| {
+            reference
+                .get(key)
+                .and_then(|v| v.as_str())
+                .filter(|v| !v.is_empty())
+                .map(String::from)
+        };
+
+        field("commit")
+            .map(GitRef::Commit)
+            .or_else(|| field("name").map(GitRef::Name))
+            .or_else(|| field("semver").map(GitRef::Semver))
+            .or_else(|| field("tag").map(GitRef::Tag))
+            .or_else(|| field("branch").map(GitRef::Branch))
+    }
+
+    /// Parse `[branch=|tag=|semver=|name=|commit=]<value>`, a bare value
+    /// being a branch
+    pub fn parse(input: &str) -> Option<Self> {
+        let (ref_type, value) = input.split_once('=').unwrap_or(("branch", input));
+        if value.is_empty() {
+            return None;
+        }
+        let value = value.to_string();
+        match ref_type {
+            "branch" => Some(GitRef::Branch(value)),
+            "tag" => Some(GitRef::Tag(value)),
+            "semver" => Some(GitRef::Semver(value)),
+            "name" => Some(GitRef::Name(value)),
+            "commit" => Some(GitRef::Commit(value)),
+            _ => None,
+        }
+    }
+
+    /// Merge patch for `spec.ref` tracking only `reference` (None = the
+    /// default branch), clearing the other fields
+    pub fn merge_patch(reference: Option<&GitRef>) -> serde_json::Value {
+        let mut fields: serde_json::Map<String, serde_json::Value> = REF_FIELDS
+            .iter()
+            .map(|field| (field.to_string(), serde_json::Value::Null))
+            .collect();
+        if let Some(reference) = reference {
+            fields.insert(reference.ref_type().to_string(), reference.value().into());
+        }
+        fields.into()
+    }
+
+    /// Reference type (branch, tag, semver, name, commit)
+    pub fn ref_type(&self) -> &'static str {
+        match self {
+            GitRef::Branch(_) => "branch",
+            GitRef::Tag(_) => "tag",
+            GitRef::Semver(_) => "semver",
+            GitRef::Name(_) => "name",
+            GitRef::Commit(_) => "commit",
+        }
+    }
+
+    /// Reference value
+    pub fn value(&self) -> &str {
+        match self {
+            GitRef::Branch(v)
+            | GitRef::Tag(v)
+            | GitRef::Semver(v)
+            | GitRef::Name(v)
+            | GitRef::Commit(v) => v,
+        }
+    }
+}
+
+impl fmt::Display for GitRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.ref_type(), self.value())
+    }
+}
+
+/// A ref set from flux-tui that diverges from the GitRepository in Git
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RefOverride {
+    /// Ref to restore on revert (None = source-controller default branch)
+    pub original: Option<GitRef>,
+}
+
+impl RefOverride {
+    /// Read the override from the GitRepository's annotations
+    fn from_metadata(metadata: &ObjectMeta) -> Option<Self> {
+        let annotation = metadata
+            .annotations
+            .as_ref()?
+            .get(ORIGINAL_REF_ANNOTATION)?;
+        let original: serde_json::Value = serde_json::from_str(annotation).ok()?;
+        Some(Self {
+            original: GitRef::from_spec(&original),
+        })
+    }
+
+    /// Annotation value recording the original ref
+    pub fn annotation(original: Option<&GitRef>) -> String {
+        let mut spec = serde_json::Map::new();
+        if let Some(original) = original {
+            spec.insert(original.ref_type().to_string(), original.value().into());
+        }
+        serde_json::Value::from(spec).to_string()
+    }
+}
+
+/// Another GitRepository whose artifact is included in this one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitInclude {
+    /// Name of the included GitRepository
+    pub repository: String,
+
+    /// Path to copy from the included artifact
+    pub from_path: String,
+
+    /// Path to copy to in this artifact
+    pub to_path: String,
+}
+
+/// Flux GitRepository resource
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GitRepository {
+    /// Resource name
+    pub name: String,
+
+    /// Resource namespace
+    pub namespace: String,
+
+    /// Cluster the resource was listed from (kube context or cluster name)
+    pub cluster: String,
+
+    /// Current status
+    pub status: ResourceStatus,
+
+    /// Status message
+    pub status_message: String,
+
+    /// Repository URL
+    pub url: String,
+
+    /// Tracked reference (None = source-controller default branch)
+    pub git_ref: Option<GitRef>,
+
+    /// Set when the ref was overridden from flux-tui
+    pub ref_override: Option<RefOverride>,
+
+    /// Ignore rules in .gitignore format
+    pub ignore: Option<String>,
+
+    /// Included GitRepositories
+    pub include: Vec<GitInclude>,
+
+    /// Whether submodules are cloned recursively
+    pub recurse_submodules: bool,
+
+    /// Whether the resource is suspended
+    pub suspended: bool,
+
+    /// Last fetched revision
+    pub revision: Option<String>,
+
+    /// Where source-controller serves the artifact tarball (status.artifact.url)
+    pub artifact_url: Option<String>,
+
+    /// Creation timestamp
+    pub created_at: Option<DateTime<Utc>>,
+
+    /// Spec generation (metadata.generation)
+    pub generation: Option<i64>,
+
+    /// Generation last observed by the controller (status.observedGeneration)
+    pub observed_generation: Option<i64>,
+
+    /// Reconcile settings and status conditions
+    pub reconcile: ReconcileInfo,
+
+    /// Metadata labels
+    pub labels: BTreeMap<String, String>,
+
+    /// Metadata annotations, without kubectl's last-applied-configuration
+    pub annotations: BTreeMap<String, String>,
+}
+
+impl GitRepository {
+    /// Create a new GitRepository from raw K8s data
+    pub fn from_kube(
+        metadata: &ObjectMeta,
+        spec: &serde_json::Value,
+        status: &serde_json::Value,
+    ) -> Self {
+        let name = metadata.name.clone().unwrap_or_default();
+        let namespace = metadata.namespace.clone().unwrap_or_default();
+        let created_at = creation_timestamp(metadata);
+
+        let suspended = spec
+            .get("suspend")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let url = spec
+            .get("url")
+            .and_then(|u| u.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let git_ref = spec.get("ref").and_then(GitRef::from_spec);
+
+        let ignore = spec
+            .get("ignore")
+            .and_then(|i| i.as_str())
+            .map(|i| i.trim().to_string())
+            .filter(|i| !i.is_empty());
+
+        let include = spec
+            .get("include")
+            .and_then(|i| i.as_array())
+            .map(|entries| entries.iter().filter_map(parse_include).collect())
+            .unwrap_or_default();
+
+        let recurse_submodules = spec
+            .get("recurseSubmodules")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let revision = status
+            .get("artifact")
+            .and_then(|a| a.get("revision"))
+            .and_then(|r| r.as_str())
+            .map(truncate_revision);
+
+        let artifact_url = status
+            .get("artifact")
+            .and_then(|a| a.get("url"))
+            .and_then(|u| u.as_str())
+            .map(String::from);
+
+        let (resource_status, status_message) = parse_status(status, suspended, &[]);
+
+        Self {
+            name,
+            namespace,
+            // Set by the client, which knows which cluster it talks to
+            cluster: String::new(),
+            status: resource_status,
+            status_message,
+            url,
+            git_ref,
+            ref_override: RefOverride::from_metadata(metadata),
+            ignore,
+            include,
+            recurse_submodules,
+            suspended,
+            revision,
+            artifact_url,
+            created_at,
+            generation: metadata.generation,
+            observed_generation: observed_generation(status),
+            reconcile: ReconcileInfo::from_kube(spec, status),
+            labels: metadata.labels.clone().unwrap_or_default(),
+            annotations: annotations(metadata),
+        }
+    }
+
+    /// Tracked reference for display, noting when the default is used
+    pub fn ref_display(&self) -> String {
+        match &self.git_ref {
+            Some(git_ref) => git_ref.to_string(),
+            None => "branch master (default)".to_string(),
+        }
+    }
+}
+
+impl FluxResource for GitRepository {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    fn cluster(&self) -> &str {
+        &self.cluster
+    }
+
+    fn kind(&self) -> &str {
+        "GitRepository"
+    }
+
+    fn status(&self) -> &ResourceStatus {
+        &self.status
+    }
+
+    fn status_message(&self) -> &str {
+        &self.status_message
+    }
+
+    fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+
+    fn revision(&self) -> Option<&str> {
+        self.revision.as_deref()
+    }
+
+    fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    fn generation(&self) -> Option<i64> {
+        self.generation
+    }
+
+    fn observed_generation(&self) -> Option<i64> {
+        self.observed_generation
+    }
+
+    fn reconcile_info(&self) -> &ReconcileInfo {
+        &self.reconcile
+    }
+
+    fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
+
+    fn annotations(&self) -> &BTreeMap<String, String> {
+        &self.annotations
+    }
+
+    fn details(&self) -> Vec<(&'static str, String)> {
+        let include = if self.include.is_empty() {
+            "-".to_string()
+        } else {
+            self.include
+                .iter()
+                .map(|i| format!("{}: {} → {}", i.repository, i.from_path, i.to_path))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let mut details = vec![
+            ("URL", self.url.clone()),
+            ("Ref", self.ref_display()),
+            (
+                "Ignore",
+                self.ignore.clone().unwrap_or_else(|| "-".to_string()),
+            ),
+            ("Include", include),
+            (
+                "Submodules",
+                if self.recurse_submodules { "Yes" } else { "No" }.to_string(),
+            ),
+        ];
+        if let Some(ref_override) = &self.ref_override {
+            let original = ref_override
+                .original
+                .as_ref()
+                .map_or_else(|| "the default branch".to_string(), |r| r.to_string());
+            details.insert(
+                2,
+                (
+                    "Ref override",
+                    format!("Diverges from Git! Revert to {original} with `:ref revert`"),
+                ),
+            );
+        }
+        details
+    }
+}
+
+/// Parse a `spec.include` entry
+fn parse_include(entry: &serde_json::Value) -> Option<GitInclude> {
+    let repository = entry
+        .get("repository")
+        .and_then(|r| r.get("name"))
+        .and_then(|n| n.as_str())?
+        .to_string();
+
+    let from_path = entry
+        .get("fromPath")
+        .and_then(|p| p.as_str())
+        .unwrap_or(".")
+        .to_string();
+
+    // source-controller copies into a directory named after the repository by default
+    let to_path = entry
+        .get("toPath")
+        .and_then(|p| p.as_str())
+        .unwrap_or(&repository)
+        .to_string();
+
+    Some(GitInclude {
+        repository,
+        from_path,
+        to_path,
+    })
+}
+
+/// Shorten a `branch@sha1:<hash>` revision to `branch@<short hash>`
+fn truncate_revision(revision: &str) -> String {
+    match revision.rsplit_once(':') {
+        Some((prefix, hash)) => {
+            let prefix = prefix.trim_end_matches("sha1").trim_end_matches("sha256");
+            format!("{}{}", prefix, &hash[..7.min(hash.len())])
+        }
+        None => revision.chars().take(12).collect(),
+    }
+}
+
+impl ResourceKind for GitRepository {
+    const INFO: KindInfo = KindInfo {
+        api_version: "source.toolkit.fluxcd.io/v1",
+        kind: "GitRepository",
+        suspendable: true,
+        is_source: true,
+        consumes_sources: false,
+    };
+
+    fn parse(
+        metadata: &ObjectMeta,
+        spec: &serde_json::Value,
+        status: &serde_json::Value,
+        cluster: &str,
+    ) -> Self {
+        Self {
+            cluster: cluster.to_string(),
+            ..Self::from_kube(metadata, spec, status)
+        }
+    }
+
+    fn columns() -> Vec<Column<Self>> {
+        vec![
+            Column::Name,
+            Column::Namespace,
+            Column::Ready,
+            Column::Status(25),
+            Column::Field {
+                title: "URL",
+                width: Width::Min(30),
+                value: |g, _| Field::truncated(&g.url, 30),
+            },
+            Column::Field {
+                title: "REF",
+                width: Width::Min(20),
+                value: ref_field,
+            },
+            Column::Suspended,
+            Column::Age,
+        ]
+    }
+}
+
+/// REF cell, flagging a ref overridden from flux-tui (diverging from Git)
+fn ref_field(repository: &GitRepository, _now: DateTime<Utc>) -> Field {
+    if repository.ref_override.is_some() {
+        Field::plain(format!("⚠ {}", truncate(&repository.ref_display(), 18)))
+            .with(Emphasis::Warning)
+    } else {
+        Field::truncated(&repository.ref_display(), 20)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::meta;
+    use serde_json::json;
+
+    #[test]
+    fn test_git_ref_precedence() {
+        let reference = json!({"branch": "main", "tag": "v1.0.0", "commit": "abc123"});
+        assert_eq!(
+            GitRef::from_spec(&reference),
+            Some(GitRef::Commit("abc123".to_string()))
+        );
+
+        let reference = json!({"branch": "main", "semver": ">=1.0.0"});
+        assert_eq!(
+            GitRef::from_spec(&reference),
+            Some(GitRef::Semver(">=1.0.0".to_string()))
+        );
+
+        let reference = json!({"branch": "main"});
+        assert_eq!(
+            GitRef::from_spec(&reference),
+            Some(GitRef::Branch("main".to_string()))
+        );
+
+        assert_eq!(GitRef::from_spec(&json!({"branch": ""})), None);
+    }
+
+    #[test]
+    fn test_git_ref_display() {
+        assert_eq!(GitRef::Tag("v1.2.3".to_string()).to_string(), "tag v1.2.3");
+        assert_eq!(
+            GitRef::Name("refs/pull/1/head".to_string()).to_string(),
+            "name refs/pull/1/head"
+        );
+    }
+
+    #[test]
+    fn test_git_ref_parse() {
+        assert_eq!(
+            GitRef::parse("feature/x"),
+            Some(GitRef::Branch("feature/x".to_string()))
+        );
+        assert_eq!(
+            GitRef::parse("tag=v1.2.3"),
+            Some(GitRef::Tag("v1.2.3".to_string()))
+        );
+        assert_eq!(GitRef::parse("tag="), None);
+        assert_eq!(GitRef::parse("sha=abc"), None);
+    }
+
+    #[test]
+    fn test_git_ref_merge_patch() {
+        assert_eq!(
+            GitRef::merge_patch(Some(&GitRef::Tag("v1".to_string()))),
+            json!({"commit": null, "name": null, "semver": null, "tag": "v1", "branch": null})
+        );
+        assert_eq!(
+            GitRef::merge_patch(None),
+            json!({"commit": null, "name": null, "semver": null, "tag": null, "branch": null})
+        );
+    }
+
+    #[test]
+    fn test_ref_override_from_annotation() {
+        let branch = GitRef::Branch("main".to_string());
+        let mut metadata = meta("fleet", "flux-system");
+        assert_eq!(RefOverride::from_metadata(&metadata), None);
+
+        metadata.annotations = Some(
+            [(
+                ORIGINAL_REF_ANNOTATION.to_string(),
+                RefOverride::annotation(Some(&branch)),
+            )]
+            .into(),
+        );
+        let repo = GitRepository::from_kube(&metadata, &json!({}), &json!({}));
+        assert_eq!(
+            repo.ref_override,
+            Some(RefOverride {
+                original: Some(branch)
+            })
+        );
+        assert_eq!(repo.details()[2].0, "Ref override");
+
+        metadata.annotations = Some(
+            [(
+                ORIGINAL_REF_ANNOTATION.to_string(),
+                RefOverride::annotation(None),
+            )]
+            .into(),
+        );
+        assert_eq!(
+            RefOverride::from_metadata(&metadata),
+            Some(RefOverride { original: None })
+        );
+    }
+
+    #[test]
+    fn test_truncate_revision() {
+        assert_eq!(
+            truncate_revision("main@sha1:1234567890abcdef"),
+            "main@1234567"
+        );
+        assert_eq!(
+            truncate_revision("v1.0.0@sha1:abcdef123456"),
+            "v1.0.0@abcdef1"
+        );
+        assert_eq!(truncate_revision("1234567890abcdef"), "1234567890ab");
+    }
+
+    #[test]
+    fn test_gitrepository_from_kube_full() {
+        let spec = json!({
+            "url": "https://github.com/stefanprodan/podinfo",
+            "ref": {"tag": "6.5.0"},
+            "ignore": "/*\n!/deploy\n",
+            "include": [
+                {"repository": {"name": "shared"}, "fromPath": "config", "toPath": "shared-config"},
+                {"repository": {"name": "charts"}}
+            ],
+            "recurseSubmodules": true
+        });
+        let status = json!({
+            "artifact": {
+                "revision": "6.5.0@sha1:0123456789abcdef",
+                "url": "http://source-controller.flux-system.svc.cluster.local./gitrepository/flux-system/podinfo/0123456.tar.gz"
+            },
+            "conditions": [{"type": "Ready", "status": "True", "message": "stored artifact"}]
+        });
+
+        let repo = GitRepository::from_kube(&meta("podinfo", "flux-system"), &spec, &status);
+
+        assert_eq!(repo.name, "podinfo");
+        assert_eq!(repo.url, "https://github.com/stefanprodan/podinfo");
+        assert_eq!(repo.git_ref, Some(GitRef::Tag("6.5.0".to_string())));
+        assert_eq!(repo.ignore.as_deref(), Some("/*\n!/deploy"));
+        assert_eq!(
+            repo.include,
+            vec![
+                GitInclude {
+                    repository: "shared".to_string(),
+                    from_path: "config".to_string(),
+                    to_path: "shared-config".to_string(),
+                },
+                GitInclude {
+                    repository: "charts".to_string(),
+                    from_path: ".".to_string(),
+                    to_path: "charts".to_string(),
+                },
+            ]
+        );
+        assert!(repo.recurse_submodules);
+        assert_eq!(repo.revision.as_deref(), Some("6.5.0@0123456"));
+        assert!(repo
+            .artifact_url
+            .as_deref()
+            .is_some_and(|url| url.ends_with("/podinfo/0123456.tar.gz")));
+        assert_eq!(repo.status, ResourceStatus::Ready);
+    }
+
+    #[test]
+    fn test_gitrepository_from_kube_defaults() {
+        let repo = GitRepository::from_kube(&meta("minimal", "default"), &json!({}), &json!({}));
+
+        assert_eq!(repo.url, "unknown");
+        assert_eq!(repo.git_ref, None);
+        assert_eq!(repo.ref_display(), "branch master (default)");
+        assert_eq!(repo.ignore, None);
+        assert!(repo.include.is_empty());
+        assert!(!repo.recurse_submodules);
+        assert!(!repo.suspended);
+        assert_eq!(repo.artifact_url, None);
+    }
+
+    #[test]
+    fn test_gitrepository_details() {
+        let repo = GitRepository {
+            url: "ssh://git@github.com/org/fleet".to_string(),
+            git_ref: Some(GitRef::Branch("main".to_string())),
+            include: vec![GitInclude {
+                repository: "shared".to_string(),
+                from_path: ".".to_string(),
+                to_path: "shared".to_string(),
+            }],
+            ..Default::default()
+        };
+
+        let details = repo.details();
+        assert_eq!(
+            details,
+            vec![
+                ("URL", "ssh://git@github.com/org/fleet".to_string()),
+                ("Ref", "branch main".to_string()),
+                ("Ignore", "-".to_string()),
+                ("Include", "shared: . → shared".to_string()),
+                ("Submodules", "No".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gitrepository_flux_resource_trait() {
+        let repo = GitRepository {
+            name: "fleet".to_string(),
+            namespace: "flux-system".to_string(),
+            status: ResourceStatus::Suspended,
+            suspended: true,
+            revision: Some("main@abc1234".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(repo.name(), "fleet");
+        assert_eq!(repo.namespace(), "flux-system");
+        assert_eq!(repo.kind(), "GitRepository");
+        assert!(repo.is_suspended());
+        assert!(!repo.is_ready());
+        assert_eq!(repo.revision(), Some("main@abc1234"));
+    }
+}
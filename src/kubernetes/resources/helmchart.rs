@@ -1,9 +1,21 @@
 //! HelmChart resource definition
 
-use super::{FluxResource, ResourceStatus};
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    annotations, creation_timestamp,
+    kind::{Column, Field, KindInfo, ResourceKind, Width},
+    observed_generation,
+    status::parse_status,
+    FluxResource, ReconcileInfo, ResourceStatus,
+};
 
 /// Flux HelmChart resource
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct HelmChart {
     /// Resource name
     pub name: String,
@@ -11,6 +23,9 @@ pub struct HelmChart {
     /// Resource namespace
     pub namespace: String,
 
+    /// Cluster the resource was listed from (kube context or cluster name)
+    pub cluster: String,
+
     /// Current status
     pub status: ResourceStatus,
 
@@ -28,16 +43,37 @@ pub struct HelmChart {
 
     /// Last fetched revision
     pub revision: Option<String>,
+
+    /// Creation timestamp
+    pub created_at: Option<DateTime<Utc>>,
+
+    /// Spec generation (metadata.generation)
+    pub generation: Option<i64>,
+
+    /// Generation last observed by the controller (status.observedGeneration)
+    pub observed_generation: Option<i64>,
+
+    /// Reconcile settings and status conditions
+    pub reconcile: ReconcileInfo,
+
+    /// Metadata labels
+    pub labels: BTreeMap<String, String>,
+
+    /// Metadata annotations, without kubectl's last-applied-configuration
+    pub annotations: BTreeMap<String, String>,
 }
 
 impl HelmChart {
     /// Create a new HelmChart from raw K8s data
     pub fn from_kube(
-        name: String,
-        namespace: String,
+        metadata: &ObjectMeta,
         spec: &serde_json::Value,
         status: &serde_json::Value,
     ) -> Self {
+        let name = metadata.name.clone().unwrap_or_default();
+        let namespace = metadata.namespace.clone().unwrap_or_default();
+        let created_at = creation_timestamp(metadata);
+
         let chart = spec
             .get("chart")
             .and_then(|c| c.as_str())
@@ -67,17 +103,25 @@ impl HelmChart {
             .and_then(|r| r.as_str())
             .map(String::from);
 
-        let (resource_status, status_message) = parse_status(status);
+        let (resource_status, status_message) = parse_status(status, false, &[]);
 
         Self {
             name,
             namespace,
+            // Set by the client, which knows which cluster it talks to
+            cluster: String::new(),
             status: resource_status,
             status_message,
             chart,
             version,
             source_ref,
             revision,
+            created_at,
+            generation: metadata.generation,
+            observed_generation: observed_generation(status),
+            reconcile: ReconcileInfo::from_kube(spec, status),
+            labels: metadata.labels.clone().unwrap_or_default(),
+            annotations: annotations(metadata),
         }
     }
 }
@@ -91,6 +135,10 @@ impl FluxResource for HelmChart {
         &self.namespace
     }
 
+    fn cluster(&self) -> &str {
+        &self.cluster
+    }
+
     fn kind(&self) -> &str {
         "HelmChart"
     }
@@ -110,116 +158,89 @@ impl FluxResource for HelmChart {
     fn revision(&self) -> Option<&str> {
         self.revision.as_deref()
     }
-}
 
-/// Parse the status conditions to determine resource status
-fn parse_status(status: &serde_json::Value) -> (ResourceStatus, String) {
-    let conditions = status.get("conditions").and_then(|c| c.as_array());
-
-    if let Some(conditions) = conditions {
-        for condition in conditions {
-            let condition_type = condition.get("type").and_then(|t| t.as_str());
-            let condition_status = condition.get("status").and_then(|s| s.as_str());
-            let message = condition
-                .get("message")
-                .and_then(|m| m.as_str())
-                .unwrap_or("Unknown")
-                .to_string();
-
-            if condition_type == Some("Ready") {
-                match condition_status {
-                    Some("True") => return (ResourceStatus::Ready, message),
-                    Some("False") => return (ResourceStatus::Failed, message),
-                    Some("Unknown") => return (ResourceStatus::Reconciling, message),
-                    _ => {}
-                }
-            }
+    fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
 
-            if condition_type == Some("Reconciling") && condition_status == Some("True") {
-                return (ResourceStatus::Reconciling, message);
-            }
-        }
+    fn generation(&self) -> Option<i64> {
+        self.generation
     }
 
-    (ResourceStatus::Unknown, "Status unknown".to_string())
-}
+    fn observed_generation(&self) -> Option<i64> {
+        self.observed_generation
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use serde_json::json;
+    fn reconcile_info(&self) -> &ReconcileInfo {
+        &self.reconcile
+    }
 
-    #[test]
-    fn test_parse_status_ready() {
-        let status = json!({
-            "conditions": [
-                {
-                    "type": "Ready",
-                    "status": "True",
-                    "message": "stored artifact: revision '1.2.3'"
-                }
-            ]
-        });
-        let (resource_status, message) = parse_status(&status);
-        assert_eq!(resource_status, ResourceStatus::Ready);
-        assert_eq!(message, "stored artifact: revision '1.2.3'");
+    fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
     }
 
-    #[test]
-    fn test_parse_status_failed() {
-        let status = json!({
-            "conditions": [
-                {
-                    "type": "Ready",
-                    "status": "False",
-                    "message": "chart pull error"
-                }
-            ]
-        });
-        let (resource_status, message) = parse_status(&status);
-        assert_eq!(resource_status, ResourceStatus::Failed);
-        assert_eq!(message, "chart pull error");
+    fn annotations(&self) -> &BTreeMap<String, String> {
+        &self.annotations
     }
 
-    #[test]
-    fn test_parse_status_unknown() {
-        let status = json!({
-            "conditions": [
-                {
-                    "type": "Ready",
-                    "status": "Unknown",
-                    "message": "Waiting for pull"
-                }
-            ]
-        });
-        let (resource_status, message) = parse_status(&status);
-        assert_eq!(resource_status, ResourceStatus::Reconciling);
-        assert_eq!(message, "Waiting for pull");
+    fn search_fields(&self) -> Vec<&str> {
+        vec![&self.name, &self.namespace, &self.chart]
     }
+}
 
-    #[test]
-    fn test_parse_status_reconciling_condition() {
-        let status = json!({
-            "conditions": [
-                {
-                    "type": "Reconciling",
-                    "status": "True",
-                    "message": "Reconciling"
-                }
-            ]
-        });
-        let (resource_status, message) = parse_status(&status);
-        assert_eq!(resource_status, ResourceStatus::Reconciling);
-        assert_eq!(message, "Reconciling");
+impl ResourceKind for HelmChart {
+    const INFO: KindInfo = KindInfo {
+        api_version: "source.toolkit.fluxcd.io/v1",
+        kind: "HelmChart",
+        // Owned by its HelmRelease, which is suspended instead
+        suspendable: false,
+        is_source: false,
+        consumes_sources: false,
+    };
+
+    fn parse(
+        metadata: &ObjectMeta,
+        spec: &serde_json::Value,
+        status: &serde_json::Value,
+        cluster: &str,
+    ) -> Self {
+        Self {
+            cluster: cluster.to_string(),
+            ..Self::from_kube(metadata, spec, status)
+        }
     }
 
-    #[test]
-    fn test_parse_status_no_conditions() {
-        let status = json!({});
-        let (resource_status, message) = parse_status(&status);
-        assert_eq!(resource_status, ResourceStatus::Unknown);
-        assert_eq!(message, "Status unknown");
+    fn columns() -> Vec<Column<Self>> {
+        vec![
+            Column::Name,
+            Column::Namespace,
+            Column::Ready,
+            Column::Status(25),
+            Column::Field {
+                title: "CHART",
+                width: Width::Min(15),
+                value: |h, _| Field::plain(h.chart.clone()),
+            },
+            Column::Field {
+                title: "VERSION",
+                width: Width::Min(10),
+                value: |h, _| Field::or_dash(h.version.as_deref()),
+            },
+            Column::Field {
+                title: "SOURCE",
+                width: Width::Min(20),
+                value: |h, _| Field::truncated(&h.source_ref, 20),
+            },
+            Column::Age,
+        ]
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::meta;
+    use serde_json::json;
 
     #[test]
     fn test_helmchart_from_kube_basic() {
@@ -244,12 +265,7 @@ mod tests {
             ]
         });
 
-        let hc = HelmChart::from_kube(
-            "default-nginx".to_string(),
-            "flux-system".to_string(),
-            &spec,
-            &status,
-        );
+        let hc = HelmChart::from_kube(&meta("default-nginx", "flux-system"), &spec, &status);
 
         assert_eq!(hc.name, "default-nginx");
         assert_eq!(hc.namespace, "flux-system");
@@ -265,7 +281,7 @@ mod tests {
         let spec = json!({});
         let status = json!({});
 
-        let hc = HelmChart::from_kube("minimal".to_string(), "default".to_string(), &spec, &status);
+        let hc = HelmChart::from_kube(&meta("minimal", "default"), &spec, &status);
 
         assert_eq!(hc.chart, "unknown");
         assert_eq!(hc.version, None);
@@ -284,6 +300,7 @@ mod tests {
             version: Some("1.0.0".to_string()),
             source_ref: "HelmRepository/bitnami".to_string(),
             revision: Some("rev".to_string()),
+            ..Default::default()
         };
 
         assert_eq!(hc.name(), "test");
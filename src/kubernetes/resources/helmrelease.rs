@@ -1,9 +1,21 @@
 //! HelmRelease resource definition
 
-use super::{FluxResource, ResourceStatus};
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    annotations, creation_timestamp, depends_on,
+    kind::{Column, Field, KindInfo, ResourceKind, Width},
+    list_or_dash, observed_generation,
+    status::parse_status,
+    FluxResource, ReconcileInfo, ResourceStatus, SourceRef,
+};
 
 /// Flux HelmRelease resource
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct HelmRelease {
     /// Resource name
     pub name: String,
@@ -11,6 +23,9 @@ pub struct HelmRelease {
     /// Resource namespace
     pub namespace: String,
 
+    /// Cluster the resource was listed from (kube context or cluster name)
+    pub cluster: String,
+
     /// Current status
     pub status: ResourceStatus,
 
@@ -23,21 +38,110 @@ pub struct HelmRelease {
     /// Chart version
     pub version: Option<String>,
 
+    /// Chart source (chart.spec.sourceRef, or chartRef)
+    pub source: Option<SourceRef>,
+
     /// Whether the resource is suspended
     pub suspended: bool,
 
+    /// Name of the Helm release
+    pub release_name: String,
+
+    /// Namespace of the Helm release's storage Secrets
+    pub storage_namespace: String,
+
     /// Last applied revision
     pub revision: Option<String>,
+
+    /// Creation timestamp
+    pub created_at: Option<DateTime<Utc>>,
+
+    /// Resources that must be ready first (namespace/name)
+    pub depends_on: Vec<String>,
+
+    /// What happens when an install fails (spec.install.remediation)
+    pub install_remediation: Remediation,
+
+    /// What happens when an upgrade fails (spec.upgrade.remediation)
+    pub upgrade_remediation: Remediation,
+
+    /// Spec generation (metadata.generation)
+    pub generation: Option<i64>,
+
+    /// Generation last observed by the controller (status.observedGeneration)
+    pub observed_generation: Option<i64>,
+
+    /// Reconcile settings and status conditions
+    pub reconcile: ReconcileInfo,
+
+    /// Metadata labels
+    pub labels: BTreeMap<String, String>,
+
+    /// Metadata annotations, without kubectl's last-applied-configuration
+    pub annotations: BTreeMap<String, String>,
+}
+
+/// Retry and remediation settings of an install or upgrade
+///
+/// helm-controller retries a failed action `retries` times (negative =
+/// forever). Once none remain, the last failure is remediated with `strategy`
+/// if `remediate_last_failure`, which defaults to true when retries are set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Remediation {
+    pub retries: i64,
+    pub remediate_last_failure: Option<bool>,
+
+    /// "uninstall" for installs; "rollback" (default) or "uninstall" for upgrades
+    pub strategy: String,
+}
+
+impl Remediation {
+    /// Parse the `remediation` of `spec.install` or `spec.upgrade`
+    fn from_spec(action: Option<&serde_json::Value>, default_strategy: &str) -> Self {
+        let remediation = action.and_then(|a| a.get("remediation"));
+        let field = |key: &str| remediation.and_then(|r| r.get(key));
+        Self {
+            retries: field("retries").and_then(|r| r.as_i64()).unwrap_or(0),
+            remediate_last_failure: field("remediateLastFailure").and_then(|r| r.as_bool()),
+            strategy: field("strategy")
+                .and_then(|s| s.as_str())
+                .unwrap_or(default_strategy)
+                .to_string(),
+        }
+    }
+
+    /// Whether the last failure is remediated once no retries remain
+    pub fn remediates_last_failure(&self) -> bool {
+        self.remediate_last_failure.unwrap_or(self.retries > 0)
+    }
+
+    /// Summary for the details, e.g. "3 retries, then rollback"
+    pub fn describe(&self) -> String {
+        let retries = match self.retries {
+            r if r < 0 => return "retries until it succeeds".to_string(),
+            0 => "no retries".to_string(),
+            1 => "1 retry".to_string(),
+            r => format!("{r} retries"),
+        };
+        if self.remediates_last_failure() {
+            format!("{retries}, then {}", self.strategy)
+        } else {
+            format!("{retries}, then left failed")
+        }
+    }
 }
 
 impl HelmRelease {
     /// Create a new HelmRelease from raw K8s data
     pub fn from_kube(
-        name: String,
-        namespace: String,
+        metadata: &ObjectMeta,
         spec: &serde_json::Value,
         status: &serde_json::Value,
     ) -> Self {
+        let name = metadata.name.clone().unwrap_or_default();
+        let namespace = metadata.namespace.clone().unwrap_or_default();
+        let created_at = creation_timestamp(metadata);
+
         let suspended = spec
             .get("suspend")
             .and_then(|v| v.as_bool())
@@ -58,22 +162,59 @@ impl HelmRelease {
             .and_then(|v| v.as_str())
             .map(String::from);
 
+        let source = spec
+            .get("chart")
+            .and_then(|c| c.get("spec"))
+            .and_then(|s| s.get("sourceRef"))
+            .map(|sr| SourceRef::from_spec(sr, "HelmRepository", &namespace))
+            .or_else(|| {
+                spec.get("chartRef")
+                    .map(|cr| SourceRef::from_spec(cr, "OCIRepository", &namespace))
+            });
+
         let revision = status
             .get("lastAppliedRevision")
             .and_then(|r| r.as_str())
             .map(String::from);
 
-        let (resource_status, status_message) = parse_status(status, suspended);
+        let release_name = release_name(spec, status, &name);
+        let storage_namespace = spec
+            .get("storageNamespace")
+            .or_else(|| status.get("storageNamespace"))
+            .and_then(|n| n.as_str())
+            .unwrap_or(&namespace)
+            .to_string();
+
+        let dependencies = depends_on(spec, &namespace);
+        let install_remediation = Remediation::from_spec(spec.get("install"), "uninstall");
+        let upgrade_remediation = Remediation::from_spec(spec.get("upgrade"), "rollback");
+
+        let (resource_status, status_message) =
+            parse_status(status, suspended, PROGRESSING_REASONS);
 
         Self {
             name,
             namespace,
+            // Set by the client, which knows which cluster it talks to
+            cluster: String::new(),
             status: resource_status,
             status_message,
             chart,
             version,
+            source,
             suspended,
+            release_name,
+            storage_namespace,
             revision,
+            created_at,
+            depends_on: dependencies,
+            install_remediation,
+            upgrade_remediation,
+            generation: metadata.generation,
+            observed_generation: observed_generation(status),
+            reconcile: ReconcileInfo::from_kube(spec, status),
+            labels: metadata.labels.clone().unwrap_or_default(),
+            annotations: annotations(metadata),
         }
     }
 }
@@ -87,6 +228,10 @@ impl FluxResource for HelmRelease {
         &self.namespace
     }
 
+    fn cluster(&self) -> &str {
+        &self.cluster
+    }
+
     fn kind(&self) -> &str {
         "HelmRelease"
     }
@@ -106,96 +251,138 @@ impl FluxResource for HelmRelease {
     fn revision(&self) -> Option<&str> {
         self.revision.as_deref()
     }
+
+    fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    fn source(&self) -> Option<&SourceRef> {
+        self.source.as_ref()
+    }
+
+    // Charts from Helm and OCI repositories are named, not located
+    fn source_path(&self) -> Option<&str> {
+        self.source
+            .as_ref()
+            .filter(|source| matches!(source.kind.as_str(), "GitRepository" | "Bucket"))
+            .map(|_| self.chart.as_str())
+    }
+
+    fn generation(&self) -> Option<i64> {
+        self.generation
+    }
+
+    fn observed_generation(&self) -> Option<i64> {
+        self.observed_generation
+    }
+
+    fn reconcile_info(&self) -> &ReconcileInfo {
+        &self.reconcile
+    }
+
+    fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
+
+    fn annotations(&self) -> &BTreeMap<String, String> {
+        &self.annotations
+    }
+
+    fn details(&self) -> Vec<(&'static str, String)> {
+        let chart = match &self.version {
+            Some(version) => format!("{} {}", self.chart, version),
+            None => self.chart.clone(),
+        };
+        let source = self
+            .source
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_else(|| "-".to_string());
+
+        vec![
+            ("Chart", chart),
+            ("Source", source),
+            ("Depends on", list_or_dash(&self.depends_on)),
+            ("On install failure", self.install_remediation.describe()),
+            ("On upgrade failure", self.upgrade_remediation.describe()),
+        ]
+    }
+
+    fn search_fields(&self) -> Vec<&str> {
+        vec![&self.name, &self.namespace, &self.chart]
+    }
 }
 
-/// Parse the status conditions to determine resource status
-fn parse_status(status: &serde_json::Value, suspended: bool) -> (ResourceStatus, String) {
-    if suspended {
-        return (ResourceStatus::Suspended, "Suspended".to_string());
-    }
-
-    let conditions = status.get("conditions").and_then(|c| c.as_array());
-
-    if let Some(conditions) = conditions {
-        for condition in conditions {
-            let condition_type = condition.get("type").and_then(|t| t.as_str());
-            let condition_status = condition.get("status").and_then(|s| s.as_str());
-            let message = condition
-                .get("message")
-                .and_then(|m| m.as_str())
-                .unwrap_or("Unknown")
-                .to_string();
-
-            if condition_type == Some("Ready") {
-                match condition_status {
-                    Some("True") => return (ResourceStatus::Ready, message),
-                    Some("False") => {
-                        let reason = condition.get("reason").and_then(|r| r.as_str());
-                        if reason == Some("Progressing") || reason == Some("ArtifactFailed") {
-                            return (ResourceStatus::Reconciling, message);
-                        }
-                        return (ResourceStatus::Failed, message);
-                    }
-                    Some("Unknown") => return (ResourceStatus::Reconciling, message),
-                    _ => {}
-                }
-            }
+/// Helm release name
+///
+/// The controller records it in the release history; before the first
+/// release it is `spec.releaseName`, else the name prefixed with the target
+/// namespace when there is one.
+fn release_name(spec: &serde_json::Value, status: &serde_json::Value, name: &str) -> String {
+    if let Some(recorded) = status.pointer("/history/0/name").and_then(|n| n.as_str()) {
+        return recorded.to_string();
+    }
+    if let Some(release_name) = spec.get("releaseName").and_then(|n| n.as_str()) {
+        return release_name.to_string();
+    }
+    match spec.get("targetNamespace").and_then(|n| n.as_str()) {
+        Some(target) => format!("{target}-{name}"),
+        None => name.to_string(),
+    }
+}
 
-            if condition_type == Some("Reconciling") && condition_status == Some("True") {
-                return (ResourceStatus::Reconciling, message);
-            }
+/// Ready=False reasons helm-controller sets while it is still working, including
+/// waiting for the chart artifact
+const PROGRESSING_REASONS: &[&str] = &["Progressing", "ArtifactFailed"];
+impl ResourceKind for HelmRelease {
+    const INFO: KindInfo = KindInfo {
+        api_version: "helm.toolkit.fluxcd.io/v2",
+        kind: "HelmRelease",
+        suspendable: true,
+        is_source: false,
+        consumes_sources: true,
+    };
+
+    fn parse(
+        metadata: &ObjectMeta,
+        spec: &serde_json::Value,
+        status: &serde_json::Value,
+        cluster: &str,
+    ) -> Self {
+        Self {
+            cluster: cluster.to_string(),
+            ..Self::from_kube(metadata, spec, status)
         }
     }
 
-    (ResourceStatus::Unknown, "Status unknown".to_string())
+    fn columns() -> Vec<Column<Self>> {
+        vec![
+            Column::Name,
+            Column::Namespace,
+            Column::Ready,
+            Column::Status(25),
+            Column::Field {
+                title: "CHART",
+                width: Width::Min(15),
+                value: |h, _| Field::plain(h.chart.clone()),
+            },
+            Column::Field {
+                title: "VERSION",
+                width: Width::Min(10),
+                value: |h, _| Field::or_dash(h.version.as_deref()),
+            },
+            Column::Suspended,
+            Column::Age,
+        ]
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::kubernetes::resources::meta;
     use serde_json::json;
 
-    #[test]
-    fn test_parse_status_suspended() {
-        let status = json!({});
-        let (resource_status, message) = parse_status(&status, true);
-        assert_eq!(resource_status, ResourceStatus::Suspended);
-        assert_eq!(message, "Suspended");
-    }
-
-    #[test]
-    fn test_parse_status_ready() {
-        let status = json!({
-            "conditions": [
-                {
-                    "type": "Ready",
-                    "status": "True",
-                    "message": "Release reconciliation succeeded"
-                }
-            ]
-        });
-        let (resource_status, message) = parse_status(&status, false);
-        assert_eq!(resource_status, ResourceStatus::Ready);
-        assert_eq!(message, "Release reconciliation succeeded");
-    }
-
-    #[test]
-    fn test_parse_status_failed() {
-        let status = json!({
-            "conditions": [
-                {
-                    "type": "Ready",
-                    "status": "False",
-                    "reason": "InstallFailed",
-                    "message": "Helm install failed"
-                }
-            ]
-        });
-        let (resource_status, message) = parse_status(&status, false);
-        assert_eq!(resource_status, ResourceStatus::Failed);
-        assert_eq!(message, "Helm install failed");
-    }
-
     #[test]
     fn test_parse_status_progressing() {
         let status = json!({
@@ -208,7 +395,7 @@ mod tests {
                 }
             ]
         });
-        let (resource_status, message) = parse_status(&status, false);
+        let (resource_status, message) = parse_status(&status, false, PROGRESSING_REASONS);
         assert_eq!(resource_status, ResourceStatus::Reconciling);
         assert_eq!(message, "Helm upgrade in progress");
     }
@@ -225,51 +412,11 @@ mod tests {
                 }
             ]
         });
-        let (resource_status, message) = parse_status(&status, false);
+        let (resource_status, message) = parse_status(&status, false, PROGRESSING_REASONS);
         assert_eq!(resource_status, ResourceStatus::Reconciling);
         assert_eq!(message, "Waiting for HelmChart");
     }
 
-    #[test]
-    fn test_parse_status_unknown_ready_status() {
-        let status = json!({
-            "conditions": [
-                {
-                    "type": "Ready",
-                    "status": "Unknown",
-                    "message": "Waiting for reconciliation"
-                }
-            ]
-        });
-        let (resource_status, message) = parse_status(&status, false);
-        assert_eq!(resource_status, ResourceStatus::Reconciling);
-        assert_eq!(message, "Waiting for reconciliation");
-    }
-
-    #[test]
-    fn test_parse_status_reconciling_condition() {
-        let status = json!({
-            "conditions": [
-                {
-                    "type": "Reconciling",
-                    "status": "True",
-                    "message": "Reconciling"
-                }
-            ]
-        });
-        let (resource_status, message) = parse_status(&status, false);
-        assert_eq!(resource_status, ResourceStatus::Reconciling);
-        assert_eq!(message, "Reconciling");
-    }
-
-    #[test]
-    fn test_parse_status_no_conditions() {
-        let status = json!({});
-        let (resource_status, message) = parse_status(&status, false);
-        assert_eq!(resource_status, ResourceStatus::Unknown);
-        assert_eq!(message, "Status unknown");
-    }
-
     #[test]
     fn test_helmrelease_from_kube_basic() {
         let spec = json!({
@@ -291,12 +438,7 @@ mod tests {
             ]
         });
 
-        let hr = HelmRelease::from_kube(
-            "my-nginx".to_string(),
-            "default".to_string(),
-            &spec,
-            &status,
-        );
+        let hr = HelmRelease::from_kube(&meta("my-nginx", "default"), &spec, &status);
 
         assert_eq!(hr.name, "my-nginx");
         assert_eq!(hr.namespace, "default");
@@ -307,6 +449,98 @@ mod tests {
         assert!(!hr.suspended);
     }
 
+    #[test]
+    fn test_helmrelease_from_kube_source() {
+        let spec = json!({
+            "chart": {
+                "spec": {
+                    "chart": "podinfo",
+                    "sourceRef": {"kind": "HelmRepository", "name": "podinfo", "namespace": "flux-system"}
+                }
+            }
+        });
+        let hr = HelmRelease::from_kube(&meta("podinfo", "apps"), &spec, &json!({}));
+        let source = hr.source().unwrap();
+        assert_eq!(source.to_string(), "HelmRepository/podinfo");
+        assert_eq!(source.namespace, "flux-system");
+
+        let spec = json!({"chartRef": {"kind": "OCIRepository", "name": "podinfo"}});
+        let hr = HelmRelease::from_kube(&meta("podinfo", "apps"), &spec, &json!({}));
+        let source = hr.source().unwrap();
+        assert_eq!(source.to_string(), "OCIRepository/podinfo");
+        assert_eq!(source.namespace, "apps");
+
+        let hr = HelmRelease::from_kube(&meta("podinfo", "apps"), &json!({}), &json!({}));
+        assert!(hr.source().is_none());
+    }
+
+    #[test]
+    fn test_helmrelease_from_kube_release_name() {
+        let hr = HelmRelease::from_kube(&meta("podinfo", "apps"), &json!({}), &json!({}));
+        assert_eq!(hr.release_name, "podinfo");
+        assert_eq!(hr.storage_namespace, "apps");
+
+        let spec = json!({"targetNamespace": "web", "storageNamespace": "helm"});
+        let hr = HelmRelease::from_kube(&meta("podinfo", "apps"), &spec, &json!({}));
+        assert_eq!(hr.release_name, "web-podinfo");
+        assert_eq!(hr.storage_namespace, "helm");
+
+        let spec = json!({"targetNamespace": "web", "releaseName": "frontend"});
+        let hr = HelmRelease::from_kube(&meta("podinfo", "apps"), &spec, &json!({}));
+        assert_eq!(hr.release_name, "frontend");
+
+        // The name the controller recorded wins (long names are shortened)
+        let status = json!({"history": [{"name": "web-podinfo-3f2a1b", "version": 2}]});
+        let hr = HelmRelease::from_kube(&meta("podinfo", "apps"), &spec, &status);
+        assert_eq!(hr.release_name, "web-podinfo-3f2a1b");
+    }
+
+    #[test]
+    fn test_helmrelease_from_kube_remediation() {
+        let hr = HelmRelease::from_kube(&meta("podinfo", "apps"), &json!({}), &json!({}));
+        assert_eq!(
+            hr.install_remediation.describe(),
+            "no retries, then left failed"
+        );
+        assert_eq!(
+            hr.upgrade_remediation.describe(),
+            "no retries, then left failed"
+        );
+
+        let spec = json!({
+            "install": {"remediation": {"retries": -1}},
+            "upgrade": {"remediation": {"retries": 3, "strategy": "uninstall"}}
+        });
+        let hr = HelmRelease::from_kube(&meta("podinfo", "apps"), &spec, &json!({}));
+        assert_eq!(
+            hr.install_remediation.describe(),
+            "retries until it succeeds"
+        );
+        assert_eq!(
+            hr.upgrade_remediation.describe(),
+            "3 retries, then uninstall"
+        );
+
+        let spec = json!({
+            "install": {"remediation": {"retries": 1}},
+            "upgrade": {"remediation": {"retries": 2, "remediateLastFailure": false}}
+        });
+        let hr = HelmRelease::from_kube(&meta("podinfo", "apps"), &spec, &json!({}));
+        assert_eq!(hr.install_remediation.describe(), "1 retry, then uninstall");
+        assert_eq!(
+            hr.upgrade_remediation.describe(),
+            "2 retries, then left failed"
+        );
+
+        let spec = json!({"upgrade": {"remediation": {"remediateLastFailure": true}}});
+        let hr = HelmRelease::from_kube(&meta("podinfo", "apps"), &spec, &json!({}));
+        assert_eq!(
+            hr.upgrade_remediation.describe(),
+            "no retries, then rollback"
+        );
+        assert_eq!(hr.details()[3].0, "On install failure");
+    }
+
     #[test]
     fn test_helmrelease_from_kube_suspended() {
         let spec = json!({
@@ -319,12 +553,7 @@ mod tests {
         });
         let status = json!({});
 
-        let hr = HelmRelease::from_kube(
-            "my-redis".to_string(),
-            "default".to_string(),
-            &spec,
-            &status,
-        );
+        let hr = HelmRelease::from_kube(&meta("my-redis", "default"), &spec, &status);
 
         assert!(hr.suspended);
         assert_eq!(hr.status, ResourceStatus::Suspended);
@@ -335,8 +564,7 @@ mod tests {
         let spec = json!({});
         let status = json!({});
 
-        let hr =
-            HelmRelease::from_kube("minimal".to_string(), "default".to_string(), &spec, &status);
+        let hr = HelmRelease::from_kube(&meta("minimal", "default"), &spec, &status);
 
         assert_eq!(hr.chart, "unknown");
         assert_eq!(hr.version, None);
@@ -355,6 +583,7 @@ mod tests {
             version: Some("1.0.0".to_string()),
             suspended: false,
             revision: Some("rev".to_string()),
+            ..Default::default()
         };
 
         assert_eq!(hr.name(), "test");
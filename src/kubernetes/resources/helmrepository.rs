@@ -0,0 +1,424 @@
+//! HelmRepository resource definition
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    annotations, creation_timestamp, format_age,
+    kind::{Column, Emphasis, Field, KindInfo, ResourceKind, Width},
+    observed_generation, parse_duration,
+    status::parse_status,
+    FluxResource, ReconcileInfo, ResourceStatus,
+};
+
+/// Kind of Helm repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum HelmRepositoryType {
+    /// Classic repository serving an index.yaml over HTTP(S)
+    #[default]
+    Http,
+    /// OCI registry (no index; charts are pulled by tag)
+    Oci,
+}
+
+impl HelmRepositoryType {
+    /// Display label
+    pub fn label(&self) -> &'static str {
+        match self {
+            HelmRepositoryType::Http => "HTTP",
+            HelmRepositoryType::Oci => "OCI",
+        }
+    }
+}
+
+/// Flux HelmRepository resource
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HelmRepository {
+    /// Resource name
+    pub name: String,
+
+    /// Resource namespace
+    pub namespace: String,
+
+    /// Cluster the resource was listed from (kube context or cluster name)
+    pub cluster: String,
+
+    /// Current status
+    pub status: ResourceStatus,
+
+    /// Status message
+    pub status_message: String,
+
+    /// Repository URL
+    pub url: String,
+
+    /// Repository type (OCI or HTTP)
+    pub repo_type: HelmRepositoryType,
+
+    /// Reconcile interval
+    pub interval: Option<Duration>,
+
+    /// When the index artifact was last updated
+    pub last_fetched: Option<DateTime<Utc>>,
+
+    /// Whether the resource is suspended
+    pub suspended: bool,
+
+    /// Index revision (digest)
+    pub revision: Option<String>,
+
+    /// Creation timestamp
+    pub created_at: Option<DateTime<Utc>>,
+
+    /// Spec generation (metadata.generation)
+    pub generation: Option<i64>,
+
+    /// Generation last observed by the controller (status.observedGeneration)
+    pub observed_generation: Option<i64>,
+
+    /// Reconcile settings and status conditions
+    pub reconcile: ReconcileInfo,
+
+    /// Metadata labels
+    pub labels: BTreeMap<String, String>,
+
+    /// Metadata annotations, without kubectl's last-applied-configuration
+    pub annotations: BTreeMap<String, String>,
+}
+
+impl HelmRepository {
+    /// Create a new HelmRepository from raw K8s data
+    pub fn from_kube(
+        metadata: &ObjectMeta,
+        spec: &serde_json::Value,
+        status: &serde_json::Value,
+    ) -> Self {
+        let name = metadata.name.clone().unwrap_or_default();
+        let namespace = metadata.namespace.clone().unwrap_or_default();
+        let created_at = creation_timestamp(metadata);
+
+        let suspended = spec
+            .get("suspend")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let url = spec
+            .get("url")
+            .and_then(|u| u.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let is_oci =
+            spec.get("type").and_then(|t| t.as_str()) == Some("oci") || url.starts_with("oci://");
+        let repo_type = if is_oci {
+            HelmRepositoryType::Oci
+        } else {
+            HelmRepositoryType::Http
+        };
+
+        let interval = spec
+            .get("interval")
+            .and_then(|i| i.as_str())
+            .and_then(parse_duration);
+
+        let artifact = status.get("artifact");
+
+        let last_fetched = artifact
+            .and_then(|a| a.get("lastUpdateTime"))
+            .and_then(|t| t.as_str())
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc));
+
+        let revision = artifact
+            .and_then(|a| a.get("revision"))
+            .and_then(|r| r.as_str())
+            .map(|r| r.trim_start_matches("sha256:").chars().take(12).collect());
+
+        let (resource_status, status_message) = parse_status(status, suspended, &[]);
+
+        Self {
+            name,
+            namespace,
+            // Set by the client, which knows which cluster it talks to
+            cluster: String::new(),
+            status: resource_status,
+            status_message,
+            url,
+            repo_type,
+            interval,
+            last_fetched,
+            suspended,
+            revision,
+            created_at,
+            generation: metadata.generation,
+            observed_generation: observed_generation(status),
+            reconcile: ReconcileInfo::from_kube(spec, status),
+            labels: metadata.labels.clone().unwrap_or_default(),
+            annotations: annotations(metadata),
+        }
+    }
+
+    /// Whether the index is older than the reconcile interval
+    ///
+    /// OCI repositories have no index, and suspended repositories are
+    /// expected to go stale, so neither is flagged.
+    pub fn is_index_stale(&self, now: DateTime<Utc>) -> bool {
+        if self.repo_type == HelmRepositoryType::Oci || self.suspended {
+            return false;
+        }
+
+        match (self.last_fetched, self.interval) {
+            (Some(fetched), Some(interval)) => now - fetched > interval,
+            _ => false,
+        }
+    }
+
+    /// Last index fetch for display, e.g. "12m ago (stale)"
+    pub fn last_fetched_display(&self, now: DateTime<Utc>) -> String {
+        match (self.repo_type, self.last_fetched) {
+            (HelmRepositoryType::Oci, _) => "n/a (OCI)".to_string(),
+            (_, None) => "never".to_string(),
+            (_, Some(fetched)) if self.is_index_stale(now) => {
+                format!("{} ago (stale)", format_age(fetched, now))
+            }
+            (_, Some(fetched)) => format!("{} ago", format_age(fetched, now)),
+        }
+    }
+}
+
+impl FluxResource for HelmRepository {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    fn cluster(&self) -> &str {
+        &self.cluster
+    }
+
+    fn kind(&self) -> &str {
+        "HelmRepository"
+    }
+
+    fn status(&self) -> &ResourceStatus {
+        &self.status
+    }
+
+    fn status_message(&self) -> &str {
+        &self.status_message
+    }
+
+    fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+
+    fn revision(&self) -> Option<&str> {
+        self.revision.as_deref()
+    }
+
+    fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    fn generation(&self) -> Option<i64> {
+        self.generation
+    }
+
+    fn observed_generation(&self) -> Option<i64> {
+        self.observed_generation
+    }
+
+    fn reconcile_info(&self) -> &ReconcileInfo {
+        &self.reconcile
+    }
+
+    fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
+
+    fn annotations(&self) -> &BTreeMap<String, String> {
+        &self.annotations
+    }
+
+    fn details(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Type", self.repo_type.label().to_string()),
+            ("URL", self.url.clone()),
+            ("Fetched", self.last_fetched_display(Utc::now())),
+        ]
+    }
+}
+
+impl ResourceKind for HelmRepository {
+    const INFO: KindInfo = KindInfo {
+        api_version: "source.toolkit.fluxcd.io/v1",
+        kind: "HelmRepository",
+        suspendable: true,
+        is_source: true,
+        consumes_sources: false,
+    };
+
+    fn parse(
+        metadata: &ObjectMeta,
+        spec: &serde_json::Value,
+        status: &serde_json::Value,
+        cluster: &str,
+    ) -> Self {
+        Self {
+            cluster: cluster.to_string(),
+            ..Self::from_kube(metadata, spec, status)
+        }
+    }
+
+    fn columns() -> Vec<Column<Self>> {
+        vec![
+            Column::Name,
+            Column::Namespace,
+            Column::Ready,
+            Column::Status(25),
+            Column::Field {
+                title: "TYPE",
+                width: Width::Length(4),
+                value: |h, _| Field::plain(h.repo_type.label()),
+            },
+            Column::Field {
+                title: "URL",
+                width: Width::Min(30),
+                value: |h, _| Field::truncated(&h.url, 30),
+            },
+            Column::Field {
+                title: "FETCHED",
+                width: Width::Min(16),
+                value: |h, now| {
+                    let fetched = Field::plain(h.last_fetched_display(now));
+                    // Stale indexes explain "chart version not found" errors
+                    if h.is_index_stale(now) {
+                        fetched.with(Emphasis::Attention)
+                    } else {
+                        fetched
+                    }
+                },
+            },
+            Column::Age,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kubernetes::resources::meta;
+    use serde_json::json;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_helmrepository_from_kube_http() {
+        let spec = json!({
+            "url": "https://charts.bitnami.com/bitnami",
+            "interval": "10m0s"
+        });
+        let status = json!({
+            "artifact": {
+                "revision": "sha256:0123456789abcdef0123",
+                "lastUpdateTime": "2024-05-01T12:00:00Z"
+            },
+            "conditions": [{"type": "Ready", "status": "True", "message": "stored artifact"}]
+        });
+
+        let repo = HelmRepository::from_kube(&meta("bitnami", "flux-system"), &spec, &status);
+
+        assert_eq!(repo.name, "bitnami");
+        assert_eq!(repo.repo_type, HelmRepositoryType::Http);
+        assert_eq!(repo.interval, Some(Duration::minutes(10)));
+        assert_eq!(repo.last_fetched, Some(at("2024-05-01T12:00:00Z")));
+        assert_eq!(repo.revision.as_deref(), Some("0123456789ab"));
+        assert_eq!(repo.status, ResourceStatus::Ready);
+    }
+
+    #[test]
+    fn test_helmrepository_from_kube_oci() {
+        let spec = json!({"type": "oci", "url": "oci://ghcr.io/stefanprodan/charts"});
+        let repo = HelmRepository::from_kube(&meta("podinfo", "default"), &spec, &json!({}));
+        assert_eq!(repo.repo_type, HelmRepositoryType::Oci);
+
+        // The URL scheme alone is enough to identify OCI repositories
+        let spec = json!({"url": "oci://registry.example.com/charts"});
+        let repo = HelmRepository::from_kube(&meta("internal", "default"), &spec, &json!({}));
+        assert_eq!(repo.repo_type, HelmRepositoryType::Oci);
+        assert_eq!(repo.status, ResourceStatus::Pending);
+    }
+
+    #[test]
+    fn test_is_index_stale() {
+        let repo = HelmRepository {
+            interval: Some(Duration::minutes(10)),
+            last_fetched: Some(at("2024-05-01T12:00:00Z")),
+            ..Default::default()
+        };
+
+        assert!(!repo.is_index_stale(at("2024-05-01T12:09:00Z")));
+        assert!(repo.is_index_stale(at("2024-05-01T12:30:00Z")));
+        assert_eq!(
+            repo.last_fetched_display(at("2024-05-01T12:30:00Z")),
+            "30m ago (stale)"
+        );
+        assert_eq!(
+            repo.last_fetched_display(at("2024-05-01T12:05:00Z")),
+            "5m ago"
+        );
+    }
+
+    #[test]
+    fn test_is_index_stale_ignores_oci_and_suspended() {
+        let fetched = Some(at("2024-05-01T12:00:00Z"));
+        let now = at("2024-05-02T12:00:00Z");
+
+        let oci = HelmRepository {
+            repo_type: HelmRepositoryType::Oci,
+            interval: Some(Duration::minutes(10)),
+            last_fetched: fetched,
+            ..Default::default()
+        };
+        assert!(!oci.is_index_stale(now));
+        assert_eq!(oci.last_fetched_display(now), "n/a (OCI)");
+
+        let suspended = HelmRepository {
+            suspended: true,
+            interval: Some(Duration::minutes(10)),
+            last_fetched: fetched,
+            ..Default::default()
+        };
+        assert!(!suspended.is_index_stale(now));
+
+        let never = HelmRepository::default();
+        assert_eq!(never.last_fetched_display(now), "never");
+    }
+
+    #[test]
+    fn test_helmrepository_flux_resource_trait() {
+        let repo = HelmRepository {
+            name: "bitnami".to_string(),
+            namespace: "flux-system".to_string(),
+            status: ResourceStatus::Ready,
+            url: "https://charts.bitnami.com/bitnami".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(repo.kind(), "HelmRepository");
+        assert!(repo.is_ready());
+        assert!(!repo.is_suspended());
+
+        let details = repo.details();
+        assert_eq!(details[0], ("Type", "HTTP".to_string()));
+        assert_eq!(details[2], ("Fetched", "never".to_string()));
+    }
+}
@@ -0,0 +1,225 @@
+//! What the app needs to know about a Flux resource kind
+//!
+//! Each kind implements [`ResourceKind`] once: where its API lives, how to
+//! parse listed objects, what its table columns show and which actions apply.
+//! The client lists, and the tables draw, any kind from that alone, so a new
+//! tab is a struct with a `from_kube` and one impl.
+
+use chrono::{DateTime, Utc};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use serde_json::Value;
+
+use super::{FluxResource, GitRepository, HelmChart, HelmRelease, HelmRepository, Kustomization};
+
+/// API location and supported actions of a kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KindInfo {
+    /// e.g. "kustomize.toolkit.fluxcd.io/v1"
+    pub api_version: &'static str,
+
+    /// Kind as Kubernetes names it, e.g. "Kustomization"
+    pub kind: &'static str,
+
+    /// Whether `spec.suspend` can be toggled
+    pub suspendable: bool,
+
+    /// Whether other resources are built from it (suspending asks first)
+    pub is_source: bool,
+
+    /// Whether it references a source, so its tab can be filtered by one
+    pub consumes_sources: bool,
+}
+
+/// A Flux resource kind shown on its own tab
+pub trait ResourceKind: FluxResource + Clone + Sized + 'static {
+    const INFO: KindInfo;
+
+    /// Parse a listed object, labelled with the cluster it was listed from
+    fn parse(metadata: &ObjectMeta, spec: &Value, status: &Value, cluster: &str) -> Self;
+
+    /// Table columns, left to right
+    fn columns() -> Vec<Column<Self>>;
+}
+
+/// All kinds with a tab, in tab order
+pub const KINDS: [KindInfo; 5] = [
+    Kustomization::INFO,
+    HelmRelease::INFO,
+    HelmChart::INFO,
+    GitRepository::INFO,
+    HelmRepository::INFO,
+];
+
+/// Info for a kind name (e.g. "GitRepository")
+pub fn info_for(kind: &str) -> Option<&'static KindInfo> {
+    KINDS.iter().find(|info| info.kind == kind)
+}
+
+/// A table column
+pub enum Column<T> {
+    Name,
+    Namespace,
+
+    /// Status icon and failure streak
+    Ready,
+
+    /// Status message, truncated to this many characters
+    Status(usize),
+
+    /// Suspended marker
+    Suspended,
+    Age,
+
+    /// A kind-specific value
+    Field {
+        title: &'static str,
+        width: Width,
+        value: fn(&T, DateTime<Utc>) -> Field,
+    },
+}
+
+impl<T> Column<T> {
+    /// Header title, also used to hide and sort the column
+    pub fn title(&self) -> &'static str {
+        match self {
+            Column::Name => "NAME",
+            Column::Namespace => "NAMESPACE",
+            Column::Ready => "READY",
+            Column::Status(_) => "STATUS",
+            Column::Suspended => "SUS",
+            Column::Age => "AGE",
+            Column::Field { title, .. } => title,
+        }
+    }
+
+    pub fn width(&self) -> Width {
+        match self {
+            Column::Name => Width::Min(20),
+            Column::Namespace => Width::Min(15),
+            Column::Ready => Width::Length(7),
+            Column::Status(len) => Width::Min(*len as u16),
+            Column::Suspended => Width::Length(3),
+            Column::Age => Width::Length(4),
+            Column::Field { width, .. } => *width,
+        }
+    }
+}
+
+/// Column width, as a table layout constraint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Width {
+    Min(u16),
+    Length(u16),
+}
+
+/// A kind-specific cell
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub text: String,
+    pub emphasis: Emphasis,
+}
+
+impl Field {
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            emphasis: Emphasis::None,
+        }
+    }
+
+    /// Truncated to `max_len` characters
+    pub fn truncated(text: &str, max_len: usize) -> Self {
+        Self::plain(truncate(text, max_len))
+    }
+
+    /// An optional value, "-" when missing
+    pub fn or_dash(text: Option<&str>) -> Self {
+        Self::plain(text.unwrap_or("-"))
+    }
+
+    pub fn with(self, emphasis: Emphasis) -> Self {
+        Self { emphasis, ..self }
+    }
+}
+
+/// How a cell stands out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Emphasis {
+    None,
+
+    /// Worth a look (e.g. a stale index)
+    Attention,
+
+    /// Diverges from what Git says (e.g. an overridden ref)
+    Warning,
+}
+
+/// Truncate a string to a maximum number of characters
+pub fn truncate(s: &str, max_len: usize) -> String {
+    if s.chars().count() <= max_len {
+        s.to_string()
+    } else if max_len < 3 {
+        s.chars().take(max_len).collect()
+    } else {
+        format!("{}...", s.chars().take(max_len - 3).collect::<String>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_multibyte() {
+        assert_eq!(truncate("Größenänderung", 8), "Größe...");
+        assert_eq!(truncate("世界世界", 4), "世界世界");
+        assert_eq!(truncate("世界世界", 2), "世界");
+        assert_eq!(truncate("abcdef", 0), "");
+    }
+
+    #[test]
+    fn test_info_for() {
+        assert_eq!(
+            info_for("Kustomization").map(|info| info.api_version),
+            Some("kustomize.toolkit.fluxcd.io/v1")
+        );
+        assert_eq!(
+            info_for("HelmRelease").map(|info| info.api_version),
+            Some("helm.toolkit.fluxcd.io/v2")
+        );
+        assert_eq!(
+            info_for("HelmChart").map(|info| info.api_version),
+            Some("source.toolkit.fluxcd.io/v1")
+        );
+        assert_eq!(info_for("Bucket"), None);
+    }
+
+    #[test]
+    fn test_columns_start_with_name() {
+        fn titles<T: ResourceKind>() -> Vec<&'static str> {
+            T::columns().iter().map(Column::title).collect()
+        }
+        for titles in [
+            titles::<Kustomization>(),
+            titles::<HelmRelease>(),
+            titles::<HelmChart>(),
+            titles::<GitRepository>(),
+            titles::<HelmRepository>(),
+        ] {
+            assert_eq!(titles[0], "NAME");
+            assert!(titles.contains(&"STATUS"));
+        }
+    }
+
+    #[test]
+    fn test_parse_sets_cluster() {
+        let metadata = ObjectMeta {
+            name: Some("apps".to_string()),
+            namespace: Some("flux-system".to_string()),
+            ..Default::default()
+        };
+        let k = Kustomization::parse(&metadata, &Value::Null, &Value::Null, "prod");
+        assert_eq!(k.name, "apps");
+        assert_eq!(k.cluster, "prod");
+    }
+}
@@ -1,9 +1,21 @@
 //! Kustomization resource definition
 
-use super::{FluxResource, ResourceStatus};
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    annotations, creation_timestamp, depends_on,
+    kind::{Column, Emphasis, Field, KindInfo, ResourceKind, Width},
+    list_or_dash, observed_generation,
+    status::parse_status,
+    Condition, FluxResource, ReconcileInfo, ResourceStatus, SourceRef,
+};
 
 /// Flux Kustomization resource
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Kustomization {
     /// Resource name
     pub name: String,
@@ -11,6 +23,9 @@ pub struct Kustomization {
     /// Resource namespace
     pub namespace: String,
 
+    /// Cluster the resource was listed from (kube context or cluster name)
+    pub cluster: String,
+
     /// Current status
     pub status: ResourceStatus,
 
@@ -24,37 +39,66 @@ pub struct Kustomization {
     pub suspended: bool,
 
     /// Source reference
-    #[allow(dead_code)]
     pub source_ref: String,
 
+    /// Source reference including its namespace (None if the spec has none)
+    pub source: Option<SourceRef>,
+
     /// Path within the source
-    #[allow(dead_code)]
     pub path: String,
+
+    /// Creation timestamp
+    pub created_at: Option<DateTime<Utc>>,
+
+    /// Resources that must be ready first (namespace/name)
+    pub depends_on: Vec<String>,
+
+    /// Objects checked after applying, from spec.healthChecks (Kind/namespace/name)
+    #[serde(default)]
+    pub health_checks: Vec<String>,
+
+    /// Whether every applied object is checked (spec.wait)
+    #[serde(default)]
+    pub wait: bool,
+
+    /// Spec generation (metadata.generation)
+    pub generation: Option<i64>,
+
+    /// Generation last observed by the controller (status.observedGeneration)
+    pub observed_generation: Option<i64>,
+
+    /// Reconcile settings and status conditions
+    pub reconcile: ReconcileInfo,
+
+    /// Metadata labels
+    pub labels: BTreeMap<String, String>,
+
+    /// Metadata annotations, without kubectl's last-applied-configuration
+    pub annotations: BTreeMap<String, String>,
 }
 
 impl Kustomization {
     /// Create a new Kustomization from raw K8s data
     pub fn from_kube(
-        name: String,
-        namespace: String,
+        metadata: &ObjectMeta,
         spec: &serde_json::Value,
         status: &serde_json::Value,
     ) -> Self {
+        let name = metadata.name.clone().unwrap_or_default();
+        let namespace = metadata.namespace.clone().unwrap_or_default();
+        let created_at = creation_timestamp(metadata);
+
         let suspended = spec
             .get("suspend")
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
-        let source_ref = spec
+        let source = spec
             .get("sourceRef")
-            .map(|sr| {
-                let kind = sr
-                    .get("kind")
-                    .and_then(|k| k.as_str())
-                    .unwrap_or("GitRepository");
-                let name = sr.get("name").and_then(|n| n.as_str()).unwrap_or("unknown");
-                format!("{}/{}", kind, name)
-            })
+            .map(|sr| SourceRef::from_spec(sr, "GitRepository", &namespace));
+        let source_ref = source
+            .as_ref()
+            .map(ToString::to_string)
             .unwrap_or_else(|| "unknown".to_string());
 
         let path = spec
@@ -68,17 +112,34 @@ impl Kustomization {
             .and_then(|r| r.as_str())
             .map(truncate_revision);
 
-        let (resource_status, status_message) = parse_status(status, suspended);
+        let dependencies = depends_on(spec, &namespace);
+        let health_checks = health_checks(spec, &namespace);
+        let wait = spec.get("wait").and_then(|w| w.as_bool()).unwrap_or(false);
+
+        let (resource_status, status_message) =
+            parse_status(status, suspended, PROGRESSING_REASONS);
 
         Self {
             name,
             namespace,
+            // Set by the client, which knows which cluster it talks to
+            cluster: String::new(),
             status: resource_status,
             status_message,
             revision,
             suspended,
             source_ref,
+            source,
             path,
+            created_at,
+            depends_on: dependencies,
+            health_checks,
+            wait,
+            generation: metadata.generation,
+            observed_generation: observed_generation(status),
+            reconcile: ReconcileInfo::from_kube(spec, status),
+            labels: metadata.labels.clone().unwrap_or_default(),
+            annotations: annotations(metadata),
         }
     }
 }
@@ -92,6 +153,10 @@ impl FluxResource for Kustomization {
         &self.namespace
     }
 
+    fn cluster(&self) -> &str {
+        &self.cluster
+    }
+
     fn kind(&self) -> &str {
         "Kustomization"
     }
@@ -111,52 +176,130 @@ impl FluxResource for Kustomization {
     fn revision(&self) -> Option<&str> {
         self.revision.as_deref()
     }
-}
 
-/// Parse the status conditions to determine resource status
-fn parse_status(status: &serde_json::Value, suspended: bool) -> (ResourceStatus, String) {
-    if suspended {
-        return (ResourceStatus::Suspended, "Suspended".to_string());
-    }
-
-    let conditions = status.get("conditions").and_then(|c| c.as_array());
-
-    if let Some(conditions) = conditions {
-        // Look for Ready condition
-        for condition in conditions {
-            let condition_type = condition.get("type").and_then(|t| t.as_str());
-            let condition_status = condition.get("status").and_then(|s| s.as_str());
-            let message = condition
-                .get("message")
-                .and_then(|m| m.as_str())
-                .unwrap_or("Unknown")
-                .to_string();
-
-            if condition_type == Some("Ready") {
-                match condition_status {
-                    Some("True") => return (ResourceStatus::Ready, message),
-                    Some("False") => {
-                        let reason = condition.get("reason").and_then(|r| r.as_str());
-                        if reason == Some("Progressing") {
-                            return (ResourceStatus::Reconciling, message);
-                        }
-                        return (ResourceStatus::Failed, message);
-                    }
-                    Some("Unknown") => return (ResourceStatus::Reconciling, message),
-                    _ => {}
-                }
-            }
+    fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    fn source(&self) -> Option<&SourceRef> {
+        self.source.as_ref()
+    }
+
+    fn source_path(&self) -> Option<&str> {
+        Some(&self.path)
+    }
+
+    fn generation(&self) -> Option<i64> {
+        self.generation
+    }
+
+    fn observed_generation(&self) -> Option<i64> {
+        self.observed_generation
+    }
 
-            // Check for Reconciling condition
-            if condition_type == Some("Reconciling") && condition_status == Some("True") {
-                return (ResourceStatus::Reconciling, message);
+    fn reconcile_info(&self) -> &ReconcileInfo {
+        &self.reconcile
+    }
+
+    fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
+
+    fn annotations(&self) -> &BTreeMap<String, String> {
+        &self.annotations
+    }
+
+    fn details(&self) -> Vec<(&'static str, String)> {
+        let checks = if self.wait {
+            "Every applied object (wait)".to_string()
+        } else {
+            list_or_dash(&self.health_checks)
+        };
+        let health = match (self.health(), self.healthy_condition()) {
+            (Health::Unchecked, _) => "-".to_string(),
+            (health, Some(condition)) if !condition.message.is_empty() => {
+                format!("{}: {}", health.label(), condition.message)
             }
+            (health, _) => health.label().to_string(),
+        };
+        vec![
+            ("Source", self.source_ref.clone()),
+            ("Path", self.path.clone()),
+            ("Depends on", list_or_dash(&self.depends_on)),
+            ("Health checks", checks),
+            ("Health", health),
+        ]
+    }
+}
+
+/// Outcome of a Kustomization's health checks, apart from whether it applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Health {
+    /// Nothing is health checked
+    Unchecked,
+    /// Checks are configured but haven't reported, or are still running
+    Pending,
+    Healthy,
+    /// Applied, but the checked objects didn't become ready
+    Unhealthy,
+}
+
+impl Health {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Health::Unchecked => "-",
+            Health::Pending => "checking",
+            Health::Healthy => "healthy",
+            Health::Unhealthy => "unhealthy",
         }
     }
+}
+
+impl Kustomization {
+    /// The Healthy condition kustomize-controller reports for health checks
+    pub fn healthy_condition(&self) -> Option<&Condition> {
+        self.reconcile
+            .conditions
+            .iter()
+            .find(|c| c.condition_type == "Healthy")
+    }
 
-    (ResourceStatus::Unknown, "Status unknown".to_string())
+    /// Health check outcome, from the Healthy condition
+    pub fn health(&self) -> Health {
+        match self.healthy_condition().map(|c| c.status.as_str()) {
+            Some("True") => Health::Healthy,
+            Some("False") => Health::Unhealthy,
+            Some(_) => Health::Pending,
+            None if self.wait || !self.health_checks.is_empty() => Health::Pending,
+            None => Health::Unchecked,
+        }
+    }
 }
 
+/// Objects listed in spec.healthChecks as Kind/namespace/name
+fn health_checks(spec: &serde_json::Value, namespace: &str) -> Vec<String> {
+    spec.get("healthChecks")
+        .and_then(|c| c.as_array())
+        .map(|checks| {
+            checks
+                .iter()
+                .filter_map(|check| {
+                    let kind = check.get("kind").and_then(|k| k.as_str())?;
+                    let name = check.get("name").and_then(|n| n.as_str())?;
+                    let namespace = check
+                        .get("namespace")
+                        .and_then(|n| n.as_str())
+                        .unwrap_or(namespace);
+                    Some(format!("{kind}/{namespace}/{name}"))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Ready=False reasons kustomize-controller sets while it is still applying
+const PROGRESSING_REASONS: &[&str] = &["Progressing"];
+
 /// Truncate git revision to a readable format
 fn truncate_revision(revision: &str) -> String {
     if revision.contains('@') {
@@ -172,9 +315,68 @@ fn truncate_revision(revision: &str) -> String {
     revision.chars().take(12).collect()
 }
 
+impl ResourceKind for Kustomization {
+    const INFO: KindInfo = KindInfo {
+        api_version: "kustomize.toolkit.fluxcd.io/v1",
+        kind: "Kustomization",
+        suspendable: true,
+        is_source: false,
+        consumes_sources: true,
+    };
+
+    fn parse(
+        metadata: &ObjectMeta,
+        spec: &serde_json::Value,
+        status: &serde_json::Value,
+        cluster: &str,
+    ) -> Self {
+        Self {
+            cluster: cluster.to_string(),
+            ..Self::from_kube(metadata, spec, status)
+        }
+    }
+
+    fn columns() -> Vec<Column<Self>> {
+        vec![
+            Column::Name,
+            Column::Namespace,
+            Column::Ready,
+            Column::Status(30),
+            Column::Field {
+                title: "HEALTH",
+                width: Width::Length(9),
+                value: |k, _| match k.health() {
+                    Health::Unhealthy => {
+                        Field::plain(Health::Unhealthy.label()).with(Emphasis::Warning)
+                    }
+                    health => Field::plain(health.label()),
+                },
+            },
+            Column::Field {
+                title: "SOURCE",
+                width: Width::Min(20),
+                value: |k, _| Field::truncated(&k.source_ref, 30),
+            },
+            Column::Field {
+                title: "PATH",
+                width: Width::Min(15),
+                value: |k, _| Field::truncated(&k.path, 25),
+            },
+            Column::Field {
+                title: "REVISION",
+                width: Width::Min(15),
+                value: |k, _| Field::truncated(k.revision.as_deref().unwrap_or("-"), 15),
+            },
+            Column::Suspended,
+            Column::Age,
+        ]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::kubernetes::resources::meta;
     use serde_json::json;
 
     #[test]
@@ -201,47 +403,6 @@ mod tests {
         assert_eq!(truncate_revision(revision), "short");
     }
 
-    #[test]
-    fn test_parse_status_suspended() {
-        let status = json!({});
-        let (resource_status, message) = parse_status(&status, true);
-        assert_eq!(resource_status, ResourceStatus::Suspended);
-        assert_eq!(message, "Suspended");
-    }
-
-    #[test]
-    fn test_parse_status_ready() {
-        let status = json!({
-            "conditions": [
-                {
-                    "type": "Ready",
-                    "status": "True",
-                    "message": "Applied revision: main@abc1234"
-                }
-            ]
-        });
-        let (resource_status, message) = parse_status(&status, false);
-        assert_eq!(resource_status, ResourceStatus::Ready);
-        assert_eq!(message, "Applied revision: main@abc1234");
-    }
-
-    #[test]
-    fn test_parse_status_failed() {
-        let status = json!({
-            "conditions": [
-                {
-                    "type": "Ready",
-                    "status": "False",
-                    "reason": "ReconciliationFailed",
-                    "message": "kustomization error"
-                }
-            ]
-        });
-        let (resource_status, message) = parse_status(&status, false);
-        assert_eq!(resource_status, ResourceStatus::Failed);
-        assert_eq!(message, "kustomization error");
-    }
-
     #[test]
     fn test_parse_status_progressing() {
         let status = json!({
@@ -254,91 +415,140 @@ mod tests {
                 }
             ]
         });
-        let (resource_status, message) = parse_status(&status, false);
+        let (resource_status, message) = parse_status(&status, false, PROGRESSING_REASONS);
         assert_eq!(resource_status, ResourceStatus::Reconciling);
         assert_eq!(message, "Reconciliation in progress");
     }
 
     #[test]
-    fn test_parse_status_unknown_ready_status() {
-        let status = json!({
-            "conditions": [
-                {
-                    "type": "Ready",
-                    "status": "Unknown",
-                    "message": "Waiting for reconciliation"
-                }
-            ]
+    fn test_kustomization_from_kube_basic() {
+        let spec = json!({
+            "sourceRef": {
+                "kind": "GitRepository",
+                "name": "my-repo"
+            },
+            "path": "./clusters/production"
         });
-        let (resource_status, message) = parse_status(&status, false);
-        assert_eq!(resource_status, ResourceStatus::Reconciling);
-        assert_eq!(message, "Waiting for reconciliation");
-    }
-
-    #[test]
-    fn test_parse_status_reconciling_condition() {
         let status = json!({
+            "lastAppliedRevision": "main@abc1234567890",
             "conditions": [
                 {
-                    "type": "Reconciling",
+                    "type": "Ready",
                     "status": "True",
-                    "message": "Reconciling"
+                    "message": "Applied"
                 }
             ]
         });
-        let (resource_status, message) = parse_status(&status, false);
-        assert_eq!(resource_status, ResourceStatus::Reconciling);
-        assert_eq!(message, "Reconciling");
+
+        let k = Kustomization::from_kube(&meta("my-app", "flux-system"), &spec, &status);
+
+        assert_eq!(k.name, "my-app");
+        assert_eq!(k.namespace, "flux-system");
+        assert_eq!(k.status, ResourceStatus::Ready);
+        assert_eq!(k.revision, Some("main@abc1234".to_string()));
+        assert!(!k.suspended);
     }
 
     #[test]
-    fn test_parse_status_no_conditions() {
-        let status = json!({});
-        let (resource_status, message) = parse_status(&status, false);
-        assert_eq!(resource_status, ResourceStatus::Unknown);
-        assert_eq!(message, "Status unknown");
+    fn test_kustomization_from_kube_generation() {
+        let metadata = ObjectMeta {
+            generation: Some(7),
+            ..meta("apps", "flux-system")
+        };
+        let status = json!({"observedGeneration": 6});
+
+        let k = Kustomization::from_kube(&metadata, &json!({}), &status);
+
+        assert_eq!(k.generation, Some(7));
+        assert_eq!(k.observed_generation, Some(6));
+        assert!(k.has_unobserved_generation());
     }
 
     #[test]
-    fn test_parse_status_empty_conditions() {
-        let status = json!({"conditions": []});
-        let (resource_status, message) = parse_status(&status, false);
-        assert_eq!(resource_status, ResourceStatus::Unknown);
-        assert_eq!(message, "Status unknown");
+    fn test_kustomization_from_kube_labels() {
+        let metadata = ObjectMeta {
+            labels: Some(BTreeMap::from([(
+                "team".to_string(),
+                "payments".to_string(),
+            )])),
+            annotations: Some(BTreeMap::from([
+                (
+                    "kustomize.toolkit.fluxcd.io/substitute".to_string(),
+                    "disabled".to_string(),
+                ),
+                (
+                    "kubectl.kubernetes.io/last-applied-configuration".to_string(),
+                    "{}".to_string(),
+                ),
+            ])),
+            ..meta("apps", "flux-system")
+        };
+
+        let k = Kustomization::from_kube(&metadata, &json!({}), &json!({}));
+
+        assert_eq!(k.labels["team"], "payments");
+        assert_eq!(k.labels().len(), 1);
+        // The last applied configuration is dropped
+        assert_eq!(
+            k.annotations().keys().collect::<Vec<_>>(),
+            vec!["kustomize.toolkit.fluxcd.io/substitute"]
+        );
     }
 
     #[test]
-    fn test_kustomization_from_kube_basic() {
+    fn test_kustomization_from_kube_health_checks() {
         let spec = json!({
-            "sourceRef": {
-                "kind": "GitRepository",
-                "name": "my-repo"
-            },
-            "path": "./clusters/production"
+            "healthChecks": [
+                {"apiVersion": "apps/v1", "kind": "Deployment", "name": "podinfo", "namespace": "apps"},
+                {"apiVersion": "v1", "kind": "Service", "name": "podinfo"}
+            ]
         });
         let status = json!({
-            "lastAppliedRevision": "main@abc1234567890",
             "conditions": [
                 {
                     "type": "Ready",
-                    "status": "True",
-                    "message": "Applied"
+                    "status": "False",
+                    "reason": "HealthCheckFailed",
+                    "message": "health check failed after 5m0s"
+                },
+                {
+                    "type": "Healthy",
+                    "status": "False",
+                    "reason": "HealthCheckFailed",
+                    "message": "timeout waiting for: [Deployment/apps/podinfo status: 'InProgress']"
                 }
             ]
         });
 
-        let k = Kustomization::from_kube(
-            "my-app".to_string(),
-            "flux-system".to_string(),
-            &spec,
-            &status,
+        let k = Kustomization::from_kube(&meta("apps", "flux-system"), &spec, &status);
+
+        assert_eq!(
+            k.health_checks,
+            vec!["Deployment/apps/podinfo", "Service/flux-system/podinfo"]
+        );
+        assert_eq!(k.health(), Health::Unhealthy);
+        let details = k.details();
+        assert_eq!(
+            details[4],
+            (
+                "Health",
+                "unhealthy: timeout waiting for: [Deployment/apps/podinfo status: 'InProgress']"
+                    .to_string()
+            )
         );
+    }
 
-        assert_eq!(k.name, "my-app");
-        assert_eq!(k.namespace, "flux-system");
-        assert_eq!(k.status, ResourceStatus::Ready);
-        assert_eq!(k.revision, Some("main@abc1234".to_string()));
-        assert!(!k.suspended);
+    #[test]
+    fn test_health_without_a_condition() {
+        let mut k = Kustomization::default();
+        assert_eq!(k.health(), Health::Unchecked);
+        assert_eq!(k.details()[3], ("Health checks", "-".to_string()));
+        k.wait = true;
+        assert_eq!(k.health(), Health::Pending);
+        assert_eq!(
+            k.details()[3],
+            ("Health checks", "Every applied object (wait)".to_string())
+        );
     }
 
     #[test]
@@ -352,12 +562,7 @@ mod tests {
         });
         let status = json!({});
 
-        let k = Kustomization::from_kube(
-            "suspended-app".to_string(),
-            "default".to_string(),
-            &spec,
-            &status,
-        );
+        let k = Kustomization::from_kube(&meta("suspended-app", "default"), &spec, &status);
 
         assert!(k.suspended);
         assert_eq!(k.status, ResourceStatus::Suspended);
@@ -368,8 +573,7 @@ mod tests {
         let spec = json!({});
         let status = json!({});
 
-        let k =
-            Kustomization::from_kube("minimal".to_string(), "default".to_string(), &spec, &status);
+        let k = Kustomization::from_kube(&meta("minimal", "default"), &spec, &status);
 
         assert_eq!(k.source_ref, "unknown");
         assert_eq!(k.path, "./");
@@ -388,6 +592,7 @@ mod tests {
             suspended: false,
             source_ref: "GitRepository/test".to_string(),
             path: "./".to_string(),
+            ..Default::default()
         };
 
         assert_eq!(k.name(), "test");
@@ -1,17 +1,28 @@
 //! Flux CD resource definitions
 
+mod gitrepository;
 mod helmchart;
 mod helmrelease;
+mod helmrepository;
+pub mod kind;
 mod kustomization;
+mod status;
 
+pub use gitrepository::{GitRef, GitRepository, RefOverride, ORIGINAL_REF_ANNOTATION};
 pub use helmchart::HelmChart;
 pub use helmrelease::HelmRelease;
+pub use helmrepository::HelmRepository;
+pub use kind::{KindInfo, ResourceKind};
 pub use kustomization::Kustomization;
 
-use std::fmt::Debug;
+use std::{collections::BTreeMap, fmt::Debug};
+
+use chrono::{DateTime, Duration, Utc};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use serde::{Deserialize, Serialize};
 
 /// Status of a Flux resource
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum ResourceStatus {
     /// Resource is ready and reconciled
     Ready,
@@ -19,12 +30,182 @@ pub enum ResourceStatus {
     Failed,
     /// Resource is currently reconciling
     Reconciling,
+    /// Resource reconciliation is stalled and will not be retried until the spec changes
+    Stalled,
     /// Resource is suspended
     Suspended,
+    /// Resource is suspended and its last reconcile failed or stalled
+    SuspendedFailed,
+    /// Resource was just created and has no status yet
+    Pending,
     /// Status is unknown
+    #[default]
     Unknown,
 }
 
+/// Status condition as reported by the controller
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Condition {
+    /// Condition type (Ready, Reconciling, Stalled, ...)
+    pub condition_type: String,
+
+    /// Condition status (True, False, Unknown)
+    pub status: String,
+
+    /// Machine-readable reason
+    pub reason: Option<String>,
+
+    /// Human-readable message (may span several lines)
+    pub message: String,
+
+    /// When the condition last changed
+    pub last_transition: Option<DateTime<Utc>>,
+}
+
+/// Reconcile settings and controller status shared by all Flux kinds
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ReconcileInfo {
+    /// Reconcile interval (spec.interval)
+    pub interval: Option<String>,
+
+    /// Reconcile timeout (spec.timeout)
+    pub timeout: Option<String>,
+
+    /// Last manual reconcile request handled (status.lastHandledReconcileAt)
+    pub last_handled_reconcile_at: Option<String>,
+
+    /// All status conditions, in the order reported
+    pub conditions: Vec<Condition>,
+}
+
+impl ReconcileInfo {
+    /// Extract reconcile settings and conditions from raw K8s data
+    pub fn from_kube(spec: &serde_json::Value, status: &serde_json::Value) -> Self {
+        let string = |value: &serde_json::Value, key: &str| {
+            value.get(key).and_then(|v| v.as_str()).map(String::from)
+        };
+
+        let conditions = status
+            .get("conditions")
+            .and_then(|c| c.as_array())
+            .map(|conditions| {
+                conditions
+                    .iter()
+                    .map(|c| Condition {
+                        condition_type: string(c, "type").unwrap_or_default(),
+                        status: string(c, "status").unwrap_or_default(),
+                        reason: string(c, "reason"),
+                        message: string(c, "message").unwrap_or_default(),
+                        last_transition: string(c, "lastTransitionTime")
+                            .and_then(|t| DateTime::parse_from_rfc3339(&t).ok())
+                            .map(|t| t.with_timezone(&Utc)),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            interval: string(spec, "interval"),
+            timeout: string(spec, "timeout"),
+            last_handled_reconcile_at: string(status, "lastHandledReconcileAt"),
+            conditions,
+        }
+    }
+}
+
+/// Parse `spec.dependsOn` as namespace/name pairs (namespace defaults to the dependent's)
+fn depends_on(spec: &serde_json::Value, namespace: &str) -> Vec<String> {
+    spec.get("dependsOn")
+        .and_then(|d| d.as_array())
+        .map(|deps| {
+            deps.iter()
+                .filter_map(|dep| {
+                    let name = dep.get("name").and_then(|n| n.as_str())?;
+                    let namespace = dep
+                        .get("namespace")
+                        .and_then(|n| n.as_str())
+                        .unwrap_or(namespace);
+                    Some(format!("{namespace}/{name}"))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Join values one per line for the details popup, or "-" when empty
+fn list_or_dash(values: &[String]) -> String {
+    if values.is_empty() {
+        "-".to_string()
+    } else {
+        values.join("\n")
+    }
+}
+
+/// Identifies a resource across refreshes (and sessions)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ResourceKey {
+    pub cluster: String,
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+}
+
+impl ResourceKey {
+    /// Key of a resource
+    pub fn of(resource: &dyn FluxResource) -> Self {
+        Self {
+            cluster: resource.cluster().to_string(),
+            kind: resource.kind().to_string(),
+            namespace: resource.namespace().to_string(),
+            name: resource.name().to_string(),
+        }
+    }
+}
+
+/// Reference from a Kustomization or HelmRelease to the source it consumes
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct SourceRef {
+    /// Source kind (GitRepository, HelmRepository, OCIRepository, ...)
+    pub kind: String,
+
+    /// Source name
+    pub name: String,
+
+    /// Source namespace (defaults to the consumer's namespace)
+    pub namespace: String,
+}
+
+impl SourceRef {
+    /// Parse a `sourceRef`/`chartRef` object, filling in the defaults Flux applies
+    pub fn from_spec(source_ref: &serde_json::Value, default_kind: &str, namespace: &str) -> Self {
+        let field = |key: &str, default: &str| {
+            source_ref
+                .get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or(default)
+                .to_string()
+        };
+        Self {
+            kind: field("kind", default_kind),
+            name: field("name", "unknown"),
+            namespace: field("namespace", namespace),
+        }
+    }
+
+    /// Check whether this reference points at the given resource
+    pub fn refers_to(&self, resource: &dyn FluxResource) -> bool {
+        self.kind == resource.kind()
+            && self.name == resource.name()
+            && self.namespace == resource.namespace()
+    }
+}
+
+impl std::fmt::Display for SourceRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.kind, self.name)
+    }
+}
+
 /// Trait for Flux resources
 pub trait FluxResource: Debug + Send + Sync {
     /// Get the resource name
@@ -33,7 +214,10 @@ pub trait FluxResource: Debug + Send + Sync {
     /// Get the resource namespace
     fn namespace(&self) -> &str;
 
-    /// Get the resource kind (Kustomization, HelmRelease, HelmChart)
+    /// Get the cluster the resource was listed from
+    fn cluster(&self) -> &str;
+
+    /// Get the resource kind (Kustomization, HelmRelease, HelmChart, GitRepository, HelmRepository)
     fn kind(&self) -> &str;
 
     /// Get the current status
@@ -52,6 +236,138 @@ pub trait FluxResource: Debug + Send + Sync {
 
     /// Get the current revision (if available)
     fn revision(&self) -> Option<&str>;
+
+    /// Get the creation timestamp (if available)
+    fn created_at(&self) -> Option<DateTime<Utc>>;
+
+    /// Get the spec generation (if available)
+    fn generation(&self) -> Option<i64>;
+
+    /// Get the generation last observed by the controller (if available)
+    fn observed_generation(&self) -> Option<i64>;
+
+    /// Check whether the controller has not yet observed the latest spec
+    ///
+    /// Suspended resources are expected to lag behind and are not flagged.
+    fn has_unobserved_generation(&self) -> bool {
+        !self.is_suspended()
+            && matches!(
+                (self.generation(), self.observed_generation()),
+                (Some(generation), Some(observed)) if observed < generation
+            )
+    }
+
+    /// Get reconcile settings and status conditions
+    fn reconcile_info(&self) -> &ReconcileInfo;
+
+    /// Get the source this resource consumes (Kustomizations and HelmReleases)
+    fn source(&self) -> Option<&SourceRef> {
+        None
+    }
+
+    /// Get the directory or file consumed within the source: a
+    /// Kustomization's path, or the chart path of a HelmRelease built from Git
+    fn source_path(&self) -> Option<&str> {
+        None
+    }
+
+    /// Get the metadata labels
+    fn labels(&self) -> &BTreeMap<String, String>;
+
+    /// Get the metadata annotations
+    fn annotations(&self) -> &BTreeMap<String, String>;
+
+    /// Fields matched by the `/` fuzzy filter
+    fn search_fields(&self) -> Vec<&str> {
+        vec![self.name(), self.namespace()]
+    }
+
+    /// Kind-specific fields shown in the details popup, as (label, value) pairs
+    fn details(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+}
+
+/// Annotation kubectl stores the whole applied object in; too long to show
+const LAST_APPLIED_ANNOTATION: &str = "kubectl.kubernetes.io/last-applied-configuration";
+
+/// Extract the metadata annotations worth showing
+fn annotations(metadata: &ObjectMeta) -> BTreeMap<String, String> {
+    let mut annotations = metadata.annotations.clone().unwrap_or_default();
+    annotations.remove(LAST_APPLIED_ANNOTATION);
+    annotations
+}
+
+/// Extract the creation timestamp from object metadata
+fn creation_timestamp(metadata: &ObjectMeta) -> Option<DateTime<Utc>> {
+    metadata.creation_timestamp.as_ref().map(|t| t.0)
+}
+
+/// Extract `status.observedGeneration`
+fn observed_generation(status: &serde_json::Value) -> Option<i64> {
+    status.get("observedGeneration").and_then(|g| g.as_i64())
+}
+
+/// Format the time elapsed between `since` and `now` as a short age (e.g. 45s, 12m, 3h, 2d)
+pub fn format_age(since: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    format_duration(now - since)
+}
+
+/// Format a duration in the same short form as ages (e.g. 45s, 12m, 3h, 2d)
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.num_seconds().max(0);
+    match secs {
+        s if s < 120 => format!("{s}s"),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86400 => format!("{}h", s / 3600),
+        s => format!("{}d", s / 86400),
+    }
+}
+
+/// Parse a Go duration string as used by Flux intervals (e.g. "10m", "1h30m", "1m0s")
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let s = s.trim();
+    if s.is_empty() {
+        return None;
+    }
+
+    let mut total_ms = 0.0;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let value: f64 = rest[..number_len].parse().ok()?;
+        rest = &rest[number_len..];
+
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let unit_ms = match &rest[..unit_len] {
+            "ns" => 1e-6,
+            "us" | "µs" => 1e-3,
+            "ms" => 1.0,
+            "s" => 1_000.0,
+            "m" => 60_000.0,
+            "h" => 3_600_000.0,
+            _ => return None,
+        };
+        rest = &rest[unit_len..];
+
+        total_ms += value * unit_ms;
+    }
+
+    Some(Duration::milliseconds(total_ms as i64))
+}
+
+/// Metadata with just a name and namespace, for parsing tests
+#[cfg(test)]
+pub(super) fn meta(name: &str, namespace: &str) -> ObjectMeta {
+    ObjectMeta {
+        name: Some(name.to_string()),
+        namespace: Some(namespace.to_string()),
+        ..Default::default()
+    }
 }
 
 impl Clone for Box<dyn FluxResource> {
@@ -71,7 +387,9 @@ mod tests {
         assert_eq!(format!("{:?}", ResourceStatus::Ready), "Ready");
         assert_eq!(format!("{:?}", ResourceStatus::Failed), "Failed");
         assert_eq!(format!("{:?}", ResourceStatus::Reconciling), "Reconciling");
+        assert_eq!(format!("{:?}", ResourceStatus::Stalled), "Stalled");
         assert_eq!(format!("{:?}", ResourceStatus::Suspended), "Suspended");
+        assert_eq!(format!("{:?}", ResourceStatus::Pending), "Pending");
         assert_eq!(format!("{:?}", ResourceStatus::Unknown), "Unknown");
     }
 
@@ -88,6 +406,153 @@ mod tests {
         assert_ne!(ResourceStatus::Ready, ResourceStatus::Failed);
     }
 
+    #[test]
+    fn test_source_ref_from_spec() {
+        let source = SourceRef::from_spec(
+            &serde_json::json!({"kind": "GitRepository", "name": "infra", "namespace": "flux-system"}),
+            "HelmRepository",
+            "apps",
+        );
+        assert_eq!(source.kind, "GitRepository");
+        assert_eq!(source.namespace, "flux-system");
+        assert_eq!(source.to_string(), "GitRepository/infra");
+
+        let source = SourceRef::from_spec(
+            &serde_json::json!({"name": "bitnami"}),
+            "HelmRepository",
+            "apps",
+        );
+        assert_eq!(source.kind, "HelmRepository");
+        assert_eq!(source.namespace, "apps");
+    }
+
+    #[test]
+    fn test_source_ref_refers_to() {
+        let repo = GitRepository {
+            name: "infra".to_string(),
+            namespace: "flux-system".to_string(),
+            ..Default::default()
+        };
+        let source = |namespace: &str| SourceRef {
+            kind: "GitRepository".to_string(),
+            name: "infra".to_string(),
+            namespace: namespace.to_string(),
+        };
+        assert!(source("flux-system").refers_to(&repo));
+        assert!(!source("apps").refers_to(&repo));
+    }
+
+    #[test]
+    fn test_reconcile_info_from_kube() {
+        let spec = serde_json::json!({"interval": "10m", "timeout": "5m"});
+        let status = serde_json::json!({
+            "lastHandledReconcileAt": "2024-05-01T10:00:00Z",
+            "conditions": [
+                {
+                    "type": "Ready",
+                    "status": "False",
+                    "reason": "BuildFailed",
+                    "message": "kustomize build failed:\nline 2",
+                    "lastTransitionTime": "2024-05-01T10:01:00Z"
+                },
+                {"type": "Reconciling", "status": "True"}
+            ]
+        });
+
+        let info = ReconcileInfo::from_kube(&spec, &status);
+
+        assert_eq!(info.interval.as_deref(), Some("10m"));
+        assert_eq!(info.timeout.as_deref(), Some("5m"));
+        assert_eq!(
+            info.last_handled_reconcile_at.as_deref(),
+            Some("2024-05-01T10:00:00Z")
+        );
+        assert_eq!(info.conditions.len(), 2);
+        assert_eq!(info.conditions[0].reason.as_deref(), Some("BuildFailed"));
+        assert_eq!(
+            info.conditions[0].message,
+            "kustomize build failed:\nline 2"
+        );
+        assert!(info.conditions[0].last_transition.is_some());
+        assert_eq!(info.conditions[1].message, "");
+        assert!(info.conditions[1].last_transition.is_none());
+    }
+
+    #[test]
+    fn test_reconcile_info_empty() {
+        let info = ReconcileInfo::from_kube(&serde_json::json!({}), &serde_json::Value::Null);
+        assert_eq!(info, ReconcileInfo::default());
+    }
+
+    #[test]
+    fn test_depends_on() {
+        let spec = serde_json::json!({
+            "dependsOn": [{"name": "infra"}, {"name": "crds", "namespace": "flux-system"}]
+        });
+        assert_eq!(
+            depends_on(&spec, "apps"),
+            vec!["apps/infra", "flux-system/crds"]
+        );
+        assert!(depends_on(&serde_json::json!({}), "apps").is_empty());
+    }
+
+    #[test]
+    fn test_observed_generation() {
+        let status = serde_json::json!({"observedGeneration": 3});
+        assert_eq!(observed_generation(&status), Some(3));
+        assert_eq!(observed_generation(&serde_json::json!({})), None);
+    }
+
+    #[test]
+    fn test_has_unobserved_generation() {
+        let k = |generation, observed_generation| Kustomization {
+            generation,
+            observed_generation,
+            ..Default::default()
+        };
+        assert!(k(Some(4), Some(3)).has_unobserved_generation());
+        assert!(!k(Some(4), Some(4)).has_unobserved_generation());
+        // No status yet is reported as Pending, not as a mismatch
+        assert!(!k(Some(1), None).has_unobserved_generation());
+        assert!(!k(None, None).has_unobserved_generation());
+
+        let suspended = Kustomization {
+            suspended: true,
+            ..k(Some(4), Some(3))
+        };
+        assert!(!suspended.has_unobserved_generation());
+    }
+
+    #[test]
+    fn test_format_age() {
+        let now = Utc::now();
+        assert_eq!(format_age(now, now), "0s");
+        assert_eq!(format_age(now - chrono::Duration::seconds(45), now), "45s");
+        assert_eq!(format_age(now - chrono::Duration::minutes(12), now), "12m");
+        assert_eq!(format_age(now - chrono::Duration::hours(5), now), "5h");
+        assert_eq!(format_age(now - chrono::Duration::days(3), now), "3d");
+        // Clock skew should never produce a negative age
+        assert_eq!(format_age(now + chrono::Duration::seconds(10), now), "0s");
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("10m"), Some(Duration::minutes(10)));
+        assert_eq!(parse_duration("1m0s"), Some(Duration::minutes(1)));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("500ms"), Some(Duration::milliseconds(500)));
+        assert_eq!(parse_duration("1.5h"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("10"), None);
+        assert_eq!(parse_duration("10x"), None);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::minutes(10)), "10m");
+        assert_eq!(format_duration(Duration::hours(24)), "1d");
+    }
+
     #[test]
     fn test_is_ready_default_impl() {
         let k = Kustomization {
@@ -99,6 +564,7 @@ mod tests {
             suspended: false,
             source_ref: "".to_string(),
             path: "".to_string(),
+            ..Default::default()
         };
         assert!(k.is_ready());
 
@@ -111,6 +577,7 @@ mod tests {
             suspended: false,
             source_ref: "".to_string(),
             path: "".to_string(),
+            ..Default::default()
         };
         assert!(!k_failed.is_ready());
     }
@@ -121,7 +588,10 @@ mod tests {
             (ResourceStatus::Ready, true),
             (ResourceStatus::Failed, false),
             (ResourceStatus::Reconciling, false),
+            (ResourceStatus::Stalled, false),
             (ResourceStatus::Suspended, false),
+            (ResourceStatus::SuspendedFailed, false),
+            (ResourceStatus::Pending, false),
             (ResourceStatus::Unknown, false),
         ];
 
@@ -135,6 +605,7 @@ mod tests {
                 suspended: false,
                 source_ref: "".to_string(),
                 path: "".to_string(),
+                ..Default::default()
             };
             assert_eq!(k.is_ready(), expected);
         }
@@ -151,6 +622,7 @@ mod tests {
             suspended: true,
             source_ref: "GitRepository/flux-system".to_string(),
             path: "./clusters".to_string(),
+            ..Default::default()
         };
 
         assert_eq!(k.name(), "my-ks");
@@ -160,7 +632,7 @@ mod tests {
         assert_eq!(k.status_message(), "Applied");
         assert!(k.is_ready());
         assert!(k.is_suspended());
-        assert_eq!(k.revision(), Some("main@sha256:abc123".as_ref()));
+        assert_eq!(k.revision(), Some("main@sha256:abc123"));
     }
 
     #[test]
@@ -174,6 +646,7 @@ mod tests {
             version: Some("1.2.3".to_string()),
             suspended: false,
             revision: Some("5".to_string()),
+            ..Default::default()
         };
 
         assert_eq!(hr.name(), "my-release");
@@ -183,7 +656,7 @@ mod tests {
         assert_eq!(hr.status_message(), "upgrade failed");
         assert!(!hr.is_ready());
         assert!(!hr.is_suspended());
-        assert_eq!(hr.revision(), Some("5".as_ref()));
+        assert_eq!(hr.revision(), Some("5"));
     }
 
     #[test]
@@ -197,6 +670,7 @@ mod tests {
             version: Some("15.0.0".to_string()),
             source_ref: "HelmRepository/bitnami".to_string(),
             revision: None,
+            ..Default::default()
         };
 
         assert_eq!(hc.name(), "my-chart");
@@ -0,0 +1,358 @@
+//! Status parsing shared by all Flux kinds
+//!
+//! Every Flux controller reports through the same kstatus conditions (Ready,
+//! Reconciling, Stalled), so one interpretation serves all kinds. The only
+//! difference between them is which Ready=False reasons a controller sets while
+//! it is still working.
+//!
+//! The fixtures in `tests/fixtures/status` are status subresources as written by
+//! the controllers, old API versions included; each is checked against the
+//! status it is expected to parse to.
+
+use super::ResourceStatus;
+
+/// Determine a resource's status and message from its status subresource
+///
+/// `progressing` lists the Ready=False reasons the kind's controller sets
+/// while it is still working, shown as Reconciling instead of Failed.
+pub(super) fn parse_status(
+    status: &serde_json::Value,
+    suspended: bool,
+    progressing: &[&str],
+) -> (ResourceStatus, String) {
+    if suspended {
+        return suspended_status(parse_status(status, false, progressing));
+    }
+
+    if is_status_missing(status) {
+        return (
+            ResourceStatus::Pending,
+            "Pending (no status yet)".to_string(),
+        );
+    }
+
+    let conditions = status.get("conditions").and_then(|c| c.as_array());
+
+    if let Some(conditions) = conditions {
+        if let Some(message) = stalled_message(conditions) {
+            return (ResourceStatus::Stalled, message);
+        }
+
+        for condition in conditions {
+            let condition_type = condition.get("type").and_then(|t| t.as_str());
+            let condition_status = condition.get("status").and_then(|s| s.as_str());
+            let message = condition
+                .get("message")
+                .and_then(|m| m.as_str())
+                .unwrap_or("Unknown")
+                .to_string();
+
+            if condition_type == Some("Ready") {
+                match condition_status {
+                    Some("True") => return (ResourceStatus::Ready, message),
+                    Some("False") => {
+                        let reason = condition.get("reason").and_then(|r| r.as_str());
+                        if reason.is_some_and(|reason| progressing.contains(&reason)) {
+                            return (ResourceStatus::Reconciling, message);
+                        }
+                        return (ResourceStatus::Failed, message);
+                    }
+                    Some("Unknown") => return (ResourceStatus::Reconciling, message),
+                    _ => {}
+                }
+            }
+
+            if condition_type == Some("Reconciling") && condition_status == Some("True") {
+                return (ResourceStatus::Reconciling, message);
+            }
+        }
+    }
+
+    (ResourceStatus::Unknown, "Status unknown".to_string())
+}
+
+/// Check whether the status subresource has not been written yet
+///
+/// Controllers populate status on their first reconcile, so a freshly
+/// created object has no status (or an empty one) for a short while.
+fn is_status_missing(status: &serde_json::Value) -> bool {
+    match status {
+        serde_json::Value::Null => true,
+        serde_json::Value::Object(map) => map.is_empty(),
+        _ => false,
+    }
+}
+
+/// Find a `Stalled=True` condition and return its message
+///
+/// A stalled resource will not be retried by its controller until the spec
+/// changes, so this takes precedence over the Ready condition.
+fn stalled_message(conditions: &[serde_json::Value]) -> Option<String> {
+    conditions
+        .iter()
+        .find(|c| {
+            c.get("type").and_then(|t| t.as_str()) == Some("Stalled")
+                && c.get("status").and_then(|s| s.as_str()) == Some("True")
+        })
+        .map(|c| {
+            c.get("message")
+                .and_then(|m| m.as_str())
+                .unwrap_or("Stalled")
+                .to_string()
+        })
+}
+
+/// Status of a suspended resource, given the status it would have otherwise
+///
+/// Suspending keeps the last conditions, so a failure from before the suspend
+/// is kept visible instead of hiding behind it.
+fn suspended_status((status, message): (ResourceStatus, String)) -> (ResourceStatus, String) {
+    match status {
+        ResourceStatus::Failed | ResourceStatus::Stalled => (
+            ResourceStatus::SuspendedFailed,
+            format!("Suspended (was Failed): {message}"),
+        ),
+        _ => (ResourceStatus::Suspended, "Suspended".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::kubernetes::resources::{
+        FluxResource, GitRepository, HelmChart, HelmRelease, HelmRepository, Kustomization,
+    };
+
+    /// A fixture: a Flux object as read from the cluster, and what it parses to
+    #[derive(Deserialize)]
+    struct Fixture {
+        kind: String,
+        metadata: ObjectMeta,
+        #[serde(default)]
+        spec: serde_json::Value,
+        #[serde(default)]
+        status: serde_json::Value,
+        expected: Expected,
+    }
+
+    #[derive(Deserialize)]
+    struct Expected {
+        status: ResourceStatus,
+        message: String,
+    }
+
+    impl Fixture {
+        fn parse(&self) -> Box<dyn FluxResource> {
+            let (metadata, spec, status) = (&self.metadata, &self.spec, &self.status);
+            match self.kind.as_str() {
+                "Kustomization" => Box::new(Kustomization::from_kube(metadata, spec, status)),
+                "HelmRelease" => Box::new(HelmRelease::from_kube(metadata, spec, status)),
+                "HelmChart" => Box::new(HelmChart::from_kube(metadata, spec, status)),
+                "GitRepository" => Box::new(GitRepository::from_kube(metadata, spec, status)),
+                "HelmRepository" => Box::new(HelmRepository::from_kube(metadata, spec, status)),
+                kind => panic!("Unsupported kind {kind}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_fixtures() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("status");
+        let mut checked = 0;
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let contents = std::fs::read_to_string(&path).unwrap();
+            let fixture: Fixture = serde_json::from_str(&contents)
+                .unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+            let resource = fixture.parse();
+            assert_eq!(
+                (resource.status(), resource.status_message()),
+                (&fixture.expected.status, fixture.expected.message.as_str()),
+                "{}",
+                path.display()
+            );
+            checked += 1;
+        }
+        assert!(checked >= 20, "only {checked} fixtures found");
+    }
+
+    #[test]
+    fn test_parse_status_ready() {
+        let status = serde_json::json!({
+            "conditions": [{
+                "type": "Ready",
+                "status": "True",
+                "message": "Applied revision: main@abc1234"
+            }]
+        });
+        assert_eq!(
+            parse_status(&status, false, &[]),
+            (
+                ResourceStatus::Ready,
+                "Applied revision: main@abc1234".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_status_failed() {
+        let status = serde_json::json!({
+            "conditions": [{
+                "type": "Ready",
+                "status": "False",
+                "reason": "ReconciliationFailed",
+                "message": "kustomization error"
+            }]
+        });
+        assert_eq!(
+            parse_status(&status, false, &[]),
+            (ResourceStatus::Failed, "kustomization error".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_status_unknown() {
+        let status = serde_json::json!({
+            "conditions": [{
+                "type": "Ready",
+                "status": "Unknown",
+                "message": "Waiting for reconciliation"
+            }]
+        });
+        assert_eq!(
+            parse_status(&status, false, &[]),
+            (
+                ResourceStatus::Reconciling,
+                "Waiting for reconciliation".to_string()
+            )
+        );
+
+        let empty = serde_json::json!({"conditions": []});
+        assert_eq!(
+            parse_status(&empty, false, &[]),
+            (ResourceStatus::Unknown, "Status unknown".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_status_reconciling_condition() {
+        let status = serde_json::json!({
+            "conditions": [{
+                "type": "Reconciling",
+                "status": "True",
+                "message": "Reconciling"
+            }]
+        });
+        assert_eq!(
+            parse_status(&status, false, &[]),
+            (ResourceStatus::Reconciling, "Reconciling".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_status_stalled() {
+        let status = serde_json::json!({
+            "conditions": [
+                {
+                    "type": "Ready",
+                    "status": "False",
+                    "reason": "ReconciliationFailed",
+                    "message": "reconciliation failed"
+                },
+                {
+                    "type": "Stalled",
+                    "status": "True",
+                    "reason": "InvalidSpec",
+                    "message": "spec is invalid"
+                }
+            ]
+        });
+        assert_eq!(
+            parse_status(&status, false, &[]),
+            (ResourceStatus::Stalled, "spec is invalid".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_status_no_status_is_pending() {
+        let pending = (
+            ResourceStatus::Pending,
+            "Pending (no status yet)".to_string(),
+        );
+        assert_eq!(parse_status(&serde_json::json!({}), false, &[]), pending);
+        assert_eq!(parse_status(&serde_json::Value::Null, false, &[]), pending);
+    }
+
+    #[test]
+    fn test_parse_status_suspended() {
+        assert_eq!(
+            parse_status(&serde_json::json!({}), true, &[]),
+            (ResourceStatus::Suspended, "Suspended".to_string())
+        );
+
+        let failed = serde_json::json!({
+            "conditions": [{
+                "type": "Ready",
+                "status": "False",
+                "reason": "BuildFailed",
+                "message": "kustomize build failed"
+            }]
+        });
+        assert_eq!(
+            parse_status(&failed, true, &[]),
+            (
+                ResourceStatus::SuspendedFailed,
+                "Suspended (was Failed): kustomize build failed".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_progressing_reasons() {
+        let status = serde_json::json!({
+            "conditions": [{
+                "type": "Ready",
+                "status": "False",
+                "reason": "Progressing",
+                "message": "Reconciliation in progress"
+            }]
+        });
+        assert_eq!(
+            parse_status(&status, false, &["Progressing"]).0,
+            ResourceStatus::Reconciling
+        );
+        assert_eq!(parse_status(&status, false, &[]).0, ResourceStatus::Failed);
+    }
+
+    #[test]
+    fn test_stalled_message() {
+        let conditions = serde_json::json!([
+            {"type": "Ready", "status": "False", "message": "build failed"},
+            {"type": "Stalled", "status": "True", "message": "invalid path"}
+        ]);
+        assert_eq!(
+            stalled_message(conditions.as_array().unwrap()),
+            Some("invalid path".to_string())
+        );
+
+        let not_stalled = serde_json::json!([
+            {"type": "Stalled", "status": "False", "message": "ok"}
+        ]);
+        assert_eq!(stalled_message(not_stalled.as_array().unwrap()), None);
+    }
+
+    #[test]
+    fn test_is_status_missing() {
+        assert!(is_status_missing(&serde_json::Value::Null));
+        assert!(is_status_missing(&serde_json::json!({})));
+        assert!(!is_status_missing(&serde_json::json!({"conditions": []})));
+    }
+}
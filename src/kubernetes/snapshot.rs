@@ -0,0 +1,301 @@
+//! A cluster read from a snapshot file (`--snapshot`)
+//!
+//! Serves the resources saved in a snapshot, so a past state can be browsed
+//! with the regular UI. Nothing can be changed, and what the snapshot doesn't
+//! hold (Events, Helm history, artifacts, metrics) isn't available.
+
+use std::sync::Arc;
+
+use color_eyre::{eyre::eyre, Result};
+use futures::future::BoxFuture;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::api::FluxApi;
+use super::client::{new_api_log, ApiLog, ClusterSnapshot, KubeEvent};
+use super::helm::Revision;
+use super::metrics::ReconcilerMetrics;
+use super::portforward::PortForward;
+use super::preflight;
+use super::resources::{
+    FluxResource, GitRepository, HelmChart, HelmRelease, HelmRepository, KindInfo, Kustomization,
+    ReconcileInfo, ResourceKind,
+};
+
+/// The saved resources of one cluster
+pub struct SnapshotClient {
+    cluster_name: String,
+    context: Option<String>,
+    snapshot: ClusterSnapshot,
+    api_log: ApiLog,
+}
+
+impl SnapshotClient {
+    /// A cluster holding these resources, named like the client they were
+    /// listed with so their cluster labels match
+    pub fn new(cluster_name: String, context: Option<String>, snapshot: ClusterSnapshot) -> Self {
+        Self {
+            cluster_name,
+            context,
+            snapshot,
+            api_log: new_api_log(10),
+        }
+    }
+
+    /// A saved resource
+    fn find(&self, kind: &str, namespace: &str, name: &str) -> Option<&dyn FluxResource> {
+        fn find<'a, T: FluxResource>(
+            resources: &'a [T],
+            namespace: &str,
+            name: &str,
+        ) -> Option<&'a dyn FluxResource> {
+            resources
+                .iter()
+                .find(|r| r.namespace() == namespace && r.name() == name)
+                .map(|r| r as &dyn FluxResource)
+        }
+        let snapshot = &self.snapshot;
+        match kind {
+            k if k == Kustomization::INFO.kind => find(&snapshot.kustomizations, namespace, name),
+            k if k == HelmRelease::INFO.kind => find(&snapshot.helm_releases, namespace, name),
+            k if k == HelmChart::INFO.kind => find(&snapshot.helm_charts, namespace, name),
+            k if k == GitRepository::INFO.kind => find(&snapshot.git_repositories, namespace, name),
+            k if k == HelmRepository::INFO.kind => {
+                find(&snapshot.helm_repositories, namespace, name)
+            }
+            _ => None,
+        }
+    }
+
+    /// The parsed fields of a saved resource as YAML; the manifest itself
+    /// isn't saved
+    fn saved_yaml(&self, kind: &str, namespace: &str, name: &str) -> Result<String> {
+        fn yaml<T: FluxResource + Serialize>(
+            resources: &[T],
+            namespace: &str,
+            name: &str,
+        ) -> Option<Result<String>> {
+            resources
+                .iter()
+                .find(|r| r.namespace() == namespace && r.name() == name)
+                .map(|r| super::yaml::to_string(r))
+        }
+        let snapshot = &self.snapshot;
+        let yaml = match kind {
+            k if k == Kustomization::INFO.kind => yaml(&snapshot.kustomizations, namespace, name),
+            k if k == HelmRelease::INFO.kind => yaml(&snapshot.helm_releases, namespace, name),
+            k if k == HelmChart::INFO.kind => yaml(&snapshot.helm_charts, namespace, name),
+            k if k == GitRepository::INFO.kind => yaml(&snapshot.git_repositories, namespace, name),
+            k if k == HelmRepository::INFO.kind => {
+                yaml(&snapshot.helm_repositories, namespace, name)
+            }
+            _ => None,
+        };
+        let yaml = yaml.ok_or_else(|| not_saved(kind, namespace, name))??;
+        Ok(format!(
+            "# Fields saved in the snapshot, not the full manifest\n{yaml}"
+        ))
+    }
+
+    fn unavailable<T>(what: &str) -> BoxFuture<'static, Result<T>> {
+        let error = eyre!("{what} isn't available in a snapshot");
+        Box::pin(async move { Err(error) })
+    }
+}
+
+fn not_saved(kind: &str, namespace: &str, name: &str) -> color_eyre::Report {
+    eyre!("{kind} {namespace}/{name} isn't in the snapshot")
+}
+
+impl FluxApi for SnapshotClient {
+    fn cluster_name(&self) -> &str {
+        &self.cluster_name
+    }
+
+    fn context(&self) -> Option<&str> {
+        self.context.as_deref()
+    }
+
+    fn api_log(&self) -> ApiLog {
+        Arc::clone(&self.api_log)
+    }
+
+    fn fetch_all<'a>(
+        &'a self,
+        namespace: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<ClusterSnapshot>> {
+        let snapshot = match namespace {
+            Some(namespace) => self.snapshot.in_namespace(namespace),
+            None => self.snapshot.clone(),
+        };
+        Box::pin(async move { Ok(snapshot) })
+    }
+
+    fn fetch_namespace<'a>(&'a self, namespace: &'a str) -> BoxFuture<'a, Result<ClusterSnapshot>> {
+        let snapshot = ClusterSnapshot {
+            namespaces: vec![namespace.to_string()],
+            ..self.snapshot.in_namespace(namespace)
+        };
+        Box::pin(async move { Ok(snapshot) })
+    }
+
+    fn get_manifest<'a>(
+        &'a self,
+        kind: &'a str,
+        namespace: &'a str,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<String>> {
+        let yaml = self.saved_yaml(kind, namespace, name);
+        Box::pin(async move { yaml })
+    }
+
+    fn get_reconcile_info<'a>(
+        &'a self,
+        kind: &'a str,
+        namespace: &'a str,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<ReconcileInfo>> {
+        let info = self
+            .find(kind, namespace, name)
+            .map(|resource| resource.reconcile_info().clone())
+            .ok_or_else(|| not_saved(kind, namespace, name));
+        Box::pin(async move { info })
+    }
+
+    fn patch_resource<'a>(
+        &'a self,
+        _kind: &'a str,
+        _namespace: &'a str,
+        _name: &'a str,
+        _patch: &'a Value,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async { Err(eyre!("A snapshot can't be changed")) })
+    }
+
+    fn helm_history<'a>(
+        &'a self,
+        _namespace: &'a str,
+        _release: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Revision>>> {
+        Self::unavailable("Helm history")
+    }
+
+    fn artifact_forward<'a>(&'a self, _url: &'a str) -> BoxFuture<'a, Result<Option<PortForward>>> {
+        Self::unavailable("The source artifact")
+    }
+
+    fn download_artifact<'a>(
+        &'a self,
+        _url: &'a str,
+        _forward: Option<&'a PortForward>,
+    ) -> BoxFuture<'a, Result<Vec<u8>>> {
+        Self::unavailable("The source artifact")
+    }
+
+    fn server_version(&self) -> BoxFuture<'_, Result<String>> {
+        Self::unavailable("The API server")
+    }
+
+    fn crd_versions<'a>(
+        &'a self,
+        _info: &'a KindInfo,
+    ) -> BoxFuture<'a, Result<Option<Vec<String>>>> {
+        Self::unavailable("The CRDs")
+    }
+
+    fn flux_deployments(&self) -> BoxFuture<'_, Result<Vec<preflight::Deployment>>> {
+        Self::unavailable("The Flux controllers")
+    }
+
+    fn metrics_forward<'a>(&'a self, _controller: &'a str) -> BoxFuture<'a, Result<PortForward>> {
+        Self::unavailable("Controller metrics")
+    }
+
+    fn scrape_metrics<'a>(
+        &'a self,
+        _forward: &'a PortForward,
+    ) -> BoxFuture<'a, Result<Vec<ReconcilerMetrics>>> {
+        Self::unavailable("Controller metrics")
+    }
+
+    fn list_events_for<'a>(
+        &'a self,
+        _kind: &'a str,
+        _namespace: &'a str,
+        _name: &'a str,
+        _limit: usize,
+    ) -> BoxFuture<'a, Result<Vec<KubeEvent>>> {
+        Self::unavailable("Events")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client() -> SnapshotClient {
+        SnapshotClient::new(
+            "prod".to_string(),
+            None,
+            ClusterSnapshot {
+                kustomizations: vec![
+                    Kustomization {
+                        name: "apps".to_string(),
+                        namespace: "flux-system".to_string(),
+                        ..Default::default()
+                    },
+                    Kustomization {
+                        name: "apps".to_string(),
+                        namespace: "team-a".to_string(),
+                        ..Default::default()
+                    },
+                ],
+                namespaces: vec!["flux-system".to_string(), "team-a".to_string()],
+                ..Default::default()
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_fetch_filters_by_namespace() {
+        let client = client();
+        assert_eq!(
+            client.fetch_all(None).await.unwrap().kustomizations.len(),
+            2
+        );
+        let snapshot = client.fetch_namespace("team-a").await.unwrap();
+        assert_eq!(snapshot.kustomizations.len(), 1);
+        assert_eq!(snapshot.namespaces, vec!["team-a"]);
+    }
+
+    #[tokio::test]
+    async fn test_manifest_from_saved_fields() {
+        let client = client();
+        let yaml = client
+            .get_manifest("Kustomization", "team-a", "apps")
+            .await
+            .unwrap();
+        assert!(yaml.starts_with("# Fields saved in the snapshot"));
+        assert!(yaml.contains("namespace: team-a"));
+
+        let error = client
+            .get_manifest("HelmRelease", "team-a", "apps")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "HelmRelease team-a/apps isn't in the snapshot"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_is_read_only() {
+        let client = client();
+        assert!(client
+            .patch_resource("Kustomization", "team-a", "apps", &Value::Null)
+            .await
+            .is_err());
+        let error = client.server_version().await.unwrap_err();
+        assert!(error.to_string().contains("isn't available in a snapshot"));
+    }
+}
@@ -0,0 +1,286 @@
+//! YAML rendering of manifests and resources
+//!
+//! Values go through `serde_json` and are written as block-style YAML, the
+//! way `kubectl get -o yaml` prints them. Multi-line strings (patches, Helm
+//! values, annotations holding manifests) are written as `|` block literals.
+//! Strings a YAML parser would read as something else (numbers, booleans,
+//! timestamps, or text starting with an indicator) are written
+//! double-quoted, with JSON escapes, which YAML accepts.
+
+use std::fmt::Write;
+
+use color_eyre::Result;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Render a value as a YAML document
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+    let mut out = String::new();
+    write_block(&mut out, &serde_json::to_value(value)?, 0);
+    Ok(out)
+}
+
+/// Write a value starting on its own line, indented by `indent`
+fn write_block(out: &mut String, value: &Value, indent: usize) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                let _ = write!(out, "{pad}{}:", key_string(key));
+                write_nested(out, value, indent + 2, indent);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for item in items {
+                let _ = write!(out, "{pad}-");
+                match item {
+                    Value::Object(map) if !map.is_empty() => write_item(out, item, indent),
+                    Value::Array(items) if !items.is_empty() => write_item(out, item, indent),
+                    _ => {
+                        let _ = writeln!(out, " {}", scalar(item, indent + 2));
+                    }
+                }
+            }
+        }
+        _ => {
+            let _ = writeln!(out, "{pad}{}", scalar(value, indent + 2));
+        }
+    }
+}
+
+/// Write the value of a mapping key, after its colon
+///
+/// Mappings are indented under the key; sequences stay at the key's
+/// indentation, like kubectl.
+fn write_nested(out: &mut String, value: &Value, map_indent: usize, list_indent: usize) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            out.push('\n');
+            write_block(out, value, map_indent);
+        }
+        Value::Array(items) if !items.is_empty() => {
+            out.push('\n');
+            write_block(out, value, list_indent);
+        }
+        _ => {
+            let _ = writeln!(out, " {}", scalar(value, map_indent));
+        }
+    }
+}
+
+/// Write a collection inside a sequence item, its first line after the `-`
+fn write_item(out: &mut String, item: &Value, indent: usize) {
+    let mut nested = String::new();
+    write_block(&mut nested, item, indent + 2);
+    out.push(' ');
+    out.push_str(&nested[indent + 2..]);
+}
+
+/// A scalar, or the flow form of an empty collection
+///
+/// Block literals put their lines at `indent`, without the last newline.
+fn scalar(value: &Value, indent: usize) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) if is_literal(s) => literal(s, indent),
+        Value::String(s) => key_string(s),
+        Value::Array(_) => "[]".to_string(),
+        Value::Object(_) => "{}".to_string(),
+    }
+}
+
+/// A single-line string, quoted unless it reads back as the same plain
+/// string
+fn key_string(s: &str) -> String {
+    if is_plain(s) {
+        s.to_string()
+    } else {
+        serde_json::to_string(s).unwrap_or_default()
+    }
+}
+
+/// Whether a string can be written unquoted
+fn is_plain(s: &str) -> bool {
+    const INDICATORS: &[char] = &[
+        '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%', '@',
+        '`',
+    ];
+    let Some(first) = s.chars().next() else {
+        return false;
+    };
+    !INDICATORS.contains(&first)
+        && s.trim() == s
+        && !s.chars().any(char::is_control)
+        && !s.contains(": ")
+        && !s.contains(" #")
+        && !s.ends_with(':')
+        && !is_other_type(s)
+}
+
+/// Whether YAML reads a plain scalar as a boolean, null, number or timestamp
+fn is_other_type(s: &str) -> bool {
+    const WORDS: &[&str] = &[
+        "true", "false", "yes", "no", "on", "off", "y", "n", "null", "~", ".inf", "-.inf", "+.inf",
+        ".nan",
+    ];
+    let lower = s.to_lowercase();
+    let unsigned = lower.trim_start_matches(['-', '+']);
+    let digits =
+        |s: &str, radix: u32| !s.is_empty() && s.chars().all(|c| c == '_' || c.is_digit(radix));
+    let bytes = s.as_bytes();
+    WORDS.contains(&lower.as_str())
+        || unsigned.replace('_', "").parse::<f64>().is_ok()
+        || unsigned.strip_prefix("0x").is_some_and(|s| digits(s, 16))
+        || unsigned.strip_prefix("0o").is_some_and(|s| digits(s, 8))
+        || unsigned.strip_prefix("0b").is_some_and(|s| digits(s, 2))
+        // Sexagesimal numbers, e.g. 1:30
+        || (unsigned.contains(':') && unsigned.split(':').all(|part| digits(part, 10)))
+        // Dates and timestamps, e.g. 2024-01-01T00:00:00Z
+        || (bytes.len() >= 5 && bytes[..4].iter().all(u8::is_ascii_digit) && bytes[4] == b'-')
+}
+
+/// Whether a string is written as a `|` block literal
+///
+/// Literals can't hold other control characters, and a first line starting
+/// with whitespace would need an explicit indentation.
+fn is_literal(s: &str) -> bool {
+    s.contains('\n')
+        && !s.starts_with([' ', '\n'])
+        && !s.chars().any(|c| c.is_control() && c != '\n' && c != '\t')
+}
+
+/// A `|` block literal, with `-` when the string doesn't end with a newline
+/// and `+` when it ends with several
+fn literal(s: &str, indent: usize) -> String {
+    let (chomping, body) = match s.strip_suffix('\n') {
+        None => ("-", s),
+        Some(body) if body.ends_with('\n') => ("+", body),
+        Some(body) => ("", body),
+    };
+    let pad = " ".repeat(indent);
+    let mut out = format!("|{chomping}");
+    for line in body.split('\n') {
+        out.push('\n');
+        if !line.is_empty() {
+            out.push_str(&pad);
+            out.push_str(line);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_to_string() {
+        let value = json!({
+            "apiVersion": "kustomize.toolkit.fluxcd.io/v1",
+            "metadata": {"name": "apps", "labels": {}},
+            "spec": {
+                "interval": "10m",
+                "prune": true,
+                "retries": 3,
+                "dependsOn": [{"name": "infra"}],
+                "components": ["a", "b"],
+                "patches": [],
+            },
+        });
+        assert_eq!(
+            to_string(&value).unwrap(),
+            "\
+apiVersion: kustomize.toolkit.fluxcd.io/v1
+metadata:
+  labels: {}
+  name: apps
+spec:
+  components:
+  - a
+  - b
+  dependsOn:
+  - name: infra
+  interval: 10m
+  patches: []
+  prune: true
+  retries: 3
+"
+        );
+    }
+
+    #[test]
+    fn test_nested_sequences() {
+        let value = json!([[1, 2], {"a": {"b": null}, "c": "d"}]);
+        assert_eq!(
+            to_string(&value).unwrap(),
+            "- - 1\n  - 2\n- a:\n    b: null\n  c: d\n"
+        );
+    }
+
+    #[test]
+    fn test_ambiguous_strings_are_quoted() {
+        for s in [
+            "",
+            "true",
+            "No",
+            "~",
+            "3",
+            "-1_000",
+            "0x1F",
+            "1.5e3",
+            ".inf",
+            "1:30",
+            "2024-01-01T00:00:00Z",
+            "a: b",
+            "a #b",
+            "- x",
+            "*ref",
+            " padded",
+            "tab\there",
+        ] {
+            assert!(!is_plain(s), "{s:?}");
+        }
+        for s in [
+            "main",
+            "10m",
+            "1.2.3",
+            "main@sha1:abc",
+            "ghcr.io/fluxcd/flux:v2",
+            "Reconciliation succeeded",
+            "a#b",
+        ] {
+            assert!(is_plain(s), "{s:?}");
+        }
+    }
+
+    #[test]
+    fn test_multi_line_strings_are_literals() {
+        let value = json!({
+            "patches": [{"patch": "- op: remove\n  path: /spec/replicas\n"}],
+            "values": "replicas: 2\n\nimage:\n  tag: v1",
+            "kept": "a\n\n",
+            "spaced": " indented\nsecond",
+        });
+        assert_eq!(
+            to_string(&value).unwrap(),
+            "\
+kept: |+
+  a
+
+patches:
+- patch: |
+    - op: remove
+      path: /spec/replicas
+spaced: \" indented\\nsecond\"
+values: |-
+  replicas: 2
+
+  image:
+    tag: v1
+"
+        );
+    }
+}
@@ -8,38 +8,243 @@ mod event;
 mod kubernetes;
 mod ui;
 
-use std::io;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use app::{actions::Action, state::App};
-use clap::Parser;
-use color_eyre::Result;
+use app::{
+    actions::Action,
+    config::Config,
+    daemon, diagnostics, export, get,
+    script::{Flow, Script},
+    snapshot::Snapshot,
+    state::{App, Reauth},
+    ticker, version_check,
+};
+use clap::{Parser, Subcommand};
+use color_eyre::{eyre::eyre, Result};
 use crossterm::{
     event::{poll, read, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use ratatui::prelude::*;
+use kubernetes::kubeconfig_watch::KubeconfigWatch;
+use ratatui::{prelude::*, TerminalOptions, Viewport};
+use tokio::task::JoinHandle;
+use ui::{notify::Notifier, pane_title::PaneTitle};
 
 /// Tick rate for the event loop (controls refresh rate)
 const TICK_RATE: Duration = Duration::from_millis(250);
 
+/// Faster tick while a refresh is in flight, to animate the header spinner
+const SPINNER_TICK_RATE: Duration = Duration::from_millis(100);
+
+/// Most input events handled between two redraws
+const MAX_EVENTS_PER_FRAME: usize = 32;
+
 /// Data refresh interval
 const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
 
+/// Fewest rows the inline viewport may have, enough for the header, a few
+/// table rows and the status bar
+const MIN_INLINE_HEIGHT: u16 = 10;
+
+/// Screen size a script draws when stdout isn't a terminal
+const HEADLESS_SIZE: (u16, u16) = (120, 40);
+
 /// A minimalist TUI for managing Flux CD resources
 #[derive(Parser)]
 #[command(name = "flux-tui")]
 #[command(version, about, long_about = None)]
-struct Args {}
+struct Args {
+    /// Kubeconfig context to use (defaults to the current context)
+    #[arg(long, global = true)]
+    context: Option<String>,
+
+    /// Show several contexts at once, comma-separated (multi-cluster mode)
+    #[arg(long, value_delimiter = ',', conflicts_with = "context")]
+    contexts: Vec<String>,
+
+    /// Only show resources in this namespace (defaults to all namespaces)
+    #[arg(short, long, global = true)]
+    namespace: Option<String>,
+
+    /// Skip the startup check for newer flux-tui releases
+    #[arg(long)]
+    no_update_check: bool,
+
+    /// Read resources from a running `flux-tui daemon` instead of the cluster
+    #[arg(long)]
+    attach: bool,
+
+    /// Only look: refuse reconciles, suspends, rollbacks and commands
+    #[arg(long)]
+    read_only: bool,
+
+    /// Color theme: dark, light or the path of a theme file
+    #[arg(long)]
+    theme: Option<String>,
+
+    /// Draw without colors (also enabled by a non-empty NO_COLOR)
+    #[arg(long)]
+    no_color: bool,
+
+    /// Draw in this many rows below the shell prompt instead of full screen,
+    /// keeping the scrollback visible
+    #[arg(long, value_name = "ROWS", value_parser = clap::value_parser!(u16).range(MIN_INLINE_HEIGHT as i64..))]
+    inline_height: Option<u16>,
+
+    /// Run reconcile and suspend requests with the flux CLI or the Kubernetes API
+    #[arg(long, value_enum)]
+    reconcile_backend: Option<kubernetes::reconcile::Backend>,
+
+    /// Load a named profile from the config file (CLI flags still win)
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Print a one-line status summary whenever it changes instead of
+    /// starting the TUI (for a tmux status line or a narrow pane)
+    #[arg(long)]
+    ticker: bool,
+
+    /// Show the onboarding tour again
+    #[arg(long)]
+    tour: bool,
+
+    /// Start on the tab of this kind (e.g. helmreleases, hr)
+    #[arg(long, value_enum, value_name = "KIND")]
+    tab: Option<get::Kind>,
+
+    /// Read commands from stdin instead of the keyboard, e.g. `ns
+    /// flux-system`, `reconcile ks apps`, `wait ready`, `quit` (for demos and
+    /// smoke tests)
+    #[arg(long)]
+    script: bool,
+
+    /// Show a snapshot file (saved with `S` or `export --output snapshot`)
+    /// instead of connecting to a cluster; nothing can be changed
+    #[arg(long, global = true, value_name = "FILE")]
+    snapshot: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Subcommands besides the TUI itself
+#[derive(Subcommand)]
+enum Command {
+    /// Keep refreshing in the background so `--attach` keeps history across runs
+    Daemon {
+        /// Keep a JSON status summary in this file for desktop bars (waybar,
+        /// polybar), rewritten when it changes
+        #[arg(long)]
+        tray_file: Option<PathBuf>,
+    },
+
+    /// List resources once and write their status without starting the TUI
+    Export {
+        /// Output format
+        #[arg(long, value_enum, default_value = "prometheus")]
+        output: export::Format,
+
+        /// Write to this file (replaced atomically) instead of stdout
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+
+    /// Print resources of one kind and exit
+    Get {
+        /// Kind to list (e.g. kustomizations, ks, helmreleases, hr)
+        #[arg(value_enum)]
+        kind: get::Kind,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        output: get::Output,
+    },
+}
+
+impl Args {
+    /// Reject modes that always connect to the live cluster, rather than
+    /// silently ignoring `--snapshot`
+    fn check_snapshot(&self) -> Result<()> {
+        let live = match &self.command {
+            Some(Command::Daemon { .. }) => "daemon",
+            Some(Command::Get { .. }) => "get",
+            None if self.ticker => "--ticker",
+            Some(Command::Export { .. }) | None => return Ok(()),
+        };
+        if self.snapshot.is_some() {
+            return Err(eyre!(
+                "--snapshot can't be used with `{live}`, which reads the live cluster"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Apply CLI arguments on top of the configuration file
+    fn merge_into(self, mut config: Config) -> Config {
+        if self.context.is_some() {
+            // An explicit single context overrides multi-cluster mode from the file
+            config.context = self.context;
+            config.contexts.clear();
+        }
+        if !self.contexts.is_empty() {
+            config.contexts = self.contexts;
+        }
+        if self.namespace.is_some() {
+            config.namespace = self.namespace;
+        }
+        if self.no_update_check {
+            config.update_check = false;
+        }
+        if self.attach {
+            config.attach = true;
+        }
+        if self.read_only {
+            config.read_only = true;
+        }
+        if self.theme.is_some() {
+            config.theme = self.theme;
+        }
+        if self.no_color {
+            config.no_color = true;
+        }
+        if self.inline_height.is_some() {
+            config.inline_height = self.inline_height;
+        }
+        if let Some(backend) = self.reconcile_backend {
+            config.reconcile_backend = backend;
+        }
+        config
+    }
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Parse CLI arguments (handles --version and --help automatically)
-    Args::parse();
+    let mut args = Args::parse();
+    args.check_snapshot()?;
 
     // Initialize error handling
-    color_eyre::install()?;
+    install_hooks()?;
+
+    // Load the config file, with CLI arguments taking precedence
+    let command = args.command.take();
+    let ticker = args.ticker;
+    let tour = args.tour;
+    let tab = args.tab;
+    let script = args.script;
+    let snapshot = args.snapshot.take();
+    let mut config = Config::load()?;
+    if let Some(profile) = args.profile.take() {
+        config.apply_profile(&profile)?;
+    }
+    let mut config = args.merge_into(config);
+    if snapshot.is_some() {
+        config.read_only = true;
+        config.attach = false;
+    }
 
     // Initialize logging
     tracing_subscriber::fmt()
@@ -50,63 +255,486 @@ async fn main() -> Result<()> {
         .with_target(false)
         .init();
 
-    // Setup terminal
-    enable_raw_mode()?;
+    match command {
+        Some(Command::Daemon { tray_file }) => {
+            if tray_file.is_some() {
+                config.tray_file = tray_file;
+            }
+            return daemon::run(&config).await;
+        }
+        Some(Command::Export { output, file }) => {
+            let app = open_app(&config, snapshot.as_deref()).await?;
+            // Don't overwrite the last good export with empty counts
+            if let Some(error) = &app.last_error {
+                return Err(eyre!("{error}"));
+            }
+            return export::write(&export::render(&app, output)?, file.as_deref());
+        }
+        Some(Command::Get { kind, output }) => {
+            return export::write(&get::run(&config, kind, output).await?, None);
+        }
+        None if ticker => return ticker::run(&config).await,
+        None => {}
+    }
+
+    // Create app before touching the terminal so connection errors
+    // (e.g. an unknown --context) print cleanly
+    let mut app = open_app(&config, snapshot.as_deref()).await?;
+    if snapshot.is_none() {
+        app.run_preflight(false);
+        app.fetch_flux_version(false);
+    }
+    if let Some(kind) = tab {
+        app.tab = kind.tab();
+    }
+    if !script && (tour || !app.state.tour_done) {
+        app.start_tour();
+    }
+    let kubeconfig_watch = match snapshot {
+        Some(_) => KubeconfigWatch::none(),
+        None => KubeconfigWatch::new(config.kubeconfig.as_deref()),
+    };
+    let pane_title = PaneTitle::detect();
+    let notifier = Notifier::new(&config.notifications);
+
+    // Check for a newer release in the background (opt-in via config)
+    let update_check = config
+        .update_check
+        .then(|| tokio::spawn(version_check::newer_release()));
+
+    // Setup terminal. Inline mode draws below the prompt and leaves the
+    // alternate screen alone, so the scrollback stays visible above. A
+    // script takes the place of the keyboard: the terminal isn't switched to
+    // raw mode, so Ctrl+C still interrupts it, and with stdout redirected
+    // (e.g. in CI) a fixed-size screen is drawn into the redirect.
+    let headless = script && !io::stdout().is_terminal();
+    let fullscreen = !headless && config.inline_height.is_none();
+    if !script {
+        enable_raw_mode()?;
+    }
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    let viewport = match config.inline_height {
+        _ if headless => {
+            let (width, height) = HEADLESS_SIZE;
+            Viewport::Fixed(Rect::new(0, 0, width, height))
+        }
+        Some(height) => Viewport::Inline(height.max(MIN_INLINE_HEIGHT)),
+        None => {
+            execute!(stdout, EnterAlternateScreen)?;
+            Viewport::Fullscreen
+        }
+    };
+    if !script {
+        execute!(stdout, EnableMouseCapture)?;
+    }
     let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-
-    // Create app and run
-    let app = App::new().await?;
-    let res = run_app(&mut terminal, app).await;
-
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
+    let mut terminal = Terminal::with_options(backend, TerminalOptions { viewport })?;
+
+    // Run the app
+    let res = run_app(
+        &mut terminal,
+        app,
+        update_check,
+        kubeconfig_watch,
+        pane_title,
+        notifier,
+        script.then(Script::stdin),
+    )
+    .await;
+
+    // Restore terminal. An inline viewport is cleared, so the shell prompt
+    // continues where it started.
+    if !script {
+        disable_raw_mode()?;
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
+    if fullscreen {
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    } else if !headless {
+        terminal.clear()?;
+    }
     terminal.show_cursor()?;
 
-    // Handle any errors from the app
+    // Handle any errors from the app; a failed script fails the smoke test
+    // running it
     if let Err(err) = res {
         eprintln!("Error: {err:?}");
+        if script {
+            std::process::exit(1);
+        }
     }
 
     Ok(())
 }
 
-/// Main application loop implementing The Elm Architecture
-async fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<()> {
-    let mut last_refresh = std::time::Instant::now();
+/// The app on the configured clusters, or on the clusters of a snapshot file
+async fn open_app(config: &Config, snapshot: Option<&Path>) -> Result<App> {
+    match snapshot {
+        Some(path) => App::from_snapshot(config, Snapshot::load(path)?).await,
+        None => App::new(config).await,
+    }
+}
+
+/// Install error and panic hooks
+///
+/// On panic the terminal is restored first so the report is readable, and a
+/// diagnostic bundle is written for attaching to a bug report.
+fn install_hooks() -> Result<()> {
+    let (panic_hook, eyre_hook) = color_eyre::config::HookBuilder::default().into_hooks();
+    eyre_hook.install()?;
 
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
+
+        eprintln!("{}", panic_hook.panic_report(info));
+        match diagnostics::write_report(&diagnostics::panic_report(&info.to_string())) {
+            Ok(path) => eprintln!("Diagnostic report written to {}", path.display()),
+            Err(e) => eprintln!("Failed to write diagnostic report: {e}"),
+        }
+    }));
+
+    Ok(())
+}
+
+/// Main application loop implementing The Elm Architecture
+async fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    mut app: App,
+    mut update_check: Option<JoinHandle<Option<String>>>,
+    mut kubeconfig_watch: KubeconfigWatch,
+    mut pane_title: PaneTitle,
+    notifier: Notifier,
+    mut script: Option<Script>,
+) -> Result<()> {
     loop {
-        // Draw the UI
+        // Pick up the update check result once it completes
+        if let Some(handle) = update_check.take_if(|h| h.is_finished()) {
+            app.latest_version = handle.await.ok().flatten();
+        }
+
+        // Keep the selection on screen, then draw the UI. The viewport is
+        // the whole screen, or the bottom rows in inline mode.
+        let content = ui::layout::MainLayout::new(terminal.get_frame().area()).content;
+        app.scroll_to_selection(ui::widgets::resource_table::visible_rows(content));
         terminal.draw(|frame| ui::draw::draw(frame, &app))?;
+        pane_title.update(&app, &mut io::stdout())?;
+
+        // Rebuild the clients when the kubeconfig changes (e.g. a refreshed
+        // token), then refresh with them
+        if kubeconfig_watch.changed() {
+            app.reload_clients();
+        }
+
+        // Credentials were rejected (e.g. an expired exec-plugin token):
+        // obtain new ones and retry
+        if app.reauth == Reauth::Pending {
+            app.reauthenticate();
+        }
+
+        // Auto-refresh data periodically. Refreshes and everything else that
+        // talks to a cluster run on the app's bus, so drawing and input never
+        // wait on the API server
+        if app.refresh_due(REFRESH_INTERVAL) {
+            app.start_refresh();
+        }
+        if app.apply_updates() {
+            diagnostics::remember(&app);
+        }
+        notifier.update(&mut app, &mut io::stdout())?;
+
+        // Show reconcile progress, and the result as soon as it's done
+        if app.poll_reconcile() {
+            app.start_refresh();
+        }
 
-        // Auto-refresh data periodically
-        if last_refresh.elapsed() >= REFRESH_INTERVAL {
-            app.refresh_data().await?;
-            last_refresh = std::time::Instant::now();
+        let tick = if app.is_busy() || app.is_reconciling() {
+            SPINNER_TICK_RATE
+        } else {
+            TICK_RATE
+        };
+
+        // A script stands in for the keyboard
+        if let Some(script) = &mut script {
+            if script.step(&mut app)? == Flow::Quit {
+                save_usage(&app);
+                return Ok(());
+            }
+            diagnostics::remember(&app);
+            tokio::time::sleep(tick).await;
+            continue;
         }
 
         // Poll for events with timeout
-        if poll(TICK_RATE)? {
-            if let Event::Key(key) = read()? {
-                // Only handle key press events (not release)
-                if key.kind == KeyEventKind::Press {
-                    // Convert key event to action
-                    let action = event::handler::handle_key_event(key, &app);
-
-                    // Update state based on action
-                    match action {
-                        Action::Quit => return Ok(()),
-                        action => app.update(action).await?,
-                    }
-                }
+        if poll(tick)? {
+            // Handle every event already queued before drawing again, so a
+            // held key doesn't redraw once per repeat on a slow terminal
+            let mut events = vec![read()?];
+            while events.len() < MAX_EVENTS_PER_FRAME && poll(Duration::ZERO)? {
+                events.push(read()?);
+            }
+            if handle_events(&mut app, events, terminal.get_frame().area())? {
+                save_usage(&app);
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Apply the input events of one frame in order, returning whether one quits
+///
+/// Each event is converted against the state the previous one left, so
+/// several wheel scrolls in a frame add up instead of repeating one step.
+fn handle_events(app: &mut App, events: Vec<Event>, area: Rect) -> Result<bool> {
+    for event in events {
+        // Convert the event to an action
+        let action = match event {
+            // Only handle key press events; Windows also reports releases,
+            // which would otherwise run every action twice
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                event::handler::handle_key_event(key, app)
+            }
+            Event::Mouse(mouse) => event::handler::handle_mouse_event(mouse, app, area),
+            _ => Action::Noop,
+        };
+
+        // Update state based on action
+        match action {
+            Action::Quit => return Ok(true),
+            // Mouse movement alone maps to nothing
+            Action::Noop => {}
+            action => {
+                app.update(action)?;
+                diagnostics::remember(app);
             }
         }
     }
+    Ok(false)
+}
+
+/// Save the usage statistics when quitting
+fn save_usage(app: &App) {
+    if let Err(e) = app.usage.save(chrono::Utc::now()) {
+        tracing::warn!("Failed to save usage statistics: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_args_default_config() {
+        let config = Args::try_parse_from(["flux-tui"])
+            .unwrap()
+            .merge_into(Config::default());
+        assert!(config.context.is_none());
+        assert!(config.namespace.is_none());
+    }
+
+    #[test]
+    fn test_args_context_and_namespace() {
+        let config = Args::try_parse_from(["flux-tui", "--context", "prod", "-n", "flux-system"])
+            .unwrap()
+            .merge_into(Config::default());
+        assert_eq!(config.context.as_deref(), Some("prod"));
+        assert_eq!(config.namespace.as_deref(), Some("flux-system"));
+    }
+
+    #[test]
+    fn test_args_contexts() {
+        let config = Args::try_parse_from(["flux-tui", "--contexts", "prod-eu,prod-us"])
+            .unwrap()
+            .merge_into(Config::default());
+        assert_eq!(config.contexts, vec!["prod-eu", "prod-us"]);
+
+        assert!(Args::try_parse_from(["flux-tui", "--context", "a", "--contexts", "b,c"]).is_err());
+    }
+
+    #[test]
+    fn test_args_context_overrides_file_contexts() {
+        let file_config = Config {
+            contexts: vec!["prod-eu".to_string(), "prod-us".to_string()],
+            ..Config::default()
+        };
+        let config = Args::try_parse_from(["flux-tui", "--context", "dev"])
+            .unwrap()
+            .merge_into(file_config);
+        assert_eq!(config.context.as_deref(), Some("dev"));
+        assert!(config.contexts.is_empty());
+    }
+
+    #[test]
+    fn test_args_get() {
+        let args =
+            Args::try_parse_from(["flux-tui", "get", "hr", "-n", "apps", "-o", "json"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Get {
+                kind: get::Kind::HelmReleases,
+                output: get::Output::Json
+            })
+        ));
+        assert_eq!(args.namespace.as_deref(), Some("apps"));
+
+        assert!(Args::try_parse_from(["flux-tui", "get", "pods"]).is_err());
+    }
+
+    #[test]
+    fn test_args_subcommands_and_attach() {
+        let args = Args::try_parse_from(["flux-tui", "daemon"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Daemon { tray_file: None })
+        ));
+        let args =
+            Args::try_parse_from(["flux-tui", "daemon", "--tray-file", "/tmp/flux.json"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Daemon { tray_file: Some(_) })
+        ));
+
+        let args =
+            Args::try_parse_from(["flux-tui", "export", "--file", "/tmp/flux.prom"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Export {
+                output: export::Format::Prometheus,
+                file: Some(_)
+            })
+        ));
+
+        let config = Args::try_parse_from(["flux-tui", "--attach"])
+            .unwrap()
+            .merge_into(Config::default());
+        assert!(config.attach);
+    }
+
+    #[test]
+    fn test_args_snapshot_only_with_commands_that_read_it() {
+        let check = |argv: &[&str]| {
+            Args::try_parse_from(argv)
+                .map_err(|e| eyre!("{e}"))
+                .and_then(|args| args.check_snapshot())
+        };
+        assert!(check(&["flux-tui", "--snapshot", "s.json"]).is_ok());
+        assert!(check(&["flux-tui", "export", "--snapshot", "s.json"]).is_ok());
+        assert!(check(&["flux-tui", "--snapshot", "s.json", "--ticker"]).is_err());
+        assert!(check(&["flux-tui", "daemon", "--snapshot", "s.json"]).is_err());
+        assert!(check(&["flux-tui", "--snapshot", "s.json", "get", "ks"]).is_err());
+    }
+
+    fn wheel_down() -> Event {
+        Event::Mouse(crossterm::event::MouseEvent {
+            kind: crossterm::event::MouseEventKind::ScrollDown,
+            column: 10,
+            row: 10,
+            modifiers: crossterm::event::KeyModifiers::NONE,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_handle_events_applies_every_wheel_event_of_a_frame() {
+        let area = Rect::new(0, 0, 120, 40);
+        let mut app = App::for_testing(
+            app::state::Tab::Kustomizations,
+            (0..5)
+                .map(|i| kubernetes::resources::Kustomization {
+                    name: format!("ks-{i}"),
+                    ..Default::default()
+                })
+                .collect(),
+            vec![],
+            vec![],
+        );
+
+        let quit = handle_events(&mut app, vec![wheel_down(); 3], area).unwrap();
+        assert!(!quit);
+        assert_eq!(app.current_selected(), 3);
+
+        app.popup = app::state::Popup::ContextSwitcher {
+            contexts: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            selected: 0,
+        };
+        handle_events(&mut app, vec![wheel_down(); 2], area).unwrap();
+        assert!(matches!(
+            app.popup,
+            app::state::Popup::ContextSwitcher { selected: 2, .. }
+        ));
+    }
+
+    #[test]
+    fn test_args_long_namespace() {
+        let config = Args::try_parse_from(["flux-tui", "--namespace", "apps"])
+            .unwrap()
+            .merge_into(Config::default());
+        assert_eq!(config.namespace.as_deref(), Some("apps"));
+    }
+
+    #[test]
+    fn test_args_no_update_check_overrides_config() {
+        let file_config = Config {
+            update_check: true,
+            ..Config::default()
+        };
+        let config = Args::try_parse_from(["flux-tui", "--no-update-check"])
+            .unwrap()
+            .merge_into(file_config);
+        assert!(!config.update_check);
+    }
+
+    #[test]
+    fn test_args_theme_overrides_config() {
+        let file_config = Config {
+            theme: Some("dark".to_string()),
+            ..Config::default()
+        };
+        let config = Args::try_parse_from(["flux-tui", "--theme", "light"])
+            .unwrap()
+            .merge_into(file_config);
+        assert_eq!(config.theme.as_deref(), Some("light"));
+    }
+
+    #[test]
+    fn test_args_read_only() {
+        let config = Args::try_parse_from(["flux-tui", "--read-only"])
+            .unwrap()
+            .merge_into(Config::default());
+        assert!(config.read_only);
+    }
+
+    #[test]
+    fn test_args_no_color() {
+        let config = Args::try_parse_from(["flux-tui", "--no-color"])
+            .unwrap()
+            .merge_into(Config::default());
+        assert!(config.no_color);
+        let config = Args::try_parse_from(["flux-tui"])
+            .unwrap()
+            .merge_into(Config::default());
+        assert!(!config.no_color);
+    }
+
+    #[test]
+    fn test_args_inline_height() {
+        let config = Args::try_parse_from(["flux-tui", "--inline-height", "20"])
+            .unwrap()
+            .merge_into(Config::default());
+        assert_eq!(config.inline_height, Some(20));
+        assert!(Args::try_parse_from(["flux-tui", "--inline-height", "3"]).is_err());
+    }
+
+    #[test]
+    fn test_args_keep_config_file_values() {
+        let file_config = Config {
+            context: Some("from-file".to_string()),
+            namespace: Some("flux-system".to_string()),
+            ..Config::default()
+        };
+        let config = Args::try_parse_from(["flux-tui", "-n", "apps"])
+            .unwrap()
+            .merge_into(file_config);
+        assert_eq!(config.context.as_deref(), Some("from-file"));
+        assert_eq!(config.namespace.as_deref(), Some("apps"));
+    }
 }
@@ -1,16 +1,43 @@
 //! Main drawing function (View in The Elm Architecture)
 
+use std::time::Instant;
+
 use ratatui::{
     prelude::*,
     widgets::{Block, Borders, Clear, Paragraph, Wrap},
 };
 
-use crate::app::state::{App, Popup, Tab};
+use chrono::{DateTime, Utc};
+
+use crate::{
+    app::{
+        action_log::{ActionLog, Outcome},
+        activity, details, finder,
+        graph::GraphNode,
+        keymap,
+        locale::Locale,
+        progress::{LoadProgress, LoadState},
+        reconcile_progress::ReconcileProgress,
+        state::{namespace_choices, App, Popup, Tab, ALL_NAMESPACES},
+        timeline,
+        tour::{self, Target},
+        trace::TraceHop,
+    },
+    kubernetes::{
+        artifact::ManifestFile,
+        client::KubeEvent,
+        flux_version::FluxVersion,
+        helm::Revision,
+        metrics::{ControllerMetrics, ReconcilerMetrics, FLUX_NAMESPACE},
+        preflight::Check,
+        resources::{format_age, GitRef, GitRepository, HelmRelease, ResourceStatus},
+    },
+};
 
 use super::{
-    layout::{popup_area, MainLayout},
-    theme::{styles, ui},
-    widgets::{resource_table, status_bar, tabs},
+    layout::{popup_area, split_activity, split_details, MainLayout},
+    theme::{icons, Theme},
+    widgets::{message, resource_table, status_bar, tabs, yaml},
 };
 
 /// Main draw function - renders the entire UI
@@ -21,10 +48,19 @@ pub fn draw(frame: &mut Frame, app: &App) {
     draw_header(frame, layout.header, app);
 
     // Draw tabs
-    tabs::draw(frame, layout.tabs, app.tab);
+    tabs::draw(frame, layout.tabs, app.tab, &app.tab_counts(), &app.theme);
 
-    // Draw main content (resource table)
-    draw_content(frame, layout.content, app);
+    // Draw main content (resource table), with the details pane and the
+    // activity sidebar beside it
+    let (rest, pane) = split_details(layout.content, app.show_details);
+    let (table, sidebar) = split_activity(rest, app.show_activity);
+    draw_content(frame, table, app);
+    if let Some(area) = pane {
+        draw_details_pane(frame, area, app);
+    }
+    if let Some(area) = sidebar {
+        draw_activity(frame, area, app);
+    }
 
     // Draw status bar
     status_bar::draw(frame, layout.status_bar, app);
@@ -35,166 +71,1367 @@ pub fn draw(frame: &mut Frame, app: &App) {
 
 /// Draw the header bar
 fn draw_header(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let ns_display = app.namespace_filter.as_deref().unwrap_or("all");
 
-    let header_text = format!(
-        " flux-tui                                              cluster: {} │ ns: {}",
-        app.cluster_name, ns_display
+    let cluster_display = if app.is_multi_cluster() {
+        match &app.cluster_filter {
+            Some(cluster) => cluster.clone(),
+            None => format!("all ({})", app.cluster_labels().len()),
+        }
+    } else {
+        app.cluster_name.clone()
+    };
+
+    let mut header_text = format!(
+        " flux-tui                                              cluster: {} │ ns: {} │ {} resources",
+        cluster_display,
+        ns_display,
+        app.locale.compact(app.all_resources().len())
     );
+    // Read from the first cluster only, so not shown for several
+    let distribution = app
+        .flux_version
+        .as_ref()
+        .and_then(|version| version.distribution.as_deref())
+        .filter(|_| !app.is_multi_cluster());
+    if let Some(distribution) = distribution {
+        header_text.push_str(&format!(" │ flux {distribution}"));
+    }
+    if let Some(taken_at) = app.snapshot_taken {
+        header_text.push_str(&format!(
+            " │ snapshot taken {} ago",
+            format_age(taken_at, Utc::now())
+        ));
+    } else if let Some(status) = refresh_status(app.loading, app.last_refreshed, Utc::now()) {
+        header_text.push_str(&format!(" │ {status}"));
+    }
+
+    // Sensitive environments color the whole bar, so it can't be missed
+    let mut style = match &app.environment {
+        Some(env) => {
+            header_text = format!(" {} │{header_text}", env.label);
+            theme.banner(&env.color)
+        }
+        None => theme.header(),
+    };
+    if app.is_flashing(Instant::now()) {
+        style = theme.flash();
+    }
 
     let header = Paragraph::new(header_text)
-        .style(styles::header())
+        .style(style)
         .alignment(Alignment::Left);
 
     frame.render_widget(header, area);
 }
 
+/// A spinner while refreshing, else how long ago data was last refreshed
+///
+/// The spinner frame follows the clock, so it animates with every redraw.
+fn refresh_status(
+    loading: bool,
+    last_refreshed: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Option<String> {
+    if loading {
+        let frame = now.timestamp_subsec_millis() as usize / 100 % icons::SPINNER.len();
+        return Some(format!("{} refreshing", icons::SPINNER[frame]));
+    }
+    last_refreshed.map(|at| format!("updated {} ago", format_age(at, now)))
+}
+
 /// Draw the main content area
 fn draw_content(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let view = resource_table::TableView {
+        selected: app.current_selected(),
+        offset: app.current_scroll(),
+        show_cluster: app.is_multi_cluster(),
+        filter: &app.search_query,
+        editing: app.search_editing,
+        streaks: &app.failure_streaks,
+        state: &app.state,
+        sort: app.current_sort(),
+        source: app.active_source_filter().map(|f| f.label()),
+        status: app.current_status_filter().label(),
+        theme,
+        hidden_columns: &app.config.hidden_columns,
+    };
+
     match app.tab {
         Tab::Kustomizations => {
-            resource_table::draw_kustomizations(
-                frame,
-                area,
-                &app.kustomizations,
-                app.current_selected(),
-            );
+            resource_table::draw(frame, area, &app.rows(&app.kustomizations), &view);
         }
         Tab::HelmReleases => {
-            resource_table::draw_helm_releases(
-                frame,
-                area,
-                &app.helm_releases,
-                app.current_selected(),
-            );
+            resource_table::draw(frame, area, &app.rows(&app.helm_releases), &view);
         }
         Tab::HelmCharts => {
-            resource_table::draw_helm_charts(frame, area, &app.helm_charts, app.current_selected());
+            resource_table::draw(frame, area, &app.rows(&app.helm_charts), &view);
+        }
+        Tab::GitRepositories => {
+            resource_table::draw(frame, area, &app.rows(&app.git_repositories), &view);
+        }
+        Tab::HelmRepositories => {
+            resource_table::draw(frame, area, &app.rows(&app.helm_repositories), &view);
+        }
+    }
+
+    // Show per-kind progress while loading, and after a partial failure
+    if app.loading || app.load_progress.has_failures() {
+        let progress = load_progress_line(&app.load_progress, theme);
+        let width = (progress.width() as u16).min(area.width.saturating_sub(4));
+        let x = (area.x + area.width).saturating_sub(width + 2);
+        let progress_area = Rect::new(x, area.y, width, 1);
+        frame.render_widget(Paragraph::new(progress), progress_area);
+    }
+
+    // Mass churn, with the source revision that likely triggered it
+    if let Some(storm) = &app.storm {
+        let mut message = format!(" {} {} ", icons::RECONCILING, storm.message());
+        if app.is_multi_cluster() {
+            message = format!(" {}:{message}", storm.cluster);
         }
+        let width = (message.chars().count() as u16).min(area.width.saturating_sub(4));
+        let storm_area = Rect::new(area.x + 2, area.y, width, 1);
+        frame.render_widget(
+            Paragraph::new(message).style(theme.status_reconciling().add_modifier(Modifier::BOLD)),
+            storm_area,
+        );
     }
 
-    // Show loading indicator if loading
-    if app.loading {
-        let loading_area = Rect::new(area.x + area.width - 12, area.y, 10, 1);
-        let loading = Paragraph::new(" Loading... ").style(styles::muted());
-        frame.render_widget(loading, loading_area);
+    // Status of the selected resource at each refresh, on the bottom border
+    if let Some(resource) = app.get_selected_resource() {
+        let statuses = app.timelines.get(resource.as_ref());
+        if statuses.len() > 1 && area.height > 2 {
+            let line = timeline_line(&statuses, area.width.saturating_sub(4) as usize, theme);
+            let width = line.width() as u16;
+            let x = (area.x + area.width).saturating_sub(width + 2);
+            let timeline_area = Rect::new(x, area.y + area.height - 1, width, 1);
+            frame.render_widget(Paragraph::new(line), timeline_area);
+        }
+    }
+}
+
+/// Draw the recent activity sidebar: the resources whose status changed
+/// last, newest first
+fn draw_activity(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let now = Utc::now();
+    let resources = app.all_resources();
+    let lines: Vec<Line> = activity::recent(&resources, activity::RECENT)
+        .iter()
+        .map(|transition| {
+            let resource = transition.resource;
+            let (icon, style) = resource_table::status_icon_style(resource.status(), theme);
+            Line::from(vec![
+                Span::styled(format!("{icon} "), style),
+                Span::styled(
+                    format!("{:>3} ", format_age(transition.at, now)),
+                    theme.muted(),
+                ),
+                Span::styled(
+                    format!("{}/", activity::short_kind(resource.kind())),
+                    theme.muted(),
+                ),
+                Span::styled(resource.name().to_string(), theme.text()),
+            ])
+        })
+        .collect();
+    let paragraph = if lines.is_empty() {
+        Paragraph::new(Span::styled("No status changes yet", theme.muted()))
+    } else {
+        Paragraph::new(lines)
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(theme.border())
+        .title(" Recent activity ")
+        .title_style(theme.title());
+    frame.render_widget(paragraph.block(block), area);
+}
+
+/// Draw the details of the selected resource beside the table, following
+/// the selection
+fn draw_details_pane(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(theme.border())
+        .title_style(theme.title());
+    let Some(resource) = app.get_selected_resource() else {
+        let paragraph = Paragraph::new(Span::styled("No resource selected", theme.muted()));
+        frame.render_widget(paragraph.block(block.title(" Details ")), area);
+        return;
+    };
+    let text = details::text(
+        resource.as_ref(),
+        app.failure_streaks.get(resource.as_ref()),
+        &app.state,
+        &app.locale,
+        app.show_metadata,
+        Utc::now(),
+    );
+    let paragraph = Paragraph::new(text)
+        .block(block.title(format!(" {} {} ", resource.kind(), resource.name())))
+        .style(theme.text())
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
+
+/// One colored block per refresh, newest on the right, after the number of
+/// status changes: many changes mean flapping, few mean a stable state
+fn timeline_line(statuses: &[&ResourceStatus], max_width: usize, theme: &Theme) -> Line<'static> {
+    let changes = timeline::changes(statuses);
+    let label = format!(
+        " {} change{} ",
+        changes,
+        if changes == 1 { "" } else { "s" }
+    );
+    let ticks = max_width.saturating_sub(label.len() + 1);
+    let mut spans = vec![Span::styled(label, theme.muted())];
+    spans.extend(
+        statuses[statuses.len().saturating_sub(ticks)..]
+            .iter()
+            .map(|status| {
+                let (_, style) = resource_table::status_icon_style(status, theme);
+                Span::styled(icons::TIMELINE_TICK, style)
+            }),
+    );
+    spans.push(Span::raw(" "));
+    Line::from(spans)
+}
+
+/// One entry per kind: "Kustomizations ✓, HelmReleases …, HelmCharts ✗ forbidden"
+fn load_progress_line(progress: &LoadProgress, theme: &Theme) -> Line<'static> {
+    let mut spans = vec![Span::raw(" ")];
+    for (i, tab) in Tab::all().iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(", ", theme.muted()));
+        }
+        spans.push(Span::styled(format!("{} ", tab.name()), theme.muted()));
+        spans.push(match progress.get(*tab) {
+            LoadState::Loading => Span::styled("…", theme.status_reconciling()),
+            LoadState::Loaded => Span::styled(icons::READY, theme.status_ready()),
+            LoadState::Failed(reason) => {
+                Span::styled(format!("{} {reason}", icons::FAILED), theme.status_failed())
+            }
+        });
     }
+    spans.push(Span::raw(" "));
+    Line::from(spans)
 }
 
 /// Draw popup if one is active
 fn draw_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
     match &app.popup {
         Popup::None => {}
         Popup::NamespaceFilter {
             namespaces,
+            query,
+            selected,
+        } => {
+            let choices: Vec<String> = namespace_choices(namespaces, query)
+                .into_iter()
+                .map(|ns| ns.unwrap_or(ALL_NAMESPACES).to_string())
+                .collect();
+            let title = format!(" Select Namespace /{query}▏ ");
+            draw_list_popup(frame, &title, &choices, *selected, theme);
+        }
+        Popup::ContextSwitcher { contexts, selected } => {
+            draw_list_popup(frame, " Switch Context ", contexts, *selected, theme);
+        }
+        Popup::ClusterFilter { clusters, selected } => {
+            draw_list_popup(frame, " Select Cluster ", clusters, *selected, theme);
+        }
+        Popup::ResourceDetails { resource, scroll } => {
+            let text = details::text(
+                resource.as_ref(),
+                app.failure_streaks.get(resource.as_ref()),
+                &app.state,
+                &app.locale,
+                app.show_metadata,
+                Utc::now(),
+            );
+            draw_details_popup(frame, resource.kind(), &text, *scroll, theme);
+        }
+        Popup::Yaml {
+            title,
+            content,
+            scroll,
+        } => {
+            draw_yaml_popup(frame, title, content, *scroll, theme);
+        }
+        Popup::Events {
+            title,
+            events,
+            scroll,
+        } => {
+            draw_events_popup(frame, title, events, &app.locale, *scroll, theme);
+        }
+        Popup::Message {
+            title,
+            message: text,
+            scroll,
+        } => {
+            message::draw(frame, title, text, *scroll, theme);
+        }
+        Popup::Dependencies { nodes, scroll } => {
+            draw_dependencies_popup(frame, nodes, *scroll, theme);
+        }
+        Popup::Trace { hops } => {
+            draw_trace_popup(frame, hops, theme);
+        }
+        Popup::Help { scroll } => {
+            draw_help_popup(frame, *scroll, theme);
+        }
+        Popup::Usage { scroll } => {
+            draw_usage_popup(frame, &app.usage.report(Utc::now()), *scroll, theme);
+        }
+        Popup::ActionLog { scroll } => {
+            draw_action_log_popup(frame, &app.action_log, *scroll, theme);
+        }
+        Popup::Preflight { checks, scroll } => {
+            draw_preflight_popup(frame, checks, *scroll, theme);
+        }
+        Popup::FluxVersion { scroll } => {
+            if let Some(version) = &app.flux_version {
+                draw_flux_version_popup(frame, version, *scroll, theme);
+            }
+        }
+        Popup::Controllers {
+            controllers,
+            scroll,
+        } => {
+            draw_controllers_popup(frame, controllers, &app.locale, *scroll, theme);
+        }
+        Popup::PathBrowser {
+            title,
+            files,
+            selected,
+            open: true,
+            scroll,
+        } => {
+            if let Some(file) = files.get(*selected) {
+                let title = format!("{title} › {}", file.path);
+                draw_yaml_popup(frame, &title, &file.content, *scroll, theme);
+            }
+        }
+        Popup::PathBrowser {
+            title,
+            files,
             selected,
+            open: false,
+            ..
         } => {
-            draw_namespace_popup(frame, namespaces, *selected);
+            draw_path_browser_popup(frame, title, files, *selected, theme);
+        }
+        Popup::EditNote { resource, text } => {
+            draw_note_popup(frame, resource.as_ref(), text, theme);
+        }
+        Popup::ConfirmSuspend {
+            resource,
+            consumers,
+        } => {
+            draw_confirm_suspend_popup(frame, resource.as_ref(), consumers, theme);
+        }
+        Popup::ConfirmRefOverride {
+            repository,
+            reference,
+        } => {
+            draw_ref_override_popup(frame, repository, reference, theme);
+        }
+        Popup::HelmHistory {
+            release,
+            revisions,
+            selected,
+        } => {
+            draw_helm_history_popup(frame, release, revisions, *selected, theme);
+        }
+        Popup::ConfirmRollback { release, revision } => {
+            draw_rollback_popup(frame, release, revision, theme);
+        }
+        Popup::Command { text } => {
+            draw_command_popup(frame, text, theme);
         }
-        Popup::ResourceDetails { resource } => {
-            draw_details_popup(frame, resource.as_ref());
+        Popup::GlobalSearch { query, selected } => {
+            let matches: Vec<String> = app
+                .global_matches(query)
+                .into_iter()
+                .map(|resource| finder::label(resource, app.is_multi_cluster()))
+                .collect();
+            let title = format!(" Find Resource /{query}▏ ");
+            draw_list_popup(frame, &title, &matches, *selected, theme);
         }
-        Popup::Reconciling { name, namespace } => {
-            draw_reconciling_popup(frame, name, namespace);
+        Popup::Tour { step } => {
+            draw_tour_popup(frame, *step, theme);
+        }
+        Popup::ConfirmBulkSuspend {
+            selector,
+            resources,
+        } => {
+            draw_bulk_suspend_popup(frame, selector, resources, app.is_multi_cluster(), theme);
+        }
+        Popup::Reconciling {
+            name,
+            namespace,
+            progress,
+        } => {
+            draw_reconciling_popup(frame, name, namespace, progress, theme);
         }
         Popup::Error { message } => {
-            draw_error_popup(frame, message);
+            draw_error_popup(frame, message, theme);
+        }
+        Popup::Info { message } => {
+            draw_info_popup(frame, message, theme);
         }
     }
 }
 
-/// Draw namespace filter popup
-fn draw_namespace_popup(frame: &mut Frame, namespaces: &[String], selected: usize) {
+/// Draw a popup with a selectable list (namespaces, contexts)
+fn draw_list_popup(
+    frame: &mut Frame,
+    title: &str,
+    items: &[String],
+    selected: usize,
+    theme: &Theme,
+) {
     let area = popup_area(frame.area(), 40, 60);
 
     // Clear the background
     frame.render_widget(Clear, area);
 
-    let items: Vec<Line> = namespaces
+    // Scroll long lists (e.g. hundreds of namespaces) to keep the selection visible
+    let rows = usize::from(area.height.saturating_sub(2));
+    let first = (selected + 1).saturating_sub(rows.max(1));
+    let items: Vec<Line> = items
         .iter()
         .enumerate()
-        .map(|(i, ns)| {
+        .skip(first)
+        .map(|(i, item)| {
             if i == selected {
-                Line::from(format!(" ▶ {} ", ns)).style(styles::selected())
+                Line::from(format!(" ▶ {} ", item)).style(theme.selected())
             } else {
-                Line::from(format!("   {} ", ns)).style(styles::text())
+                Line::from(format!("   {} ", item)).style(theme.text())
             }
         })
         .collect();
 
     let block = Block::default()
-        .title(" Select Namespace ")
-        .title_style(styles::title())
+        .title(title)
+        .title_style(theme.title())
         .borders(Borders::ALL)
-        .border_style(styles::border_highlight());
+        .border_style(theme.border_highlight());
 
     let paragraph = Paragraph::new(items).block(block);
 
     frame.render_widget(paragraph, area);
 }
 
+/// Draw the files of a path browser, scrolled to keep the selection visible
+fn draw_path_browser_popup(
+    frame: &mut Frame,
+    title: &str,
+    files: &[ManifestFile],
+    selected: usize,
+    theme: &Theme,
+) {
+    let area = popup_area(frame.area(), 60, 70);
+
+    frame.render_widget(Clear, area);
+
+    let lines: Vec<Line> = files
+        .iter()
+        .enumerate()
+        .map(|(i, file)| {
+            let text = format!("{} ({} lines)", file.path, file.content.lines().count());
+            if i == selected {
+                Line::from(format!(" ▶ {text} ")).style(theme.selected())
+            } else {
+                Line::from(format!("   {text} ")).style(theme.text())
+            }
+        })
+        .collect();
+
+    let block = Block::default()
+        .title(format!(" {title} (read-only) "))
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+
+    let visible = area.height.saturating_sub(2) as usize;
+    let scroll = (selected + 1).saturating_sub(visible) as u16;
+    let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+
+    frame.render_widget(paragraph, area);
+}
+
 /// Draw resource details popup
-fn draw_details_popup(
+fn draw_details_popup(frame: &mut Frame, kind: &str, text: &str, scroll: u16, theme: &Theme) {
+    let area = popup_area(frame.area(), 70, 70);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" {kind} Details "))
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+
+    let paragraph = Paragraph::new(text)
+        .block(block)
+        .style(theme.text())
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0));
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Draw the YAML manifest popup
+fn draw_yaml_popup(frame: &mut Frame, title: &str, content: &str, scroll: u16, theme: &Theme) {
+    let area = popup_area(frame.area(), 80, 80);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" {title} "))
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+
+    let paragraph = Paragraph::new(yaml::highlight(content, theme))
+        .block(block)
+        .scroll((scroll, 0));
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Draw the Events about a resource, one per line with age and reason
+fn draw_events_popup(
+    frame: &mut Frame,
+    title: &str,
+    events: &[KubeEvent],
+    locale: &Locale,
+    scroll: u16,
+    theme: &Theme,
+) {
+    let area = popup_area(frame.area(), 80, 70);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" {title} "))
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+
+    let now = Utc::now();
+    let lines: Vec<Line> = if events.is_empty() {
+        vec![Line::styled(
+            "No events (they expire after about an hour)",
+            theme.muted(),
+        )]
+    } else {
+        events
+            .iter()
+            .map(|event| {
+                let age = event
+                    .last_seen
+                    .map(|at| format_age(at, now))
+                    .unwrap_or_else(|| "-".to_string());
+                let reason_style = if event.is_warning() {
+                    theme.status_failed()
+                } else {
+                    theme.status_ready()
+                };
+                let mut spans = vec![
+                    Span::styled(format!("{age:>5}  "), theme.muted()),
+                    Span::styled(event.reason.clone(), reason_style),
+                ];
+                if event.count > 1 {
+                    spans.push(Span::styled(
+                        format!(" (x{})", locale.count(event.count.max(0) as usize)),
+                        theme.muted(),
+                    ));
+                }
+                spans.push(Span::styled(format!("  {}", event.message), theme.text()));
+                Line::from(spans)
+            })
+            .collect()
+    };
+
+    let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Draw the Kustomization dependency tree, colored by status
+fn draw_dependencies_popup(frame: &mut Frame, nodes: &[GraphNode], scroll: u16, theme: &Theme) {
+    let area = popup_area(frame.area(), 70, 80);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Kustomization dependencies ")
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+
+    let multi_cluster = nodes.iter().any(|n| n.cluster != nodes[0].cluster);
+    let lines: Vec<Line> = if nodes.is_empty() {
+        vec![Line::styled("No Kustomizations", theme.muted())]
+    } else {
+        nodes
+            .iter()
+            .map(|node| {
+                let (icon, style) = resource_table::status_icon_style(&node.status, theme);
+                let mut spans = vec![
+                    Span::raw("  ".repeat(node.depth)),
+                    Span::styled(format!("{icon} "), style),
+                ];
+                if multi_cluster && node.depth == 0 {
+                    spans.push(Span::styled(format!("{}: ", node.cluster), theme.muted()));
+                }
+                spans.push(Span::styled(node.name.clone(), style));
+                if node.repeated {
+                    spans.push(Span::styled(" (see above)", theme.muted()));
+                } else if !node.waiting_on.is_empty() {
+                    spans.push(Span::styled(
+                        format!("  waiting on {}", node.waiting_on.join(", ")),
+                        theme.muted(),
+                    ));
+                }
+                Line::from(spans)
+            })
+            .collect()
+    };
+
+    let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Draw the keybinding help, grouped by category
+fn draw_help_popup(frame: &mut Frame, scroll: u16, theme: &Theme) {
+    let area = popup_area(frame.area(), 90, 90);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Help ")
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+
+    let mut lines = Vec::new();
+    for (category, bindings) in keymap::grouped() {
+        lines.push(Line::styled(category.title(), theme.title()));
+        for binding in bindings {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:<10}", binding.label()), theme.key()),
+                Span::styled(binding.description, theme.key_desc()),
+            ]));
+        }
+        lines.push(Line::default());
+    }
+
+    let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Draw the local usage statistics
+fn draw_usage_popup(frame: &mut Frame, report: &[String], scroll: u16, theme: &Theme) {
+    let area = popup_area(frame.area(), 60, 60);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Usage statistics (local only) ")
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+
+    let lines: Vec<Line> = report
+        .iter()
+        .map(|line| {
+            if line.ends_with(':') {
+                Line::styled(line.clone(), theme.title())
+            } else {
+                Line::styled(line.clone(), theme.text())
+            }
+        })
+        .collect();
+    let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Draw the changes made this session, oldest first
+fn draw_action_log_popup(frame: &mut Frame, log: &ActionLog, scroll: u16, theme: &Theme) {
+    let area = popup_area(frame.area(), 80, 60);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" Action log ({}) ", log.len()))
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+
+    let lines: Vec<Line> = if log.len() == 0 {
+        vec![Line::styled(
+            "Nothing was changed from flux-tui this session",
+            theme.muted(),
+        )]
+    } else {
+        log.entries()
+            .map(|entry| {
+                let style = match entry.outcome {
+                    Outcome::Failed(_) => theme.status_failed(),
+                    Outcome::Started => theme.status_reconciling(),
+                    Outcome::Succeeded => theme.text(),
+                };
+                Line::styled(entry.to_string(), style)
+            })
+            .collect()
+    };
+    let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Draw the Flux installation checks, passed or failed
+fn draw_preflight_popup(frame: &mut Frame, checks: &[Check], scroll: u16, theme: &Theme) {
+    let area = popup_area(frame.area(), 70, 50);
+
+    frame.render_widget(Clear, area);
+
+    let failed = checks.iter().filter(|check| !check.passed()).count();
+    let title = match failed {
+        0 => " Preflight: all checks passed ".to_string(),
+        n => format!(" Preflight: {n} of {} checks failed ", checks.len()),
+    };
+    let block = Block::default()
+        .title(title)
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+
+    let lines: Vec<Line> = checks
+        .iter()
+        .map(|check| {
+            let (icon, style, text) = match &check.result {
+                Ok(text) => (icons::READY, theme.status_ready(), text),
+                Err(text) => (icons::FAILED, theme.status_failed(), text),
+            };
+            Line::from(vec![
+                Span::styled(format!("{icon} "), style),
+                Span::styled(format!("{:<24}", check.name), theme.text()),
+                Span::styled(text.clone(), style),
+            ])
+        })
+        .collect();
+    let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Draw the Flux distribution version and the image of each controller
+fn draw_flux_version_popup(frame: &mut Frame, version: &FluxVersion, scroll: u16, theme: &Theme) {
+    let area = popup_area(frame.area(), 70, 50);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Flux version ")
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+
+    let distribution = match (&version.distribution, version.mixed) {
+        (Some(distribution), false) => Span::styled(distribution.clone(), theme.text()),
+        (Some(distribution), true) => Span::styled(
+            format!("{distribution} (controllers are labeled with different versions)"),
+            theme.status_failed(),
+        ),
+        (None, _) => Span::styled(
+            "unknown (the Deployments have no app.kubernetes.io/version label)",
+            theme.muted(),
+        ),
+    };
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(format!("{:<28}", "flux"), theme.text()),
+            distribution,
+        ]),
+        Line::from(""),
+    ];
+    lines.extend(version.controllers.iter().map(|controller| {
+        let tag = match controller.tag() {
+            Some(tag) => Span::styled(tag.to_string(), theme.text()),
+            None => Span::styled(
+                controller
+                    .image
+                    .clone()
+                    .unwrap_or_else(|| "no image".to_string()),
+                theme.muted(),
+            ),
+        };
+        Line::from(vec![
+            Span::styled(format!("{:<28}", controller.name), theme.text()),
+            tag,
+        ])
+    }));
+    if version.controllers.is_empty() {
+        lines.push(Line::styled(
+            format!("No Deployments in {FLUX_NAMESPACE}"),
+            theme.muted(),
+        ));
+    }
+    let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Draw the hops from a resource to its source, with readiness and revision
+fn draw_trace_popup(frame: &mut Frame, hops: &[TraceHop], theme: &Theme) {
+    let area = popup_area(frame.area(), 70, 60);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Trace ")
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+
+    let mut lines = Vec::new();
+    for (i, hop) in hops.iter().enumerate() {
+        if i > 0 {
+            lines.push(Line::styled("  ↓", theme.muted()));
+        }
+        let (icon, style) = match &hop.status {
+            Some(status) => resource_table::status_icon_style(status, theme),
+            None => (icons::UNKNOWN, theme.status_unknown()),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{icon} "), style),
+            Span::styled(format!("{} ", hop.kind), theme.title()),
+            Span::styled(hop.name.clone(), theme.text()),
+        ]));
+        if let Some(revision) = &hop.revision {
+            lines.push(Line::styled(
+                format!("    Revision: {revision}"),
+                theme.muted(),
+            ));
+        }
+        if !hop.message.is_empty() {
+            lines.push(Line::styled(format!("    {}", hop.message), style));
+        }
+    }
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Draw the Flux controllers' work queues and reconcile stats
+///
+/// A controller header is followed by a line per reconciler, or by the
+/// error that kept its metrics from being scraped.
+fn draw_controllers_popup(
+    frame: &mut Frame,
+    controllers: &[ControllerMetrics],
+    locale: &Locale,
+    scroll: u16,
+    theme: &Theme,
+) {
+    let area = popup_area(frame.area(), 80, 70);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Controllers ")
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+
+    let mut lines = Vec::new();
+    for controller in controllers {
+        lines.push(Line::styled(controller.controller.clone(), theme.title()));
+        match &controller.reconcilers {
+            Ok(reconcilers) => {
+                lines.extend(
+                    reconcilers
+                        .iter()
+                        .map(|r| reconciler_line(r, locale, theme)),
+                );
+            }
+            Err(e) => lines.push(Line::styled(format!("  {e}"), theme.status_failed())),
+        }
+    }
+
+    let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Queue depth, reconcile count, error rate and mean duration of a reconciler
+fn reconciler_line<'a>(reconciler: &ReconcilerMetrics, locale: &Locale, theme: &Theme) -> Line<'a> {
+    let queue_style = if reconciler.is_overloaded() {
+        theme.status_failed()
+    } else {
+        theme.text()
+    };
+    let error_rate = reconciler.error_rate();
+    let error_style = if error_rate.is_some_and(|rate| rate > 0.0) {
+        theme.status_failed()
+    } else {
+        theme.text()
+    };
+    let error_rate = error_rate.map_or_else(|| "-".to_string(), |r| format!("{:.1}%", r * 100.0));
+    let duration = reconciler
+        .mean_duration()
+        .map_or_else(|| "-".to_string(), |d| format!("{d:.2}s"));
+    Line::from(vec![
+        Span::styled(format!("  {:<24}", reconciler.name), theme.text()),
+        Span::styled("queue ", theme.muted()),
+        Span::styled(format!("{:>4}", reconciler.queue_depth), queue_style),
+        Span::styled("  reconciles ", theme.muted()),
+        Span::styled(
+            format!("{:>8}", locale.count(reconciler.reconciles as usize)),
+            theme.text(),
+        ),
+        Span::styled("  errors ", theme.muted()),
+        Span::styled(format!("{error_rate:>6}"), error_style),
+        Span::styled("  avg ", theme.muted()),
+        Span::styled(format!("{duration:>7}"), theme.text()),
+    ])
+}
+
+/// Draw the note editor for a resource
+fn draw_note_popup(
     frame: &mut Frame,
     resource: &dyn crate::kubernetes::resources::FluxResource,
+    text: &str,
+    theme: &Theme,
 ) {
-    let area = popup_area(frame.area(), 70, 70);
+    let area = popup_area(frame.area(), 60, 30);
 
     frame.render_widget(Clear, area);
 
-    let details = format!(
-        "Name:      {}\n\
-         Namespace: {}\n\
-         Ready:     {}\n\
-         Status:    {}\n\
-         Revision:  {}\n\
-         Suspended: {}",
-        resource.name(),
-        resource.namespace(),
-        if resource.is_ready() { "Yes" } else { "No" },
-        resource.status_message(),
-        resource.revision().unwrap_or("-"),
-        if resource.is_suspended() { "Yes" } else { "No" }
-    );
+    let block = Block::default()
+        .title(format!(
+            " Note: {} {}/{} ",
+            resource.kind(),
+            resource.namespace(),
+            resource.name()
+        ))
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled(text.to_string(), theme.text()),
+            Span::styled("▏", theme.key()),
+        ]),
+        Line::from(""),
+        Line::styled(
+            "Kept locally in the flux-tui state file, not in the cluster.",
+            theme.muted(),
+        ),
+    ];
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Draw the confirmation for suspending a source, listing affected consumers
+fn draw_confirm_suspend_popup(
+    frame: &mut Frame,
+    resource: &dyn crate::kubernetes::resources::FluxResource,
+    consumers: &[String],
+    theme: &Theme,
+) {
+    let area = popup_area(frame.area(), 60, 50);
+
+    frame.render_widget(Clear, area);
+
+    let mut lines = if consumers.is_empty() {
+        vec![Line::from(
+            "No Kustomizations or HelmReleases use this source.",
+        )]
+    } else {
+        let mut lines = vec![
+            Line::from(format!(
+                "{} resource(s) will stop receiving updates:",
+                consumers.len()
+            )),
+            Line::from(""),
+        ];
+        lines.extend(
+            consumers
+                .iter()
+                .map(|c| Line::styled(format!("  • {c}"), theme.status_reconciling())),
+        );
+        lines
+    };
+    lines.push(Line::from(""));
+    lines.push(Line::styled(
+        "Enter/y to suspend, Esc/n to cancel",
+        theme.muted(),
+    ));
 
     let block = Block::default()
-        .title(format!(" {} Details ", resource.kind()))
-        .title_style(styles::title())
+        .title(format!(
+            " Suspend {} {}/{}? ",
+            resource.kind(),
+            resource.namespace(),
+            resource.name()
+        ))
+        .title_style(theme.title())
         .borders(Borders::ALL)
-        .border_style(styles::border_highlight());
+        .border_style(theme.border_highlight());
 
-    let paragraph = Paragraph::new(details)
+    let paragraph = Paragraph::new(lines)
         .block(block)
-        .style(styles::text())
+        .style(theme.text())
         .wrap(Wrap { trim: false });
 
     frame.render_widget(paragraph, area);
 }
 
-/// Draw reconciling popup
-fn draw_reconciling_popup(frame: &mut Frame, name: &str, namespace: &str) {
-    let area = popup_area(frame.area(), 50, 20);
+/// Draw the warning before overriding a GitRepository's ref
+fn draw_ref_override_popup(
+    frame: &mut Frame,
+    repository: &GitRepository,
+    reference: &GitRef,
+    theme: &Theme,
+) {
+    let area = popup_area(frame.area(), 60, 40);
 
     frame.render_widget(Clear, area);
 
-    let text = format!("Reconciling {}/{} ...", namespace, name);
+    let current = match &repository.ref_override {
+        Some(_) => format!("{} (already overridden)", repository.ref_display()),
+        None => repository.ref_display(),
+    };
+    let lines = vec![
+        Line::styled("⚠ This diverges from Git!", theme.status_failed()),
+        Line::from(""),
+        Line::from(format!("Current ref: {current}")),
+        Line::from(format!("New ref:     {reference}")),
+        Line::from(""),
+        Line::from(
+            "The override lasts until reverted with `:ref revert`, or until \
+             whatever applies this GitRepository from Git (often the \
+             flux-system Kustomization) resets it.",
+        ),
+        Line::from(""),
+        Line::styled("Enter/y to override, Esc/n to cancel", theme.muted()),
+    ];
 
     let block = Block::default()
-        .title(" Reconciling ")
-        .title_style(styles::title())
+        .title(format!(
+            " Override ref of GitRepository {}/{}? ",
+            repository.namespace, repository.name
+        ))
+        .title_style(theme.title())
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(ui::PRIMARY));
+        .border_style(theme.border_highlight());
 
-    let paragraph = Paragraph::new(text)
+    let paragraph = Paragraph::new(lines)
         .block(block)
-        .style(styles::text())
-        .alignment(Alignment::Center);
+        .style(theme.text())
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Draw the Helm release revisions, newest first, scrolled to keep the
+/// selection visible
+fn draw_helm_history_popup(
+    frame: &mut Frame,
+    release: &HelmRelease,
+    revisions: &[Revision],
+    selected: usize,
+    theme: &Theme,
+) {
+    let area = popup_area(frame.area(), 80, 60);
+
+    frame.render_widget(Clear, area);
+
+    let now = Utc::now();
+    let chart_width = revisions.iter().map(|r| r.chart.len()).max().unwrap_or(0);
+    let lines: Vec<Line> = revisions
+        .iter()
+        .enumerate()
+        .map(|(i, revision)| {
+            let age = revision
+                .updated
+                .map(|at| format_age(at, now))
+                .unwrap_or_else(|| "-".to_string());
+            let marker = if i == selected { " ▶ " } else { "   " };
+            let status_style = match revision.status.as_str() {
+                "deployed" => theme.status_ready(),
+                "failed" => theme.status_failed(),
+                "superseded" => theme.muted(),
+                _ => theme.status_reconciling(),
+            };
+            let line = Line::from(vec![
+                Span::raw(format!("{marker}{:>4}  ", revision.revision)),
+                Span::styled(format!("{:<10}  ", revision.status), status_style),
+                Span::raw(format!(
+                    "{:<chart_width$}  {:>5}  {}",
+                    revision.chart, age, revision.description
+                )),
+            ]);
+            if i == selected {
+                line.style(theme.selected())
+            } else {
+                line.style(theme.text())
+            }
+        })
+        .collect();
+
+    let block = Block::default()
+        .title(format!(
+            " Helm history of {} ({}/{}) ",
+            release.release_name, release.namespace, release.name
+        ))
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+
+    let visible = area.height.saturating_sub(2) as usize;
+    let scroll = (selected + 1).saturating_sub(visible) as u16;
+    let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Draw the confirmation before rolling a Helm release back
+fn draw_rollback_popup(
+    frame: &mut Frame,
+    release: &HelmRelease,
+    revision: &Revision,
+    theme: &Theme,
+) {
+    let area = popup_area(frame.area(), 60, 40);
+
+    frame.render_widget(Clear, area);
+
+    let mut lines = vec![
+        Line::from(format!(
+            "Roll {} back to revision {} ({}).",
+            release.release_name, revision.revision, revision.chart
+        )),
+        Line::from(""),
+    ];
+    if !release.suspended {
+        lines.push(Line::styled(
+            "⚠ The HelmRelease is suspended first, so helm-controller doesn't \
+             upgrade the release again. Resume it with `s` to return to what Git \
+             declares.",
+            theme.status_reconciling(),
+        ));
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::styled(
+        "Enter/y to roll back, Esc/n to cancel",
+        theme.muted(),
+    ));
+
+    let block = Block::default()
+        .title(format!(
+            " Roll back HelmRelease {}/{}? ",
+            release.namespace, release.name
+        ))
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .style(theme.text())
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Draw the command palette input
+fn draw_command_popup(frame: &mut Frame, text: &str, theme: &Theme) {
+    let area = popup_area(frame.area(), 60, 20);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Command ")
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled(":", theme.muted()),
+            Span::styled(text.to_string(), theme.text()),
+            Span::styled("▏", theme.key()),
+        ]),
+        Line::from(""),
+        Line::styled(
+            "suspend -l key=value[,key!=value,key,!key]  suspend matching Kustomizations and HelmReleases",
+            theme.muted(),
+        ),
+    ];
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Draw the dry-run preview of a bulk suspend, listing every matching resource
+fn draw_bulk_suspend_popup(
+    frame: &mut Frame,
+    selector: &str,
+    resources: &[Box<dyn crate::kubernetes::resources::FluxResource>],
+    show_cluster: bool,
+    theme: &Theme,
+) {
+    let area = popup_area(frame.area(), 70, 70);
+
+    frame.render_widget(Clear, area);
+
+    let mut lines = if resources.is_empty() {
+        vec![Line::from(
+            "No unsuspended Kustomizations or HelmReleases match.",
+        )]
+    } else {
+        let mut lines = vec![
+            Line::from(format!(
+                "{} resource(s) will be suspended:",
+                resources.len()
+            )),
+            Line::from(""),
+        ];
+        lines.extend(resources.iter().map(|resource| {
+            let cluster = if show_cluster {
+                format!("{}: ", resource.cluster())
+            } else {
+                String::new()
+            };
+            Line::styled(
+                format!(
+                    "  • {cluster}{} {}/{}",
+                    resource.kind(),
+                    resource.namespace(),
+                    resource.name()
+                ),
+                theme.status_reconciling(),
+            )
+        }));
+        lines
+    };
+    lines.push(Line::from(""));
+    lines.push(Line::styled(
+        "Enter/y to suspend, Esc/n to cancel",
+        theme.muted(),
+    ));
+
+    let block = Block::default()
+        .title(format!(" Suspend -l {selector}? (dry run) "))
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .style(theme.text())
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Draw the live progress of a reconcile, newest steps at the bottom
+fn draw_reconciling_popup(
+    frame: &mut Frame,
+    name: &str,
+    namespace: &str,
+    progress: &ReconcileProgress,
+    theme: &Theme,
+) {
+    let area = popup_area(frame.area(), 70, 50);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" Reconciling {namespace}/{name} "))
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.ui.primary));
+
+    let now = Utc::now();
+    let mut lines: Vec<Line> = progress
+        .steps
+        .iter()
+        .map(|step| {
+            let offset = (step.at - progress.started).num_seconds().max(0);
+            let style = if step.warning {
+                theme.status_failed()
+            } else {
+                theme.text()
+            };
+            Line::from(vec![
+                Span::styled(format!("{:>5}  ", format!("+{offset}s")), theme.muted()),
+                Span::styled(step.text.clone(), style),
+            ])
+        })
+        .collect();
+    if !lines.is_empty() {
+        lines.push(Line::from(""));
+    }
+    let elapsed = (now - progress.started).num_seconds().max(0);
+    lines.push(match &progress.outcome {
+        None => {
+            let frame_index = (now.timestamp_subsec_millis() / 100) as usize % icons::SPINNER.len();
+            Line::styled(
+                format!("{} Reconciling... {elapsed}s", icons::SPINNER[frame_index]),
+                theme.status_reconciling(),
+            )
+        }
+        Some(Ok(())) => Line::styled(
+            format!("{} Reconciled in {elapsed}s", icons::READY),
+            theme.status_ready(),
+        ),
+        Some(Err(e)) => Line::styled(
+            format!("{} Reconcile failed: {e}", icons::FAILED),
+            theme.status_failed(),
+        ),
+    });
+
+    // Keep the latest lines in view
+    let height = usize::from(area.height.saturating_sub(2));
+    let scroll = u16::try_from(lines.len().saturating_sub(height)).unwrap_or(u16::MAX);
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .style(theme.text())
+        .scroll((scroll, 0));
 
     frame.render_widget(paragraph, area);
 }
 
 /// Draw error popup
-fn draw_error_popup(frame: &mut Frame, message: &str) {
+fn draw_error_popup(frame: &mut Frame, message: &str, theme: &Theme) {
     let area = popup_area(frame.area(), 60, 30);
 
     frame.render_widget(Clear, area);
@@ -203,15 +1440,78 @@ fn draw_error_popup(frame: &mut Frame, message: &str) {
         .title(" Error ")
         .title_style(
             Style::default()
-                .fg(crate::ui::theme::status::FAILED)
+                .fg(theme.status.failed)
                 .add_modifier(Modifier::BOLD),
         )
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(crate::ui::theme::status::FAILED));
+        .border_style(Style::default().fg(theme.status.failed));
+
+    let paragraph = Paragraph::new(message)
+        .block(block)
+        .style(theme.text())
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Draw a step of the onboarding tour: the area it describes is highlighted
+/// and a card explains it
+fn draw_tour_popup(frame: &mut Frame, step: usize, theme: &Theme) {
+    let Some(current) = tour::STEPS.get(step) else {
+        return;
+    };
+    let layout = MainLayout::new(frame.area());
+
+    // One-row bars can't take a border, so they're drawn selected instead
+    let target = layout.tour_target(current.target);
+    match current.target {
+        Target::Header | Target::StatusBar => {
+            frame.buffer_mut().set_style(target, theme.selected())
+        }
+        Target::Tabs | Target::Table => frame.render_widget(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border_highlight()),
+            target,
+        ),
+    }
+
+    let area = layout.tour_card(current.target);
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" {} ", current.title))
+        .title_style(theme.title())
+        .title_bottom(Line::styled(
+            format!(" {}/{} ", step + 1, tour::STEPS.len()),
+            theme.muted(),
+        ))
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+
+    let paragraph = Paragraph::new(current.text)
+        .block(block)
+        .style(theme.text())
+        .wrap(Wrap { trim: true });
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Draw info popup
+fn draw_info_popup(frame: &mut Frame, message: &str, theme: &Theme) {
+    let area = popup_area(frame.area(), 60, 30);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Info ")
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
 
     let paragraph = Paragraph::new(message)
         .block(block)
-        .style(styles::text())
+        .style(theme.text())
         .wrap(Wrap { trim: false });
 
     frame.render_widget(paragraph, area);
@@ -2,6 +2,8 @@
 
 use ratatui::prelude::*;
 
+use crate::app::tour::Target;
+
 /// Main layout areas
 pub struct MainLayout {
     /// Header area (title bar)
@@ -36,6 +38,83 @@ impl MainLayout {
     }
 }
 
+/// Width of the recent activity sidebar
+const ACTIVITY_WIDTH: u16 = 40;
+
+/// Split the content area into the table and, when shown, the activity
+/// sidebar on its right
+///
+/// The sidebar is left out when it would squeeze the table below its own width.
+pub fn split_activity(content: Rect, show: bool) -> (Rect, Option<Rect>) {
+    if !show || content.width < 2 * ACTIVITY_WIDTH {
+        return (content, None);
+    }
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(ACTIVITY_WIDTH)])
+        .split(content);
+    (chunks[0], Some(chunks[1]))
+}
+
+/// Share of the content width the details pane takes
+const DETAILS_PERCENT: u16 = 40;
+
+/// Narrowest table the details pane leaves
+const MIN_TABLE_WIDTH: u16 = 60;
+
+/// Split the content area into the table and, when shown, the details pane
+/// on the right 40%
+///
+/// The pane is left out when the table would get narrower than it can be read.
+pub fn split_details(content: Rect, show: bool) -> (Rect, Option<Rect>) {
+    let pane_width = content.width * DETAILS_PERCENT / 100;
+    if !show || content.width - pane_width < MIN_TABLE_WIDTH {
+        return (content, None);
+    }
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(pane_width)])
+        .split(content);
+    (chunks[0], Some(chunks[1]))
+}
+
+/// The table area left beside the details pane and the activity sidebar
+pub fn table_area(content: Rect, show_details: bool, show_activity: bool) -> Rect {
+    let (rest, _) = split_details(content, show_details);
+    split_activity(rest, show_activity).0
+}
+
+/// Size of an onboarding tour card
+const TOUR_CARD_WIDTH: u16 = 60;
+const TOUR_CARD_HEIGHT: u16 = 7;
+
+impl MainLayout {
+    /// Area a tour step highlights
+    pub fn tour_target(&self, target: Target) -> Rect {
+        match target {
+            Target::Header => self.header,
+            Target::Tabs => self.tabs,
+            Target::Table => self.content,
+            Target::StatusBar => self.status_bar,
+        }
+    }
+
+    /// Where a tour card goes: inside the table, next to the area it
+    /// describes, so the highlight stays visible
+    pub fn tour_card(&self, target: Target) -> Rect {
+        let content = self.content;
+        let width = TOUR_CARD_WIDTH.min(content.width);
+        let height = TOUR_CARD_HEIGHT.min(content.height);
+        let x = content.x + (content.width - width) / 2;
+        let y = match target {
+            Target::Header | Target::Tabs => content.y + 1.min(content.height - height),
+            Target::Table => content.y + (content.height - height) / 2,
+            Target::StatusBar => content.bottom() - height,
+        };
+        Rect::new(x, y, width, height)
+    }
+}
+
 /// Popup layout - centered on screen
 pub fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
     let popup_layout = Layout::default()
@@ -120,6 +199,53 @@ mod tests {
         assert!(popup.height <= 25);
     }
 
+    #[test]
+    fn test_split_activity() {
+        let content = Rect::new(0, 4, 120, 30);
+        assert_eq!(split_activity(content, false), (content, None));
+
+        let (table, sidebar) = split_activity(content, true);
+        assert_eq!(table, Rect::new(0, 4, 80, 30));
+        assert_eq!(sidebar, Some(Rect::new(80, 4, 40, 30)));
+
+        // Too narrow to share
+        let narrow = Rect::new(0, 4, 60, 30);
+        assert_eq!(split_activity(narrow, true), (narrow, None));
+    }
+
+    #[test]
+    fn test_split_details() {
+        let content = Rect::new(0, 4, 150, 30);
+        assert_eq!(split_details(content, false), (content, None));
+
+        let (table, pane) = split_details(content, true);
+        assert_eq!(table, Rect::new(0, 4, 90, 30));
+        assert_eq!(pane, Some(Rect::new(90, 4, 60, 30)));
+        assert_eq!(table_area(content, true, true), Rect::new(0, 4, 50, 30));
+
+        // Too narrow to share
+        let narrow = Rect::new(0, 4, 90, 30);
+        assert_eq!(split_details(narrow, true), (narrow, None));
+    }
+
+    #[test]
+    fn test_tour_card() {
+        let layout = MainLayout::new(Rect::new(0, 0, 100, 50));
+        assert_eq!(layout.tour_card(Target::Header), Rect::new(20, 5, 60, 7));
+        assert_eq!(layout.tour_card(Target::Table), Rect::new(20, 23, 60, 7));
+        assert_eq!(
+            layout.tour_card(Target::StatusBar),
+            Rect::new(20, 42, 60, 7)
+        );
+        assert_eq!(layout.tour_target(Target::StatusBar), layout.status_bar);
+
+        // Shrinks to fit a small terminal
+        let layout = MainLayout::new(Rect::new(0, 0, 40, 10));
+        let card = layout.tour_card(Target::Tabs);
+        assert!(layout.content.contains(card.as_position()));
+        assert_eq!(card.width, 40);
+    }
+
     #[test]
     fn test_main_layout_small_terminal() {
         // Test with a small terminal size
@@ -2,5 +2,7 @@
 
 pub mod draw;
 pub mod layout;
+pub mod notify;
+pub mod pane_title;
 pub mod theme;
 pub mod widgets;
@@ -0,0 +1,144 @@
+//! Failure notifications
+//!
+//! For flux-tui left open on a second monitor: when a refresh finds a
+//! resource that was Ready has failed, the terminal bell rings and a desktop
+//! notification is sent, each if enabled in the config file. The header flash
+//! is drawn from the app state.
+
+use std::io::{self, Write};
+use std::process::Stdio;
+
+use crate::app::{config::Notifications, state::App};
+
+/// Failures named in a notification before the rest are counted
+const MAX_NAMED: usize = 3;
+
+/// Rings the bell and sends desktop notifications for new failures
+#[derive(Debug, Default)]
+pub struct Notifier {
+    bell: bool,
+    desktop: bool,
+}
+
+impl Notifier {
+    pub fn new(notifications: &Notifications) -> Self {
+        Self {
+            bell: notifications.bell,
+            desktop: notifications.desktop,
+        }
+    }
+
+    /// Notify of the failures found since the last call
+    pub fn update(&self, app: &mut App, out: &mut impl Write) -> io::Result<()> {
+        let failures = std::mem::take(&mut app.new_failures);
+        if failures.is_empty() {
+            return Ok(());
+        }
+        if self.bell {
+            write!(out, "\x07")?;
+            out.flush()?;
+        }
+        if self.desktop {
+            send_desktop(&summary(&failures));
+        }
+        Ok(())
+    }
+}
+
+/// Notification text, e.g. "HelmRelease apps/podinfo failed"
+fn summary(failures: &[String]) -> String {
+    match failures {
+        [failure] => format!("{failure} failed"),
+        _ if failures.len() <= MAX_NAMED => {
+            format!(
+                "{} resources failed: {}",
+                failures.len(),
+                failures.join(", ")
+            )
+        }
+        _ => format!(
+            "{} resources failed: {} and {} more",
+            failures.len(),
+            failures[..MAX_NAMED].join(", "),
+            failures.len() - MAX_NAMED
+        ),
+    }
+}
+
+/// Send a desktop notification without waiting for it; failures are only
+/// logged, e.g. when notify-send isn't installed
+fn send_desktop(body: &str) {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = tokio::process::Command::new("osascript");
+        command.arg("-e").arg(format!(
+            "display notification {} with title \"flux-tui\"",
+            applescript_string(body)
+        ));
+        command
+    } else {
+        let mut command = tokio::process::Command::new("notify-send");
+        command.args(["--app-name=flux-tui", "flux-tui", body]);
+        command
+    };
+    // Anything printed would garble the screen
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    match command.spawn() {
+        Ok(mut child) => {
+            tokio::spawn(async move { child.wait().await });
+        }
+        Err(e) => tracing::debug!("Failed to send a desktop notification: {e}"),
+    }
+}
+
+/// A quoted AppleScript string
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::state::Tab;
+
+    #[test]
+    fn test_summary() {
+        let failures: Vec<String> = (1..=5).map(|i| format!("Kustomization a/k{i}")).collect();
+        assert_eq!(summary(&failures[..1]), "Kustomization a/k1 failed");
+        assert_eq!(
+            summary(&failures[..2]),
+            "2 resources failed: Kustomization a/k1, Kustomization a/k2"
+        );
+        assert_eq!(
+            summary(&failures),
+            "5 resources failed: Kustomization a/k1, Kustomization a/k2, \
+             Kustomization a/k3 and 2 more"
+        );
+    }
+
+    #[test]
+    fn test_applescript_string() {
+        assert_eq!(applescript_string(r#"a "b" \c"#), r#""a \"b\" \\c""#);
+    }
+
+    #[test]
+    fn test_update_rings_for_new_failures() {
+        let mut app = App::for_testing(Tab::Kustomizations, vec![], vec![], vec![]);
+        let notifier = Notifier::new(&Notifications {
+            bell: true,
+            desktop: false,
+            flash: false,
+        });
+        let mut out = Vec::new();
+
+        notifier.update(&mut app, &mut out).unwrap();
+        assert!(out.is_empty());
+
+        app.new_failures = vec!["HelmRelease apps/podinfo".to_string()];
+        notifier.update(&mut app, &mut out).unwrap();
+        assert_eq!(out, b"\x07");
+        assert!(app.new_failures.is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+//! tmux pane titles
+//!
+//! Under tmux the pane title names the cluster, the active tab and how many
+//! resources are failing, so a window of several flux-tui panes tells at a
+//! glance which is which. The title is only written when it changes.
+
+use std::io::{self, Write};
+
+use crate::{
+    app::state::App,
+    kubernetes::resources::{FluxResource, ResourceStatus},
+};
+
+/// Sets the pane title while running inside tmux
+#[derive(Debug, Default)]
+pub struct PaneTitle {
+    enabled: bool,
+
+    /// Title last written
+    last: Option<String>,
+}
+
+impl PaneTitle {
+    /// Enabled when the `TMUX` variable tmux sets for its panes is present
+    pub fn detect() -> Self {
+        Self {
+            enabled: std::env::var_os("TMUX").is_some_and(|value| !value.is_empty()),
+            last: None,
+        }
+    }
+
+    /// Write the title for the app's current state, if it changed
+    pub fn update(&mut self, app: &App, out: &mut impl Write) -> io::Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let cluster = app.cluster_filter.as_deref().unwrap_or(&app.cluster_name);
+        let title = title(cluster, app.tab.name(), failing(&app.all_resources()));
+        if self.last.as_ref() == Some(&title) {
+            return Ok(());
+        }
+        write!(out, "{}", escape(&title))?;
+        out.flush()?;
+        self.last = Some(title);
+        Ok(())
+    }
+}
+
+/// Pane title, e.g. "flux-tui · prod-eu · HelmReleases · 2 failing"
+fn title(cluster: &str, tab: &str, failing: usize) -> String {
+    let mut title = format!("flux-tui · {cluster} · {tab}");
+    if failing > 0 {
+        title.push_str(&format!(" · {failing} failing"));
+    }
+    title
+}
+
+/// Resources that are failed or stalled and not suspended
+fn failing(resources: &[&dyn FluxResource]) -> usize {
+    resources
+        .iter()
+        .filter(|r| {
+            !r.is_suspended()
+                && matches!(r.status(), ResourceStatus::Failed | ResourceStatus::Stalled)
+        })
+        .count()
+}
+
+/// The OSC 2 sequence tmux takes as the pane title, without control
+/// characters that would end it early
+fn escape(title: &str) -> String {
+    let title: String = title.chars().filter(|c| !c.is_control()).collect();
+    format!("\x1b]2;{title}\x1b\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{app::state::Tab, kubernetes::resources::Kustomization};
+
+    #[test]
+    fn test_title() {
+        assert_eq!(
+            title("prod-eu", "HelmReleases", 2),
+            "flux-tui · prod-eu · HelmReleases · 2 failing"
+        );
+        assert_eq!(
+            title("dev", "Kustomizations", 0),
+            "flux-tui · dev · Kustomizations"
+        );
+    }
+
+    #[test]
+    fn test_failing() {
+        let failed = Kustomization {
+            status: ResourceStatus::Failed,
+            ..Default::default()
+        };
+        let suspended = Kustomization {
+            status: ResourceStatus::Stalled,
+            suspended: true,
+            ..Default::default()
+        };
+        let ready = Kustomization {
+            status: ResourceStatus::Ready,
+            ..Default::default()
+        };
+        assert_eq!(failing(&[&failed, &suspended, &ready]), 1);
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a\x1bb\nc"), "\x1b]2;abc\x1b\\");
+    }
+
+    #[test]
+    fn test_update_writes_changes_only() {
+        let app = App::for_testing(Tab::HelmReleases, Vec::new(), Vec::new(), Vec::new());
+        let mut pane = PaneTitle {
+            enabled: true,
+            last: None,
+        };
+        let mut out = Vec::new();
+        pane.update(&app, &mut out).unwrap();
+        assert!(String::from_utf8_lossy(&out).starts_with("\x1b]2;flux-tui · "));
+
+        out.clear();
+        pane.update(&app, &mut out).unwrap();
+        assert!(out.is_empty());
+
+        let mut disabled = PaneTitle::default();
+        disabled.update(&app, &mut out).unwrap();
+        assert!(out.is_empty());
+    }
+}
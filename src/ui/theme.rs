@@ -1,198 +1,482 @@
 //! Theme and color definitions
 //!
-//! Uses a minimalist, modern color palette inspired by Tailwind CSS.
+//! The built-in dark theme uses a minimalist palette inspired by Tailwind CSS;
+//! the light theme uses darker shades of it that stay readable on a light
+//! background. A theme file can start from either and override single colors:
+//!
+//! ```toml
+//! base = "light"
+//!
+//! [status]
+//! failed = "#b91c1c"
+//!
+//! [ui]
+//! text = "black"
+//! ```
+//!
+//! With `NO_COLOR` set or `--no-color`, every color is the terminal default
+//! and selections are shown reversed instead.
 
+use std::{collections::HashMap, path::Path, str::FromStr};
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
 use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
 
-/// Status colors for resource states
-pub mod status {
-    use super::*;
+/// Colors for resource states
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusColors {
+    /// Ready/Success status
+    pub ready: Color,
+
+    /// Failed/Error status
+    pub failed: Color,
 
-    /// Ready/Success status (green)
-    pub const READY: Color = Color::Rgb(34, 197, 94); // green-500
+    /// Reconciling/In-progress status
+    pub reconciling: Color,
 
-    /// Failed/Error status (red)
-    pub const FAILED: Color = Color::Rgb(239, 68, 68); // red-500
+    /// Stalled status
+    pub stalled: Color,
 
-    /// Reconciling/In-progress status (yellow/amber)
-    pub const RECONCILING: Color = Color::Rgb(245, 158, 11); // amber-500
+    /// Suspended status
+    pub suspended: Color,
 
-    /// Suspended status (gray)
-    pub const SUSPENDED: Color = Color::Rgb(107, 114, 128); // gray-500
+    /// Pending status, no status reported yet
+    pub pending: Color,
 
-    /// Unknown status (slate)
-    pub const UNKNOWN: Color = Color::Rgb(148, 163, 184); // slate-400
+    /// Unknown status
+    pub unknown: Color,
 }
 
 /// UI element colors
-pub mod ui {
-    use super::*;
-
-    /// Primary accent color (blue)
-    pub const PRIMARY: Color = Color::Rgb(59, 130, 246); // blue-500
-
-    /// Secondary/muted color
-    #[allow(dead_code)]
-    pub const SECONDARY: Color = Color::Rgb(148, 163, 184); // slate-400
-
-    /// Background color
-    #[allow(dead_code)]
-    pub const BG: Color = Color::Reset;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UiColors {
+    /// Primary accent color
+    pub primary: Color,
 
     /// Border color
-    pub const BORDER: Color = Color::Rgb(71, 85, 105); // slate-600
+    pub border: Color,
 
     /// Highlighted border
-    pub const BORDER_HIGHLIGHT: Color = Color::Rgb(59, 130, 246); // blue-500
+    pub border_highlight: Color,
 
     /// Text color
-    pub const TEXT: Color = Color::Rgb(226, 232, 240); // slate-200
+    pub text: Color,
 
     /// Muted text color
-    pub const TEXT_MUTED: Color = Color::Rgb(148, 163, 184); // slate-400
+    pub text_muted: Color,
 
     /// Selection background
-    pub const SELECTION_BG: Color = Color::Rgb(30, 41, 59); // slate-800
+    pub selection_bg: Color,
 
     /// Tab active background
-    pub const TAB_ACTIVE_BG: Color = Color::Rgb(30, 58, 138); // blue-900
+    pub tab_active_bg: Color,
 
     /// Header background
-    pub const HEADER_BG: Color = Color::Rgb(15, 23, 42); // slate-900
+    pub header_bg: Color,
 }
 
-/// Status icons
-pub mod icons {
-    /// Ready icon
-    pub const READY: &str = "✓";
+/// Colors used by every widget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub status: StatusColors,
+    pub ui: UiColors,
 
-    /// Failed icon
-    pub const FAILED: &str = "✗";
+    /// No colors at all; highlights use reverse video
+    pub monochrome: bool,
+}
 
-    /// Reconciling icon
-    pub const RECONCILING: &str = "●";
+/// Theme file contents: a base theme and colors overriding it
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ThemeFile {
+    base: Option<String>,
+    status: HashMap<String, String>,
+    ui: HashMap<String, String>,
+}
 
-    /// Suspended icon
-    pub const SUSPENDED: &str = "⏸";
+impl Theme {
+    /// Default theme, for dark terminals
+    pub fn dark() -> Self {
+        Self {
+            status: StatusColors {
+                ready: Color::Rgb(34, 197, 94),        // green-500
+                failed: Color::Rgb(239, 68, 68),       // red-500
+                reconciling: Color::Rgb(245, 158, 11), // amber-500
+                stalled: Color::Rgb(234, 88, 12),      // orange-600
+                suspended: Color::Rgb(107, 114, 128),  // gray-500
+                pending: Color::Rgb(56, 189, 248),     // sky-400
+                unknown: Color::Rgb(148, 163, 184),    // slate-400
+            },
+            ui: UiColors {
+                primary: Color::Rgb(59, 130, 246),          // blue-500
+                border: Color::Rgb(71, 85, 105),            // slate-600
+                border_highlight: Color::Rgb(59, 130, 246), // blue-500
+                text: Color::Rgb(226, 232, 240),            // slate-200
+                text_muted: Color::Rgb(148, 163, 184),      // slate-400
+                selection_bg: Color::Rgb(30, 41, 59),       // slate-800
+                tab_active_bg: Color::Rgb(30, 58, 138),     // blue-900
+                header_bg: Color::Rgb(15, 23, 42),          // slate-900
+            },
+            monochrome: false,
+        }
+    }
 
-    /// Unknown icon
-    pub const UNKNOWN: &str = "?";
-}
+    /// Theme for light terminals
+    pub fn light() -> Self {
+        Self {
+            status: StatusColors {
+                ready: Color::Rgb(22, 163, 74),       // green-600
+                failed: Color::Rgb(220, 38, 38),      // red-600
+                reconciling: Color::Rgb(217, 119, 6), // amber-600
+                stalled: Color::Rgb(194, 65, 12),     // orange-700
+                suspended: Color::Rgb(107, 114, 128), // gray-500
+                pending: Color::Rgb(2, 132, 199),     // sky-600
+                unknown: Color::Rgb(100, 116, 139),   // slate-500
+            },
+            ui: UiColors {
+                primary: Color::Rgb(37, 99, 235),          // blue-600
+                border: Color::Rgb(203, 213, 225),         // slate-300
+                border_highlight: Color::Rgb(37, 99, 235), // blue-600
+                text: Color::Rgb(30, 41, 59),              // slate-800
+                text_muted: Color::Rgb(100, 116, 139),     // slate-500
+                selection_bg: Color::Rgb(226, 232, 240),   // slate-200
+                tab_active_bg: Color::Rgb(219, 234, 254),  // blue-100
+                header_bg: Color::Rgb(241, 245, 249),      // slate-100
+            },
+            monochrome: false,
+        }
+    }
 
-/// Pre-defined styles for common UI elements
-pub mod styles {
-    use super::*;
+    /// Theme without colors, for NO_COLOR and monochrome terminals
+    pub fn monochrome() -> Self {
+        let reset = Color::Reset;
+        Self {
+            status: StatusColors {
+                ready: reset,
+                failed: reset,
+                reconciling: reset,
+                stalled: reset,
+                suspended: reset,
+                pending: reset,
+                unknown: reset,
+            },
+            ui: UiColors {
+                primary: reset,
+                border: reset,
+                border_highlight: reset,
+                text: reset,
+                text_muted: reset,
+                selection_bg: reset,
+                tab_active_bg: reset,
+                header_bg: reset,
+            },
+            monochrome: true,
+        }
+    }
+
+    /// Load a theme by name ("dark", "light") or from a TOML file
+    pub fn load(spec: Option<&str>) -> Result<Self> {
+        match spec {
+            None | Some("dark") => Ok(Self::dark()),
+            Some("light") => Ok(Self::light()),
+            Some(path) => {
+                let contents = std::fs::read_to_string(Path::new(path))
+                    .wrap_err_with(|| format!("Failed to read theme {path}"))?;
+                Self::from_toml(&contents).wrap_err_with(|| format!("Invalid theme {path}"))
+            }
+        }
+    }
+
+    /// The configured theme, or no colors at all when they are turned off
+    pub fn resolve(spec: Option<&str>, no_color: bool) -> Result<Self> {
+        if no_color {
+            Ok(Self::monochrome())
+        } else {
+            Self::load(spec)
+        }
+    }
+
+    /// Parse a theme file
+    pub fn from_toml(contents: &str) -> Result<Self> {
+        let file: ThemeFile = toml::from_str(contents)?;
+        let mut theme = match file.base.as_deref() {
+            None | Some("dark") => Self::dark(),
+            Some("light") => Self::light(),
+            Some(other) => return Err(eyre!("Unknown base theme \"{other}\"")),
+        };
+        for (name, value) in &file.status {
+            *theme.status_color(name)? = parse_color(value)?;
+        }
+        for (name, value) in &file.ui {
+            *theme.ui_color(name)? = parse_color(value)?;
+        }
+        Ok(theme)
+    }
+
+    /// Status color by its name in a theme file
+    fn status_color(&mut self, name: &str) -> Result<&mut Color> {
+        let status = &mut self.status;
+        Ok(match name {
+            "ready" => &mut status.ready,
+            "failed" => &mut status.failed,
+            "reconciling" => &mut status.reconciling,
+            "stalled" => &mut status.stalled,
+            "suspended" => &mut status.suspended,
+            "pending" => &mut status.pending,
+            "unknown" => &mut status.unknown,
+            _ => return Err(eyre!("Unknown status color \"{name}\"")),
+        })
+    }
+
+    /// UI color by its name in a theme file
+    fn ui_color(&mut self, name: &str) -> Result<&mut Color> {
+        let ui = &mut self.ui;
+        Ok(match name {
+            "primary" => &mut ui.primary,
+            "border" => &mut ui.border,
+            "border_highlight" => &mut ui.border_highlight,
+            "text" => &mut ui.text,
+            "text_muted" => &mut ui.text_muted,
+            "selection_bg" => &mut ui.selection_bg,
+            "tab_active_bg" => &mut ui.tab_active_bg,
+            "header_bg" => &mut ui.header_bg,
+            _ => return Err(eyre!("Unknown ui color \"{name}\"")),
+        })
+    }
 
     /// Default text style
-    pub fn text() -> Style {
-        Style::default().fg(ui::TEXT)
+    pub fn text(&self) -> Style {
+        Style::default().fg(self.ui.text)
     }
 
     /// Muted text style
-    pub fn muted() -> Style {
-        Style::default().fg(ui::TEXT_MUTED)
+    pub fn muted(&self) -> Style {
+        Style::default().fg(self.ui.text_muted)
     }
 
     /// Header style
-    pub fn header() -> Style {
+    pub fn header(&self) -> Style {
         Style::default()
-            .fg(ui::TEXT)
-            .bg(ui::HEADER_BG)
+            .fg(self.ui.text)
+            .bg(self.ui.header_bg)
             .add_modifier(Modifier::BOLD)
     }
 
     /// Selected row style
-    pub fn selected() -> Style {
-        Style::default().bg(ui::SELECTION_BG).fg(ui::TEXT)
+    pub fn selected(&self) -> Style {
+        self.highlight(Style::default().bg(self.ui.selection_bg).fg(self.ui.text))
     }
 
     /// Border style
-    pub fn border() -> Style {
-        Style::default().fg(ui::BORDER)
+    pub fn border(&self) -> Style {
+        Style::default().fg(self.ui.border)
     }
 
     /// Highlighted border style
-    pub fn border_highlight() -> Style {
-        Style::default().fg(ui::BORDER_HIGHLIGHT)
+    pub fn border_highlight(&self) -> Style {
+        Style::default().fg(self.ui.border_highlight)
     }
 
     /// Tab style (inactive)
-    pub fn tab() -> Style {
-        Style::default().fg(ui::TEXT_MUTED)
+    pub fn tab(&self) -> Style {
+        Style::default().fg(self.ui.text_muted)
     }
 
     /// Tab style (active)
-    pub fn tab_active() -> Style {
-        Style::default()
-            .fg(ui::TEXT)
-            .bg(ui::TAB_ACTIVE_BG)
-            .add_modifier(Modifier::BOLD)
+    pub fn tab_active(&self) -> Style {
+        self.highlight(
+            Style::default()
+                .fg(self.ui.text)
+                .bg(self.ui.tab_active_bg)
+                .add_modifier(Modifier::BOLD),
+        )
+    }
+
+    /// Header style while connected to a marked environment
+    pub fn banner(&self, color: &str) -> Style {
+        if self.monochrome {
+            return self.header().add_modifier(Modifier::REVERSED);
+        }
+        self.header()
+            .fg(Color::White)
+            .bg(parse_color(color).unwrap_or(Color::Red))
+    }
+
+    /// Header style while flashing for a new failure
+    pub fn flash(&self) -> Style {
+        self.highlight(self.header().fg(Color::White).bg(self.status.failed))
+    }
+
+    /// A background-colored style, reversed instead without colors
+    fn highlight(&self, style: Style) -> Style {
+        if self.monochrome {
+            style.add_modifier(Modifier::REVERSED)
+        } else {
+            style
+        }
     }
 
     /// Status style based on resource state
-    pub fn status_ready() -> Style {
-        Style::default().fg(status::READY)
+    pub fn status_ready(&self) -> Style {
+        Style::default().fg(self.status.ready)
+    }
+
+    pub fn status_failed(&self) -> Style {
+        Style::default().fg(self.status.failed)
     }
 
-    pub fn status_failed() -> Style {
-        Style::default().fg(status::FAILED)
+    pub fn status_reconciling(&self) -> Style {
+        Style::default().fg(self.status.reconciling)
     }
 
-    pub fn status_reconciling() -> Style {
-        Style::default().fg(status::RECONCILING)
+    pub fn status_stalled(&self) -> Style {
+        Style::default().fg(self.status.stalled)
     }
 
-    pub fn status_suspended() -> Style {
-        Style::default().fg(status::SUSPENDED)
+    pub fn status_suspended(&self) -> Style {
+        Style::default().fg(self.status.suspended)
     }
 
-    pub fn status_unknown() -> Style {
-        Style::default().fg(status::UNKNOWN)
+    pub fn status_pending(&self) -> Style {
+        Style::default().fg(self.status.pending)
+    }
+
+    pub fn status_unknown(&self) -> Style {
+        Style::default().fg(self.status.unknown)
     }
 
     /// Title style
-    pub fn title() -> Style {
+    pub fn title(&self) -> Style {
         Style::default()
-            .fg(ui::PRIMARY)
+            .fg(self.ui.primary)
             .add_modifier(Modifier::BOLD)
     }
 
     /// Keybinding key style
-    pub fn key() -> Style {
+    pub fn key(&self) -> Style {
         Style::default()
-            .fg(ui::PRIMARY)
+            .fg(self.ui.primary)
             .add_modifier(Modifier::BOLD)
     }
 
     /// Keybinding description style
-    pub fn key_desc() -> Style {
-        Style::default().fg(ui::TEXT_MUTED)
+    pub fn key_desc(&self) -> Style {
+        Style::default().fg(self.ui.text_muted)
     }
 }
 
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// Parse a color: a name ("red", "darkgray"), "#rrggbb" or a 256-color index
+pub fn parse_color(value: &str) -> Result<Color> {
+    Color::from_str(value).map_err(|_| eyre!("Invalid color \"{value}\""))
+}
+
+/// Status icons
+pub mod icons {
+    /// Ready icon
+    pub const READY: &str = "✓";
+
+    /// Failed icon
+    pub const FAILED: &str = "✗";
+
+    /// Reconciling icon
+    pub const RECONCILING: &str = "●";
+
+    /// Stalled icon
+    pub const STALLED: &str = "⊘";
+
+    /// Suspended icon
+    pub const SUSPENDED: &str = "⏸";
+
+    /// Suspended while failed icon
+    pub const SUSPENDED_FAILED: &str = "⊠";
+
+    /// Pending icon
+    pub const PENDING: &str = "○";
+
+    /// Unknown icon
+    pub const UNKNOWN: &str = "?";
+
+    /// Spec generation not yet observed by the controller
+    pub const GENERATION_LAG: &str = "⧗";
+
+    /// Resource has a local note
+    pub const NOTE: &str = "✎";
+
+    /// One refresh in a status timeline
+    pub const TIMELINE_TICK: &str = "▇";
+
+    /// Frames of the spinner shown while refreshing
+    pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_status_colors() {
-        assert_eq!(status::READY, Color::Rgb(34, 197, 94));
-        assert_eq!(status::FAILED, Color::Rgb(239, 68, 68));
-        assert_eq!(status::RECONCILING, Color::Rgb(245, 158, 11));
-        assert_eq!(status::SUSPENDED, Color::Rgb(107, 114, 128));
-        assert_eq!(status::UNKNOWN, Color::Rgb(148, 163, 184));
+    fn test_dark_colors() {
+        let theme = Theme::dark();
+        assert_eq!(theme.status.ready, Color::Rgb(34, 197, 94));
+        assert_eq!(theme.status.failed, Color::Rgb(239, 68, 68));
+        assert_eq!(theme.status.reconciling, Color::Rgb(245, 158, 11));
+        assert_eq!(theme.status.stalled, Color::Rgb(234, 88, 12));
+        assert_eq!(theme.status.suspended, Color::Rgb(107, 114, 128));
+        assert_eq!(theme.status.pending, Color::Rgb(56, 189, 248));
+        assert_eq!(theme.status.unknown, Color::Rgb(148, 163, 184));
+        assert_eq!(theme.ui.primary, Color::Rgb(59, 130, 246));
+        assert_eq!(theme.ui.border, Color::Rgb(71, 85, 105));
+        assert_eq!(theme.ui.border_highlight, Color::Rgb(59, 130, 246));
+        assert_eq!(theme.ui.text, Color::Rgb(226, 232, 240));
+        assert_eq!(theme.ui.text_muted, Color::Rgb(148, 163, 184));
+        assert_eq!(theme.ui.selection_bg, Color::Rgb(30, 41, 59));
+        assert_eq!(theme.ui.tab_active_bg, Color::Rgb(30, 58, 138));
+        assert_eq!(theme.ui.header_bg, Color::Rgb(15, 23, 42));
+        assert_eq!(Theme::default(), theme);
+    }
+
+    #[test]
+    fn test_load_builtin_themes() {
+        assert_eq!(Theme::load(None).unwrap(), Theme::dark());
+        assert_eq!(Theme::load(Some("dark")).unwrap(), Theme::dark());
+        assert_eq!(Theme::load(Some("light")).unwrap(), Theme::light());
+        assert!(Theme::load(Some("/nonexistent/theme.toml")).is_err());
+        assert_eq!(
+            Theme::resolve(Some("/nonexistent/theme.toml"), true).unwrap(),
+            Theme::monochrome()
+        );
     }
 
     #[test]
-    fn test_ui_colors() {
-        assert_eq!(ui::PRIMARY, Color::Rgb(59, 130, 246));
-        assert_eq!(ui::BORDER, Color::Rgb(71, 85, 105));
-        assert_eq!(ui::BORDER_HIGHLIGHT, Color::Rgb(59, 130, 246));
-        assert_eq!(ui::TEXT, Color::Rgb(226, 232, 240));
-        assert_eq!(ui::TEXT_MUTED, Color::Rgb(148, 163, 184));
-        assert_eq!(ui::SELECTION_BG, Color::Rgb(30, 41, 59));
-        assert_eq!(ui::TAB_ACTIVE_BG, Color::Rgb(30, 58, 138));
-        assert_eq!(ui::HEADER_BG, Color::Rgb(15, 23, 42));
+    fn test_theme_file_overrides_base() {
+        let theme = Theme::from_toml(
+            r##"
+            base = "light"
+
+            [status]
+            failed = "#b91c1c"
+
+            [ui]
+            text = "black"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(theme.status.failed, Color::Rgb(185, 28, 28));
+        assert_eq!(theme.ui.text, Color::Black);
+        assert_eq!(theme.status.ready, Theme::light().status.ready);
+
+        assert!(Theme::from_toml("base = \"solarized\"").is_err());
+        assert!(Theme::from_toml("[status]\nfailing = \"red\"").is_err());
+        assert!(Theme::from_toml("[ui]\ntext = \"not-a-color\"").is_err());
     }
 
     #[test]
@@ -200,89 +484,97 @@ mod tests {
         assert_eq!(icons::READY, "✓");
         assert_eq!(icons::FAILED, "✗");
         assert_eq!(icons::RECONCILING, "●");
+        assert_eq!(icons::STALLED, "⊘");
         assert_eq!(icons::SUSPENDED, "⏸");
+        assert_eq!(icons::SUSPENDED_FAILED, "⊠");
+        assert_eq!(icons::PENDING, "○");
         assert_eq!(icons::UNKNOWN, "?");
+        assert_eq!(icons::GENERATION_LAG, "⧗");
+        assert_eq!(icons::NOTE, "✎");
     }
 
     #[test]
     fn test_styles_text() {
-        let style = styles::text();
-        assert_eq!(style.fg, Some(ui::TEXT));
-    }
-
-    #[test]
-    fn test_styles_muted() {
-        let style = styles::muted();
-        assert_eq!(style.fg, Some(ui::TEXT_MUTED));
+        let theme = Theme::dark();
+        assert_eq!(theme.text().fg, Some(theme.ui.text));
+        assert_eq!(theme.muted().fg, Some(theme.ui.text_muted));
     }
 
     #[test]
     fn test_styles_header() {
-        let style = styles::header();
-        assert_eq!(style.fg, Some(ui::TEXT));
-        assert_eq!(style.bg, Some(ui::HEADER_BG));
+        let theme = Theme::dark();
+        let style = theme.header();
+        assert_eq!(style.fg, Some(theme.ui.text));
+        assert_eq!(style.bg, Some(theme.ui.header_bg));
         assert!(style.add_modifier.contains(Modifier::BOLD));
     }
 
     #[test]
     fn test_styles_selected() {
-        let style = styles::selected();
-        assert_eq!(style.bg, Some(ui::SELECTION_BG));
-        assert_eq!(style.fg, Some(ui::TEXT));
+        let theme = Theme::light();
+        let style = theme.selected();
+        assert_eq!(style.bg, Some(theme.ui.selection_bg));
+        assert_eq!(style.fg, Some(theme.ui.text));
     }
 
     #[test]
     fn test_styles_border() {
-        let style = styles::border();
-        assert_eq!(style.fg, Some(ui::BORDER));
-    }
-
-    #[test]
-    fn test_styles_border_highlight() {
-        let style = styles::border_highlight();
-        assert_eq!(style.fg, Some(ui::BORDER_HIGHLIGHT));
+        let theme = Theme::dark();
+        assert_eq!(theme.border().fg, Some(theme.ui.border));
+        assert_eq!(theme.border_highlight().fg, Some(theme.ui.border_highlight));
     }
 
     #[test]
     fn test_styles_tab() {
-        let style = styles::tab();
-        assert_eq!(style.fg, Some(ui::TEXT_MUTED));
-    }
-
-    #[test]
-    fn test_styles_tab_active() {
-        let style = styles::tab_active();
-        assert_eq!(style.fg, Some(ui::TEXT));
-        assert_eq!(style.bg, Some(ui::TAB_ACTIVE_BG));
+        let theme = Theme::dark();
+        assert_eq!(theme.tab().fg, Some(theme.ui.text_muted));
+        let style = theme.tab_active();
+        assert_eq!(style.fg, Some(theme.ui.text));
+        assert_eq!(style.bg, Some(theme.ui.tab_active_bg));
         assert!(style.add_modifier.contains(Modifier::BOLD));
     }
 
     #[test]
     fn test_styles_status() {
-        assert_eq!(styles::status_ready().fg, Some(status::READY));
-        assert_eq!(styles::status_failed().fg, Some(status::FAILED));
-        assert_eq!(styles::status_reconciling().fg, Some(status::RECONCILING));
-        assert_eq!(styles::status_suspended().fg, Some(status::SUSPENDED));
-        assert_eq!(styles::status_unknown().fg, Some(status::UNKNOWN));
-    }
-
-    #[test]
-    fn test_styles_title() {
-        let style = styles::title();
-        assert_eq!(style.fg, Some(ui::PRIMARY));
-        assert!(style.add_modifier.contains(Modifier::BOLD));
+        let theme = Theme::light();
+        assert_eq!(theme.status_ready().fg, Some(theme.status.ready));
+        assert_eq!(theme.status_failed().fg, Some(theme.status.failed));
+        assert_eq!(
+            theme.status_reconciling().fg,
+            Some(theme.status.reconciling)
+        );
+        assert_eq!(theme.status_stalled().fg, Some(theme.status.stalled));
+        assert_eq!(theme.status_suspended().fg, Some(theme.status.suspended));
+        assert_eq!(theme.status_pending().fg, Some(theme.status.pending));
+        assert_eq!(theme.status_unknown().fg, Some(theme.status.unknown));
     }
 
     #[test]
-    fn test_styles_key() {
-        let style = styles::key();
-        assert_eq!(style.fg, Some(ui::PRIMARY));
-        assert!(style.add_modifier.contains(Modifier::BOLD));
+    fn test_styles_title_and_keys() {
+        let theme = Theme::dark();
+        assert_eq!(theme.title().fg, Some(theme.ui.primary));
+        assert!(theme.title().add_modifier.contains(Modifier::BOLD));
+        assert_eq!(theme.key().fg, Some(theme.ui.primary));
+        assert!(theme.key().add_modifier.contains(Modifier::BOLD));
+        assert_eq!(theme.key_desc().fg, Some(theme.ui.text_muted));
     }
 
     #[test]
-    fn test_styles_key_desc() {
-        let style = styles::key_desc();
-        assert_eq!(style.fg, Some(ui::TEXT_MUTED));
+    fn test_monochrome() {
+        let theme = Theme::monochrome();
+        assert_eq!(theme.status_failed().fg, Some(Color::Reset));
+        assert_eq!(theme.header().bg, Some(Color::Reset));
+        assert!(theme.selected().add_modifier.contains(Modifier::REVERSED));
+        assert!(theme.tab_active().add_modifier.contains(Modifier::REVERSED));
+        assert_eq!(theme.banner("red").bg, Some(Color::Reset));
+        assert!(theme
+            .banner("red")
+            .add_modifier
+            .contains(Modifier::REVERSED));
+
+        let dark = Theme::dark();
+        assert!(!dark.selected().add_modifier.contains(Modifier::REVERSED));
+        assert_eq!(dark.banner("red").bg, Some(Color::Red));
+        assert_eq!(dark.banner("nope").bg, Some(Color::Red));
     }
 }
@@ -0,0 +1,92 @@
+//! Full status message viewer
+//!
+//! Tables cut status messages short; this popup shows the whole message,
+//! word-wrapped at a fixed width so the line count (and with it the scroll
+//! range) doesn't depend on the terminal size.
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Clear, Padding, Paragraph},
+};
+
+use crate::ui::{layout::popup_area, theme::Theme};
+
+/// Columns the message is wrapped at
+pub const WIDTH: usize = 72;
+
+/// Word-wrap text at `width` columns, keeping its line breaks
+///
+/// Words longer than a line are split.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            let len = line.chars().count();
+            if len > 0 && len + 1 + word.len() <= width {
+                line.push(' ');
+            } else if len > 0 {
+                lines.push(std::mem::take(&mut line));
+            }
+            while word.len() > width {
+                lines.push(word.drain(..width).collect());
+            }
+            line.extend(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Draw the message popup, scrolled by `scroll` lines
+pub fn draw(frame: &mut Frame, title: &str, message: &str, scroll: u16, theme: &Theme) {
+    let column = popup_area(frame.area(), 100, 60);
+    let width = (WIDTH as u16 + 4).min(column.width);
+    let area = Rect {
+        x: column.x + (column.width - width) / 2,
+        width,
+        ..column
+    };
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" {title} "))
+        .title_style(theme.title())
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+
+    let lines: Vec<Line> = if message.trim().is_empty() {
+        vec![Line::styled("No status message", theme.muted())]
+    } else {
+        let text_width = usize::from(area.width.saturating_sub(4));
+        wrap(message, text_width.min(WIDTH))
+            .into_iter()
+            .map(|line| Line::styled(line, theme.text()))
+            .collect()
+    };
+
+    let paragraph = Paragraph::new(lines)
+        .block(block.padding(Padding::horizontal(1)))
+        .scroll((scroll, 0));
+
+    frame.render_widget(paragraph, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(
+            wrap("kustomize build failed: accumulating resources", 20),
+            vec!["kustomize build", "failed: accumulating", "resources"]
+        );
+        assert_eq!(wrap("first\n\nthird", 20), vec!["first", "", "third"]);
+        assert_eq!(wrap("abcdefgh ij", 3), vec!["abc", "def", "gh", "ij"]);
+        assert_eq!(wrap("", 10), Vec::<String>::new());
+    }
+}
@@ -1,5 +1,7 @@
 //! UI widgets module
 
+pub mod message;
 pub mod resource_table;
 pub mod status_bar;
 pub mod tabs;
+pub mod yaml;
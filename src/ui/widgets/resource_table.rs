@@ -2,153 +2,245 @@
 
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, Cell, Row, Table, TableState},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
 };
 
-use crate::kubernetes::resources::{HelmChart, HelmRelease, Kustomization, ResourceStatus};
-use crate::ui::theme::{icons, styles};
+use chrono::{DateTime, Utc};
 
-/// Draw the Kustomizations table
-pub fn draw_kustomizations(
+use crate::app::{
+    group::{Group, TableRow},
+    sort::SortState,
+    state_file::StateFile,
+    streaks::{FailureStreaks, PERSISTENT_STREAK},
+};
+use crate::kubernetes::resources::{
+    format_age,
+    kind::{truncate, Column, Emphasis, Field, Width},
+    FluxResource, ResourceKind, ResourceStatus,
+};
+use crate::ui::theme::{icons, Theme};
+
+/// Draw the table of a resource kind, with the columns the kind defines
+pub fn draw<T: ResourceKind>(
     frame: &mut Frame,
     area: Rect,
-    kustomizations: &[Kustomization],
-    selected: usize,
+    items: &[TableRow<T>],
+    view: &TableView,
 ) {
-    let header_cells = ["NAME", "NAMESPACE", "READY", "STATUS", "REVISION", "SUS"]
-        .iter()
-        .map(|h| Cell::from(*h).style(styles::header()));
-    let header = Row::new(header_cells).height(1);
+    let columns = T::columns();
+    let titles: Vec<&'static str> = columns.iter().map(Column::title).collect();
+    let header = header_row(&titles, view);
 
-    let rows: Vec<Row> = kustomizations
+    let now = Utc::now();
+    let window = view.window(items, area);
+    let rows: Vec<Row> = window
         .iter()
-        .map(|k| {
-            let (icon, style) = status_icon_style(&k.status);
-            Row::new([
-                Cell::from(k.name.clone()),
-                Cell::from(k.namespace.clone()),
-                Cell::from(icon).style(style),
-                Cell::from(truncate(&k.status_message, 30)),
-                Cell::from(truncate(
-                    &k.revision.clone().unwrap_or_else(|| "-".to_string()),
-                    15,
-                )),
-                Cell::from(if k.suspended { "⏸" } else { "-" }),
-            ])
+        .map(|item| match item {
+            // Drawn across the columns once the table is rendered
+            TableRow::Group(_) => Row::default(),
+            TableRow::Resource(item) => row(
+                view.show_cluster.then_some(item.cluster()),
+                view.shown(
+                    &titles,
+                    columns.iter().map(|column| cell(column, *item, view, now)),
+                ),
+            ),
         })
         .collect();
 
-    let widths = [
-        Constraint::Min(20),
-        Constraint::Min(15),
-        Constraint::Length(5),
-        Constraint::Min(30),
-        Constraint::Min(15),
-        Constraint::Length(3),
-    ];
-
-    render_table(
-        frame,
-        area,
-        header,
-        rows,
-        widths,
-        selected,
-        "Kustomizations",
+    let widths = column_widths(
+        &view.shown(
+            &titles,
+            columns.iter().map(|column| match column.width() {
+                Width::Min(n) => Constraint::Min(n),
+                Width::Length(n) => Constraint::Length(n),
+            }),
+        ),
+        view.show_cluster,
     );
+
+    render_table(frame, area, header, rows, widths, view);
+
+    let first = view.first_row(area);
+    for (line, item) in window.iter().enumerate().take(visible_rows(area)) {
+        if let TableRow::Group(group) = item {
+            let selected = first + line == view.selected;
+            draw_group(frame, area, line, group, selected, view.theme);
+        }
+    }
 }
 
-/// Draw the HelmReleases table
-pub fn draw_helm_releases(
+/// Draw a source group header across the table, on the given line of rows
+fn draw_group(
     frame: &mut Frame,
     area: Rect,
-    helm_releases: &[HelmRelease],
-    selected: usize,
+    line: usize,
+    group: &Group,
+    selected: bool,
+    theme: &Theme,
 ) {
-    let header_cells = [
-        "NAME",
-        "NAMESPACE",
-        "READY",
-        "STATUS",
-        "CHART",
-        "VERSION",
-        "SUS",
-    ]
-    .iter()
-    .map(|h| Cell::from(*h).style(styles::header()));
-    let header = Row::new(header_cells).height(1);
-
-    let rows: Vec<Row> = helm_releases
-        .iter()
-        .map(|h| {
-            let (icon, style) = status_icon_style(&h.status);
-            Row::new([
-                Cell::from(h.name.clone()),
-                Cell::from(h.namespace.clone()),
-                Cell::from(icon).style(style),
-                Cell::from(truncate(&h.status_message, 25)),
-                Cell::from(h.chart.clone()),
-                Cell::from(h.version.clone().unwrap_or_else(|| "-".to_string())),
-                Cell::from(if h.suspended { "⏸" } else { "-" }),
-            ])
-        })
-        .collect();
+    // Inside the borders, below the header
+    let row = Rect::new(
+        area.x + 1,
+        area.y + 2 + line as u16,
+        area.width.saturating_sub(2),
+        1,
+    );
+    let (symbol, style) = if selected {
+        ("▶ ", theme.selected())
+    } else {
+        ("  ", theme.title())
+    };
+    let style = match group.failing {
+        0 => style,
+        _ => style.patch(theme.status_failed()),
+    };
+    let title = Paragraph::new(format!("{symbol}{}", group.title())).style(style);
+    frame.render_widget(title, row);
+}
 
-    let widths = [
-        Constraint::Min(20),
-        Constraint::Min(15),
-        Constraint::Length(5),
-        Constraint::Min(25),
-        Constraint::Min(15),
-        Constraint::Min(10),
-        Constraint::Length(3),
-    ];
-
-    render_table(frame, area, header, rows, widths, selected, "HelmReleases");
+/// The cell of a column for a resource
+fn cell<T: ResourceKind>(
+    column: &Column<T>,
+    resource: &T,
+    view: &TableView,
+    now: DateTime<Utc>,
+) -> Cell<'static> {
+    match column {
+        Column::Name => name_cell(resource, view),
+        Column::Namespace => Cell::from(resource.namespace().to_string()),
+        Column::Ready => ready_cell(resource, view),
+        Column::Status(max_len) => status_cell(resource, *max_len, view.theme),
+        Column::Suspended => Cell::from(if resource.is_suspended() { "⏸" } else { "-" }),
+        Column::Age => age_cell(resource),
+        Column::Field { value, .. } => field_cell(value(resource, now), view.theme),
+    }
 }
 
-/// Draw the HelmCharts table
-pub fn draw_helm_charts(frame: &mut Frame, area: Rect, helm_charts: &[HelmChart], selected: usize) {
-    let header_cells = [
-        "NAME",
-        "NAMESPACE",
-        "READY",
-        "STATUS",
-        "CHART",
-        "VERSION",
-        "SOURCE",
-    ]
-    .iter()
-    .map(|h| Cell::from(*h).style(styles::header()));
-    let header = Row::new(header_cells).height(1);
-
-    let rows: Vec<Row> = helm_charts
-        .iter()
-        .map(|h| {
-            let (icon, style) = status_icon_style(&h.status);
-            Row::new([
-                Cell::from(h.name.clone()),
-                Cell::from(h.namespace.clone()),
-                Cell::from(icon).style(style),
-                Cell::from(truncate(&h.status_message, 25)),
-                Cell::from(h.chart.clone()),
-                Cell::from(h.version.clone().unwrap_or_else(|| "-".to_string())),
-                Cell::from(truncate(&h.source_ref, 20)),
-            ])
-        })
-        .collect();
+/// A kind-specific cell, styled by its emphasis
+fn field_cell(field: Field, theme: &Theme) -> Cell<'static> {
+    let cell = Cell::from(field.text);
+    match field.emphasis {
+        Emphasis::None => cell,
+        Emphasis::Attention => cell.style(theme.status_reconciling()),
+        Emphasis::Warning => cell.style(theme.status_failed()),
+    }
+}
+
+/// Build the header row, with a leading CLUSTER column in multi-cluster mode
+/// and an arrow on the sorted column
+fn header_row(titles: &[&'static str], view: &TableView) -> Row<'static> {
+    let cluster = view.show_cluster.then_some("CLUSTER");
+    let titles = view.shown(titles, titles.iter().copied());
+    let cells = cluster.into_iter().chain(titles).map(|h| {
+        let title = match view.sort.indicator(h) {
+            Some(arrow) => format!("{h} {arrow}"),
+            None => h.to_string(),
+        };
+        Cell::from(title).style(view.theme.header())
+    });
+    Row::new(cells).height(1)
+}
+
+/// Build a table row, with a leading cluster cell when a cluster is given
+fn row<'a>(cluster: Option<&str>, cells: impl IntoIterator<Item = Cell<'a>>) -> Row<'a> {
+    let cluster = cluster.map(|c| Cell::from(c.to_string()));
+    Row::new(cluster.into_iter().chain(cells))
+}
+
+/// Column widths, with room for a leading CLUSTER column in multi-cluster mode
+fn column_widths(widths: &[Constraint], show_cluster: bool) -> Vec<Constraint> {
+    let cluster = show_cluster.then_some(Constraint::Min(12));
+    cluster.into_iter().chain(widths.iter().copied()).collect()
+}
+
+/// Rows built past the bottom of the table, so a resize before the next
+/// frame still fills it
+const ROW_MARGIN: usize = 5;
+
+/// Number of resource rows a table area shows (inside the borders, below the header)
+pub fn visible_rows(area: Rect) -> usize {
+    area.height.saturating_sub(3) as usize
+}
+
+/// First row to show: the previous offset, scrolled just far enough to keep
+/// the selection on screen
+pub fn scroll_offset(offset: usize, selected: usize, rows: usize) -> usize {
+    offset
+        .min(selected)
+        .max((selected + 1).saturating_sub(rows.max(1)))
+}
 
-    let widths = [
-        Constraint::Min(20),
-        Constraint::Min(15),
-        Constraint::Length(5),
-        Constraint::Min(25),
-        Constraint::Min(15),
-        Constraint::Min(10),
-        Constraint::Min(20),
-    ];
-
-    render_table(frame, area, header, rows, widths, selected, "HelmCharts");
+/// Display options shared by all resource tables
+pub struct TableView<'a> {
+    /// Selected row index (within the visible rows)
+    pub selected: usize,
+
+    /// First row shown, as last scrolled to
+    pub offset: usize,
+
+    /// Show a leading CLUSTER column (multi-cluster mode)
+    pub show_cluster: bool,
+
+    /// Fuzzy filter query shown in the table title
+    pub filter: &'a str,
+
+    /// Whether the filter is being typed
+    pub editing: bool,
+
+    /// Failure streaks shown next to the status icon
+    pub streaks: &'a FailureStreaks,
+
+    /// Local notes, flagged next to the name, and acknowledgements
+    pub state: &'a StateFile,
+
+    /// Sort state, indicated in the header
+    pub sort: SortState,
+
+    /// Source filter label shown in the table title
+    pub source: Option<String>,
+
+    /// Status filter label shown in the table title
+    pub status: Option<&'static str>,
+
+    /// Colors to draw with
+    pub theme: &'a Theme,
+
+    /// Column titles to leave out (NAME is always shown)
+    pub hidden_columns: &'a [String],
+}
+
+impl TableView<'_> {
+    /// The items of the columns with these titles that are not hidden
+    fn shown<T>(&self, titles: &[&str], items: impl IntoIterator<Item = T>) -> Vec<T> {
+        titles
+            .iter()
+            .zip(items)
+            .filter(|(title, _)| {
+                **title == "NAME"
+                    || !self
+                        .hidden_columns
+                        .iter()
+                        .any(|hidden| hidden.eq_ignore_ascii_case(title))
+            })
+            .map(|(_, item)| item)
+            .collect()
+    }
+
+    /// First row shown in a table area
+    fn first_row(&self, area: Rect) -> usize {
+        scroll_offset(self.offset, self.selected, visible_rows(area))
+    }
+
+    /// The resources that get a row: those on screen, plus a margin
+    ///
+    /// Building cells for every resource each frame is slow with thousands of
+    /// them, so rows outside the window are never built.
+    fn window<'b, T>(&self, items: &'b [T], area: Rect) -> &'b [T] {
+        let start = self.first_row(area).min(items.len());
+        let end = (start + visible_rows(area) + ROW_MARGIN).min(items.len());
+        &items[start..end]
+    }
 }
 
 /// Render a table with the given configuration
@@ -158,48 +250,366 @@ fn render_table<'a>(
     header: Row<'a>,
     rows: Vec<Row<'a>>,
     widths: impl IntoIterator<Item = Constraint>,
-    selected: usize,
-    _title: &str,
+    view: &TableView,
 ) {
+    let theme = view.theme;
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(theme.border_highlight());
+    if let Some(source) = &view.source {
+        block = block
+            .title(format!(" {source} "))
+            .title_style(theme.title());
+    }
+    if let Some(status) = view.status {
+        block = block
+            .title(format!(" status: {status} "))
+            .title_style(theme.title());
+    }
+    if let Some(title) = filter_title(view.filter, view.editing) {
+        block = block.title(title).title_style(theme.title());
+    }
+
     let table = Table::new(rows, widths)
         .header(header)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(styles::border_highlight()),
-        )
-        .row_highlight_style(styles::selected())
+        .block(block)
+        .row_highlight_style(theme.selected())
         .highlight_symbol("▶ ");
 
+    // Rows start at the first visible one, so the table itself never scrolls
     let mut state = TableState::default();
-    state.select(Some(selected));
+    state.select(Some(view.selected - view.first_row(area)));
 
     frame.render_stateful_widget(table, area, &mut state);
 }
 
+/// Index of the row under a terminal row, in a table scrolled to `offset`
+pub fn row_at(area: Rect, offset: usize, row: u16) -> Option<usize> {
+    // Below the top border and the header
+    let first = area.y + 2;
+    let line = row.checked_sub(first)? as usize;
+    if line >= visible_rows(area) {
+        return None;
+    }
+    Some(offset + line)
+}
+
+/// Table title showing the fuzzy filter, with a cursor while typing
+fn filter_title(filter: &str, editing: bool) -> Option<String> {
+    match (filter, editing) {
+        (_, true) => Some(format!(" /{filter}▏ ")),
+        ("", false) => None,
+        (_, false) => Some(format!(" /{filter} ")),
+    }
+}
+
+/// READY cell: the status icon, followed by the failure streak once it repeats
+///
+/// Acknowledged resources are muted.
+fn ready_cell(resource: &dyn FluxResource, view: &TableView) -> Cell<'static> {
+    if view
+        .state
+        .acknowledged_until(resource, Utc::now())
+        .is_some()
+    {
+        let (icon, _) = status_icon_style(resource.status(), view.theme);
+        return Cell::from(icon).style(view.theme.muted());
+    }
+    streak_cell(resource.status(), view.streaks.get(resource), view.theme)
+}
+
+/// AGE cell: time since creation, like kubectl (e.g. 3d, 12h)
+fn age_cell(resource: &dyn FluxResource) -> Cell<'static> {
+    let age = resource
+        .created_at()
+        .map(|created| format_age(created, Utc::now()))
+        .unwrap_or_else(|| "-".to_string());
+    Cell::from(age)
+}
+
+/// Status icon, followed by the failure streak once it repeats
+///
+/// Persistent streaks are shown in bold so they stand out from one-off blips.
+fn streak_cell(status: &ResourceStatus, streak: u32, theme: &Theme) -> Cell<'static> {
+    let (icon, style) = status_icon_style(status, theme);
+    match streak {
+        0 | 1 => Cell::from(icon).style(style),
+        n if n < PERSISTENT_STREAK => Cell::from(format!("{icon}{n}")).style(style),
+        n => Cell::from(format!("{icon}{n}")).style(style.add_modifier(Modifier::BOLD)),
+    }
+}
+
+/// NAME cell, with a badge when the resource has a local note
+fn name_cell(resource: &dyn FluxResource, view: &TableView) -> Cell<'static> {
+    match view.state.note(resource) {
+        Some(_) => Cell::from(Line::from(vec![
+            Span::raw(resource.name().to_string()),
+            Span::styled(format!(" {}", icons::NOTE), view.theme.status_reconciling()),
+        ])),
+        None => Cell::from(resource.name().to_string()),
+    }
+}
+
 /// Get the status icon and style for a resource status
-fn status_icon_style(status: &ResourceStatus) -> (&'static str, Style) {
+pub fn status_icon_style(status: &ResourceStatus, theme: &Theme) -> (&'static str, Style) {
     match status {
-        ResourceStatus::Ready => (icons::READY, styles::status_ready()),
-        ResourceStatus::Failed => (icons::FAILED, styles::status_failed()),
-        ResourceStatus::Reconciling => (icons::RECONCILING, styles::status_reconciling()),
-        ResourceStatus::Suspended => (icons::SUSPENDED, styles::status_suspended()),
-        ResourceStatus::Unknown => (icons::UNKNOWN, styles::status_unknown()),
+        ResourceStatus::Ready => (icons::READY, theme.status_ready()),
+        ResourceStatus::Failed => (icons::FAILED, theme.status_failed()),
+        ResourceStatus::Reconciling => (icons::RECONCILING, theme.status_reconciling()),
+        ResourceStatus::Stalled => (icons::STALLED, theme.status_stalled()),
+        ResourceStatus::Suspended => (icons::SUSPENDED, theme.status_suspended()),
+        ResourceStatus::SuspendedFailed => (icons::SUSPENDED_FAILED, theme.status_failed()),
+        ResourceStatus::Pending => (icons::PENDING, theme.status_pending()),
+        ResourceStatus::Unknown => (icons::UNKNOWN, theme.status_unknown()),
     }
 }
 
-/// Truncate a string to a maximum length
-fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max_len - 3])
+/// STATUS cell, flagging spec changes the controller has not observed yet
+fn status_cell(resource: &dyn FluxResource, max_len: usize, theme: &Theme) -> Cell<'static> {
+    let text = status_text(
+        resource.status(),
+        resource.status_message(),
+        resource.created_at(),
+        max_len,
+    );
+    match generation_lag(resource) {
+        Some(lag) => Cell::from(format!("{lag} {text}")).style(theme.status_reconciling()),
+        None => Cell::from(text),
+    }
+}
+
+/// Marker for an unobserved spec generation, as observed/generation (e.g. "⧗ 4/5")
+fn generation_lag(resource: &dyn FluxResource) -> Option<String> {
+    if !resource.has_unobserved_generation() {
+        return None;
+    }
+    Some(format!(
+        "{} {}/{}",
+        icons::GENERATION_LAG,
+        resource.observed_generation()?,
+        resource.generation()?
+    ))
+}
+
+/// Get the status column text, showing the creation age for pending resources
+///
+/// Pending text is never truncated so the age stays visible.
+fn status_text(
+    status: &ResourceStatus,
+    message: &str,
+    created_at: Option<DateTime<Utc>>,
+    max_len: usize,
+) -> String {
+    match (status, created_at) {
+        (ResourceStatus::Pending, Some(created)) => {
+            format!("{} · {}", message, format_age(created, Utc::now()))
+        }
+        _ => truncate(message, max_len),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::kubernetes::resources::{GitRepository, Kustomization, RefOverride};
+
+    #[test]
+    fn test_column_widths_cluster() {
+        let widths = [Constraint::Min(20), Constraint::Length(3)];
+        assert_eq!(column_widths(&widths, false), widths.to_vec());
+        assert_eq!(
+            column_widths(&widths, true),
+            vec![
+                Constraint::Min(12),
+                Constraint::Min(20),
+                Constraint::Length(3)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_row_at() {
+        let area = Rect::new(0, 4, 80, 10);
+        assert_eq!(row_at(area, 0, 5), None);
+        assert_eq!(row_at(area, 0, 6), Some(0));
+        assert_eq!(row_at(area, 0, 12), Some(6));
+        assert_eq!(row_at(area, 0, 13), None);
+
+        // A table scrolled by 4 rows
+        assert_eq!(row_at(area, 4, 6), Some(4));
+        assert_eq!(row_at(area, 4, 12), Some(10));
+    }
+
+    #[test]
+    fn test_scroll_offset() {
+        // Within the window the offset stays put
+        assert_eq!(scroll_offset(0, 6, 7), 0);
+        assert_eq!(scroll_offset(4, 5, 7), 4);
+        // Past the bottom, the selection ends up on the last line
+        assert_eq!(scroll_offset(0, 10, 7), 4);
+        // Above the top, on the first line
+        assert_eq!(scroll_offset(4, 2, 7), 2);
+        assert_eq!(scroll_offset(3, 3, 0), 3);
+    }
+
+    #[test]
+    fn test_window_builds_visible_rows_only() {
+        let theme = Theme::dark();
+        let streaks = FailureStreaks::default();
+        let state = StateFile::default();
+        let mut view = TableView {
+            selected: 500,
+            offset: 0,
+            show_cluster: false,
+            filter: "",
+            editing: false,
+            streaks: &streaks,
+            state: &state,
+            sort: SortState::default(),
+            source: None,
+            status: None,
+            theme: &theme,
+            hidden_columns: &[],
+        };
+        let items: Vec<usize> = (0..10_000).collect();
+        let area = Rect::new(0, 4, 80, 10);
+        let window = view.window(&items, area);
+        assert_eq!(window.first(), Some(&494));
+        assert_eq!(window.len(), 7 + ROW_MARGIN);
+
+        view.selected = 2;
+        assert_eq!(view.window(&items[..3], area), &[0, 1, 2]);
+    }
+
+    #[test]
+    fn test_shown_skips_hidden_columns() {
+        let theme = Theme::dark();
+        let streaks = FailureStreaks::default();
+        let state = StateFile::default();
+        let hidden = ["age".to_string(), "NAME".to_string()];
+        let view = TableView {
+            selected: 0,
+            offset: 0,
+            show_cluster: false,
+            filter: "",
+            editing: false,
+            streaks: &streaks,
+            state: &state,
+            sort: SortState::default(),
+            source: None,
+            status: None,
+            theme: &theme,
+            hidden_columns: &hidden,
+        };
+        let titles = ["NAME", "READY", "AGE"];
+        assert_eq!(view.shown(&titles, [1, 2, 3]), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_age_cell() {
+        let created = Kustomization {
+            created_at: Some(Utc::now() - chrono::Duration::hours(12)),
+            ..Default::default()
+        };
+        assert_eq!(age_cell(&created), Cell::from("12h"));
+        assert_eq!(age_cell(&Kustomization::default()), Cell::from("-"));
+    }
+
+    #[test]
+    fn test_streak_cell() {
+        let theme = Theme::dark();
+        assert_eq!(
+            streak_cell(&ResourceStatus::Failed, 1, &theme),
+            Cell::from(icons::FAILED).style(theme.status_failed())
+        );
+        assert_eq!(
+            streak_cell(&ResourceStatus::Failed, 2, &theme),
+            Cell::from("✗2").style(theme.status_failed())
+        );
+        assert_eq!(
+            streak_cell(&ResourceStatus::Stalled, 12, &theme),
+            Cell::from("⊘12").style(theme.status_stalled().add_modifier(Modifier::BOLD))
+        );
+    }
+
+    #[test]
+    fn test_generation_lag() {
+        let k = Kustomization {
+            generation: Some(5),
+            observed_generation: Some(4),
+            ..Default::default()
+        };
+        assert_eq!(generation_lag(&k), Some("⧗ 4/5".to_string()));
+
+        let observed = Kustomization {
+            observed_generation: Some(5),
+            ..k.clone()
+        };
+        assert_eq!(generation_lag(&observed), None);
+    }
+
+    #[test]
+    fn test_kind_columns_become_cells() {
+        let theme = Theme::dark();
+        let streaks = FailureStreaks::default();
+        let state = StateFile::default();
+        let view = TableView {
+            selected: 0,
+            offset: 0,
+            show_cluster: false,
+            filter: "",
+            editing: false,
+            streaks: &streaks,
+            state: &state,
+            sort: SortState::default(),
+            source: None,
+            status: None,
+            theme: &theme,
+            hidden_columns: &[],
+        };
+        let now = Utc::now();
+        let k = Kustomization {
+            namespace: "flux-system".to_string(),
+            suspended: true,
+            source_ref: "GitRepository/flux-system".to_string(),
+            path: "./apps/prod".to_string(),
+            ..Default::default()
+        };
+        let cells: Vec<Cell> = Kustomization::columns()
+            .iter()
+            .map(|column| cell(column, &k, &view, now))
+            .collect();
+        assert_eq!(cells[1], Cell::from("flux-system"));
+        assert_eq!(cells[4], Cell::from("-"));
+        assert_eq!(cells[5], Cell::from("GitRepository/flux-system"));
+        assert_eq!(cells[6], Cell::from("./apps/prod"));
+        assert_eq!(cells[7], Cell::from("-"));
+        assert_eq!(cells[8], Cell::from("⏸"));
+
+        // An overridden ref is flagged
+        let repo = GitRepository {
+            ref_override: Some(RefOverride { original: None }),
+            ..Default::default()
+        };
+        let columns = GitRepository::columns();
+        let reference = columns.iter().find(|c| c.title() == "REF").unwrap();
+        let Column::Field { value, .. } = reference else {
+            panic!("REF is a field");
+        };
+        assert_eq!(value(&repo, now).emphasis, Emphasis::Warning);
+        assert_eq!(
+            field_cell(value(&repo, now), &theme),
+            Cell::from(value(&repo, now).text).style(theme.status_failed())
+        );
+    }
+
+    #[test]
+    fn test_filter_title() {
+        assert_eq!(filter_title("", false), None);
+        assert_eq!(filter_title("", true), Some(" /▏ ".to_string()));
+        assert_eq!(filter_title("nginx", true), Some(" /nginx▏ ".to_string()));
+        assert_eq!(filter_title("nginx", false), Some(" /nginx ".to_string()));
+    }
 
     #[test]
     fn test_truncate_short_string() {
@@ -221,37 +631,81 @@ mod tests {
 
     #[test]
     fn test_status_icon_style_ready() {
-        let (icon, style) = status_icon_style(&ResourceStatus::Ready);
+        let theme = Theme::dark();
+        let (icon, style) = status_icon_style(&ResourceStatus::Ready, &theme);
         assert_eq!(icon, icons::READY);
-        assert_eq!(style.fg, Some(crate::ui::theme::status::READY));
+        assert_eq!(style.fg, Some(theme.status.ready));
     }
 
     #[test]
     fn test_status_icon_style_failed() {
-        let (icon, style) = status_icon_style(&ResourceStatus::Failed);
+        let theme = Theme::dark();
+        let (icon, style) = status_icon_style(&ResourceStatus::Failed, &theme);
         assert_eq!(icon, icons::FAILED);
-        assert_eq!(style.fg, Some(crate::ui::theme::status::FAILED));
+        assert_eq!(style.fg, Some(theme.status.failed));
     }
 
     #[test]
     fn test_status_icon_style_reconciling() {
-        let (icon, style) = status_icon_style(&ResourceStatus::Reconciling);
+        let theme = Theme::dark();
+        let (icon, style) = status_icon_style(&ResourceStatus::Reconciling, &theme);
         assert_eq!(icon, icons::RECONCILING);
-        assert_eq!(style.fg, Some(crate::ui::theme::status::RECONCILING));
+        assert_eq!(style.fg, Some(theme.status.reconciling));
+    }
+
+    #[test]
+    fn test_status_icon_style_stalled() {
+        let theme = Theme::dark();
+        let (icon, style) = status_icon_style(&ResourceStatus::Stalled, &theme);
+        assert_eq!(icon, icons::STALLED);
+        assert_eq!(style.fg, Some(theme.status.stalled));
     }
 
     #[test]
     fn test_status_icon_style_suspended() {
-        let (icon, style) = status_icon_style(&ResourceStatus::Suspended);
+        let theme = Theme::dark();
+        let (icon, style) = status_icon_style(&ResourceStatus::Suspended, &theme);
         assert_eq!(icon, icons::SUSPENDED);
-        assert_eq!(style.fg, Some(crate::ui::theme::status::SUSPENDED));
+        assert_eq!(style.fg, Some(theme.status.suspended));
+
+        let (icon, style) = status_icon_style(&ResourceStatus::SuspendedFailed, &theme);
+        assert_eq!(icon, icons::SUSPENDED_FAILED);
+        assert_eq!(style.fg, Some(theme.status.failed));
+    }
+
+    #[test]
+    fn test_status_icon_style_pending() {
+        let theme = Theme::dark();
+        let (icon, style) = status_icon_style(&ResourceStatus::Pending, &theme);
+        assert_eq!(icon, icons::PENDING);
+        assert_eq!(style.fg, Some(theme.status.pending));
+    }
+
+    #[test]
+    fn test_status_text_pending_shows_age() {
+        let created = Utc::now() - chrono::Duration::seconds(30);
+        let text = status_text(
+            &ResourceStatus::Pending,
+            "Pending (no status yet)",
+            Some(created),
+            10,
+        );
+        assert!(text.starts_with("Pending (no status yet) · "));
+        assert!(text.ends_with('s'));
+    }
+
+    #[test]
+    fn test_status_text_truncates_other_statuses() {
+        let text = status_text(&ResourceStatus::Failed, "a very long message", None, 10);
+        assert_eq!(text, "a very ...");
     }
 
     #[test]
     fn test_status_icon_style_unknown() {
-        let (icon, style) = status_icon_style(&ResourceStatus::Unknown);
+        let theme = Theme::dark();
+        let (icon, style) = status_icon_style(&ResourceStatus::Unknown, &theme);
         assert_eq!(icon, icons::UNKNOWN);
-        assert_eq!(style.fg, Some(crate::ui::theme::status::UNKNOWN));
+        assert_eq!(style.fg, Some(theme.status.unknown));
     }
 
     #[test]
@@ -2,34 +2,50 @@
 
 use ratatui::{prelude::*, widgets::Paragraph};
 
-use crate::app::state::{App, Popup};
-use crate::ui::theme::styles;
+use crate::app::{
+    actions::Action,
+    keymap,
+    state::{App, Popup, Tab},
+};
+use crate::kubernetes::resources::{kind::truncate, FluxResource, ResourceStatus};
 
 /// Draw the status bar with keybindings
 pub fn draw(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let keybindings = match &app.popup {
-        Popup::None => normal_keybindings(),
-        Popup::NamespaceFilter { .. } => namespace_keybindings(),
-        Popup::ResourceDetails { .. } | Popup::Reconciling { .. } => popup_keybindings(),
-        Popup::Error { .. } => error_keybindings(),
+        Popup::None if !app.search_editing => {
+            normal_keybindings(app.tab, app.get_selected_resource().as_deref())
+        }
+        popup => popup_keybindings_for(popup, app.search_editing)
+            .into_iter()
+            .map(|(key, desc)| (key.to_string(), desc))
+            .collect(),
     };
 
     // Build the status bar with styled spans
     let mut spans: Vec<Span> = Vec::new();
-    for (i, (key, desc)) in keybindings.iter().enumerate() {
+    for (i, (key, desc)) in keybindings.into_iter().enumerate() {
         if i > 0 {
-            spans.push(Span::styled(" │ ", styles::muted()));
+            spans.push(Span::styled(" │ ", theme.muted()));
         }
-        spans.push(Span::styled(*key, styles::key()));
+        spans.push(Span::styled(key, theme.key()));
         spans.push(Span::raw(" "));
-        spans.push(Span::styled(*desc, styles::key_desc()));
+        spans.push(Span::styled(desc, theme.key_desc()));
     }
 
     // Add error message if present
     if let Some(ref error) = app.last_error {
         spans.push(Span::styled(
             format!("  │  Error: {}", truncate(error, 40)),
-            Style::default().fg(crate::ui::theme::status::FAILED),
+            Style::default().fg(theme.status.failed),
+        ));
+    }
+
+    // Hint about a newer release without getting in the way
+    if let Some(ref version) = app.latest_version {
+        spans.push(Span::styled(
+            format!("  │  v{version} available"),
+            theme.muted(),
         ));
     }
 
@@ -38,26 +54,177 @@ pub fn draw(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(paragraph, area);
 }
 
-/// Normal mode keybindings
-fn normal_keybindings() -> Vec<(&'static str, &'static str)> {
+/// Keybindings of a popup, or of the fuzzy filter input
+fn popup_keybindings_for(popup: &Popup, search_editing: bool) -> Vec<(&'static str, &'static str)> {
+    match popup {
+        Popup::None if search_editing => search_keybindings(),
+        Popup::None => Vec::new(),
+        Popup::NamespaceFilter { .. } => namespace_keybindings(),
+        Popup::ContextSwitcher { .. } | Popup::ClusterFilter { .. } => list_keybindings(),
+        Popup::ResourceDetails { .. } => resource_details_keybindings(),
+        Popup::Yaml { .. }
+        | Popup::Events { .. }
+        | Popup::Message { .. }
+        | Popup::Dependencies { .. }
+        | Popup::Help { .. }
+        | Popup::Usage { .. } => details_keybindings(),
+        Popup::ActionLog { .. } => action_log_keybindings(),
+        Popup::ConfirmSuspend { .. } | Popup::ConfirmBulkSuspend { .. } => confirm_keybindings(),
+        Popup::ConfirmRefOverride { .. } => ref_override_keybindings(),
+        Popup::HelmHistory { .. } => helm_history_keybindings(),
+        Popup::ConfirmRollback { .. } => rollback_keybindings(),
+        Popup::Controllers { .. } => controllers_keybindings(),
+        Popup::Preflight { .. } => preflight_keybindings(),
+        Popup::FluxVersion { .. } => controllers_keybindings(),
+        Popup::PathBrowser { open: false, .. } => path_browser_keybindings(),
+        Popup::PathBrowser { open: true, .. } => file_keybindings(),
+        Popup::EditNote { .. } => note_keybindings(),
+        Popup::Command { .. } => command_keybindings(),
+        Popup::GlobalSearch { .. } => global_search_keybindings(),
+        Popup::Tour { .. } => tour_keybindings(),
+        Popup::Reconciling { progress, .. } if progress.is_done() => error_keybindings(),
+        Popup::Reconciling { .. } => popup_keybindings(),
+        Popup::Error { .. } | Popup::Info { .. } | Popup::Trace { .. } => error_keybindings(),
+    }
+}
+
+/// Normal mode keybindings from the keymap hints, offering only what the
+/// selected resource supports
+fn normal_keybindings(
+    tab: Tab,
+    selected: Option<&dyn FluxResource>,
+) -> Vec<(String, &'static str)> {
+    keymap::hints(tab)
+        .into_iter()
+        .map(|(keys, binding)| {
+            let hint = binding.hint.unwrap_or(binding.description);
+            let hint = match binding.action {
+                Action::ToggleSuspend if selected.is_some_and(|r| r.is_suspended()) => "Resume",
+                Action::ShowHelmHistory
+                    if selected.is_some_and(|r| *r.status() == ResourceStatus::Failed) =>
+                {
+                    "Rollback"
+                }
+                _ => hint,
+            };
+            (keys, hint)
+        })
+        .collect()
+}
+
+/// Fuzzy filter input keybindings
+fn search_keybindings() -> Vec<(&'static str, &'static str)> {
     vec![
+        ("Enter", "Apply"),
+        ("Esc", "Clear"),
         ("↑↓", "Navigate"),
-        ("←→", "Tabs"),
-        ("Enter", "Details"),
-        ("r", "Reconcile"),
-        ("R", "+Source"),
-        ("s", "Suspend"),
-        ("n", "Namespace"),
-        ("F5", "Refresh"),
-        ("q", "Quit"),
+        ("Tab", "Complete"),
     ]
 }
 
 /// Namespace popup keybindings
 fn namespace_keybindings() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("type", "Search"),
+        ("↑↓", "Select"),
+        ("Enter", "Apply"),
+        ("Esc", "Cancel"),
+    ]
+}
+
+/// Context and cluster popup keybindings
+fn list_keybindings() -> Vec<(&'static str, &'static str)> {
     vec![("↑↓", "Select"), ("Enter", "Apply"), ("Esc", "Cancel")]
 }
 
+/// YAML, events and other scrolling popup keybindings
+fn details_keybindings() -> Vec<(&'static str, &'static str)> {
+    vec![("↑↓", "Scroll"), ("PgUp/PgDn", "Page"), ("Esc", "Close")]
+}
+
+/// Resource details keybindings
+fn resource_details_keybindings() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("↑↓", "Scroll"),
+        ("m", "Labels/annotations"),
+        ("Esc", "Close"),
+    ]
+}
+
+/// Action log keybindings
+fn action_log_keybindings() -> Vec<(&'static str, &'static str)> {
+    vec![("↑↓", "Scroll"), ("w", "Write to file"), ("Esc", "Close")]
+}
+
+/// Controllers popup keybindings
+fn controllers_keybindings() -> Vec<(&'static str, &'static str)> {
+    vec![("↑↓", "Scroll"), ("r", "Refresh"), ("Esc", "Close")]
+}
+
+/// Preflight popup keybindings
+fn preflight_keybindings() -> Vec<(&'static str, &'static str)> {
+    vec![("↑↓", "Scroll"), ("r", "Check again"), ("Esc", "Close")]
+}
+
+/// Path browser file list keybindings
+fn path_browser_keybindings() -> Vec<(&'static str, &'static str)> {
+    vec![("↑↓", "Select"), ("Enter", "Open"), ("Esc", "Close")]
+}
+
+/// Path browser file keybindings
+fn file_keybindings() -> Vec<(&'static str, &'static str)> {
+    vec![("↑↓", "Scroll"), ("PgUp/PgDn", "Page"), ("Esc", "Back")]
+}
+
+/// Ref override confirmation keybindings
+fn ref_override_keybindings() -> Vec<(&'static str, &'static str)> {
+    vec![("Enter/y", "Override"), ("Esc/n", "Cancel")]
+}
+
+/// Helm history keybindings
+fn helm_history_keybindings() -> Vec<(&'static str, &'static str)> {
+    vec![("↑↓", "Select"), ("Enter", "Roll back"), ("Esc", "Close")]
+}
+
+/// Rollback confirmation keybindings
+fn rollback_keybindings() -> Vec<(&'static str, &'static str)> {
+    vec![("Enter/y", "Roll back"), ("Esc/n", "Cancel")]
+}
+
+/// Suspend confirmation keybindings
+fn confirm_keybindings() -> Vec<(&'static str, &'static str)> {
+    vec![("Enter/y", "Suspend"), ("Esc/n", "Cancel")]
+}
+
+/// Note editor keybindings
+fn note_keybindings() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("Enter", "Save (empty removes)"),
+        ("Tab", "Complete"),
+        ("Esc", "Cancel"),
+    ]
+}
+
+/// Command palette keybindings
+fn command_keybindings() -> Vec<(&'static str, &'static str)> {
+    vec![("Enter", "Preview"), ("Tab", "Complete"), ("Esc", "Cancel")]
+}
+
+/// Search across tabs keybindings
+fn global_search_keybindings() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("type", "Search"),
+        ("↑↓", "Select"),
+        ("Enter", "Go to"),
+        ("Esc", "Cancel"),
+    ]
+}
+
+/// Onboarding tour keybindings
+fn tour_keybindings() -> Vec<(&'static str, &'static str)> {
+    vec![("Enter/→", "Next"), ("←", "Back"), ("Esc", "Skip tour")]
+}
+
 /// Generic popup keybindings
 fn popup_keybindings() -> Vec<(&'static str, &'static str)> {
     vec![("Esc", "Close"), ("q", "Quit")]
@@ -68,15 +235,6 @@ fn error_keybindings() -> Vec<(&'static str, &'static str)> {
     vec![("Enter/Esc", "Dismiss"), ("q", "Quit")]
 }
 
-/// Truncate a string to a maximum length
-fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max_len - 3])
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -107,18 +265,45 @@ mod tests {
 
     #[test]
     fn test_normal_keybindings() {
-        let bindings = normal_keybindings();
+        let bindings = normal_keybindings(Tab::Kustomizations, None);
         assert!(!bindings.is_empty());
         // Check first and last bindings
-        assert_eq!(bindings[0], ("↑↓", "Navigate"));
-        assert_eq!(bindings[bindings.len() - 1], ("q", "Quit"));
+        assert_eq!(bindings[0], ("↑↓".to_string(), "Navigate"));
+        assert_eq!(bindings[bindings.len() - 1], ("q".to_string(), "Quit"));
+    }
+
+    #[test]
+    fn test_normal_keybindings_follow_selection() {
+        use crate::kubernetes::resources::{HelmRelease, Kustomization};
+
+        let suspended = Kustomization {
+            suspended: true,
+            ..Default::default()
+        };
+        let bindings = normal_keybindings(Tab::Kustomizations, Some(&suspended));
+        assert!(bindings.contains(&("s".to_string(), "Resume")));
+        assert!(!bindings.contains(&("s".to_string(), "Suspend")));
+
+        let bindings = normal_keybindings(Tab::HelmCharts, None);
+        assert!(!bindings.iter().any(|(key, _)| *key == "s"));
+        assert!(!bindings.iter().any(|(key, _)| *key == "H"));
+
+        let failed = HelmRelease {
+            status: ResourceStatus::Failed,
+            ..Default::default()
+        };
+        let bindings = normal_keybindings(Tab::HelmReleases, Some(&failed));
+        assert!(bindings.contains(&("H".to_string(), "Rollback")));
+        let bindings = normal_keybindings(Tab::HelmReleases, None);
+        assert!(bindings.contains(&("H".to_string(), "History")));
     }
 
     #[test]
     fn test_namespace_keybindings() {
         let bindings = namespace_keybindings();
-        assert_eq!(bindings.len(), 3);
-        assert_eq!(bindings[0], ("↑↓", "Select"));
+        assert_eq!(bindings.len(), 4);
+        assert_eq!(bindings[0], ("type", "Search"));
+        assert_eq!(list_keybindings().len(), 3);
     }
 
     #[test]
@@ -137,9 +322,9 @@ mod tests {
 
     #[test]
     fn test_normal_keybindings_complete() {
-        let bindings = normal_keybindings();
+        let bindings = normal_keybindings(Tab::Kustomizations, None);
         // Verify all expected bindings are present
-        assert_eq!(bindings.len(), 9);
+        assert_eq!(bindings.len(), 12);
 
         let expected = [
             ("↑↓", "Navigate"),
@@ -149,7 +334,10 @@ mod tests {
             ("R", "+Source"),
             ("s", "Suspend"),
             ("n", "Namespace"),
+            ("/", "Filter"),
+            ("o", "Sort"),
             ("F5", "Refresh"),
+            ("?", "Help"),
             ("q", "Quit"),
         ];
 
@@ -162,9 +350,9 @@ mod tests {
     #[test]
     fn test_namespace_keybindings_complete() {
         let bindings = namespace_keybindings();
-        assert_eq!(bindings[0], ("↑↓", "Select"));
-        assert_eq!(bindings[1], ("Enter", "Apply"));
-        assert_eq!(bindings[2], ("Esc", "Cancel"));
+        assert_eq!(bindings[1], ("↑↓", "Select"));
+        assert_eq!(bindings[2], ("Enter", "Apply"));
+        assert_eq!(bindings[3], ("Esc", "Cancel"));
     }
 
     #[test]
@@ -200,5 +388,11 @@ mod tests {
 
         // String with max_len + 1 characters
         assert_eq!(truncate("abcdefghi", 8), "abcde...");
+
+        // Multi-byte characters across the cut
+        assert_eq!(
+            truncate("Kontext „prod-eu“ nicht gefunden", 20),
+            "Kontext „prod-eu“..."
+        );
     }
 }
@@ -5,20 +5,29 @@ use ratatui::{
     widgets::{Block, Borders, Tabs as RataTabs},
 };
 
-use crate::app::state::Tab;
-use crate::ui::theme::styles;
+use crate::app::state::{Tab, TabCount};
+use crate::ui::theme::{icons, Theme};
 
-/// Draw the tab bar
-pub fn draw(frame: &mut Frame, area: Rect, current_tab: Tab) {
+/// Divider between tab titles
+const DIVIDER: &str = " │ ";
+
+/// Draw the tab bar, each title with its resource count and failures
+pub fn draw(
+    frame: &mut Frame,
+    area: Rect,
+    current_tab: Tab,
+    counts: &[TabCount; 5],
+    theme: &Theme,
+) {
     let titles: Vec<Line> = Tab::all()
         .iter()
         .map(|t| {
             let style = if *t == current_tab {
-                styles::tab_active()
+                theme.tab_active()
             } else {
-                styles::tab()
+                theme.tab()
             };
-            Line::from(format!(" {} ", t.name())).style(style)
+            title(*t, counts[*t as usize], theme).style(style)
         })
         .collect();
 
@@ -26,11 +35,78 @@ pub fn draw(frame: &mut Frame, area: Rect, current_tab: Tab) {
         .block(
             Block::default()
                 .borders(Borders::BOTTOM)
-                .border_style(styles::border()),
+                .border_style(theme.border()),
         )
         .select(current_tab as usize)
-        .divider(" │ ")
-        .highlight_style(styles::tab_active());
+        .divider(DIVIDER)
+        // Titles are styled above; a highlight style would recolor the badge
+        .highlight_style(Style::default());
 
     frame.render_widget(tabs, area);
 }
+
+/// Title " name 24 • 2✗ ", the failure badge only when something failed
+fn title(tab: Tab, count: TabCount, theme: &Theme) -> Line<'static> {
+    let mut spans = vec![Span::raw(format!(" {} {}", tab.name(), count.total))];
+    if count.failed > 0 {
+        spans.push(Span::raw(" • "));
+        spans.push(Span::styled(
+            format!("{}{}", count.failed, icons::FAILED),
+            theme.status_failed(),
+        ));
+    }
+    spans.push(Span::raw(" "));
+    Line::from(spans)
+}
+
+/// Tab under a column of the tab bar, following the layout of [`draw`]
+pub fn tab_at(area: Rect, column: u16, counts: &[TabCount; 5], theme: &Theme) -> Option<Tab> {
+    let mut x = area.x;
+    for tab in Tab::all() {
+        // Title, plus the widget's one-column padding on each side
+        let width = title(*tab, counts[*tab as usize], theme).width() as u16 + 2;
+        if (x..x + width).contains(&column) {
+            return Some(*tab);
+        }
+        x += width + Span::raw(DIVIDER).width() as u16;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tab_at() {
+        let area = Rect::new(0, 1, 120, 3);
+        let theme = Theme::default();
+        let counts = [TabCount::default(); 5];
+        assert_eq!(tab_at(area, 0, &counts, &theme), Some(Tab::Kustomizations));
+        // " Kustomizations 0 " is 18 wide: 20 columns with padding, then the divider
+        assert_eq!(tab_at(area, 19, &counts, &theme), Some(Tab::Kustomizations));
+        assert_eq!(tab_at(area, 21, &counts, &theme), None);
+        assert_eq!(tab_at(area, 23, &counts, &theme), Some(Tab::HelmReleases));
+        assert_eq!(tab_at(area, 119, &counts, &theme), None);
+    }
+
+    #[test]
+    fn test_title_badge() {
+        let theme = Theme::default();
+        let clean = TabCount {
+            total: 24,
+            failed: 0,
+        };
+        assert_eq!(
+            title(Tab::Kustomizations, clean, &theme).to_string(),
+            " Kustomizations 24 "
+        );
+        let failing = TabCount {
+            total: 24,
+            failed: 2,
+        };
+        let line = title(Tab::Kustomizations, failing, &theme);
+        assert_eq!(line.to_string(), " Kustomizations 24 • 2✗ ");
+        assert_eq!(line.spans[2].style, theme.status_failed());
+    }
+}
@@ -0,0 +1,127 @@
+//! Minimal YAML syntax highlighting for the manifest viewer
+
+use ratatui::prelude::*;
+
+use crate::ui::theme::Theme;
+
+/// Highlight a YAML document line by line
+pub fn highlight(content: &str, theme: &Theme) -> Vec<Line<'static>> {
+    content
+        .lines()
+        .map(|line| highlight_line(line, theme))
+        .collect()
+}
+
+/// Highlight one line: comments, list markers, keys and scalar values
+fn highlight_line(line: &str, theme: &Theme) -> Line<'static> {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+    let mut spans = vec![Span::raw(indent.to_string())];
+
+    if trimmed.starts_with('#') {
+        spans.push(Span::styled(trimmed.to_string(), theme.muted()));
+        return Line::from(spans);
+    }
+
+    let mut rest = trimmed;
+    while let Some(item) = rest.strip_prefix("- ") {
+        spans.push(Span::styled("- ", theme.muted()));
+        rest = item;
+    }
+
+    match split_key(rest) {
+        Some((key, value)) => {
+            spans.push(Span::styled(key.to_string(), theme.key()));
+            spans.push(Span::styled(":", theme.muted()));
+            spans.push(value_span(value, theme));
+        }
+        None => spans.push(value_span(rest, theme)),
+    }
+
+    Line::from(spans)
+}
+
+/// Split "key: value" (or "key:") into key and the text after the colon
+fn split_key(text: &str) -> Option<(&str, &str)> {
+    if text.starts_with(['"', '\'']) && !text.contains("\":") && !text.contains("':") {
+        return None;
+    }
+    let colon = text
+        .find(": ")
+        .or_else(|| text.strip_suffix(':').map(str::len))?;
+    Some((&text[..colon], &text[colon + 1..]))
+}
+
+/// Style a scalar value by type
+fn value_span(value: &str, theme: &Theme) -> Span<'static> {
+    let scalar = value.trim();
+    let style = if scalar.starts_with(['"', '\'']) {
+        Style::default().fg(theme.status.ready)
+    } else if matches!(scalar, "true" | "false" | "null" | "~") || scalar.parse::<f64>().is_ok() {
+        Style::default().fg(theme.status.reconciling)
+    } else {
+        theme.text()
+    };
+    Span::styled(value.to_string(), style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(line: &Line) -> Vec<String> {
+        line.spans.iter().map(|s| s.content.to_string()).collect()
+    }
+
+    #[test]
+    fn test_highlight_key_value() {
+        let theme = Theme::dark();
+        let line = highlight_line("  name: podinfo", &theme);
+        assert_eq!(texts(&line), vec!["  ", "name", ":", " podinfo"]);
+        assert_eq!(line.spans[1].style, theme.key());
+        assert_eq!(line.spans[3].style, theme.text());
+    }
+
+    #[test]
+    fn test_highlight_mapping_key() {
+        let theme = Theme::dark();
+        let line = highlight_line("metadata:", &theme);
+        assert_eq!(texts(&line), vec!["", "metadata", ":", ""]);
+    }
+
+    #[test]
+    fn test_highlight_list_item() {
+        let theme = Theme::dark();
+        let line = highlight_line("- type: Ready", &theme);
+        assert_eq!(texts(&line), vec!["", "- ", "type", ":", " Ready"]);
+
+        let line = highlight_line("  - ./apps", &theme);
+        assert_eq!(texts(&line), vec!["  ", "- ", "./apps"]);
+    }
+
+    #[test]
+    fn test_highlight_scalar_types() {
+        let theme = Theme::light();
+        let line = highlight_line("suspend: false", &theme);
+        assert_eq!(line.spans[3].style.fg, Some(theme.status.reconciling));
+
+        let line = highlight_line("generation: 3", &theme);
+        assert_eq!(line.spans[3].style.fg, Some(theme.status.reconciling));
+
+        let line = highlight_line("message: 'Applied revision: main@sha1:abc'", &theme);
+        assert_eq!(texts(&line)[1], "message");
+        assert_eq!(line.spans[3].style.fg, Some(theme.status.ready));
+    }
+
+    #[test]
+    fn test_highlight_comment() {
+        let theme = Theme::dark();
+        let line = highlight_line("# generated", &theme);
+        assert_eq!(line.spans[1].style, theme.muted());
+    }
+
+    #[test]
+    fn test_highlight_document() {
+        assert_eq!(highlight("a: 1\nb: 2", &Theme::dark()).len(), 2);
+    }
+}
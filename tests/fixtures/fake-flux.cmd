@@ -0,0 +1,10 @@
+@echo off
+rem Fake flux CLI for tests on Windows, see fake-flux
+for %%a in (%*) do (
+  if "%%a"=="broken" (
+    echo x reconciliation failed: kustomize build failed 1>&2
+    exit /b 1
+  )
+  if "%%a"=="hang" ping -n 31 127.0.0.1 >nul
+)
+echo %*